@@ -0,0 +1,57 @@
+//! Solving scrambles with the engine from another program.
+//!
+//! `cargo run --example embed` loads a small word list from memory, solves a
+//! few scrambles on another thread, and prints what it found.
+
+use jumble_helper::engine::{DictionarySpec, Engine, Query};
+use std::sync::Arc;
+use std::thread;
+
+const WORDS: &str = "\
+#! name=Example words
+BEAGLE
+POUND
+LATCH
+GRAPE
+PAGER
+EAT
+TEA
+";
+
+fn main() {
+    let engine = match Engine::new(DictionarySpec::new(WORDS)) {
+        Ok(engine) => Arc::new(engine),
+        Err(e) => {
+            eprintln!("can't load the word list: {e}");
+            std::process::exit(2);
+        }
+    };
+    println!("loaded {} words", engine.word_count());
+
+    let solver = Arc::clone(&engine);
+    let solutions = thread::spawn(move || {
+        ["galbee", "nudop", "GAPER", "XYZZY"].map(|letters| solver.solve(Query::new(letters)))
+    })
+    .join()
+    .expect("the solving thread panicked");
+    for solution in &solutions {
+        match solution.answer() {
+            Some(answer) => println!("{} -> {answer}", solution.letters),
+            None if solution.matches.is_empty() => println!("{} -> no match", solution.letters),
+            None => {
+                let words: Vec<&str> = solution.matches.iter().map(|m| m.word.as_str()).collect();
+                println!("{} -> one of {}", solution.letters, words.join(", "));
+            }
+        }
+    }
+
+    let mut query = Query::new("tae");
+    query.min_len = 3;
+    let words: Vec<String> = engine
+        .solve(query)
+        .matches
+        .into_iter()
+        .map(|m| m.word)
+        .collect();
+    println!("TAE -> {}", words.join(", "));
+}
@@ -0,0 +1,14 @@
+#!/usr/bin/env python3
+"""Example post_process_command: longest matches first, each noted with its length.
+
+Reads the matches as JSON on stdin and writes them back the same way.
+"""
+import json
+import sys
+
+candidates = json.load(sys.stdin)
+matches = sorted(candidates["matches"], key=lambda m: (-len(m["word"]), m["word"]))
+for match in matches:
+    match["note"] = f"{len(match['word'])} letters"
+candidates["matches"] = matches
+json.dump(candidates, sys.stdout)
@@ -0,0 +1,103 @@
+//! Optional allocation counting for the debug overlay.
+//!
+//! Built with `--features alloc-count`, a counting wrapper around the system
+//! allocator is installed so the overlay can show allocations per keystroke.
+//! The library's own tests always count, per thread too, so they can check
+//! what a frame allocates while other tests run.
+
+#[cfg(any(test, feature = "alloc-count"))]
+pub(crate) mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    thread_local! {
+        /// Allocations made by this thread.
+        pub static THREAD_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Counts an allocation, overall and for this thread.
+    fn count() {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // Gone while the thread exits, when its allocations no longer matter
+        let _ = THREAD_ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+    }
+
+    /// The system allocator, counting every allocation and reallocation.
+    pub struct CountingAlloc;
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            count();
+            unsafe { System.alloc(layout) }
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            count();
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+}
+
+/// Returns the number of allocations so far, or `None` if counting is not built in.
+pub fn allocation_count() -> Option<usize> {
+    #[cfg(feature = "alloc-count")]
+    {
+        Some(counting::ALLOCATIONS.load(std::sync::atomic::Ordering::Relaxed))
+    }
+    #[cfg(not(feature = "alloc-count"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::counting::THREAD_ALLOCATIONS;
+    use crate::alphabet::Alphabet;
+    use crate::wordmap::make_word_map_with_progress;
+    use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+
+    /// Most allocations the lookup for one keystroke may make: sorting the
+    /// letters and collecting them into a key.
+    const MAX_KEYSTROKE_ALLOCATIONS: usize = 4;
+
+    /// Returns how many allocations this thread makes running `f`.
+    fn allocations(f: impl FnOnce()) -> usize {
+        let before = THREAD_ALLOCATIONS.with(|n| n.get());
+        f();
+        THREAD_ALLOCATIONS.with(|n| n.get()) - before
+    }
+
+    #[test]
+    fn keystroke_lookups_allocate_at_most_the_key() {
+        let map = make_word_map_with_progress(
+            "pound\nupon\nsound\nunsound\n",
+            Alphabet::English,
+            10,
+            1000,
+            |_| (),
+        );
+        let mut key_buf = Vec::with_capacity(MAX_WORD_LENGTH);
+        for entry in ["U", "UP", "UPO", "UPON", "NOPUD", "DOUNNUS", "XYZZYXYZZY"] {
+            let n = allocations(|| {
+                let found = map.find_match(entry, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf);
+                std::hint::black_box(found);
+            });
+            assert_eq!(n, 0, "{entry}");
+        }
+        // Letters outside ASCII take the sorted key the slow way
+        let n = allocations(|| {
+            let found = map.find_match("ÑANDU", MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf);
+            std::hint::black_box(found);
+        });
+        assert!(n <= MAX_KEYSTROKE_ALLOCATIONS, "{n}");
+    }
+}
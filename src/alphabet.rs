@@ -0,0 +1,160 @@
+//! The letters a word list is spelled with, and how typed characters map to them.
+//!
+//! A word map's keys are its words' letters sorted in the alphabet's order, so an
+//! alphabet with a letter of its own keeps words spelled with it apart from those
+//! with the plain letter: in Spanish, `AÑO` isn't an anagram of `ANO`. Accented
+//! letters that aren't the alphabet's own fold to plain ones, as in English.
+//!
+//! A word list names its alphabet in an `alphabet=` header line (see
+//! `dictionary`), or else the config's `[dictionary]` table does; English is the
+//! default. Keyboards without `Ñ` type it as `N` then `~`.
+
+use crate::wordmap::fold_letter;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Most letters an alphabet has, for counting letters in fixed-size arrays.
+pub const MAX_LETTERS: usize = 32;
+
+/// The letters of a word list, in order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alphabet {
+    /// `A`-`Z`.
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    /// `A`-`Z` with `Ñ` after `N`.
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Alphabet {
+    /// Returns the alphabet with the code `code` (`en` or `es`), if there is one.
+    pub fn parse(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Alphabet::English),
+            "es" => Some(Alphabet::Spanish),
+            _ => None,
+        }
+    }
+    /// Returns the alphabet's code, as `parse` takes it.
+    pub fn code(self) -> &'static str {
+        match self {
+            Alphabet::English => "en",
+            Alphabet::Spanish => "es",
+        }
+    }
+    /// Returns the letters, uppercase and in order; at most `MAX_LETTERS`.
+    pub fn letters(self) -> &'static str {
+        match self {
+            Alphabet::English => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Alphabet::Spanish => "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ",
+        }
+    }
+    /// Returns where `letter` comes in the alphabet, if it's one of its letters.
+    pub fn index(self, letter: char) -> Option<usize> {
+        self.letters().chars().position(|c| c == letter)
+    }
+    /// Returns the rows of the on-screen keyboard, top first.
+    pub fn keyboard_rows(self) -> [&'static str; 3] {
+        match self {
+            Alphabet::English => ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"],
+            Alphabet::Spanish => ["QWERTYUIOP", "ASDFGHJKLÑ", "ZXCVBNM"],
+        }
+    }
+    /// Returns `c` as one of the alphabet's letters: uppercased, and with any
+    /// accent dropped unless the accented letter is the alphabet's own. `None` if
+    /// it isn't a letter.
+    pub fn fold(self, c: char) -> Option<char> {
+        let upper = c.to_uppercase().next()?;
+        if !upper.is_ascii() && self.index(upper).is_some() {
+            return Some(upper);
+        }
+        fold_letter(c)
+    }
+    /// Returns the letter `before` and `typed` make together, for a letter the
+    /// keyboard lacks (`N` then `~` gives `Ñ` in Spanish), if they make one.
+    pub fn combine(self, before: char, typed: char) -> Option<char> {
+        match (self, before, typed) {
+            (Alphabet::Spanish, 'N', '~') => Some('Ñ'),
+            _ => None,
+        }
+    }
+    /// Returns `word` uppercased with its letters folded as `fold` does and
+    /// ligatures spelled out, so it matches what can be typed (`Œuvre` gives
+    /// `OEUVRE`).
+    ///
+    /// Characters that don't fold are kept as they are.
+    pub fn fold_word(self, word: &str) -> Cow<'_, str> {
+        if word.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Cow::Borrowed(word);
+        }
+        let mut folded = String::with_capacity(word.len());
+        for c in word.chars() {
+            match c {
+                'Œ' | 'œ' => folded.push_str("OE"),
+                'Æ' | 'æ' => folded.push_str("AE"),
+                'ß' => folded.push_str("SS"),
+                _ => folded.push(self.fold(c).unwrap_or(c)),
+            }
+        }
+        Cow::Owned(folded)
+    }
+    /// Returns the letters of `word`, folded, in the alphabet's order: its key in
+    /// a `WordMap`. Characters that aren't letters go last.
+    pub fn key(self, word: &str) -> String {
+        let mut letters: Vec<char> = self.fold_word(word).chars().collect();
+        letters.sort_by_key(|&c| (self.index(c).unwrap_or(MAX_LETTERS), c));
+        letters.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for alphabet in [Alphabet::English, Alphabet::Spanish] {
+            assert_eq!(Alphabet::parse(alphabet.code()), Some(alphabet));
+            assert!(alphabet.letters().chars().count() <= MAX_LETTERS);
+        }
+        assert_eq!(Alphabet::parse("fr"), None);
+    }
+
+    #[test]
+    fn spanish_keeps_its_own_letter() {
+        assert_eq!(Alphabet::Spanish.fold('ñ'), Some('Ñ'));
+        assert_eq!(Alphabet::English.fold('ñ'), Some('N'));
+        assert_eq!(Alphabet::Spanish.fold('é'), Some('E'));
+        assert_eq!(Alphabet::Spanish.fold('3'), None);
+        assert_eq!(Alphabet::Spanish.index('Ñ'), Some(14));
+        assert_eq!(Alphabet::Spanish.index('O'), Some(15));
+    }
+
+    #[test]
+    fn keys_sort_in_the_alphabets_order() {
+        assert_eq!(Alphabet::Spanish.key("año"), "AÑO");
+        assert_eq!(Alphabet::Spanish.key("oña"), "AÑO");
+        assert_ne!(Alphabet::Spanish.key("año"), Alphabet::Spanish.key("ano"));
+        assert_eq!(Alphabet::English.key("año"), "ANO");
+        assert_eq!(Alphabet::English.key("great"), "AEGRT");
+    }
+
+    #[test]
+    fn words_fold_to_what_can_be_typed() {
+        assert_eq!(Alphabet::English.fold_word("Œuvre"), "OEUVRE");
+        assert_eq!(Alphabet::English.fold_word("straße"), "STRASSE");
+        assert!(matches!(
+            Alphabet::English.fold_word("GREAT"),
+            Cow::Borrowed("GREAT")
+        ));
+    }
+
+    #[test]
+    fn only_spanish_combines_a_tilde() {
+        assert_eq!(Alphabet::Spanish.combine('N', '~'), Some('Ñ'));
+        assert_eq!(Alphabet::Spanish.combine('A', '~'), None);
+        assert_eq!(Alphabet::English.combine('N', '~'), None);
+    }
+}
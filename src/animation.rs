@@ -0,0 +1,71 @@
+//! Short timed effects, such as the entry box shaking.
+//!
+//! An `Animation` only keeps time; what moves, and how, is up to whoever draws it.
+//! With reduced motion on (see `Animation::reduced`), one ends as it starts, so
+//! whatever it moves is drawn where it ends up.
+
+/// An effect running for `duration` seconds from `start` (in `AppContext::now`
+/// seconds).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Animation {
+    start: f64,
+    duration: f64,
+}
+
+impl Animation {
+    /// Starts an effect at `now` that runs for `duration` seconds.
+    pub fn new(now: f64, duration: f64) -> Self {
+        Self {
+            start: now,
+            duration,
+        }
+    }
+    /// Returns the effect ending as it starts if `reduced_motion` is on, for
+    /// those bothered by movement on screen.
+    pub fn reduced(self, reduced_motion: bool) -> Self {
+        match reduced_motion {
+            true => Self {
+                duration: 0.0,
+                ..self
+            },
+            false => self,
+        }
+    }
+    /// Returns how far through the effect `now` is, from 0.0 to 1.0.
+    pub fn progress(&self, now: f64) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        ((now - self.start) / self.duration).clamp(0.0, 1.0) as f32
+    }
+    /// Returns whether the effect has run its course by `now`.
+    pub fn is_done(&self, now: f64) -> bool {
+        now >= self.start + self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_runs_from_start_to_end() {
+        let shake = Animation::new(10.0, 0.5);
+        assert_eq!(shake.progress(9.0), 0.0);
+        assert_eq!(shake.progress(10.25), 0.5);
+        assert_eq!(shake.progress(11.0), 1.0);
+        assert!(!shake.is_done(10.4) && shake.is_done(10.5));
+        assert_eq!(Animation::new(10.0, 0.0).progress(10.0), 1.0);
+    }
+
+    #[test]
+    fn reduced_motion_ends_an_effect_as_it_starts() {
+        let shake = Animation::new(10.0, 0.5).reduced(true);
+        assert_eq!(shake.progress(10.0), 1.0);
+        assert!(shake.is_done(10.0));
+        assert_eq!(
+            Animation::new(10.0, 0.5).reduced(false),
+            Animation::new(10.0, 0.5)
+        );
+    }
+}
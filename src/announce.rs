@@ -0,0 +1,228 @@
+//! Spoken (or printed) announcements for screen reader users.
+//!
+//! The window is invisible to screen readers, so when enabled, state changes are
+//! described in short sentences printed to stdout and optionally passed to a
+//! speech command such as `spd-say` or `say`. Fast typing is coalesced so only
+//! the latest state is announced, at most once per `ANNOUNCE_INTERVAL`.
+
+use crate::strings::{fill, Language, StringKey};
+use std::process::{Child, Command};
+
+/// Shortest time between announcements, in seconds.
+pub const ANNOUNCE_INTERVAL: f64 = 0.5;
+
+/// Describes the entry and its matches in a sentence or two, in `lang`.
+///
+/// The entry is spelled out letter by letter, which screen readers read better
+/// than a jumble of capitals.
+pub fn announcement(
+    lang: Language,
+    entry: &str,
+    matches: &[String],
+    answer: Option<&str>,
+    searching: bool,
+) -> String {
+    if entry.is_empty() {
+        return lang.text(StringKey::EntryCleared).into();
+    }
+    let letters: Vec<String> = entry.chars().map(String::from).collect();
+    let spelled = fill(lang.text(StringKey::EntryIs), letters.join(" "));
+    let result = match (answer, matches.len()) {
+        _ if searching => lang.text(StringKey::AnnounceSearching).to_string(),
+        (Some(answer), _) => fill(lang.text(StringKey::AnswerIs), answer),
+        (None, 0) => lang.text(StringKey::NoMatches).to_string(),
+        (None, 1) => lang.text(StringKey::OneMatch).to_string(),
+        (None, n) => fill(lang.text(StringKey::ManyMatches), n),
+    };
+    format!("{spelled} {result}")
+}
+
+/// Sends announcements, dropping any superseded before their turn came.
+pub struct Announcer {
+    /// Speech program and its leading arguments; the text is appended.
+    command: Option<Vec<String>>,
+    pending: Option<String>,
+    last: String,
+    last_sent_at: f64,
+    speaking: Option<Child>,
+}
+
+impl Announcer {
+    /// Creates an announcer running `command` (e.g. `"spd-say -r 20"`), if given.
+    pub fn new(command: Option<&str>) -> Self {
+        let command = command
+            .map(|c| c.split_whitespace().map(str::to_owned).collect::<Vec<_>>())
+            .filter(|args| !args.is_empty());
+        Self {
+            command,
+            pending: None,
+            last: String::new(),
+            last_sent_at: f64::NEG_INFINITY,
+            speaking: None,
+        }
+    }
+    /// Queues `text`, replacing anything not yet announced.
+    pub fn offer(&mut self, text: String) {
+        self.pending = (text != self.last).then_some(text);
+    }
+    /// Announces the queued text if the interval has passed since the last one
+    /// and the previous announcement has finished speaking.
+    ///
+    /// The speech command runs in the background; this never waits on it. Text
+    /// offered while it speaks stays queued, so only the newest is spoken next.
+    pub fn tick(&mut self, now: f64) {
+        if now - self.last_sent_at < ANNOUNCE_INTERVAL || self.pending.is_none() {
+            return;
+        }
+        // Reap the previous announcement, or let it finish before the next
+        if let Some(child) = self.speaking.as_mut() {
+            match child.try_wait() {
+                Ok(None) => return,
+                Ok(Some(_)) => self.speaking = None,
+                Err(e) => {
+                    log::warn!("[Announcer::tick] lost track of the speech command: {e}");
+                    self.stop_speaking();
+                }
+            }
+        }
+        let Some(text) = self.pending.take() else {
+            return;
+        };
+        self.last_sent_at = now;
+        println!("{text}");
+
+        if let Some((program, args)) = self.command.as_ref().and_then(|c| c.split_first()) {
+            match Command::new(program).args(args).arg(&text).spawn() {
+                Ok(child) => self.speaking = Some(child),
+                Err(e) => {
+                    log::warn!("[Announcer::tick] can't run '{program}': {e}; printing only");
+                    self.command = None;
+                }
+            }
+        }
+        self.last = text;
+    }
+    /// Ends the announcement being spoken, if any, and reaps it.
+    fn stop_speaking(&mut self) {
+        if let Some(mut child) = self.speaking.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for Announcer {
+    fn drop(&mut self) {
+        self.stop_speaking();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn the_entry_is_spelled_out_with_its_result() {
+        let matches = words(&["GREAT", "GRATE"]);
+        assert_eq!(
+            announcement(Language::En, "GERTA", &matches, None, false),
+            "Entry G E R T A. 2 matches."
+        );
+        assert_eq!(
+            announcement(Language::En, "GERTA", &matches, Some("GREAT"), false),
+            "Entry G E R T A. Answer: GREAT."
+        );
+        assert_eq!(
+            announcement(Language::En, "NUPO", &words(&["UPON"]), None, false),
+            "Entry N U P O. 1 match."
+        );
+        assert_eq!(
+            announcement(Language::En, "XQZV", &[], None, false),
+            "Entry X Q Z V. No matches."
+        );
+    }
+
+    #[test]
+    fn searching_hides_stale_results() {
+        let matches = words(&["GREAT"]);
+        assert_eq!(
+            announcement(Language::En, "GERTA", &matches, Some("GREAT"), true),
+            "Entry G E R T A. Searching."
+        );
+    }
+
+    #[test]
+    fn an_empty_entry_is_cleared() {
+        assert_eq!(
+            announcement(Language::En, "", &[], None, false),
+            "Entry cleared."
+        );
+        assert_eq!(
+            announcement(Language::En, "", &[], None, true),
+            "Entry cleared."
+        );
+    }
+
+    #[test]
+    fn repeated_text_is_announced_once() {
+        let mut announcer = Announcer::new(None);
+        announcer.offer("Entry cleared.".into());
+        announcer.tick(0.0);
+        assert_eq!(announcer.last, "Entry cleared.");
+        announcer.offer("Entry cleared.".into());
+        assert_eq!(announcer.pending, None);
+    }
+
+    #[test]
+    fn the_interval_holds_back_the_next_announcement() {
+        let mut announcer = Announcer::new(None);
+        announcer.offer("Entry A.".into());
+        announcer.tick(0.0);
+        announcer.offer("Entry A B.".into());
+        announcer.offer("Entry A B C.".into());
+        announcer.tick(ANNOUNCE_INTERVAL / 2.0);
+        assert_eq!(announcer.last, "Entry A.");
+        announcer.tick(ANNOUNCE_INTERVAL);
+        assert_eq!(announcer.last, "Entry A B C.");
+        assert_eq!(announcer.pending, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn speech_never_overlaps_and_is_reaped() {
+        // Speaks for a moment, whatever text is appended
+        let mut announcer = Announcer::new(None);
+        announcer.command = Some(words(&["sh", "-c", "sleep 0.3", "sh"]));
+
+        announcer.offer("Entry A.".into());
+        announcer.tick(0.0);
+        assert!(announcer.speaking.is_some());
+        let first = announcer.speaking.as_ref().unwrap().id();
+
+        // Still speaking: the newest text waits
+        announcer.offer("Entry A B.".into());
+        announcer.offer("Entry A B C.".into());
+        announcer.tick(10.0);
+        assert_eq!(announcer.last, "Entry A.");
+        assert_eq!(announcer.pending.as_deref(), Some("Entry A B C."));
+        assert_eq!(announcer.speaking.as_ref().unwrap().id(), first);
+
+        // Once it finishes it's reaped and the newest text is spoken
+        std::thread::sleep(std::time::Duration::from_millis(600));
+        announcer.tick(20.0);
+        assert_eq!(announcer.last, "Entry A B C.");
+        assert_ne!(announcer.speaking.as_ref().unwrap().id(), first);
+
+        // Stopping (as dropping does) ends and reaps the one still speaking
+        let pid = announcer.speaking.as_ref().unwrap().id();
+        announcer.stop_speaking();
+        assert!(announcer.speaking.is_none());
+        if cfg!(target_os = "linux") {
+            assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+        }
+    }
+}
@@ -0,0 +1,2441 @@
+//! The running app: its screens, the resources they share, and how events reach
+//! them.
+//!
+//! Each frame, `main` feeds the frame's events to `App::handle_event`, advances
+//! time with `App::update`, and draws with `App::draw`. None of these read input or
+//! draw directly, so they can run without a window.
+//!
+//! State that outlives the session lives in `AppState`. Changes are held in memory
+//! and written on shutdown (or on leaving the settings screen) rather than on every
+//! keypress. Quitting, closing
+//! the window, and panicking all flush them. An entry saved on quit (from the
+//! confirm-quit dialog) is restored on the next launch. The open tabs are
+//! autosaved as they go (see `session`), and a clean shutdown says so, so tabs
+//! left by a crash can be offered back. Unique answers are kept in a history
+//! (see `history`), saved like the config, as are the dictionary coverage tallies
+//! (see `coverage`), and the answers confirmed as seen in print (see
+//! `confirmed`). The user's words to mask (see `mask`) are read at launch. With
+//! usage metrics on, the features used are counted and added to the stored
+//! counts on saving (see `usage`).
+
+use crate::config::{Config, CONFIG_FORMAT, CONFIG_KEY};
+use crate::confirmed::{Confirmed, CONFIRMED_FORMAT, CONFIRMED_KEY};
+use crate::coverage::{CoverageLog, COVERAGE_FORMAT, COVERAGE_KEY};
+use crate::events::AppEvent;
+use crate::history::{History, HISTORY_ARCHIVE_KEY, HISTORY_CAPACITY, HISTORY_FORMAT, HISTORY_KEY};
+use crate::keymap::Keymap;
+use crate::letters::LetterMask;
+use crate::loading::LoadState;
+use crate::mask::{WordMask, MASK_FORMAT, MASK_KEY};
+use crate::metrics::TextMetrics;
+use crate::modal::Focus;
+use crate::pins::Pins;
+use crate::recall::Recall;
+use crate::redraw::FrameCache;
+use crate::render::{Measure, Renderer};
+use crate::screen::{Screen, Transition};
+use crate::session::{Session, CLEAN, CLEAN_FORMAT, CLEAN_KEY, SESSION_FORMAT, SESSION_KEY};
+use crate::sound::Sounds;
+use crate::storage::{Format, Storage};
+use crate::strings::{fill, StringKey};
+use crate::style::AppStyle;
+use crate::theme::ThemeSet;
+use crate::timing::{time, Stage};
+use crate::toast::{draw_toasts, Severity, Toasts, TOAST_TIME};
+use crate::update::Manifest;
+use crate::usage::{save_usage, Feature, UsageLog, USAGE_KEY};
+use crate::wordmap::WordMap;
+use macroquad::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Storage key of the entry saved on quit.
+pub const ENTRY_KEY: &str = "entry.txt";
+/// Format of `ENTRY_KEY`.
+pub const ENTRY_FORMAT: Format = Format {
+    name: "entry",
+    version: 1,
+};
+
+/// Text waiting to be written, shared with the panic hook.
+#[derive(Debug, Default)]
+struct Unsaved {
+    config: Option<String>,
+    entry: Option<String>,
+    history: Option<String>,
+    coverage: Option<String>,
+    confirmed: Option<String>,
+    /// Uses counted since the last save.
+    usage: UsageLog,
+}
+
+/// Persistent application state.
+pub struct AppState {
+    pub config: Config,
+    /// Whether to walk through onboarding once the dictionary loads.
+    pub onboarding: bool,
+    /// Whether to start in the mini window (see `mini`).
+    pub mini: bool,
+    storage: Arc<dyn Storage>,
+    unsaved: Arc<Mutex<Unsaved>>,
+    /// Whether this session has looked for tabs left by a crash yet.
+    recovery_checked: bool,
+    /// Words pinned from the results, shared by every tab.
+    pub pins: Pins,
+    /// Entries cleared or answered this session, for Up and Down to call back.
+    pub recall: Recall,
+    /// Answers confirmed as seen in print, in this session or before.
+    pub confirmed: Confirmed,
+    /// Words kept out of the results while `mask_words` is on.
+    pub mask: WordMask,
+    /// Letters left out of every match this session (see `letters`).
+    pub letters: LetterMask,
+}
+
+impl AppState {
+    /// Creates the state for `config`, which is saved to `storage`.
+    pub fn new(config: Config, storage: Arc<dyn Storage>) -> Self {
+        let confirmed = match storage.read_format(CONFIRMED_KEY, CONFIRMED_FORMAT) {
+            Ok(text) => Confirmed::parse(&text.unwrap_or_default()),
+            Err(e) => {
+                log::warn!("[AppState::new] failed to read {CONFIRMED_KEY}: {e}");
+                Confirmed::default()
+            }
+        };
+        let mask = match storage.read_format(MASK_KEY, MASK_FORMAT) {
+            Ok(text) => WordMask::new(&text.unwrap_or_default()),
+            Err(e) => {
+                log::warn!("[AppState::new] failed to read {MASK_KEY}: {e}");
+                WordMask::new("")
+            }
+        };
+        Self {
+            config,
+            onboarding: false,
+            mini: false,
+            storage,
+            unsaved: Default::default(),
+            recovery_checked: false,
+            pins: Pins::default(),
+            recall: Recall::default(),
+            confirmed,
+            mask,
+            letters: LetterMask::default(),
+        }
+    }
+    /// Notes that the config changed, so it's saved on shutdown.
+    pub fn config_changed(&mut self) {
+        match self.config.to_toml() {
+            Ok(text) => {
+                if let Ok(mut unsaved) = self.unsaved.lock() {
+                    unsaved.config = Some(text);
+                }
+            }
+            Err(e) => log::error!("[AppState::config_changed] failed to serialize config: {e}"),
+        }
+    }
+    /// Returns the entry saved by the last session, if any.
+    ///
+    /// The saved entry is cleared on shutdown unless saved again.
+    pub fn take_saved_entry(&mut self) -> String {
+        let saved = match self.storage.read_format(ENTRY_KEY, ENTRY_FORMAT) {
+            Ok(saved) => saved.unwrap_or_default(),
+            Err(e) => {
+                log::warn!("[AppState::take_saved_entry] failed to read saved entry: {e}");
+                String::new()
+            }
+        };
+        if !saved.is_empty() {
+            self.save_entry("");
+        }
+        saved
+    }
+    /// Saves `entry` on shutdown, to be restored next launch.
+    pub fn save_entry(&mut self, entry: &str) {
+        if let Ok(mut unsaved) = self.unsaved.lock() {
+            unsaved.entry = Some(entry.to_owned());
+        }
+    }
+    /// Returns the answers found in earlier sessions, first moving all but the
+    /// newest `HISTORY_CAPACITY` to the archive.
+    pub fn read_history(&self) -> History {
+        let mut history = match self.storage.read_format(HISTORY_KEY, HISTORY_FORMAT) {
+            Ok(text) => History::parse(&text.unwrap_or_default()),
+            Err(e) => {
+                log::warn!("[AppState::read_history] failed to read {HISTORY_KEY}: {e}");
+                return History::default();
+            }
+        };
+        let mut older = history.trim(HISTORY_CAPACITY);
+        if older.solves.is_empty() {
+            return history;
+        }
+        if let Err(e) = self.storage.archive(HISTORY_ARCHIVE_KEY, &older.to_text()) {
+            log::warn!("[AppState::read_history] failed to archive older answers: {e}");
+            older.solves.append(&mut history.solves);
+            return older;
+        }
+        // Saved now, so the archived answers aren't archived again after a crash
+        let text = history.to_text();
+        if let Err(e) = self
+            .storage
+            .write_format(HISTORY_KEY, HISTORY_FORMAT, &text)
+        {
+            log::warn!("[AppState::read_history] failed to save {HISTORY_KEY}: {e}");
+        }
+        log::info!(
+            "[AppState::read_history] archived {} older answers",
+            older.solves.len()
+        );
+        history
+    }
+    /// Saves `history` on shutdown.
+    pub fn save_history(&mut self, history: &History) {
+        if let Ok(mut unsaved) = self.unsaved.lock() {
+            unsaved.history = Some(history.to_text());
+        }
+    }
+    /// Returns the coverage tallied in earlier sessions.
+    pub fn read_coverage(&self) -> CoverageLog {
+        match self.storage.read_format(COVERAGE_KEY, COVERAGE_FORMAT) {
+            Ok(text) => CoverageLog::parse(&text.unwrap_or_default()),
+            Err(e) => {
+                log::warn!("[AppState::read_coverage] failed to read {COVERAGE_KEY}: {e}");
+                CoverageLog::default()
+            }
+        }
+    }
+    /// Saves `coverage` on shutdown.
+    pub fn save_coverage(&mut self, coverage: &CoverageLog) {
+        if let Ok(mut unsaved) = self.unsaved.lock() {
+            unsaved.coverage = Some(coverage.to_text());
+        }
+    }
+    /// Confirms `word` as seen in print, saving it on shutdown; returns false if
+    /// it already was.
+    pub fn confirm(&mut self, word: &str) -> bool {
+        if !self.confirmed.insert(word) {
+            return false;
+        }
+        if let Ok(mut unsaved) = self.unsaved.lock() {
+            unsaved.confirmed = Some(self.confirmed.to_text());
+        }
+        true
+    }
+    /// Adds `word` to the mask and to the user's words to mask in `MASK_KEY`,
+    /// saving them now; returns false if it already was masked.
+    pub fn mask_word(&mut self, word: &str) -> bool {
+        if !self.mask.insert(word) {
+            return false;
+        }
+        let mut text = match self.storage.read_format(MASK_KEY, MASK_FORMAT) {
+            Ok(text) => text.unwrap_or_default(),
+            Err(e) => {
+                log::warn!("[AppState::mask_word] failed to read {MASK_KEY}: {e}");
+                return true;
+            }
+        };
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&word.to_uppercase());
+        text.push('\n');
+        if let Err(e) = self.storage.write_format(MASK_KEY, MASK_FORMAT, &text) {
+            log::error!("[AppState::mask_word] failed to save {MASK_KEY}: {e}");
+        }
+        true
+    }
+    /// Counts a use of `feature` today, if usage metrics are on.
+    pub fn count(&mut self, feature: Feature) {
+        let Some(day) = crate::date::today().filter(|_| self.config.usage_metrics) else {
+            return;
+        };
+        if let Ok(mut unsaved) = self.unsaved.lock() {
+            unsaved.usage.count(day, feature);
+        }
+    }
+    /// Returns the words to keep out of the results, if masking is on.
+    pub fn active_mask(&self) -> Option<&WordMask> {
+        self.config.mask_words.then_some(&self.mask)
+    }
+    /// Returns the tabs autosaved by a session that didn't shut down cleanly, if
+    /// there are any worth restoring, and notes that this session is running.
+    ///
+    /// Only the first call looks; later ones return `None`.
+    pub fn take_recovered_session(&mut self) -> Option<Session> {
+        if std::mem::replace(&mut self.recovery_checked, true) {
+            return None;
+        }
+        let clean = match self.storage.read_format(CLEAN_KEY, CLEAN_FORMAT) {
+            Ok(mark) => mark.as_deref() == Some(CLEAN),
+            Err(e) => {
+                log::warn!("[AppState::take_recovered_session] failed to read {CLEAN_KEY}: {e}");
+                true
+            }
+        };
+        if let Err(e) = self.storage.write_format(CLEAN_KEY, CLEAN_FORMAT, "") {
+            log::warn!("[AppState::take_recovered_session] failed to write {CLEAN_KEY}: {e}");
+        }
+        if clean {
+            return None;
+        }
+        let text = match self.storage.read_format(SESSION_KEY, SESSION_FORMAT) {
+            Ok(text) => text?,
+            Err(e) => {
+                log::warn!("[AppState::take_recovered_session] ignoring {SESSION_KEY}: {e}");
+                return None;
+            }
+        };
+        match Session::parse(&text) {
+            Ok(session) if !session.is_empty() => Some(session),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("[AppState::take_recovered_session] ignoring {SESSION_KEY}: {e}");
+                None
+            }
+        }
+    }
+    /// Writes `session` now, so it survives a crash.
+    pub fn autosave(&mut self, session: &Session) {
+        if let Err(e) = self
+            .storage
+            .write_format(SESSION_KEY, SESSION_FORMAT, &session.to_text())
+        {
+            log::error!("[AppState::autosave] failed to save {SESSION_KEY}: {e}");
+        }
+    }
+    /// Saves unsaved changes if the app panics.
+    ///
+    /// The hook only uses the last serialized config, which might be a change
+    /// behind if the panic struck mid-update; that's the best it can do.
+    pub fn install_panic_hook(&self) {
+        let storage = Arc::clone(&self.storage);
+        let unsaved = Arc::clone(&self.unsaved);
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            let _ = flush(storage.as_ref(), &unsaved);
+        }));
+    }
+    /// Writes everything still unsaved now, returning whether it all saved.
+    pub fn save(&mut self) -> bool {
+        flush(self.storage.as_ref(), &self.unsaved)
+    }
+    /// Writes everything still unsaved, and marks the shutdown clean. Call on
+    /// every exit path but a crash.
+    pub fn shutdown(&mut self) {
+        log::info!("[AppState::shutdown]");
+        let _ = self.save();
+        if let Err(e) = self.storage.write_format(CLEAN_KEY, CLEAN_FORMAT, CLEAN) {
+            log::error!("[AppState::shutdown] failed to save {CLEAN_KEY}: {e}");
+        }
+    }
+}
+
+/// Writes whatever is unsaved, logging failures; returns whether it all saved.
+fn flush(storage: &dyn Storage, unsaved: &Mutex<Unsaved>) -> bool {
+    // `try_lock` so a panic while the lock is held can't deadlock the hook
+    let Ok(mut unsaved) = unsaved.try_lock() else {
+        log::warn!("[flush] state busy; not saved");
+        return false;
+    };
+    let mut saved = true;
+    let pending = [
+        (CONFIG_KEY, CONFIG_FORMAT, unsaved.config.take()),
+        (ENTRY_KEY, ENTRY_FORMAT, unsaved.entry.take()),
+        (HISTORY_KEY, HISTORY_FORMAT, unsaved.history.take()),
+        (COVERAGE_KEY, COVERAGE_FORMAT, unsaved.coverage.take()),
+        (CONFIRMED_KEY, CONFIRMED_FORMAT, unsaved.confirmed.take()),
+    ];
+    for (key, format, text) in pending {
+        if let Some(text) = text {
+            if let Err(e) = storage.write_format(key, format, &text) {
+                log::error!("[flush] failed to save {key}: {e}");
+                saved = false;
+            }
+        }
+    }
+    if !unsaved.usage.is_empty() {
+        match save_usage(storage, &unsaved.usage) {
+            Ok(()) => unsaved.usage = UsageLog::default(),
+            Err(e) => {
+                log::error!("[flush] failed to save {USAGE_KEY}: {e}");
+                saved = false;
+            }
+        }
+    }
+    saved
+}
+
+/// Resources shared by every screen.
+pub struct AppContext {
+    pub state: AppState,
+    pub keymap: Keymap,
+    pub font: Option<Font>,
+    /// Sizes text for layout.
+    pub measure: Measure,
+    /// Times queries, in seconds.
+    pub clock: fn() -> f64,
+    /// Seconds of `update` time elapsed.
+    pub now: f64,
+    /// The window size.
+    pub size: Vec2,
+    pub sounds: Sounds,
+    pub style: AppStyle,
+    pub themes: ThemeSet,
+    /// The word map, once loaded.
+    pub dictionary: Option<Arc<WordMap>>,
+    /// Counts the word maps loaded, so a replay can tell it has the same one.
+    pub dictionary_generation: u32,
+    /// Short messages over whichever screen is shown.
+    pub toasts: Toasts,
+    /// Text to put on the system clipboard, taken by the main loop.
+    pub clipboard: Option<String>,
+    /// Whether the app can resize its window (see `mini::can_resize`).
+    pub can_resize: bool,
+    /// Whether effects end as they start and the demo never plays (see
+    /// `Config::reduced_motion`).
+    pub reduced_motion: bool,
+    /// Size to make the window, taken by the main loop.
+    pub window_size: Option<Vec2>,
+    /// Whether the scene needs redrawing.
+    pub dirty: bool,
+    /// The newer release that's out, if one is (see `update`).
+    pub update: Option<Manifest>,
+}
+
+impl AppContext {
+    /// Switches to `map` as the dictionary.
+    pub fn set_dictionary(&mut self, map: WordMap) {
+        self.dictionary = Some(Arc::new(map));
+        self.dictionary_generation += 1;
+    }
+    /// Measures the font at `size`.
+    pub fn text_metrics(&self, size: u16) -> TextMetrics {
+        let font = self.font.as_ref();
+        TextMetrics::from_measurer(size, |text| (self.measure)(text, font, size))
+    }
+}
+
+/// The running app: shared resources and the screen currently shown.
+pub struct App {
+    pub ctx: AppContext,
+    screen: Screen,
+    /// Screens under the current one, waiting to resume; the last is on top.
+    below: Vec<Screen>,
+    quit: bool,
+}
+
+impl App {
+    /// Creates the app for a `size` window and starts loading the dictionary.
+    pub fn new(
+        state: AppState,
+        keymap: Keymap,
+        font: Option<Font>,
+        sounds: Sounds,
+        size: Vec2,
+        clock: fn() -> f64,
+        measure: Measure,
+    ) -> Self {
+        let config = &state.config;
+        let loading = LoadState::start(config.dictionary.clone(), config.language);
+        let ctx = AppContext {
+            keymap,
+            font,
+            measure,
+            clock,
+            now: 0.0,
+            size,
+            sounds,
+            style: AppStyle::new(config.text_scale, config.dpi_scale.resolve(1.0)),
+            themes: ThemeSet::new(&config.themes, &config.theme),
+            dictionary: None,
+            dictionary_generation: 0,
+            toasts: Toasts::default(),
+            clipboard: None,
+            can_resize: false,
+            reduced_motion: config.reduced_motion(),
+            window_size: None,
+            dirty: true,
+            update: None,
+            state,
+        };
+        Self {
+            ctx,
+            screen: Screen::Loading(loading),
+            below: Vec::new(),
+            quit: false,
+        }
+    }
+    /// Returns the screen currently shown.
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+    /// Returns who receives input.
+    pub fn focus(&self) -> Focus {
+        self.screen.inner().focus()
+    }
+    /// Returns whether the user has chosen to quit; call `shutdown` next.
+    pub fn is_quitting(&self) -> bool {
+        self.quit
+    }
+    /// Saves state on the way out.
+    pub fn shutdown(&mut self) {
+        self.ctx.state.shutdown();
+    }
+    /// Returns whether the scene needs drawing this frame, clearing the request.
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.ctx.dirty)
+            || self.screen.inner().is_animating()
+            || self.ctx.toasts.is_fading(self.ctx.now)
+    }
+    /// Takes the text waiting to go on the clipboard, if any.
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.ctx.clipboard.take()
+    }
+    /// Takes the size the window should be made, if it's to change.
+    pub fn take_window_size(&mut self) -> Option<Vec2> {
+        self.ctx.window_size.take()
+    }
+    /// Tells the user the app is already open, when it's launched again.
+    pub fn raised(&mut self) {
+        let text = self.ctx.state.config.language.text(StringKey::AlreadyOpen);
+        let now = self.ctx.now;
+        self.ctx.toasts.push(text, Severity::Info, TOAST_TIME, now);
+        self.ctx.dirty = true;
+    }
+    /// Tells the user of the newer release `manifest` describes.
+    pub fn update_available(&mut self, manifest: Manifest) {
+        let lang = self.ctx.state.config.language;
+        let text = fill(lang.text(StringKey::UpdateAvailable), &manifest.version);
+        let now = self.ctx.now;
+        self.ctx.toasts.push(text, Severity::Info, TOAST_TIME, now);
+        self.ctx.update = Some(manifest);
+        self.ctx.dirty = true;
+    }
+    /// Returns whether the next frame is due soon, so the app shouldn't idle.
+    pub fn is_busy(&self) -> bool {
+        let screen = self.screen.inner();
+        screen.is_animating()
+            || screen.is_waiting()
+            || self.ctx.toasts.is_active()
+            || self.ctx.dirty
+    }
+
+    /// Applies one event to the current screen.
+    pub fn handle_event(&mut self, ev: AppEvent) {
+        match ev {
+            AppEvent::WindowSize(size) => self.ctx.size = size,
+            AppEvent::DpiScale(detected) => {
+                let dpi = self.ctx.state.config.dpi_scale.resolve(detected);
+                if dpi == self.ctx.style.dpi {
+                    return;
+                }
+                log::info!("[App::handle_event] DPI scale now {dpi}");
+                self.ctx.style = self.ctx.style.with_dpi(dpi);
+                self.ctx.dirty = true;
+            }
+            _ => (),
+        }
+        let screen = self.screen.inner_mut();
+        let transition = time(Stage::Event, || screen.handle_event(&mut self.ctx, ev));
+        self.apply(transition);
+    }
+    /// Advances time by `dt` seconds.
+    pub fn update(&mut self, dt: f64) {
+        self.ctx.now += dt;
+        let transition = self.screen.inner_mut().update(&mut self.ctx, dt);
+        self.apply(transition);
+        if self.ctx.toasts.update(self.ctx.now) {
+            self.ctx.dirty = true;
+        }
+    }
+    /// Draws the current screen, with any toasts over it.
+    pub fn draw(&self, gfx: &dyn Renderer) {
+        let ctx = &self.ctx;
+        time(Stage::Layout, || self.screen.inner().draw(ctx, gfx));
+        let (font, font_size) = (ctx.font.as_ref(), ctx.style.result_size / 2);
+        draw_toasts(
+            gfx,
+            &ctx.toasts,
+            ctx.now,
+            font,
+            font_size,
+            ctx.themes.current(),
+        );
+    }
+    /// Draws the debug overlay, if shown, with the frame rate and `frame`'s counts.
+    ///
+    /// The overlay changes every frame, so it's drawn over the cached scene.
+    pub fn draw_overlay(&self, gfx: &dyn Renderer, fps: i32, frame: &FrameCache) {
+        if let Screen::Entry(entry) = &self.screen {
+            entry.draw_overlay(&self.ctx, gfx, fps, frame);
+        }
+    }
+
+    fn apply(&mut self, transition: Option<Transition>) {
+        match transition {
+            None => (),
+            Some(Transition::To(screen)) => {
+                self.screen = screen;
+                self.ctx.dirty = true;
+            }
+            Some(Transition::Push(screen)) => {
+                let under = std::mem::replace(&mut self.screen, screen);
+                self.below.push(under);
+                self.ctx.dirty = true;
+            }
+            Some(Transition::Pop) => match self.below.pop() {
+                Some(under) => {
+                    self.screen = under;
+                    self.screen.inner_mut().resume(&mut self.ctx);
+                    self.ctx.dirty = true;
+                }
+                None => self.quit = true,
+            },
+            Some(Transition::Quit) => self.quit = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc_count::counting::THREAD_ALLOCATIONS;
+    use crate::attract::MIN_ATTRACT_SECONDS;
+    use crate::confirmed::CONFIRMED_MARK;
+    use crate::debug::DEBUG_OVERLAY_DEFAULT;
+    use crate::entry::SHAKE_TIME;
+    use crate::entry_screen::EntryScreen;
+    use crate::escalate::Strategy;
+    use crate::events::Nav;
+    use crate::feedback::{AsTyped, MatchOutcome};
+    use crate::history::load_history;
+    use crate::keymap::Action;
+    use crate::layout::ScreenLayout;
+    use crate::length_filter::chips_height;
+    use crate::mask::{MASK_FORMAT, MASK_KEY};
+    use crate::mini::MINI_SIZE;
+    use crate::modal::{Modal, QuitChoice};
+    use crate::palette::PaletteKey;
+    use crate::pointer::PointerEvent;
+    use crate::query::cost::SCAN_COST;
+    use crate::query::QueryMode;
+    use crate::reveal::UNLOCK_HOLD;
+    use crate::screen::ScreenTrait;
+    use crate::session::AUTOSAVE_INTERVAL;
+    use crate::storage::FileStorage;
+    use crate::strings::{fill, Language, StringKey};
+    use crate::style::DpiScale;
+    use crate::toast::TOAST_TIME;
+    use crate::tutorial::TutorialStep;
+    use crate::window_title::title_for;
+    use crate::{CAPACITY_FLASH_TIME, MAX_TABS};
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    /// A directory of its own under the system's temporary directory, emptied.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Returns state saving to a fresh `FileStorage` in `dir`, and the storage.
+    fn state_in(dir: &PathBuf) -> (AppState, Arc<dyn Storage>) {
+        let storage: Arc<dyn Storage> = Arc::new(FileStorage::new(dir));
+        (
+            AppState::new(Config::default(), Arc::clone(&storage)),
+            storage,
+        )
+    }
+
+    #[test]
+    fn reading_the_history_moves_the_oldest_answers_to_the_archive() {
+        let dir = scratch_dir("history-archive");
+        let (state, storage) = state_in(&dir);
+        let lines: String = (0..HISTORY_CAPACITY + 3)
+            .map(|i| format!("2024-03-01 S{i} A{i}\n"))
+            .collect();
+        storage
+            .write_format(HISTORY_KEY, HISTORY_FORMAT, &lines)
+            .unwrap();
+        let history = state.read_history();
+        assert_eq!(history.solves.len(), HISTORY_CAPACITY);
+        assert_eq!(history.solves[0].answer, "A3");
+        let archived = storage.read_archive(HISTORY_ARCHIVE_KEY).unwrap();
+        let oldest = "2024-03-01 S0 A0\n2024-03-01 S1 A1\n2024-03-01 S2 A2\n";
+        assert_eq!(archived.as_deref(), Some(oldest));
+
+        // The live file was trimmed, so nothing is archived twice
+        assert_eq!(state.read_history(), history);
+        let archived = storage.read_archive(HISTORY_ARCHIVE_KEY).unwrap();
+        assert_eq!(archived.as_deref(), Some(oldest));
+        let everything = load_history(storage.as_ref()).unwrap();
+        assert_eq!(everything.solves.len(), HISTORY_CAPACITY + 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn shutdown_writes_a_changed_config() {
+        let dir = scratch_dir("shutdown");
+        let (mut state, storage) = state_in(&dir);
+        state.config.text_scale = 2.0;
+        state.config_changed();
+        assert_eq!(
+            storage.read(CONFIG_KEY).unwrap(),
+            None,
+            "nothing until shutdown"
+        );
+        state.shutdown();
+        let saved = Config::load(storage.as_ref());
+        assert_eq!(saved.text_scale, 2.0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn shutdown_leaves_an_unchanged_config_alone() {
+        let dir = scratch_dir("shutdown-unchanged");
+        let (mut state, storage) = state_in(&dir);
+        state.shutdown();
+        assert_eq!(storage.read(CONFIG_KEY).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_panic_writes_a_changed_config() {
+        let dir = scratch_dir("panic");
+        let (mut state, storage) = state_in(&dir);
+        state.config.text_scale = 0.5;
+        state.config_changed();
+        state.install_panic_hook();
+        let panicked = std::thread::spawn(|| panic!("on purpose")).join();
+        // Put the default hook back for the tests that follow
+        let _ = std::panic::take_hook();
+        assert!(panicked.is_err());
+        let saved = Config::load(storage.as_ref());
+        assert_eq!(saved.text_scale, 0.5);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// The words the app tests look up.
+    const WORDS: &str = "GREAT\nGRATE\nUPON\nPOUND\nSOUND\nGRATES\n";
+    /// Seconds per frame at 60 frames per second.
+    const FRAME: f64 = 1.0 / 60.0;
+
+    /// Measures every character as 0.6 em wide, so no window is needed.
+    fn measure(text: &str, _font: Option<&Font>, size: u16) -> TextDimensions {
+        let size = size as f32;
+        TextDimensions {
+            width: text.chars().count() as f32 * size * 0.6,
+            height: size,
+            offset_y: size * 0.8,
+        }
+    }
+
+    /// Keeps the text drawn and the outlines' thicknesses, measuring as `measure`
+    /// does.
+    #[derive(Default)]
+    struct TextRenderer {
+        texts: RefCell<Vec<(String, Vec2)>>,
+        outlines: RefCell<Vec<f32>>,
+    }
+
+    impl TextRenderer {
+        /// Returns whether `text` was drawn since the last clear.
+        fn drew(&self, text: &str) -> bool {
+            self.texts.borrow().iter().any(|(drawn, _)| drawn == text)
+        }
+    }
+
+    impl Renderer for TextRenderer {
+        fn screen_size(&self) -> Vec2 {
+            vec2(800.0, 600.0)
+        }
+        fn clear(&self, _: Color) {
+            self.texts.borrow_mut().clear();
+            self.outlines.borrow_mut().clear();
+        }
+        fn rect(&self, _: Rect, _: Color) {}
+        fn rect_lines(&self, _: Rect, thickness: f32, _: Color) {
+            self.outlines.borrow_mut().push(thickness);
+        }
+        fn line(&self, _: Vec2, _: Vec2, _: f32, _: Color) {}
+        fn circle(&self, _: Vec2, _: f32, _: Color) {}
+        fn triangle(&self, _: Vec2, _: Vec2, _: Vec2, _: Color) {}
+        fn text(&self, text: &str, pos: Vec2, _: Option<&Font>, _: u16, _: Color) {
+            self.texts.borrow_mut().push((text.to_owned(), pos));
+        }
+        fn measure(&self, text: &str, font: Option<&Font>, size: u16) -> TextDimensions {
+            measure(text, font, size)
+        }
+    }
+
+    /// Returns an app for an 800x600 window, saving to `dir` and loading `words`
+    /// from a file there.
+    fn app_in(dir: &PathBuf, words: &str) -> App {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join("words.txt");
+        std::fs::write(&path, words).unwrap();
+        let (mut state, _) = state_in(dir);
+        state.config.dictionary.path = Some(path.display().to_string());
+        // Not the desktop's, so effects run the same everywhere
+        state.config.reduced_motion = Some(false);
+        let sounds = Sounds::silent(state.config.volume, state.config.muted);
+        App::new(
+            state,
+            Keymap::defaults(),
+            None,
+            sounds,
+            vec2(800.0, 600.0),
+            || 0.0,
+            measure,
+        )
+    }
+
+    /// Steps `app` until it leaves the loading screen, or fails after a while.
+    fn finish_loading(app: &mut App) {
+        for _ in 0..1000 {
+            app.update(FRAME);
+            if !matches!(app.screen(), Screen::Loading(_)) {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        panic!("the dictionary never loaded");
+    }
+
+    /// Returns a loaded app, saving to `dir` and looking words up in `WORDS`.
+    fn loaded_app(dir: &PathBuf) -> App {
+        let mut app = app_in(dir, WORDS);
+        finish_loading(&mut app);
+        app
+    }
+
+    /// Returns the entry screen, which `app` must be showing.
+    fn entry(app: &App) -> &EntryScreen {
+        match app.screen() {
+            Screen::Entry(entry) => entry,
+            _ => panic!("not on the entry screen"),
+        }
+    }
+
+    /// Types `text` and steps a frame.
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_event(AppEvent::Letter(c));
+        }
+        app.update(FRAME);
+    }
+
+    /// Sends `action` and steps a frame.
+    fn act(app: &mut App, action: Action) {
+        app.handle_event(AppEvent::Action(action));
+        app.update(FRAME);
+    }
+
+    #[test]
+    fn a_loaded_dictionary_moves_on_to_entry() {
+        let dir = scratch_dir("app-load");
+        let mut app = app_in(&dir, WORDS);
+        assert!(matches!(app.screen(), Screen::Loading(_)));
+        assert!(app.is_busy(), "the spinner turns");
+        finish_loading(&mut app);
+        assert_eq!(entry(&app).entry(), "");
+        assert!(app.take_redraw(), "the new screen is drawn");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn an_empty_dictionary_shows_an_error() {
+        let dir = scratch_dir("app-load-empty");
+        let mut app = app_in(&dir, "");
+        finish_loading(&mut app);
+        let lang = app.ctx.state.config.language;
+        match app.screen() {
+            Screen::Error(error) => {
+                assert_eq!(error.message, lang.text(StringKey::EmptyDictionary))
+            }
+            _ => panic!("not on the error screen"),
+        }
+        // The error screen's only way out is quitting
+        type_text(&mut app, "NUPO");
+        assert!(!app.is_quitting());
+        act(&mut app, Action::Quit);
+        assert!(app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn quitting_while_loading_needs_no_confirmation() {
+        let dir = scratch_dir("app-load-quit");
+        let mut app = app_in(&dir, WORDS);
+        app.handle_event(AppEvent::CloseRequested);
+        assert!(app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn typing_a_scramble_finds_its_answer() {
+        let dir = scratch_dir("app-typing");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "nupo");
+        assert_eq!(entry(&app).entry(), "NUPO");
+        assert_eq!(entry(&app).matches(), ["UPON"]);
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "TAGER");
+        assert_eq!(entry(&app).matches(), ["GREAT", "GRATE"]);
+        assert_eq!(entry(&app).answer(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn edits_are_looked_up_on_the_next_update() {
+        let dir = scratch_dir("app-update");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        app.handle_event(AppEvent::Action(Action::DeleteBack));
+        assert_eq!(entry(&app).entry(), "NUP");
+        assert_eq!(entry(&app).matches(), ["UPON"], "not until the update");
+        app.update(FRAME);
+        assert!(entry(&app).matches().is_empty());
+        assert_eq!(entry(&app).answer(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_full_entry_flashes_for_the_flash_time() {
+        let dir = scratch_dir("app-flash");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        let thickness = app.ctx.themes.current().outline_thickness;
+        let outline = |app: &App| {
+            app.draw(&gfx);
+            gfx.outlines.borrow()[0]
+        };
+        // The entry holds as many letters as the longest word, GRATES
+        type_text(&mut app, "AAAAAA");
+        assert_eq!(outline(&app), thickness);
+        type_text(&mut app, "B");
+        assert_eq!(entry(&app).entry(), "AAAAAA");
+        assert_eq!(outline(&app), thickness * 3.0);
+        let frames = (CAPACITY_FLASH_TIME / FRAME).ceil() as usize;
+        for _ in 0..frames {
+            app.update(FRAME);
+        }
+        assert_eq!(outline(&app), thickness);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_full_entry_counts_its_letters_and_shakes_off_another() {
+        let dir = scratch_dir("app-shake");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        let entry_x = |app: &App| {
+            app.draw(&gfx);
+            let texts = gfx.texts.borrow();
+            texts.iter().find(|(text, _)| text == "GRATES").unwrap().1.x
+        };
+        type_text(&mut app, "GRATES");
+        app.update(FRAME);
+        let still = entry_x(&app);
+        assert!(gfx.drew("6/6"));
+
+        type_text(&mut app, "X");
+        app.update(SHAKE_TIME / 4.0);
+        assert_ne!(entry_x(&app), still);
+        let frames = (SHAKE_TIME / FRAME).ceil() as usize;
+        for _ in 0..frames {
+            app.update(FRAME);
+        }
+        assert_eq!(entry_x(&app), still);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reduced_motion_keeps_the_entry_still_and_the_demo_off() {
+        let dir = scratch_dir("app-reduced-motion");
+        let mut app = loaded_app(&dir);
+        app.ctx.reduced_motion = true;
+        app.ctx.state.config.attract = true;
+        app.ctx.state.config.attract_seconds = 1.0;
+        let gfx = TextRenderer::default();
+        let entry_x = |app: &App| {
+            app.draw(&gfx);
+            let texts = gfx.texts.borrow();
+            texts.iter().find(|(text, _)| text == "GRATES").unwrap().1.x
+        };
+        type_text(&mut app, "GRATES");
+        let still = entry_x(&app);
+        type_text(&mut app, "X");
+        assert!(gfx.drew("6/6"));
+        for _ in 0..4 {
+            assert_eq!(entry_x(&app), still);
+            app.update(SHAKE_TIME / 4.0);
+        }
+
+        // Well past the shortest wait for the demo
+        for _ in 0..(MIN_ATTRACT_SECONDS * 8.0) as u32 {
+            app.update(0.25);
+        }
+        assert!(!matches!(app.screen(), Screen::Attract(_)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn coverage_is_tallied_and_kept_across_restarts() {
+        let dir = scratch_dir("app-coverage");
+        let mut app = loaded_app(&dir);
+        for scramble in ["NUPO", "TAGER", "ZZZZ", ":len 4"] {
+            type_text(&mut app, scramble);
+            app.update(FRAME);
+            act(&mut app, Action::ClearEntry);
+        }
+        app.shutdown();
+        let tallies = app.ctx.state.read_coverage();
+        let (_, tally) = tallies.sources.iter().next().unwrap();
+        assert_eq!((tally.unique, tally.ambiguous, tally.miss), (1, 1, 1));
+
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        app.update(FRAME);
+        app.shutdown();
+        let tallies = app.ctx.state.read_coverage();
+        assert_eq!(tallies.sources.values().next().unwrap().unique, 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn features_used_are_counted_only_when_on_and_add_up_across_restarts() {
+        let dir = scratch_dir("app-usage");
+        let stored = |app: &App| {
+            let text = app.ctx.state.storage.read(USAGE_KEY).unwrap();
+            text.map(|text| UsageLog::parse(&text).unwrap())
+        };
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::ToggleBigMode);
+        app.shutdown();
+        assert_eq!(stored(&app), None);
+
+        let today = crate::date::today().unwrap();
+        for _ in 0..2 {
+            let mut app = loaded_app(&dir);
+            app.ctx.state.config.usage_metrics = true;
+            act(&mut app, Action::ToggleBigMode);
+            act(&mut app, Action::ToggleBigMode);
+            act(&mut app, Action::ShowHelp);
+            app.handle_event(AppEvent::Dismiss);
+            // Letters typed aren't a feature
+            type_text(&mut app, "NUPO");
+            app.handle_event(AppEvent::Paste("TAGER".into()));
+            app.shutdown();
+        }
+        let usage = stored(&app_in(&dir, WORDS)).unwrap();
+        assert_eq!(usage.get(today, Feature::BigMode), 4);
+        assert_eq!(usage.get(today, Feature::Help), 2);
+        assert_eq!(usage.get(today, Feature::Paste), 2);
+        assert_eq!(usage.get(today, Feature::Hint), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn confirmed_answers_are_marked_and_kept_across_restarts() {
+        let dir = scratch_dir("app-confirmed");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "DNUOP");
+        act(&mut app, Action::ConfirmAnswer);
+        app.draw(&gfx);
+        assert!(gfx.drew("Confirmed POUND"));
+        act(&mut app, Action::ConfirmAnswer);
+        app.draw(&gfx);
+        assert!(gfx.drew("POUND was already confirmed"));
+        // Several matches aren't an answer to confirm
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "TAGER");
+        act(&mut app, Action::ConfirmAnswer);
+        assert_eq!(app.ctx.state.confirmed.words(), ["POUND"]);
+        app.shutdown();
+
+        let mut app = loaded_app(&dir);
+        assert_eq!(app.ctx.state.confirmed.words(), ["POUND"]);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "POUNDS");
+        app.update(app.ctx.state.config.debounce_ms as f64 / 1000.0);
+        assert!(entry(&app).matches().len() > 1);
+        // The confirmed word carries the check mark among the others
+        let gfx = TextRenderer::default();
+        app.draw(&gfx);
+        assert!(gfx
+            .texts
+            .borrow()
+            .iter()
+            .any(|(text, _)| text.contains(CONFIRMED_MARK)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_inspector_shows_the_selected_word_and_hides_it_on_x() {
+        let dir = scratch_dir("app-inspect");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "TAGER");
+        // Nothing is selected, and there's no single answer
+        act(&mut app, Action::InspectWord);
+        app.draw(&gfx);
+        assert!(!gfx.drew("AEGRT"));
+        let results = ScreenLayout::new(800.0, 600.0, 1.0).results;
+        let row_h = app.ctx.style.result_size as f32 * 1.3;
+        let first = vec2(results.x + 10.0, results.y + row_h * 0.5);
+        app.handle_event(AppEvent::Pointer(PointerEvent::Pressed(first)));
+        app.handle_event(AppEvent::Pointer(PointerEvent::Released(first)));
+        act(&mut app, Action::InspectWord);
+        app.draw(&gfx);
+        assert!(gfx.drew("AEGRT"));
+        type_text(&mut app, "X");
+        app.handle_event(AppEvent::Dismiss);
+        app.update(FRAME);
+        let tab = &entry(&app).tabs().0[0];
+        let shown: Vec<&str> = tab.shown().map(|i| tab.matches()[i].as_str()).collect();
+        assert_eq!(shown, ["GRATE"]);
+        let storage = FileStorage::new(&dir);
+        let masked = storage.read_format(MASK_KEY, MASK_FORMAT).unwrap();
+        assert_eq!(masked.as_deref(), Some("GREAT\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn masked_words_stay_hidden_until_revealed() {
+        let dir = scratch_dir("app-mask");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MASK_KEY), "grate\nupon\n").unwrap();
+        let mut app = app_in(&dir, WORDS);
+        finish_loading(&mut app);
+        let shown = |app: &App| -> Vec<String> {
+            let tab = &entry(app).tabs().0[0];
+            tab.shown().map(|i| tab.matches()[i].clone()).collect()
+        };
+        type_text(&mut app, "TAGER");
+        assert_eq!(shown(&app), ["GREAT"]);
+        assert_eq!(entry(&app).tabs().0[0].hidden_count(), 1);
+        act(&mut app, Action::RevealHidden);
+        assert_eq!(shown(&app), ["GREAT", "GRATE"]);
+        act(&mut app, Action::RevealHidden);
+        assert_eq!(shown(&app), ["GREAT"]);
+
+        // An edit hides them again
+        act(&mut app, Action::RevealHidden);
+        act(&mut app, Action::DeleteBack);
+        type_text(&mut app, "R");
+        assert_eq!(shown(&app), ["GREAT"]);
+
+        // A masked lone match is never the answer, even revealed
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "NUPO");
+        assert_eq!(entry(&app).tabs().0[0].answer(), None);
+        act(&mut app, Action::RevealHidden);
+        assert_eq!(shown(&app), ["UPON"]);
+        assert_eq!(entry(&app).tabs().0[0].answer(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_final_answer_is_spelled_from_the_entrys_letters() {
+        let dir = scratch_dir("app-pool");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "TOAN");
+        act(&mut app, Action::SpellFinal);
+        // Typing now spells the phrase; the entry is left alone
+        type_text(&mut app, "AT ON");
+        app.draw(&gfx);
+        assert!(gfx.drew("AT ON"));
+        // The only A is used up, so another is refused
+        type_text(&mut app, "A");
+        app.draw(&gfx);
+        assert!(gfx.drew("AT ON") && !gfx.drew("AT ONA"));
+        act(&mut app, Action::DeleteBack);
+        app.draw(&gfx);
+        assert!(gfx.drew("AT O"));
+        app.handle_event(AppEvent::Dismiss);
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "TOAN");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_mini_window_shrinks_to_the_entry_and_answer_and_grows_back() {
+        let dir = scratch_dir("app-mini");
+        let mut app = loaded_app(&dir);
+        app.ctx.can_resize = true;
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "TAGER");
+        act(&mut app, Action::ToggleMini);
+        assert_eq!(app.take_window_size(), Some(MINI_SIZE));
+        app.draw(&gfx);
+        assert!(gfx.drew("TAGER") && gfx.drew("GREAT"));
+        assert!(!gfx.drew("GRATE"), "the list isn't shown");
+        act(&mut app, Action::ToggleMini);
+        assert_eq!(app.take_window_size(), Some(vec2(800.0, 600.0)));
+        app.draw(&gfx);
+        assert!(gfx.drew("GRATE"));
+
+        // Where the window can't be resized, the strip goes in the window as it is
+        app.ctx.can_resize = false;
+        act(&mut app, Action::ToggleMini);
+        assert_eq!(app.take_window_size(), None);
+        app.draw(&gfx);
+        assert!(!gfx.drew("GRATE"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn locked_answers_never_leak_until_the_lock_is_held_open() {
+        let dir = scratch_dir("app-reveal-lock");
+        let mut app = loaded_app(&dir);
+        app.ctx.can_resize = true;
+        let gfx = TextRenderer::default();
+        let words = ["GREAT", "GRATE", "UPON", "POUND", "SOUND", "GRATES"];
+        // Every text drawn, the title, and what spectators see keep the words out
+        let assert_nothing_leaks = |app: &App, when: &str| {
+            app.draw(&gfx);
+            for (text, _) in gfx.texts.borrow().iter() {
+                let word = words.iter().find(|word| text.contains(*word));
+                assert!(word.is_none(), "{when}: drew '{text}'");
+            }
+            let title = title_for(app.screen(), &app.ctx.state.config);
+            assert!(
+                !words.iter().any(|word| title.contains(word)),
+                "{when}: {title}"
+            );
+            let summary = entry(app).summary();
+            assert_eq!(summary.answer, None, "{when}");
+            assert!(
+                summary.matches.is_empty() && summary.solved.is_empty(),
+                "{when}"
+            );
+        };
+        act(&mut app, Action::ToggleRevealLock);
+        assert!(!entry(&app).policy().shows_words());
+
+        let pause = app.ctx.state.config.debounce_ms as f64 / 1000.0;
+        // One answer, several matches, completions, and a typo's fixes
+        for scramble in ["DNUOP", "TAGER", "DNOU", "NUDIP"] {
+            type_text(&mut app, scramble);
+            app.update(pause);
+            assert_nothing_leaks(&app, scramble);
+            act(&mut app, Action::ClearEntry);
+        }
+        type_text(&mut app, "DNUOP");
+        app.update(pause);
+        app.draw(&gfx);
+        assert!(gfx.drew("1 match."));
+        for mode in [Action::ToggleBigMode, Action::ToggleMini] {
+            act(&mut app, mode);
+            assert_nothing_leaks(&app, mode.name());
+            act(&mut app, mode);
+        }
+
+        // Nothing copies, prints, or hints at a word either
+        let lang = app.ctx.state.config.language;
+        for action in [Action::CopyAnswer, Action::ShowHint, Action::InspectWord] {
+            act(&mut app, action);
+            assert_eq!(app.ctx.clipboard, None, "{}", action.name());
+            assert_nothing_leaks(&app, action.name());
+            assert!(gfx.drew(lang.text(StringKey::AnswersStillLocked)));
+        }
+
+        // A short press doesn't unlock, holding long enough does
+        act(&mut app, Action::ToggleRevealLock);
+        app.handle_event(AppEvent::ActionReleased(Action::ToggleRevealLock));
+        app.update(UNLOCK_HOLD);
+        assert!(!entry(&app).policy().shows_words());
+        act(&mut app, Action::ToggleRevealLock);
+        app.update(UNLOCK_HOLD);
+        assert!(entry(&app).policy().shows_words());
+        app.draw(&gfx);
+        assert!(gfx.drew("POUND"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn assist_holds_the_answer_back_while_the_entry_has_focus() {
+        let dir = scratch_dir("app-assist");
+        let mut app = loaded_app(&dir);
+        app.ctx.state.config.assist = true;
+        app.ctx.state.config.assist_seconds = 5.0;
+        let wait = |app: &mut App, seconds: u32| {
+            for _ in 0..seconds * 4 {
+                app.update(0.25);
+            }
+        };
+        let revealed = |app: &App| entry(app).tabs().0[0].assist.is_revealed();
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUPO");
+        app.draw(&gfx);
+        assert!(!gfx.drew("UPON"));
+        wait(&mut app, 4);
+        assert!(!revealed(&app));
+
+        // A dialog over the screen pauses it
+        act(&mut app, Action::ShowHelp);
+        wait(&mut app, 30);
+        app.handle_event(AppEvent::Dismiss);
+        wait(&mut app, 1);
+        assert!(!revealed(&app));
+        wait(&mut app, 1);
+        assert!(revealed(&app));
+        app.draw(&gfx);
+        assert!(gfx.drew("UPON"));
+
+        // Typed again, it starts over, and Enter shows it at once
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "NUPO");
+        assert!(!revealed(&app));
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.update(FRAME);
+        assert!(revealed(&app));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_demo_plays_when_idle_and_any_input_puts_everything_back() {
+        let dir = scratch_dir("app-attract");
+        let mut app = loaded_app(&dir);
+        app.ctx.state.config.attract = true;
+        // Below the shortest wait, which is used instead
+        app.ctx.state.config.attract_seconds = 1.0;
+        let wait = |app: &mut App, seconds: f64| {
+            for _ in 0..(seconds * 4.0) as u32 {
+                app.update(0.25);
+            }
+        };
+        let attract = |app: &App| matches!(app.screen(), Screen::Attract(_));
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "TAGER");
+        let recall = app.ctx.state.recall.clone();
+
+        // Resizing doesn't count as input
+        wait(&mut app, 3.0);
+        app.handle_event(AppEvent::WindowSize(vec2(700.0, 500.0)));
+        wait(&mut app, 1.75);
+        assert!(!attract(&app));
+        wait(&mut app, 0.5);
+        assert!(attract(&app));
+        let gfx = TextRenderer::default();
+        app.draw(&gfx);
+        assert!(gfx.drew("DEMO"));
+
+        // The demo types and clears a scramble, and a resize doesn't end it
+        wait(&mut app, 8.0);
+        app.handle_event(AppEvent::WindowSize(vec2(800.0, 600.0)));
+        app.update(FRAME);
+        assert!(attract(&app));
+        assert_ne!(app.ctx.state.recall, recall);
+
+        // A key ends it without being typed, and the entry and recall are as they were
+        app.handle_event(AppEvent::Letter('X'));
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        assert_eq!(app.ctx.state.recall, recall);
+        // The wait starts over
+        wait(&mut app, 4.75);
+        assert!(!attract(&app));
+        wait(&mut app, 0.5);
+        assert!(attract(&app));
+        app.handle_event(AppEvent::Nav(Nav::Down));
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn up_and_down_call_back_cleared_and_answered_entries() {
+        let dir = scratch_dir("app-recall");
+        let mut app = loaded_app(&dir);
+        let nav = |app: &mut App, nav: Nav| {
+            app.handle_event(AppEvent::Nav(nav));
+            app.update(FRAME);
+        };
+        type_text(&mut app, "TAGER");
+        act(&mut app, Action::ClearEntry);
+        // Found as the answer, NUPO is remembered without being cleared
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::DeleteBack);
+        act(&mut app, Action::DeleteBack);
+        nav(&mut app, Nav::Up);
+        assert_eq!(entry(&app).entry(), "NUPO");
+        assert_eq!(entry(&app).matches(), ["UPON"]);
+        nav(&mut app, Nav::Up);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        nav(&mut app, Nav::Down);
+        nav(&mut app, Nav::Down);
+        assert_eq!(entry(&app).entry(), "NU");
+
+        // Typing carries on from the entry called back
+        nav(&mut app, Nav::Up);
+        type_text(&mut app, "S");
+        assert_eq!(entry(&app).entry(), "NUPOS");
+        nav(&mut app, Nav::Down);
+        assert_eq!(entry(&app).entry(), "NUPOS");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn letters_that_already_spell_a_match_are_told_apart_from_an_answer() {
+        let dir = scratch_dir("app-as-typed");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "POUND");
+        let tab = &entry(&app).tabs().0[0];
+        assert_eq!((tab.as_typed, tab.answer()), (AsTyped::OnlyMatch, None));
+        assert_eq!(tab.outcomes.current(), MatchOutcome::AsTyped);
+        app.draw(&gfx);
+        assert!(gfx.drew("These letters already spell POUND — no other anagram found"));
+
+        // Among other matches the letters as typed are kept, but listed last
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "GRATE");
+        let tab = &entry(&app).tabs().0[0];
+        assert_eq!(tab.as_typed, AsTyped::AmongOthers(1));
+        let shown: Vec<&str> = tab.shown().map(|i| tab.matches()[i].as_str()).collect();
+        assert_eq!(shown, ["GREAT", "GRATE"]);
+        app.draw(&gfx);
+        assert!(gfx.drew("(as typed)"));
+
+        // Letters that aren't a word as typed give their lone match as the answer
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "DNUOP");
+        assert_eq!(entry(&app).tabs().0[0].answer(), Some("POUND"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn an_entry_with_no_exact_match_falls_back_to_looser_searches() {
+        let dir = scratch_dir("app-escalate");
+        let mut app = loaded_app(&dir);
+        app.ctx.state.config.auto_escalate = true;
+        let settle = |app: &mut App| {
+            for _ in 0..100 {
+                app.update(FRAME);
+            }
+        };
+        // One mistyped letter away from GREAT and GRATE
+        type_text(&mut app, "GRBAT");
+        settle(&mut app);
+        assert_eq!(entry(&app).tabs().0[0].escalation, Strategy::Typo);
+
+        // No typo fits, but dropping a letter leaves POUND or SOUND
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "POUNDS");
+        settle(&mut app);
+        let tab = &entry(&app).tabs().0[0];
+        assert_eq!(tab.escalation, Strategy::DropOne);
+        assert_eq!(tab.matches(), ["POUND", "SOUND"]);
+        assert_eq!(tab.answer(), None);
+
+        // Too short to be a word, nothing is tried
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "PO");
+        settle(&mut app);
+        assert_eq!(entry(&app).tabs().0[0].escalation, Strategy::Exact);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sub_anagrams_wait_for_typing_to_pause() {
+        let dir = scratch_dir("app-sub-anagrams");
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "GRATES");
+        assert!(entry(&app).matches().is_empty());
+        assert!(app.is_busy(), "frames are needed to notice the pause");
+        app.update(app.ctx.state.config.debounce_ms as f64 / 1000.0);
+        assert_eq!(entry(&app).matches(), ["GRATES", "GRATE", "GREAT"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn query_commands_run_once_typing_pauses_and_say_what_is_wrong() {
+        let dir = scratch_dir("app-query-command");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, ":pat g___e");
+        assert_eq!(entry(&app).entry(), ":PAT G___E");
+        app.update(app.ctx.state.config.debounce_ms as f64 / 1000.0);
+        assert_eq!(entry(&app).matches(), ["GRATE"]);
+
+        type_text(&mut app, " 3");
+        app.update(app.ctx.state.config.debounce_ms as f64 / 1000.0);
+        assert!(entry(&app).matches().is_empty());
+        app.draw(&gfx);
+        let lang = app.ctx.state.config.language;
+        assert!(gfx.drew(&fill(
+            lang.text(StringKey::QueryError),
+            "usage: :pat _O__ER"
+        )));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn listed_phrases_answer_phrase_queries_but_never_the_entry() {
+        let dir = scratch_dir("app-phrases");
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/phrase_words.txt");
+        let mut app = app_in(&dir, &std::fs::read_to_string(path).unwrap());
+        finish_loading(&mut app);
+        let pause = app.ctx.state.config.debounce_ms as f64 / 1000.0;
+        type_text(&mut app, "DGHOOT");
+        app.update(pause);
+        assert!(entry(&app).matches().is_empty());
+
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, ":phrase dghoot 3 3");
+        app.update(pause);
+        // Phrases can take long enough to go to the worker
+        for _ in 0..1000 {
+            if entry(&app).is_settled() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            app.update(FRAME);
+        }
+        assert_eq!(entry(&app).matches(), ["HOT DOG", "DOG HOT", "HOG TOD"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn costly_queries_wait_for_enter_and_the_worst_are_refused() {
+        let dir = scratch_dir("app-cost");
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/phrase_words.txt");
+        let mut app = app_in(&dir, &std::fs::read_to_string(path).unwrap());
+        finish_loading(&mut app);
+        let pause = app.ctx.state.config.debounce_ms as f64 / 1000.0;
+        let settle = |app: &mut App| {
+            app.update(pause);
+            for _ in 0..1000 {
+                if entry(app).is_settled() {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(2));
+                app.update(FRAME);
+            }
+        };
+        let gfx = TextRenderer::default();
+        let slow = "This search may take a while; press Enter to run it";
+        // Single scans are never held back, even at the lowest limit a phrase
+        // can pass
+        app.ctx.state.config.confirm_query_cost = SCAN_COST;
+        type_text(&mut app, ":sub lanwi");
+        settle(&mut app);
+        assert!(!entry(&app).tabs().0[0].held);
+        assert!(entry(&app).matches().contains(&"NAIL".to_owned()));
+
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, ":phrase dghoot 3 3");
+        settle(&mut app);
+        assert!(entry(&app).tabs().0[0].held);
+        assert!(entry(&app).matches().is_empty());
+        app.draw(&gfx);
+        assert!(gfx.drew(slow));
+        // Waiting longer doesn't run it; Enter does
+        settle(&mut app);
+        assert!(entry(&app).matches().is_empty());
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        settle(&mut app);
+        assert!(!entry(&app).tabs().0[0].held);
+        assert_eq!(entry(&app).matches(), ["HOT DOG", "DOG HOT", "HOG TOD"]);
+        app.draw(&gfx);
+        assert!(!gfx.drew(slow));
+
+        // Editing the command holds it back again
+        act(&mut app, Action::DeleteBack);
+        type_text(&mut app, "3");
+        settle(&mut app);
+        assert!(entry(&app).tabs().0[0].held);
+
+        app.ctx.state.config.refuse_query_cost = SCAN_COST;
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, ":phrase dghoot 3 3");
+        settle(&mut app);
+        let tab = &entry(&app).tabs().0[0];
+        assert!(!tab.held);
+        assert!(tab
+            .command_error
+            .as_ref()
+            .unwrap()
+            .starts_with("too many ways"));
+        assert!(entry(&app).matches().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn letters_left_out_never_show_whatever_the_query() {
+        let dir = scratch_dir("app-letters");
+        let mut app = loaded_app(&dir);
+        let pause = app.ctx.state.config.debounce_ms as f64 / 1000.0;
+        // Waits out the typing pause and any query sent to the worker
+        let settle = |app: &mut App| {
+            app.update(pause);
+            for _ in 0..1000 {
+                if entry(app).is_settled() {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(2));
+                app.update(FRAME);
+            }
+        };
+        let sorted = |app: &App| {
+            let mut words = entry(app).matches().to_vec();
+            words.sort();
+            words
+        };
+        type_text(&mut app, ":letters -s");
+        settle(&mut app);
+        assert_eq!(app.ctx.state.letters, LetterMask::new("S"));
+
+        let queries: [(&str, &[&str]); 6] = [
+            ("AEGRST", &[]),
+            (":sub aegrst", &["GRATE", "GREAT"]),
+            (":pat _OUND", &["POUND"]),
+            (":bank dnopsu", &["POUND", "UPON"]),
+            (":phrase dnnooppuu 4 5", &["UPON POUND"]),
+            (":phrase dnnoopsuu 4 5", &[]),
+        ];
+        for (query, expected) in queries {
+            act(&mut app, Action::ClearEntry);
+            type_text(&mut app, query);
+            settle(&mut app);
+            assert_eq!(sorted(&app), expected, "{query}");
+        }
+
+        // Sub-anagram mode drops them as the command does
+        act(&mut app, Action::ClearEntry);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "AEGRST");
+        settle(&mut app);
+        assert_eq!(sorted(&app), ["GRATE", "GREAT"]);
+        act(&mut app, Action::ToggleSubAnagrams);
+
+        // Nor are they offered to complete the entry or to fix a typo
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "DNOU");
+        settle(&mut app);
+        assert_eq!(entry(&app).tabs().0[0].completions.words, ["POUND"]);
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "DNUOT");
+        settle(&mut app);
+        let suggestions = &entry(&app).tabs().0[0].suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].words, ["POUND"]);
+
+        // Until they're brought back
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, ":letters");
+        settle(&mut app);
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "AEGRST");
+        settle(&mut app);
+        assert_eq!(entry(&app).matches(), ["GRATES"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_window_title_follows_the_entry_and_the_tabs() {
+        let dir = scratch_dir("app-title");
+        let mut app = app_in(&dir, WORDS);
+        let title = |app: &App| title_for(app.screen(), &app.ctx.state.config);
+        assert_eq!(title(&app), "Jumble Helper");
+        finish_loading(&mut app);
+        assert_eq!(title(&app), "Jumble Helper");
+
+        type_text(&mut app, "DNUOP");
+        assert_eq!(title(&app), "Jumble Helper — DNUOP → POUND");
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "TAGER");
+        assert_eq!(title(&app), "Jumble Helper — TAGER");
+
+        // A held back answer stays out of it
+        app.ctx.state.config.assist = true;
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "NUPO");
+        assert_eq!(title(&app), "Jumble Helper — NUPO");
+        app.ctx.state.config.assist = false;
+        assert_eq!(title(&app), "Jumble Helper — NUPO → UPON");
+
+        act(&mut app, Action::NewTab);
+        type_text(&mut app, "TAGER");
+        assert_eq!(title(&app), "Jumble Helper — 1/2 solved");
+        app.ctx.state.config.language = Language::Fr;
+        assert_eq!(title(&app), "Le Mélimélo — 1/2 résolus");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pins_stay_across_entries_until_found_as_the_answer() {
+        let dir = scratch_dir("app-pins");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "TAGER");
+        assert_eq!(entry(&app).matches(), ["GREAT", "GRATE"]);
+        // Nothing is selected yet
+        act(&mut app, Action::TogglePin);
+        assert!(app.ctx.state.pins.is_empty());
+
+        let results = ScreenLayout::new(800.0, 600.0, 1.0).results;
+        let row_h = app.ctx.style.result_size as f32 * 1.3;
+        let second = vec2(results.x + 10.0, results.y + row_h * 1.5);
+        app.handle_event(AppEvent::Pointer(PointerEvent::Pressed(second)));
+        app.handle_event(AppEvent::Pointer(PointerEvent::Released(second)));
+        act(&mut app, Action::TogglePin);
+        let pins = app.ctx.state.pins.as_slice();
+        assert_eq!(
+            (pins[0].word.as_str(), pins[0].query.as_str()),
+            ("GRATE", "TAGER")
+        );
+
+        // The single match is pinned when nothing is selected
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::TogglePin);
+        assert!(app.ctx.state.pins.contains("UPON"));
+        assert!(app.ctx.state.pins.contains("GRATE"));
+
+        // Found again as the answer, UPON is done with
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "NUPO");
+        assert!(!app.ctx.state.pins.contains("UPON"));
+        assert!(app.ctx.state.pins.contains("GRATE"));
+        act(&mut app, Action::ClearPins);
+        assert!(app.ctx.state.pins.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn hints_cycle_over_the_matches_and_start_over_on_an_edit() {
+        let dir = scratch_dir("app-hints");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "TAGER");
+        // GREAT, the first match, has two hints
+        act(&mut app, Action::ShowHint);
+        app.draw(&gfx);
+        assert!(gfx.drew("Starts with a consonant") && !gfx.drew("GRATE"));
+        assert!(!gfx.drew("Starts with a consonant cluster"));
+        act(&mut app, Action::ShowHint);
+        app.draw(&gfx);
+        assert!(gfx.drew("Starts with a consonant cluster"));
+        // Past the last hint, the matches are back
+        act(&mut app, Action::ShowHint);
+        app.draw(&gfx);
+        assert!(gfx.drew("GRATE") && !gfx.drew("Starts with a consonant"));
+        act(&mut app, Action::ShowHint);
+        app.draw(&gfx);
+        assert!(gfx.drew("Starts with a consonant"));
+
+        type_text(&mut app, "S");
+        app.draw(&gfx);
+        assert!(!gfx.drew("Starts with a consonant"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copying_queues_the_answer_plain_or_spelled_out() {
+        let dir = scratch_dir("app-copy");
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::CopyAnswer);
+        assert_eq!(app.ctx.clipboard, None);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::CopyAnswer);
+        assert_eq!(app.ctx.clipboard.take().as_deref(), Some("UPON"));
+        app.ctx.state.config.copy_phonetic = true;
+        act(&mut app, Action::CopyAnswer);
+        assert_eq!(
+            app.ctx.clipboard.as_deref(),
+            Some("U - uniform, P - papa, O - oscar, N - november")
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn filtering_lengths_scrolls_to_the_top_and_drops_a_hidden_selection() {
+        let dir = scratch_dir("app-lengths");
+        let words = "GRATES\nGREATS\nGREAT\nGRATE\nGEARS\nRAGES\nSTAGE\nGATES\n\
+                     TEARS\nRATES\nSTARE\nASTER\nRATE\nTEAR\nGATE\nSAGE\n";
+        let mut app = app_in(&dir, words);
+        finish_loading(&mut app);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "GRATESX");
+        app.update(app.ctx.state.config.debounce_ms as f64 / 1000.0);
+        assert_eq!(entry(&app).matches().len(), 16);
+
+        // Select GREATS, the first row since GRATES as typed sorts last, then
+        // scroll the list down
+        let results = ScreenLayout::new(800.0, 600.0, 1.0).results;
+        let row_h = app.ctx.style.result_size as f32 * 1.3;
+        let first = vec2(results.x + 10.0, results.y + row_h * 0.5);
+        app.handle_event(AppEvent::Pointer(PointerEvent::Pressed(first)));
+        app.handle_event(AppEvent::Pointer(PointerEvent::Released(first)));
+        let tab = |app: &App| entry(app).tabs().0[0].results.clone();
+        assert_eq!(tab(&app).selected, Some(1));
+        assert_eq!(entry(&app).matches()[1], "GREATS");
+        for _ in 0..3 {
+            app.handle_event(AppEvent::Wheel(-1.0));
+        }
+        assert!(tab(&app).scroll > 0.0);
+
+        act(&mut app, Action::ShowLength5);
+        assert_eq!((tab(&app).scroll, tab(&app).selected), (0.0, None));
+        let shown: Vec<&str> = {
+            let ws = &entry(&app).tabs().0[0];
+            ws.shown().map(|i| ws.matches()[i].as_str()).collect()
+        };
+        assert_eq!(shown.len(), 10);
+        assert!(shown.iter().all(|word| word.len() == 5));
+
+        // A selection the filter still shows stays; the list now starts below
+        // the length chips
+        let chips = chips_height(app.ctx.style.result_size / 2);
+        let first = first + vec2(0.0, chips);
+        app.handle_event(AppEvent::Pointer(PointerEvent::Pressed(first)));
+        app.handle_event(AppEvent::Pointer(PointerEvent::Released(first)));
+        let selected = tab(&app).selected.unwrap();
+        assert_eq!(entry(&app).matches()[selected].len(), 5);
+        act(&mut app, Action::ShowLength4);
+        assert_eq!(tab(&app).selected, Some(selected));
+        act(&mut app, Action::ShowAllLengths);
+        assert_eq!(entry(&app).tabs().0[0].shown().count(), 16);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_mistyped_letter_is_suggested_until_the_entry_changes() {
+        let dir = scratch_dir("app-typo");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUDIP");
+        assert!(entry(&app).matches().is_empty());
+        let suggestions = &entry(&app).tabs().0[0].suggestions;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].entry, "NUDOP");
+        app.draw(&gfx);
+        assert!(gfx.drew("NUDOP") && gfx.drew("  POUND"));
+
+        act(&mut app, Action::DeleteBack);
+        assert!(entry(&app).tabs().0[0].suggestions.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn quitting_an_empty_entry_needs_no_confirmation() {
+        let dir = scratch_dir("app-quit-empty");
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::Quit);
+        assert!(app.is_quitting());
+        assert_eq!(app.focus(), Focus::Entry);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn quitting_an_entry_in_progress_asks_first() {
+        let dir = scratch_dir("app-quit-ask");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        for request in [AppEvent::Action(Action::Quit), AppEvent::CloseRequested] {
+            app.handle_event(request);
+            app.update(FRAME);
+            assert!(!app.is_quitting());
+            assert_eq!(app.focus(), Focus::Modal(Modal::ConfirmQuit));
+
+            // The dialog takes all input
+            type_text(&mut app, "S");
+            act(&mut app, Action::ClearEntry);
+            assert_eq!(entry(&app).entry(), "NUPO");
+
+            app.handle_event(AppEvent::QuitChoice(QuitChoice::Stay));
+            app.update(FRAME);
+            assert_eq!(app.focus(), Focus::Entry);
+            assert!(!app.is_quitting());
+        }
+        app.handle_event(AppEvent::Action(Action::Quit));
+        app.update(FRAME);
+        app.handle_event(AppEvent::QuitChoice(QuitChoice::Quit));
+        app.update(FRAME);
+        assert!(app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn quit_choices_need_the_dialog() {
+        let dir = scratch_dir("app-quit-choice");
+        let mut app = loaded_app(&dir);
+        app.handle_event(AppEvent::QuitChoice(QuitChoice::Quit));
+        app.update(FRAME);
+        assert!(!app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn an_entry_saved_on_quit_is_restored_next_launch() {
+        let dir = scratch_dir("app-save-entry");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        app.handle_event(AppEvent::Action(Action::Quit));
+        app.update(FRAME);
+        app.handle_event(AppEvent::QuitChoice(QuitChoice::SaveAndQuit));
+        app.update(FRAME);
+        assert!(app.is_quitting());
+        app.shutdown();
+        let storage = FileStorage::new(&dir);
+        assert_eq!(
+            storage
+                .read_format(ENTRY_KEY, ENTRY_FORMAT)
+                .unwrap()
+                .as_deref(),
+            Some("NUPO")
+        );
+
+        let mut app = loaded_app(&dir);
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "NUPO");
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+        // Restored once only
+        app.shutdown();
+        assert_eq!(
+            storage
+                .read_format(ENTRY_KEY, ENTRY_FORMAT)
+                .unwrap()
+                .as_deref(),
+            Some("")
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn settings_changed_by_actions_are_saved_on_shutdown() {
+        let dir = scratch_dir("app-settings");
+        let mut app = loaded_app(&dir);
+        for action in [
+            Action::ToggleMute,
+            Action::ToggleBigMode,
+            Action::TextLarger,
+        ] {
+            act(&mut app, action);
+        }
+        app.shutdown();
+        let saved = Config::load(&FileStorage::new(&dir));
+        assert!(saved.muted && saved.big_mode);
+        assert!(saved.text_scale > Config::default().text_scale);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_new_dpi_scale_resizes_the_text_unless_the_config_fixes_it() {
+        let dir = scratch_dir("app-dpi");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::TextLarger);
+        app.update(FRAME);
+        assert!(app.take_redraw());
+        assert_eq!(app.ctx.style.entry_size, 79);
+        app.handle_event(AppEvent::DpiScale(2.0));
+        app.update(FRAME);
+        assert!(app.take_redraw());
+        assert_eq!(app.ctx.style.entry_size, 158);
+        assert_eq!(app.ctx.style.scale, 1.1);
+        // The same scale again changes nothing
+        app.handle_event(AppEvent::DpiScale(2.0));
+        app.update(FRAME);
+        assert!(!app.take_redraw());
+        // A bogus scale falls back to 1
+        app.handle_event(AppEvent::DpiScale(0.0));
+        assert_eq!(app.ctx.style.entry_size, 79);
+
+        app.ctx.state.config.dpi_scale = DpiScale::Fixed(1.5);
+        app.handle_event(AppEvent::DpiScale(2.0));
+        assert_eq!(app.ctx.style.dpi, 1.5);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_resize_moves_the_entry() {
+        let dir = scratch_dir("app-resize");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUPO");
+        let entry_pos = |app: &App| {
+            app.draw(&gfx);
+            let texts = gfx.texts.borrow();
+            texts.iter().find(|(text, _)| text == "NUPO").unwrap().1
+        };
+        let before = entry_pos(&app);
+        app.handle_event(AppEvent::WindowSize(vec2(1920.0, 1080.0)));
+        app.update(FRAME);
+        let after = entry_pos(&app);
+        assert_eq!(after.x - before.x, (1920.0 - 800.0) / 2.0);
+        assert!(after.y > before.y);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_scene_shows_the_entry_and_its_answer() {
+        let dir = scratch_dir("app-draw");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUPO");
+        assert!(app.take_redraw());
+        app.draw(&gfx);
+        assert!(gfx.drew("NUPO") && gfx.drew("UPON"));
+        // Nothing changed, so nothing to redraw
+        app.update(FRAME);
+        assert!(!app.take_redraw());
+
+        act(&mut app, Action::ClearEntry);
+        type_text(&mut app, "TAGER");
+        app.draw(&gfx);
+        assert!(gfx.drew("GREAT") && gfx.drew("GRATE"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Returns whether `app` is showing the settings screen.
+    fn in_settings(app: &App) -> bool {
+        matches!(app.screen(), Screen::Settings(_))
+    }
+
+    #[test]
+    fn settings_open_over_the_entry_and_close_back_to_it() {
+        let dir = scratch_dir("app-settings-push");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::OpenSettings);
+        assert!(in_settings(&app));
+        // Letters don't reach the entry underneath
+        type_text(&mut app, "S");
+        act(&mut app, Action::OpenSettings);
+        assert_eq!(entry(&app).entry(), "NUPO");
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+
+        // So does tapping the gear
+        let gear = ScreenLayout::new(800.0, 600.0, 1.0).settings.center();
+        app.handle_event(AppEvent::Pointer(PointerEvent::Pressed(gear)));
+        app.handle_event(AppEvent::Pointer(PointerEvent::Released(gear)));
+        assert!(in_settings(&app));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn escape_backs_out_of_settings_but_quits_from_the_entry() {
+        let dir = scratch_dir("app-settings-escape");
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::OpenSettings);
+        act(&mut app, Action::Quit);
+        assert!(!app.is_quitting());
+        assert_eq!(entry(&app).entry(), "");
+        act(&mut app, Action::Quit);
+        assert!(app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn closing_the_window_from_settings_quits() {
+        let dir = scratch_dir("app-settings-close");
+        let mut app = loaded_app(&dir);
+        act(&mut app, Action::OpenSettings);
+        app.handle_event(AppEvent::CloseRequested);
+        assert!(app.is_quitting());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn settings_apply_on_return_and_are_saved_on_leaving() {
+        let dir = scratch_dir("app-settings-apply");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::OpenSettings);
+        // Big mode is the sixth setting
+        for _ in 0..5 {
+            app.handle_event(AppEvent::Nav(Nav::Down));
+        }
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        assert!(app.ctx.state.config.big_mode);
+        act(&mut app, Action::Quit);
+        assert!(Config::load(&FileStorage::new(&dir)).big_mode);
+
+        // The entry comes back in big mode, which draws no entry box, once the
+        // "saved" toast is gone
+        for _ in 0..(TOAST_TIME / FRAME) as usize + 2 {
+            app.update(FRAME);
+        }
+        app.draw(&gfx);
+        assert!(gfx.drew("NUPO") && gfx.drew("UPON"));
+        assert!(gfx.outlines.borrow().is_empty());
+        assert_eq!(entry(&app).entry(), "NUPO");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaving_settings_shows_a_toast_until_it_expires() {
+        let dir = scratch_dir("app-settings-toast");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        act(&mut app, Action::OpenSettings);
+        act(&mut app, Action::Quit);
+        let saved = app.ctx.state.config.language.text(StringKey::SettingsSaved);
+        app.draw(&gfx);
+        assert!(gfx.drew(saved));
+        assert!(app.is_busy(), "frames are needed to expire it");
+
+        for _ in 0..(TOAST_TIME / FRAME) as usize + 2 {
+            app.update(FRAME);
+            app.take_redraw();
+        }
+        assert!(!app.is_busy());
+        app.draw(&gfx);
+        assert!(!gfx.drew(saved));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_pasted_pattern_limits_the_matches_until_the_entry_changes() {
+        let dir = scratch_dir("app-paste");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        let lang = app.ctx.state.config.language;
+        app.handle_event(AppEvent::Paste("1. T A G E R (_ _ _ T _)".into()));
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        assert_eq!(entry(&app).matches(), ["GRATE"]);
+        app.draw(&gfx);
+        assert!(gfx.drew(&fill(lang.text(StringKey::PatternApplied), "___T_")));
+
+        act(&mut app, Action::DeleteBack);
+        type_text(&mut app, "R");
+        assert_eq!(entry(&app).matches(), ["GREAT", "GRATE"]);
+
+        app.handle_event(AppEvent::Paste("1. two lower words".into()));
+        app.update(FRAME);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        app.draw(&gfx);
+        assert!(gfx.drew(lang.text(StringKey::NoScramble)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn help_opens_over_the_entry_and_any_key_closes_it() {
+        let dir = scratch_dir("app-help");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::ShowHelp);
+        assert_eq!(entry(&app).focus(), Focus::Modal(Modal::Help));
+        app.draw(&gfx);
+        assert!(gfx.drew("Keys") && gfx.drew("Delete last letter"));
+
+        // Keys go to the help, not the entry
+        type_text(&mut app, "S");
+        act(&mut app, Action::ClearEntry);
+        assert_eq!(entry(&app).entry(), "NUPO");
+
+        app.handle_event(AppEvent::Dismiss);
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        app.update(FRAME);
+        app.draw(&gfx);
+        assert!(!gfx.drew("Keys") && gfx.drew("UPON"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_tutorial_moves_on_as_each_step_is_done() {
+        let dir = scratch_dir("app-tutorial");
+        let mut app = app_in(&dir, "TRICK\nSTYLE\nGREAT\n");
+        finish_loading(&mut app);
+        let pause = app.ctx.state.config.debounce_ms as f64 / 1000.0;
+        let gfx = TextRenderer::default();
+        let step = |app: &App| {
+            app.draw(&gfx);
+            (1..=TutorialStep::ALL.len())
+                .find(|n| gfx.drew(&format!("{n}/{}", TutorialStep::ALL.len())))
+        };
+        act(&mut app, Action::ShowHelp);
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        assert!(matches!(app.screen(), Screen::Tutorial(_)));
+        assert_eq!(step(&app), Some(1));
+
+        // Enter with no answer for the clue writes nothing in
+        type_text(&mut app, "KRICX");
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        act(&mut app, Action::DeleteBack);
+        assert_eq!(step(&app), Some(1));
+        type_text(&mut app, "T");
+        assert_eq!(step(&app), Some(2));
+        app.update(pause);
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.update(FRAME);
+        assert_eq!(step(&app), Some(3));
+
+        type_text(&mut app, "LYTES");
+        app.update(pause);
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.update(FRAME);
+        assert_eq!(step(&app), Some(4));
+        type_text(&mut app, "TRITY");
+        assert_eq!(step(&app), Some(5));
+        act(&mut app, Action::SpellFinal);
+        type_text(&mut app, "TRY IT");
+        assert_eq!(step(&app), Some(6));
+
+        // Enter leaves once it's solved, as Escape would any time
+        app.handle_event(AppEvent::Dismiss);
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.update(FRAME);
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+
+        act(&mut app, Action::ShowHelp);
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        assert_eq!(step(&app), Some(1));
+        act(&mut app, Action::Quit);
+        assert!(matches!(app.screen(), Screen::Entry(_)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_palette_runs_the_chosen_action_and_closes() {
+        let dir = scratch_dir("app-palette");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::CommandPalette);
+        assert_eq!(entry(&app).focus(), Focus::Modal(Modal::Palette));
+
+        // Letters go to the palette's query, not the entry
+        type_text(&mut app, "clear");
+        assert_eq!(entry(&app).entry(), "NUPO");
+        app.handle_event(AppEvent::PaletteKey(PaletteKey::Run));
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        assert_eq!(entry(&app).entry(), "");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn onboarding_walks_through_its_steps_then_shows_the_entry() {
+        let dir = scratch_dir("app-onboarding");
+        let mut app = app_in(&dir, WORDS);
+        app.ctx.state.onboarding = true;
+        finish_loading(&mut app);
+        assert!(matches!(app.screen(), Screen::Onboarding(_)));
+
+        // Typing doesn't reach the entry underneath
+        type_text(&mut app, "NUPO");
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.handle_event(AppEvent::Nav(Nav::Left));
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        assert!(matches!(app.screen(), Screen::Onboarding(_)));
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        assert_eq!(entry(&app).entry(), "");
+
+        // Finishing saves the config, so the next start skips onboarding
+        assert!(FileStorage::new(&dir).read(CONFIG_KEY).unwrap().is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn escape_skips_the_rest_of_onboarding() {
+        let dir = scratch_dir("app-onboarding-skip");
+        let mut app = app_in(&dir, WORDS);
+        app.ctx.state.onboarding = true;
+        finish_loading(&mut app);
+        act(&mut app, Action::Quit);
+        assert!(!app.is_quitting());
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Returns each tab's entry, and the index of the one in front.
+    fn tab_entries(app: &App) -> (Vec<String>, usize) {
+        let (tabs, active) = entry(app).tabs();
+        let entries = tabs.iter().map(|t| t.entry.as_str().to_owned()).collect();
+        (entries, active)
+    }
+
+    #[test]
+    fn tabs_open_in_front_and_cycle_in_order() {
+        let dir = scratch_dir("app-tabs");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::NewTab);
+        type_text(&mut app, "TAGER");
+        act(&mut app, Action::NewTab);
+        assert_eq!(
+            tab_entries(&app),
+            (vec!["NUPO".into(), "TAGER".into(), "".into()], 2)
+        );
+
+        act(&mut app, Action::NextTab);
+        assert_eq!(tab_entries(&app).1, 0);
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+        act(&mut app, Action::NextTab);
+        assert_eq!(entry(&app).entry(), "TAGER");
+        assert_eq!(entry(&app).matches().len(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn tabs_keep_their_own_entry_and_mode() {
+        let dir = scratch_dir("app-tabs-isolated");
+        let mut app = loaded_app(&dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::NewTab);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "DNUOPS");
+        act(&mut app, Action::DeleteBack);
+        assert_eq!(entry(&app).mode(), QueryMode::SubAnagram);
+
+        act(&mut app, Action::NextTab);
+        assert_eq!(entry(&app).entry(), "NUPO");
+        assert_eq!(entry(&app).mode(), QueryMode::Exact);
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+        let (tabs, _) = entry(&app).tabs();
+        assert_eq!(tabs[1].entry.as_str(), "DNUOP");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn tabs_stop_at_the_limit_and_closing_the_last_leaves_one_empty() {
+        let dir = scratch_dir("app-tabs-limit");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        for _ in 0..MAX_TABS {
+            act(&mut app, Action::NewTab);
+        }
+        assert_eq!(tab_entries(&app).0.len(), MAX_TABS);
+        app.draw(&gfx);
+        let limit = app.ctx.state.config.language.text(StringKey::TabLimit);
+        assert!(gfx.drew(&fill(limit, MAX_TABS)));
+
+        // Closing a tab brings the one after it to the front, or the last
+        act(&mut app, Action::NextTab);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::NextTab);
+        act(&mut app, Action::NextTab);
+        act(&mut app, Action::CloseTab);
+        assert_eq!(tab_entries(&app).1, 2);
+        for _ in 0..MAX_TABS {
+            act(&mut app, Action::CloseTab);
+        }
+        assert_eq!(tab_entries(&app), (vec![String::new()], 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Types two tabs' worth of entries into a loaded app in `dir`, sub-anagrams
+    /// on in the second, and lets them autosave.
+    fn autosaved_tabs(dir: &PathBuf) -> App {
+        let mut app = loaded_app(dir);
+        type_text(&mut app, "NUPO");
+        act(&mut app, Action::NewTab);
+        act(&mut app, Action::ToggleSubAnagrams);
+        type_text(&mut app, "TAGER");
+        app.update(AUTOSAVE_INTERVAL);
+        app
+    }
+
+    #[test]
+    fn tabs_left_by_a_crash_are_offered_back() {
+        let dir = scratch_dir("app-crash-restore");
+        // No shutdown: the process died
+        drop(autosaved_tabs(&dir));
+
+        let mut app = loaded_app(&dir);
+        assert_eq!(entry(&app).focus(), Focus::Modal(Modal::Restore));
+        app.handle_event(AppEvent::Nav(Nav::Select));
+        app.update(FRAME);
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        assert_eq!(tab_entries(&app), (vec!["NUPO".into(), "TAGER".into()], 1));
+        assert_eq!(entry(&app).mode(), QueryMode::SubAnagram);
+        act(&mut app, Action::NextTab);
+        assert_eq!(entry(&app).answer(), Some("UPON"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn declining_the_restore_starts_afresh() {
+        let dir = scratch_dir("app-crash-decline");
+        drop(autosaved_tabs(&dir));
+
+        let mut app = loaded_app(&dir);
+        app.handle_event(AppEvent::Dismiss);
+        assert_eq!(tab_entries(&app), (vec![String::new()], 0));
+        // The empty tab autosaved since then isn't offered after the next crash
+        app.update(AUTOSAVE_INTERVAL);
+        drop(app);
+        let app = loaded_app(&dir);
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_clean_shutdown_offers_nothing_back() {
+        let dir = scratch_dir("app-clean-shutdown");
+        let mut app = autosaved_tabs(&dir);
+        app.shutdown();
+        drop(app);
+
+        let app = loaded_app(&dir);
+        assert_eq!(entry(&app).focus(), Focus::Entry);
+        assert_eq!(tab_entries(&app), (vec![String::new()], 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn idle_frames_allocate_nothing_and_keystrokes_little() {
+        /// Most allocations a keystroke may make: handling it, looking the entry
+        /// up, and drawing the frame.
+        const MAX_KEYSTROKE_ALLOCATIONS: usize = 40;
+
+        let allocations = |f: &mut dyn FnMut()| {
+            let before = THREAD_ALLOCATIONS.with(|n| n.get());
+            f();
+            THREAD_ALLOCATIONS.with(|n| n.get()) - before
+        };
+        let frame = |app: &mut App, gfx: &TextRenderer| {
+            app.update(FRAME);
+            if app.take_redraw() {
+                app.draw(gfx);
+            }
+        };
+
+        let dir = scratch_dir("app-allocations");
+        let mut app = loaded_app(&dir);
+        let gfx = TextRenderer::default();
+        // Room for the text drawn, so recording it doesn't count
+        gfx.texts.borrow_mut().reserve(64);
+        gfx.outlines.borrow_mut().reserve(64);
+        // The overlay describes itself afresh every frame
+        if DEBUG_OVERLAY_DEFAULT {
+            act(&mut app, Action::ToggleDebug);
+        }
+        let mut most = 0;
+        for scramble in ["TAGER", "NUPO", "XQZV"] {
+            act(&mut app, Action::ClearEntry);
+            for _ in 0..10 {
+                frame(&mut app, &gfx);
+            }
+            for c in scramble.chars() {
+                let n = allocations(&mut || {
+                    app.handle_event(AppEvent::Letter(c));
+                    app.handle_event(AppEvent::KeyActivity);
+                    frame(&mut app, &gfx);
+                });
+                most = most.max(n);
+            }
+            for _ in 0..10 {
+                let n = allocations(&mut || frame(&mut app, &gfx));
+                assert_eq!(n, 0, "after {scramble}");
+            }
+            assert_eq!(entry(&app).entry(), scramble);
+        }
+        assert!(most <= MAX_KEYSTROKE_ALLOCATIONS, "{most} allocations");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+//! Locating bundled assets and per-user data files.
+//!
+//! Assets are looked up next to the executable first, so desktop shortcuts work,
+//! then in the working directory, which covers `cargo run` from the repo root.
+//! User data lives in the platform data directory (`%APPDATA%`,
+//! `~/Library/Application Support`, or `$XDG_DATA_HOME`).
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the user data directory (used for tests).
+pub const DATA_DIR_ENV: &str = "JUMBLE_HELPER_DATA_DIR";
+
+/// Returns the candidate locations of asset `relative`, in lookup order.
+pub fn asset_candidates(relative: &Path, exe_dir: Option<&Path>, cwd: &Path) -> Vec<PathBuf> {
+    if relative.is_absolute() {
+        return vec![relative.to_path_buf()];
+    }
+    exe_dir
+        .into_iter()
+        .chain(std::iter::once(cwd))
+        .map(|dir| dir.join(relative))
+        .collect()
+}
+
+/// Resolves asset `relative` to the first candidate that exists.
+///
+/// If none exist, the working-directory candidate is returned so that the caller's
+/// error message names a sensible path.
+pub fn resolve_asset(relative: impl AsRef<Path>) -> PathBuf {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    resolve_asset_in(relative.as_ref(), exe_dir.as_deref(), Path::new("."))
+}
+
+/// Resolves asset `relative` to the first of its candidates under `exe_dir` and
+/// `cwd` that exists, as `resolve_asset` does.
+pub fn resolve_asset_in(relative: &Path, exe_dir: Option<&Path>, cwd: &Path) -> PathBuf {
+    let candidates = asset_candidates(relative, exe_dir, cwd);
+
+    candidates
+        .iter()
+        .find(|path| path.exists())
+        .or(candidates.last())
+        .cloned()
+        .unwrap_or_else(|| relative.to_path_buf())
+}
+
+/// Chooses the data directory: the override if set, else the platform directory,
+/// else the working directory.
+pub fn choose_data_dir(env_override: Option<OsString>, platform_dir: Option<PathBuf>) -> PathBuf {
+    match env_override {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => platform_dir.unwrap_or_else(|| PathBuf::from(".")),
+    }
+}
+
+/// Returns the directory holding config and other user data files.
+pub fn data_dir() -> PathBuf {
+    let platform_dir = directories::ProjectDirs::from("", "ArchTangent", "Jumble Helper")
+        .map(|dirs| dirs.data_dir().to_path_buf());
+    choose_data_dir(std::env::var_os(DATA_DIR_ENV), platform_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory of its own under the system's temporary directory, emptied.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Creates `relative` under `dir`, with any directories it needs.
+    fn touch(dir: &Path, relative: &str) {
+        let path = dir.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn executable_directory_comes_before_working_directory() {
+        let root = scratch_dir("assets-both");
+        let (exe, cwd) = (root.join("exe"), root.join("cwd"));
+        touch(&exe, "fonts/a.ttf");
+        touch(&cwd, "fonts/a.ttf");
+        let found = resolve_asset_in(Path::new("fonts/a.ttf"), Some(&exe), &cwd);
+        assert_eq!(found, exe.join("fonts/a.ttf"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn working_directory_is_the_fallback() {
+        let root = scratch_dir("assets-cwd");
+        let (exe, cwd) = (root.join("exe"), root.join("cwd"));
+        touch(&cwd, "dictionary/words.txt");
+        let relative = Path::new("dictionary/words.txt");
+        assert_eq!(
+            resolve_asset_in(relative, Some(&exe), &cwd),
+            cwd.join(relative)
+        );
+        // Nowhere at all still names the working directory's path
+        let missing = Path::new("fonts/missing.ttf");
+        assert_eq!(
+            resolve_asset_in(missing, Some(&exe), &cwd),
+            cwd.join(missing)
+        );
+        assert_eq!(resolve_asset_in(missing, None, &cwd), cwd.join(missing));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn absolute_paths_are_used_as_given() {
+        let absolute = std::env::temp_dir().join("elsewhere.txt");
+        let candidates = asset_candidates(&absolute, Some(Path::new("exe")), Path::new("."));
+        assert_eq!(candidates, [absolute]);
+    }
+
+    #[test]
+    fn data_dir_override_wins_unless_empty() {
+        let root = scratch_dir("assets-data");
+        let platform = Some(root.join("platform"));
+        let chosen = choose_data_dir(Some(root.join("override").into()), platform.clone());
+        assert_eq!(chosen, root.join("override"));
+        let chosen = choose_data_dir(Some(OsString::new()), platform.clone());
+        assert_eq!(chosen, root.join("platform"));
+        assert_eq!(choose_data_dir(None, platform), root.join("platform"));
+        assert_eq!(choose_data_dir(None, None), PathBuf::from("."));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
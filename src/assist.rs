@@ -0,0 +1,170 @@
+//! Revealing the answer only after a while, for solving with a safety net.
+//!
+//! With `assist` on in the config, a unique answer that uses all of the entry's
+//! letters isn't shown at once: a ring in the entry box counts down
+//! `assist_seconds` first, and the answer shows when it runs out. Hints (see
+//! `hints`) can be asked for meanwhile, and Enter shows the answer at once. The
+//! countdown pauses while the answer is being looked up, while a dialog covers
+//! the screen, and while its tab isn't in front; it starts over when the entry
+//! changes.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+/// Seconds counted down before the answer shows, unless the config says.
+pub const DEFAULT_ASSIST_SECONDS: f32 = 45.0;
+/// Shortest and longest countdowns the config can ask for.
+pub const MIN_ASSIST_SECONDS: f32 = 5.0;
+pub const MAX_ASSIST_SECONDS: f32 = 300.0;
+/// Line segments drawn for a whole ring.
+const RING_SEGMENTS: usize = 48;
+
+/// Counts down to revealing one entry's answer.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AssistTimer {
+    /// The entry counted down for.
+    entry: String,
+    /// Seconds counted so far.
+    elapsed: f64,
+    /// When the countdown last advanced, while it's running.
+    last_tick: Option<f64>,
+    /// Whether the answer is shown, once the countdown ran out or was skipped.
+    revealed: bool,
+}
+
+impl AssistTimer {
+    /// Starts over if `entry` isn't the entry counted down for.
+    pub fn sync(&mut self, entry: &str) {
+        if self.entry != entry {
+            *self = Self {
+                entry: entry.to_owned(),
+                ..Self::default()
+            };
+        }
+    }
+    /// Advances the countdown to `now` while there's an `answer` to count down
+    /// to and it isn't `paused`, stopping once `seconds` are counted. Returns
+    /// whether it just ran out.
+    pub fn tick(&mut self, now: f64, answer: bool, paused: bool, seconds: f64) -> bool {
+        if !answer || paused || self.revealed {
+            // Time spent stopped isn't counted
+            self.last_tick = None;
+            return false;
+        }
+        if let Some(last) = self.last_tick {
+            self.elapsed += (now - last).max(0.0);
+        }
+        self.last_tick = Some(now);
+        if self.elapsed >= seconds {
+            self.reveal();
+            return true;
+        }
+        false
+    }
+    /// Shows the answer without waiting any longer.
+    pub fn reveal(&mut self) {
+        self.revealed = true;
+        self.last_tick = None;
+    }
+    /// Returns whether the answer is shown.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+    /// Returns whether the countdown is running, and so needs frames drawn.
+    pub fn is_running(&self) -> bool {
+        self.last_tick.is_some()
+    }
+    /// Returns the seconds left of `seconds`.
+    pub fn remaining(&self, seconds: f64) -> f64 {
+        (seconds - self.elapsed).max(0.0)
+    }
+}
+
+/// Draws the countdown ring around `center`: a faint whole ring, and over it
+/// the `fraction` of it left, clockwise from the top, with `label` inside.
+pub fn draw_countdown(
+    gfx: &dyn Renderer,
+    center: Vec2,
+    radius: f32,
+    fraction: f32,
+    label: &str,
+    font: Option<&Font>,
+    theme: &Theme,
+) {
+    let point = |i: usize| {
+        let angle = TAU * i as f32 / RING_SEGMENTS as f32 - TAU / 4.0;
+        center + vec2(angle.cos(), angle.sin()) * radius
+    };
+    let thickness = (radius / 5.0).max(1.0);
+    let mut faint = theme.box_outline;
+    faint.a *= 0.3;
+    let left = (fraction.clamp(0.0, 1.0) * RING_SEGMENTS as f32).ceil() as usize;
+    for i in 0..RING_SEGMENTS {
+        let color = if i < left { theme.highlight } else { faint };
+        gfx.line(point(i), point(i + 1), thickness, color);
+    }
+    let size = radius as u16;
+    let dims = gfx.measure(label, font, size);
+    let pos = center + vec2(-dims.width / 2.0, dims.offset_y / 2.0);
+    gfx.text(label, pos, font, size, theme.entry_text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECONDS: f64 = 10.0;
+
+    /// Ticks `timer` from `from` to `to` seconds, a second at a time, as frames
+    /// would, returning whether it ran out on any of them.
+    fn run(timer: &mut AssistTimer, from: u32, to: u32, paused: bool) -> bool {
+        (from..=to).any(|now| timer.tick(f64::from(now), true, paused, SECONDS))
+    }
+
+    #[test]
+    fn the_answer_shows_once_the_countdown_runs_out() {
+        let mut timer = AssistTimer::default();
+        timer.sync("NUPO");
+        assert!(!run(&mut timer, 0, 9, false));
+        assert_eq!(timer.remaining(SECONDS), 1.0);
+        assert!(timer.is_running() && !timer.is_revealed());
+        assert!(run(&mut timer, 10, 10, false));
+        assert!(timer.is_revealed() && !timer.is_running());
+        // It runs out once
+        assert!(!run(&mut timer, 11, 20, false));
+    }
+
+    #[test]
+    fn time_paused_or_without_an_answer_isnt_counted() {
+        let mut timer = AssistTimer::default();
+        timer.sync("NUPO");
+        run(&mut timer, 0, 4, false);
+        assert!(!run(&mut timer, 5, 100, true));
+        assert!(!timer.is_running());
+        // Still looking the answer up
+        assert!(!timer.tick(150.0, false, false, SECONDS));
+        // Counting picks up where it left off, from the next frame
+        assert!(!run(&mut timer, 200, 205, false));
+        assert_eq!(timer.remaining(SECONDS), 1.0);
+        assert!(run(&mut timer, 206, 206, false));
+    }
+
+    #[test]
+    fn a_changed_entry_starts_over_and_the_same_one_doesnt() {
+        let mut timer = AssistTimer::default();
+        timer.sync("NUPO");
+        run(&mut timer, 0, 8, false);
+        timer.sync("NUPO");
+        assert_eq!(timer.remaining(SECONDS), 2.0);
+        timer.sync("TAGER");
+        assert_eq!(timer.remaining(SECONDS), SECONDS);
+        assert!(!timer.is_running());
+
+        timer.reveal();
+        assert!(timer.is_revealed());
+        timer.sync("TAGE");
+        assert!(!timer.is_revealed());
+    }
+}
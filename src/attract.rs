@@ -0,0 +1,194 @@
+//! The attract mode: a demo that plays by itself while nobody's using the app.
+//!
+//! With `attract` on in the config, once the entry screen has had no input for
+//! `attract_seconds`, an `AttractScreen` is pushed over it. It plays the bundled
+//! `demos/attract.txt` script, a few scrambles typed letter by letter and
+//! cleared, on a demo `EntryScreen` of its own through a `Replayer`, looping,
+//! with a "DEMO" watermark. Any real input closes it, and the entry screen
+//! underneath, never touched, carries on as it was; the little the demo changes
+//! in the shared state (the entries Up and Down call back) is put back.
+
+use crate::app::AppContext;
+use crate::entry_screen::EntryScreen;
+use crate::events::AppEvent;
+use crate::keymap::Action;
+use crate::recall::Recall;
+use crate::record::{Recording, Replayer};
+use crate::render::Renderer;
+use crate::screen::{ScreenTrait, Transition};
+use crate::strings::StringKey;
+use macroquad::prelude::*;
+
+/// Events the attract mode replays: scrambles typed one letter at a time, each
+/// left up a while once solved, then cleared.
+const ATTRACT_SCRIPT: &str = include_str!("../demos/attract.txt");
+/// Seconds without input before the attract mode starts, unless the config
+/// says.
+pub const DEFAULT_ATTRACT_SECONDS: f32 = 60.0;
+/// Shortest wait the config can ask for.
+pub const MIN_ATTRACT_SECONDS: f32 = 5.0;
+/// Seconds between the end of the script and its next run.
+const SCRIPT_PAUSE: f64 = 2.0;
+
+/// Tells how long it's been since the last input.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct IdleTimer {
+    /// When the last input came.
+    last_input: f64,
+}
+
+impl IdleTimer {
+    /// Creates a timer counting from `now`.
+    pub fn new(now: f64) -> Self {
+        Self { last_input: now }
+    }
+    /// Notes that `ev`, handled at `now`, came from the user, if it did.
+    pub fn note(&mut self, ev: &AppEvent, now: f64) {
+        if is_input(ev) {
+            self.last_input = now;
+        }
+    }
+    /// Starts counting again from `now`, as when the screen comes back.
+    pub fn reset(&mut self, now: f64) {
+        self.last_input = now;
+    }
+    /// Returns whether there's been no input for `seconds` by `now`.
+    pub fn is_idle(&self, now: f64, seconds: f64) -> bool {
+        now - self.last_input >= seconds
+    }
+}
+
+/// Returns whether `ev` came from the user, rather than the window.
+pub fn is_input(ev: &AppEvent) -> bool {
+    !matches!(
+        ev,
+        AppEvent::WindowSize(_) | AppEvent::DpiScale(_) | AppEvent::CloseRequested
+    )
+}
+
+/// Returns the attract script's events.
+fn attract_replayer() -> Replayer {
+    let recording = Recording::parse(ATTRACT_SCRIPT).unwrap_or_else(|e| {
+        log::error!("[attract_replayer] bad attract script: {e}");
+        Recording::default()
+    });
+    Replayer::new(recording)
+}
+
+/// The demo playing over the entry screen.
+pub struct AttractScreen {
+    /// The entry screen the demo plays on.
+    demo: EntryScreen,
+    replayer: Replayer,
+    /// Seconds since the script started its current run.
+    time: f64,
+    /// The entries Up and Down called back before the demo, put back after.
+    recall: Recall,
+}
+
+impl AttractScreen {
+    /// Creates the attract mode over the loaded dictionary, keeping what it has
+    /// to put back.
+    pub fn new(ctx: &mut AppContext) -> Self {
+        let recall = ctx.state.recall.clone();
+        let mut demo = EntryScreen::new(ctx).into_demo();
+        demo.handle_event(ctx, AppEvent::Action(Action::ClearEntry));
+        log::info!("[AttractScreen::new] no input for a while; playing the demo");
+        Self {
+            demo,
+            replayer: attract_replayer(),
+            time: 0.0,
+            recall,
+        }
+    }
+}
+
+impl ScreenTrait for AttractScreen {
+    /// Any input ends the demo.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        match ev {
+            AppEvent::WindowSize(_) | AppEvent::DpiScale(_) => {
+                self.demo.handle_event(ctx, ev);
+                None
+            }
+            AppEvent::CloseRequested => Some(Transition::Quit),
+            _ => {
+                ctx.state.recall = std::mem::take(&mut self.recall);
+                log::info!("[AttractScreen::handle_event] input; ending the demo");
+                Some(Transition::Pop)
+            }
+        }
+    }
+    /// Plays the script, starting it over after a pause once it ends.
+    fn update(&mut self, ctx: &mut AppContext, dt: f64) -> Option<Transition> {
+        self.time += dt;
+        for ev in self.replayer.replace_input(Vec::new(), self.time) {
+            self.demo.handle_event(ctx, ev);
+        }
+        if self.replayer.is_finished() && !self.demo.is_waiting() {
+            self.replayer = attract_replayer();
+            self.time = -SCRIPT_PAUSE;
+        }
+        self.demo.update(ctx, dt);
+        None
+    }
+    /// Draws the demo with the watermark faint across the bottom.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        self.demo.draw(ctx, gfx);
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let text = ctx.state.config.language.text(StringKey::AttractWatermark);
+        let size = ctx.style.entry_size * 2;
+        let dims = gfx.measure(text, font, size);
+        let pos = vec2((ctx.size.x - dims.width) / 2.0, ctx.size.y - dims.height);
+        let mut color = theme.highlight;
+        color.a *= 0.25;
+        gfx.text(text, pos, font, size, color);
+    }
+    /// The demo moves on its own.
+    fn is_animating(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Nav;
+
+    #[test]
+    fn only_input_counts_against_idleness() {
+        let mut idle = IdleTimer::new(0.0);
+        assert!(!idle.is_idle(59.0, 60.0));
+        assert!(idle.is_idle(60.0, 60.0));
+        idle.note(&AppEvent::WindowSize(vec2(400.0, 300.0)), 30.0);
+        idle.note(&AppEvent::DpiScale(2.0), 30.0);
+        assert!(idle.is_idle(60.0, 60.0));
+        idle.note(&AppEvent::Nav(Nav::Down), 30.0);
+        assert!(!idle.is_idle(60.0, 60.0));
+        assert!(idle.is_idle(90.0, 60.0));
+        idle.reset(100.0);
+        assert!(!idle.is_idle(150.0, 60.0));
+    }
+
+    #[test]
+    fn the_script_types_and_clears_each_scramble() {
+        let recording = Recording::parse(ATTRACT_SCRIPT).unwrap();
+        let mut replayer = Replayer::new(recording);
+        let events = replayer.replace_input(Vec::new(), f64::MAX);
+        let letters: String = events
+            .iter()
+            .filter_map(|ev| match ev {
+                AppEvent::Letter(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(letters, "geblaeketlinrubtle");
+        let clears = events
+            .iter()
+            .filter(|&ev| *ev == AppEvent::Action(Action::ClearEntry))
+            .count();
+        assert_eq!(clears, 3);
+        assert!(events.last() == Some(&AppEvent::Action(Action::ClearEntry)));
+    }
+}
@@ -0,0 +1,263 @@
+//! Daily copies of the user data files, and putting one back.
+//!
+//! On startup, if there's no backup from today, each user data file is copied
+//! into `backups/YYYY-MM-DD/` in the data directory, and all but the newest
+//! `KEEP_BACKUPS` dated folders are deleted. `jumble_helper restore --date
+//! YYYY-MM-DD` copies a day's files back into place; it refuses while the app is
+//! running.
+
+use crate::date::{format_day, parse_day};
+use crate::instance::{is_running, INFO_FILE};
+use crate::storage::{archive_file, safe_write, ARCHIVE_KEYS, DATA_KEYS};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The folder in the data directory that holds the backups.
+pub const BACKUP_DIR: &str = "backups";
+/// Most dated backup folders kept.
+pub const KEEP_BACKUPS: usize = 14;
+
+/// Returns the user data files backed up, by name in the data directory: every
+/// key in `DATA_KEYS`, and the file of every archive in `ARCHIVE_KEYS`.
+pub fn backed_up() -> Vec<String> {
+    let archives = ARCHIVE_KEYS.into_iter().map(archive_file);
+    DATA_KEYS
+        .into_iter()
+        .map(str::to_owned)
+        .chain(archives)
+        .collect()
+}
+
+/// Returns the folder for the backup made on `day`.
+pub fn backup_path(data_dir: &Path, day: i64) -> PathBuf {
+    data_dir.join(BACKUP_DIR).join(format_day(day))
+}
+
+/// Returns the days with a backup folder in `data_dir`, oldest first; anything
+/// else in the backups folder is ignored.
+pub fn backup_days(data_dir: &Path) -> Vec<i64> {
+    let Ok(entries) = fs::read_dir(data_dir.join(BACKUP_DIR)) else {
+        return Vec::new();
+    };
+    let mut days: Vec<i64> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| parse_day(entry.file_name().to_str()?))
+        .collect();
+    days.sort_unstable();
+    days
+}
+
+/// Returns whether a backup is due on `today`: when the newest is from an
+/// earlier day, or there's none.
+pub fn is_backup_due(days: &[i64], today: i64) -> bool {
+    days.last().is_none_or(|&newest| newest < today)
+}
+
+/// Copies each of `backed_up` that exists in `data_dir` into the backup folder
+/// for `day`, returning how many were copied.
+pub fn back_up(data_dir: &Path, day: i64) -> io::Result<usize> {
+    let folder = backup_path(data_dir, day);
+    fs::create_dir_all(&folder)?;
+    let mut copied = 0;
+    for name in backed_up() {
+        let from = data_dir.join(&name);
+        if from.is_file() {
+            fs::copy(&from, folder.join(&name))?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Deletes all but the newest `keep` backup folders, returning the days deleted.
+pub fn prune(data_dir: &Path, keep: usize) -> io::Result<Vec<i64>> {
+    let days = backup_days(data_dir);
+    let old = &days[..days.len().saturating_sub(keep)];
+    for &day in old {
+        fs::remove_dir_all(backup_path(data_dir, day))?;
+    }
+    Ok(old.to_vec())
+}
+
+/// Backs up the data files if there's no backup from `today`, then prunes the
+/// old ones, logging what it did. Failures are logged; the app runs anyway.
+pub fn run_daily_backup(data_dir: &Path, today: i64) {
+    if !is_backup_due(&backup_days(data_dir), today) {
+        return;
+    }
+    let folder = backup_path(data_dir, today);
+    match back_up(data_dir, today) {
+        Ok(copied) => log::info!("[run_daily_backup] copied {copied} files to {folder:?}"),
+        Err(e) => {
+            log::error!("[run_daily_backup] failed to back up to {folder:?}: {e}");
+            return;
+        }
+    }
+    match prune(data_dir, KEEP_BACKUPS) {
+        Ok(pruned) if pruned.is_empty() => (),
+        Ok(pruned) => {
+            let days: Vec<String> = pruned.into_iter().map(format_day).collect();
+            log::info!(
+                "[run_daily_backup] deleted backups from {}",
+                days.join(", ")
+            );
+        }
+        Err(e) => log::warn!("[run_daily_backup] failed to delete old backups: {e}"),
+    }
+}
+
+/// Copies the files backed up on `day` back into `data_dir`, replacing them,
+/// and returns their names. Files the backup doesn't have are left as they are.
+///
+/// Refuses if the app is running or there's no backup from that day.
+pub fn restore(data_dir: &Path, day: i64) -> Result<Vec<String>, String> {
+    if is_running(data_dir) {
+        let holder = fs::read_to_string(data_dir.join(INFO_FILE)).unwrap_or_default();
+        return Err(match holder.split_whitespace().next() {
+            Some(pid) => format!("Jumble Helper is running (process {pid}); close it first"),
+            None => "Jumble Helper is running; close it first".into(),
+        });
+    }
+    let folder = backup_path(data_dir, day);
+    if !folder.is_dir() {
+        return Err(format!("there's no backup from {}", format_day(day)));
+    }
+    let mut restored = Vec::new();
+    for name in backed_up() {
+        let from = folder.join(&name);
+        if !from.is_file() {
+            continue;
+        }
+        fs::read(&from)
+            .and_then(|bytes| safe_write(&data_dir.join(&name), &bytes))
+            .map_err(|e| format!("failed to restore {name}: {e}"))?;
+        restored.push(name);
+    }
+    Ok(restored)
+}
+
+/// Runs `jumble_helper restore --date <day>` on the data directory, printing
+/// what was restored or why not; returns the exit code.
+pub fn run_restore(data_dir: &Path, day: i64) -> i32 {
+    match restore(data_dir, day) {
+        Ok(restored) => {
+            log::info!(
+                "[run_restore] restored {restored:?} from {}",
+                format_day(day)
+            );
+            println!("restored {} from {}", restored.join(", "), format_day(day));
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            let days: Vec<String> = backup_days(data_dir).into_iter().map(format_day).collect();
+            if !days.is_empty() {
+                eprintln!("backups: {}", days.join(", "));
+            }
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HISTORY_ARCHIVE_KEY;
+    use crate::instance::{acquire, Startup};
+    use crate::storage::{FileStorage, Storage};
+
+    /// A data directory of its own under the system's temporary directory,
+    /// emptied.
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn day(text: &str) -> i64 {
+        parse_day(text).unwrap()
+    }
+
+    #[test]
+    fn every_data_file_is_backed_up() {
+        let dir = data_dir("backup");
+        let storage = FileStorage::new(&dir);
+        for key in DATA_KEYS {
+            storage.write(key, key).unwrap();
+        }
+        storage
+            .archive(HISTORY_ARCHIVE_KEY, "2023-03-10 TLAE LATE\n")
+            .unwrap();
+        storage.write("notes.txt", "not user data").unwrap();
+        let names = backed_up();
+        assert_eq!(names.len(), DATA_KEYS.len() + ARCHIVE_KEYS.len());
+        assert_eq!(back_up(&dir, day("2024-03-01")).unwrap(), names.len());
+        let folder = backup_path(&dir, day("2024-03-01"));
+        for key in DATA_KEYS {
+            assert_eq!(fs::read_to_string(folder.join(key)).unwrap(), key);
+        }
+        let archive = archive_file(HISTORY_ARCHIVE_KEY);
+        assert_eq!(
+            fs::read(folder.join(&archive)).unwrap(),
+            fs::read(dir.join(&archive)).unwrap()
+        );
+        assert!(!folder.join("notes.txt").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backups_are_due_once_a_day_and_pruned_to_the_newest() {
+        assert!(is_backup_due(&[], day("2024-03-01")));
+        assert!(!is_backup_due(&[day("2024-03-01")], day("2024-03-01")));
+        assert!(is_backup_due(&[day("2024-03-01")], day("2024-03-02")));
+
+        let dir = data_dir("prune");
+        for d in ["2024-03-03", "2024-03-01", "2024-03-02"] {
+            fs::create_dir_all(backup_path(&dir, day(d))).unwrap();
+        }
+        fs::write(dir.join(BACKUP_DIR).join("notes.txt"), "").unwrap();
+        assert_eq!(prune(&dir, 2).unwrap(), [day("2024-03-01")]);
+        assert_eq!(backup_days(&dir), [day("2024-03-02"), day("2024-03-03")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restore_puts_a_days_files_back() {
+        let dir = data_dir("restore");
+        let storage = FileStorage::new(&dir);
+        storage.write("config.toml", "a = 1\n").unwrap();
+        back_up(&dir, day("2024-03-01")).unwrap();
+        storage.write("config.toml", "a = 2\n").unwrap();
+        storage.write("entry.txt", "GERTA").unwrap();
+
+        assert!(restore(&dir, day("2024-02-29")).is_err());
+        assert_eq!(restore(&dir, day("2024-03-01")).unwrap(), ["config.toml"]);
+        assert_eq!(
+            storage.read("config.toml").unwrap().as_deref(),
+            Some("a = 1\n")
+        );
+        // Files the backup doesn't have are left alone
+        assert_eq!(storage.read("entry.txt").unwrap().as_deref(), Some("GERTA"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restore_refuses_while_the_app_runs() {
+        let dir = data_dir("restore-running");
+        back_up(&dir, day("2024-03-01")).unwrap();
+        let Ok(Startup::First(guard)) = acquire(&dir) else {
+            panic!("nothing else holds the lock");
+        };
+        let e = restore(&dir, day("2024-03-01")).unwrap_err();
+        assert!(
+            e.contains(&format!("process {}", std::process::id())),
+            "{e}"
+        );
+        drop(guard);
+        assert!(restore(&dir, day("2024-03-01")).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
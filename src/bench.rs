@@ -0,0 +1,198 @@
+//! Timing keystrokes from end to end.
+//!
+//! `jumble_helper --bench-latency` builds the app on the built-in dictionary,
+//! without a window, and types `KEYSTROKES` keystrokes into it: the letters of
+//! words from the dictionary, scrambled, each word cleared once it's typed. A
+//! keystroke is the event handled, an update run, and the frame drawn, the way
+//! the main loop does them. The second half is typed in big mode, which ranks
+//! the matches every frame. It prints the 50th, 95th, and 99th percentiles of
+//! the whole keystroke and, when built with `--features stage-timing` (see
+//! `timing`), of each stage in it.
+//!
+//! Build with `--release` for numbers worth comparing.
+
+use crate::app::{App, AppState};
+use crate::config::Config;
+use crate::dictionary::{load_words, DictionaryOptions};
+use crate::events::AppEvent;
+use crate::keymap::{Action, Keymap};
+use crate::record::{wait_for_load, wall_clock, MAX_STEP};
+use crate::render::{fixed_measure, RecordingRenderer};
+use crate::soak::Rng;
+use crate::sound::Sounds;
+use crate::storage::MemoryStorage;
+use crate::strings::Language;
+use crate::timing::{take_stage_times, Stage, StageTimes};
+use crate::wordmap::fold_accents;
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use macroquad::prelude::*;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Keystrokes typed in a run.
+pub const KEYSTROKES: usize = 10_000;
+/// Seed for picking and scrambling the words, so every run types the same.
+const SEED: u64 = 0x6a75_6d62_6c65;
+/// The percentiles printed.
+const PERCENTILES: [f64; 3] = [50.0, 95.0, 99.0];
+
+/// Returns the `p`th percentile, from 0 to 100, of `sorted` by nearest rank,
+/// or `None` if it's empty.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Returns `count` keystrokes typing scrambles of `words`, each cleared once
+/// it's typed.
+fn script(words: &[String], count: usize) -> Vec<AppEvent> {
+    let mut rng = Rng::new(SEED);
+    let mut events = Vec::with_capacity(count);
+    while events.len() < count {
+        let mut letters: Vec<char> = words[rng.below(words.len())].chars().collect();
+        for i in (1..letters.len()).rev() {
+            letters.swap(i, rng.below(i + 1));
+        }
+        events.extend(letters.into_iter().map(AppEvent::Letter));
+        events.push(AppEvent::Action(Action::ClearEntry));
+    }
+    events.truncate(count);
+    events
+}
+
+/// Prints a row of the table: `name`, how many keystrokes `samples` has times
+/// for, and its percentiles in milliseconds.
+fn print_row(name: &str, samples: &mut [f64]) {
+    samples.sort_by(f64::total_cmp);
+    let mut row = format!("{name:<8} {:>6}", samples.len());
+    for p in PERCENTILES {
+        match percentile(samples, p) {
+            Some(seconds) => row += &format!(" {:>9.3}", seconds * 1000.0),
+            None => row += &format!(" {:>9}", "-"),
+        }
+    }
+    println!("{row}");
+}
+
+/// Types `KEYSTROKES` keystrokes and prints how long they took, returning the
+/// exit code: 0, or 1 if a keystroke's 95th percentile took longer than
+/// `fail_over` milliseconds, or 2 if the app couldn't start.
+pub fn run_bench(fail_over: Option<f64>) -> i32 {
+    let options = DictionaryOptions::default();
+    let words: Vec<String> = load_words(&options, Language::En)
+        .lines()
+        .map(|word| fold_accents(word).into_owned())
+        .filter(|word| (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word.len()))
+        .filter(|word| word.bytes().all(|b| b.is_ascii_uppercase()))
+        .collect();
+    if words.is_empty() {
+        eprintln!("[run_bench] the dictionary has no words to type");
+        return 2;
+    }
+    let config = Config {
+        announce: false,
+        dictionary: options,
+        language: Language::En,
+        ..Config::default()
+    };
+    let sounds = Sounds::silent(config.volume, true);
+    let state = AppState::new(config, Arc::new(MemoryStorage::default()));
+    let size = vec2(800.0, 600.0);
+    let keymap = Keymap::defaults();
+    let mut app = App::new(state, keymap, None, sounds, size, wall_clock, fixed_measure);
+    if !wait_for_load(&mut app) {
+        eprintln!("[run_bench] the dictionary didn't load");
+        return 2;
+    }
+
+    let gfx = RecordingRenderer::new(size);
+    let mut totals = Vec::with_capacity(KEYSTROKES);
+    let mut stages: Vec<StageTimes> = Vec::with_capacity(KEYSTROKES);
+    for (i, event) in script(&words, KEYSTROKES).into_iter().enumerate() {
+        if i == KEYSTROKES / 2 {
+            app.handle_event(AppEvent::Action(Action::ToggleBigMode));
+            app.update(MAX_STEP);
+        }
+        take_stage_times();
+        let started = Instant::now();
+        app.handle_event(event);
+        app.update(MAX_STEP);
+        app.draw(&gfx);
+        totals.push(started.elapsed().as_secs_f64());
+        stages.extend(take_stage_times());
+        gfx.take();
+        app.take_redraw();
+    }
+
+    println!("{KEYSTROKES} keystrokes, in milliseconds:");
+    println!(
+        "{:<8} {:>6} {:>9} {:>9} {:>9}",
+        "stage", "runs", "p50", "p95", "p99"
+    );
+    if stages.is_empty() {
+        println!("(build with --features stage-timing to time each stage)");
+    } else {
+        for (i, stage) in Stage::ALL.into_iter().enumerate() {
+            let mut samples: Vec<f64> = stages.iter().filter_map(|times| times[i]).collect();
+            print_row(stage.name(), &mut samples);
+        }
+    }
+    print_row("total", &mut totals);
+
+    let p95 = percentile(&totals, 95.0).unwrap_or(0.0) * 1000.0;
+    match fail_over {
+        Some(limit) if p95 > limit => {
+            println!("FAILED: p95 of {p95:.3} ms is over {limit} ms");
+            1
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_are_taken_by_nearest_rank() {
+        let sorted: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 50.0), Some(10.0));
+        assert_eq!(percentile(&sorted, 95.0), Some(19.0));
+        assert_eq!(percentile(&sorted, 99.0), Some(20.0));
+        assert_eq!(percentile(&sorted, 100.0), Some(20.0));
+        // The lowest rank is the first, not before it
+        assert_eq!(percentile(&sorted, 0.0), Some(1.0));
+        assert_eq!(percentile(&sorted, 1.0), Some(1.0));
+        assert_eq!(percentile(&[7.0], 50.0), Some(7.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn the_script_types_scrambles_and_clears_each() {
+        let words = ["GRATE".to_owned(), "UPON".to_owned()];
+        let events = script(&words, 100);
+        assert_eq!(events.len(), 100);
+        assert_eq!(events, script(&words, 100), "every run types the same");
+        let typed: Vec<String> = events
+            .split(|event| *event == AppEvent::Action(Action::ClearEntry))
+            .map(|word| {
+                let mut letters: Vec<char> = word
+                    .iter()
+                    .map(|event| match event {
+                        AppEvent::Letter(c) => *c,
+                        _ => panic!("not a letter: {event:?}"),
+                    })
+                    .collect();
+                letters.sort_unstable();
+                letters.into_iter().collect()
+            })
+            .collect();
+        // Whole words, bar the last, cut off at the count
+        for letters in &typed[..typed.len() - 1] {
+            assert!(["AEGRT", "NOPU"].contains(&letters.as_str()), "{letters}");
+        }
+    }
+}
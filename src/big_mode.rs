@@ -0,0 +1,38 @@
+//! Large-print mode: just the entry and one answer, as big as the window allows.
+//!
+//! Each line is sized to fill `BIG_MODE_WIDTH` of the window, so short words get
+//! huge letters. It always uses the high-contrast theme.
+
+use crate::metrics::fit_font_size;
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Fraction of the window width each line fills.
+pub const BIG_MODE_WIDTH: f32 = 0.8;
+/// Fraction of the window height a line may be at most.
+const BIG_MODE_HEIGHT: f32 = 0.3;
+
+/// Draws `entry` and `answer`, each centered and fitted to the window.
+pub fn draw_big_mode(
+    gfx: &dyn Renderer,
+    entry: &str,
+    answer: &str,
+    font: Option<&Font>,
+    theme: &Theme,
+) {
+    let Vec2 { x: sw, y: sh } = gfx.screen_size();
+    let max_size = (sh * BIG_MODE_HEIGHT) as u16;
+    let lines = [
+        (entry, sh * 0.4, theme.entry_text),
+        (answer, sh * 0.8, theme.result),
+    ];
+
+    for (text, baseline, color) in lines {
+        let size = fit_font_size(text, sw * BIG_MODE_WIDTH, max_size, |text, size| {
+            gfx.measure(text, font, size).width
+        });
+        let width = gfx.measure(text, font, size).width;
+        gfx.text(text, vec2((sw - width) / 2.0, baseline), font, size, color);
+    }
+}
@@ -0,0 +1,106 @@
+//! Soak testing: random input through the app, checking it stays consistent.
+//!
+//! `soak` runs `jumble_helper::soak` for 10,000 steps, or `--iterations`, from
+//! `--seed`, or a seed taken from the clock. A failure prints the seed to run
+//! again with. Exits with 0 if every check passed, 1 if one failed or the app
+//! panicked, and 2 if it couldn't start.
+
+#[cfg(not(target_arch = "wasm32"))]
+use jumble_helper::logging::{init_logging, select_level};
+#[cfg(not(target_arch = "wasm32"))]
+use jumble_helper::soak::run_soak;
+
+const USAGE: &str = "\
+Usage: soak [--iterations <N>] [--seed <N>]
+
+Options:
+    --iterations <N>  Random steps to run (default 10000)
+    --seed <N>        Seed for the random events, to repeat a run
+    -h, --help        Print this help and exit";
+
+/// Options parsed from the command line.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct SoakArgs {
+    iterations: u64,
+    /// Seed for the random events, or `None` to pick one.
+    seed: Option<u64>,
+    help: bool,
+}
+
+/// Parses the command line, without the program name.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<SoakArgs, String> {
+    let mut soak = SoakArgs {
+        iterations: 10_000,
+        seed: None,
+        help: false,
+    };
+    let number = |name: &str, value: Option<String>| {
+        let value = value.ok_or(format!("{name} requires a number"))?;
+        value
+            .parse::<u64>()
+            .map_err(|_| format!("{name} must be a whole number, not '{value}'"))
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" => soak.iterations = number("--iterations", args.next())?,
+            "--seed" => soak.seed = Some(number("--seed", args.next())?),
+            "-h" | "--help" => soak.help = true,
+            _ => return Err(format!("unrecognized option '{arg}'")),
+        }
+    }
+    Ok(soak)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+    let rust_log = std::env::var("RUST_LOG").ok();
+    init_logging(None, select_level(rust_log.as_deref(), "warn"));
+    let seed = args.seed.unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        now.map_or(0, |d| d.as_nanos() as u64)
+    });
+    std::process::exit(run_soak(args.iterations, seed));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    eprintln!("soak is not supported on the web");
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Result<SoakArgs, String> {
+        parse_args(text.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn parses_iterations_and_seed() {
+        let soak = args("--iterations 200 --seed 7").unwrap();
+        assert_eq!((soak.iterations, soak.seed), (200, Some(7)));
+        assert_eq!(args("").unwrap().seed, None);
+        assert!(args("--iterations").is_err());
+        assert!(args("--seed seven").is_err());
+        assert!(args("--forever").is_err());
+    }
+
+    #[test]
+    fn a_short_run_passes() {
+        let soak = args("--iterations 200 --seed 7").unwrap();
+        assert_eq!(run_soak(soak.iterations, soak.seed.unwrap()), 0);
+    }
+}
@@ -0,0 +1,461 @@
+//! The `check` command: reports problems in a word list, and can write a
+//! cleaned copy. The `confirmed --merge` command writes a copy the same way,
+//! with the answers confirmed as seen in print (see `confirmed`) made common,
+//! and the `prep` command with counts from a table of word counts (see
+//! `frequency`).
+
+use crate::cli::{CheckArgs, MergeArgs, PrepArgs};
+use crate::confirmed::Confirmed;
+use crate::dictionary::{
+    normalize_word, parse_header, prepare_words, read_word_file, DictionaryHeader,
+    DictionaryOptions, DictionaryReport, WordCase,
+};
+use crate::frequency::read_table;
+use crate::storage::safe_write;
+use crate::wordmap::make_word_map_with_progress;
+use itertools::Itertools;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Most line numbers listed per issue; the rest are only counted.
+const MAX_LINES_LISTED: usize = 10;
+
+/// Checks the word list named in `args`, printing a report, and returns the exit
+/// code: 0 if clean, 1 if issues were found, 2 if the check couldn't run.
+pub fn run_check(args: &CheckArgs, options: &DictionaryOptions) -> i32 {
+    let text = match read_word_file(Path::new(&args.dict), options.max_file_bytes()) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("[run_check] {e}");
+            return 2;
+        }
+    };
+    let (header, body, header_lines) = match parse_header(&text) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("[run_check] '{}': {e}", args.dict);
+            return 2;
+        }
+    };
+
+    // Read each line as the header says, keeping one word per line so the line
+    // numbers still point into the file
+    let words = match header.rewrites_lines() {
+        true => Cow::Owned(body.lines().map(|line| header.word_of(line)).join("\n")),
+        false => Cow::Borrowed(body),
+    };
+    let mut report = DictionaryReport::check(&words, true);
+    report.lines += header_lines;
+    for lines in report.issues.values_mut() {
+        lines.iter_mut().for_each(|line| *line += header_lines);
+    }
+    print!("{}", describe_report(&args.dict, &report));
+
+    if let Some(out) = &args.fix_out {
+        // The cleaned words are uppercase and within the lengths, so only the
+        // name and language carry over
+        let cleaned = cleaned_words(words.lines().filter(|line| header.fits(line.trim())));
+        let count = cleaned.lines().count();
+        let header = DictionaryHeader {
+            name: args.name.clone().or(header.name),
+            language: args.language.clone().or(header.language),
+            case: Some(WordCase::Upper),
+            ..DictionaryHeader::default()
+        };
+        if let Err(e) = write_list(out, &header, &cleaned) {
+            eprintln!("[run_check] can't write '{out}': {e}");
+            return 2;
+        }
+        println!("wrote {count} words to '{out}'");
+    }
+
+    match report.issue_count() {
+        0 => 0,
+        _ => 1,
+    }
+}
+
+/// Returns the report as text, one line per kind of issue.
+fn describe_report(name: &str, report: &DictionaryReport) -> String {
+    let mut text = format!(
+        "'{name}': {} lines, {} issues\n",
+        report.lines,
+        report.issue_count()
+    );
+    for (issue, lines) in &report.issues {
+        let listed = lines
+            .iter()
+            .take(MAX_LINES_LISTED)
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let more = match lines.len().saturating_sub(MAX_LINES_LISTED) {
+            0 => String::new(),
+            n => format!(", and {n} more"),
+        };
+        let count = lines.len();
+        let desc = issue.description();
+        let label = if count == 1 { "line" } else { "lines" };
+        text.push_str(&format!("  {desc}: {count} ({label} {listed}{more})\n"));
+    }
+    text
+}
+
+/// Returns `lines` normalized, deduplicated, and one per line, dropping lines
+/// that can't be words.
+fn cleaned_words<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let mut seen = HashSet::new();
+    let mut cleaned = String::new();
+    for word in lines.filter_map(normalize_word) {
+        if seen.insert(word.clone()) {
+            cleaned.push_str(&word);
+            cleaned.push('\n');
+        }
+    }
+    cleaned
+}
+
+/// Writes a word list to `out`: `header`'s lines, then `body`.
+fn write_list(out: &str, header: &DictionaryHeader, body: &str) -> std::io::Result<()> {
+    safe_write(Path::new(out), (header.to_text() + body).as_bytes())
+}
+
+/// Writes a copy of the word list named in `args` with a frequency column, in
+/// which each of `confirmed` is at least as common as the list's most common
+/// word, adding those the list lacks. Returns the exit code: 0 if written, 2 if
+/// not.
+pub fn run_merge(args: &MergeArgs, confirmed: &Confirmed, options: &DictionaryOptions) -> i32 {
+    let text = match read_word_file(Path::new(&args.dict), options.max_file_bytes()) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("[run_merge] {e}");
+            return 2;
+        }
+    };
+    let (header, body, _) = match parse_header(&text) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("[run_merge] '{}': {e}", args.dict);
+            return 2;
+        }
+    };
+
+    // Each word once, in the list's order, with the highest count it was given
+    let mut words: Vec<String> = Vec::new();
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for line in body.lines() {
+        let Some(word) = normalize_word(&header.word_of(line)).filter(|w| header.fits(w)) else {
+            continue;
+        };
+        let count = header.frequency_of(line).unwrap_or(0);
+        match counts.get_mut(&word) {
+            Some(known) => *known = (*known).max(count),
+            None => {
+                counts.insert(word.clone(), count);
+                words.push(word);
+            }
+        }
+    }
+    let top = counts.values().copied().max().unwrap_or(0).max(1);
+    let mut added = 0;
+    for word in confirmed.words() {
+        match counts.get_mut(word) {
+            Some(count) => *count = top,
+            None => {
+                counts.insert(word.clone(), top);
+                words.push(word.clone());
+                added += 1;
+            }
+        }
+    }
+
+    let merged: String = words
+        .iter()
+        .map(|word| format!("{word} {}\n", counts[word]))
+        .collect();
+    let header = DictionaryHeader {
+        case: Some(WordCase::Upper),
+        frequency_column: true,
+        ..header
+    };
+    if let Err(e) = write_list(&args.out, &header, &merged) {
+        eprintln!("[run_merge] can't write '{}': {e}", args.out);
+        return 2;
+    }
+    println!(
+        "merged {} confirmed words ({added} new) into {} words in '{}'",
+        confirmed.words().len(),
+        words.len(),
+        args.out
+    );
+    0
+}
+
+/// Writes a copy of the word list named in `args` with a frequency column,
+/// taking the counts from the table `args` names, or from the list for words
+/// the table lacks. Returns the exit code: 0 if written, 2 if not.
+pub fn run_prep(args: &PrepArgs, options: &DictionaryOptions) -> i32 {
+    let text = match read_word_file(Path::new(&args.dict), options.max_file_bytes()) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("[run_prep] {e}");
+            return 2;
+        }
+    };
+    let (header, words) = match prepare_words(&text) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("[run_prep] '{}': {e}", args.dict);
+            return 2;
+        }
+    };
+    let alphabet = options.alphabet_for(&header);
+    let map = make_word_map_with_progress(&words, alphabet, usize::MAX, usize::MAX, |_| ());
+    let table = match read_table(Path::new(&args.frequencies), &map) {
+        Ok(table) => table,
+        Err(e) => {
+            eprintln!("[run_prep] {e}");
+            return 2;
+        }
+    };
+    let (_, body, _) = match parse_header(&text) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("[run_prep] '{}': {e}", args.dict);
+            return 2;
+        }
+    };
+
+    // Each word once, in the list's order, with the table's count if it has one
+    let mut seen = HashSet::new();
+    let mut counted = 0;
+    let mut prepared = String::with_capacity(body.len());
+    for line in body.lines() {
+        let Some(word) = normalize_word(&header.word_of(line)).filter(|w| header.fits(w)) else {
+            continue;
+        };
+        if !seen.insert(word.clone()) {
+            continue;
+        }
+        match table.get(&word).copied().or(header.frequency_of(line)) {
+            Some(count) => {
+                counted += 1;
+                prepared.push_str(&format!("{word} {count}\n"));
+            }
+            None => prepared.push_str(&format!("{word}\n")),
+        }
+    }
+    let header = DictionaryHeader {
+        case: Some(WordCase::Upper),
+        frequency_column: true,
+        ..header
+    };
+    if let Err(e) = write_list(&args.out, &header, &prepared) {
+        eprintln!("[run_prep] can't write '{}': {e}", args.out);
+        return 2;
+    }
+    println!(
+        "wrote {} words, {counted} with counts, to '{}'",
+        seen.len(),
+        args.out
+    );
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::LineIssue;
+    use std::path::PathBuf;
+
+    /// Returns the path of fixture `name` under `tests/check`.
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/check")
+            .join(name)
+    }
+
+    /// Checks fixture `name`, returning each issue found with its lines.
+    fn issues_in(name: &str) -> Vec<(LineIssue, Vec<usize>)> {
+        let words = std::fs::read_to_string(fixture(name)).unwrap();
+        DictionaryReport::check(&words, true)
+            .issues
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn a_clean_list_has_no_issues() {
+        assert_eq!(issues_in("clean.txt"), []);
+    }
+
+    #[test]
+    fn duplicates_are_found_after_their_first_line() {
+        assert_eq!(
+            issues_in("duplicate.txt"),
+            [(LineIssue::Duplicate, vec![3, 5])]
+        );
+    }
+
+    #[test]
+    fn words_outside_the_length_bounds_are_found() {
+        assert_eq!(
+            issues_in("bad_length.txt"),
+            [(LineIssue::BadLength, vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn non_ascii_words_are_found() {
+        assert_eq!(issues_in("not_ascii.txt"), [(LineIssue::NotAscii, vec![2])]);
+    }
+
+    #[test]
+    fn punctuation_is_found() {
+        assert_eq!(
+            issues_in("not_a_letter.txt"),
+            [(LineIssue::NotALetter, vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn lowercase_is_found() {
+        assert_eq!(
+            issues_in("lowercase.txt"),
+            [(LineIssue::Lowercase, vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn stray_whitespace_and_carriage_returns_are_found() {
+        assert_eq!(
+            issues_in("stray_whitespace.txt"),
+            [(LineIssue::StrayWhitespace, vec![1, 2, 3])]
+        );
+    }
+
+    #[test]
+    fn the_exit_code_says_whether_issues_were_found() {
+        let options = DictionaryOptions::default();
+        let check = |name: &str| {
+            let args = CheckArgs {
+                dict: fixture(name).display().to_string(),
+                fix_out: None,
+                name: None,
+                language: None,
+            };
+            run_check(&args, &options)
+        };
+        assert_eq!(check("clean.txt"), 0);
+        assert_eq!(check("lowercase.txt"), 1);
+        assert_eq!(check("no_such_list.txt"), 2);
+    }
+
+    #[test]
+    fn the_cleaned_copy_is_normalized_and_deduplicated() {
+        let out =
+            std::env::temp_dir().join(format!("jumble_helper-cleaned-{}.txt", std::process::id()));
+        for name in ["duplicate.txt", "lowercase.txt", "stray_whitespace.txt"] {
+            let args = CheckArgs {
+                dict: fixture(name).display().to_string(),
+                fix_out: Some(out.display().to_string()),
+                name: None,
+                language: None,
+            };
+            assert_eq!(run_check(&args, &DictionaryOptions::default()), 1);
+            let cleaned = std::fs::read_to_string(&out).unwrap();
+            assert_eq!(cleaned, "#! case=upper\nPOUND\nUPON\nSOUND\n", "{name}");
+            // The cleaned copy checks clean, header and all
+            let (_, words, _) = parse_header(&cleaned).unwrap();
+            assert_eq!(DictionaryReport::check(words, true).issue_count(), 0);
+        }
+        std::fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn the_report_lists_each_kind_of_issue() {
+        let words = std::fs::read_to_string(fixture("duplicate.txt")).unwrap();
+        let report = DictionaryReport::check(&words, true);
+        assert_eq!(
+            describe_report("words.txt", &report),
+            "'words.txt': 5 lines, 2 issues\n  duplicate word: 2 (lines 3, 5)\n"
+        );
+    }
+
+    #[test]
+    fn confirmed_words_merge_as_the_most_common() {
+        let scratch = |name: &str| {
+            std::env::temp_dir().join(format!("jumble_helper-{name}-{}.txt", std::process::id()))
+        };
+        let (dict, out) = (scratch("merge-in"), scratch("merge-out"));
+        std::fs::write(
+            &dict,
+            "#! frequency_column=true\nUPON 12\nPOUND 3\nupon 20\n",
+        )
+        .unwrap();
+        let args = MergeArgs {
+            dict: dict.display().to_string(),
+            out: out.display().to_string(),
+        };
+        let confirmed = Confirmed::parse("POUND\nGRATE\n");
+        assert_eq!(
+            run_merge(&args, &confirmed, &DictionaryOptions::default()),
+            0
+        );
+        let merged = std::fs::read_to_string(&out).unwrap();
+        let (header, body, _) = parse_header(&merged).unwrap();
+        assert!(header.frequency_column);
+        // UPON once, at its highest count, which the confirmed words then match
+        assert_eq!(body, "UPON 20\nPOUND 20\nGRATE 20\n");
+
+        let missing = MergeArgs {
+            dict: scratch("merge-missing").display().to_string(),
+            ..args
+        };
+        assert_eq!(
+            run_merge(&missing, &confirmed, &DictionaryOptions::default()),
+            2
+        );
+        std::fs::remove_file(&dict).unwrap();
+        std::fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn prep_bakes_the_table_counts_into_a_copy() {
+        let scratch = |name: &str| {
+            std::env::temp_dir().join(format!("jumble_helper-{name}-{}.txt", std::process::id()))
+        };
+        let (dict, table, out) = (
+            scratch("prep-in"),
+            scratch("prep-table"),
+            scratch("prep-out"),
+        );
+        std::fs::write(&dict, "#! name=Mine\nUPON\nPOUND\nupon\nGRATE\n").unwrap();
+        std::fs::write(
+            &table,
+            "upon\t1999\t5\t1\nupon\t2000\t7\t1\npound\t2000\t2\t1\n",
+        )
+        .unwrap();
+        let args = PrepArgs {
+            dict: dict.display().to_string(),
+            frequencies: table.display().to_string(),
+            out: out.display().to_string(),
+        };
+        assert_eq!(run_prep(&args, &DictionaryOptions::default()), 0);
+        let prepped = std::fs::read_to_string(&out).unwrap();
+        let (header, body, _) = parse_header(&prepped).unwrap();
+        assert!(header.frequency_column);
+        assert_eq!(header.name.as_deref(), Some("Mine"));
+        assert_eq!(body, "UPON 12\nPOUND 2\nGRATE\n");
+
+        let missing = PrepArgs {
+            frequencies: scratch("prep-missing").display().to_string(),
+            ..args
+        };
+        assert_eq!(run_prep(&missing, &DictionaryOptions::default()), 2);
+        for path in [dict, table, out] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
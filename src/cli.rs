@@ -0,0 +1,735 @@
+//! Command-line options.
+
+use crate::date::parse_day;
+use crate::digest::{parse_range, DayRange, DEFAULT_SINCE};
+use crate::history::DEFAULT_TOP;
+use crate::platform::BackendChoice;
+
+pub const USAGE: &str = "\
+Usage: jumble_helper [OPTIONS]
+       jumble_helper check --dict <PATH> [--fix --out <PATH> [--name <NAME>] [--language <CODE>]]
+       jumble_helper snapshot [--dir <DIR>]
+       jumble_helper history [--top [<N>]]
+       jumble_helper digest [--since <RANGE>] [--out <PATH>] [--format text|html]
+       jumble_helper coverage --history <PATH>
+       jumble_helper diff <OLD> <NEW> [--format text|json]
+       jumble_helper restore --date <YYYY-MM-DD>
+       jumble_helper confirmed --merge --dict <PATH> --out <PATH>
+       jumble_helper prep --dict <PATH> --frequencies <PATH> --out <PATH>
+       jumble_helper verify <ARCHIVE> [--fail-under <SHARE>]
+       jumble_helper metrics --summary
+
+Options:
+    --backend <NAME>  Windowing backend on Linux: auto, wayland, or x11
+    --print-keymap    Print the effective key bindings and exit
+    --onboarding      Show the first-run walkthrough again
+    --mini            Start in the mini window: just the entry and the answer
+    --record <PATH>   Record every input event to PATH, for replaying later
+    --replay <PATH>   Replay recorded events without a window, printing the state
+    --visual          With --replay, show the replay in the window instead
+    --speed <N>       With --visual, replay N times faster, e.g. 10
+    --spectate <URL>  Watch the app sharing its screen at URL, read-only
+    --terminal        Look up scrambles typed at a prompt, without a window
+    --bench-latency   Time 10,000 typed keystrokes without a window and exit
+    --fail-over <MS>  With --bench-latency, exit with 1 if the 95th percentile
+                      keystroke takes longer than MS milliseconds
+    -h, --help        Print this help and exit
+
+Check options:
+    --dict <PATH>     Word list to check for problems
+    --fix             Write a cleaned copy of the word list (requires --out)
+    --out <PATH>      Where --fix writes the cleaned copy
+    --name <NAME>     With --fix, the name to give the copy in its header
+    --language <CODE> With --fix, the language to give the copy in its header
+
+Snapshot options:
+    --dir <DIR>       Where the snapshots are kept (default: snapshots);
+                      set JUMBLE_BLESS=1 to rewrite them
+
+Soak options:
+    --iterations <N>  Random steps to run (default: 10000)
+    --seed <N>        Seed for the random events (default: from the clock)
+
+History options:
+    --top [<N>]       How many of the most frequent answers to list (default: 10)
+
+Digest options:
+    --since <RANGE>   Days to summarize: 7d for the last seven (the default), a
+                      day such as 2024-03-01 on, or 2024-03-01..2024-03-08
+    --out <PATH>      Where to write the digest (default: print it)
+    --format <NAME>   text (default), or html for pasting into an email
+
+Coverage options:
+    --history <PATH>  History file whose scrambles to look up in the dictionary
+
+Diff options:
+    --format <NAME>   text (default), or json for tools
+
+Restore options:
+    --date <DATE>     Which day's backup of the user data files to put back
+
+Confirmed options:
+    --merge           Write a copy of a word list with the confirmed answers
+                      made as common as its most common word
+    --dict <PATH>     Word list to merge them into
+    --out <PATH>      Where to write the merged copy
+
+Prep options:
+    --dict <PATH>         Word list to give a frequency column
+    --frequencies <PATH>  Table of word counts: an ngram export or word count lines
+    --out <PATH>          Where to write the copy with the counts
+
+Verify options:
+    --fail-under <SHARE>  Exit with 1 if the official answer ranks first in
+                          less than this share of the puzzles, e.g. 0.95
+
+Metrics options:
+    --summary  Print the uses of each feature counted on this machine, when
+               counting them is on in the settings";
+
+/// Options for the `check` command.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct CheckArgs {
+    pub dict: String,
+    /// Where to write a cleaned copy, if `--fix` was given.
+    pub fix_out: Option<String>,
+    /// Name for the cleaned copy's header, replacing the original's.
+    pub name: Option<String>,
+    /// Language for the cleaned copy's header, replacing the original's.
+    pub language: Option<String>,
+}
+
+/// Options for the `confirmed --merge` command.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct MergeArgs {
+    pub dict: String,
+    pub out: String,
+}
+
+/// Options for the `prep` command.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct PrepArgs {
+    pub dict: String,
+    /// Table of word counts to bake into the copy (see `frequency`).
+    pub frequencies: String,
+    pub out: String,
+}
+
+/// Options for the `verify` command.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct VerifyArgs {
+    /// CSV file of dates, scrambles, and official answers.
+    pub archive: String,
+    /// Least share of answers ranked first, from 0 to 1, for success.
+    pub fail_under: Option<f64>,
+}
+
+/// How `diff` prints the differences.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub enum DiffFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// How `digest` writes the summary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub enum DigestFormat {
+    #[default]
+    Text,
+    Html,
+}
+
+/// Options for the `digest` command.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct DigestArgs {
+    pub since: DayRange,
+    /// Where to write the digest, or `None` to print it.
+    pub out: Option<String>,
+    pub format: DigestFormat,
+}
+
+/// Options for the `diff` command.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct DiffArgs {
+    pub old: String,
+    pub new: String,
+    pub format: DiffFormat,
+}
+
+/// Options parsed from the command line.
+#[derive(Debug, Default, Clone)]
+pub struct CliArgs {
+    pub backend: Option<BackendChoice>,
+    pub print_keymap: bool,
+    /// Whether to show onboarding even though it's not the first run.
+    pub onboarding: bool,
+    /// Whether to start in the mini window.
+    pub mini: bool,
+    pub help: bool,
+    /// Where to record input events.
+    pub record: Option<String>,
+    /// Recording to replay.
+    pub replay: Option<String>,
+    /// Whether to show the replay in the window.
+    pub visual: bool,
+    /// How many times faster than recorded to replay.
+    pub speed: Option<f64>,
+    /// URL of the app to watch, instead of running one.
+    pub spectate: Option<String>,
+    /// Whether to look scrambles up in the terminal instead of a window.
+    pub terminal: bool,
+    /// Whether to time keystrokes instead of running the app.
+    pub bench_latency: bool,
+    /// Milliseconds the 95th percentile keystroke may take in the benchmark.
+    pub fail_over: Option<f64>,
+    /// Set when run as `jumble_helper check ...`.
+    pub check: Option<CheckArgs>,
+    /// Snapshot directory, set when run as `jumble_helper snapshot ...`.
+    pub snapshot: Option<String>,
+    /// Answers to list, set when run as `jumble_helper history ...`.
+    pub history: Option<usize>,
+    /// Set when run as `jumble_helper digest ...`.
+    pub digest: Option<DigestArgs>,
+    /// History file to check, set when run as `jumble_helper coverage ...`.
+    pub coverage: Option<String>,
+    /// Set when run as `jumble_helper diff ...`.
+    pub diff: Option<DiffArgs>,
+    /// Day of the backup to restore, set when run as `jumble_helper restore ...`.
+    pub restore: Option<i64>,
+    /// Set when run as `jumble_helper confirmed --merge ...`.
+    pub merge: Option<MergeArgs>,
+    /// Set when run as `jumble_helper prep ...`.
+    pub prep: Option<PrepArgs>,
+    /// Set when run as `jumble_helper verify ...`.
+    pub verify: Option<VerifyArgs>,
+    /// Set when run as `jumble_helper metrics --summary`.
+    pub metrics: bool,
+}
+
+impl CliArgs {
+    /// Parses options from `args`, which excludes the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter().peekable();
+        if args.peek().is_some_and(|arg| arg == "check") {
+            args.next();
+            cli.check = Some(parse_check(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "snapshot") {
+            args.next();
+            cli.snapshot = Some(parse_snapshot(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "history") {
+            args.next();
+            cli.history = Some(parse_history(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "digest") {
+            args.next();
+            cli.digest = Some(parse_digest(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "coverage") {
+            args.next();
+            cli.coverage = Some(parse_coverage(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "diff") {
+            args.next();
+            cli.diff = Some(parse_diff(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "restore") {
+            args.next();
+            cli.restore = Some(parse_restore(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "confirmed") {
+            args.next();
+            cli.merge = Some(parse_confirmed(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "prep") {
+            args.next();
+            cli.prep = Some(parse_prep(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "verify") {
+            args.next();
+            cli.verify = Some(parse_verify(args)?);
+            return Ok(cli);
+        }
+        if args.peek().is_some_and(|arg| arg == "metrics") {
+            args.next();
+            parse_metrics(args)?;
+            cli.metrics = true;
+            return Ok(cli);
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--backend" => {
+                    let name = args.next().ok_or("--backend requires a value")?;
+                    cli.backend = Some(BackendChoice::parse(&name)?);
+                }
+                "--print-keymap" => cli.print_keymap = true,
+                "--onboarding" => cli.onboarding = true,
+                "--mini" => cli.mini = true,
+                "--record" => cli.record = Some(args.next().ok_or("--record requires a path")?),
+                "--replay" => cli.replay = Some(args.next().ok_or("--replay requires a path")?),
+                "--visual" => cli.visual = true,
+                "--speed" => {
+                    let speed = args.next().ok_or("--speed requires a value")?;
+                    match speed.parse::<f64>() {
+                        Ok(n) if n > 0.0 => cli.speed = Some(n),
+                        _ => {
+                            return Err(format!("--speed must be a positive number, not '{speed}'"))
+                        }
+                    }
+                }
+                "--spectate" => {
+                    cli.spectate = Some(args.next().ok_or("--spectate requires a URL")?)
+                }
+                "--terminal" => cli.terminal = true,
+                "--bench-latency" => cli.bench_latency = true,
+                "--fail-over" => {
+                    let ms = args.next().ok_or("--fail-over requires milliseconds")?;
+                    match ms.parse::<f64>() {
+                        Ok(n) if n > 0.0 => cli.fail_over = Some(n),
+                        _ => {
+                            return Err(format!(
+                                "--fail-over must be a positive number of milliseconds, not '{ms}'"
+                            ))
+                        }
+                    }
+                }
+                "-h" | "--help" => cli.help = true,
+                _ => return Err(format!("unrecognized option '{arg}'")),
+            }
+        }
+        if cli.replay.is_none() && cli.visual {
+            return Err("--visual is only used with --replay".into());
+        }
+        if !cli.visual && cli.speed.is_some() {
+            return Err("--speed is only used with --replay --visual".into());
+        }
+        if cli.replay.is_some() && cli.record.is_some() {
+            return Err("--record and --replay can't be used together".into());
+        }
+        if cli.onboarding && (cli.record.is_some() || cli.replay.is_some()) {
+            return Err("--onboarding can't be used with --record or --replay".into());
+        }
+        let local = cli.onboarding || cli.record.is_some() || cli.replay.is_some();
+        if cli.spectate.is_some() && local {
+            return Err("--spectate can't be used with --onboarding, --record, or --replay".into());
+        }
+        if cli.terminal && (local || cli.spectate.is_some()) {
+            return Err(
+                "--terminal can't be used with --onboarding, --record, --replay, or --spectate"
+                    .into(),
+            );
+        }
+        if !cli.bench_latency && cli.fail_over.is_some() {
+            return Err("--fail-over is only used with --bench-latency".into());
+        }
+        if cli.bench_latency && (local || cli.spectate.is_some() || cli.terminal) {
+            return Err("--bench-latency can't be used with other modes".into());
+        }
+        Ok(cli)
+    }
+}
+
+/// Parses the options following `check`.
+fn parse_check(mut args: impl Iterator<Item = String>) -> Result<CheckArgs, String> {
+    let (mut dict, mut fix, mut out) = (None, false, None);
+    let (mut name, mut language) = (None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dict" => dict = Some(args.next().ok_or("--dict requires a path")?),
+            "--fix" => fix = true,
+            "--out" => out = Some(args.next().ok_or("--out requires a path")?),
+            "--name" => name = Some(args.next().ok_or("--name requires a value")?),
+            "--language" => language = Some(args.next().ok_or("--language requires a code")?),
+            _ => return Err(format!("unrecognized check option '{arg}'")),
+        }
+    }
+    let dict = dict.ok_or("check requires --dict <PATH>")?;
+    let fix_out = match (fix, out) {
+        (true, Some(out)) => Some(out),
+        (true, None) => return Err("--fix requires --out <PATH>".into()),
+        (false, Some(_)) => return Err("--out is only used with --fix".into()),
+        (false, None) => None,
+    };
+    if fix_out.is_none() && (name.is_some() || language.is_some()) {
+        return Err("--name and --language are only used with --fix".into());
+    }
+    Ok(CheckArgs {
+        dict,
+        fix_out,
+        name,
+        language,
+    })
+}
+
+/// Parses the options following `snapshot`, returning the snapshot directory.
+fn parse_snapshot(mut args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut dir = "snapshots".to_owned();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => dir = args.next().ok_or("--dir requires a path")?,
+            _ => return Err(format!("unrecognized snapshot option '{arg}'")),
+        }
+    }
+    Ok(dir)
+}
+
+/// Parses the options following `history`, returning how many answers to list.
+fn parse_history(args: impl Iterator<Item = String>) -> Result<usize, String> {
+    let mut top = DEFAULT_TOP;
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--top" => {
+                if let Some(value) = args.next_if(|value| !value.starts_with('-')) {
+                    top = value
+                        .parse()
+                        .map_err(|_| format!("--top must be a whole number, not '{value}'"))?;
+                }
+            }
+            _ => return Err(format!("unrecognized history option '{arg}'")),
+        }
+    }
+    Ok(top)
+}
+
+/// Parses the options following `digest`.
+fn parse_digest(mut args: impl Iterator<Item = String>) -> Result<DigestArgs, String> {
+    let mut digest = DigestArgs {
+        since: DEFAULT_SINCE,
+        out: None,
+        format: DigestFormat::Text,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--since" => {
+                let range = args.next().ok_or("--since requires a range of days")?;
+                digest.since = parse_range(&range).map_err(|e| format!("--since: {e}"))?;
+            }
+            "--out" => digest.out = Some(args.next().ok_or("--out requires a path")?),
+            "--format" => {
+                digest.format = match args.next().as_deref() {
+                    Some("text") => DigestFormat::Text,
+                    Some("html") => DigestFormat::Html,
+                    Some(name) => {
+                        return Err(format!("--format must be text or html, not '{name}'"))
+                    }
+                    None => return Err("--format requires text or html".into()),
+                }
+            }
+            _ => return Err(format!("unrecognized digest option '{arg}'")),
+        }
+    }
+    Ok(digest)
+}
+
+/// Parses the options following `coverage`, returning the history file's path.
+fn parse_coverage(mut args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut history = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--history" => history = Some(args.next().ok_or("--history requires a path")?),
+            _ => return Err(format!("unrecognized coverage option '{arg}'")),
+        }
+    }
+    history.ok_or_else(|| "coverage requires --history <PATH>".into())
+}
+
+/// Parses the arguments following `diff`.
+fn parse_diff(mut args: impl Iterator<Item = String>) -> Result<DiffArgs, String> {
+    let mut paths = Vec::new();
+    let mut format = DiffFormat::Text;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => DiffFormat::Text,
+                    Some("json") => DiffFormat::Json,
+                    Some(name) => {
+                        return Err(format!("--format must be text or json, not '{name}'"))
+                    }
+                    None => return Err("--format requires text or json".into()),
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("unrecognized diff option '{arg}'")),
+            _ => paths.push(arg),
+        }
+    }
+    match <[String; 2]>::try_from(paths) {
+        Ok([old, new]) => Ok(DiffArgs { old, new, format }),
+        Err(_) => Err("diff requires two word lists: <OLD> <NEW>".into()),
+    }
+}
+
+/// Parses the options following `restore`, returning the day of the backup.
+fn parse_restore(mut args: impl Iterator<Item = String>) -> Result<i64, String> {
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--date" => {
+                let date = args.next().ok_or("--date requires a date")?;
+                let parsed = parse_day(&date)
+                    .ok_or(format!("--date must be a date as YYYY-MM-DD, not '{date}'"))?;
+                day = Some(parsed);
+            }
+            _ => return Err(format!("unrecognized restore option '{arg}'")),
+        }
+    }
+    day.ok_or_else(|| "restore requires --date <YYYY-MM-DD>".into())
+}
+
+/// Parses the options following `confirmed`.
+fn parse_confirmed(mut args: impl Iterator<Item = String>) -> Result<MergeArgs, String> {
+    let (mut merge, mut dict, mut out) = (false, None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--merge" => merge = true,
+            "--dict" => dict = Some(args.next().ok_or("--dict requires a path")?),
+            "--out" => out = Some(args.next().ok_or("--out requires a path")?),
+            _ => return Err(format!("unrecognized confirmed option '{arg}'")),
+        }
+    }
+    if !merge {
+        return Err("confirmed requires --merge".into());
+    }
+    let dict = dict.ok_or("--merge requires --dict <PATH>")?;
+    let out = out.ok_or("--merge requires --out <PATH>")?;
+    Ok(MergeArgs { dict, out })
+}
+
+/// Parses the options following `prep`.
+fn parse_prep(mut args: impl Iterator<Item = String>) -> Result<PrepArgs, String> {
+    let (mut dict, mut frequencies, mut out) = (None, None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dict" => dict = Some(args.next().ok_or("--dict requires a path")?),
+            "--frequencies" => {
+                frequencies = Some(args.next().ok_or("--frequencies requires a path")?)
+            }
+            "--out" => out = Some(args.next().ok_or("--out requires a path")?),
+            _ => return Err(format!("unrecognized prep option '{arg}'")),
+        }
+    }
+    Ok(PrepArgs {
+        dict: dict.ok_or("prep requires --dict <PATH>")?,
+        frequencies: frequencies.ok_or("prep requires --frequencies <PATH>")?,
+        out: out.ok_or("prep requires --out <PATH>")?,
+    })
+}
+
+/// Parses the arguments following `verify`.
+fn parse_verify(mut args: impl Iterator<Item = String>) -> Result<VerifyArgs, String> {
+    let (mut archive, mut fail_under) = (None, None);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fail-under" => {
+                let share = args.next().ok_or("--fail-under requires a share")?;
+                match share.parse::<f64>() {
+                    Ok(n) if (0.0..=1.0).contains(&n) => fail_under = Some(n),
+                    _ => {
+                        return Err(format!(
+                            "--fail-under must be a share from 0 to 1, not '{share}'"
+                        ))
+                    }
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("unrecognized verify option '{arg}'")),
+            _ if archive.is_none() => archive = Some(arg),
+            _ => return Err(format!("verify takes one archive, not also '{arg}'")),
+        }
+    }
+    let archive = archive.ok_or("verify requires an archive: <ARCHIVE>")?;
+    Ok(VerifyArgs {
+        archive,
+        fail_under,
+    })
+}
+
+/// Parses the arguments following `metrics`.
+fn parse_metrics(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut summary = false;
+    for arg in args {
+        match arg.as_str() {
+            "--summary" => summary = true,
+            _ => return Err(format!("unrecognized metrics option '{arg}'")),
+        }
+    }
+    if !summary {
+        return Err("metrics requires --summary".into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn diff_takes_two_lists_and_a_format() {
+        let diff = parse(&["diff", "old.txt", "--format", "json", "new.txt"])
+            .unwrap()
+            .diff;
+        assert_eq!(
+            diff,
+            Some(DiffArgs {
+                old: "old.txt".into(),
+                new: "new.txt".into(),
+                format: DiffFormat::Json,
+            })
+        );
+        assert!(parse(&["diff", "old.txt"]).is_err());
+        assert!(parse(&["diff", "a", "b", "c"]).is_err());
+        assert!(parse(&["diff", "a", "b", "--format", "yaml"]).is_err());
+        assert!(parse(&["diff", "a", "b", "--brief"]).is_err());
+    }
+
+    #[test]
+    fn restore_needs_a_date() {
+        let day = parse(&["restore", "--date", "2024-03-01"]).unwrap().restore;
+        assert_eq!(day, parse_day("2024-03-01"));
+        assert!(parse(&["restore"]).is_err());
+        assert!(parse(&["restore", "--date", "March 1"]).is_err());
+        assert!(parse(&["restore", "--date"]).is_err());
+        assert!(parse(&["restore", "--all"]).is_err());
+    }
+
+    #[test]
+    fn merging_confirmed_words_needs_a_list_and_an_output() {
+        let merge = parse(&[
+            "confirmed",
+            "--merge",
+            "--dict",
+            "in.txt",
+            "--out",
+            "out.txt",
+        ])
+        .unwrap()
+        .merge
+        .unwrap();
+        assert_eq!(
+            (merge.dict.as_str(), merge.out.as_str()),
+            ("in.txt", "out.txt")
+        );
+        assert!(parse(&["confirmed", "--dict", "in.txt", "--out", "out.txt"]).is_err());
+        assert!(parse(&["confirmed", "--merge", "--out", "out.txt"]).is_err());
+        assert!(parse(&["confirmed", "--merge", "--dict", "in.txt"]).is_err());
+        assert!(parse(&["confirmed", "--merge", "--all"]).is_err());
+    }
+
+    #[test]
+    fn prep_needs_a_list_a_table_and_an_output() {
+        let prep = parse(&[
+            "prep",
+            "--frequencies",
+            "ngrams.tsv",
+            "--dict",
+            "in.txt",
+            "--out",
+            "out.txt",
+        ])
+        .unwrap()
+        .prep;
+        let expected = PrepArgs {
+            dict: "in.txt".into(),
+            frequencies: "ngrams.tsv".into(),
+            out: "out.txt".into(),
+        };
+        assert_eq!(prep, Some(expected));
+        assert!(parse(&["prep", "--dict", "in.txt", "--out", "out.txt"]).is_err());
+        assert!(parse(&["prep", "--frequencies", "t", "--out", "out.txt"]).is_err());
+        assert!(parse(&["prep", "--dict", "in.txt", "--frequencies"]).is_err());
+        assert!(parse(&["prep", "--sum"]).is_err());
+    }
+
+    #[test]
+    fn verify_takes_an_archive_and_a_share_to_fail_under() {
+        let verify = parse(&["verify", "answers.csv", "--fail-under", "0.8"])
+            .unwrap()
+            .verify
+            .unwrap();
+        assert_eq!(verify.archive, "answers.csv");
+        assert_eq!(verify.fail_under, Some(0.8));
+        assert!(parse(&["verify"]).is_err());
+        assert!(parse(&["verify", "a.csv", "b.csv"]).is_err());
+        assert!(parse(&["verify", "a.csv", "--fail-under", "80"]).is_err());
+        assert!(parse(&["verify", "a.csv", "--fail-under"]).is_err());
+    }
+
+    #[test]
+    fn digest_takes_a_range_an_output_and_a_format() {
+        let digest = parse(&[
+            "digest", "--since", "3d", "--format", "html", "--out", "d.html",
+        ])
+        .unwrap()
+        .digest;
+        assert_eq!(
+            digest,
+            Some(DigestArgs {
+                since: DayRange::Last(3),
+                out: Some("d.html".into()),
+                format: DigestFormat::Html,
+            })
+        );
+        let defaults = parse(&["digest"]).unwrap().digest.unwrap();
+        assert_eq!(
+            (defaults.since, defaults.out, defaults.format),
+            (DEFAULT_SINCE, None, DigestFormat::Text)
+        );
+        assert!(parse(&["digest", "--since", "a week"]).is_err());
+        assert!(parse(&["digest", "--since"]).is_err());
+        assert!(parse(&["digest", "--format", "pdf"]).is_err());
+        assert!(parse(&["digest", "--all"]).is_err());
+    }
+
+    #[test]
+    fn coverage_needs_a_history_file() {
+        let path = parse(&["coverage", "--history", "h.txt"]).unwrap().coverage;
+        assert_eq!(path.as_deref(), Some("h.txt"));
+        assert!(parse(&["coverage"]).is_err());
+        assert!(parse(&["coverage", "--history"]).is_err());
+        assert!(parse(&["coverage", "--all"]).is_err());
+    }
+
+    #[test]
+    fn the_latency_bench_takes_a_limit_and_runs_alone() {
+        let cli = parse(&["--bench-latency", "--fail-over", "12.5"]).unwrap();
+        assert!(cli.bench_latency);
+        assert_eq!(cli.fail_over, Some(12.5));
+        assert_eq!(parse(&["--bench-latency"]).unwrap().fail_over, None);
+        assert!(parse(&["--fail-over", "20"]).is_err());
+        assert!(parse(&["--bench-latency", "--fail-over", "0"]).is_err());
+        assert!(parse(&["--bench-latency", "--fail-over"]).is_err());
+        assert!(parse(&["--bench-latency", "--terminal"]).is_err());
+    }
+
+    #[test]
+    fn metrics_only_prints_a_summary() {
+        assert!(parse(&["metrics", "--summary"]).unwrap().metrics);
+        assert!(!parse(&[]).unwrap().metrics);
+        assert!(parse(&["metrics"]).is_err());
+        assert!(parse(&["metrics", "--summary", "--all"]).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Completing a scramble before all its letters are typed.
+//!
+//! While the letters typed so far match nothing, the words that use them all
+//! and have room for more (up to the entry's capacity, and of the lengths the
+//! list is limited to, if any) are counted, and the count shows in the entry
+//! box. Once there are `MAX_COMPLETIONS` or fewer, they're named instead, and
+//! the first one's missing letters show faintly after the entry: Tab types them,
+//! after the letters typed so far in the order they were typed.
+//!
+//! The words are found in a `KeyTrie` of the dictionary's sorted keys rather
+//! than by looking at every word. A `TrieCursor` marks where the letters typed
+//! lead in it, and follows each letter as it's added, so a letter typed costs
+//! only the part of the trie still in reach.
+
+use crate::alphabet::{Alphabet, MAX_LETTERS};
+
+/// Most words named as completions; more are only counted.
+pub const MAX_COMPLETIONS: usize = 5;
+/// Fewest letters typed before completions are looked for.
+pub const MIN_TYPED: usize = 2;
+
+/// The words that could complete the entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Completions {
+    /// How many there are.
+    pub count: usize,
+    /// The words, shortest and then alphabetically first, when there are
+    /// `MAX_COMPLETIONS` or fewer.
+    pub words: Vec<String>,
+}
+
+impl Completions {
+    /// Counts `word`, keeping it while there are few enough to name.
+    pub fn add(&mut self, word: &str) {
+        self.count += 1;
+        match self.count <= MAX_COMPLETIONS {
+            true => self.words.push(word.to_owned()),
+            false => self.words.clear(),
+        }
+    }
+    /// Puts the words named in order, once all are added.
+    pub fn finish(&mut self) {
+        self.words.sort_by(|a, b| {
+            a.chars()
+                .count()
+                .cmp(&b.chars().count())
+                .then_with(|| a.cmp(b))
+        });
+    }
+    /// Returns the first word named, which Tab completes the entry to.
+    pub fn first(&self) -> Option<&str> {
+        self.words.first().map(String::as_str)
+    }
+}
+
+/// Returns the letters of `word` that `typed` doesn't already have, in `word`'s
+/// order; both are taken as folded for the same alphabet.
+pub fn missing_letters(typed: &str, word: &str) -> String {
+    let mut unused: Vec<char> = typed.chars().collect();
+    word.chars()
+        .filter(|c| match unused.iter().position(|u| u == c) {
+            Some(i) => {
+                unused.swap_remove(i);
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// The sorted keys of a dictionary, letter by letter, in the order of its
+/// alphabet.
+#[derive(Debug)]
+pub struct KeyTrie {
+    alphabet: Alphabet,
+    /// The root first.
+    nodes: Vec<TrieNode>,
+}
+
+/// A letter of a key in a `KeyTrie`.
+#[derive(Debug, Default)]
+struct TrieNode {
+    /// The letters that can follow, in key order (see `KeyTrie::rank`), with
+    /// their nodes.
+    children: Vec<((usize, char), u32)>,
+    /// Whether a key ends here.
+    key_end: bool,
+    /// The letters of the keys under this node after it, a bit each by their
+    /// place in the alphabet, so a search for a letter skips nodes without it.
+    below: u32,
+}
+
+/// Where the letters typed so far lead in a `KeyTrie`: the highest nodes whose
+/// keys use every one of them, under which lie all the keys that do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieCursor {
+    /// The letters followed, as a sorted key.
+    typed: String,
+    /// The nodes reached, each with its key.
+    reached: Vec<(u32, String)>,
+}
+
+impl TrieCursor {
+    /// Returns the letters followed, as a sorted key.
+    pub fn typed(&self) -> &str {
+        &self.typed
+    }
+}
+
+impl KeyTrie {
+    /// Creates an empty trie for keys sorted in `alphabet`'s order.
+    pub fn new(alphabet: Alphabet) -> Self {
+        Self {
+            alphabet,
+            nodes: vec![TrieNode::default()],
+        }
+    }
+    /// Returns where `c` sorts in a key; see `Alphabet::key`.
+    fn rank(&self, c: char) -> (usize, char) {
+        (self.alphabet.index(c).unwrap_or(MAX_LETTERS), c)
+    }
+    /// Returns the bit of `c` in `TrieNode::below`, or all of them for a
+    /// character that isn't one of the alphabet's letters.
+    fn bit(&self, c: char) -> u32 {
+        self.alphabet.index(c).map_or(u32::MAX, |i| 1 << i)
+    }
+    /// Adds the sorted `key`.
+    pub fn insert(&mut self, key: &str) {
+        let mut node = 0;
+        for (at, c) in key.char_indices() {
+            let rest = key[at..].chars().fold(0, |below, c| below | self.bit(c));
+            self.nodes[node].below |= rest;
+            let rank = self.rank(c);
+            let children = &self.nodes[node].children;
+            let next = match children.binary_search_by_key(&rank, |&(rank, _)| rank) {
+                Ok(i) => children[i].1,
+                Err(i) => {
+                    let next = self.nodes.len() as u32;
+                    self.nodes[node].children.insert(i, (rank, next));
+                    self.nodes.push(TrieNode::default());
+                    next
+                }
+            };
+            node = next as usize;
+        }
+        self.nodes[node].key_end = true;
+    }
+    /// Returns a cursor that has followed the letters of the sorted `key`.
+    pub fn cursor(&self, key: &str) -> TrieCursor {
+        let letters: Vec<char> = key.chars().collect();
+        let mut reached = Vec::new();
+        self.search(0, &mut String::new(), &letters, &mut reached);
+        TrieCursor {
+            typed: key.to_owned(),
+            reached,
+        }
+    }
+    /// Adds to `reached` the highest nodes under `node`, of `key`, whose keys
+    /// use every one of the sorted `letters` too. Each letter is taken from the
+    /// first edge that has it, passing over smaller ones only where all the
+    /// letters are still to be found below.
+    fn search(
+        &self,
+        node: u32,
+        key: &mut String,
+        letters: &[char],
+        reached: &mut Vec<(u32, String)>,
+    ) {
+        let Some(&next) = letters.first() else {
+            reached.push((node, key.clone()));
+            return;
+        };
+        let rank = self.rank(next);
+        let wanted = letters.iter().fold(0, |wanted, &c| wanted | self.bit(c));
+        for &((i, c), child) in &self.nodes[node as usize].children {
+            if (i, c) > rank {
+                break;
+            }
+            key.push(c);
+            match (i, c) == rank {
+                true => self.search(child, key, &letters[1..], reached),
+                false if self.nodes[child as usize].below & wanted == wanted => {
+                    self.search(child, key, letters, reached)
+                }
+                false => (),
+            }
+            key.pop();
+        }
+    }
+    /// Moves `cursor` on to the keys that also use `letter`.
+    ///
+    /// A node whose key has a `letter` to spare stays; the others give way to
+    /// the nodes under them a `letter` further down.
+    pub fn advance(&self, cursor: &mut TrieCursor, letter: char) {
+        let need = cursor.typed.chars().filter(|&c| c == letter).count() + 1;
+        let mut reached = Vec::new();
+        for (node, mut key) in std::mem::take(&mut cursor.reached) {
+            match key.chars().filter(|&c| c == letter).count() >= need {
+                true => reached.push((node, key)),
+                false => self.search(node, &mut key, &[letter], &mut reached),
+            }
+        }
+        cursor.reached = reached;
+        let at = cursor
+            .typed
+            .char_indices()
+            .find(|&(_, c)| self.rank(c) > self.rank(letter))
+            .map_or(cursor.typed.len(), |(i, _)| i);
+        cursor.typed.insert(at, letter);
+    }
+    /// Calls `found` with every key under `cursor` longer than the letters it
+    /// followed, of at most `maxlen` letters.
+    pub fn keys_below(&self, cursor: &TrieCursor, maxlen: usize, mut found: impl FnMut(&str)) {
+        let typed = cursor.typed.chars().count();
+        for (node, key) in &cursor.reached {
+            let mut key = key.clone();
+            let len = key.chars().count();
+            self.walk(*node, &mut key, len, typed, maxlen, &mut found);
+        }
+    }
+    /// Calls `found` with `key`, of `len` letters, if a key ends at `node`, and
+    /// with the keys under it, while they're between `typed` and `maxlen`
+    /// letters long.
+    fn walk(
+        &self,
+        node: u32,
+        key: &mut String,
+        len: usize,
+        typed: usize,
+        maxlen: usize,
+        found: &mut impl FnMut(&str),
+    ) {
+        let node = &self.nodes[node as usize];
+        if node.key_end && len > typed && len <= maxlen {
+            found(key);
+        }
+        if len >= maxlen {
+            return;
+        }
+        for &((_, c), child) in &node.children {
+            key.push(c);
+            self.walk(child, key, len + 1, typed, maxlen, found);
+            key.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordmap::{make_word_map_with_progress, WordMap};
+    use std::sync::OnceLock;
+
+    const WORDS: &str = include_str!("../dictionary/ENGLISH_US_4_TO_8.txt");
+
+    fn word_map() -> &'static WordMap {
+        static MAP: OnceLock<WordMap> = OnceLock::new();
+        MAP.get_or_init(|| {
+            make_word_map_with_progress(WORDS, Alphabet::English, usize::MAX, usize::MAX, |_| ())
+        })
+    }
+
+    /// Finds the completions of `typed` by looking at every word.
+    fn every_word(map: &WordMap, typed: &str, maxlen: usize) -> Completions {
+        let mut completions = Completions::default();
+        let mut words: Vec<(&str, &[String])> = map.groups().collect();
+        words.sort_unstable_by_key(|&(key, _)| key);
+        for (key, words) in words {
+            let len = key.chars().count();
+            if len > typed.len() && len <= maxlen && missing_letters(key, typed).is_empty() {
+                words.iter().for_each(|word| completions.add(word));
+            }
+        }
+        completions.finish();
+        completions
+    }
+
+    #[test]
+    fn suggestions_match_a_search_of_every_word() {
+        let map = word_map();
+        for typed in ["UKCQ", "QU", "GRT", "TLAE", "EE", "ZZX", "XJ", "SS"] {
+            let cursor = map.follow(None, typed);
+            for maxlen in [5, 8] {
+                let found = map.suggestions_at_cursor(&cursor, maxlen, |_| true);
+                assert_eq!(found, every_word(map, typed, maxlen), "{typed}, {maxlen}");
+            }
+        }
+        let cursor = map.follow(None, "UKCQ");
+        let found = map.suggestions_at_cursor(&cursor, 8, |word| word != "QUACK");
+        assert!(found.words.iter().all(|word| word != "QUACK"), "{found:?}");
+    }
+
+    #[test]
+    fn the_cursor_follows_letters_added_and_restarts_otherwise() {
+        let map = word_map();
+        let mut cursor = map.follow(None, "GR");
+        // Several letters in one frame
+        cursor = map.follow(Some(cursor), "GRTE");
+        assert_eq!(cursor, map.follow(None, "ERGT"));
+        assert_eq!(cursor.typed(), "EGRT");
+        // A letter taken out
+        cursor = map.follow(Some(cursor), "GRA");
+        assert_eq!(cursor, map.follow(None, "GRA"));
+    }
+
+    #[test]
+    fn tab_adds_the_missing_letters_after_those_typed() {
+        let map = word_map();
+        // As in a five-letter entry
+        let completions = map.suggestions_at_cursor(&map.follow(None, "UKCQ"), 5, |_| true);
+        let first = completions.first().unwrap();
+        assert_eq!(first, "QUACK");
+        assert_eq!(missing_letters("UKCQ", first), "A");
+        assert_eq!(missing_letters("LTE", "LETTER"), "TER");
+    }
+}
@@ -0,0 +1,202 @@
+//! User configuration persisted between sessions.
+
+use crate::assist::DEFAULT_ASSIST_SECONDS;
+use crate::attract::DEFAULT_ATTRACT_SECONDS;
+use crate::dictionary::DictionaryOptions;
+use crate::platform::{desktop_reduces_motion, BackendChoice};
+use crate::query::cost::{CostLimits, DEFAULT_CONFIRM_COST, DEFAULT_REFUSE_COST};
+use crate::rank::RankWeights;
+use crate::remap::InputConfig;
+use crate::storage::{Format, Storage};
+use crate::strings::Language;
+use crate::style::DpiScale;
+use crate::theme::ThemeSpec;
+use crate::typo::KeyboardLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+
+pub const CONFIG_KEY: &str = "config.toml";
+/// Format of `CONFIG_KEY`; its envelope line is a TOML comment.
+pub const CONFIG_FORMAT: Format = Format {
+    name: "config",
+    version: 1,
+};
+
+/// Settings loaded from and saved to `CONFIG_KEY` in the app's storage.
+///
+/// Missing fields take their default values, so older config files keep working.
+/// Table-valued fields are declared last, as TOML requires tables after values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Multiplier applied to the base entry and result text sizes.
+    pub text_scale: f32,
+    /// Display scale: `"auto"` to follow the monitor, or a number such as 2.0.
+    pub dpi_scale: DpiScale,
+    /// Name of the selected theme (built-in or custom).
+    pub theme: String,
+    /// Windowing backend on Linux.
+    pub backend: BackendChoice,
+    /// Large-print mode: only the entry and one answer, in high contrast.
+    pub big_mode: bool,
+    /// Whether to spell out the unique answer in the NATO phonetic alphabet.
+    pub phonetic: bool,
+    /// Whether copying the answer copies it spelled out phonetically.
+    pub copy_phonetic: bool,
+    /// Whether an entry with no exact match falls back to close words (see
+    /// `escalate`).
+    pub auto_escalate: bool,
+    /// Whether to keep vulgar words out of the results (see `mask`).
+    pub mask_words: bool,
+    /// Whether a unique answer is held back behind a countdown (see `assist`).
+    pub assist: bool,
+    /// Seconds the countdown lasts.
+    pub assist_seconds: f32,
+    /// Whether a demo plays after a spell without input (see `attract`).
+    pub attract: bool,
+    /// Seconds without input before the demo plays.
+    pub attract_seconds: f32,
+    /// Whether effects end as soon as they start and the demo never plays, for
+    /// those bothered by movement. Unset, it follows the desktop's setting
+    /// where one is found (see `platform`).
+    pub reduced_motion: Option<bool>,
+    /// Whether to show the on-screen keyboard (it also appears on first touch).
+    pub on_screen_keyboard: bool,
+    /// Layout of the physical keyboard (qwerty or azerty), for suggesting
+    /// fixes to mistyped letters.
+    pub keyboard_layout: KeyboardLayout,
+    /// Font file to use instead of the embedded font.
+    pub font_path: Option<String>,
+    /// Milliseconds the entry must stay unchanged before slow query modes run.
+    pub debounce_ms: u64,
+    /// Estimated steps (see `query::cost`) above which a query command waits
+    /// for Enter before it runs.
+    pub confirm_query_cost: u64,
+    /// Estimated steps above which a query command is refused.
+    pub refuse_query_cost: u64,
+    /// Whether audio cues are silenced.
+    pub muted: bool,
+    /// Volume of audio cues, from 0.0 to 1.0.
+    pub volume: f32,
+    /// Least severe log level recorded: error, warn, info, debug, or trace.
+    /// `RUST_LOG` takes precedence.
+    pub log_level: String,
+    /// Whether to announce state changes for screen readers (see `announce`).
+    pub announce: bool,
+    /// Speech command run with each announcement appended, e.g. `spd-say`.
+    pub announce_command: Option<String>,
+    /// Command that reorders, filters, or annotates the matches, given them as
+    /// JSON on stdin (see `post_process`).
+    pub post_process_command: Option<String>,
+    /// Port on which to share the screen with spectators on the local network,
+    /// or 0 not to (see `share`).
+    pub share_port: u16,
+    /// Token spectators must present; nothing is shared without one.
+    pub share_token: Option<String>,
+    /// Whether to check once a day for a newer release (see `update`).
+    pub update_check: bool,
+    /// Where the manifest of the latest release is fetched from.
+    pub update_url: Option<String>,
+    /// Whether to count the features used, day by day, in a file on this
+    /// machine (see `usage`). Only counts are kept, and nothing is sent.
+    pub usage_metrics: bool,
+    /// Interface language: en, fr, or es. Also picks the default word list.
+    pub language: Language,
+    /// Word list file and size limits.
+    pub dictionary: DictionaryOptions,
+    /// How much each part of a match's score counts in ranking the matches.
+    pub ranking: RankWeights,
+    /// A one-handed preset and keys remapped for typing (see `remap`).
+    pub input: InputConfig,
+    /// Key binding overrides: action name to comma-separated chords.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, String>,
+    /// Custom themes keyed by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, ThemeSpec>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            text_scale: 1.0,
+            dpi_scale: DpiScale::Auto,
+            theme: "default".into(),
+            backend: BackendChoice::Auto,
+            big_mode: false,
+            phonetic: false,
+            copy_phonetic: false,
+            auto_escalate: false,
+            mask_words: true,
+            assist: false,
+            assist_seconds: DEFAULT_ASSIST_SECONDS,
+            attract: false,
+            attract_seconds: DEFAULT_ATTRACT_SECONDS,
+            reduced_motion: None,
+            on_screen_keyboard: cfg!(target_os = "android"),
+            keyboard_layout: KeyboardLayout::default(),
+            font_path: None,
+            debounce_ms: 150,
+            confirm_query_cost: DEFAULT_CONFIRM_COST,
+            refuse_query_cost: DEFAULT_REFUSE_COST,
+            muted: false,
+            volume: 0.7,
+            log_level: "info".into(),
+            announce: false,
+            announce_command: None,
+            post_process_command: None,
+            share_port: 0,
+            share_token: None,
+            update_check: false,
+            update_url: None,
+            usage_metrics: false,
+            language: Language::default(),
+            dictionary: DictionaryOptions::default(),
+            ranking: RankWeights::default(),
+            input: InputConfig::default(),
+            keys: BTreeMap::new(),
+            themes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config from `storage`, falling back to defaults if it is missing or
+    /// invalid.
+    pub fn load(storage: &dyn Storage) -> Self {
+        let text = match storage.read_format(CONFIG_KEY, CONFIG_FORMAT) {
+            Ok(Some(text)) => text,
+            Ok(None) => return Self::default(),
+            Err(e) => {
+                log::warn!("[Config::load] failed to read config: {e}");
+                return Self::default();
+            }
+        };
+        match toml::from_str(&text) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("[Config::load] ignoring invalid config: {e}");
+                Self::default()
+            }
+        }
+    }
+    /// Returns whether motion should be reduced: as set, or else as the
+    /// desktop asks.
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+            .unwrap_or_else(|| desktop_reduces_motion(|name| std::env::var(name).ok()))
+    }
+    /// Returns the query costs above which commands wait for Enter, and are
+    /// refused.
+    pub fn query_limits(&self) -> CostLimits {
+        CostLimits {
+            confirm: self.confirm_query_cost,
+            refuse: self.refuse_query_cost,
+        }
+    }
+    /// Returns the config as TOML text, as saved under `CONFIG_KEY`.
+    pub fn to_toml(&self) -> io::Result<String> {
+        toml::to_string_pretty(self).map_err(io::Error::other)
+    }
+}
@@ -0,0 +1,92 @@
+//! Answers confirmed as seen in print.
+//!
+//! With a unique answer shown, Ctrl+Shift+T marks it confirmed: the word is
+//! added to `CONFIRMED_KEY`, gets a check mark in later result lists, and ranks
+//! higher (see `rank`). `jumble_helper confirmed --merge` folds the confirmed
+//! words into a copy of a word list, so they rank as common words anywhere.
+//!
+//! `CONFIRMED_KEY` holds one word per line, in the order confirmed.
+
+use crate::memory::MemorySize;
+use crate::storage::Format;
+
+/// Storage key of the confirmed words.
+pub const CONFIRMED_KEY: &str = "confirmed.txt";
+/// Format of `CONFIRMED_KEY`.
+pub const CONFIRMED_FORMAT: Format = Format {
+    name: "confirmed",
+    version: 1,
+};
+/// Shown beside a confirmed word in the result list.
+pub const CONFIRMED_MARK: &str = "✓";
+
+/// The confirmed words, each once, in the order confirmed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Confirmed {
+    words: Vec<String>,
+}
+
+impl Confirmed {
+    /// Parses the confirmed words, uppercased, skipping blank and `#` lines and
+    /// words already listed.
+    pub fn parse(text: &str) -> Self {
+        let mut confirmed = Self::default();
+        for line in text.lines() {
+            let word = line.trim();
+            if !word.is_empty() && !word.starts_with('#') {
+                confirmed.insert(word);
+            }
+        }
+        confirmed
+    }
+    /// Writes the words in the file format above.
+    pub fn to_text(&self) -> String {
+        self.words.iter().map(|word| format!("{word}\n")).collect()
+    }
+    /// Returns whether `word` is confirmed.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w == word)
+    }
+    /// Adds `word`, uppercased, returning false if it was already confirmed.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let word = word.to_uppercase();
+        let new = !self.contains(&word);
+        if new {
+            self.words.push(word);
+        }
+        new
+    }
+    /// Returns the words in the order confirmed.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+impl MemorySize for Confirmed {
+    fn memory_size(&self) -> usize {
+        self.words.memory_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_word_is_kept_once_uppercased() {
+        let confirmed = Confirmed::parse("upon\n# from the paper\n\n GRATE \nUPON\n");
+        assert_eq!(confirmed.words(), ["UPON", "GRATE"]);
+        assert!(confirmed.contains("GRATE"));
+        assert!(!confirmed.contains("grate"));
+    }
+
+    #[test]
+    fn words_are_appended_in_the_order_confirmed() {
+        let mut confirmed = Confirmed::parse("UPON\n");
+        assert!(confirmed.insert("pound"));
+        assert!(!confirmed.insert("POUND"));
+        assert!(!confirmed.insert("upon"));
+        assert_eq!(confirmed.to_text(), "UPON\nPOUND\n");
+        assert_eq!(Confirmed::parse(&confirmed.to_text()), confirmed);
+    }
+}
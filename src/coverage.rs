@@ -0,0 +1,278 @@
+//! How often the dictionary has the answer to a scramble.
+//!
+//! Each time an entry long enough to be a word settles on a new outcome, it counts
+//! toward its dictionary's tally: one match, several, or none. The tallies are
+//! kept across sessions in `COVERAGE_KEY`, and the debug overlay shows this
+//! session's next to the running total. `jumble_helper coverage` works the same
+//! numbers out from a history file.
+//!
+//! Each line of `COVERAGE_KEY` is one dictionary: `UNIQUE AMBIGUOUS MISS SOURCE`,
+//! where the source (a word list path, or `built-in`) may contain spaces.
+
+use crate::dictionary::DictionaryOptions;
+use crate::feedback::MatchOutcome;
+use crate::storage::Format;
+use crate::strings::Language;
+use std::collections::BTreeMap;
+
+/// Storage key of the coverage tallies.
+pub const COVERAGE_KEY: &str = "coverage.txt";
+/// Format of `COVERAGE_KEY`.
+pub const COVERAGE_FORMAT: Format = Format {
+    name: "coverage",
+    version: 1,
+};
+/// Source named for the word list embedded in the app.
+pub const BUILT_IN_SOURCE: &str = "built-in";
+
+/// Counts of entries by how many matches they had.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    /// Entries with exactly one match.
+    pub unique: u64,
+    /// Entries with more than one match.
+    pub ambiguous: u64,
+    /// Entries with no matches.
+    pub miss: u64,
+}
+
+impl Coverage {
+    /// Counts `outcome`; an entry too short to be a word doesn't count.
+    pub fn record(&mut self, outcome: MatchOutcome) {
+        match outcome {
+            // The dictionary had the one word either way
+            MatchOutcome::Unique | MatchOutcome::AsTyped => self.unique += 1,
+            MatchOutcome::Several => self.ambiguous += 1,
+            MatchOutcome::NoMatch => self.miss += 1,
+            MatchOutcome::Incomplete => (),
+        }
+    }
+    /// Returns the number of entries counted.
+    pub fn total(&self) -> u64 {
+        self.unique + self.ambiguous + self.miss
+    }
+    /// Returns the unique, ambiguous, and miss shares as whole percentages that
+    /// add up to 100, or `None` if nothing was counted.
+    ///
+    /// Each share is rounded down, then the points left over go to the largest
+    /// remainders, so the line never reads 99% or 101%.
+    pub fn percentages(&self) -> Option<[u64; 3]> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let counts = [self.unique, self.ambiguous, self.miss];
+        let mut shares = counts.map(|n| n * 100 / total);
+        let mut remainders: Vec<(u64, usize)> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n * 100 % total, i))
+            .collect();
+        // Largest remainder first; ties go to the earlier share
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let left = 100 - shares.iter().sum::<u64>();
+        for &(_, i) in remainders.iter().take(left as usize) {
+            shares[i] += 1;
+        }
+        Some(shares)
+    }
+    /// Returns the shares as a line such as `unique 82% / ambiguous 11% / miss 7%`,
+    /// or `None` if nothing was counted.
+    pub fn describe(&self) -> Option<String> {
+        let [unique, ambiguous, miss] = self.percentages()?;
+        Some(format!(
+            "unique {unique}% / ambiguous {ambiguous}% / miss {miss}%"
+        ))
+    }
+    /// Adds `other`'s counts to these.
+    pub fn add(&mut self, other: &Coverage) {
+        self.unique += other.unique;
+        self.ambiguous += other.ambiguous;
+        self.miss += other.miss;
+    }
+}
+
+/// Coverage tallies by dictionary source.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CoverageLog {
+    pub sources: BTreeMap<String, Coverage>,
+}
+
+impl CoverageLog {
+    /// Parses a coverage file, skipping (and logging) lines it can't read.
+    pub fn parse(text: &str) -> Self {
+        let mut sources = BTreeMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(4, ' ');
+            let mut count = || fields.next().and_then(|n| n.parse::<u64>().ok());
+            let (unique, ambiguous, miss) = (count(), count(), count());
+            let source = fields.next().map(str::trim).filter(|s| !s.is_empty());
+            match (unique, ambiguous, miss, source) {
+                (Some(unique), Some(ambiguous), Some(miss), Some(source)) => {
+                    let coverage = Coverage {
+                        unique,
+                        ambiguous,
+                        miss,
+                    };
+                    sources
+                        .entry(source.to_owned())
+                        .or_insert_with(Coverage::default)
+                        .add(&coverage);
+                }
+                _ => log::warn!("[CoverageLog::parse] skipping line {}: '{line}'", i + 1),
+            }
+        }
+        Self { sources }
+    }
+    /// Writes the tallies in the file format above, sources in order.
+    pub fn to_text(&self) -> String {
+        self.sources
+            .iter()
+            .map(|(source, c)| format!("{} {} {} {source}\n", c.unique, c.ambiguous, c.miss))
+            .collect()
+    }
+    /// Counts `outcome` toward `source`'s tally.
+    pub fn record(&mut self, source: &str, outcome: MatchOutcome) {
+        if outcome == MatchOutcome::Incomplete {
+            return;
+        }
+        self.sources
+            .entry(source.to_owned())
+            .or_default()
+            .record(outcome);
+    }
+    /// Returns `source`'s tally, empty if it has none.
+    pub fn get(&self, source: &str) -> Coverage {
+        self.sources.get(source).copied().unwrap_or_default()
+    }
+}
+
+/// Returns the name coverage is tallied under for the word list `options` and
+/// `lang` call for: its path, or `BUILT_IN_SOURCE`.
+pub fn dictionary_source(options: &DictionaryOptions, lang: Language) -> String {
+    options
+        .path
+        .as_deref()
+        .or(lang.dictionary_file())
+        .unwrap_or(BUILT_IN_SOURCE)
+        .to_owned()
+}
+
+/// Prints how the configured dictionary covers the scrambles in the history file
+/// at `path`, returning the exit code: 0, or 2 if the file or dictionary can't be
+/// read.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_coverage(path: &str, options: &DictionaryOptions, lang: Language) -> i32 {
+    use crate::dictionary::{load_words, prepare_words};
+    use crate::history::History;
+    use crate::wordmap::make_word_map_with_progress;
+    use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+
+    let history = match std::fs::read_to_string(path) {
+        Ok(text) => History::parse(&text),
+        Err(e) => {
+            eprintln!("[run_coverage] can't read '{path}': {e}");
+            return 2;
+        }
+    };
+    let words = load_words(options, lang);
+    let (header, words) = match prepare_words(&words) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("[run_coverage] can't read the dictionary: {e}");
+            return 2;
+        }
+    };
+    let alphabet = options.alphabet_for(&header);
+    let map = make_word_map_with_progress(&words, alphabet, options.max_words, usize::MAX, |_| ());
+
+    let mut coverage = Coverage::default();
+    let mut key_buf = Vec::new();
+    for solve in &history.solves {
+        let matches = map
+            .find_match(
+                &solve.scramble,
+                MIN_WORD_LENGTH,
+                MAX_WORD_LENGTH,
+                &mut key_buf,
+            )
+            .map_or(0, <[String]>::len);
+        coverage.record(MatchOutcome::of(solve.scramble.chars().count(), matches));
+    }
+    let source = dictionary_source(options, lang);
+    match coverage.describe() {
+        Some(line) => println!("{source}: {line} of {} scrambles", coverage.total()),
+        None => println!("{source}: no scrambles in '{path}'"),
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage(unique: u64, ambiguous: u64, miss: u64) -> Coverage {
+        Coverage {
+            unique,
+            ambiguous,
+            miss,
+        }
+    }
+
+    #[test]
+    fn outcomes_are_counted_unless_incomplete() {
+        let mut tally = Coverage::default();
+        for outcome in [
+            MatchOutcome::Unique,
+            MatchOutcome::Unique,
+            MatchOutcome::Several,
+            MatchOutcome::NoMatch,
+            MatchOutcome::Incomplete,
+        ] {
+            tally.record(outcome);
+        }
+        assert_eq!(tally, coverage(2, 1, 1));
+        assert_eq!(tally.total(), 4);
+    }
+
+    #[test]
+    fn shares_add_up_to_a_hundred() {
+        assert_eq!(Coverage::default().percentages(), None);
+        assert_eq!(coverage(1, 1, 1).percentages(), Some([34, 33, 33]));
+        assert_eq!(coverage(2, 1, 0).percentages(), Some([67, 33, 0]));
+        assert_eq!(coverage(82, 11, 7).percentages(), Some([82, 11, 7]));
+        for (u, a, m) in [(1, 2, 4), (5, 0, 2), (7, 7, 1), (999, 1, 1)] {
+            let shares = coverage(u, a, m).percentages().unwrap();
+            assert_eq!(shares.iter().sum::<u64>(), 100, "{u} {a} {m}");
+        }
+        assert_eq!(
+            coverage(82, 11, 7).describe().as_deref(),
+            Some("unique 82% / ambiguous 11% / miss 7%")
+        );
+    }
+
+    #[test]
+    fn tallies_read_back_as_written_by_source() {
+        let mut log = CoverageLog::default();
+        log.record("built-in", MatchOutcome::Unique);
+        log.record("/home/me/My Words.txt", MatchOutcome::NoMatch);
+        log.record("/home/me/My Words.txt", MatchOutcome::Several);
+        log.record("built-in", MatchOutcome::Incomplete);
+        let text = log.to_text();
+        assert_eq!(text, "0 1 1 /home/me/My Words.txt\n1 0 0 built-in\n");
+        assert_eq!(CoverageLog::parse(&text), log);
+        assert_eq!(log.get("built-in"), coverage(1, 0, 0));
+        assert_eq!(log.get("elsewhere"), Coverage::default());
+    }
+
+    #[test]
+    fn unreadable_lines_are_skipped_and_repeats_summed() {
+        let log = CoverageLog::parse("# tallies\n1 2 3 a\nx 2 3 b\n1 2 c\n4 0 0 a\n\n1 1 1 \n");
+        assert_eq!(log.sources.len(), 1);
+        assert_eq!(log.get("a"), coverage(5, 2, 3));
+    }
+}
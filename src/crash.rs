@@ -0,0 +1,179 @@
+//! Writing a crash report when the app panics.
+//!
+//! Reports go to `crash-<timestamp>.txt` in the data directory, with the panic
+//! message, a backtrace, the app version, and what was on screen. The on-screen
+//! state is kept in a `StateSummary` the hook can read from any thread, which
+//! is also what `share` sends to spectators.
+
+// `DeJson`'s expansion for `Option` fields trips this lint
+#![allow(clippy::question_mark)]
+
+use crate::logging::timestamp;
+use crate::storage::safe_write;
+use arc_swap::ArcSwap;
+use nanoserde::{DeJson, SerJson};
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// What the user was looking at, for crash reports and spectators.
+#[derive(Debug, Default, Clone, PartialEq, Eq, DeJson, SerJson)]
+pub struct StateSummary {
+    pub entry: String,
+    #[nserde(default)]
+    pub answer: Option<String>,
+    pub match_count: usize,
+    /// The matches as listed, hidden words left out.
+    pub matches: Vec<String>,
+    /// The answers of the open tabs, in tab order.
+    pub solved: Vec<String>,
+    /// The final answer's letters, while they're being spelled (see `pool`).
+    pub pool: String,
+    /// The final answer spelled so far.
+    pub phrase: String,
+}
+
+/// Keeps the state summary current and reports panics with it.
+pub struct CrashReporter {
+    summary: Arc<ArcSwap<StateSummary>>,
+}
+
+impl CrashReporter {
+    /// Installs a panic hook writing reports to `dir`, ahead of any existing hook.
+    pub fn install(dir: PathBuf) -> Self {
+        let summary: Arc<ArcSwap<StateSummary>> = Default::default();
+        let hook_summary = Arc::clone(&summary);
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            match write_crash_report(&dir, info, &hook_summary.load()) {
+                Ok(path) => notify_user(&path),
+                Err(e) => eprintln!("[CrashReporter] failed to write crash report: {e}"),
+            }
+            previous(info);
+        }));
+        Self { summary }
+    }
+    /// Records the current state, if it differs from the last one recorded.
+    pub fn update(&self, summary: StateSummary) {
+        if **self.summary.load() != summary {
+            self.summary.store(Arc::new(summary));
+        }
+    }
+    /// Returns the recorded state, which is replaced (not changed) on each update.
+    pub fn summary(&self) -> Arc<ArcSwap<StateSummary>> {
+        Arc::clone(&self.summary)
+    }
+}
+
+/// Writes a report for the panic described by `info`, returning its path.
+pub fn write_crash_report(
+    dir: &Path,
+    info: &PanicHookInfo,
+    summary: &StateSummary,
+) -> std::io::Result<PathBuf> {
+    let stamp = timestamp();
+    let file_stamp: String = stamp
+        .chars()
+        .filter_map(|c| match c {
+            '-' | ':' => None,
+            ' ' => Some('-'),
+            c => Some(c),
+        })
+        .collect();
+    let path = dir.join(format!("crash-{file_stamp}.txt"));
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "jumble_helper {} crashed at {stamp} UTC",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(text, "\n{info}");
+    let _ = writeln!(text, "\nentry: '{}'", summary.entry);
+    let _ = writeln!(text, "answer: {:?}", summary.answer);
+    let _ = writeln!(text, "matches: {}", summary.match_count);
+    let _ = writeln!(text, "\nbacktrace:\n{}", Backtrace::force_capture());
+
+    std::fs::create_dir_all(dir)?;
+    safe_write(&path, text.as_bytes())?;
+    Ok(path)
+}
+
+/// Tells the user where the report went, with a desktop dialog if one is at hand.
+fn notify_user(path: &Path) {
+    let message = format!("Something went wrong. Details saved to {}", path.display());
+    eprintln!("[CrashReporter] {message}");
+
+    let dialog = if cfg!(target_os = "macos") {
+        let script = format!("display alert \"Jumble Helper\" message {message:?}");
+        std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .spawn()
+    } else if cfg!(target_os = "windows") {
+        std::process::Command::new("msg")
+            .args(["*", &message])
+            .spawn()
+    } else {
+        std::process::Command::new("zenity")
+            .args(["--error", "--title=Jumble Helper", "--text", &message])
+            .spawn()
+    };
+    if dialog.is_err() {
+        eprintln!("[CrashReporter] no dialog available; see the message above");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Set, to the directory to report to, in the process
+    /// `a_panic_writes_a_report` runs itself in to panic.
+    const CHILD_DIR_ENV: &str = "JUMBLE_CRASH_TEST_DIR";
+
+    // Elsewhere the dialog can't be kept from showing
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    #[test]
+    fn a_panic_writes_a_report() {
+        if let Some(dir) = std::env::var_os(CHILD_DIR_ENV) {
+            let reporter = CrashReporter::install(dir.into());
+            reporter.update(StateSummary {
+                entry: "GERTA".into(),
+                answer: Some("GREAT".into()),
+                match_count: 1,
+                ..Default::default()
+            });
+            panic!("forced for the crash report test");
+        }
+
+        let dir = std::env::temp_dir().join(format!("jumble_helper-crash-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "crash::tests::a_panic_writes_a_report"])
+            .env(CHILD_DIR_ENV, &dir)
+            .env_remove("DISPLAY")
+            .env_remove("WAYLAND_DISPLAY")
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "the child didn't panic");
+
+        let reports: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                let name = path.file_name().unwrap().to_string_lossy();
+                name.starts_with("crash-") && name.ends_with(".txt")
+            })
+            .collect();
+        assert_eq!(reports.len(), 1, "{reports:?}");
+        let text = std::fs::read_to_string(&reports[0]).unwrap();
+        assert!(text.contains(env!("CARGO_PKG_VERSION")), "{text}");
+        assert!(text.contains("forced for the crash report test"), "{text}");
+        assert!(text.contains("entry: 'GERTA'"), "{text}");
+        assert!(text.contains("answer: Some(\"GREAT\")"), "{text}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
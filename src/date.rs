@@ -0,0 +1,119 @@
+//! Calendar dates as whole days since 1970-01-01.
+//!
+//! Today is the user's local calendar day, so an answer found late in the
+//! evening counts for that evening's puzzle, not the next day's. Days are
+//! numbered as calendar dates, so a day an hour short or long around a clock
+//! change is still one day.
+
+/// Converts days since 1970-01-01 to a (year, month, day) date.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a (year, month, day) date to days since 1970-01-01; the inverse of
+/// `civil_from_days`.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Formats `days` as `YYYY-MM-DD`.
+pub fn format_day(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parses a `YYYY-MM-DD` date, rejecting days the month doesn't have.
+pub fn parse_day(text: &str) -> Option<i64> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    let days = days_from_civil(year, month, day);
+    (civil_from_days(days) == (year, month, day)).then_some(days)
+}
+
+/// Returns today's local date, or `None` on the web, which has no system clock.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> Option<i64> {
+    Some(local_day(&chrono::Local::now()))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> Option<i64> {
+    None
+}
+
+/// Returns the calendar date `time` falls on in its own time zone.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn local_day<Tz: chrono::TimeZone>(time: &chrono::DateTime<Tz>) -> i64 {
+    use chrono::Datelike;
+    let date = time.date_naive();
+    days_from_civil(i64::from(date.year()), date.month(), date.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_and_dates_round_trip() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(days_from_civil(2000, 2, 29), 11_016);
+        for days in -800_000..800_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn days_parse_as_they_format() {
+        let day = parse_day("2023-04-11").unwrap();
+        assert_eq!(format_day(day), "2023-04-11");
+        assert_eq!(
+            parse_day("2024-02-29").map(format_day).as_deref(),
+            Some("2024-02-29")
+        );
+        assert_eq!(parse_day("2023-02-29"), None);
+        assert_eq!(parse_day("2023-13-01"), None);
+        assert_eq!(parse_day("2023-04"), None);
+        assert_eq!(parse_day("yesterday"), None);
+    }
+
+    #[test]
+    fn local_days_follow_the_offset() {
+        use chrono::{FixedOffset, TimeZone, Utc};
+        // 2023-03-12 03:30 UTC: still the 11th in New York, on either side of
+        // its clock change that morning
+        let time = Utc.with_ymd_and_hms(2023, 3, 12, 3, 30, 0).unwrap();
+        let at = |hours: i32| {
+            local_day(&time.with_timezone(&FixedOffset::east_opt(hours * 3600).unwrap()))
+        };
+        assert_eq!(format_day(local_day(&time)), "2023-03-12");
+        assert_eq!(format_day(at(-5)), "2023-03-11");
+        assert_eq!(format_day(at(-4)), "2023-03-11");
+        // and already the 12th further east
+        assert_eq!(format_day(at(9)), "2023-03-12");
+        // Before the epoch rounds down, not toward zero
+        let before = Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(local_day(&before), -1);
+    }
+}
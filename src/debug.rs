@@ -0,0 +1,87 @@
+//! Debug overlay showing timing and query internals.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+pub const DEBUG_TEXT_SIZE: u16 = 20;
+
+/// Whether the debug overlay is shown at startup: on in debug builds only.
+pub const DEBUG_OVERLAY_DEFAULT: bool = cfg!(debug_assertions);
+
+/// Values shown by the debug overlay, assembled by the `App`.
+#[derive(Debug, Default, Clone)]
+pub struct DebugInfo {
+    pub fps: i32,
+    pub frames_drawn: u64,
+    pub frames_skipped: u64,
+    pub entry: String,
+    pub entry_offset: f32,
+    pub sorted_key: String,
+    pub match_count: usize,
+    /// Fraction of lookups served from cache, if a cache is in use.
+    pub cache_hit_rate: Option<f32>,
+    /// Duration of the most recent dictionary query, in microseconds.
+    pub last_query_us: Option<f64>,
+    /// Heap allocations during the last keystroke (needs the `alloc-count` feature).
+    pub allocs_per_keystroke: Option<usize>,
+    /// How often the dictionary had the answer, this session and in all, if
+    /// anything's been counted.
+    pub coverage: [Option<String>; 2],
+    /// What the long-lived stores hold, in all and the largest (see `memory`).
+    pub memory: Option<String>,
+}
+
+impl DebugInfo {
+    /// Returns the overlay text, one entry per line.
+    pub fn lines(&self) -> Vec<String> {
+        let cache = match self.cache_hit_rate {
+            Some(rate) => format!("{:.1}%", rate * 100.0),
+            None => "n/a".into(),
+        };
+        let query = match self.last_query_us {
+            Some(us) => format!("{us:.0} us"),
+            None => "-".into(),
+        };
+        let memory = self.memory.as_deref().unwrap_or("-");
+        let [session, total] = self
+            .coverage
+            .clone()
+            .map(|line| line.unwrap_or_else(|| "-".into()));
+        vec![
+            format!("FPS: {}", self.fps),
+            format!(
+                "Frames drawn/skipped: {}/{}",
+                self.frames_drawn, self.frames_skipped
+            ),
+            format!("Entry: '{}' (offset {:.1})", self.entry, self.entry_offset),
+            format!("Sorted key: '{}'", self.sorted_key),
+            format!("Matches: {}", self.match_count),
+            format!("Cache hit rate: {cache}"),
+            format!("Last query: {query}"),
+            match self.allocs_per_keystroke {
+                Some(n) => format!("Allocs/keystroke: {n}"),
+                None => "Allocs/keystroke: n/a".into(),
+            },
+            format!("Coverage (session): {session}"),
+            format!("Coverage (all): {total}"),
+            format!("Memory: {memory}"),
+        ]
+    }
+}
+
+/// Draws the debug overlay in the top-left corner of the window.
+pub fn draw_debug_overlay(
+    gfx: &dyn Renderer,
+    info: &DebugInfo,
+    font: Option<&Font>,
+    theme: &Theme,
+) {
+    let line_h = DEBUG_TEXT_SIZE as f32 * 1.2;
+    let mut y = line_h;
+
+    for line in info.lines() {
+        gfx.text(&line, vec2(8.0, y), font, DEBUG_TEXT_SIZE, theme.highlight);
+        y += line_h;
+    }
+}
@@ -0,0 +1,739 @@
+//! Choosing and reading the word list, with limits so a bad file can't exhaust
+//! memory.
+//!
+//! A word list can be named in the `[dictionary]` table of the config file:
+//!
+//! ```toml
+//! [dictionary]
+//! path = "dictionary/my_words.txt"
+//! max_file_mb = 64
+//! max_words = 1000000
+//! alphabet = "es"
+//! frequencies = "dictionary/counts.txt"
+//! ```
+//!
+//! Otherwise the configured language's own list is used if it's installed (e.g.
+//! `dictionary/FRENCH_4_TO_8.txt`), then the embedded English list, joined by the
+//! longer words of bonus jumbles in `BONUS_WORDS` if that's installed.
+//!
+//! A word list may describe itself in `#!` lines at the top:
+//!
+//! ```text
+//! #! name=Mom's words
+//! #! language=en
+//! #! alphabet=en
+//! #! min_len=5
+//! #! max_len=6
+//! #! case=lower
+//! #! frequency_column=true
+//! ```
+//!
+//! Words outside the lengths given are skipped, words are uppercased unless
+//! `case=upper`, and with a frequency column each line's last field is a count,
+//! kept for ranking matches (see `rank`), and the rest is the word. The
+//! header's `alphabet` (see `alphabet`) takes the place of the config's.
+//!
+//! A line may also be a phrase, its words joined by spaces or hyphens (`HOT
+//! DOG`, `IN-LAW`), for the final answer; its letters are what count toward the
+//! lengths, and only `:phrase` queries find it (see `WordMap::phrases`). A list
+//! without a frequency column takes its counts from the config's `frequencies`
+//! table, if it names one (see `frequency`).
+
+use crate::alphabet::Alphabet;
+use crate::strings::Language;
+use crate::wordmap::{fold_letter, phrase_letters, PHRASE_JOINERS};
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The default word list, embedded so the binary works from any directory.
+const EMBEDDED_WORDS: &str = include_str!("../dictionary/ENGLISH_US_4_TO_8.txt");
+/// Nine- and ten-letter words added to the embedded list, for the longer bonus
+/// scrambles some papers run.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+const BONUS_WORDS: &str = "dictionary/ENGLISH_US_9_TO_10.txt";
+
+/// Where the word list comes from, and how much of it to accept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictionaryOptions {
+    /// Word list file (one word per line) to use instead of the embedded list.
+    pub path: Option<String>,
+    /// Files larger than this many megabytes are refused.
+    pub max_file_mb: u64,
+    /// Words past this many are ignored.
+    pub max_words: usize,
+    /// The letters the words are spelled with, unless the list's header says.
+    pub alphabet: Option<Alphabet>,
+    /// Table of word counts for a list without a frequency column.
+    pub frequencies: Option<String>,
+}
+
+impl Default for DictionaryOptions {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_file_mb: 64,
+            max_words: 1_000_000,
+            alphabet: None,
+            frequencies: None,
+        }
+    }
+}
+
+impl DictionaryOptions {
+    /// Returns the file size limit in bytes.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn max_file_bytes(&self) -> u64 {
+        self.max_file_mb.saturating_mul(1024 * 1024)
+    }
+    /// Returns the alphabet of a word list with `header`: the header's, else the
+    /// configured one, else English.
+    pub fn alphabet_for(&self, header: &DictionaryHeader) -> Alphabet {
+        header.alphabet.or(self.alphabet).unwrap_or_default()
+    }
+}
+
+/// Returns the configured word list, or `lang`'s list if none is configured,
+/// falling back to the embedded list if it can't be read.
+pub fn load_words(options: &DictionaryOptions, lang: Language) -> Cow<'static, str> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let path = options.path.as_deref().or_else(|| {
+        lang.dictionary_file()
+            .filter(|file| crate::assets::resolve_asset(file).exists())
+    });
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = path {
+        match read_word_file(
+            &crate::assets::resolve_asset(path),
+            options.max_file_bytes(),
+        ) {
+            Ok(words) => {
+                log::info!("[load_words] using dictionary '{path}'");
+                return Cow::Owned(words);
+            }
+            Err(e) => log::warn!("[load_words] using the built-in dictionary: {e}"),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    if let Some(path) = options.path.as_deref().or(lang.dictionary_file()) {
+        log::warn!("[load_words] dictionary files are not supported on the web: '{path}'");
+    }
+
+    log::info!("[load_words] using the built-in dictionary");
+    built_in_words(options)
+}
+
+/// Returns the embedded list, with the words in `BONUS_WORDS` added if it's
+/// installed and can be read.
+fn built_in_words(options: &DictionaryOptions) -> Cow<'static, str> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let file = crate::assets::resolve_asset(BONUS_WORDS);
+        if file.exists() {
+            match read_word_file(&file, options.max_file_bytes()) {
+                Ok(bonus) => {
+                    log::info!("[built_in_words] adding the words in '{BONUS_WORDS}'");
+                    return Cow::Owned(format!("{}\n{bonus}", EMBEDDED_WORDS.trim_end()));
+                }
+                Err(e) => log::warn!("[built_in_words] leaving out the bonus words: {e}"),
+            }
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = options;
+    Cow::Borrowed(EMBEDDED_WORDS)
+}
+
+/// Returns the word list at `path`, or `lang`'s default list for `None`.
+///
+/// Unlike `load_words`, a file that can't be read is an error rather than a
+/// fallback, so a choice can be checked before it's used.
+pub fn try_load_words(
+    path: Option<&str>,
+    options: &DictionaryOptions,
+    lang: Language,
+) -> Result<Cow<'static, str>, String> {
+    let Some(path) = path else {
+        let options = DictionaryOptions {
+            path: None,
+            ..options.clone()
+        };
+        return Ok(load_words(&options, lang));
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let file = crate::assets::resolve_asset(path);
+        read_word_file(&file, options.max_file_bytes()).map(Cow::Owned)
+    }
+    #[cfg(target_arch = "wasm32")]
+    Err(format!(
+        "dictionary files are not supported on the web: '{path}'"
+    ))
+}
+
+/// Reads the text file at `path`, refusing files over `max_bytes` or that aren't
+/// UTF-8.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_word_file(path: &std::path::Path, max_bytes: u64) -> Result<String, String> {
+    use std::io::Read;
+
+    let name = path.display();
+    let file = std::fs::File::open(path).map_err(|e| format!("can't open '{name}': {e}"))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let too_big = |size: u64| {
+        format!(
+            "'{name}' is {:.1} MB; the limit is {:.1} MB",
+            size as f64 / 1024.0 / 1024.0,
+            max_bytes as f64 / 1024.0 / 1024.0
+        )
+    };
+    if size > max_bytes {
+        return Err(too_big(size));
+    }
+
+    // The file may grow after its size was checked, so cap the read as well
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("can't read '{name}': {e}"))?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(too_big(bytes.len() as u64));
+    }
+
+    String::from_utf8(bytes).map_err(|e| {
+        let offset = e.utf8_error().valid_up_to();
+        format!("'{name}' is not UTF-8 text: invalid byte sequence at offset {offset}")
+    })
+}
+
+/// The prefix of a word list's header lines.
+const HEADER_PREFIX: &str = "#!";
+
+/// How a word list's words are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordCase {
+    Upper,
+    Lower,
+    Mixed,
+}
+
+/// What a word list says about itself in its `#!` header.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DictionaryHeader {
+    pub name: Option<String>,
+    /// A language code, e.g. `en`.
+    pub language: Option<String>,
+    /// The letters the words are spelled with.
+    pub alphabet: Option<Alphabet>,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub case: Option<WordCase>,
+    /// Whether each line is a word followed by its frequency.
+    pub frequency_column: bool,
+}
+
+impl DictionaryHeader {
+    /// Returns whether `word_of` changes lines.
+    pub fn rewrites_lines(&self) -> bool {
+        self.frequency_column || matches!(self.case, Some(WordCase::Lower | WordCase::Mixed))
+    }
+    /// Returns whether the header changes which words are read, or how.
+    fn is_plain(&self) -> bool {
+        self.min_len.is_none() && self.max_len.is_none() && !self.rewrites_lines()
+    }
+    /// Returns the word on `line` as the header says to read it: all but the
+    /// count if there's a frequency column, uppercased unless the list is.
+    pub fn word_of<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let word = match self.frequency_column {
+            true => match line.trim().rsplit_once(char::is_whitespace) {
+                Some((word, count)) if count.parse::<u64>().is_ok() => word.trim_end(),
+                _ => line.trim(),
+            },
+            false => line,
+        };
+        match self.case {
+            Some(WordCase::Lower | WordCase::Mixed) => Cow::Owned(word.to_uppercase()),
+            _ => Cow::Borrowed(word),
+        }
+    }
+    /// Returns the frequency given on `line`, if the list has a frequency column
+    /// and the line's last field is a count.
+    pub fn frequency_of(&self, line: &str) -> Option<u64> {
+        if !self.frequency_column {
+            return None;
+        }
+        line.split_whitespace().last()?.parse().ok()
+    }
+    /// Returns whether `word` is within the header's length limits, counting a
+    /// phrase's letters.
+    pub fn fits(&self, word: &str) -> bool {
+        let len = phrase_letters(word.trim()).chars().count();
+        self.min_len.is_none_or(|min| len >= min) && self.max_len.is_none_or(|max| len <= max)
+    }
+    /// Returns a short description for the interface, e.g. `Mom's words (en)`,
+    /// or `None` if the list isn't named.
+    pub fn title(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        Some(match &self.language {
+            Some(language) => format!("{name} ({language})"),
+            None => name.to_owned(),
+        })
+    }
+    /// Writes the header as `#!` lines, leaving out what isn't set.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(name) = &self.name {
+            lines.push(format!("name={name}"));
+        }
+        if let Some(language) = &self.language {
+            lines.push(format!("language={language}"));
+        }
+        if let Some(alphabet) = self.alphabet {
+            lines.push(format!("alphabet={}", alphabet.code()));
+        }
+        if let Some(min_len) = self.min_len {
+            lines.push(format!("min_len={min_len}"));
+        }
+        if let Some(max_len) = self.max_len {
+            lines.push(format!("max_len={max_len}"));
+        }
+        if let Some(case) = self.case {
+            let case = match case {
+                WordCase::Upper => "upper",
+                WordCase::Lower => "lower",
+                WordCase::Mixed => "mixed",
+            };
+            lines.push(format!("case={case}"));
+        }
+        if self.frequency_column {
+            lines.push("frequency_column=true".into());
+        }
+        lines
+            .iter()
+            .map(|line| format!("{HEADER_PREFIX} {line}\n"))
+            .collect()
+    }
+}
+
+/// Parses the `#!` lines at the top of a word list, returning the header, the
+/// rest of the list, and how many lines the header took.
+///
+/// Unknown keys are skipped with a warning; a line that can't be read is an
+/// error naming it.
+pub fn parse_header(text: &str) -> Result<(DictionaryHeader, &str, usize), String> {
+    let mut header = DictionaryHeader::default();
+    let mut rest = text;
+    let mut line_no = 0;
+    while rest.starts_with(HEADER_PREFIX) {
+        let (line, next) = rest.split_once('\n').unwrap_or((rest, ""));
+        rest = next;
+        line_no += 1;
+        let line = line[HEADER_PREFIX.len()..].trim();
+        let bad = |why: String| format!("header line {line_no}: {why}");
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| bad(format!("expected 'key=value', got '{line}'")))?;
+        let number = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| bad(format!("{key} must be a whole number, not '{value}'")))
+        };
+        match key {
+            "name" => header.name = Some(value.to_owned()),
+            "language" => header.language = Some(value.to_lowercase()),
+            "alphabet" => {
+                let alphabet = Alphabet::parse(&value.to_lowercase())
+                    .ok_or_else(|| bad(format!("alphabet must be en or es, not '{value}'")))?;
+                header.alphabet = Some(alphabet);
+            }
+            "min_len" => header.min_len = Some(number(value)?),
+            "max_len" => header.max_len = Some(number(value)?),
+            "case" => {
+                header.case = Some(match value {
+                    "upper" => WordCase::Upper,
+                    "lower" => WordCase::Lower,
+                    "mixed" => WordCase::Mixed,
+                    _ => {
+                        return Err(bad(format!(
+                            "case must be upper, lower, or mixed, not '{value}'"
+                        )))
+                    }
+                })
+            }
+            "frequency_column" => {
+                header.frequency_column = match value {
+                    "true" => true,
+                    "false" => false,
+                    _ => {
+                        return Err(bad(format!(
+                            "frequency_column must be true or false, not '{value}'"
+                        )))
+                    }
+                }
+            }
+            _ => log::warn!("[parse_header] ignoring unknown key '{key}' on header line {line_no}"),
+        }
+        if let (Some(min), Some(max)) = (header.min_len, header.max_len) {
+            if min > max {
+                return Err(bad(format!("min_len {min} is more than max_len {max}")));
+            }
+        }
+    }
+    Ok((header, rest, line_no))
+}
+
+/// Returns a word list's header and its words, read as the header says, ready
+/// for the word map.
+pub fn prepare_words(text: &str) -> Result<(DictionaryHeader, Cow<'_, str>), String> {
+    let (header, body, _) = parse_header(text)?;
+    if header.is_plain() {
+        return Ok((header, Cow::Borrowed(body)));
+    }
+    let mut words = String::with_capacity(body.len());
+    for word in body.lines().map(|line| header.word_of(line)) {
+        if !word.is_empty() && header.fits(&word) {
+            words.push_str(&word);
+            words.push('\n');
+        }
+    }
+    Ok((header, Cow::Owned(words)))
+}
+
+/// Returns each word's frequency from a word list with a frequency column, for
+/// ranking matches; empty if it has none (or a header that can't be read).
+pub fn read_frequencies(text: &str) -> HashMap<String, u64> {
+    let Ok((header, body, _)) = parse_header(text) else {
+        return HashMap::new();
+    };
+    body.lines()
+        .filter_map(|line| {
+            Some((
+                header.word_of(line).into_owned(),
+                header.frequency_of(line)?,
+            ))
+        })
+        .collect()
+}
+
+/// Problems a line of a word list can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LineIssue {
+    /// The word appeared on an earlier line.
+    Duplicate,
+    /// Shorter than `MIN_WORD_LENGTH` or longer than `MAX_WORD_LENGTH` letters.
+    BadLength,
+    /// Contains non-ASCII characters other than accented letters.
+    NotAscii,
+    /// Contains ASCII characters other than letters.
+    NotALetter,
+    /// Contains lowercase letters, which never match the uppercase entry.
+    Lowercase,
+    /// Leading or trailing whitespace, including a Windows `\r`.
+    StrayWhitespace,
+}
+
+impl LineIssue {
+    /// Returns a short human-readable description of the issue.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn description(self) -> &'static str {
+        match self {
+            LineIssue::Duplicate => "duplicate word",
+            LineIssue::BadLength => "word length out of bounds",
+            LineIssue::NotAscii => "non-ASCII characters other than accented letters",
+            LineIssue::NotALetter => "characters other than letters",
+            LineIssue::Lowercase => "lowercase letters",
+            LineIssue::StrayWhitespace => "leading or trailing whitespace",
+        }
+    }
+}
+
+/// Returns the issues `line` has on its own; duplicates need the whole list.
+pub fn line_issues(line: &str) -> Vec<LineIssue> {
+    let word = line.trim();
+    let len = phrase_letters(word).chars().count();
+    let checks = [
+        (
+            LineIssue::BadLength,
+            !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&len),
+        ),
+        (
+            LineIssue::NotAscii,
+            word.chars()
+                .any(|c| !c.is_ascii() && fold_letter(c).is_none()),
+        ),
+        (
+            LineIssue::NotALetter,
+            word.chars()
+                .any(|c| c.is_ascii() && !c.is_ascii_alphabetic() && !PHRASE_JOINERS.contains(&c)),
+        ),
+        (LineIssue::Lowercase, word.chars().any(char::is_lowercase)),
+        (LineIssue::StrayWhitespace, word.len() != line.len()),
+    ];
+    checks
+        .into_iter()
+        .filter_map(|(issue, found)| found.then_some(issue))
+        .collect()
+}
+
+/// Returns `line` cleaned up into a word the loader can match, or `None` if it
+/// can't be one.
+///
+/// Whitespace is trimmed and letters uppercased, keeping accents and what joins
+/// a phrase's words; lines with anything else, or of the wrong length, are
+/// dropped.
+pub fn normalize_word(line: &str) -> Option<String> {
+    let word = line.trim().to_uppercase();
+    let letters_only = word
+        .chars()
+        .all(|c| fold_letter(c).is_some() || PHRASE_JOINERS.contains(&c));
+    let len = phrase_letters(&word).chars().count();
+    let len_ok = (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&len);
+    (letters_only && len_ok).then_some(word)
+}
+
+/// Issues found in a word list, by the 1-based lines they appear on.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DictionaryReport {
+    pub lines: usize,
+    pub issues: BTreeMap<LineIssue, Vec<usize>>,
+}
+
+impl DictionaryReport {
+    /// Checks every line of `words`.
+    ///
+    /// Finding duplicates hashes every word, so startup checks skip it.
+    pub fn check(words: &str, find_duplicates: bool) -> Self {
+        let mut report = Self::default();
+        let mut seen = HashSet::new();
+
+        // `str::lines` would hide a trailing `\r`
+        let body = words.strip_suffix('\n').unwrap_or(words);
+        let lines = body.split('\n').filter(|_| !words.is_empty());
+        for (i, line) in lines.enumerate() {
+            let line_no = i + 1;
+            report.lines = line_no;
+            let mut issues = line_issues(line);
+            if find_duplicates {
+                if let Some(word) = normalize_word(line) {
+                    if !seen.insert(word) {
+                        issues.push(LineIssue::Duplicate);
+                    }
+                }
+            }
+            for issue in issues {
+                report.issues.entry(issue).or_default().push(line_no);
+            }
+        }
+        report
+    }
+    /// Returns the total number of issues found.
+    pub fn issue_count(&self) -> usize {
+        self.issues.values().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordmap::make_word_map_with_progress;
+    use std::path::PathBuf;
+
+    /// Writes `bytes` to a file of its own under the system's temporary directory.
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("jumble_helper-{name}-{}.txt", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn files_over_the_cap_are_refused_with_their_size() {
+        let path = temp_file("oversized", &b"pound\n".repeat(200_000));
+        let e = read_word_file(&path, 1024 * 1024).unwrap_err();
+        assert!(e.contains("is 1.1 MB; the limit is 1.0 MB"), "{e}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn files_at_the_cap_are_read() {
+        let path = temp_file("at-cap", b"pound\nupon\n");
+        assert_eq!(read_word_file(&path, 11).unwrap(), "pound\nupon\n");
+        assert!(read_word_file(&path, 10).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported_at_its_offset() {
+        let path = temp_file("binary", b"pound\nupon\n\xff\xfe\x00\x01");
+        let e = read_word_file(&path, 1024).unwrap_err();
+        assert!(e.contains("is not UTF-8 text"), "{e}");
+        assert!(e.ends_with("at offset 11"), "{e}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_missing_file_names_itself() {
+        let path = std::env::temp_dir().join("jumble_helper-no-such-words.txt");
+        let e = read_word_file(&path, 1024).unwrap_err();
+        assert!(e.contains("no-such-words.txt"), "{e}");
+    }
+
+    #[test]
+    fn the_word_cap_stops_loading() {
+        let path = temp_file("many", "pound\nupon\nsound\nsnoop\n".as_bytes());
+        let options = DictionaryOptions {
+            path: Some(path.display().to_string()),
+            max_words: 2,
+            ..DictionaryOptions::default()
+        };
+        let words = load_words(&options, Language::En);
+        let mut loaded = 0;
+        let map =
+            make_word_map_with_progress(&words, Alphabet::English, options.max_words, 1, |n| {
+                loaded = n
+            });
+        assert_eq!(loaded, 2);
+        assert!(map.find_match("UPON", 4, 8, &mut Vec::new()).is_some());
+        assert!(map.find_match("SOUND", 4, 8, &mut Vec::new()).is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn an_unreadable_list_falls_back_to_the_embedded_one() {
+        let path = temp_file("fallback", b"\xff");
+        let options = DictionaryOptions {
+            path: Some(path.display().to_string()),
+            ..DictionaryOptions::default()
+        };
+        let words = load_words(&options, Language::En);
+        // The built-in list, with the bonus words when they're installed
+        assert_eq!(words, built_in_words(&DictionaryOptions::default()));
+        assert!(words.starts_with(EMBEDDED_WORDS.trim_end()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn headers_read_every_field_and_leave_the_words() {
+        let text = "#! name=Mom's words\n#!language = FR\n#! min_len=4\n#! max_len=6\n\
+                    #! case=mixed\n#! frequency_column=true\n#! colour=blue\nUpon 12\n";
+        let (header, rest, lines) = parse_header(text).unwrap();
+        assert_eq!(
+            header,
+            DictionaryHeader {
+                name: Some("Mom's words".into()),
+                language: Some("fr".into()),
+                min_len: Some(4),
+                max_len: Some(6),
+                case: Some(WordCase::Mixed),
+                frequency_column: true,
+                alphabet: None,
+            }
+        );
+        assert_eq!((rest, lines), ("Upon 12\n", 7));
+        assert_eq!(header.title().as_deref(), Some("Mom's words (fr)"));
+        // Written back without the unknown key, it reads the same
+        let written = header.to_text();
+        assert_eq!(written.lines().count(), 6);
+        assert_eq!(parse_header(&written).unwrap().0, header);
+    }
+
+    #[test]
+    fn lists_without_a_header_read_as_they_are() {
+        let (header, rest, lines) = parse_header("UPON\n#! name=late\n").unwrap();
+        assert_eq!(header, DictionaryHeader::default());
+        assert_eq!((rest, lines), ("UPON\n#! name=late\n", 0));
+        assert_eq!(header.title(), None);
+        assert_eq!(header.to_text(), "");
+        // A comment that isn't a header line ends the header
+        let (_, rest, lines) = parse_header("#! name=a\n# note\n").unwrap();
+        assert_eq!((rest, lines), ("# note\n", 1));
+    }
+
+    #[test]
+    fn bad_header_lines_are_refused_by_number() {
+        let error = |text: &str| parse_header(text).unwrap_err();
+        assert_eq!(
+            error("#! name=a\n#! max_len=six\n"),
+            "header line 2: max_len must be a whole number, not 'six'"
+        );
+        assert_eq!(
+            error("#! nonsense\n"),
+            "header line 1: expected 'key=value', got 'nonsense'"
+        );
+        assert_eq!(
+            error("#! =upper\n"),
+            "header line 1: expected 'key=value', got '=upper'"
+        );
+        assert!(error("#! case=title\n").starts_with("header line 1: case must be"));
+        assert!(error("#! frequency_column=yes\n").starts_with("header line 1: frequency_"));
+        assert_eq!(
+            error("#! max_len=4\n#! min_len=5\n"),
+            "header line 2: min_len 5 is more than max_len 4"
+        );
+    }
+
+    #[test]
+    fn words_are_prepared_as_the_header_says() {
+        let (_, words) = prepare_words("UPON\nPOUND\n").unwrap();
+        assert!(matches!(words, Cow::Borrowed("UPON\nPOUND\n")));
+        let text = "#! case=lower\n#! frequency_column=true\n#! max_len=4\n\
+                    upon 120\npound 80\nsoup\n\n";
+        let (_, words) = prepare_words(text).unwrap();
+        assert_eq!(words, "UPON\nSOUP\n");
+        let (_, words) = prepare_words("#! min_len=5\nUPON\nPOUND\n").unwrap();
+        assert_eq!(words, "POUND\n");
+    }
+
+    #[test]
+    fn frequencies_are_read_from_the_last_column() {
+        let text = "#! frequency_column=true\nUPON 12\nPOUND 3\nSNOUT\nHOT DOG 30\n";
+        let frequencies = read_frequencies(text);
+        assert_eq!(frequencies.get("UPON"), Some(&12));
+        assert_eq!(frequencies.get("POUND"), Some(&3));
+        assert_eq!(frequencies.get("HOT DOG"), Some(&30));
+        assert_eq!(frequencies.len(), 3);
+        assert!(read_frequencies("UPON 12\n").is_empty());
+    }
+
+    #[test]
+    fn phrases_keep_their_joiners_and_count_only_their_letters() {
+        assert_eq!(normalize_word(" in-law ").as_deref(), Some("IN-LAW"));
+        assert_eq!(normalize_word("hot dog").as_deref(), Some("HOT DOG"));
+        assert_eq!(normalize_word("HOT_DOG"), None);
+        // Nine letters, though the line is eleven characters
+        assert_eq!(
+            normalize_word("JACK-IN-BOX").as_deref(),
+            Some("JACK-IN-BOX")
+        );
+        assert_eq!(normalize_word("JACK-IN-THE-BOX"), None);
+        assert!(line_issues("HOT DOG").is_empty());
+        assert!(line_issues("IN-LAW").is_empty());
+        assert_eq!(line_issues("HOT_DOG"), [LineIssue::NotALetter]);
+
+        let (header, _, _) = parse_header("#! frequency_column=true\n#! min_len=6\n").unwrap();
+        assert_eq!(header.word_of("HOT DOG 30"), "HOT DOG");
+        assert_eq!(header.frequency_of("HOT DOG 30"), Some(30));
+        assert_eq!(header.word_of("HOT DOG"), "HOT DOG");
+        assert_eq!(header.frequency_of("HOT DOG"), None);
+        assert!(header.fits("HOT DOG"));
+        assert!(!header.fits("IN-LAW"));
+    }
+
+    #[test]
+    fn bonus_scrambles_resolve() {
+        let words = built_in_words(&DictionaryOptions::default());
+        let map =
+            make_word_map_with_progress(&words, Alphabet::English, usize::MAX, usize::MAX, |_| ());
+        let mut key_buf = Vec::new();
+        let mut solve = |scramble| {
+            map.find_match(scramble, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf)
+                .map(<[String]>::to_vec)
+        };
+        assert_eq!(solve("NODABANED"), Some(vec!["ABANDONED".to_owned()]));
+        assert_eq!(solve("SHIPMOCCAL"), Some(vec!["ACCOMPLISH".to_owned()]));
+    }
+}
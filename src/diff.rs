@@ -0,0 +1,244 @@
+//! The `diff` command: what a change to a word list does to solving.
+//!
+//! A plain text diff of two lists says which lines changed; this says which
+//! scrambles now solve differently. Groups of anagrams that grew past one word
+//! or shrank to one matter most, since a lone match is shown as the answer.
+
+use crate::cli::{DiffArgs, DiffFormat};
+use crate::dictionary::{prepare_words, read_word_file, DictionaryOptions};
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use nanoserde::SerJson;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// An anagram group whose size differs between the two lists.
+#[derive(Debug, Clone, PartialEq, Eq, SerJson)]
+pub struct GroupChange {
+    /// The sorted letters the group is filed under.
+    pub key: String,
+    pub old: Vec<String>,
+    pub new: Vec<String>,
+}
+
+/// How the anagram groups of one word list differ from another's.
+#[derive(Debug, Default, Clone, PartialEq, Eq, SerJson)]
+pub struct DictionaryDiff {
+    pub words_added: Vec<String>,
+    pub words_removed: Vec<String>,
+    /// Groups of one word that gained more, so no longer give an answer.
+    pub became_ambiguous: Vec<GroupChange>,
+    /// Groups of several words that lost all but one, so now give an answer.
+    pub became_unique: Vec<GroupChange>,
+    /// Other groups that grew or shrank.
+    pub resized: Vec<GroupChange>,
+    /// Keys with words in the old list and none in the new.
+    pub keys_removed: Vec<String>,
+}
+
+impl DictionaryDiff {
+    /// Compares the anagram groups of `old` and `new`. Everything is sorted, and
+    /// a word listed twice counts once.
+    pub fn compare(old: &WordMap, new: &WordMap) -> Self {
+        let (old, new) = (group_sets(old), group_sets(new));
+        let words = |groups: &BTreeMap<&str, BTreeSet<&str>>| -> BTreeSet<String> {
+            groups.values().flatten().map(|w| w.to_string()).collect()
+        };
+        let (old_words, new_words) = (words(&old), words(&new));
+
+        let mut diff = Self {
+            words_added: new_words.difference(&old_words).cloned().collect(),
+            words_removed: old_words.difference(&new_words).cloned().collect(),
+            ..Self::default()
+        };
+        for (key, old_group) in &old {
+            let Some(new_group) = new.get(key) else {
+                diff.keys_removed.push(key.to_string());
+                continue;
+            };
+            if old_group.len() == new_group.len() {
+                continue;
+            }
+            let change = GroupChange {
+                key: key.to_string(),
+                old: old_group.iter().map(|w| w.to_string()).collect(),
+                new: new_group.iter().map(|w| w.to_string()).collect(),
+            };
+            match (old_group.len(), new_group.len()) {
+                (1, _) => diff.became_ambiguous.push(change),
+                (_, 1) => diff.became_unique.push(change),
+                _ => diff.resized.push(change),
+            }
+        }
+        diff
+    }
+    /// Returns whether the lists hold the same words.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+    /// Returns the differences as text, one section per kind of change.
+    pub fn describe(&self) -> String {
+        let mut text = String::new();
+        let mut list = |title: &str, items: Vec<String>| {
+            text.push_str(&format!("{title}: {}\n", items.len()));
+            for item in items {
+                text.push_str(&format!("  {item}\n"));
+            }
+        };
+        let change =
+            |c: &GroupChange| format!("{}: {} -> {}", c.key, c.old.join(" "), c.new.join(" "));
+        list("words added", self.words_added.clone());
+        list("words removed", self.words_removed.clone());
+        list(
+            "groups now ambiguous",
+            self.became_ambiguous.iter().map(change).collect(),
+        );
+        list(
+            "groups now unique",
+            self.became_unique.iter().map(change).collect(),
+        );
+        list("groups resized", self.resized.iter().map(change).collect());
+        list("keys removed", self.keys_removed.clone());
+        text
+    }
+}
+
+/// Returns each key's words, deduplicated, with both in order.
+fn group_sets(map: &WordMap) -> BTreeMap<&str, BTreeSet<&str>> {
+    map.groups()
+        .map(|(key, words)| (key, words.iter().map(String::as_str).collect()))
+        .collect()
+}
+
+/// Compares the word lists named in `args`, printing the differences, and
+/// returns the exit code: 0 if they hold the same words, 1 if not, 2 if either
+/// can't be read.
+pub fn run_diff(args: &DiffArgs, options: &DictionaryOptions) -> i32 {
+    let load = |path: &str| {
+        let text = read_word_file(Path::new(path), options.max_file_bytes())?;
+        let (header, words) = prepare_words(&text).map_err(|e| format!("'{path}': {e}"))?;
+        Ok::<_, String>(make_word_map_with_progress(
+            &words,
+            options.alphabet_for(&header),
+            options.max_words,
+            usize::MAX,
+            |_| (),
+        ))
+    };
+    let (old, new) = match (load(&args.old), load(&args.new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("[run_diff] {e}");
+            return 2;
+        }
+    };
+
+    let diff = DictionaryDiff::compare(&old, &new);
+    match args.format {
+        DiffFormat::Text => print!("'{}' -> '{}'\n{}", args.old, args.new, diff.describe()),
+        DiffFormat::Json => println!("{}", diff.serialize_json()),
+    }
+    match diff.is_empty() {
+        true => 0,
+        false => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+
+    /// Between them, the lists make one change of every kind: RAGE gains GEAR,
+    /// STOP loses its anagrams, TALE's group shrinks by one, and LIME goes.
+    /// QUACK is listed twice in the old list and once in the new.
+    const OLD: &str = "RAGE\nSTOP\nPOTS\nTOPS\nTALE\nLATE\nTEAL\nLIME\nQUACK\nQUACK\n";
+    const NEW: &str = "RAGE\nGEAR\nSTOP\nTALE\nLATE\nQUACK\n";
+
+    fn compare(old: &str, new: &str) -> DictionaryDiff {
+        let map = |words| {
+            make_word_map_with_progress(words, Alphabet::English, usize::MAX, usize::MAX, |_| ())
+        };
+        DictionaryDiff::compare(&map(old), &map(new))
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn every_kind_of_change_is_sorted_into_its_place() {
+        let diff = compare(OLD, NEW);
+        assert_eq!(diff.words_added, words(&["GEAR"]));
+        assert_eq!(diff.words_removed, words(&["LIME", "POTS", "TEAL", "TOPS"]));
+        assert_eq!(
+            diff.became_ambiguous,
+            [GroupChange {
+                key: "AEGR".into(),
+                old: words(&["RAGE"]),
+                new: words(&["GEAR", "RAGE"]),
+            }]
+        );
+        assert_eq!(
+            diff.became_unique,
+            [GroupChange {
+                key: "OPST".into(),
+                old: words(&["POTS", "STOP", "TOPS"]),
+                new: words(&["STOP"]),
+            }]
+        );
+        assert_eq!(
+            diff.resized,
+            [GroupChange {
+                key: "AELT".into(),
+                old: words(&["LATE", "TALE", "TEAL"]),
+                new: words(&["LATE", "TALE"]),
+            }]
+        );
+        assert_eq!(diff.keys_removed, words(&["EILM"]));
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn duplicates_and_order_make_no_difference() {
+        let diff = compare("QUACK\nTALE\nQUACK\nLATE\n", "LATE\nTALE\nQUACK\n");
+        assert!(diff.is_empty(), "{diff:?}");
+        assert_eq!(
+            diff.describe(),
+            "words added: 0\nwords removed: 0\ngroups now ambiguous: 0\n\
+             groups now unique: 0\ngroups resized: 0\nkeys removed: 0\n"
+        );
+    }
+
+    #[test]
+    fn describes_each_section_with_its_changes() {
+        let text = compare(OLD, NEW).describe();
+        assert!(text.starts_with("words added: 1\n  GEAR\nwords removed: 4\n"));
+        assert!(text.contains("groups now ambiguous: 1\n  AEGR: RAGE -> GEAR RAGE\n"));
+        assert!(text.contains("groups now unique: 1\n  OPST: POTS STOP TOPS -> STOP\n"));
+        assert!(text.ends_with("keys removed: 1\n  EILM\n"));
+    }
+
+    #[test]
+    fn exit_codes_say_whether_the_lists_differ() {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-diff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, words: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, words).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let (old, new) = (write("old.txt", OLD), write("new.txt", NEW));
+        let run = |old: &str, new: &str| {
+            let args = DiffArgs {
+                old: old.into(),
+                new: new.into(),
+                format: DiffFormat::Json,
+            };
+            run_diff(&args, &DictionaryOptions::default())
+        };
+        assert_eq!(run(&old, &old), 0);
+        assert_eq!(run(&old, &new), 1);
+        assert_eq!(run(&old, &dir.join("missing.txt").to_string_lossy()), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,377 @@
+//! A summary of the answers found over a range of days, for sending on.
+//!
+//! `jumble_helper digest --since 7d --out digest.txt` lists the history's
+//! answers in the range day by day, each scramble with its answer, and ends
+//! with the totals and the longest answer; `--format html` writes it as a web
+//! page instead, for pasting into an email. The history keeps the day each
+//! answer was found on, not the time, so the day is as precise as it gets.
+
+use crate::date::{format_day, parse_day};
+use crate::history::{History, Solve};
+use std::fmt::Write;
+
+/// The range `digest` covers when `--since` isn't given.
+pub const DEFAULT_SINCE: DayRange = DayRange::Last(7);
+
+/// Names of the days of the week, from Monday.
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Days to summarize, as given to `--since`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub enum DayRange {
+    /// The last this many days, up to and including today: `7d`.
+    Last(u32),
+    /// From a day through today: `2024-03-01`.
+    Since(i64),
+    /// From the first day through the last: `2024-03-01..2024-03-08`.
+    Between(i64, i64),
+}
+
+impl DayRange {
+    /// Returns the first and last day of the range, taking it to be `today`.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn days(self, today: i64) -> (i64, i64) {
+        match self {
+            Self::Last(n) => (today - i64::from(n) + 1, today),
+            Self::Since(first) => (first, today),
+            Self::Between(first, last) => (first, last),
+        }
+    }
+}
+
+/// Parses a range of days as `--since` takes it: a number of days such as
+/// `7d`, a day to start from, or two days joined by `..`.
+pub fn parse_range(text: &str) -> Result<DayRange, String> {
+    let day =
+        |text: &str| parse_day(text).ok_or_else(|| format!("'{text}' isn't a date as YYYY-MM-DD"));
+    if let Some((first, last)) = text.split_once("..") {
+        let (first, last) = (day(first)?, day(last)?);
+        if first > last {
+            return Err(format!("'{text}' ends before it starts"));
+        }
+        return Ok(DayRange::Between(first, last));
+    }
+    if let Some(count) = text.strip_suffix('d') {
+        return match count.parse::<u32>() {
+            Ok(n) if n > 0 => Ok(DayRange::Last(n)),
+            _ => Err(format!("'{text}' isn't a number of days such as 7d")),
+        };
+    }
+    day(text).map(DayRange::Since)
+}
+
+/// Returns the name of the day of the week `day` falls on.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn weekday(day: i64) -> &'static str {
+    // 1970-01-01 was a Thursday
+    WEEKDAYS[(day + 3).rem_euclid(7) as usize]
+}
+
+/// The answers found over a range of days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct Digest<'a> {
+    pub first: i64,
+    pub last: i64,
+    /// Each day with an answer found, in order, with its answers in the order
+    /// they were found.
+    pub days: Vec<(i64, Vec<&'a Solve>)>,
+    pub total: usize,
+    /// The answer with the most letters, the earliest of equally long ones.
+    pub longest: Option<&'a Solve>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl<'a> Digest<'a> {
+    /// Gathers the answers in `history` found from day `first` through `last`.
+    pub fn new(history: &'a History, first: i64, last: i64) -> Self {
+        let mut solves: Vec<&Solve> = history
+            .solves
+            .iter()
+            .filter(|s| (first..=last).contains(&s.day))
+            .collect();
+        solves.sort_by_key(|s| s.day);
+        let mut days: Vec<(i64, Vec<&Solve>)> = Vec::new();
+        let mut longest: Option<&Solve> = None;
+        for &solve in &solves {
+            match days.last_mut() {
+                Some((day, day_solves)) if *day == solve.day => day_solves.push(solve),
+                _ => days.push((solve.day, vec![solve])),
+            }
+            let len = solve.answer.chars().count();
+            if longest.is_none_or(|l| len > l.answer.chars().count()) {
+                longest = Some(solve);
+            }
+        }
+        Self {
+            first,
+            last,
+            days,
+            total: solves.len(),
+            longest,
+        }
+    }
+    /// Returns what the totals line says.
+    fn totals(&self) -> String {
+        let plural = |n: usize, word: &str| match n {
+            1 => format!("1 {word}"),
+            n => format!("{n} {word}s"),
+        };
+        format!(
+            "{} found on {}",
+            plural(self.total, "answer"),
+            plural(self.days.len(), "day")
+        )
+    }
+    /// Returns what the longest answer's line says, if there is one.
+    fn longest_line(&self) -> Option<String> {
+        self.longest.map(|solve| {
+            let len = solve.answer.chars().count();
+            format!("Longest: {} ({len} letters)", solve.answer)
+        })
+    }
+    /// Returns the heading naming the range.
+    fn title(&self) -> String {
+        format!(
+            "Jumble answers, {} to {}",
+            format_day(self.first),
+            format_day(self.last)
+        )
+    }
+    /// Renders the digest as plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{}\n", self.title());
+        for (day, solves) in &self.days {
+            let _ = writeln!(out, "\n{} {}", weekday(*day), format_day(*day));
+            for solve in solves {
+                let _ = writeln!(out, "  {} -> {}", solve.scramble, solve.answer);
+            }
+        }
+        let _ = writeln!(out, "\n{}", self.totals());
+        if let Some(line) = self.longest_line() {
+            let _ = writeln!(out, "{line}");
+        }
+        out
+    }
+    /// Renders the digest as a web page.
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for (day, solves) in &self.days {
+            let _ = writeln!(out, "<h2>{} {}</h2>\n<ul>", weekday(*day), format_day(*day));
+            for solve in solves {
+                let (scramble, answer) = (escape_html(&solve.scramble), escape_html(&solve.answer));
+                let _ = writeln!(out, "<li>{scramble} &rarr; <strong>{answer}</strong></li>");
+            }
+            out.push_str("</ul>\n");
+        }
+        let _ = writeln!(out, "<p>{}</p>", self.totals());
+        if let Some(line) = self.longest_line() {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(&line));
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+/// Escapes the characters HTML gives a meaning.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the digest `args` asks for from the history in the user data
+/// directory, returning the exit code: 0, or 2 if the history can't be read or
+/// the digest can't be written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_digest(args: &crate::cli::DigestArgs) -> i32 {
+    use crate::cli::DigestFormat;
+    use crate::history::load_history;
+    use crate::storage::{default_storage, safe_write};
+
+    let history = match load_history(&*default_storage()) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("[run_digest] can't read the history: {e}");
+            return 2;
+        }
+    };
+    let Some(today) = crate::date::today() else {
+        eprintln!("[run_digest] can't tell today's date");
+        return 2;
+    };
+    let (first, last) = args.since.days(today);
+    let digest = Digest::new(&history, first, last);
+    let text = match args.format {
+        DigestFormat::Text => digest.to_text(),
+        DigestFormat::Html => digest.to_html(),
+    };
+    match &args.out {
+        Some(path) => match safe_write(std::path::Path::new(path), text.as_bytes()) {
+            Ok(()) => {
+                println!("wrote the digest to '{path}'");
+                0
+            }
+            Err(e) => {
+                eprintln!("[run_digest] can't write '{path}': {e}");
+                2
+            }
+        },
+        None => {
+            print!("{text}");
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(text: &str) -> i64 {
+        parse_day(text).unwrap()
+    }
+
+    fn history() -> History {
+        let solve = |date: &str, scramble: &str, answer: &str| Solve {
+            day: day(date),
+            scramble: scramble.to_owned(),
+            answer: answer.to_owned(),
+        };
+        History {
+            solves: vec![
+                solve("2024-02-28", "NUPO", "UPON"),
+                solve("2024-03-01", "TAGER", "GREAT"),
+                solve("2024-03-01", "DUNOP", "POUND"),
+                solve("2024-03-03", "SDUNO", "SOUND"),
+                solve("2024-03-09", "TAGERS", "GRATES"),
+            ],
+        }
+    }
+
+    #[test]
+    fn ranges_are_counts_of_days_a_start_or_both_ends() {
+        assert_eq!(parse_range("7d"), Ok(DayRange::Last(7)));
+        assert_eq!(
+            parse_range("2024-03-01"),
+            Ok(DayRange::Since(day("2024-03-01")))
+        );
+        assert_eq!(
+            parse_range("2024-03-01..2024-03-08"),
+            Ok(DayRange::Between(day("2024-03-01"), day("2024-03-08")))
+        );
+        for bad in [
+            "0d",
+            "-2d",
+            "d",
+            "week",
+            "2024-03-08..2024-03-01",
+            "2024-03-01..",
+            "..",
+        ] {
+            assert!(parse_range(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn ranges_end_today_unless_given_an_end() {
+        let today = day("2024-03-08");
+        assert_eq!(DayRange::Last(7).days(today), (day("2024-03-02"), today));
+        assert_eq!(DayRange::Last(1).days(today), (today, today));
+        let first = day("2024-03-01");
+        assert_eq!(DayRange::Since(first).days(today), (first, today));
+        assert_eq!(DayRange::Between(first, first).days(today), (first, first));
+    }
+
+    #[test]
+    fn the_text_digest_lists_each_day_in_range() {
+        let history = history();
+        let digest = Digest::new(&history, day("2024-03-01"), day("2024-03-08"));
+        assert_eq!(
+            digest.to_text(),
+            "\
+Jumble answers, 2024-03-01 to 2024-03-08
+
+Friday 2024-03-01
+  TAGER -> GREAT
+  DUNOP -> POUND
+
+Sunday 2024-03-03
+  SDUNO -> SOUND
+
+3 answers found on 2 days
+Longest: GREAT (5 letters)
+"
+        );
+    }
+
+    #[test]
+    fn the_html_digest_is_a_page_with_the_same_lines() {
+        let history = history();
+        let digest = Digest::new(&history, day("2024-03-03"), day("2024-03-03"));
+        assert_eq!(
+            digest.to_html(),
+            "\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Jumble answers, 2024-03-03 to 2024-03-03</title>
+</head>
+<body>
+<h1>Jumble answers, 2024-03-03 to 2024-03-03</h1>
+<h2>Sunday 2024-03-03</h2>
+<ul>
+<li>SDUNO &rarr; <strong>SOUND</strong></li>
+</ul>
+<p>1 answer found on 1 day</p>
+<p>Longest: SOUND (5 letters)</p>
+</body>
+</html>
+"
+        );
+    }
+
+    #[test]
+    fn html_special_characters_are_escaped() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        let history = History {
+            solves: vec![Solve {
+                day: 0,
+                scramble: "A<B".to_owned(),
+                answer: "R&D".to_owned(),
+            }],
+        };
+        let html = Digest::new(&history, 0, 0).to_html();
+        assert!(
+            html.contains("<li>A&lt;B &rarr; <strong>R&amp;D</strong></li>"),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn an_empty_range_has_no_longest_answer() {
+        let history = history();
+        let digest = Digest::new(&history, day("2024-03-04"), day("2024-03-08"));
+        assert_eq!((digest.total, digest.longest), (0, None));
+        assert!(digest.to_text().ends_with("\n0 answers found on 0 days\n"));
+    }
+}
@@ -0,0 +1,167 @@
+//! The anagram solver on its own, for embedding in other programs.
+//!
+//! This module is the library's stable API: what it exposes changes only with
+//! a new major version, while the rest of the crate is the app's own and may
+//! change in any release. Nothing here involves the window or its types, an
+//! `Engine` is built entirely from a word list held in memory, and every type
+//! can be sent and shared between threads, so a GUI can keep one engine behind
+//! an `Arc` and solve from wherever it likes. `examples/embed.rs` shows it in
+//! use from a plain `fn main`, and `tests/engine_api.rs` pins the signatures.
+
+use crate::alphabet::Alphabet;
+use crate::dictionary::{prepare_words, read_frequencies};
+use crate::rank::{rank, scrabble_points, RankWeights};
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+
+/// Most words loaded when the spec gives no limit.
+const DEFAULT_MAX_WORDS: usize = 1_000_000;
+
+/// Where an `Engine` gets its words.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DictionarySpec {
+    /// The word list: a word per line, optionally after a `#!` header as the
+    /// app's own lists have.
+    pub words: String,
+    /// The code of the letters the words are spelled with, `en` or `es`, for a
+    /// list whose header doesn't say; `en` if neither does.
+    pub alphabet: Option<String>,
+    /// Words past this many are ignored; a million if `None`.
+    pub max_words: Option<usize>,
+}
+
+impl DictionarySpec {
+    /// Creates a spec for the word list `words`, with its header's settings.
+    pub fn new(words: impl Into<String>) -> Self {
+        Self {
+            words: words.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Letters to solve for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// The scrambled letters, in any case; accents fold as the word list's
+    /// alphabet says.
+    pub letters: String,
+    /// Fewest letters a match may have.
+    pub min_len: usize,
+    /// Most letters a match may have.
+    pub max_len: usize,
+    /// Whether to find the words spelled from some of the letters, rather than
+    /// only those using them all. This scans every word.
+    pub sub_anagrams: bool,
+}
+
+impl Query {
+    /// Creates a query for the words using all of `letters`, within the app's
+    /// word lengths.
+    pub fn new(letters: impl Into<String>) -> Self {
+        Self {
+            letters: letters.into(),
+            min_len: MIN_WORD_LENGTH,
+            max_len: MAX_WORD_LENGTH,
+            sub_anagrams: false,
+        }
+    }
+}
+
+/// A word found for a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    /// The word, as listed.
+    pub word: String,
+    /// How well it ranks: higher is likelier to be the answer.
+    pub score: f32,
+    /// How common the word list says it is, if it says.
+    pub frequency: Option<u64>,
+    /// Its Scrabble points.
+    pub scrabble: u32,
+}
+
+/// The words found for a query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Solution {
+    /// The letters solved for, as folded for the word list.
+    pub letters: String,
+    /// The matches, best ranked first.
+    pub matches: Vec<Match>,
+}
+
+impl Solution {
+    /// Returns the answer, if exactly one word matched.
+    pub fn answer(&self) -> Option<&str> {
+        match self.matches.as_slice() {
+            [only] => Some(&only.word),
+            _ => None,
+        }
+    }
+}
+
+/// A word list ready to solve scrambles from.
+pub struct Engine {
+    map: WordMap,
+    weights: RankWeights,
+}
+
+impl Engine {
+    /// Loads the word list `spec` describes, or returns why it can't: a
+    /// malformed header, or an unknown alphabet.
+    pub fn new(spec: DictionarySpec) -> Result<Self, String> {
+        let fallback = match spec.alphabet.as_deref() {
+            Some(code) => {
+                Some(Alphabet::parse(code).ok_or_else(|| format!("unknown alphabet '{code}'"))?)
+            }
+            None => None,
+        };
+        let (header, words) = prepare_words(&spec.words)?;
+        let alphabet = header.alphabet.or(fallback).unwrap_or_default();
+        let max_words = spec.max_words.unwrap_or(DEFAULT_MAX_WORDS);
+        let mut map = make_word_map_with_progress(&words, alphabet, max_words, usize::MAX, |_| ());
+        map.set_header(header);
+        map.set_frequencies(read_frequencies(&spec.words));
+        Ok(Self {
+            map,
+            weights: RankWeights::default(),
+        })
+    }
+    /// Returns how many words were loaded.
+    pub fn word_count(&self) -> usize {
+        self.map.stats().words
+    }
+    /// Finds and ranks the words `query` asks for.
+    pub fn solve(&self, query: Query) -> Solution {
+        let alphabet = self.map.alphabet();
+        let letters = alphabet.fold_word(&query.letters).into_owned();
+        let words = match query.sub_anagrams {
+            true => {
+                let mut words = self.map.sub_anagrams(&letters, query.min_len);
+                words.retain(|word| word.chars().count() <= query.max_len);
+                words
+            }
+            false => {
+                let mut key_buf = Vec::new();
+                self.map
+                    .find_match(&letters, query.min_len, query.max_len, &mut key_buf)
+                    .unwrap_or_default()
+                    .to_vec()
+            }
+        };
+        let ranked = rank(&words, &self.map, |_| false, |_| false, &self.weights);
+        let matches = ranked
+            .into_iter()
+            .map(|ranked| {
+                let word = &words[ranked.index];
+                Match {
+                    word: word.clone(),
+                    score: ranked.score.total(),
+                    frequency: self.map.frequency(word),
+                    scrabble: scrabble_points(word),
+                }
+            })
+            .collect();
+        Solution { letters, matches }
+    }
+}
@@ -0,0 +1,406 @@
+//! The letters typed so far, and the rules for what may be typed.
+//!
+//! Every way of changing the entry (keys, taps, restoring a saved entry) goes
+//! through `EntryBuffer`, so it only ever holds up to `max_len` letters of its
+//! alphabet (see `alphabet`), or else a query command (see `query::lang`) of up
+//! to `MAX_COMMAND_LENGTH` characters.
+
+use crate::alphabet::Alphabet;
+use crate::query::lang::{is_command, is_command_char, COMMAND_PREFIX, MAX_COMMAND_LENGTH};
+use std::f32::consts::TAU;
+use std::fmt;
+
+/// How long the entry shakes when a letter doesn't fit, in seconds.
+pub const SHAKE_TIME: f64 = 0.15;
+/// Times the entry swings each way during a shake.
+const SHAKE_SWINGS: f32 = 3.0;
+
+/// Why an edit was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The character isn't a letter of the alphabet (in either case, accents
+    /// allowed), or one a query command may have.
+    NotALetter(char),
+    /// There was nothing to delete.
+    Empty,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RejectReason::NotALetter(c) => write!(f, "'{c}' is not a letter"),
+            RejectReason::Empty => write!(f, "the entry is empty"),
+        }
+    }
+}
+
+/// What an edit did, for the UI to turn into feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryEffect {
+    /// The entry changed.
+    Accepted,
+    /// The entry is unchanged.
+    Rejected { reason: RejectReason },
+    /// The entry is full; letters past the cap were dropped.
+    AtCapacity,
+}
+
+impl EntryEffect {
+    /// Returns whether the entry's text may have changed.
+    pub fn changed(self) -> bool {
+        // A paste that overflows still replaces the entry
+        !matches!(self, EntryEffect::Rejected { .. })
+    }
+}
+
+/// Uppercase letter entry with a caret and a length cap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryBuffer {
+    text: String,
+    /// Byte index where the next letter goes; always on a letter boundary.
+    caret: usize,
+    max_len: usize,
+    /// The letters that may be typed, and how typed characters map to them.
+    alphabet: Alphabet,
+}
+
+impl EntryBuffer {
+    /// Creates an empty entry holding at most `max_len` letters.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            text: String::with_capacity(max_len),
+            caret: 0,
+            max_len,
+            alphabet: Alphabet::default(),
+        }
+    }
+    /// Returns the entry's letters.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+    /// Returns the number of letters entered.
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+    /// Returns whether no letters are entered.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+    /// Returns the most letters the entry holds.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+    /// Holds at most `max_len` letters from now on, dropping any past it.
+    ///
+    /// A query command keeps its own cap and is left as it is.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+        if self.is_command() {
+            return;
+        }
+        if let Some((end, _)) = self.text.char_indices().nth(max_len) {
+            self.text.truncate(end);
+            self.caret = self.caret.min(end);
+        }
+    }
+    /// Takes letters of `alphabet` from now on; letters already entered stay.
+    pub fn set_alphabet(&mut self, alphabet: Alphabet) {
+        self.alphabet = alphabet;
+    }
+    /// Returns the letters entered out of the most allowed, e.g. `5/8`, or `None`
+    /// for a query command.
+    pub fn counter(&self) -> Option<String> {
+        (!self.is_command()).then(|| format!("{}/{}", self.len(), self.max_len))
+    }
+    /// Returns whether the entry holds a query command rather than letters.
+    pub fn is_command(&self) -> bool {
+        is_command(&self.text)
+    }
+    /// Returns the most characters the entry may hold as it stands.
+    fn cap(&self) -> usize {
+        match self.is_command() {
+            true => MAX_COMMAND_LENGTH,
+            false => self.max_len,
+        }
+    }
+    /// Inserts `c`, uppercased and without accents (but for the alphabet's own
+    /// accented letters), at the caret.
+    ///
+    /// `COMMAND_PREFIX` starts a query command in an empty entry, which then
+    /// takes the other characters commands use too. A character that makes a
+    /// letter with the one before it (see `Alphabet::combine`) replaces that one.
+    pub fn push_char(&mut self, c: char) -> EntryEffect {
+        let before = self.text[..self.caret].chars().next_back();
+        if let Some(letter) = before.and_then(|before| self.alphabet.combine(before, c)) {
+            self.delete_back();
+            return self.push_char(letter);
+        }
+        let starts_command = c == COMMAND_PREFIX && self.text.is_empty();
+        let c = match self.alphabet.fold(c) {
+            Some(letter) => letter,
+            None if starts_command || (self.is_command() && is_command_char(c)) => c,
+            None => {
+                let reason = RejectReason::NotALetter(c);
+                return EntryEffect::Rejected { reason };
+            }
+        };
+        if self.len() >= self.cap() {
+            return EntryEffect::AtCapacity;
+        }
+        self.text.insert(self.caret, c);
+        self.caret += c.len_utf8();
+        EntryEffect::Accepted
+    }
+    /// Removes the letter before the caret.
+    pub fn delete_back(&mut self) -> EntryEffect {
+        if self.caret == 0 {
+            let reason = RejectReason::Empty;
+            return EntryEffect::Rejected { reason };
+        }
+        let before = self.text[..self.caret]
+            .chars()
+            .next_back()
+            .map_or(1, char::len_utf8);
+        self.caret -= before;
+        self.text.remove(self.caret);
+        EntryEffect::Accepted
+    }
+    /// Removes every letter.
+    pub fn clear(&mut self) -> EntryEffect {
+        if self.text.is_empty() {
+            let reason = RejectReason::Empty;
+            return EntryEffect::Rejected { reason };
+        }
+        self.text.clear();
+        self.caret = 0;
+        EntryEffect::Accepted
+    }
+    /// Replaces the entry with the letters of `pasted`, ignoring whitespace, or
+    /// with `pasted` as it is if it's a query command.
+    ///
+    /// Anything else that isn't a letter rejects the whole paste; letters past the
+    /// cap are dropped.
+    pub fn set_from_paste(&mut self, pasted: &str) -> EntryEffect {
+        if is_command(pasted.trim_start()) {
+            return self.set_command(pasted.trim());
+        }
+        let alphabet = self.alphabet;
+        let mut letters = pasted.chars().filter(|c| !c.is_whitespace());
+        if let Some(c) = letters.clone().find(|&c| alphabet.fold(c).is_none()) {
+            let reason = RejectReason::NotALetter(c);
+            return EntryEffect::Rejected { reason };
+        }
+
+        self.text.clear();
+        self.text.extend(
+            letters
+                .by_ref()
+                .take(self.max_len)
+                .filter_map(|c| alphabet.fold(c)),
+        );
+        self.caret = self.text.len();
+        match letters.next() {
+            Some(_) => EntryEffect::AtCapacity,
+            None => EntryEffect::Accepted,
+        }
+    }
+    /// Replaces the entry with the query command `command`, as `set_from_paste`
+    /// does letters.
+    fn set_command(&mut self, command: &str) -> EntryEffect {
+        if let Some(c) = command.chars().skip(1).find(|&c| !is_command_char(c)) {
+            let reason = RejectReason::NotALetter(c);
+            return EntryEffect::Rejected { reason };
+        }
+        let alphabet = self.alphabet;
+        let mut chars = command.chars().map(|c| alphabet.fold(c).unwrap_or(c));
+        self.text.clear();
+        self.text.extend(chars.by_ref().take(MAX_COMMAND_LENGTH));
+        self.caret = self.text.len();
+        match chars.next() {
+            Some(_) => EntryEffect::AtCapacity,
+            None => EntryEffect::Accepted,
+        }
+    }
+}
+
+/// The combined effect of a frame's edits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditSummary {
+    pub changed: bool,
+    pub at_capacity: bool,
+}
+
+impl EditSummary {
+    /// Adds `effect` to the summary.
+    pub fn record(&mut self, effect: EntryEffect) {
+        self.changed |= effect.changed();
+        self.at_capacity |= effect == EntryEffect::AtCapacity;
+    }
+}
+
+/// Returns how far the entry sits from its place, as a fraction of the widest
+/// swing, `progress` (0.0 to 1.0) of the way through a shake.
+///
+/// The entry swings back and forth `SHAKE_SWINGS` times, settling as it goes, and
+/// ends where it started.
+pub fn shake_offset(progress: f32) -> f32 {
+    let progress = progress.clamp(0.0, 1.0);
+    (progress * SHAKE_SWINGS * TAU).sin() * (1.0 - progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a buffer holding `letters`, capped at `max_len`.
+    fn buffer(letters: &str, max_len: usize) -> EntryBuffer {
+        let mut entry = EntryBuffer::new(max_len);
+        for c in letters.chars() {
+            assert_eq!(entry.push_char(c), EntryEffect::Accepted);
+        }
+        entry
+    }
+
+    #[test]
+    fn letters_are_accepted_uppercased() {
+        let entry = buffer("pOu", 8);
+        assert_eq!(entry.as_str(), "POU");
+        assert_eq!(entry.len(), 3);
+    }
+
+    #[test]
+    fn other_characters_are_rejected() {
+        let mut entry = buffer("PO", 8);
+        for c in ['1', ' ', '-', 'Ω', '€'] {
+            let reason = RejectReason::NotALetter(c);
+            assert_eq!(entry.push_char(c), EntryEffect::Rejected { reason });
+        }
+        assert_eq!(entry.as_str(), "PO");
+    }
+
+    #[test]
+    fn accented_letters_lose_their_accents() {
+        let mut entry = buffer("", 8);
+        for c in ['é', 'Ñ', 'ü', 'Ç'] {
+            assert_eq!(entry.push_char(c), EntryEffect::Accepted);
+        }
+        assert_eq!(entry.as_str(), "ENUC");
+        assert_eq!(entry.set_from_paste("élève"), EntryEffect::Accepted);
+        assert_eq!(entry.as_str(), "ELEVE");
+    }
+
+    #[test]
+    fn a_full_entry_is_at_capacity() {
+        let mut entry = buffer("POUND", 5);
+        assert_eq!(entry.push_char('S'), EntryEffect::AtCapacity);
+        assert_eq!(entry.as_str(), "POUND");
+        // A non-letter is still refused for being one
+        assert!(matches!(entry.push_char('1'), EntryEffect::Rejected { .. }));
+    }
+
+    #[test]
+    fn deleting_takes_the_last_letter_until_empty() {
+        let mut entry = buffer("UP", 8);
+        assert_eq!(entry.delete_back(), EntryEffect::Accepted);
+        assert_eq!(entry.delete_back(), EntryEffect::Accepted);
+        assert!(entry.is_empty());
+        let reason = RejectReason::Empty;
+        assert_eq!(entry.delete_back(), EntryEffect::Rejected { reason });
+    }
+
+    #[test]
+    fn clearing_an_empty_entry_is_rejected() {
+        let mut entry = buffer("UP", 8);
+        assert_eq!(entry.clear(), EntryEffect::Accepted);
+        assert!(entry.is_empty());
+        let reason = RejectReason::Empty;
+        assert_eq!(entry.clear(), EntryEffect::Rejected { reason });
+        // Typing resumes at the start
+        entry.push_char('a');
+        assert_eq!(entry.as_str(), "A");
+    }
+
+    #[test]
+    fn pastes_replace_the_entry_ignoring_whitespace() {
+        let mut entry = buffer("XY", 8);
+        assert_eq!(entry.set_from_paste(" n u\tdop\n"), EntryEffect::Accepted);
+        assert_eq!(entry.as_str(), "NUDOP");
+        // The caret follows the paste
+        entry.push_char('s');
+        assert_eq!(entry.as_str(), "NUDOPS");
+    }
+
+    #[test]
+    fn an_overflowing_paste_keeps_what_fits() {
+        let mut entry = buffer("", 4);
+        assert_eq!(entry.set_from_paste("abcdefg"), EntryEffect::AtCapacity);
+        assert_eq!(entry.as_str(), "ABCD");
+        assert!(EntryEffect::AtCapacity.changed());
+    }
+
+    #[test]
+    fn a_paste_with_a_non_letter_changes_nothing() {
+        let mut entry = buffer("UP", 8);
+        let reason = RejectReason::NotALetter('3');
+        assert_eq!(
+            entry.set_from_paste("ab3d"),
+            EntryEffect::Rejected { reason }
+        );
+        assert_eq!(entry.as_str(), "UP");
+        assert!(!EntryEffect::Rejected { reason }.changed());
+    }
+
+    #[test]
+    fn a_summary_combines_a_frames_edits() {
+        let mut summary = EditSummary::default();
+        summary.record(EntryEffect::Rejected {
+            reason: RejectReason::Empty,
+        });
+        assert_eq!(summary, EditSummary::default());
+        summary.record(EntryEffect::Accepted);
+        summary.record(EntryEffect::AtCapacity);
+        assert_eq!(
+            summary,
+            EditSummary {
+                changed: true,
+                at_capacity: true
+            }
+        );
+    }
+
+    #[test]
+    fn reasons_read_as_sentences() {
+        assert_eq!(
+            RejectReason::NotALetter('3').to_string(),
+            "'3' is not a letter"
+        );
+        assert_eq!(RejectReason::Empty.to_string(), "the entry is empty");
+    }
+
+    #[test]
+    fn the_counter_shows_letters_out_of_the_most() {
+        let mut entry = buffer("POUND", 8);
+        assert_eq!(entry.counter().as_deref(), Some("5/8"));
+        entry.set_max_len(4);
+        assert_eq!(entry.as_str(), "POUN");
+        assert_eq!(entry.counter().as_deref(), Some("4/4"));
+        assert_eq!(entry.push_char('D'), EntryEffect::AtCapacity);
+        assert_eq!(entry.set_from_paste(":len 5"), EntryEffect::Accepted);
+        assert_eq!(entry.counter(), None);
+        // A query command is longer than the letters allow, and kept whole
+        entry.set_max_len(3);
+        assert_eq!(entry.as_str(), ":LEN 5");
+    }
+
+    #[test]
+    fn a_shake_swings_both_ways_and_settles_where_it_began() {
+        assert_eq!(shake_offset(0.0), 0.0);
+        assert!(shake_offset(1.0).abs() < 1e-6);
+        assert!(shake_offset(2.0).abs() < 1e-6);
+        let swings: Vec<f32> = (0..=100).map(|i| shake_offset(i as f32 / 100.0)).collect();
+        assert!(swings.iter().any(|&x| x > 0.5) && swings.iter().any(|&x| x < -0.5));
+        for (i, x) in swings.iter().enumerate() {
+            assert!(x.abs() <= 1.0 - i as f32 / 100.0 + 1e-6, "{x} at {i}%");
+        }
+    }
+}
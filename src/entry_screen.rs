@@ -0,0 +1,2132 @@
+//! The word entry screen: type a jumble, see what it unscrambles to.
+
+use crate::animation::Animation;
+use crate::announce::{announcement, Announcer};
+use crate::app::AppContext;
+use crate::assist::{draw_countdown, MAX_ASSIST_SECONDS, MIN_ASSIST_SECONDS};
+use crate::attract::{AttractScreen, IdleTimer, MIN_ATTRACT_SECONDS};
+use crate::big_mode::draw_big_mode;
+use crate::complete::{missing_letters, Completions, MAX_COMPLETIONS};
+use crate::config::Config;
+use crate::confirmed::CONFIRMED_MARK;
+use crate::coverage::{dictionary_source, Coverage, CoverageLog};
+use crate::date::{format_day, today};
+use crate::debug::{draw_debug_overlay, DebugInfo, DEBUG_OVERLAY_DEFAULT};
+use crate::entry::{shake_offset, EntryEffect, SHAKE_TIME};
+use crate::events::{AppEvent, Nav};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::export::{open_file, print_and_open, EXPORTS_DIR};
+use crate::export::{PrintSection, PrintSheet};
+use crate::feedback::{AsTyped, MatchOutcome};
+use crate::help::{draw_help, help_groups, help_line_h, help_panel, layout_help};
+use crate::hints::{applying, draw_hints, hint_rules, HintRule};
+use crate::history::History;
+use crate::inspect::{draw_inspector, WordFlags, WordInfo};
+use crate::keyboard::{draw_keyboard, hit_test, keyboard_buttons, KeyButton};
+use crate::keymap::Action;
+use crate::layout::{entry_text_size, ScreenLayout, WindowValues};
+use crate::length_filter::{chip_length_at, draw_length_chips, split_chips};
+use crate::letter_chart::{draw_stats, StatsView};
+use crate::memory::MemoryReport;
+use crate::metrics::{fit_font_size, TextMetrics};
+use crate::mini::{draw_mini, MiniLayout, DEFAULT_WINDOW_SIZE, MINI_SIZE, TIP_TIME};
+use crate::modal::{draw_modal, draw_panel, Focus, FocusStack, Modal, QuitChoice};
+use crate::pairing::{draw_paired, pair_letters, tile_pairs};
+use crate::palette::{draw_palette, Palette, PaletteKey};
+use crate::parse::extract_scramble;
+use crate::phonetic::{draw_phonetic, phonetic};
+use crate::pins::{
+    chip_at, chip_rects, draw_pin_button, draw_pins, pin_button, split_results, PinEffect,
+    PIN_CAPACITY,
+};
+use crate::pointer::{Gesture, GestureTracker, PointerEvent};
+use crate::pool::{draw_pool, PooledEntryBuffer, REFUSED_TIME};
+use crate::query::lang::is_command;
+use crate::query::QueryMode;
+use crate::rank::{rank, ranking_rows, Ranked};
+use crate::redraw::FrameCache;
+use crate::remap::Remapper;
+use crate::render::Renderer;
+use crate::results::{draw_results, draw_styled_text, max_scroll, ResultStyle};
+use crate::reveal::{draw_padlock, PresentationPolicy, RevealLock};
+use crate::screen::{Screen, ScreenTrait, Transition};
+use crate::session::{SavedTab, Session, AUTOSAVE_INTERVAL};
+use crate::settings::{draw_gear, SettingsScreen};
+use crate::sound::Cue;
+use crate::strings::{fill, Language, StringKey};
+use crate::table::layout_table;
+use crate::theme::Theme;
+use crate::tiles::{draw_tiles, letter_edges, slot_at};
+use crate::timing::{time, Stage};
+use crate::toast::{Severity, TOAST_TIME};
+use crate::tutorial::TutorialScreen;
+use crate::typo::draw_suggestions;
+use crate::usage::Feature;
+use crate::wordmap::{count_letters, fold_letter};
+use crate::workspace::{EntryStatus, Workspace};
+use crate::{CAPACITY_FLASH_TIME, MAX_TABS};
+use macroquad::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// State of the word entry screen.
+pub struct EntryScreen {
+    /// The open entry tabs, never empty.
+    tabs: Vec<Workspace>,
+    /// Index of the tab in front.
+    active: usize,
+    big_mode_theme: Theme,
+    flash_until: Option<f64>,
+    /// The entry shaking off a letter that didn't fit.
+    shake: Option<Animation>,
+    entry_offset: f32,
+    debug_overlay: bool,
+    debug_info: DebugInfo,
+    stats_view: StatsView,
+    layout: ScreenLayout,
+    entry_metrics: TextMetrics,
+    result_metrics: TextMetrics,
+    wv: Option<WindowValues>,
+    /// Measured (again) before use, and until the font measures sensibly.
+    layout_stale: bool,
+    buttons: Vec<(KeyButton, Rect)>,
+    show_keyboard: bool,
+    big_mode: bool,
+    /// Whether the mini window is shown (see `mini`).
+    mini: bool,
+    /// The window's size before it was made mini, to grow back to.
+    full_size: Vec2,
+    /// When the mini window's tooltip goes away, while it's up.
+    mini_tip_until: Option<f64>,
+    announcer: Option<Announcer>,
+    gestures: GestureTracker,
+    focus: FocusStack,
+    /// How far the help overlay is scrolled.
+    help_scroll: f32,
+    palette: Palette,
+    quit_requested: bool,
+    /// Tabs left by a crash, offered back until the user answers.
+    recovered: Option<Session>,
+    /// When the tabs are next autosaved.
+    next_autosave: f64,
+    /// The tabs as last autosaved, so unchanged tabs aren't written again.
+    autosaved: Option<Session>,
+    /// Unique answers found, this session and before.
+    history: History,
+    /// How often each dictionary had the answer, before and during this session.
+    coverage: CoverageLog,
+    /// How often the dictionary had the answer this session.
+    session_coverage: Coverage,
+    /// The scramble whose answer was found before, and the last day it was.
+    last_seen: Option<(String, i64)>,
+    /// A demo doesn't autosave or keep a history or coverage.
+    demo: bool,
+    hint_rules: Vec<HintRule>,
+    /// The final answer being spelled from the entry's letters.
+    pool: PooledEntryBuffer,
+    /// The letter the pool just refused, and when it stops showing.
+    pool_refused: Option<(char, f64)>,
+    /// Whether Tab asked to complete the entry this frame (see `complete`).
+    complete_requested: bool,
+    /// The match being inspected, by index, and what's known about it.
+    inspected: Option<(usize, WordInfo)>,
+    /// Turns letters typed into those the entry gets (see `remap`).
+    remapper: Remapper,
+    /// How long it's been since the last input, for the attract mode.
+    idle: IdleTimer,
+    /// Whether the answers are locked away (see `reveal`).
+    reveal: RevealLock,
+}
+
+impl EntryScreen {
+    /// Creates the screen for the loaded dictionary, restoring any entry saved last
+    /// session and offering back any tabs left by a crash.
+    ///
+    /// # Panics
+    ///
+    /// If `ctx.dictionary` isn't loaded yet.
+    pub fn new(ctx: &mut AppContext) -> Self {
+        let word_map = Arc::clone(ctx.dictionary.as_ref().expect("dictionary not loaded"));
+        // The first frame looks up the entry restored from the last session
+        let mut tab = Workspace::new(word_map, ctx);
+        if let EntryEffect::Rejected { reason } =
+            tab.entry.set_from_paste(&ctx.state.take_saved_entry())
+        {
+            log::warn!("[EntryScreen::new] ignoring saved entry: {reason}");
+        }
+        let recovered = ctx.state.take_recovered_session();
+        let mut focus = FocusStack::default();
+        if recovered.is_some() {
+            focus.open(Modal::Restore);
+        }
+        let config = &ctx.state.config;
+        let layout = ScreenLayout::new(ctx.size.x, ctx.size.y, ctx.style.dpi);
+        let buttons = keyboard_buttons(layout.keyboard, tab.word_map.alphabet());
+        let entry_metrics = ctx.text_metrics(entry_size(ctx, &layout, tab.entry.max_len()));
+        if !entry_metrics.is_monospace() {
+            log::info!(
+                "[EntryScreen::new] font is proportional; text widths will be measured per string"
+            );
+        }
+
+        Self {
+            tabs: vec![tab],
+            active: 0,
+            big_mode_theme: Theme::high_contrast(),
+            flash_until: None,
+            shake: None,
+            entry_offset: 0.0,
+            debug_overlay: DEBUG_OVERLAY_DEFAULT,
+            debug_info: DebugInfo::default(),
+            stats_view: StatsView::Off,
+            layout,
+            entry_metrics,
+            result_metrics: ctx.text_metrics(ctx.style.result_size),
+            wv: None,
+            layout_stale: true,
+            buttons,
+            show_keyboard: config.on_screen_keyboard,
+            big_mode: config.big_mode,
+            mini: ctx.state.mini,
+            // A launch with `--mini` opens the window mini where it can
+            full_size: match ctx.state.mini && ctx.can_resize {
+                true => DEFAULT_WINDOW_SIZE,
+                false => ctx.size,
+            },
+            mini_tip_until: None,
+            announcer: config
+                .announce
+                .then(|| Announcer::new(config.announce_command.as_deref())),
+            gestures: GestureTracker::default(),
+            focus,
+            help_scroll: 0.0,
+            palette: Palette::default(),
+            quit_requested: false,
+            recovered,
+            next_autosave: ctx.now + AUTOSAVE_INTERVAL,
+            autosaved: None,
+            history: ctx.state.read_history(),
+            coverage: ctx.state.read_coverage(),
+            session_coverage: Coverage::default(),
+            last_seen: None,
+            demo: false,
+            hint_rules: hint_rules(),
+            pool: PooledEntryBuffer::default(),
+            pool_refused: None,
+            complete_requested: false,
+            inspected: None,
+            remapper: load_remapper(ctx),
+            idle: IdleTimer::new(ctx.now),
+            reveal: RevealLock::default(),
+        }
+    }
+    /// Makes this screen a demo, which leaves the user's saved tabs and history
+    /// alone.
+    pub fn into_demo(mut self) -> Self {
+        self.demo = true;
+        self
+    }
+    /// Shows the on-screen keyboard, out of big mode, whatever the config says,
+    /// for the tutorial to outline keys on.
+    pub fn with_keyboard(mut self) -> Self {
+        self.show_keyboard = true;
+        self.big_mode = false;
+        self
+    }
+    /// Returns the tab in front.
+    fn tab(&self) -> &Workspace {
+        &self.tabs[self.active]
+    }
+    fn tab_mut(&mut self) -> &mut Workspace {
+        &mut self.tabs[self.active]
+    }
+    /// Returns the open tabs and the index of the one in front.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn tabs(&self) -> (&[Workspace], usize) {
+        (&self.tabs, self.active)
+    }
+    /// Returns the entry's letters.
+    pub fn entry(&self) -> &str {
+        self.tab().entry.as_str()
+    }
+    /// Returns the words matching the entry.
+    pub fn matches(&self) -> &[String] {
+        self.tab().matches()
+    }
+    /// Returns the unique (or chosen) answer, if any.
+    pub fn answer(&self) -> Option<&str> {
+        self.tab().answer()
+    }
+    /// Returns the on-screen keyboard's buttons, or none if it isn't shown.
+    pub fn key_buttons(&self) -> &[(KeyButton, Rect)] {
+        match self.show_keyboard && !self.big_mode && !self.mini {
+            true => &self.buttons,
+            false => &[],
+        }
+    }
+    /// Returns the final answer spelled so far, while its panel is open.
+    pub fn final_phrase(&self) -> Option<String> {
+        (self.focus() == Focus::Modal(Modal::Pool)).then(|| self.pool.phrase())
+    }
+    /// Returns what may be shown of the answers (see `reveal`).
+    pub fn policy(&self) -> PresentationPolicy {
+        self.reveal.policy()
+    }
+    /// Returns what's on screen, for crash reports and spectators; while the
+    /// answers are locked, that's no words.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn summary(&self) -> crate::crash::StateSummary {
+        let (tab, policy) = (self.tab(), self.policy());
+        let (pool, phrase) = match self.focus() == Focus::Modal(Modal::Pool) {
+            true => (self.pool.letters(), self.pool.phrase()),
+            false => Default::default(),
+        };
+        let matches: Vec<String> = tab.shown().map(|i| tab.matches()[i].clone()).collect();
+        let solved = self
+            .tabs
+            .iter()
+            .filter_map(|t| t.answer().map(str::to_owned));
+        let solved: Vec<String> = solved.collect();
+        crate::crash::StateSummary {
+            entry: self.entry().to_owned(),
+            answer: policy.word(self.answer()).map(str::to_owned),
+            match_count: self.matches().len(),
+            matches: policy.words(&matches).to_vec(),
+            solved: policy.words(&solved).to_vec(),
+            pool,
+            phrase,
+        }
+    }
+    /// Returns how the entry is matched.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn mode(&self) -> QueryMode {
+        self.tab().mode
+    }
+    /// Returns whether the matches in every tab are up to date with its entry,
+    /// with no query changed, held back, or running.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn is_settled(&self) -> bool {
+        self.tabs.iter().all(Workspace::is_settled)
+    }
+    fn row_h(&self, ctx: &AppContext) -> f32 {
+        ctx.style.result_size as f32 * 1.3
+    }
+    fn results_max(&self, ctx: &AppContext) -> f32 {
+        max_scroll(
+            self.tab().shown().count(),
+            self.row_h(ctx),
+            self.results_area(ctx).2,
+        )
+    }
+    /// Returns the strip of pinned words and the row of length chips, each empty
+    /// if unused, and the area left below them for the list of matches.
+    fn results_area(&self, ctx: &AppContext) -> (Rect, Rect, Rect) {
+        let size = ctx.style.result_size / 2;
+        let (strip, rest) = split_results(self.layout.results, &ctx.state.pins, size);
+        let (chips, mut list) = split_chips(rest, self.tab().lengths, size);
+        // A list found by a looser search, or with words masked, goes under the
+        // label saying so
+        if self.list_label(ctx.state.config.language).is_some() {
+            let label_h = size as f32 * 1.4;
+            list.y += label_h;
+            list.h = (list.h - label_h).max(0.0);
+        }
+        (strip, chips, list)
+    }
+    /// Returns the label over the list, if it needs one: which looser search
+    /// than the entry asked for found the words, and how many the mask hides.
+    fn list_label(&self, lang: Language) -> Option<String> {
+        let tab = self.tab();
+        if tab.matches().len() < 2 || self.big_mode {
+            return None;
+        }
+        let hidden = tab.hidden_count();
+        let parts = [
+            tab.escalation.label().map(|key| lang.text(key).to_owned()),
+            (hidden > 0).then(|| fill(lang.text(StringKey::WordsHidden), hidden)),
+        ];
+        let label = parts.into_iter().flatten().collect::<Vec<_>>().join("   ");
+        (!label.is_empty()).then_some(label)
+    }
+    /// Returns where each pinned word's chip is drawn in `strip`.
+    fn pin_chips(&self, ctx: &AppContext, strip: Rect) -> Vec<Rect> {
+        let (font, size) = (ctx.font.as_ref(), ctx.style.result_size / 2);
+        chip_rects(strip, &ctx.state.pins, size, |text, size| {
+            (ctx.measure)(text, font, size).width
+        })
+    }
+    /// Returns the pin button of the selected row, with the match's index, if the
+    /// list is shown and the row is in view.
+    fn selected_pin_button(&self, ctx: &AppContext) -> Option<(usize, Rect)> {
+        let tab = self.tab();
+        let index = tab.results.selected.filter(|_| {
+            let listed = tab.matches().len() > 1 && !self.big_mode && self.hints_shown().is_none();
+            listed && self.policy().shows_words()
+        })?;
+        let row = tab.shown().position(|i| i == index)?;
+        let (_, _, list) = self.results_area(ctx);
+        pin_button(list, self.row_h(ctx), tab.results.scroll, row).map(|button| (index, button))
+    }
+    /// Opens the settings, which preview how the tab's matches rank.
+    fn open_settings(&self) -> Transition {
+        let matches = self.tab().matches().to_vec();
+        Transition::Push(Screen::Settings(SettingsScreen::with_preview(matches)))
+    }
+    /// Returns the tab's matches ranked, best first.
+    fn ranking(&self, ctx: &AppContext) -> Vec<Ranked> {
+        let tab = self.tab();
+        let is_pinned = |word: &str| ctx.state.pins.contains(word);
+        let is_confirmed = |word: &str| ctx.state.confirmed.contains(word);
+        time(Stage::Ranking, || {
+            rank(
+                tab.matches(),
+                &tab.word_map,
+                is_pinned,
+                is_confirmed,
+                &ctx.state.config.ranking,
+            )
+        })
+    }
+    /// Returns where the unique answer is drawn, centered under the entry, for
+    /// tapping it.
+    fn answer_rect(&self, ctx: &AppContext) -> Option<Rect> {
+        let (tab, wv) = (self.tab(), self.wv.as_ref()?);
+        let shown = tab.matches().len() == 1 && !self.big_mode && self.hints_shown().is_none();
+        let shown = shown && self.countdown(ctx).is_none() && self.policy().shows_words();
+        let index = tab.answer.filter(|_| shown && !tab.is_searching())?;
+        let (font, metrics) = (ctx.font.as_ref(), &self.result_metrics);
+        let text = &tab.matches()[index];
+        let w = metrics.width(text, |t| (ctx.measure)(t, font, metrics.size));
+        let top = wv.tby + wv.margin_y;
+        Some(Rect::new(wv.tcx - w / 2.0, top, w, metrics.height))
+    }
+    /// Switches to the mini window and back, resizing the window if it can be.
+    fn toggle_mini(&mut self, ctx: &mut AppContext) {
+        self.mini = !self.mini;
+        ctx.state.mini = self.mini;
+        self.mini_tip_until = None;
+        if ctx.can_resize {
+            ctx.window_size = Some(match self.mini {
+                true => {
+                    self.full_size = ctx.size;
+                    MINI_SIZE
+                }
+                false => self.full_size,
+            });
+        }
+        ctx.dirty = true;
+    }
+    /// Returns what the full window would say about the matches besides the
+    /// answer, for the mini window's tooltip.
+    fn mini_status(&self, lang: Language) -> String {
+        let tab = self.tab();
+        let count = tab.shown().count();
+        let parts = [
+            (count > 1).then(|| fill(lang.text(StringKey::ManyMatches), count)),
+            self.list_label(lang),
+            tab.command_error
+                .as_ref()
+                .map(|error| fill(lang.text(StringKey::QueryError), error)),
+            tab.held.then(|| lang.text(StringKey::SlowQuery).to_owned()),
+        ];
+        parts.into_iter().flatten().collect::<Vec<_>>().join("   ")
+    }
+    /// Types the rest of the first word that could complete the entry, if there
+    /// is one, after the letters typed so far.
+    fn complete(&mut self, ctx: &AppContext) {
+        let tab = &mut self.tabs[self.active];
+        let completions = tab.find_completions(ctx.state.active_mask());
+        let Some(word) = completions.first() else {
+            return;
+        };
+        let word = tab.word_map.alphabet().fold_word(word);
+        for c in missing_letters(tab.entry.as_str(), &word).chars() {
+            tab.edits.record(tab.entry.push_char(c));
+        }
+    }
+    /// Opens the panel for spelling the final answer from the entry's letters,
+    /// if it has any.
+    fn spell_final(&mut self, ctx: &mut AppContext) {
+        let entry = &self.tab().entry;
+        if entry.is_empty() || entry.is_command() {
+            return;
+        }
+        self.pool = PooledEntryBuffer::new(entry.as_str());
+        self.pool_refused = None;
+        self.focus.open(Modal::Pool);
+        ctx.dirty = true;
+    }
+    /// Opens the inspector on the selected match, or the answer, if there is one.
+    fn inspect_word(&mut self, ctx: &mut AppContext) {
+        let tab = self.tab();
+        let Some(index) = tab.results.selected.or(tab.answer) else {
+            return;
+        };
+        let Some(word) = tab.matches().get(index) else {
+            return;
+        };
+        self.inspected = Some((index, WordInfo::gather(&tab.word_map, word)));
+        self.focus.open(Modal::Inspect);
+        ctx.dirty = true;
+    }
+    /// Returns what the printable page shows: each tab's scramble and matches,
+    /// and the final answer spelled so far.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn print_sheet(&self) -> PrintSheet {
+        let sections = self
+            .tabs
+            .iter()
+            .filter(|tab| !tab.entry.is_empty() && !tab.entry.is_command())
+            .map(|tab| PrintSection {
+                scramble: tab.entry.as_str().to_owned(),
+                matches: tab.shown().map(|i| tab.matches()[i].clone()).collect(),
+                answer: tab.answer().map(str::to_owned),
+            })
+            .collect();
+        let phrase = self.pool.phrase();
+        PrintSheet {
+            date: today().map(format_day),
+            sections,
+            final_answer: (!phrase.trim().is_empty()).then_some(phrase),
+        }
+    }
+    /// Writes the printable page to the exports folder and opens it, or says
+    /// where it is if nothing opens it (see `export`).
+    #[cfg(not(target_arch = "wasm32"))]
+    fn print_results(&mut self, ctx: &mut AppContext) {
+        if self.demo {
+            return;
+        }
+        let lang = ctx.state.config.language;
+        let dir = crate::assets::data_dir().join(EXPORTS_DIR);
+        let (text, severity) = print_and_open(&dir, &self.print_sheet(), lang, open_file);
+        ctx.toasts.push(text, severity, TOAST_TIME, ctx.now);
+        ctx.dirty = true;
+    }
+    #[cfg(target_arch = "wasm32")]
+    fn print_results(&mut self, _ctx: &mut AppContext) {
+        log::warn!("[EntryScreen::print_results] printing is not supported on the web");
+    }
+    /// Hides, confirms, or pins the word being inspected, for X, C, or B.
+    fn inspector_key(&mut self, ctx: &mut AppContext, key: char) {
+        let Some((index, info)) = &self.inspected else {
+            return;
+        };
+        let (index, word) = (*index, info.word.clone());
+        let lang = ctx.state.config.language;
+        let toast = match key {
+            'X' => match ctx.state.mask_word(&word) {
+                true => {
+                    // The mask changes what's shown
+                    self.tab_mut().entry_status = EntryStatus::Changed;
+                    Some(StringKey::HiddenWord)
+                }
+                false => Some(StringKey::AlreadyHidden),
+            },
+            'C' => match ctx.state.confirm(&word) {
+                true => Some(StringKey::ConfirmedWord),
+                false => Some(StringKey::AlreadyConfirmed),
+            },
+            'B' => {
+                self.toggle_pin(ctx, index);
+                None
+            }
+            _ => None,
+        };
+        if let Some(key) = toast {
+            let text = fill(lang.text(key), &word);
+            ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+        }
+        ctx.dirty = true;
+    }
+    /// Opens the table of how the matches rank, if there are any.
+    fn explain_ranking(&mut self, ctx: &mut AppContext) {
+        if !self.tab().matches().is_empty() {
+            self.focus.open(Modal::Ranking);
+            ctx.dirty = true;
+        }
+    }
+    /// Returns the seconds left before the tab's answer shows, and of how many,
+    /// while it's held back (see `assist`).
+    fn countdown(&self, ctx: &AppContext) -> Option<(f64, f64)> {
+        let seconds = assist_seconds(&ctx.state.config)?;
+        let tab = self.tab();
+        (holds_back(tab) && !tab.assist.is_revealed())
+            .then(|| (tab.assist.remaining(seconds), seconds))
+    }
+    /// Returns the word hints are about: the answer, or else the first match
+    /// shown, once the matches are in.
+    fn hint_word(&self) -> Option<&str> {
+        let tab = self.tab();
+        if tab.is_searching() {
+            return None;
+        }
+        let index = tab.answer.or_else(|| tab.shown().next())?;
+        Some(&tab.matches()[index])
+    }
+    /// Returns which tile supplies each letter of the match selected in the
+    /// list (see `pairing`), or nothing if none is selected or the list isn't
+    /// shown.
+    fn letter_pairs(&self) -> Vec<Option<usize>> {
+        let tab = self.tab();
+        let listed = !self.big_mode
+            && !self.mini
+            && !self.reveal.is_locked()
+            && !tab.entry.is_command()
+            && tab.matches().len() > 1
+            && self.hints_shown().is_none();
+        let selected = tab.results.selected.filter(|_| listed);
+        let Some(word) = selected.and_then(|i| tab.matches().get(i)) else {
+            return Vec::new();
+        };
+        let tiles = tab.tiles.display(tab.entry.as_str());
+        let alphabet = tab.word_map.alphabet();
+        pair_letters(word, &tiles, |c| alphabet.fold(c))
+    }
+    /// Returns the hints shown, while they hide the matches.
+    fn hints_shown(&self) -> Option<Vec<&HintRule>> {
+        let count = self.tab().hints.filter(|_| !self.big_mode)?;
+        let mut hints = applying(&self.hint_rules, self.hint_word()?);
+        (count <= hints.len()).then(|| {
+            hints.truncate(count);
+            hints
+        })
+    }
+    /// Shows the next hint, or after the last one, the matches again.
+    fn next_hint(&mut self, ctx: &mut AppContext) {
+        let Some(word) = self.hint_word().filter(|_| !self.big_mode) else {
+            return;
+        };
+        let available = applying(&self.hint_rules, word).len();
+        let tab = self.tab_mut();
+        tab.hints = match tab.hints {
+            Some(count) if count <= available => Some(count + 1),
+            _ => Some(1),
+        };
+        ctx.dirty = true;
+    }
+    /// Shows or hides the matches of length `len`, or with `None` shows every
+    /// length. A change scrolls the list back to the top and drops a selection
+    /// the list no longer shows.
+    fn filter_lengths(&mut self, ctx: &mut AppContext, len: Option<usize>) {
+        let tab = self.tab_mut();
+        let changed = match len {
+            Some(len) => tab.lengths.toggle(len),
+            None => tab.lengths.clear(),
+        };
+        if !changed {
+            return;
+        }
+        tab.results.scroll = 0.0;
+        tab.results.velocity = 0.0;
+        let hidden = tab
+            .results
+            .selected
+            .filter(|&i| !tab.lengths.shows(&tab.matches()[i]));
+        if hidden.is_some() {
+            tab.results.selected = None;
+            if tab.answer == hidden {
+                tab.answer = None;
+            }
+        }
+        ctx.dirty = true;
+    }
+    /// Returns the edges of the entry's letters as shown, if they can be
+    /// rearranged: plain letters, two or more, outside big mode.
+    fn entry_letter_edges(&self, ctx: &AppContext) -> Option<Vec<f32>> {
+        let tab = self.tab();
+        if self.big_mode || self.mini || tab.entry.is_command() || tab.entry.len() < 2 {
+            return None;
+        }
+        let wv = self.wv.as_ref()?;
+        let (font, size) = (ctx.font.as_ref(), self.entry_text_metrics().size);
+        let text = tab.tiles.display(tab.entry.as_str());
+        let x = wv.scx - self.entry_offset;
+        Some(letter_edges(&text, x, |t| {
+            (ctx.measure)(t, font, size).width
+        }))
+    }
+    /// Picks up, drags, or drops an entry letter, returning whether `event` was
+    /// part of rearranging the letters.
+    fn drag_tile(&mut self, ctx: &AppContext, event: PointerEvent) -> bool {
+        let dragging = self.tab().tiles.drag.is_some();
+        match event {
+            PointerEvent::Pressed(pos) => {
+                let Some((edges, wv)) = self.entry_letter_edges(ctx).zip(self.wv) else {
+                    return false;
+                };
+                let in_box = (wv.tty - wv.margin_y..=wv.tby + wv.margin_y).contains(&pos.y);
+                let slot = slot_at(pos.x, &edges).filter(|_| in_box);
+                slot.is_some_and(|slot| self.tab_mut().tiles.pick_up(slot, pos.x, &edges))
+            }
+            PointerEvent::Moved(pos) if dragging => {
+                self.tab_mut().tiles.drag_to(pos.x);
+                true
+            }
+            PointerEvent::Released(_) if dragging => {
+                self.tab_mut().tiles.drop_tile();
+                true
+            }
+            PointerEvent::Moved(_) | PointerEvent::Released(_) => false,
+        }
+    }
+    /// Pins match `index` with the entry it matched, or unpins it if it's pinned.
+    fn toggle_pin(&mut self, ctx: &mut AppContext, index: usize) {
+        let Some(word) = self.matches().get(index).cloned() else {
+            return;
+        };
+        let query = self.entry().to_owned();
+        if ctx.state.pins.toggle(&word, &query) == PinEffect::Full {
+            let text = fill(
+                ctx.state.config.language.text(StringKey::PinLimit),
+                PIN_CAPACITY,
+            );
+            ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+        }
+        ctx.dirty = true;
+    }
+    /// Returns the metrics the entry is drawn with: smaller for a query command,
+    /// which runs longer than any word.
+    fn entry_text_metrics(&self) -> &TextMetrics {
+        match self.tab().entry.is_command() {
+            true => &self.result_metrics,
+            false => &self.entry_metrics,
+        }
+    }
+    fn entry_width(&self, ctx: &AppContext) -> f32 {
+        let metrics = self.entry_text_metrics();
+        let font = ctx.font.as_ref();
+        let measure = |text: &str| (ctx.measure)(text, font, metrics.size);
+        metrics.width(self.entry(), measure)
+    }
+
+    /// Returns every tab as autosaved.
+    fn session(&self) -> Session {
+        Session {
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| SavedTab {
+                    entry: tab.entry.as_str().to_owned(),
+                    mode: tab.mode,
+                })
+                .collect(),
+            active: self.active,
+        }
+    }
+    /// Replaces the tabs with those of `session`.
+    fn restore(&mut self, ctx: &mut AppContext, session: Session) {
+        let word_map = Arc::clone(&self.tab().word_map);
+        self.tabs = session
+            .tabs
+            .iter()
+            .take(MAX_TABS)
+            .map(|saved| {
+                let mut tab = Workspace::new(Arc::clone(&word_map), ctx);
+                if let EntryEffect::Rejected { reason } = tab.entry.set_from_paste(&saved.entry) {
+                    log::warn!("[EntryScreen::restore] ignoring saved entry: {reason}");
+                }
+                tab.mode = saved.mode;
+                tab
+            })
+            .collect();
+        self.switch_to(ctx, session.active.min(self.tabs.len() - 1));
+    }
+    /// Confirms the unique answer in front as seen in print, saying so.
+    fn confirm_answer(&mut self, ctx: &mut AppContext) {
+        let Some(answer) = self.answer().filter(|_| !self.demo) else {
+            return;
+        };
+        let answer = answer.to_owned();
+        let key = match ctx.state.confirm(&answer) {
+            true => StringKey::ConfirmedWord,
+            false => StringKey::AlreadyConfirmed,
+        };
+        let text = fill(ctx.state.config.language.text(key), &answer);
+        ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+        ctx.dirty = true;
+    }
+    /// Adds the unique answer in front to the history, first noting the last day
+    /// it was found for the same scramble, if any.
+    fn note_answer(&mut self, ctx: &mut AppContext) {
+        let (Some(day), Some(answer)) = (today(), self.answer()) else {
+            return;
+        };
+        // A query command isn't a scramble
+        if self.tab().entry.is_command() {
+            return;
+        }
+        let (scramble, answer) = (self.entry().to_owned(), answer.to_owned());
+        ctx.state.recall.remember(&scramble);
+        self.last_seen = self
+            .history
+            .last_seen(&scramble, &answer, day)
+            .map(|seen| (scramble.clone(), seen));
+        if self.history.record(day, &scramble, &answer) {
+            ctx.state.save_history(&self.history);
+        }
+        // A word recorded as the answer has served its purpose pinned
+        if ctx.state.pins.unpin(&answer) {
+            ctx.dirty = true;
+        }
+    }
+    /// Counts the new `outcome` of the entry in front toward the dictionary's
+    /// coverage.
+    fn note_coverage(&mut self, ctx: &mut AppContext, outcome: MatchOutcome) {
+        // A query command isn't a scramble
+        if outcome == MatchOutcome::Incomplete || self.tab().entry.is_command() {
+            return;
+        }
+        let config = &ctx.state.config;
+        let source = dictionary_source(&config.dictionary, config.language);
+        self.session_coverage.record(outcome);
+        self.coverage.record(&source, outcome);
+        ctx.state.save_coverage(&self.coverage);
+    }
+    /// Calls back an earlier entry (Up) or a later one (Down) in place of the
+    /// entry, caret at the end, and looks it up.
+    fn recall(&mut self, ctx: &mut AppContext, nav: Nav) {
+        let tab = &mut self.tabs[self.active];
+        let recall = &mut ctx.state.recall;
+        let recalled = match nav {
+            Nav::Up => recall.older(tab.entry.as_str()),
+            _ => recall.newer(),
+        };
+        if let Some(text) = recalled {
+            tab.edits.record(tab.entry.set_from_paste(text));
+            ctx.dirty = true;
+        }
+    }
+    /// Replaces the entry with pasted text: a plain word or query command as it
+    /// is, or otherwise the scramble found in it, limited to any blank pattern
+    /// given with it.
+    fn paste(&mut self, ctx: &mut AppContext, text: &str) {
+        if !self.demo {
+            ctx.state.count(Feature::Paste);
+        }
+        let lang = ctx.state.config.language;
+        let plain = is_command(text.trim_start())
+            || text
+                .chars()
+                .all(|c| c.is_whitespace() || fold_letter(c).is_some());
+        let (scramble, pattern) = match plain {
+            true => (text.to_owned(), None),
+            false => match extract_scramble(text) {
+                Some(found) => found,
+                None => {
+                    log::info!("[EntryScreen::paste] no scramble in '{text}'");
+                    let text = lang.text(StringKey::NoScramble).to_owned();
+                    ctx.toasts.push(text, Severity::Error, TOAST_TIME, ctx.now);
+                    return;
+                }
+            },
+        };
+        let tab = self.tab_mut();
+        tab.edits.record(tab.entry.set_from_paste(&scramble));
+        tab.pattern = pattern.map(|pattern| (tab.entry.as_str().to_owned(), pattern));
+        if let Some((_, pattern)) = &tab.pattern {
+            let text = fill(lang.text(StringKey::PatternApplied), pattern);
+            ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+        }
+    }
+    /// Brings tab `index` to the front.
+    fn switch_to(&mut self, ctx: &mut AppContext, index: usize) {
+        self.active = index;
+        ctx.state.recall.stop();
+        self.flash_until = None;
+        self.shake = None;
+        self.layout_stale = true;
+        ctx.dirty = true;
+    }
+    /// Opens an empty tab in front, unless `MAX_TABS` are open already.
+    fn new_tab(&mut self, ctx: &mut AppContext) {
+        if self.tabs.len() == MAX_TABS {
+            let text = fill(
+                ctx.state.config.language.text(StringKey::TabLimit),
+                MAX_TABS,
+            );
+            ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+            return;
+        }
+        let word_map = Arc::clone(&self.tab().word_map);
+        self.tabs.push(Workspace::new(word_map, ctx));
+        self.switch_to(ctx, self.tabs.len() - 1);
+    }
+    /// Closes the tab in front; closing the last one leaves a fresh empty tab.
+    fn close_tab(&mut self, ctx: &mut AppContext) {
+        let closed = self.tabs.remove(self.active);
+        if self.tabs.is_empty() {
+            self.tabs.push(Workspace::new(closed.word_map, ctx));
+        }
+        self.switch_to(ctx, self.active.min(self.tabs.len() - 1));
+    }
+
+    fn apply_action(&mut self, ctx: &mut AppContext, action: Action) -> Option<Transition> {
+        if !self.policy().allows(action) {
+            let text = ctx
+                .state
+                .config
+                .language
+                .text(StringKey::AnswersStillLocked);
+            ctx.toasts
+                .push(text.to_owned(), Severity::Info, TOAST_TIME, ctx.now);
+            ctx.dirty = true;
+            return None;
+        }
+        if let Some(feature) = Feature::for_action(action).filter(|_| !self.demo) {
+            ctx.state.count(feature);
+        }
+        match action {
+            Action::DeleteBack => {
+                let tab = self.tab_mut();
+                tab.edits.record(tab.entry.delete_back());
+            }
+            Action::ClearEntry => {
+                self.remapper.reset();
+                let tab = self.tab_mut();
+                ctx.state.recall.remember(tab.entry.as_str());
+                tab.edits.record(tab.entry.clear());
+            }
+            Action::Quit => self.quit_requested = true,
+            Action::ToggleDebug => self.debug_overlay = !self.debug_overlay,
+            Action::CycleStats => {
+                self.stats_view = self.stats_view.next();
+                ctx.dirty = true;
+            }
+            Action::CycleTheme => {
+                ctx.state.config.theme = ctx.themes.cycle().name.clone();
+                ctx.state.config_changed();
+            }
+            Action::ToggleSubAnagrams => {
+                let tab = self.tab_mut();
+                tab.mode = match tab.mode {
+                    QueryMode::Exact => QueryMode::SubAnagram,
+                    QueryMode::SubAnagram => QueryMode::Exact,
+                };
+                tab.entry_status = EntryStatus::Changed;
+            }
+            Action::ToggleBigMode => {
+                self.big_mode = !self.big_mode;
+                ctx.state.config.big_mode = self.big_mode;
+                ctx.state.config_changed();
+                ctx.dirty = true;
+            }
+            Action::ToggleMini => self.toggle_mini(ctx),
+            Action::ExplainRanking => self.explain_ranking(ctx),
+            Action::SpellFinal => self.spell_final(ctx),
+            Action::InspectWord => self.inspect_word(ctx),
+            Action::PrintResults => self.print_results(ctx),
+            Action::ToggleRevealLock => {
+                if self.reveal.press(ctx.now) {
+                    let lang = ctx.state.config.language;
+                    let chord = ctx.keymap.chords(action).next();
+                    let chord = chord.map(|chord| chord.to_string()).unwrap_or_default();
+                    let text = fill(lang.text(StringKey::AnswersLocked), chord);
+                    ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+                }
+                ctx.dirty = true;
+            }
+            Action::Complete => self.complete_requested = true,
+            Action::TogglePhonetic => {
+                ctx.state.config.phonetic = !ctx.state.config.phonetic;
+                ctx.state.config_changed();
+                ctx.dirty = true;
+            }
+            // The list's selection, or else the single match
+            Action::CopyAnswer => {
+                let tab = self.tab();
+                if let Some(index) = tab.results.selected.or(tab.answer) {
+                    let word = &tab.matches()[index];
+                    let lang = ctx.state.config.language;
+                    let text = fill(lang.text(StringKey::Copied), word);
+                    ctx.clipboard = Some(match ctx.state.config.copy_phonetic {
+                        true => phonetic(word),
+                        false => word.clone(),
+                    });
+                    ctx.toasts.push(text, Severity::Info, TOAST_TIME, ctx.now);
+                }
+            }
+            Action::ConfirmAnswer => self.confirm_answer(ctx),
+            Action::RevealHidden => {
+                let tab = self.tab_mut();
+                if !tab.hidden.is_empty() {
+                    tab.revealed = !tab.revealed;
+                    tab.results.reset();
+                    ctx.dirty = true;
+                }
+            }
+            Action::ToggleMute => {
+                ctx.sounds.muted = !ctx.sounds.muted;
+                ctx.state.config.muted = ctx.sounds.muted;
+                ctx.state.config_changed();
+            }
+            Action::TextLarger | Action::TextSmaller | Action::TextReset => {
+                let new_style = match action {
+                    Action::TextLarger => ctx.style.scaled_up(),
+                    Action::TextSmaller => ctx.style.scaled_down(),
+                    _ => ctx.style.reset(),
+                };
+                if new_style != ctx.style {
+                    ctx.style = new_style;
+                    self.layout_stale = true;
+                    self.tab_mut().entry_status = EntryStatus::Changed;
+                    ctx.state.config.text_scale = ctx.style.scale;
+                    ctx.state.config_changed();
+                }
+            }
+            Action::OpenSettings => return Some(self.open_settings()),
+            Action::CommandPalette => {
+                self.palette = Palette::new(ctx.state.config.language);
+                self.focus.open(Modal::Palette);
+                ctx.dirty = true;
+            }
+            Action::ShowHelp => {
+                self.focus.open(Modal::Help);
+                self.help_scroll = 0.0;
+                ctx.dirty = true;
+            }
+            Action::NewTab => self.new_tab(ctx),
+            Action::CloseTab => self.close_tab(ctx),
+            Action::NextTab => {
+                if self.tabs.len() > 1 {
+                    self.switch_to(ctx, (self.active + 1) % self.tabs.len());
+                }
+            }
+            // The list's selection, or else the single match
+            Action::TogglePin => {
+                let tab = self.tab();
+                if let Some(index) = tab.results.selected.or(tab.answer) {
+                    self.toggle_pin(ctx, index);
+                }
+            }
+            Action::ClearPins => {
+                if ctx.state.pins.clear() {
+                    ctx.dirty = true;
+                }
+            }
+            Action::ShowLength4
+            | Action::ShowLength5
+            | Action::ShowLength6
+            | Action::ShowLength7
+            | Action::ShowLength8
+            | Action::ShowLength9
+            | Action::ShowAllLengths => self.filter_lengths(ctx, action.shown_length()),
+            Action::ShowHint => self.next_hint(ctx),
+        }
+        None
+    }
+
+    fn handle_gesture(
+        &mut self,
+        ctx: &mut AppContext,
+        gesture: Option<Gesture>,
+    ) -> Option<Transition> {
+        let (row_h, results_max) = (self.row_h(ctx), self.results_max(ctx));
+        let (strip, lengths, area) = self.results_area(ctx);
+        let tab_at =
+            |pos| (0..self.tabs.len()).find(|&i| tab_rect(self.layout.tabs, i).contains(pos));
+        let chip = |pos| chip_at(pos, &self.pin_chips(ctx, strip));
+        let pin_button = self.selected_pin_button(ctx);
+        let hints_shown = self.hints_shown().is_some();
+        // The mini window has nothing to tap but the strip, which shows the tooltip
+        if self.mini {
+            let strip = MiniLayout::new(ctx.size).strip;
+            let status = self.mini_status(ctx.state.config.language);
+            if let Some(Gesture::Tap(pos)) = gesture {
+                if strip.contains(pos) && !status.is_empty() {
+                    self.mini_tip_until = Some(ctx.now + TIP_TIME);
+                    ctx.dirty = true;
+                }
+            }
+            return None;
+        }
+        match gesture {
+            Some(Gesture::Tap(pos)) if self.layout.settings.contains(pos) => {
+                return Some(self.open_settings());
+            }
+            Some(Gesture::Tap(pos)) if self.tabs.len() > 1 && tab_at(pos).is_some() => {
+                let index = tab_at(pos).unwrap_or(self.active);
+                self.switch_to(ctx, index);
+            }
+            Some(Gesture::Tap(pos)) if strip.contains(pos) => {
+                if let Some(i) = chip(pos) {
+                    let word = ctx.state.pins.as_slice()[i].word.clone();
+                    ctx.state.pins.unpin(&word);
+                }
+            }
+            Some(Gesture::Tap(pos)) if self.answer_rect(ctx).is_some_and(|r| r.contains(pos)) => {
+                self.explain_ranking(ctx);
+            }
+            Some(Gesture::Tap(pos)) if lengths.contains(pos) => {
+                if let Some(len) = chip_length_at(pos, lengths) {
+                    self.filter_lengths(ctx, Some(len));
+                }
+            }
+            Some(Gesture::Tap(pos))
+                if pin_button.is_some_and(|(_, button)| button.contains(pos)) =>
+            {
+                if let Some((index, _)) = pin_button {
+                    self.toggle_pin(ctx, index);
+                }
+            }
+            Some(Gesture::Tap(pos)) => {
+                let keyboard_shown = self.show_keyboard && !self.big_mode;
+                let button = keyboard_shown
+                    .then(|| hit_test(&self.buttons, pos))
+                    .flatten();
+                if button.is_some() && !self.demo {
+                    ctx.state.count(Feature::KeyboardTap);
+                }
+                let big_mode = self.big_mode;
+                let tab = self.tab_mut();
+                if let Some(button) = button {
+                    ctx.state.recall.stop();
+                    tab.edits.record(match button {
+                        KeyButton::Letter(c) => tab.entry.push_char(c),
+                        KeyButton::DeleteBack => tab.entry.delete_back(),
+                        KeyButton::Clear => {
+                            ctx.state.recall.remember(tab.entry.as_str());
+                            tab.entry.clear()
+                        }
+                    });
+                } else if !big_mode && !hints_shown {
+                    let count = tab.shown().count();
+                    let row = tab.results.row_at(pos, area, row_h, count);
+                    if let Some(index) = row.and_then(|row| tab.shown().nth(row)) {
+                        tab.results.selected = Some(index);
+                        tab.answer = Some(index);
+                    }
+                }
+            }
+            Some(Gesture::Drag { start, dy }) if area.contains(start) => {
+                self.tab_mut().results.drag(dy, results_max);
+            }
+            Some(Gesture::Fling { start, velocity }) if area.contains(start) => {
+                self.tab_mut().results.fling(velocity);
+            }
+            _ => (),
+        }
+        None
+    }
+
+    /// Scrolls the help overlay by `lines`, keeping it within its content.
+    fn scroll_help(&mut self, ctx: &mut AppContext, lines: f32) {
+        let (font, font_size) = (ctx.font.as_ref(), ctx.style.result_size / 2);
+        let (_, content) = help_panel(ctx.size, font_size);
+        let line_h = help_line_h(font_size);
+        let lang = ctx.state.config.language;
+        let groups = help_groups(&ctx.keymap, lang);
+        let layout = layout_help(&groups, lang, line_h, content.w, |text| {
+            (ctx.measure)(text, font, font_size).width
+        });
+        let max_scroll = layout.max_scroll(content.h);
+        self.help_scroll = (self.help_scroll + lines * line_h).clamp(0.0, max_scroll);
+        ctx.dirty = true;
+    }
+
+    /// Returns the theme in effect; big mode always uses high contrast.
+    fn theme<'t>(&'t self, ctx: &'t AppContext) -> &'t Theme {
+        if self.big_mode {
+            &self.big_mode_theme
+        } else {
+            ctx.themes.current()
+        }
+    }
+
+    /// Draws the text entered by the user, centered in text box.
+    ///
+    /// Entry offset shifts text to the left to keep it centered in the text box.
+    ///
+    /// In Macroquad, text is drawn from the *bottom*, as opposed to rectangles, which
+    /// are drawn from the *top* of the shape.
+    fn draw_text_entry(
+        &self,
+        ctx: &AppContext,
+        gfx: &dyn Renderer,
+        theme: &Theme,
+        wv: &WindowValues,
+    ) {
+        // The shake swings the tile row up to a quarter of a letter each way
+        let shake = self
+            .shake
+            .map_or(0.0, |shake| shake_offset(shake.progress(ctx.now)));
+        let swing = self.entry_metrics.height * 0.25;
+        let pos = vec2(wv.scx - self.entry_offset + shake * swing, wv.tby);
+
+        if self.debug_overlay {
+            // Text start
+            gfx.circle(pos, 3.0, theme.highlight);
+        }
+
+        let (font, size) = (ctx.font.as_ref(), self.entry_text_metrics().size);
+        let tiles = &self.tab().tiles;
+        let text = tiles.display(self.entry());
+        let pairs = self.letter_pairs();
+        match tiles.drag {
+            Some(drag) => draw_tiles(gfx, &text, drag, pos, font, size, theme),
+            None if pairs.iter().any(Option::is_some) => {
+                let tiles = tile_pairs(&pairs, text.chars().count());
+                let metrics = self.entry_text_metrics();
+                draw_paired(
+                    gfx,
+                    &text,
+                    &tiles,
+                    true,
+                    pos,
+                    font,
+                    metrics,
+                    theme.entry_text,
+                );
+            }
+            None => {
+                gfx.text(&text, pos, font, size, theme.entry_text);
+                // What Tab would type, faintly after the letters typed
+                let tab = self.tab();
+                if let Some(word) = tab.completions.first() {
+                    let word = tab.word_map.alphabet().fold_word(word);
+                    let rest = missing_letters(tab.entry.as_str(), &word);
+                    let x = pos.x + gfx.measure(&text, font, size).width;
+                    let mut color = theme.entry_text;
+                    color.a *= 0.3;
+                    gfx.text(&rest, vec2(x, pos.y), font, size, color);
+                }
+            }
+        }
+        if let Some(counter) = self.tab().entry.counter() {
+            draw_entry_counter(gfx, &counter, wv, font, size / 3, theme);
+        }
+        let lang = ctx.state.config.language;
+        // The words that would complete the entry are answers too
+        let completions = Some(&self.tab().completions).filter(|_| self.policy().shows_words());
+        if let Some(caption) = completions.and_then(|c| completions_caption(c, lang)) {
+            draw_completions(gfx, &caption, wv, font, size / 3, theme);
+        }
+        let letters = &ctx.state.letters;
+        let left_out = letters
+            .is_active()
+            .then(|| fill(lang.text(StringKey::LettersLeftOut), letters.letters()));
+        let indicator = [self.remapper.indicator(lang), left_out];
+        let indicator: Vec<String> = indicator.into_iter().flatten().collect();
+        if !indicator.is_empty() {
+            draw_input_indicator(gfx, &indicator.join(", "), wv, font, size / 3, theme);
+        }
+    }
+
+    /// Draws a single text result in `style`, below the text box and centered on
+    /// the x-axis.
+    fn draw_answer_centered(
+        &self,
+        ctx: &AppContext,
+        gfx: &dyn Renderer,
+        text: &str,
+        style: ResultStyle,
+        wv: &WindowValues,
+    ) {
+        let theme = self.theme(ctx);
+        let (font, metrics) = (ctx.font.as_ref(), &self.result_metrics);
+        let answer_w = metrics.width(text, |t| gfx.measure(t, font, metrics.size));
+        let answer_h = metrics.height;
+
+        let x = wv.tcx - answer_w / 2.0;
+        let y = wv.tby + (wv.margin_y + answer_h);
+
+        if self.debug_overlay {
+            // Reference line
+            let start = vec2(x - 2.0, y - answer_h);
+            let end = vec2(x + answer_w + 2.0, y - answer_h);
+            gfx.line(start, end, 1.0, theme.highlight);
+            gfx.circle(start, 3.0, theme.highlight);
+        }
+
+        draw_styled_text(gfx, text, x, y, style, font, metrics);
+    }
+
+    /// Draws `text` (the answer's note, and when it was last found for this
+    /// scramble, or what's wrong with a query command) small in `color`, under
+    /// the centered answer.
+    fn draw_answer_note(
+        &self,
+        ctx: &AppContext,
+        gfx: &dyn Renderer,
+        text: &str,
+        color: Color,
+        wv: &WindowValues,
+    ) {
+        let (font, size) = (ctx.font.as_ref(), ctx.style.result_size / 2);
+        let y = wv.tby + wv.margin_y + self.result_metrics.height + size as f32 * 1.6;
+        // A long note, or a long entry in it, shrinks to fit the panel
+        let (_, _, list) = self.results_area(ctx);
+        let room = 2.0 * (wv.tcx - list.x).min(list.right() - wv.tcx);
+        let measure = |text: &str, size| gfx.measure(text, font, size).width;
+        let size = fit_font_size(text, room, size, measure);
+        let w = gfx.measure(text, font, size).width;
+        gfx.text(text, vec2(wv.tcx - w / 2.0, y), font, size, color);
+    }
+
+    /// Draws the debug overlay, if shown, with the frame rate and `frame`'s counts.
+    pub fn draw_overlay(&self, ctx: &AppContext, gfx: &dyn Renderer, fps: i32, frame: &FrameCache) {
+        if !self.debug_overlay {
+            return;
+        }
+        let info = DebugInfo {
+            fps,
+            frames_drawn: frame.frames_drawn,
+            frames_skipped: frame.frames_skipped,
+            ..self.debug_info.clone()
+        };
+        draw_debug_overlay(gfx, &info, ctx.font.as_ref(), self.theme(ctx));
+    }
+}
+
+impl ScreenTrait for EntryScreen {
+    /// An open dialog takes all input; the entry and key bindings wait.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        self.idle.note(&ev, ctx.now);
+        let entry_focused = self.focus() == Focus::Entry;
+        let help_shown = self.focus() == Focus::Modal(Modal::Help);
+        let palette_shown = self.focus() == Focus::Modal(Modal::Palette);
+        let restore_shown = self.focus() == Focus::Modal(Modal::Restore);
+        let ranking_shown = self.focus() == Focus::Modal(Modal::Ranking);
+        let pool_shown = self.focus() == Focus::Modal(Modal::Pool);
+        let inspector_shown = self.focus() == Focus::Modal(Modal::Inspect);
+        // Typing over a called-back entry keeps it, as the entry to edit
+        let edits = matches!(
+            ev,
+            AppEvent::Letter(_)
+                | AppEvent::Paste(_)
+                | AppEvent::Action(Action::DeleteBack | Action::ClearEntry | Action::Complete)
+        );
+        if entry_focused && edits {
+            ctx.state.recall.stop();
+        }
+        match ev {
+            // Layout follows the window size
+            // The DPI scale only comes through when it changes
+            AppEvent::DpiScale(_) => {
+                self.layout = ScreenLayout::new(ctx.size.x, ctx.size.y, ctx.style.dpi);
+                self.layout_stale = true;
+                self.buttons =
+                    keyboard_buttons(self.layout.keyboard, self.tab().word_map.alphabet());
+                self.tab_mut().entry_status = EntryStatus::Changed;
+            }
+            AppEvent::WindowSize(size) => {
+                if self.layout.width != size.x || self.layout.height != size.y {
+                    self.layout = ScreenLayout::new(size.x, size.y, ctx.style.dpi);
+                    self.layout_stale = true;
+                    self.buttons =
+                        keyboard_buttons(self.layout.keyboard, self.tab().word_map.alphabet());
+                    let results = &mut self.tab_mut().results;
+                    results.scroll = 0.0;
+                    results.velocity = 0.0;
+                }
+            }
+            AppEvent::CloseRequested => self.quit_requested = true,
+            AppEvent::KeyActivity => ctx.dirty = true,
+            AppEvent::Touch => self.show_keyboard = true,
+            AppEvent::QuitChoice(choice) => {
+                if self.focus() != Focus::Modal(Modal::ConfirmQuit) {
+                    return None;
+                }
+                match choice {
+                    QuitChoice::Quit => return Some(Transition::Quit),
+                    QuitChoice::SaveAndQuit => {
+                        ctx.state.save_entry(self.entry());
+                        return Some(Transition::Quit);
+                    }
+                    QuitChoice::Stay => {
+                        self.focus.close();
+                        ctx.dirty = true;
+                    }
+                }
+            }
+            AppEvent::Nav(Nav::Select) if restore_shown => {
+                self.focus.close();
+                if let Some(session) = self.recovered.take() {
+                    self.restore(ctx, session);
+                }
+                ctx.dirty = true;
+            }
+            AppEvent::Dismiss if restore_shown => {
+                self.focus.close();
+                self.recovered = None;
+                ctx.dirty = true;
+            }
+            AppEvent::Dismiss if help_shown || ranking_shown || pool_shown => {
+                self.focus.close();
+                ctx.dirty = true;
+            }
+            AppEvent::Dismiss if inspector_shown => {
+                self.focus.close();
+                self.inspected = None;
+                ctx.dirty = true;
+            }
+            AppEvent::Letter(c) if inspector_shown => self.inspector_key(ctx, c),
+            // The chosen action runs just as if its keys were pressed
+            AppEvent::PaletteKey(key) if palette_shown => {
+                ctx.dirty = true;
+                let action = self.palette.press(key);
+                if action.is_some() || key == PaletteKey::Close {
+                    self.focus.close();
+                }
+                if let Some(action) = action {
+                    return self.apply_action(ctx, action);
+                }
+            }
+            AppEvent::Letter(c) if palette_shown => {
+                self.palette.push(c);
+                ctx.dirty = true;
+            }
+            // A letter the pool has no tile left for flashes, and isn't added
+            AppEvent::Letter(c) if pool_shown => {
+                if !self.pool.push(c) && c != ' ' {
+                    let letter = c.to_uppercase().next().unwrap_or(c);
+                    self.pool_refused = Some((letter, ctx.now + REFUSED_TIME));
+                }
+                ctx.dirty = true;
+            }
+            AppEvent::Action(Action::DeleteBack) if pool_shown => {
+                self.pool.pop();
+                self.pool_refused = None;
+                ctx.dirty = true;
+            }
+            // Enter in the help starts the practice puzzle
+            AppEvent::Nav(Nav::Select) if help_shown => {
+                self.focus.close();
+                ctx.dirty = true;
+                if !self.demo {
+                    ctx.state.count(Feature::Tutorial);
+                }
+                let tutorial = TutorialScreen::new(ctx);
+                return Some(Transition::Push(Screen::Tutorial(Box::new(tutorial))));
+            }
+            AppEvent::Nav(Nav::Up) if help_shown => self.scroll_help(ctx, -1.0),
+            AppEvent::Nav(Nav::Down) if help_shown => self.scroll_help(ctx, 1.0),
+            AppEvent::Nav(nav @ (Nav::Up | Nav::Down)) if entry_focused => self.recall(ctx, nav),
+            // Enter runs a query command held back for its cost
+            AppEvent::Nav(Nav::Select) if entry_focused && self.tab().held => {
+                self.tab_mut().run_held();
+                ctx.dirty = true;
+            }
+            // Enter shows an answer held back at once
+            AppEvent::Nav(Nav::Select) if entry_focused && self.countdown(ctx).is_some() => {
+                let tab = self.tab_mut();
+                tab.assist.reveal();
+                tab.hints = None;
+                ctx.dirty = true;
+            }
+            AppEvent::Wheel(wheel_y) if help_shown => self.scroll_help(ctx, -wheel_y.signum()),
+            AppEvent::Action(action) if entry_focused => return self.apply_action(ctx, action),
+            AppEvent::ActionReleased(Action::ToggleRevealLock) => {
+                self.reveal.release();
+                ctx.dirty = true;
+            }
+            AppEvent::Letter(c) if entry_focused => {
+                let Some(c) = self.remapper.map(c) else {
+                    ctx.dirty = true;
+                    return None;
+                };
+                let tab = self.tab_mut();
+                tab.edits.record(tab.entry.push_char(c));
+            }
+            AppEvent::Paste(text) if entry_focused => self.paste(ctx, &text),
+            AppEvent::Pointer(event) if entry_focused => {
+                ctx.dirty = true;
+                if self.drag_tile(ctx, event) {
+                    return None;
+                }
+                let gesture = self.gestures.feed(event, ctx.now);
+                return self.handle_gesture(ctx, gesture);
+            }
+            AppEvent::Wheel(wheel_y) if entry_focused => {
+                ctx.dirty = true;
+                let (row_h, results_max) = (self.row_h(ctx), self.results_max(ctx));
+                self.tab_mut()
+                    .results
+                    .drag(wheel_y.signum() * row_h, results_max);
+            }
+            AppEvent::Action(_) | AppEvent::Letter(_) | AppEvent::Paste(_) => (),
+            AppEvent::ActionReleased(_) => (),
+            AppEvent::Pointer(_) | AppEvent::Wheel(_) | AppEvent::Nav(_) => (),
+            AppEvent::Dismiss | AppEvent::PaletteKey(_) => (),
+        }
+        None
+    }
+
+    /// Applies this frame's edits and runs any queries they call for, in every
+    /// tab; only the one in front plays sounds and speaks.
+    fn update(&mut self, ctx: &mut AppContext, dt: f64) -> Option<Transition> {
+        let now = ctx.now;
+
+        // Quitting with an entry in progress asks first
+        if std::mem::take(&mut self.quit_requested) && self.focus() == Focus::Entry {
+            if self.tabs.iter().all(|tab| tab.entry.is_empty()) {
+                return Some(Transition::Quit);
+            }
+            self.focus.open(Modal::ConfirmQuit);
+            ctx.dirty = true;
+        }
+
+        // Letters come in as their keys are released, so the last one typed
+        // may arrive after the Tab pressed to complete the entry
+        if std::mem::take(&mut self.complete_requested) {
+            self.complete(ctx);
+        }
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            let edits = std::mem::take(&mut tab.edits);
+            if edits.changed {
+                tab.entry_status = EntryStatus::Changed;
+                tab.hints = None;
+            }
+            tab.tiles.sync(tab.entry.as_str());
+            if edits.at_capacity && i == self.active {
+                self.flash_until = Some(now + CAPACITY_FLASH_TIME);
+                self.shake = Some(Animation::new(now, SHAKE_TIME).reduced(ctx.reduced_motion));
+                ctx.sounds.play(Cue::Reject);
+                ctx.dirty = true;
+            }
+        }
+        // The frame the flash ends needs drawing too, to clear it
+        if self.flash_until.is_some_and(|until| now >= until) {
+            self.flash_until = None;
+            ctx.dirty = true;
+        }
+        if self.mini_tip_until.is_some_and(|until| now >= until) {
+            self.mini_tip_until = None;
+            ctx.dirty = true;
+        }
+        if self.pool_refused.is_some_and(|(_, until)| now >= until) {
+            self.pool_refused = None;
+            ctx.dirty = true;
+        }
+        if self.shake.is_some_and(|shake| shake.is_done(now)) {
+            self.shake = None;
+            ctx.dirty = true;
+        }
+
+        let results_max = self.results_max(ctx);
+        self.tab_mut().results.update(dt as f32, results_max);
+
+        if self.layout_stale {
+            let capacity = self.tab().entry.max_len();
+            self.entry_metrics = ctx.text_metrics(entry_size(ctx, &self.layout, capacity));
+            self.result_metrics = ctx.text_metrics(ctx.style.result_size);
+            self.wv = WindowValues::new(&self.entry_metrics, &self.layout, capacity);
+            self.entry_offset = self.entry_width(ctx) / 2.0;
+            self.layout_stale = self.wv.is_none();
+            ctx.dirty = true;
+        }
+        if matches!(self.tab().entry_status, EntryStatus::Changed) {
+            self.entry_offset = self.entry_width(ctx) / 2.0;
+        }
+
+        // Tabs left by a crash aren't overwritten until the user has answered
+        if now >= self.next_autosave && self.recovered.is_none() && !self.demo {
+            self.next_autosave = now + AUTOSAVE_INTERVAL;
+            let session = self.session();
+            if self.autosaved.as_ref() != Some(&session) {
+                ctx.state.autosave(&session);
+                self.autosaved = Some(session);
+            }
+        }
+
+        let mut outcome = None;
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            let debug = (i == self.active).then_some(&mut self.debug_info);
+            let tab_outcome = tab.update(ctx, debug);
+            if i == self.active {
+                outcome = tab_outcome;
+            }
+        }
+
+        // A held back answer shows once its countdown runs out
+        if let Some(seconds) = assist_seconds(&ctx.state.config) {
+            let entry_focused = self.focus() == Focus::Entry;
+            for (i, tab) in self.tabs.iter_mut().enumerate() {
+                tab.assist.sync(tab.entry.as_str());
+                let answer = holds_back(tab);
+                let paused = i != self.active || !entry_focused;
+                if tab.assist.tick(now, answer, paused, seconds) {
+                    tab.hints = None;
+                    ctx.dirty = true;
+                }
+            }
+        }
+
+        // Holding the lock key long enough shows the answers again
+        if self.reveal.tick(now) {
+            let text = ctx.state.config.language.text(StringKey::AnswersUnlocked);
+            ctx.toasts
+                .push(text.to_owned(), Severity::Info, TOAST_TIME, now);
+            ctx.dirty = true;
+        }
+
+        let tab = &self.tabs[self.active];
+        if outcome.is_some() && self.announcer.is_some() {
+            let text = announcement(
+                ctx.state.config.language,
+                tab.entry.as_str(),
+                tab.matches(),
+                self.reveal.policy().word(tab.answer()),
+                tab.is_searching(),
+            );
+            if let Some(announcer) = self.announcer.as_mut() {
+                announcer.offer(text);
+            }
+        }
+        if let Some(announcer) = self.announcer.as_mut() {
+            announcer.tick(now);
+        }
+        let changed = outcome.and_then(|o| self.tabs[self.active].outcomes.update(o));
+        if changed == Some(MatchOutcome::Unique) && !self.demo {
+            self.note_answer(ctx);
+        }
+        if let Some(outcome) = changed.filter(|_| !self.demo) {
+            self.note_coverage(ctx, outcome);
+        }
+        if let Some(cue) = changed.and_then(Cue::for_outcome) {
+            ctx.sounds.play(cue);
+        }
+
+        // Nobody's been here for a while, so the demo plays
+        if let Some(seconds) = attract_seconds(&ctx.state.config, ctx.reduced_motion) {
+            let idle = self.idle.is_idle(now, seconds);
+            if idle && !self.demo && !self.mini && self.focus() == Focus::Entry {
+                let screen = AttractScreen::new(ctx);
+                return Some(Transition::Push(Screen::Attract(Box::new(screen))));
+            }
+        }
+
+        if self.debug_overlay {
+            let tab = &self.tabs[self.active];
+            self.debug_info.entry.clear();
+            self.debug_info.entry.push_str(tab.entry.as_str());
+            self.debug_info.sorted_key = tab.word_map.alphabet().key(tab.entry.as_str());
+            self.debug_info.match_count = tab.matches().len();
+            self.debug_info.entry_offset = self.entry_offset;
+            let config = &ctx.state.config;
+            let source = dictionary_source(&config.dictionary, config.language);
+            self.debug_info.coverage = [self.session_coverage, self.coverage.get(&source)]
+                .map(|coverage| coverage.describe());
+            let mut memory = MemoryReport::default();
+            memory.register("history", &self.history);
+            memory.register("recall", &ctx.state.recall);
+            memory.register("pins", &ctx.state.pins);
+            memory.register("confirmed", &ctx.state.confirmed);
+            memory.register("mask", &ctx.state.mask);
+            self.debug_info.memory = Some(memory.describe(2));
+        }
+        None
+    }
+
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = self.theme(ctx);
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        let tab = self.tab();
+        let matches = tab.matches();
+        // Big mode and the mini window show the top-ranked match even when there are
+        // several, but not the entry's own letters as its only match
+        let searching = tab.is_searching();
+        let no_match = tab.outcomes.current() == MatchOutcome::NoMatch;
+        let top = || {
+            let ranked = self.ranking(ctx).into_iter();
+            ranked
+                .map(|ranked| ranked.index)
+                .find(|&i| !tab.is_hidden(i))
+        };
+        // nor a word found by a looser search
+        let escalated = tab.escalation.label();
+        let compact = self.big_mode || self.mini;
+        let big_top = compact && tab.as_typed != AsTyped::OnlyMatch && escalated.is_none();
+        let lone_escalated = escalated.is_some() && matches.len() == 1 && !self.big_mode;
+        // A masked lone match is only shown once revealed, and never as the answer
+        let lone_revealed = tab.revealed && !tab.hidden.is_empty() && matches.len() == 1;
+        let answer_text = match tab.answer.or_else(|| big_top.then(top).flatten()) {
+            _ if searching => lang.text(StringKey::Searching),
+            Some(i) => &matches[i],
+            None if lone_escalated || lone_revealed => &matches[0],
+            None if no_match => lang.text(escalated.unwrap_or(StringKey::NoMatches)),
+            None => "",
+        };
+        // An answer held back shows the countdown in its place
+        let countdown = self.countdown(ctx);
+        let countdown_text =
+            countdown.map(|(left, _)| fill(lang.text(StringKey::AssistCountdown), left.ceil()));
+        let answer_text = countdown_text.as_deref().unwrap_or(answer_text);
+        // Locked answers show how many matches there are instead
+        let locked = !self.policy().shows_words() && !searching;
+        let count_text = locked.then(|| match tab.shown().count() {
+            0 => lang.text(StringKey::NoMatches).to_owned(),
+            1 => lang.text(StringKey::OneMatch).to_owned(),
+            n => fill(lang.text(StringKey::ManyMatches), n),
+        });
+        let answer_text = count_text.as_deref().unwrap_or(answer_text);
+        let answer_style = if searching || countdown.is_some() || locked {
+            ResultStyle::plain(theme)
+        } else if tab.answer.is_some() {
+            ResultStyle::top(theme)
+        } else if no_match && !lone_escalated {
+            ResultStyle::no_match(theme)
+        } else {
+            ResultStyle::plain(theme)
+        };
+
+        gfx.clear(theme.background);
+        if self.mini {
+            let entry = tab.tiles.display(tab.entry.as_str());
+            let layout = MiniLayout::new(ctx.size);
+            let status = self.mini_status(lang);
+            let tip_shown = self.mini_tip_until.is_some();
+            let color = answer_style.color;
+            draw_mini(
+                gfx,
+                &layout,
+                &entry,
+                answer_text,
+                color,
+                &status,
+                tip_shown,
+                font,
+                theme,
+            );
+        } else if self.big_mode {
+            let entry = tab.tiles.display(tab.entry.as_str());
+            draw_big_mode(gfx, &entry, answer_text, font, theme);
+        } else {
+            if let Some(wv) = &self.wv {
+                let flashing = self.flash_until.is_some();
+                draw_text_box(gfx, theme, flashing, wv, self.debug_overlay);
+                self.draw_text_entry(ctx, gfx, theme, wv);
+                if let Some((left, seconds)) = countdown {
+                    let radius = wv.th * 0.2;
+                    let pad = wv.margin_y / 2.0;
+                    let center = vec2(wv.tlx + wv.tw - pad - radius, wv.tty + pad + radius);
+                    let fraction = (left / seconds) as f32;
+                    let label = left.ceil().to_string();
+                    draw_countdown(gfx, center, radius, fraction, &label, font, theme);
+                }
+            }
+            let (strip, lengths, list) = self.results_area(ctx);
+            let size = ctx.style.result_size / 2;
+            if !locked {
+                draw_pins(gfx, strip, &ctx.state.pins, font, size, theme);
+            }
+            draw_length_chips(gfx, lengths, tab.lengths, font, size, theme);
+            if let Some(wv) = self.wv.as_ref().filter(|_| locked) {
+                let size = self.result_metrics.height;
+                let center = vec2(wv.tcx, wv.tby + wv.margin_y + size / 2.0);
+                draw_padlock(gfx, center, size, self.reveal.hold_progress(ctx.now), theme);
+                self.draw_answer_note(ctx, gfx, answer_text, theme.result, wv);
+            } else if let Some(rules) = self.hints_shown() {
+                if let Some(wv) = &self.wv {
+                    let heading = lang.text(StringKey::HintsShown);
+                    self.draw_answer_centered(ctx, gfx, heading, ResultStyle::plain(theme), wv);
+                    let top = wv.tby + wv.margin_y + self.result_metrics.height + size as f32;
+                    let area = Rect::new(list.x, top, list.w, (list.bottom() - top).max(0.0));
+                    let hints: Vec<String> = rules.iter().map(|rule| rule.text(lang)).collect();
+                    draw_hints(gfx, &hints, area, font, size, theme);
+                }
+            } else if matches.len() > 1 {
+                if let Some(label) = self.list_label(lang) {
+                    let x = list.center().x - gfx.measure(&label, font, size).width / 2.0;
+                    let baseline = list.y - size as f32 * 0.4;
+                    gfx.text(&label, vec2(x, baseline), font, size, theme.result);
+                }
+                let (view, row_h) = (&tab.results, self.row_h(ctx));
+                let metrics = &self.result_metrics;
+                let mut notes = Cow::Borrowed(tab.notes.as_slice());
+                let as_typed = match tab.as_typed {
+                    AsTyped::AmongOthers(i) => Some(i),
+                    _ => None,
+                };
+                for (i, word) in matches.iter().enumerate() {
+                    let mut marks = Vec::new();
+                    if ctx.state.confirmed.contains(word) {
+                        marks.push(CONFIRMED_MARK);
+                    }
+                    if as_typed == Some(i) {
+                        marks.push(lang.text(StringKey::AsTyped));
+                    }
+                    if tab.fresh.is_new(word) {
+                        marks.push(lang.text(StringKey::NewMatch));
+                    }
+                    if marks.is_empty() {
+                        continue;
+                    }
+                    let notes = notes.to_mut();
+                    notes.resize(matches.len(), None);
+                    marks.extend(notes[i].as_deref());
+                    notes[i] = Some(marks.join(" "));
+                }
+                let (shown, notes) = (tab.shown(), &notes);
+                let pairs = &self.letter_pairs();
+                draw_results(
+                    gfx, matches, shown, notes, pairs, view, list, row_h, font, metrics, theme,
+                );
+                if let Some((index, button)) = self.selected_pin_button(ctx) {
+                    let pinned = ctx.state.pins.contains(&matches[index]);
+                    draw_pin_button(gfx, button, pinned, theme);
+                }
+            } else if let Some(wv) = &self.wv {
+                self.draw_answer_centered(ctx, gfx, answer_text, answer_style, wv);
+                let seen = self.last_seen.as_ref().filter(|(scramble, _)| {
+                    tab.answer.is_some() && !searching && scramble == tab.entry.as_str()
+                });
+                let seen =
+                    seen.map(|(_, day)| fill(lang.text(StringKey::LastSeen), format_day(*day)));
+                let answer = tab.answer.filter(|_| countdown.is_none());
+                let note = answer.and_then(|i| tab.notes.get(i)).cloned().flatten();
+                let hidden = Some(tab.hidden_count())
+                    .filter(|&n| n > 0 && !searching)
+                    .map(|n| fill(lang.text(StringKey::WordsHidden), n));
+                let text = [note, seen, hidden]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join("   ");
+                if let Some(error) = &tab.command_error {
+                    let text = fill(lang.text(StringKey::QueryError), error);
+                    self.draw_answer_note(ctx, gfx, &text, theme.error, wv);
+                } else if tab.held {
+                    let text = lang.text(StringKey::SlowQuery);
+                    self.draw_answer_note(ctx, gfx, text, theme.result, wv);
+                } else if tab.as_typed == AsTyped::OnlyMatch && !searching {
+                    let text = fill(lang.text(StringKey::AlreadySpells), tab.entry.as_str());
+                    self.draw_answer_note(ctx, gfx, &text, theme.result, wv);
+                } else if let Some(label) = escalated.filter(|_| lone_escalated && !searching) {
+                    self.draw_answer_note(ctx, gfx, lang.text(label), theme.result, wv);
+                } else if !text.is_empty() {
+                    self.draw_answer_note(ctx, gfx, &text, theme.result, wv);
+                }
+                // Below the note line, whether or not there's a note
+                let size = ctx.style.result_size / 2;
+                let top = wv.tby + wv.margin_y + self.result_metrics.height + size as f32 * 2.4;
+                let area = Rect::new(list.x, top, list.w, (list.bottom() - top).max(0.0));
+                let heading = lang.text(StringKey::DidYouMean);
+                draw_suggestions(gfx, heading, &tab.suggestions, area, font, size, theme);
+                let spelled = answer.filter(|_| ctx.state.config.phonetic && !searching);
+                if let Some(i) = spelled {
+                    draw_phonetic(gfx, &matches[i], area, font, size, theme);
+                }
+            }
+            if self.show_keyboard {
+                let letters = &ctx.state.letters;
+                draw_keyboard(gfx, &self.buttons, letters, font, theme, lang);
+            }
+        }
+        if self.tabs.len() > 1 {
+            draw_tabs(gfx, &self.tabs, self.active, self.layout.tabs, font, theme);
+        }
+        draw_gear(gfx, self.layout.settings, theme.result, theme.background);
+        let font_size = ctx.style.result_size / 2;
+        if self.stats_view != StatsView::Off {
+            let stats = tab.word_map.stats();
+            let letters = (self.stats_view == StatsView::Chart && !matches.is_empty())
+                .then(|| count_letters(matches));
+            let view = self.stats_view;
+            draw_stats(gfx, view, &stats, letters.as_ref(), font, font_size, theme);
+        }
+        match self.focus() {
+            Focus::Modal(Modal::Help) => {
+                let groups = help_groups(&ctx.keymap, lang);
+                draw_help(gfx, &groups, self.help_scroll, font, font_size, theme, lang);
+            }
+            Focus::Modal(Modal::Palette) => {
+                draw_palette(gfx, &self.palette, &ctx.keymap, font, font_size, theme);
+            }
+            Focus::Modal(Modal::Ranking) => {
+                let ranked = self.ranking(ctx);
+                draw_ranking(gfx, &ranked, matches, font, font_size, theme, lang);
+            }
+            Focus::Modal(Modal::Pool) => {
+                let refused = self.pool_refused.map(|(letter, _)| letter);
+                draw_pool(gfx, &self.pool, refused, font, font_size, theme, lang);
+            }
+            Focus::Modal(Modal::Inspect) => {
+                if let Some((_, info)) = &self.inspected {
+                    let state = &ctx.state;
+                    let flags = WordFlags {
+                        confirmed: state.confirmed.contains(&info.word),
+                        hidden: state.mask.hides(&info.word),
+                        pinned: state.pins.contains(&info.word),
+                    };
+                    let source = dictionary_source(&state.config.dictionary, lang);
+                    draw_inspector(gfx, info, flags, &source, font, font_size, theme, lang);
+                }
+            }
+            Focus::Modal(modal) => draw_modal(gfx, modal, font, font_size, theme, lang),
+            Focus::Entry => (),
+        }
+    }
+
+    fn focus(&self) -> Focus {
+        self.focus.focus()
+    }
+
+    fn is_animating(&self) -> bool {
+        let tab = self.tab();
+        let moving = tab.results.is_moving() || self.shake.is_some() || tab.assist.is_running();
+        moving || self.reveal.is_held()
+    }
+
+    // A pending debounce needs frames to notice the delay has passed
+    fn is_waiting(&self) -> bool {
+        self.tabs
+            .iter()
+            .any(|tab| tab.debounce.is_pending() || tab.is_post_processing())
+    }
+
+    /// Picks up whatever the settings screen changed, keeping the entries.
+    fn resume(&mut self, ctx: &mut AppContext) {
+        let config = &ctx.state.config;
+        self.big_mode = config.big_mode;
+        self.show_keyboard = config.on_screen_keyboard;
+        self.remapper = load_remapper(ctx);
+        self.idle.reset(ctx.now);
+        for tab in &mut self.tabs {
+            match &ctx.dictionary {
+                Some(dictionary) => tab.set_dictionary(dictionary, ctx.dictionary_generation),
+                None => tab.entry_status = EntryStatus::Changed,
+            }
+        }
+        // The word list may have a different alphabet
+        self.buttons = keyboard_buttons(self.layout.keyboard, self.tab().word_map.alphabet());
+        self.layout_stale = true;
+    }
+}
+
+/// Returns the remapper the config's `[input]` table asks for, or the standard
+/// one if the table is invalid.
+fn load_remapper(ctx: &AppContext) -> Remapper {
+    let config = &ctx.state.config;
+    Remapper::new(&config.input, config.keyboard_layout, &ctx.keymap).unwrap_or_else(|e| {
+        log::warn!("[load_remapper] invalid [input], typing as usual: {e}");
+        Remapper::default()
+    })
+}
+
+/// Returns how many seconds the answer is held back for, if `config` holds it
+/// back at all.
+fn assist_seconds(config: &Config) -> Option<f64> {
+    let seconds = config
+        .assist_seconds
+        .clamp(MIN_ASSIST_SECONDS, MAX_ASSIST_SECONDS);
+    config.assist.then_some(f64::from(seconds))
+}
+
+/// Returns how many seconds without input start the attract mode, if `config`
+/// has it on and motion isn't `reduced_motion`.
+fn attract_seconds(config: &Config, reduced_motion: bool) -> Option<f64> {
+    let seconds = config.attract_seconds.max(MIN_ATTRACT_SECONDS);
+    (config.attract && !reduced_motion).then_some(f64::from(seconds))
+}
+
+/// Returns whether `tab` has an answer to hold back: a unique one, using all
+/// of the entry's letters.
+fn holds_back(tab: &Workspace) -> bool {
+    let letters = |text: &str| text.chars().filter(|c| c.is_alphabetic()).count();
+    let entry = letters(tab.entry.as_str());
+    !tab.is_searching() && tab.answer().is_some_and(|answer| letters(answer) == entry)
+}
+
+/// Returns the entry's font size for an entry holding up to `capacity` letters,
+/// shrunk from the style's if need be so a full entry fits the window.
+fn entry_size(ctx: &AppContext, layout: &ScreenLayout, capacity: usize) -> u16 {
+    let font = ctx.font.as_ref();
+    entry_text_size(capacity, ctx.style.entry_size, layout, |text, size| {
+        (ctx.measure)(text, font, size).width
+    })
+}
+
+/// Returns the transition to the settings screen.
+/// Returns where tab `index` sits in the tab strip `area`; each has a fixed
+/// share, so tabs don't move as others open and close.
+fn tab_rect(area: Rect, index: usize) -> Rect {
+    let w = area.w / MAX_TABS as f32;
+    Rect::new(
+        area.x + index as f32 * w,
+        area.y,
+        (w - 4.0).max(0.0),
+        area.h,
+    )
+}
+
+/// Draws the tab strip, each tab labeled with its number and entry, the one in
+/// front filled in.
+fn draw_tabs(
+    gfx: &dyn Renderer,
+    tabs: &[Workspace],
+    active: usize,
+    area: Rect,
+    font: Option<&Font>,
+    theme: &Theme,
+) {
+    let size = (area.h * 0.5) as u16;
+    let pad = area.h * 0.25;
+    for (i, tab) in tabs.iter().enumerate() {
+        let rect = tab_rect(area, i);
+        let color = if i == active {
+            gfx.rect(rect, theme.box_outline);
+            theme.background
+        } else {
+            gfx.rect_lines(rect, theme.outline_thickness, theme.box_outline);
+            theme.entry_text
+        };
+        // Long entries lose letters from the end to fit
+        let mut label = format!("{} {}", i + 1, tab.entry.as_str())
+            .trim_end()
+            .to_string();
+        while label.len() > 1 && gfx.measure(&label, font, size).width > rect.w - pad * 2.0 {
+            label.pop();
+        }
+        let dims = gfx.measure(&label, font, size);
+        let pos = vec2(rect.x + pad, rect.y + (rect.h + dims.offset_y) / 2.0);
+        gfx.text(&label, pos, font, size, color);
+    }
+}
+
+/// Draws `counter` (letters entered out of the most allowed) small and faint,
+/// inside the right edge of the text box.
+fn draw_entry_counter(
+    gfx: &dyn Renderer,
+    counter: &str,
+    wv: &WindowValues,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let width = gfx.measure(counter, font, size).width;
+    let pad = wv.margin_y / 2.0;
+    let pos = vec2(wv.tlx + wv.tw - width - pad, wv.tby - pad);
+    let mut color = theme.box_outline;
+    color.a *= 0.7;
+    gfx.text(counter, pos, font, size, color);
+}
+
+/// Returns what to say in the entry box about `completions`: the words, if
+/// there are few enough to name, or else how many there are.
+fn completions_caption(completions: &Completions, lang: Language) -> Option<String> {
+    match completions.count {
+        0 => None,
+        n if n > MAX_COMPLETIONS => Some(fill(lang.text(StringKey::CompletionsCount), n)),
+        _ => Some(fill(
+            lang.text(StringKey::CompletionsTab),
+            completions.words.join(", "),
+        )),
+    }
+}
+
+/// Draws `caption` (see `completions_caption`) small and faint, inside the left
+/// edge of the text box, across from the counter.
+fn draw_completions(
+    gfx: &dyn Renderer,
+    caption: &str,
+    wv: &WindowValues,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let pad = wv.margin_y / 2.0;
+    let mut color = theme.box_outline;
+    color.a *= 0.7;
+    gfx.text(caption, vec2(wv.tlx + pad, wv.tby - pad), font, size, color);
+}
+
+/// Draws `indicator` (see `Remapper::indicator`), and the letters left out,
+/// small and faint, inside the top left corner of the text box.
+fn draw_input_indicator(
+    gfx: &dyn Renderer,
+    indicator: &str,
+    wv: &WindowValues,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let pad = wv.margin_y / 2.0;
+    let mut color = theme.box_outline;
+    color.a *= 0.7;
+    let pos = vec2(wv.tlx + pad, wv.tty + pad + size as f32);
+    gfx.text(indicator, pos, font, size, color);
+}
+
+/// Draws the data entry box, outlined thicker and in the error color when
+/// `flashing`, so the flash doesn't rely on color alone.
+fn draw_text_box(
+    gfx: &dyn Renderer,
+    theme: &Theme,
+    flashing: bool,
+    wv: &WindowValues,
+    debug: bool,
+) {
+    let (outline, thickness) = if flashing {
+        (theme.error, theme.outline_thickness * 3.0)
+    } else {
+        (theme.box_outline, theme.outline_thickness)
+    };
+    // Text box
+    gfx.rect_lines(Rect::new(wv.tlx, wv.tty, wv.tw, wv.th), thickness, outline);
+    if debug {
+        gfx.circle(vec2(wv.tlx, wv.tty), 3.0, theme.box_outline);
+    }
+}
+
+/// Draws the ranking table for `ranked` among `words` in a dialog over a dimmed
+/// screen, under a title and over a hint.
+fn draw_ranking(
+    gfx: &dyn Renderer,
+    ranked: &[Ranked],
+    words: &[String],
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+    lang: Language,
+) {
+    let measure = |text: &str| gfx.measure(text, font, font_size).width;
+    let (rows, aligns) = ranking_rows(ranked, words, lang);
+    let table = layout_table(&rows, &aligns, font_size as f32, measure);
+    let (title, hint) = (
+        lang.text(StringKey::RankTitle),
+        lang.text(StringKey::RankHint),
+    );
+
+    let line_h = font_size as f32 * 1.4;
+    let pad = font_size as f32;
+    let w = table.width.max(measure(title)).max(measure(hint)) + pad * 2.0;
+    let h = line_h * (rows.len() + 2) as f32 + pad * 2.0;
+    let panel = draw_panel(gfx, w, h, theme);
+
+    let baseline = |line: usize| panel.y + pad + line_h * (line as f32 + 0.75);
+    let centered = |text: &str| panel.center().x - measure(text) / 2.0;
+    gfx.text(
+        title,
+        vec2(centered(title), baseline(0)),
+        font,
+        font_size,
+        theme.entry_text,
+    );
+    let left = panel.center().x - table.width / 2.0;
+    for (i, (row, xs)) in rows.iter().zip(&table.cells).enumerate() {
+        let color = if i == 0 {
+            theme.result
+        } else {
+            theme.entry_text
+        };
+        for (cell, x) in row.iter().zip(xs) {
+            gfx.text(
+                cell,
+                vec2(left + x, baseline(i + 1)),
+                font,
+                font_size,
+                color,
+            );
+        }
+    }
+    let last = rows.len() + 1;
+    gfx.text(
+        hint,
+        vec2(centered(hint), baseline(last)),
+        font,
+        font_size,
+        theme.result,
+    );
+}
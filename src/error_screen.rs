@@ -0,0 +1,55 @@
+//! The screen shown when the app can't continue, e.g. the dictionary failed to
+//! load.
+
+use crate::app::AppContext;
+use crate::events::AppEvent;
+use crate::keymap::Action;
+use crate::render::Renderer;
+use crate::screen::{ScreenTrait, Transition};
+use crate::strings::StringKey;
+use macroquad::prelude::*;
+
+/// What went wrong, in the user's language.
+pub struct ErrorScreen {
+    pub message: String,
+}
+
+impl ErrorScreen {
+    /// Creates a screen showing `message`.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl ScreenTrait for ErrorScreen {
+    // There's nothing to go back to, so Quit leaves the app
+    fn handle_event(&mut self, _ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        match ev {
+            AppEvent::Action(Action::Quit) | AppEvent::CloseRequested => Some(Transition::Quit),
+            _ => None,
+        }
+    }
+    fn update(&mut self, _ctx: &mut AppContext, _dt: f64) -> Option<Transition> {
+        None
+    }
+    /// Draws the title, message, and hint, centered.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        let Vec2 { x: cx, y: cy } = gfx.screen_size() / 2.0;
+
+        gfx.clear(theme.background);
+        let lines = [
+            (lang.text(StringKey::ErrorTitle), cy - 50.0, 40, theme.error),
+            (self.message.as_str(), cy + 10.0, 24, theme.entry_text),
+            (lang.text(StringKey::ErrorHint), cy + 50.0, 20, theme.result),
+        ];
+        for (text, y, size, color) in lines {
+            let width = gfx.measure(text, font, size).width;
+            gfx.text(text, vec2(cx - width / 2.0, y), font, size, color);
+        }
+    }
+}
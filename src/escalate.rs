@@ -0,0 +1,85 @@
+//! Trying looser searches when the exact one finds nothing.
+//!
+//! With `auto_escalate` on, an entry long enough to be a word that has no exact
+//! anagram falls back to the entries one mistyped letter away (see `typo`), and if
+//! there are none of those either, to the words using all but one of its letters.
+//! The results are labeled with the strategy that found them, and never count as
+//! an answer.
+
+use crate::strings::StringKey;
+use crate::MIN_WORD_LENGTH;
+
+/// A way of finding words for the entry, in the order they're tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Words using exactly the entry's letters.
+    #[default]
+    Exact,
+    /// Entries one mistyped letter away that match.
+    Typo,
+    /// Words using all but one of the entry's letters.
+    DropOne,
+}
+
+impl Strategy {
+    /// Returns the strategy to try once this one has found `found` results for
+    /// an entry of `entry_len` letters, or `None` to stop here.
+    ///
+    /// Nothing escalates for an entry too short to be a word, or past a search
+    /// that found something; dropping a letter must leave a word long enough.
+    pub fn next(self, entry_len: usize, found: usize) -> Option<Strategy> {
+        if found > 0 || entry_len < MIN_WORD_LENGTH {
+            return None;
+        }
+        match self {
+            Strategy::Exact => Some(Strategy::Typo),
+            Strategy::Typo if entry_len > MIN_WORD_LENGTH => Some(Strategy::DropOne),
+            Strategy::Typo | Strategy::DropOne => None,
+        }
+    }
+    /// Returns the label for results this strategy found, if they need one.
+    pub fn label(self) -> Option<StringKey> {
+        match self {
+            Strategy::Exact => None,
+            Strategy::Typo => Some(StringKey::EscalatedTypo),
+            Strategy::DropOne => Some(StringKey::EscalatedDropOne),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_are_tried_in_order_while_nothing_is_found() {
+        let len = MIN_WORD_LENGTH + 1;
+        assert_eq!(Strategy::Exact.next(len, 0), Some(Strategy::Typo));
+        assert_eq!(Strategy::Typo.next(len, 0), Some(Strategy::DropOne));
+        assert_eq!(Strategy::DropOne.next(len, 0), None);
+    }
+
+    #[test]
+    fn escalation_stops_once_something_is_found() {
+        let len = MIN_WORD_LENGTH + 1;
+        assert_eq!(Strategy::Exact.next(len, 1), None);
+        assert_eq!(Strategy::Typo.next(len, 3), None);
+    }
+
+    #[test]
+    fn short_entries_never_escalate() {
+        for len in 0..MIN_WORD_LENGTH {
+            assert_eq!(Strategy::Exact.next(len, 0), None);
+            assert_eq!(Strategy::Typo.next(len, 0), None);
+        }
+        // Dropping a letter from the shortest word leaves too few
+        assert_eq!(Strategy::Typo.next(MIN_WORD_LENGTH, 0), None);
+    }
+
+    #[test]
+    fn only_looser_searches_are_labeled() {
+        assert_eq!(Strategy::Exact.label(), None);
+        assert_eq!(Strategy::Typo.label(), Some(StringKey::EscalatedTypo));
+        assert_eq!(Strategy::DropOne.label(), Some(StringKey::EscalatedDropOne));
+    }
+}
@@ -0,0 +1,319 @@
+//! Input and window changes, gathered once per frame as `AppEvent`s.
+//!
+//! Only `poll_events` reads macroquad's input state; everything downstream works
+//! on the events, so it can be driven without a window. Key bindings and letters
+//! go by the modifiers each key went down with, as `ModifierTracker` follows
+//! them, rather than those held at the end of the frame.
+
+use crate::keymap::{Action, Keymap, Modifiers};
+use crate::modal::{Focus, Modal, QuitChoice};
+use crate::modifiers::{is_modifier, ModifierTracker};
+use crate::palette::PaletteKey;
+use crate::pointer::{poll_pointer_events, PointerEvent};
+use macroquad::prelude::*;
+
+/// Something that happened since the last frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppEvent {
+    /// The window's current size; sent every frame.
+    WindowSize(Vec2),
+    /// The DPI scale the platform reports for the window's monitor; sent every
+    /// frame, but only passed on to screens when the scale in use changes.
+    DpiScale(f32),
+    /// The window's close button was pressed.
+    CloseRequested,
+    /// Some key was pressed or released, so the screen may need redrawing.
+    KeyActivity,
+    /// A key binding was pressed.
+    Action(Action),
+    /// The key of a key binding came up, as when letting go of one held down.
+    ActionReleased(Action),
+    /// A letter key, or a key for one of the other characters of query
+    /// commands, was typed.
+    Letter(char),
+    /// Text was pasted (Ctrl+V).
+    Paste(String),
+    /// A key answering the confirm-quit dialog was pressed.
+    QuitChoice(QuitChoice),
+    Pointer(PointerEvent),
+    /// The screen is being touched.
+    Touch,
+    /// The mouse wheel turned by this many notches; positive is up.
+    Wheel(f32),
+    /// A navigation key was pressed.
+    Nav(Nav),
+    /// A key closing the help overlay, or declining a dialog, was pressed.
+    Dismiss,
+    /// A key working the command palette was pressed.
+    PaletteKey(PaletteKey),
+}
+
+/// Keys that move around lists and change values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+}
+
+impl Nav {
+    /// Returns the navigation keys among those `pressed`, ignoring key chords.
+    fn pressed(pressed: &[(KeyCode, Modifiers)]) -> Vec<Nav> {
+        pressed
+            .iter()
+            .filter(|(_, mods)| !mods.is_chord())
+            .filter_map(|&(keycode, _)| match keycode {
+                KeyCode::Up => Some(Nav::Up),
+                KeyCode::Down => Some(Nav::Down),
+                KeyCode::Left => Some(Nav::Left),
+                KeyCode::Right => Some(Nav::Right),
+                KeyCode::Enter | KeyCode::KpEnter => Some(Nav::Select),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Collects this frame's events, in the order they should be handled.
+///
+/// An open dialog takes all input: while `focus` is on one, only its answers are
+/// collected, not key bindings, letters, or pointer input.
+pub fn poll_events(
+    keymap: &Keymap,
+    focus: Focus,
+    modifiers: &mut ModifierTracker,
+) -> Vec<AppEvent> {
+    modifiers.poll();
+    let (pressed, released) = (modifiers.pressed(), modifiers.released());
+    let mut events = vec![
+        AppEvent::WindowSize(vec2(screen_width(), screen_height())),
+        AppEvent::DpiScale(screen_dpi_scale()),
+    ];
+    if is_quit_requested() {
+        events.push(AppEvent::CloseRequested);
+    }
+    if !get_keys_pressed().is_empty() || !get_keys_released().is_empty() {
+        events.push(AppEvent::KeyActivity);
+    }
+    if !touches().is_empty() {
+        events.push(AppEvent::Touch);
+    }
+
+    match focus {
+        Focus::Modal(Modal::ConfirmQuit) => {
+            events.extend(QuitChoice::pressed().map(AppEvent::QuitChoice));
+        }
+        // Enter restores the tabs; Escape starts afresh
+        Focus::Modal(Modal::Restore) => {
+            for key in get_keys_pressed() {
+                match key {
+                    KeyCode::Enter | KeyCode::KpEnter => events.push(AppEvent::Nav(Nav::Select)),
+                    KeyCode::Escape => events.push(AppEvent::Dismiss),
+                    _ => (),
+                }
+            }
+        }
+        Focus::Modal(Modal::Palette) => {
+            events.extend(PaletteKey::pressed().into_iter().map(AppEvent::PaletteKey));
+            let letters = typed_letters(released)
+                .into_iter()
+                .filter(char::is_ascii_alphabetic);
+            events.extend(letters.map(AppEvent::Letter));
+        }
+        // Letters and spaces spell the phrase; Backspace takes one back, and
+        // Escape closes it
+        Focus::Modal(Modal::Pool) => {
+            for key in get_keys_pressed() {
+                match key {
+                    KeyCode::Backspace => events.push(AppEvent::Action(Action::DeleteBack)),
+                    KeyCode::Escape => events.push(AppEvent::Dismiss),
+                    _ => (),
+                }
+            }
+            let letters = typed_letters(released)
+                .into_iter()
+                .filter(|&c| c.is_alphabetic() || c == ' ');
+            events.extend(letters.map(AppEvent::Letter));
+        }
+        // X, C, and B hide, confirm, and pin the word; Escape or Enter closes it
+        Focus::Modal(Modal::Inspect) => {
+            for key in get_keys_pressed() {
+                if matches!(key, KeyCode::Escape | KeyCode::Enter | KeyCode::KpEnter) {
+                    events.push(AppEvent::Dismiss);
+                }
+            }
+            let letters = typed_letters(released)
+                .into_iter()
+                .filter(|c| matches!(c, 'X' | 'C' | 'B'));
+            events.extend(letters.map(AppEvent::Letter));
+        }
+        // Any key or click closes it
+        Focus::Modal(Modal::Ranking) => {
+            let keys = get_keys_pressed().into_iter().any(|key| !is_modifier(key));
+            if keys || is_mouse_button_pressed(MouseButton::Left) || !touches().is_empty() {
+                events.push(AppEvent::Dismiss);
+            }
+        }
+        // Up and Down scroll the help, and Enter starts the tutorial; any other
+        // key closes it
+        Focus::Modal(Modal::Help) => {
+            let mut dismiss = false;
+            for key in get_keys_pressed() {
+                match key {
+                    KeyCode::Up => events.push(AppEvent::Nav(Nav::Up)),
+                    KeyCode::Down => events.push(AppEvent::Nav(Nav::Down)),
+                    KeyCode::Enter | KeyCode::KpEnter => events.push(AppEvent::Nav(Nav::Select)),
+                    key if is_modifier(key) => (),
+                    _ => dismiss = true,
+                }
+            }
+            if dismiss {
+                events.push(AppEvent::Dismiss);
+            }
+            let (_, wheel_y) = mouse_wheel();
+            if wheel_y != 0.0 {
+                events.push(AppEvent::Wheel(wheel_y));
+            }
+        }
+        Focus::Entry => {
+            let actions = keymap.pressed_actions(pressed).into_iter();
+            events.extend(actions.map(AppEvent::Action));
+            let released_actions = keymap.released_actions(released).into_iter();
+            events.extend(released_actions.map(AppEvent::ActionReleased));
+            events.extend(typed_letters(released).into_iter().map(AppEvent::Letter));
+            events.extend(pasted_text(pressed).map(AppEvent::Paste));
+            events.extend(Nav::pressed(pressed).into_iter().map(AppEvent::Nav));
+            events.extend(poll_pointer_events().into_iter().map(AppEvent::Pointer));
+            let (_, wheel_y) = mouse_wheel();
+            if wheel_y != 0.0 {
+                events.push(AppEvent::Wheel(wheel_y));
+            }
+        }
+    }
+    events
+}
+
+/// The number keys along the top of the keyboard, `0` first.
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Returns the clipboard's text if Ctrl+V is among the keys `pressed`.
+fn pasted_text(pressed: &[(KeyCode, Modifiers)]) -> Option<String> {
+    let paste = |&(key, mods): &(KeyCode, Modifiers)| {
+        key == KeyCode::V && mods.ctrl && !mods.alt && !mods.logo
+    };
+    if !pressed.iter().any(paste) {
+        return None;
+    }
+    miniquad::window::clipboard_get().filter(|text| !text.trim().is_empty())
+}
+
+/// Returns the letters typed by the keys `released`, with the other characters
+/// query commands use (`:`, digits, space, `_`, `.`, `=`, and `-`; `?` opens the
+/// help) and `~`, which makes a letter with the one before it in some
+/// alphabets.
+///
+/// Keys that went down with Ctrl, Alt, or Super held belong to key chords and
+/// are ignored; Shift picks the character, as it was when the key went down.
+fn typed_letters(released: &[(KeyCode, Modifiers)]) -> Vec<char> {
+    let digit = |keycode: KeyCode| {
+        let index = DIGIT_KEYS.iter().position(|&key| key == keycode)?;
+        char::from_digit(index as u32, 10)
+    };
+
+    released
+        .iter()
+        .filter(|(_, mods)| !mods.is_chord())
+        .filter_map(|&(keycode, mods)| match keycode {
+            KeyCode::A => Some('A'),
+            KeyCode::B => Some('B'),
+            KeyCode::C => Some('C'),
+            KeyCode::D => Some('D'),
+            KeyCode::E => Some('E'),
+            KeyCode::F => Some('F'),
+            KeyCode::G => Some('G'),
+            KeyCode::H => Some('H'),
+            KeyCode::I => Some('I'),
+            KeyCode::J => Some('J'),
+            KeyCode::K => Some('K'),
+            KeyCode::L => Some('L'),
+            KeyCode::M => Some('M'),
+            KeyCode::N => Some('N'),
+            KeyCode::O => Some('O'),
+            KeyCode::P => Some('P'),
+            KeyCode::Q => Some('Q'),
+            KeyCode::R => Some('R'),
+            KeyCode::S => Some('S'),
+            KeyCode::T => Some('T'),
+            KeyCode::U => Some('U'),
+            KeyCode::V => Some('V'),
+            KeyCode::W => Some('W'),
+            KeyCode::X => Some('X'),
+            KeyCode::Y => Some('Y'),
+            KeyCode::Z => Some('Z'),
+            KeyCode::Semicolon if mods.shift => Some(':'),
+            KeyCode::Minus if mods.shift => Some('_'),
+            KeyCode::GraveAccent if mods.shift => Some('~'),
+            KeyCode::Space => Some(' '),
+            KeyCode::Period => Some('.'),
+            KeyCode::Equal if !mods.shift => Some('='),
+            KeyCode::Minus if !mods.shift => Some('-'),
+            keycode if !mods.shift => digit(keycode),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        shift: false,
+        alt: false,
+        logo: false,
+    };
+    const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..NONE
+    };
+
+    #[test]
+    fn shift_picks_the_character_and_chords_type_nothing() {
+        let released = [
+            (KeyCode::A, SHIFT),
+            (KeyCode::Semicolon, SHIFT),
+            (KeyCode::Key4, NONE),
+            (KeyCode::Minus, NONE),
+            (KeyCode::Minus, SHIFT),
+            (KeyCode::V, Modifiers { ctrl: true, ..NONE }),
+            (KeyCode::G, Modifiers { alt: true, ..NONE }),
+            (KeyCode::K, Modifiers { logo: true, ..NONE }),
+            (KeyCode::Key4, SHIFT),
+        ];
+        assert_eq!(typed_letters(&released), ['A', ':', '4', '-', '_']);
+    }
+
+    #[test]
+    fn navigation_keys_in_chords_are_not_navigation() {
+        let pressed = [
+            (KeyCode::Up, NONE),
+            (KeyCode::Down, SHIFT),
+            (KeyCode::Left, Modifiers { ctrl: true, ..NONE }),
+        ];
+        assert_eq!(Nav::pressed(&pressed), [Nav::Up, Nav::Down]);
+    }
+}
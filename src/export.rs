@@ -0,0 +1,285 @@
+//! A printable page of the results.
+//!
+//! Ctrl+Shift+E writes the open tabs to an HTML page in the `exports` folder of
+//! the data directory and opens it with whatever the system opens web pages
+//! with, ready to print: large serif type, black on white, today's date at the
+//! top, a section per scramble with its matches (the answer in bold), and the
+//! final answer spelled with Alt+F (see `pool`) at the bottom. If nothing will
+//! open it, a toast says where the page is.
+
+pub mod html;
+
+use crate::strings::{fill, Language, StringKey};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::toast::Severity;
+use html::Html;
+
+/// Folder of the data directory the pages are written to.
+pub const EXPORTS_DIR: &str = "exports";
+
+/// Large black serif type on white, with the sections kept whole on a page.
+const PRINT_CSS: &str = "body { font-family: Georgia, 'Times New Roman', serif; \
+    font-size: 20pt; color: black; background: white; margin: 1in; }\n\
+    section { break-inside: avoid; margin-bottom: 1.5em; }\n\
+    h2 { letter-spacing: 0.2em; }\n";
+
+/// A scramble on the page, with its matches.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintSection {
+    pub scramble: String,
+    /// The matches, as the list shows them.
+    pub matches: Vec<String>,
+    /// The unique answer, if there is one.
+    pub answer: Option<String>,
+}
+
+/// What goes on the page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintSheet {
+    /// Today's date, as `YYYY-MM-DD`, if known.
+    pub date: Option<String>,
+    pub sections: Vec<PrintSection>,
+    /// The final answer spelled so far, if any.
+    pub final_answer: Option<String>,
+}
+
+/// Returns `sheet` as an HTML page in `lang`.
+pub fn print_page(sheet: &PrintSheet, lang: Language) -> String {
+    let title = lang.text(StringKey::AppTitle);
+    let mut page = Html::new();
+    page.open("html", &[("lang", lang.code())]);
+    page.open("head", &[]).void("meta", &[("charset", "utf-8")]);
+    page.element("title", title).style(PRINT_CSS).close();
+    page.open("body", &[]);
+    page.open("header", &[]).element("h1", title);
+    if let Some(date) = &sheet.date {
+        page.element("p", date);
+    }
+    page.close();
+    for section in &sheet.sections {
+        page.open("section", &[]).element("h2", &section.scramble);
+        if section.matches.is_empty() {
+            page.element("p", lang.text(StringKey::NoMatches));
+        } else {
+            page.open("ul", &[]);
+            for word in &section.matches {
+                match section.answer.as_ref() == Some(word) {
+                    true => page.open("li", &[]).element("strong", word).close(),
+                    false => page.element("li", word),
+                };
+            }
+            page.close();
+        }
+        page.close();
+    }
+    if let Some(answer) = &sheet.final_answer {
+        let text = fill(lang.text(StringKey::PrintFinalAnswer), answer);
+        page.open("footer", &[]).element("h2", &text).close();
+    }
+    page.finish()
+}
+
+/// Writes `sheet` as a page in `lang` to a new file in `dir`, returning its
+/// path.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_print_page(
+    dir: &std::path::Path,
+    sheet: &PrintSheet,
+    lang: Language,
+) -> std::io::Result<std::path::PathBuf> {
+    let stamp: String = crate::logging::timestamp()
+        .chars()
+        .filter_map(|c| match c {
+            '-' | ':' => None,
+            ' ' => Some('-'),
+            c => Some(c),
+        })
+        .collect();
+    let path = dir.join(format!("print-{stamp}.html"));
+    std::fs::create_dir_all(dir)?;
+    crate::storage::safe_write(&path, print_page(sheet, lang).as_bytes())?;
+    Ok(path)
+}
+
+/// Writes `sheet` as a page in `lang` to `dir` and opens it with `open`,
+/// returning what the toast says about it and how it's marked: where the page
+/// was opened from, where it was saved if `open` fails, or why it couldn't be
+/// written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print_and_open(
+    dir: &std::path::Path,
+    sheet: &PrintSheet,
+    lang: Language,
+    open: impl FnOnce(&std::path::Path) -> std::io::Result<()>,
+) -> (String, Severity) {
+    let (key, value, severity) = match write_print_page(dir, sheet, lang) {
+        Ok(path) => match open(&path) {
+            Ok(()) => (
+                StringKey::PrintOpened,
+                path.display().to_string(),
+                Severity::Info,
+            ),
+            Err(e) => {
+                log::warn!("[print_and_open] can't open the page: {e}");
+                (
+                    StringKey::PrintSaved,
+                    path.display().to_string(),
+                    Severity::Info,
+                )
+            }
+        },
+        Err(e) => {
+            log::error!("[print_and_open] can't write the page: {e}");
+            (StringKey::PrintFailed, e.to_string(), Severity::Error)
+        }
+    };
+    (fill(lang.text(key), value), severity)
+}
+
+/// Opens `path` with the system's default handler, without waiting for it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_file(path: &std::path::Path) -> std::io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        // `start` takes its first quoted argument as the window title
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reaped in the background, so it doesn't linger once done
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty directory of its own under the system's temporary directory.
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn sheet() -> PrintSheet {
+        PrintSheet {
+            date: Some("2026-10-15".into()),
+            sections: vec![
+                PrintSection {
+                    scramble: "DNUOP".into(),
+                    matches: vec!["POUND".into()],
+                    answer: Some("POUND".into()),
+                },
+                PrintSection {
+                    scramble: "TAGER".into(),
+                    matches: vec!["GRATE".into(), "GREAT".into()],
+                    answer: None,
+                },
+                PrintSection {
+                    scramble: "XQZV".into(),
+                    ..Default::default()
+                },
+            ],
+            final_answer: Some("GREAT POUND".into()),
+        }
+    }
+
+    #[test]
+    fn the_page_lists_each_scramble_with_its_answer_in_bold() {
+        let expected = format!(
+            "<!DOCTYPE html>\n\
+             <html lang=\"en\"><head><meta charset=\"utf-8\">\n\
+             <title>Jumble Helper</title>\n\
+             <style>{PRINT_CSS}</style>\n\
+             </head>\n\
+             <body><header><h1>Jumble Helper</h1>\n\
+             <p>2026-10-15</p>\n\
+             </header>\n\
+             <section><h2>DNUOP</h2>\n\
+             <ul><li><strong>POUND</strong>\n\
+             </li>\n\
+             </ul>\n\
+             </section>\n\
+             <section><h2>TAGER</h2>\n\
+             <ul><li>GRATE</li>\n\
+             <li>GREAT</li>\n\
+             </ul>\n\
+             </section>\n\
+             <section><h2>XQZV</h2>\n\
+             <p>No matches.</p>\n\
+             </section>\n\
+             <footer><h2>Final answer: GREAT POUND</h2>\n\
+             </footer>\n\
+             </body>\n\
+             </html>\n"
+        );
+        assert_eq!(print_page(&sheet(), Language::En), expected);
+    }
+
+    #[test]
+    fn words_from_the_list_are_escaped_on_the_page() {
+        let sheet = PrintSheet {
+            sections: vec![PrintSection {
+                scramble: "<B>".into(),
+                matches: vec!["R&D".into(), "<script>".into()],
+                answer: Some("<script>".into()),
+            }],
+            ..Default::default()
+        };
+        let page = print_page(&sheet, Language::Fr);
+        assert!(page.contains("<html lang=\"fr\">"));
+        assert!(page.contains("<h2>&lt;B&gt;</h2>"));
+        assert!(page.contains("<li>R&amp;D</li>"));
+        assert!(page.contains("<strong>&lt;script&gt;</strong>"));
+        assert!(!page.contains("<script>") && !page.contains("<B>"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn the_toast_says_where_the_page_is_whether_or_not_it_opens() {
+        let dir = scratch_dir("export-open");
+        let lang = Language::En;
+        let (text, severity) = print_and_open(&dir, &sheet(), lang, |_| Ok(()));
+        assert!(
+            text.starts_with("Opened ") && text.ends_with(" to print"),
+            "{text}"
+        );
+        assert_eq!(severity, Severity::Info);
+
+        let failed = || Err(std::io::Error::other("no handler for text/html"));
+        let (text, severity) = print_and_open(&dir, &sheet(), lang, |_| failed());
+        let path = text
+            .strip_prefix("Saved ")
+            .unwrap()
+            .strip_suffix(" to print")
+            .unwrap();
+        assert!(path.starts_with(dir.to_str().unwrap()), "{path}");
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .contains("<h2>DNUOP</h2>"));
+        assert_eq!(severity, Severity::Info);
+
+        // A file where the folder should be can't be written into
+        let blocked = dir.join("blocked");
+        std::fs::write(&blocked, "").unwrap();
+        let (text, severity) = print_and_open(&blocked, &sheet(), lang, |_| Ok(()));
+        assert!(
+            text.starts_with("Can't write the page to print: "),
+            "{text}"
+        );
+        assert_eq!(severity, Severity::Error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+//! Building HTML pages with their text escaped.
+//!
+//! `Html` writes elements one after another, escaping every piece of text and
+//! attribute value it's given, so a word list's odd entries can't break the
+//! page. It only knows as much HTML as the printable page needs.
+
+use std::borrow::Cow;
+
+/// Returns `text` with the characters HTML treats specially replaced by
+/// entities, fit for element text and quoted attribute values alike.
+pub fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// An HTML document being written, one element at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Html {
+    out: String,
+    /// The elements opened and not yet closed, innermost last.
+    open: Vec<&'static str>,
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Html {
+    /// Starts a document with its doctype.
+    pub fn new() -> Self {
+        Self {
+            out: String::from("<!DOCTYPE html>\n"),
+            open: Vec::new(),
+        }
+    }
+    /// Opens `tag` with `attrs`, their values escaped.
+    pub fn open(&mut self, tag: &'static str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.start_tag(tag, attrs);
+        self.open.push(tag);
+        self
+    }
+    /// Writes `tag`, an element that never has content or a closing tag, such
+    /// as `meta`, with `attrs`.
+    pub fn void(&mut self, tag: &'static str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.start_tag(tag, attrs);
+        self.out.push('\n');
+        self
+    }
+    fn start_tag(&mut self, tag: &str, attrs: &[(&str, &str)]) {
+        self.out.push('<');
+        self.out.push_str(tag);
+        for (name, value) in attrs {
+            self.out.push_str(&format!(" {name}=\"{}\"", escape(value)));
+        }
+        self.out.push('>');
+    }
+    /// Closes the innermost element open, if any.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(tag) = self.open.pop() {
+            self.out.push_str(&format!("</{tag}>"));
+        }
+        self.out.push('\n');
+        self
+    }
+    /// Writes `text`, escaped.
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.out.push_str(&escape(text));
+        self
+    }
+    /// Writes `tag` holding only `text`, escaped.
+    pub fn element(&mut self, tag: &'static str, text: &str) -> &mut Self {
+        self.open(tag, &[]).text(text).close()
+    }
+    /// Writes `css` into a `<style>` element. It isn't escaped, so it mustn't
+    /// come from outside the app.
+    pub fn style(&mut self, css: &'static str) -> &mut Self {
+        self.open("style", &[]);
+        self.out.push_str(css);
+        self.close()
+    }
+    /// Closes whatever is still open and returns the document.
+    pub fn finish(mut self) -> String {
+        while !self.open.is_empty() {
+            self.close();
+        }
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters_become_entities() {
+        assert_eq!(escape("GRATE"), "GRATE");
+        assert!(matches!(escape("GRATE"), Cow::Borrowed(_)));
+        assert_eq!(
+            escape(r#"<a href="x">R&D's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;R&amp;D&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(escape("&amp;"), "&amp;amp;");
+        assert_eq!(escape("CAFÉ"), "CAFÉ");
+    }
+
+    #[test]
+    fn text_and_attributes_are_escaped_and_elements_closed() {
+        let mut page = Html::new();
+        page.open("p", &[("title", "\"quoted\" & <b>")])
+            .text("1 < 2");
+        page.element("em", "<i>");
+        page.void("br", &[]);
+        page.open("ul", &[]).element("li", "R&D");
+        assert_eq!(
+            page.finish(),
+            "<!DOCTYPE html>\n\
+             <p title=\"&quot;quoted&quot; &amp; &lt;b&gt;\">1 &lt; 2<em>&lt;i&gt;</em>\n\
+             <br>\n\
+             <ul><li>R&amp;D</li>\n\
+             </ul>\n\
+             </p>\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Detecting changes in what the entry matches.
+//!
+//! Feedback such as sounds should fire once when the outcome changes, not on every
+//! frame or keystroke that leaves it the same.
+
+use crate::MIN_WORD_LENGTH;
+
+/// What the entry's matches amount to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchOutcome {
+    /// Too short to be a word.
+    #[default]
+    Incomplete,
+    NoMatch,
+    Unique,
+    Several,
+    /// The letters already spell the only match, so there's no anagram to find.
+    AsTyped,
+}
+
+impl MatchOutcome {
+    /// Classifies an entry of `entry_len` letters with `match_count` matches.
+    pub fn of(entry_len: usize, match_count: usize) -> Self {
+        if entry_len < MIN_WORD_LENGTH {
+            return MatchOutcome::Incomplete;
+        }
+        match match_count {
+            0 => MatchOutcome::NoMatch,
+            1 => MatchOutcome::Unique,
+            _ => MatchOutcome::Several,
+        }
+    }
+}
+
+/// How the entry's own letters, in the order typed, figure among its matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsTyped {
+    /// The letters as typed aren't a word, or aren't one of the matches.
+    #[default]
+    NotAMatch,
+    /// The letters as typed spell the only match.
+    OnlyMatch,
+    /// The letters as typed spell the match at this index, among others.
+    AmongOthers(usize),
+}
+
+impl AsTyped {
+    /// Classifies `entry` against its `matches`; `is_word` says whether the
+    /// dictionary has the entry as a word at all.
+    pub fn of(entry: &str, matches: &[String], is_word: impl FnOnce(&str) -> bool) -> Self {
+        if entry.is_empty() || !is_word(entry) {
+            return AsTyped::NotAMatch;
+        }
+        match matches.iter().position(|word| word == entry) {
+            None => AsTyped::NotAMatch,
+            Some(_) if matches.len() == 1 => AsTyped::OnlyMatch,
+            Some(i) => AsTyped::AmongOthers(i),
+        }
+    }
+    /// Returns `outcome` as it stands once the letters as typed are taken into
+    /// account: a lone match the letters already spell is no answer.
+    pub fn adjust(self, outcome: MatchOutcome) -> MatchOutcome {
+        match (self, outcome) {
+            (AsTyped::OnlyMatch, MatchOutcome::Unique) => MatchOutcome::AsTyped,
+            _ => outcome,
+        }
+    }
+}
+
+/// Remembers the last outcome so only transitions are reported.
+#[derive(Debug, Default)]
+pub struct OutcomeTracker {
+    last: MatchOutcome,
+}
+
+impl OutcomeTracker {
+    /// Records `outcome`, returning it if it differs from the previous one.
+    pub fn update(&mut self, outcome: MatchOutcome) -> Option<MatchOutcome> {
+        if outcome == self.last {
+            return None;
+        }
+        self.last = outcome;
+        Some(outcome)
+    }
+    /// Returns the most recently recorded outcome.
+    pub fn current(&self) -> MatchOutcome {
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcomes_follow_length_then_count() {
+        assert_eq!(
+            MatchOutcome::of(MIN_WORD_LENGTH - 1, 0),
+            MatchOutcome::Incomplete
+        );
+        assert_eq!(
+            MatchOutcome::of(MIN_WORD_LENGTH - 1, 3),
+            MatchOutcome::Incomplete
+        );
+        assert_eq!(MatchOutcome::of(MIN_WORD_LENGTH, 0), MatchOutcome::NoMatch);
+        assert_eq!(MatchOutcome::of(MIN_WORD_LENGTH, 1), MatchOutcome::Unique);
+        assert_eq!(MatchOutcome::of(MIN_WORD_LENGTH, 2), MatchOutcome::Several);
+    }
+
+    #[test]
+    fn only_changes_are_reported() {
+        let mut tracker = OutcomeTracker::default();
+        // Typing P, O, U, N, D, then deleting back to POUN
+        let counts = [(1, 0), (2, 0), (3, 0), (4, 0), (5, 1), (5, 1), (4, 0)];
+        let reported: Vec<_> = counts
+            .into_iter()
+            .filter_map(|(len, count)| tracker.update(MatchOutcome::of(len, count)))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                MatchOutcome::NoMatch,
+                MatchOutcome::Unique,
+                MatchOutcome::NoMatch
+            ]
+        );
+    }
+
+    #[test]
+    fn a_fresh_tracker_stays_quiet_while_incomplete() {
+        let mut tracker = OutcomeTracker::default();
+        assert_eq!(tracker.update(MatchOutcome::Incomplete), None);
+        assert_eq!(
+            tracker.update(MatchOutcome::Several),
+            Some(MatchOutcome::Several)
+        );
+        assert_eq!(
+            tracker.update(MatchOutcome::Incomplete),
+            Some(MatchOutcome::Incomplete)
+        );
+    }
+
+    #[test]
+    fn letters_as_typed_are_classified_against_the_matches() {
+        let matches = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        let is_word = |word: &str| ["POUND", "GREAT", "GRATE"].contains(&word);
+        let of = |entry, words: &[&str]| AsTyped::of(entry, &matches(words), is_word);
+        assert_eq!(of("", &[]), AsTyped::NotAMatch);
+        assert_eq!(of("DNUOP", &["POUND"]), AsTyped::NotAMatch);
+        assert_eq!(of("POUND", &["POUND"]), AsTyped::OnlyMatch);
+        assert_eq!(of("GRATE", &["GREAT", "GRATE"]), AsTyped::AmongOthers(1));
+        // A word the dictionary lacks, however it's spelled, is never as typed
+        assert_eq!(of("UPON", &["UPON"]), AsTyped::NotAMatch);
+    }
+
+    #[test]
+    fn only_a_lone_match_as_typed_stops_being_an_answer() {
+        let unique = MatchOutcome::Unique;
+        assert_eq!(AsTyped::OnlyMatch.adjust(unique), MatchOutcome::AsTyped);
+        assert_eq!(AsTyped::NotAMatch.adjust(unique), unique);
+        let several = MatchOutcome::Several;
+        assert_eq!(AsTyped::AmongOthers(0).adjust(several), several);
+        assert_eq!(
+            AsTyped::OnlyMatch.adjust(MatchOutcome::Incomplete),
+            MatchOutcome::Incomplete
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! Font loading with an embedded default and fallbacks.
+//!
+//! The font loaded is checked for the characters outside ASCII the UI draws,
+//! and those it lacks are drawn as ASCII instead (see `glyphs`).
+
+use crate::glyphs::{check_font, probe, set_missing};
+use macroquad::prelude::*;
+use std::fmt;
+
+/// The default font, embedded so the binary works from any directory.
+const EMBEDDED_FONT: &[u8] = include_bytes!("../fonts/FiraMono-Bold.ttf");
+
+/// Where the font in use came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontSource {
+    /// A font file named in the config.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Config(String),
+    /// The embedded FiraMono Bold.
+    Embedded,
+    /// Macroquad's built-in font (`font: None`).
+    BuiltIn,
+}
+
+impl fmt::Display for FontSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FontSource::Config(path) => write!(f, "config font '{path}'"),
+            FontSource::Embedded => write!(f, "embedded FiraMono Bold"),
+            FontSource::BuiltIn => write!(f, "macroquad built-in font"),
+        }
+    }
+}
+
+/// Loads the font at `path` if given, falling back to the embedded font and then
+/// to macroquad's built-in font, and checks which special characters it lacks.
+pub fn load_font(path: Option<&str>) -> (Option<Font>, FontSource) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = path {
+        let loaded = std::fs::read(crate::assets::resolve_asset(path))
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                let font = load_ttf_font_from_bytes(&bytes).map_err(|e| e.to_string())?;
+                Ok((font, bytes))
+            });
+        match loaded {
+            Ok((font, bytes)) => {
+                check_font(&bytes);
+                return (Some(font), FontSource::Config(path.to_string()));
+            }
+            Err(e) => log::warn!("[load_font] failed to load font '{path}': {e}"),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    if let Some(path) = path {
+        log::warn!("[load_font] font files are not supported on the web: '{path}'");
+    }
+
+    match load_ttf_font_from_bytes(EMBEDDED_FONT) {
+        Ok(font) => {
+            check_font(EMBEDDED_FONT);
+            (Some(font), FontSource::Embedded)
+        }
+        Err(e) => {
+            log::warn!("[load_font] failed to load embedded font: {e}");
+            // Its bytes can't be had to look in, but it stops at Latin-1
+            set_missing(probe(|c| u32::from(c) < 0x100));
+            (None, FontSource::BuiltIn)
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Word frequencies from a table of counts, for ranking matches by how common
+//! they are (see `rank`).
+//!
+//! A word list can carry its counts in a frequency column (see `dictionary`);
+//! one without can take them from a separate table named as `frequencies` in
+//! the `[dictionary]` table of the config, or have them baked in with
+//! `jumble_helper prep`. The table is either of two formats, told apart by its
+//! first line: a Google Books ngram export, a line per word per year of
+//! `word TAB year TAB count TAB volumes`, whose counts are summed across the
+//! years, or lines of a word and its count, such as `beagle 23106`. Words are
+//! uppercased with their accents dropped, and only those in the word list are
+//! kept, so a table of millions of words costs no more memory than the list
+//! does. Tagged ngrams such as `run_VERB` are never in a list, so they're
+//! skipped too.
+
+use crate::dictionary::DictionaryOptions;
+use crate::wordmap::{fold_accents, WordMap};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// The formats `load_table` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// `word TAB year TAB count TAB volumes`, a line per word per year.
+    Ngram,
+    /// `word count`, separated by any whitespace.
+    Counts,
+}
+
+/// Returns the format of a table whose first line is `line`.
+pub fn detect_format(line: &str) -> TableFormat {
+    let fields: Vec<&str> = line.trim_end().split('\t').collect();
+    let numbers = |fields: &[&str]| fields.iter().all(|f| f.parse::<u64>().is_ok());
+    match fields.as_slice() {
+        [_, rest @ ..] if rest.len() == 3 && numbers(rest) => TableFormat::Ngram,
+        _ => TableFormat::Counts,
+    }
+}
+
+/// Returns the word and count on `line` of a table in `format`, if it has them.
+fn parse_line(line: &str, format: TableFormat) -> Option<(&str, u64)> {
+    let (word, count) = match format {
+        TableFormat::Ngram => {
+            let mut fields = line.split('\t');
+            let word = fields.next()?;
+            (word, fields.nth(1)?)
+        }
+        TableFormat::Counts => {
+            let mut fields = line.split_whitespace();
+            (fields.next()?, fields.next()?)
+        }
+    };
+    Some((word, count.trim().parse().ok()?))
+}
+
+/// Reads a table of word counts in either format from `reader`, keeping the
+/// counts of the words in `words`, summed over every line each is on.
+///
+/// Lines without a word and a count are skipped; only a read failing is an
+/// error.
+pub fn load_table(reader: impl BufRead, words: &WordMap) -> std::io::Result<HashMap<String, u64>> {
+    let mut table: HashMap<String, u64> = HashMap::new();
+    let mut format = None;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let format = *format.get_or_insert_with(|| detect_format(&line));
+        let Some((word, count)) = parse_line(&line, format) else {
+            continue;
+        };
+        let word = fold_accents(word);
+        if let Some(total) = table.get_mut(word.as_ref()) {
+            *total = total.saturating_add(count);
+        } else if words.contains(&word) {
+            table.insert(word.into_owned(), count);
+        }
+    }
+    Ok(table)
+}
+
+/// Reads the table at `path` for the words in `words`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_table(path: &std::path::Path, words: &WordMap) -> Result<HashMap<String, u64>, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("can't open '{}': {e}", path.display()))?;
+    load_table(std::io::BufReader::new(file), words)
+        .map_err(|e| format!("can't read '{}': {e}", path.display()))
+}
+
+/// Returns the frequencies for `words`, read from word list `text`: its
+/// frequency column if it has one, else the table `options` names, if any.
+pub fn load_frequencies(
+    text: &str,
+    options: &DictionaryOptions,
+    words: &WordMap,
+) -> HashMap<String, u64> {
+    if words.header().frequency_column {
+        return crate::dictionary::read_frequencies(text);
+    }
+    let Some(path) = options.frequencies.as_deref() else {
+        return HashMap::new();
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    match read_table(&crate::assets::resolve_asset(path), words) {
+        Ok(table) => {
+            log::info!(
+                "[load_frequencies] {} word frequencies from '{path}'",
+                table.len()
+            );
+            table
+        }
+        Err(e) => {
+            log::warn!("[load_frequencies] ranking without frequencies: {e}");
+            HashMap::new()
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        log::warn!("[load_frequencies] frequency tables are not supported on the web: '{path}'");
+        HashMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::wordmap::make_word_map_with_progress;
+
+    fn words() -> WordMap {
+        make_word_map_with_progress(
+            "BEAGLE\nPOUND\nETAGE\n",
+            Alphabet::English,
+            usize::MAX,
+            usize::MAX,
+            |_| (),
+        )
+    }
+
+    fn load(table: &str) -> Vec<(String, u64)> {
+        let mut counts: Vec<_> = load_table(table.as_bytes(), &words())
+            .unwrap()
+            .into_iter()
+            .collect();
+        counts.sort();
+        counts
+    }
+
+    #[test]
+    fn the_first_line_tells_the_format() {
+        assert_eq!(detect_format("beagle\t1999\t40\t12"), TableFormat::Ngram);
+        assert_eq!(
+            detect_format("beagle\t1999\t40\t12\r\n"),
+            TableFormat::Ngram
+        );
+        assert_eq!(detect_format("beagle 23106"), TableFormat::Counts);
+        assert_eq!(detect_format("beagle\t23106"), TableFormat::Counts);
+        // Three tabbed numbers are needed, all of them numbers
+        assert_eq!(detect_format("beagle\t1999\t40"), TableFormat::Counts);
+        assert_eq!(
+            detect_format("beagle\t1999\tforty\t12"),
+            TableFormat::Counts
+        );
+    }
+
+    #[test]
+    fn ngram_counts_are_summed_across_the_years() {
+        let table = "\
+beagle\t1999\t40\t12
+beagle\t2000\t2\t1
+Pound\t2000\t7\t3
+étage\t1950\t5\t5
+ÉTAGE\t1951\t6\t2
+run_VERB\t2000\t900\t90
+latch\t2000\t100\t9
+pound\t2001\tmany\t3
+";
+        let expected = [("BEAGLE", 42), ("ETAGE", 11), ("POUND", 7)];
+        let expected: Vec<_> = expected.map(|(w, n)| (w.to_owned(), n)).into();
+        assert_eq!(load(table), expected);
+    }
+
+    #[test]
+    fn word_count_lines_keep_only_the_list_words() {
+        let table = "beagle 23106\n\nPOUND  900\nlatch 12\npound\netage 4 extra\n";
+        let expected = [("BEAGLE", 23106), ("ETAGE", 4), ("POUND", 900)];
+        let expected: Vec<_> = expected.map(|(w, n)| (w.to_owned(), n)).into();
+        assert_eq!(load(table), expected);
+    }
+
+    #[test]
+    fn an_inline_column_wins_over_a_table() {
+        let options = DictionaryOptions {
+            frequencies: Some("no/such/table.txt".into()),
+            ..DictionaryOptions::default()
+        };
+        let text = "#! frequency_column=true\nBEAGLE 3\n";
+        let mut map = words();
+        map.set_header(crate::dictionary::parse_header(text).unwrap().0);
+        let counts = load_frequencies(text, &options, &map);
+        assert_eq!(counts.get("BEAGLE"), Some(&3));
+        // Without a column, a table that can't be read gives no counts
+        assert!(load_frequencies("BEAGLE\n", &options, &words()).is_empty());
+    }
+}
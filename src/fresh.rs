@@ -0,0 +1,154 @@
+//! Marking the matches a one-letter change of the entry brought in.
+//!
+//! Trying letters in turn for one that might be misread, it's easy to lose
+//! track of which matches are new. When the entry is one letter changed, added,
+//! or taken out from the last entry that had matches, the matches that entry
+//! didn't have are marked "new" in the list for `FRESH_TIME` seconds. An entry
+//! with no matches isn't remembered, so changing a letter by deleting it and
+//! typing another compares with the entry before the deletion.
+
+use std::collections::HashSet;
+
+/// Seconds the matches stay marked new.
+pub const FRESH_TIME: f64 = 4.0;
+
+/// Returns whether `b` is `a` with exactly one letter changed, added, or taken
+/// out.
+pub fn one_edit_apart(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let prefix = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+    match long.len() - short.len() {
+        0 => prefix < short.len() && short[prefix + 1..] == long[prefix + 1..],
+        1 => short[prefix..] == long[prefix + 1..],
+        _ => false,
+    }
+}
+
+/// Returns the words of `current` that aren't in `previous`.
+pub fn new_words(previous: &[String], current: &[String]) -> HashSet<String> {
+    let previous: HashSet<&String> = previous.iter().collect();
+    current
+        .iter()
+        .filter(|word| !previous.contains(word))
+        .cloned()
+        .collect()
+}
+
+/// Keeps the last entry with matches, and which of the current matches are new.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FreshMatches {
+    /// The last entry that had matches, and its matches.
+    previous: Option<(String, Vec<String>)>,
+    /// The matches marked new.
+    fresh: HashSet<String>,
+    /// When the marks come off.
+    until: Option<f64>,
+}
+
+impl FreshMatches {
+    /// Notes that `entry` now has `matches`, at `now`, marking those new if
+    /// `entry` is one edit from the last entry with matches. Matches found again
+    /// for the same entry, such as reordered, keep their marks.
+    pub fn observe(&mut self, entry: &str, matches: &[String], now: f64) {
+        let same = self
+            .previous
+            .as_ref()
+            .is_some_and(|(last, _)| last == entry);
+        if !same {
+            self.fresh = match &self.previous {
+                Some((last, words)) if one_edit_apart(last, entry) => new_words(words, matches),
+                _ => HashSet::new(),
+            };
+            self.until = (!self.fresh.is_empty()).then_some(now + FRESH_TIME);
+        }
+        if !matches.is_empty() {
+            self.previous = Some((entry.to_owned(), matches.to_vec()));
+        }
+    }
+    /// Returns whether `word` is marked new.
+    pub fn is_new(&self, word: &str) -> bool {
+        self.fresh.contains(word)
+    }
+    /// Takes the marks off once their time is up at `now`, returning whether it
+    /// just did.
+    pub fn expire(&mut self, now: f64) -> bool {
+        if self.until.is_some_and(|until| now >= until) {
+            self.fresh.clear();
+            self.until = None;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn one_edit_is_a_change_an_addition_or_a_removal() {
+        assert!(one_edit_apart("TAGER", "TAMER"));
+        assert!(one_edit_apart("TAGER", "XAGER"));
+        assert!(one_edit_apart("TAGER", "TAGEX"));
+        assert!(one_edit_apart("TAGER", "STAGER"));
+        assert!(one_edit_apart("TAGER", "TAGEER"));
+        assert!(one_edit_apart("TAGER", "TAGERS"));
+        assert!(one_edit_apart("TAGERS", "TAGER"));
+        assert!(one_edit_apart("TAGER", "AGER"));
+        assert!(one_edit_apart("", "A"));
+        assert!(one_edit_apart("ÉTAGE", "ETAGE"));
+
+        assert!(!one_edit_apart("TAGER", "TAGER"));
+        assert!(!one_edit_apart("", ""));
+        assert!(!one_edit_apart("TAGER", "TAXEX"));
+        assert!(!one_edit_apart("TAGER", "ATGER"));
+        assert!(!one_edit_apart("TAGER", "TAGERST"));
+        assert!(!one_edit_apart("TAGER", "TGR"));
+        assert!(!one_edit_apart("TAGER", "STAGE"));
+    }
+
+    #[test]
+    fn new_words_are_those_the_last_matches_lacked() {
+        let previous = words(&["GREAT", "GRATE"]);
+        let current = words(&["GRATE", "GATER", "GREAT", "TERGA"]);
+        let fresh = new_words(&previous, &current);
+        assert_eq!(fresh, HashSet::from(["GATER".into(), "TERGA".into()]));
+        assert!(new_words(&current, &previous).is_empty());
+        assert!(new_words(&[], &[]).is_empty());
+        assert_eq!(new_words(&[], &previous).len(), 2);
+    }
+
+    #[test]
+    fn marks_follow_one_letter_changes_and_wear_off() {
+        let mut fresh = FreshMatches::default();
+        fresh.observe("TAGER", &words(&["GREAT", "GRATE"]), 0.0);
+        assert!(!fresh.is_new("GREAT"), "nothing to compare the first with");
+
+        fresh.observe("TAGEX", &[], 1.0);
+        fresh.observe("TAGES", &words(&["GATES", "STAGE"]), 2.0);
+        assert!(fresh.is_new("GATES") && fresh.is_new("STAGE"));
+        // The same entry found again keeps its marks and their time
+        fresh.observe("TAGES", &words(&["STAGE", "GATES"]), 3.0);
+        assert!(fresh.is_new("STAGE"));
+        assert!(!fresh.expire(2.0 + FRESH_TIME - 0.1));
+        assert!(fresh.expire(2.0 + FRESH_TIME));
+        assert!(!fresh.is_new("STAGE"));
+        assert!(!fresh.expire(100.0), "only once");
+
+        // Two letters changed marks nothing
+        fresh.observe("TOGAS", &words(&["GOATS", "TOGAS"]), 10.0);
+        assert!(!fresh.is_new("GOATS"));
+        // Compared with the entry before, not the one before that
+        fresh.observe("TOGAE", &words(&["TOGAE", "STAGE"]), 11.0);
+        assert!(fresh.is_new("TOGAE") && fresh.is_new("STAGE"));
+    }
+}
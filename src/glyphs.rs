@@ -0,0 +1,191 @@
+//! Standing in for characters the font in use can't draw.
+//!
+//! Macroquad draws nothing for a character its font lacks, and measures it as
+//! nothing too, so a font without `Ñ` would drop letters from the tiles and
+//! throw the layout out without a word. `SPECIAL_CHARS` lists every character
+//! outside ASCII the UI draws, each with plain ASCII to draw instead. When a
+//! font is loaded, `check_font` finds which of them it lacks, and from then on
+//! `for_display` swaps those out of the text the window draws and measures.
+//! Only what's drawn changes: the entry, the words, and what's copied or saved
+//! keep their own letters.
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Every character outside ASCII the UI draws, with what to draw instead if the
+/// font lacks it: the confirmed mark, the Spanish alphabet's letter, and those
+/// of the French and Spanish strings. A character added to the UI goes here too.
+pub const SPECIAL_CHARS: &[(char, &str)] = &[
+    ('✓', "*"),
+    ('Ñ', "N~"),
+    ('ñ', "n~"),
+    ('—', "-"),
+    ('À', "A"),
+    ('à', "a"),
+    ('á', "a"),
+    ('ç', "c"),
+    ('É', "E"),
+    ('é', "e"),
+    ('è', "e"),
+    ('ê', "e"),
+    ('í', "i"),
+    ('î', "i"),
+    ('ó', "o"),
+    ('ú', "u"),
+    ('¿', ""),
+    ('¡', ""),
+];
+
+// Each character takes a bit of `MISSING`
+const _: () = assert!(SPECIAL_CHARS.len() <= 32);
+
+/// Which of `SPECIAL_CHARS` the font in use lacks, a bit each, in order.
+static MISSING: AtomicU32 = AtomicU32::new(0);
+
+/// Returns which of `SPECIAL_CHARS` a font lacks, a bit each, as `has_glyph`
+/// says.
+pub fn probe(has_glyph: impl Fn(char) -> bool) -> u32 {
+    SPECIAL_CHARS
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(c, _))| !has_glyph(c))
+        .fold(0, |missing, (i, _)| missing | 1 << i)
+}
+
+/// Returns `text` with each character `missing` marks (see `probe`) swapped for
+/// its fallback.
+pub fn substitute(text: &str, missing: u32) -> Cow<'_, str> {
+    let fallback = |c: char| {
+        let i = SPECIAL_CHARS
+            .iter()
+            .position(|&(special, _)| special == c)?;
+        (missing & 1 << i != 0).then_some(SPECIAL_CHARS[i].1)
+    };
+    if missing == 0 || text.is_ascii() || !text.chars().any(|c| fallback(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+    let mut swapped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match fallback(c) {
+            Some(ascii) => swapped.push_str(ascii),
+            None => swapped.push(c),
+        }
+    }
+    Cow::Owned(swapped)
+}
+
+/// Returns `text` as it's drawn with the font in use.
+pub fn for_display(text: &str) -> Cow<'_, str> {
+    substitute(text, MISSING.load(Ordering::Relaxed))
+}
+
+/// Makes the characters `missing` marks (see `probe`) those swapped out of the
+/// text drawn from now on, saying which they are.
+pub fn set_missing(missing: u32) {
+    MISSING.store(missing, Ordering::Relaxed);
+    let swaps: Vec<String> = SPECIAL_CHARS
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| missing & 1 << i != 0)
+        .map(|(_, (c, ascii))| format!("'{ascii}' for '{c}'"))
+        .collect();
+    if swaps.is_empty() {
+        log::debug!("[set_missing] the font has every special character");
+    } else {
+        log::info!(
+            "[set_missing] the font lacks some characters; drawing {}",
+            swaps.join(", ")
+        );
+    }
+}
+
+/// Finds which of `SPECIAL_CHARS` the font in `bytes` lacks, and swaps them out
+/// of the text drawn from now on.
+pub fn check_font(bytes: &[u8]) {
+    match fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()) {
+        Ok(font) => set_missing(probe(|c| font.lookup_glyph_index(c) != 0)),
+        Err(e) => {
+            log::warn!("[check_font] can't look for characters in the font: {e}");
+            set_missing(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::confirmed::CONFIRMED_MARK;
+
+    /// Returns what a font with only ASCII and `extra` lacks.
+    fn lacking(extra: &str) -> u32 {
+        probe(|c| c.is_ascii() || extra.contains(c))
+    }
+
+    #[test]
+    fn the_probe_marks_each_character_the_font_lacks() {
+        assert_eq!(probe(|_| true), 0);
+        assert_eq!(probe(|_| false).count_ones() as usize, SPECIAL_CHARS.len());
+        let missing = lacking("Ññ—");
+        let swapped: Vec<char> = SPECIAL_CHARS
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| missing & 1 << i != 0)
+            .map(|(_, &(c, _))| c)
+            .collect();
+        assert!(swapped.contains(&'✓') && swapped.contains(&'é'));
+        assert!(!swapped.contains(&'Ñ') && !swapped.contains(&'—'));
+    }
+
+    #[test]
+    fn only_the_characters_lacking_are_swapped() {
+        let missing = lacking("é");
+        assert_eq!(substitute("AÑO ✓", missing), "AN~O *");
+        assert_eq!(substitute("¿Qué año?", missing), "Qué an~o?");
+        assert_eq!(
+            substitute("Réglages — à propos", missing),
+            "Réglages - a propos"
+        );
+        // Characters outside the list are left as they are
+        assert_eq!(substitute("→", missing), "→");
+    }
+
+    #[test]
+    fn text_with_nothing_to_swap_is_borrowed() {
+        assert!(matches!(substitute("AÑO", 0), Cow::Borrowed("AÑO")));
+        assert!(matches!(substitute("PLAIN", u32::MAX), Cow::Borrowed(_)));
+        assert!(matches!(substitute("AÑO", lacking("Ñ")), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn the_list_covers_what_the_ui_draws_with_ascii_stand_ins() {
+        for (i, &(c, ascii)) in SPECIAL_CHARS.iter().enumerate() {
+            assert!(!c.is_ascii() && ascii.is_ascii(), "{c}");
+            assert!(
+                SPECIAL_CHARS[..i].iter().all(|&(other, _)| other != c),
+                "{c}"
+            );
+        }
+        // Every translation is a literal in `strings`
+        let drawn = CONFIRMED_MARK
+            .chars()
+            .chain(Alphabet::Spanish.letters().chars())
+            .chain(include_str!("strings.rs").chars());
+        for c in drawn.filter(|c| !c.is_ascii()) {
+            assert!(
+                SPECIAL_CHARS.iter().any(|&(special, _)| special == c),
+                "{c}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_embedded_font_lacks_only_the_check_mark() {
+        let bytes = include_bytes!("../fonts/FiraMono-Bold.ttf");
+        let font = fontdue::Font::from_bytes(&bytes[..], fontdue::FontSettings::default());
+        let font = font.unwrap();
+        let missing = probe(|c| font.lookup_glyph_index(c) != 0);
+        assert_eq!(substitute(CONFIRMED_MARK, missing), "*");
+        assert_eq!(missing.count_ones(), 1);
+    }
+}
@@ -0,0 +1,408 @@
+//! The help overlay: every key binding, grouped by what it does.
+//!
+//! The list is built from the `Keymap` each time it's shown, so it always matches
+//! the bindings in effect, including overrides from the config.
+
+use crate::keymap::{Action, ActionGroup, Keymap};
+use crate::render::Renderer;
+use crate::strings::{Language, StringKey};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// The bound actions in one group: chords and what they do.
+pub struct HelpGroup {
+    pub group: ActionGroup,
+    pub bindings: Vec<(String, &'static str)>,
+}
+
+/// Returns the bound actions of `keymap`, by group, described in `lang`; unbound
+/// actions and empty groups are left out.
+pub fn help_groups(keymap: &Keymap, lang: Language) -> Vec<HelpGroup> {
+    ActionGroup::ALL
+        .into_iter()
+        .map(|group| HelpGroup {
+            group,
+            bindings: Action::ALL
+                .into_iter()
+                .filter(|action| action.group() == group)
+                .filter_map(|action| {
+                    let chords: Vec<String> =
+                        keymap.chords(action).map(|c| c.to_string()).collect();
+                    (!chords.is_empty())
+                        .then(|| (chords.join(", "), lang.text(action.description())))
+                })
+                .collect(),
+        })
+        .filter(|g| !g.bindings.is_empty())
+        .collect()
+}
+
+/// Where one line of the help goes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HelpRow {
+    pub column: usize,
+    /// Top of the line, from the top of the content.
+    pub y: f32,
+    pub group: usize,
+    /// Index into the group's bindings, or `None` for its heading.
+    pub binding: Option<usize>,
+}
+
+/// The help laid out in one or two columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpLayout {
+    pub columns: usize,
+    pub column_w: f32,
+    /// Width of the chords, before the descriptions.
+    pub key_w: f32,
+    pub rows: Vec<HelpRow>,
+    /// Height of the tallest column.
+    pub height: f32,
+}
+
+impl HelpLayout {
+    /// Returns how far the content scrolls when `visible_h` of it fits.
+    pub fn max_scroll(&self, visible_h: f32) -> f32 {
+        (self.height - visible_h).max(0.0)
+    }
+}
+
+/// Lays out `groups` in `width`: in two columns if they fit side by side,
+/// otherwise one. Groups aren't split across columns; the split balances the
+/// columns' heights.
+pub fn layout_help(
+    groups: &[HelpGroup],
+    lang: Language,
+    line_h: f32,
+    width: f32,
+    width_of: impl Fn(&str) -> f32,
+) -> HelpLayout {
+    let gap = line_h;
+    let bindings = || groups.iter().flat_map(|g| g.bindings.iter());
+    let key_w = widest(bindings().map(|b| b.0.as_str()), &width_of);
+    let desc_w = widest(bindings().map(|b| b.1), &width_of);
+    let title_w = widest(groups.iter().map(|g| lang.text(g.group.title())), &width_of);
+    let column_w = (key_w + gap + desc_w).max(title_w);
+    let columns = if column_w * 2.0 + gap <= width { 2 } else { 1 };
+
+    // A heading, its bindings, and a blank line before the next group
+    let heights: Vec<f32> = groups
+        .iter()
+        .map(|g| (g.bindings.len() + 2) as f32 * line_h)
+        .collect();
+    let total: f32 = heights.iter().sum();
+    let taller = |split: usize| {
+        let first: f32 = heights[..split].iter().sum();
+        first.max(total - first)
+    };
+    // Groups before `split` go in the first column
+    let split = if columns == 2 {
+        (0..=groups.len())
+            .min_by(|&a, &b| taller(a).total_cmp(&taller(b)))
+            .unwrap_or(groups.len())
+    } else {
+        groups.len()
+    };
+
+    let mut rows = Vec::new();
+    let mut y = [0.0_f32; 2];
+    for (gi, g) in groups.iter().enumerate() {
+        let column = usize::from(gi >= split);
+        rows.push(HelpRow {
+            column,
+            y: y[column],
+            group: gi,
+            binding: None,
+        });
+        y[column] += line_h;
+        for bi in 0..g.bindings.len() {
+            rows.push(HelpRow {
+                column,
+                y: y[column],
+                group: gi,
+                binding: Some(bi),
+            });
+            y[column] += line_h;
+        }
+        y[column] += line_h;
+    }
+
+    HelpLayout {
+        columns,
+        column_w,
+        key_w,
+        rows,
+        // The last group's trailing blank line isn't shown
+        height: (y[0].max(y[1]) - line_h).max(0.0),
+    }
+}
+
+/// Returns the width of the widest of `texts`.
+fn widest<'a>(texts: impl Iterator<Item = &'a str>, width_of: &impl Fn(&str) -> f32) -> f32 {
+    texts.map(width_of).fold(0.0, f32::max)
+}
+
+/// Returns the height of one line of help at `font_size`.
+pub fn help_line_h(font_size: u16) -> f32 {
+    font_size as f32 * 1.4
+}
+
+/// Returns the help panel and, inside it, the area its bindings scroll within.
+pub fn help_panel(screen: Vec2, font_size: u16) -> (Rect, Rect) {
+    let margin = screen.x.min(screen.y) * 0.05;
+    let panel = Rect::new(
+        margin,
+        margin,
+        screen.x - margin * 2.0,
+        screen.y - margin * 2.0,
+    );
+    let pad = font_size as f32;
+    let line_h = help_line_h(font_size);
+    let content = Rect::new(
+        panel.x + pad,
+        panel.y + pad + line_h * 1.5,
+        (panel.w - pad * 2.0).max(0.0),
+        (panel.h - pad * 2.0 - line_h * 2.5).max(0.0),
+    );
+    (panel, content)
+}
+
+/// Draws the help over a dimmed screen, with the bindings scrolled by `scroll`.
+///
+/// Lines that don't fit the content area are left out, and arrows show which way
+/// there's more.
+pub fn draw_help(
+    gfx: &dyn Renderer,
+    groups: &[HelpGroup],
+    scroll: f32,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+    lang: Language,
+) {
+    let screen = gfx.screen_size();
+    let (panel, content) = help_panel(screen, font_size);
+    let line_h = help_line_h(font_size);
+    let layout = layout_help(groups, lang, line_h, content.w, |text| {
+        gfx.measure(text, font, font_size).width
+    });
+    let scroll = scroll.clamp(0.0, layout.max_scroll(content.h));
+
+    let mut dim = theme.background;
+    dim.a = 0.7;
+    let mut fill = theme.background;
+    fill.a = 0.92;
+    gfx.rect(Rect::new(0.0, 0.0, screen.x, screen.y), dim);
+    gfx.rect(panel, fill);
+    gfx.rect_lines(panel, theme.outline_thickness, theme.box_outline);
+
+    let baseline = |top: f32| top + line_h * 0.75;
+    let title = lang.text(StringKey::HelpTitle);
+    let title_pos = vec2(content.x, baseline(panel.y + font_size as f32));
+    gfx.text(title, title_pos, font, font_size, theme.entry_text);
+
+    for row in &layout.rows {
+        let top = content.y + row.y - scroll;
+        if top < content.y || top + line_h > content.bottom() {
+            continue;
+        }
+        let x = content.x + row.column as f32 * (layout.column_w + line_h);
+        let group = &groups[row.group];
+        match row.binding {
+            None => {
+                let heading = lang.text(group.group.title());
+                gfx.text(
+                    heading,
+                    vec2(x, baseline(top)),
+                    font,
+                    font_size,
+                    theme.highlight,
+                );
+            }
+            Some(i) => {
+                let (chords, description) = &group.bindings[i];
+                gfx.text(
+                    chords,
+                    vec2(x, baseline(top)),
+                    font,
+                    font_size,
+                    theme.entry_text,
+                );
+                let desc_x = x + layout.key_w + line_h;
+                gfx.text(
+                    description,
+                    vec2(desc_x, baseline(top)),
+                    font,
+                    font_size,
+                    theme.result,
+                );
+            }
+        }
+    }
+
+    // Arrows at the right edge when there's more above or below
+    let (ax, r) = (content.right() - line_h * 0.25, line_h * 0.25);
+    if scroll > 0.0 {
+        let y = content.y + r;
+        gfx.triangle(
+            vec2(ax, y - r),
+            vec2(ax - r, y + r),
+            vec2(ax + r, y + r),
+            theme.highlight,
+        );
+    }
+    if scroll < layout.max_scroll(content.h) {
+        let y = content.bottom() - r;
+        gfx.triangle(
+            vec2(ax - r, y - r),
+            vec2(ax + r, y - r),
+            vec2(ax, y + r),
+            theme.highlight,
+        );
+    }
+
+    let hint = lang.text(StringKey::HelpHint);
+    let hint_pos = vec2(content.x, baseline(content.bottom() + line_h * 0.25));
+    gfx.text(hint, hint_pos, font, font_size, theme.result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Ten pixels a character.
+    fn width_of(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    fn bindings(groups: &[HelpGroup], group: ActionGroup) -> Vec<(String, &'static str)> {
+        let group = groups.iter().find(|g| g.group == group).unwrap();
+        group.bindings.clone()
+    }
+
+    #[test]
+    fn groups_follow_the_keymap_in_group_order() {
+        let groups = help_groups(&Keymap::defaults(), Language::En);
+        let order: Vec<ActionGroup> = groups.iter().map(|g| g.group).collect();
+        assert_eq!(order, ActionGroup::ALL);
+        assert_eq!(
+            bindings(&groups, ActionGroup::Editing),
+            [
+                ("Backspace".to_owned(), "Delete last letter"),
+                ("Delete".to_owned(), "Clear entry"),
+                ("Ctrl+C".to_owned(), "Copy the answer"),
+                (
+                    "Ctrl+Shift+T".to_owned(),
+                    "Mark the answer as seen in print"
+                ),
+                ("Tab".to_owned(), "Type the rest of the word suggested"),
+            ]
+        );
+        let described: usize = groups.iter().map(|g| g.bindings.len()).sum();
+        assert_eq!(described, Action::ALL.len());
+    }
+
+    #[test]
+    fn overrides_show_and_unbound_actions_are_left_out() {
+        let overrides = BTreeMap::from([
+            ("quit".to_owned(), "Ctrl+Q, F9".to_owned()),
+            ("delete_back".to_owned(), String::new()),
+            ("clear_entry".to_owned(), String::new()),
+            ("copy_answer".to_owned(), String::new()),
+            ("confirm_answer".to_owned(), String::new()),
+            ("complete".to_owned(), String::new()),
+        ]);
+        let keymap = Keymap::with_overrides(&overrides).unwrap();
+        let groups = help_groups(&keymap, Language::En);
+        assert!(groups.iter().all(|g| g.group != ActionGroup::Editing));
+        let quit = bindings(&groups, ActionGroup::Application);
+        assert!(
+            quit.contains(&("Ctrl+Q, F9".to_owned(), "Quit")),
+            "{quit:?}"
+        );
+    }
+
+    #[test]
+    fn descriptions_are_in_the_configured_language() {
+        let groups = help_groups(&Keymap::defaults(), Language::Fr);
+        let editing = bindings(&groups, ActionGroup::Editing);
+        assert_eq!(editing[0].1, Language::Fr.text(StringKey::ActionDeleteBack));
+        assert_ne!(editing[0].1, Language::En.text(StringKey::ActionDeleteBack));
+    }
+
+    #[test]
+    fn wide_areas_get_two_balanced_columns() {
+        let groups = help_groups(&Keymap::defaults(), Language::En);
+        let layout = layout_help(&groups, Language::En, 20.0, 2000.0, width_of);
+        assert_eq!(layout.columns, 2);
+        let height_of = |column: usize| {
+            let rows = layout.rows.iter().filter(|r| r.column == column);
+            rows.map(|r| r.y + 20.0).fold(0.0, f32::max)
+        };
+        assert!(height_of(0) > 0.0 && height_of(1) > 0.0);
+        assert_eq!(layout.height, height_of(0).max(height_of(1)));
+
+        // No other split of whole groups has a shorter tallest column
+        let heights: Vec<f32> = groups
+            .iter()
+            .map(|g| (g.bindings.len() + 2) as f32 * 20.0)
+            .collect();
+        let total: f32 = heights.iter().sum();
+        let best = (0..=groups.len())
+            .map(|split| {
+                let first: f32 = heights[..split].iter().sum();
+                first.max(total - first) - 20.0
+            })
+            .fold(f32::MAX, f32::min);
+        assert_eq!(layout.height, best);
+    }
+
+    #[test]
+    fn groups_stay_in_one_column() {
+        let groups = help_groups(&Keymap::defaults(), Language::En);
+        let layout = layout_help(&groups, Language::En, 20.0, 2000.0, width_of);
+        for (gi, _) in groups.iter().enumerate() {
+            let mut columns = layout
+                .rows
+                .iter()
+                .filter(|r| r.group == gi)
+                .map(|r| r.column);
+            let first = columns.next().unwrap();
+            assert!(columns.all(|c| c == first));
+        }
+    }
+
+    #[test]
+    fn narrow_areas_get_one_column() {
+        let groups = help_groups(&Keymap::defaults(), Language::En);
+        let layout = layout_help(&groups, Language::En, 20.0, 300.0, width_of);
+        assert_eq!(layout.columns, 1);
+        assert!(layout.rows.iter().all(|r| r.column == 0));
+        let lines: usize = groups.iter().map(|g| g.bindings.len() + 2).sum();
+        assert_eq!(layout.height, (lines - 1) as f32 * 20.0);
+    }
+
+    #[test]
+    fn content_too_tall_for_the_panel_scrolls() {
+        let groups = help_groups(&Keymap::defaults(), Language::En);
+        let layout = layout_help(&groups, Language::En, 20.0, 300.0, width_of);
+        assert_eq!(layout.max_scroll(layout.height + 50.0), 0.0);
+        assert_eq!(layout.max_scroll(layout.height - 50.0), 50.0);
+
+        let (_, content) = help_panel(vec2(320.0, 200.0), 20);
+        assert!(layout.max_scroll(content.h) > 0.0);
+    }
+
+    #[test]
+    fn the_panel_keeps_its_content_inside() {
+        let (panel, content) = help_panel(vec2(800.0, 600.0), 20);
+        assert_eq!(panel, Rect::new(30.0, 30.0, 740.0, 540.0));
+        assert!(content.x >= panel.x && content.right() <= panel.right());
+        assert!(content.y >= panel.y && content.bottom() <= panel.bottom());
+
+        // Too small for any content
+        let (_, content) = help_panel(vec2(40.0, 40.0), 20);
+        assert_eq!((content.w, content.h), (0.0, 0.0));
+    }
+}
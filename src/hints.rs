@@ -0,0 +1,190 @@
+//! Hints about the answer's shape, for solving it without being told.
+//!
+//! Alt+G hides the matches and shows the first hint about the top candidate;
+//! each press after that shows the next, vaguest first, and the press after the
+//! last shows the matches again. A hint is a rule about the word's spelling
+//! that holds for it, such as "Contains a double letter". The hints start over
+//! when the entry changes. The rules look at the word with its accents dropped,
+//! and the endings they name are English ones.
+
+use crate::render::Renderer;
+use crate::strings::{fill, Language, StringKey};
+use crate::theme::Theme;
+use crate::wordmap::{fold_accents, fold_letter};
+use macroquad::prelude::*;
+
+/// Two-letter spellings common enough to hint at.
+const DIGRAPHS: [&str; 6] = ["TH", "CH", "SH", "PH", "WH", "QU"];
+/// Common endings, hinted at in this order.
+const SUFFIXES: [&str; 8] = ["ING", "TION", "NESS", "EST", "ED", "ER", "LY", "S"];
+/// Common beginnings, hinted at in this order.
+const PREFIXES: [&str; 5] = ["UN", "RE", "DIS", "PRE", "OVER"];
+
+/// A fact that may hold for a word, and how to tell.
+pub struct HintRule {
+    /// The hint as shown when the rule holds, with `{}` filled by `arg`.
+    pub text: StringKey,
+    pub arg: Option<&'static str>,
+    /// Tells whether the rule holds for a word folded by `fold_accents`.
+    pub applies: Box<dyn Fn(&str) -> bool>,
+}
+
+impl HintRule {
+    fn new(text: StringKey, applies: impl Fn(&str) -> bool + 'static) -> Self {
+        Self {
+            text,
+            arg: None,
+            applies: Box::new(applies),
+        }
+    }
+
+    fn with_arg(
+        text: StringKey,
+        arg: &'static str,
+        applies: impl Fn(&str) -> bool + 'static,
+    ) -> Self {
+        Self {
+            arg: Some(arg),
+            ..Self::new(text, applies)
+        }
+    }
+
+    /// Returns the hint in `lang`.
+    pub fn text(&self, lang: Language) -> String {
+        match self.arg {
+            Some(arg) => fill(lang.text(self.text), arg),
+            None => lang.text(self.text).to_owned(),
+        }
+    }
+}
+
+/// Returns whether `c` is a vowel, accented or not (`Y` isn't counted).
+fn is_vowel(c: char) -> bool {
+    matches!(fold_letter(c), Some('A' | 'E' | 'I' | 'O' | 'U'))
+}
+
+/// Returns the hint rules, from the vaguest to the most telling.
+pub fn hint_rules() -> Vec<HintRule> {
+    let first = |word: &str| word.chars().next();
+    let mut rules = vec![
+        HintRule::new(StringKey::HintStartsVowel, move |w| {
+            first(w).is_some_and(is_vowel)
+        }),
+        HintRule::new(StringKey::HintStartsConsonant, move |w| {
+            first(w).is_some_and(|c| !is_vowel(c))
+        }),
+        HintRule::new(StringKey::HintEndsVowel, |w| {
+            w.chars().last().is_some_and(is_vowel)
+        }),
+        HintRule::new(StringKey::HintConsonantCluster, |w| {
+            let start: Vec<char> = w.chars().take(2).collect();
+            start.len() == 2 && !start.iter().copied().any(is_vowel)
+        }),
+        HintRule::new(StringKey::HintDoubleLetter, |w| {
+            w.chars().zip(w.chars().skip(1)).any(|(a, b)| a == b)
+        }),
+    ];
+    rules.extend(DIGRAPHS.map(|digraph| {
+        HintRule::with_arg(StringKey::HintContains, digraph, move |w| {
+            w.contains(digraph)
+        })
+    }));
+    rules.extend(PREFIXES.map(|prefix| {
+        HintRule::with_arg(StringKey::HintStartsWith, prefix, move |w| {
+            w.chars().count() > prefix.len() + 2 && w.starts_with(prefix)
+        })
+    }));
+    rules.extend(SUFFIXES.map(|suffix| {
+        HintRule::with_arg(StringKey::HintEndsIn, suffix, move |w| {
+            w.chars().count() > suffix.len() + 2 && w.ends_with(suffix)
+        })
+    }));
+    rules
+}
+
+/// Returns the rules that hold for `word`, in the order of `rules`. Accents are
+/// dropped first, so `ÉTÉ` starts with a vowel and `ÉCOLE` with `E`.
+pub fn applying<'r>(rules: &'r [HintRule], word: &str) -> Vec<&'r HintRule> {
+    let word = fold_accents(word);
+    rules.iter().filter(|rule| (rule.applies)(&word)).collect()
+}
+
+/// Draws `hints`, one per line down from the top of `area`, centered, leaving
+/// out lines that don't fit.
+pub fn draw_hints(
+    gfx: &dyn Renderer,
+    hints: &[String],
+    area: Rect,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let line_h = size as f32 * 1.4;
+    for (i, hint) in hints.iter().enumerate() {
+        let baseline = area.y + size as f32 + i as f32 * line_h;
+        if baseline > area.bottom() {
+            break;
+        }
+        let w = gfx.measure(hint, font, size).width;
+        let pos = vec2(area.center().x - w / 2.0, baseline);
+        gfx.text(hint, pos, font, size, theme.highlight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(word: &str) -> Vec<String> {
+        let rules = hint_rules();
+        let held = applying(&rules, word);
+        held.iter().map(|rule| rule.text(Language::En)).collect()
+    }
+
+    #[test]
+    fn vowels_and_consonants_at_the_ends() {
+        assert_eq!(hints("OPERA"), ["Starts with a vowel", "Ends in a vowel"]);
+        assert_eq!(
+            hints("GRAPH")[..2],
+            ["Starts with a consonant", "Starts with a consonant cluster"]
+        );
+        assert!(!hints("GAME").contains(&"Starts with a consonant cluster".to_owned()));
+        assert!(!hints("YARD").contains(&"Starts with a vowel".to_owned()));
+    }
+
+    #[test]
+    fn accented_letters_count_as_their_plain_ones() {
+        assert_eq!(hints("ÉTÉ"), ["Starts with a vowel", "Ends in a vowel"]);
+        assert_eq!(hints("été"), hints("ETE"));
+        assert!(hints("ÉCOLE").contains(&"Ends in a vowel".to_owned()));
+        assert!(is_vowel('ü') && is_vowel('Â') && !is_vowel('Ç'));
+    }
+
+    #[test]
+    fn double_letters_and_digraphs() {
+        assert!(hints("BALLOON").contains(&"Contains a double letter".to_owned()));
+        assert!(!hints("BALON").contains(&"Contains a double letter".to_owned()));
+        assert!(hints("ETHER").contains(&"Contains TH".to_owned()));
+        assert!(hints("ÉQUIPE").contains(&"Contains QU".to_owned()));
+    }
+
+    #[test]
+    fn prefixes_and_suffixes_need_a_long_enough_word() {
+        assert!(hints("UNDONE").contains(&"Starts with UN-".to_owned()));
+        assert!(!hints("UNDO").contains(&"Starts with UN-".to_owned()));
+        assert!(hints("WALKING").contains(&"Ends in -ING".to_owned()));
+        assert!(!hints("KING").contains(&"Ends in -ING".to_owned()));
+        let long = hints("OVERTHINKING");
+        assert!(long.contains(&"Starts with OVER-".to_owned()));
+        assert!(long.contains(&"Ends in -ING".to_owned()));
+    }
+
+    #[test]
+    fn hints_are_translated_with_their_spelling_filled_in() {
+        let rules = hint_rules();
+        let held = applying(&rules, "ETHER");
+        let french: Vec<String> = held.iter().map(|rule| rule.text(Language::Fr)).collect();
+        assert_eq!(french[0], "Commence par une voyelle");
+        assert!(french.contains(&"Contient TH".to_owned()));
+    }
+}
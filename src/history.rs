@@ -0,0 +1,302 @@
+//! The unique answers found, day by day.
+//!
+//! The syndicated Jumble reuses scrambles every few years, so when an answer
+//! comes up for a scramble it was found for on an earlier day, the entry screen
+//! says when. `jumble_helper history` summarizes the file.
+//!
+//! Each line of `HISTORY_KEY` is one answer: `YYYY-MM-DD SCRAMBLE ANSWER`.
+//! Only the newest `HISTORY_CAPACITY` answers are kept there; older ones are
+//! moved to `HISTORY_ARCHIVE_KEY`, in the same format, when the history is
+//! read. On the desktop the archive is gzipped.
+
+use crate::date::{format_day, parse_day};
+use crate::memory::MemorySize;
+use crate::storage::{Format, Storage};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::io;
+
+/// Storage key of the answer history.
+pub const HISTORY_KEY: &str = "history.txt";
+/// Format of `HISTORY_KEY`.
+pub const HISTORY_FORMAT: Format = Format {
+    name: "history",
+    version: 1,
+};
+/// Storage key of the answers too old to keep in `HISTORY_KEY`, oldest first.
+pub const HISTORY_ARCHIVE_KEY: &str = "history-archive.txt";
+/// Most answers kept in `HISTORY_KEY`.
+pub const HISTORY_CAPACITY: usize = 10_000;
+/// Answers `history` lists when `--top` gives no number.
+pub const DEFAULT_TOP: usize = 10;
+
+/// An answer found for a scramble on a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solve {
+    /// Local calendar day, as days since 1970-01-01 (see `date`).
+    pub day: i64,
+    pub scramble: String,
+    pub answer: String,
+}
+
+/// Every answer found, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    pub solves: Vec<Solve>,
+}
+
+impl History {
+    /// Parses a history file, skipping (and logging) lines it can't read.
+    pub fn parse(text: &str) -> Self {
+        let mut solves = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let solve = match line.split(' ').collect::<Vec<_>>()[..] {
+                [day, scramble, answer] => parse_day(day).map(|day| Solve {
+                    day,
+                    scramble: scramble.to_owned(),
+                    answer: answer.to_owned(),
+                }),
+                _ => None,
+            };
+            match solve {
+                Some(solve) => solves.push(solve),
+                None => log::warn!("[History::parse] skipping line {}: '{line}'", i + 1),
+            }
+        }
+        Self { solves }
+    }
+    /// Writes the history in the file format above.
+    pub fn to_text(&self) -> String {
+        self.solves
+            .iter()
+            .map(|s| format!("{} {} {}\n", format_day(s.day), s.scramble, s.answer))
+            .collect()
+    }
+    /// Returns the latest day before `day` that `answer` was found for `scramble`.
+    pub fn last_seen(&self, scramble: &str, answer: &str, day: i64) -> Option<i64> {
+        self.solves
+            .iter()
+            .filter(|s| s.day < day && s.scramble == scramble && s.answer == answer)
+            .map(|s| s.day)
+            .max()
+    }
+    /// Adds `answer` for `scramble` on `day`, returning false if it was already
+    /// there for that day.
+    pub fn record(&mut self, day: i64, scramble: &str, answer: &str) -> bool {
+        let known = self
+            .solves
+            .iter()
+            .any(|s| s.day == day && s.scramble == scramble && s.answer == answer);
+        if !known {
+            self.solves.push(Solve {
+                day,
+                scramble: scramble.to_owned(),
+                answer: answer.to_owned(),
+            });
+        }
+        !known
+    }
+    /// Removes all but the newest `keep` answers, returning the rest, oldest
+    /// first.
+    pub fn trim(&mut self, keep: usize) -> History {
+        let excess = self.solves.len().saturating_sub(keep);
+        History {
+            solves: self.solves.drain(..excess).collect(),
+        }
+    }
+    /// Returns the `n` answers found most often with their counts, most often
+    /// first and ties alphabetically.
+    pub fn top_answers(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for solve in &self.solves {
+            *counts.entry(solve.answer.as_str()).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .sorted_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)))
+            .take(n)
+            .collect()
+    }
+    /// Returns the longest run of consecutive days with an answer found, as its
+    /// first day and length; the earliest of equally long runs.
+    pub fn longest_streak(&self) -> Option<(i64, usize)> {
+        let days: Vec<i64> = self.solves.iter().map(|s| s.day).sorted().dedup().collect();
+        let mut best: Option<(i64, usize)> = None;
+        let mut start = 0;
+        for i in 0..days.len() {
+            let ends = i + 1 == days.len() || days[i + 1] != days[i] + 1;
+            if ends {
+                let len = i + 1 - start;
+                if best.is_none_or(|(_, best_len)| len > best_len) {
+                    best = Some((days[start], len));
+                }
+                start = i + 1;
+            }
+        }
+        best
+    }
+}
+
+impl MemorySize for Solve {
+    fn memory_size(&self) -> usize {
+        self.scramble.memory_size() + self.answer.memory_size()
+    }
+}
+
+impl MemorySize for History {
+    fn memory_size(&self) -> usize {
+        self.solves.memory_size()
+    }
+}
+
+/// Returns every answer found, the archived ones and then those in
+/// `HISTORY_KEY`, or why either can't be read.
+pub fn load_history(storage: &dyn Storage) -> io::Result<History> {
+    let with_key =
+        |key: &'static str| move |e: io::Error| io::Error::new(e.kind(), format!("{key}: {e}"));
+    let archived = storage
+        .read_archive(HISTORY_ARCHIVE_KEY)
+        .map_err(with_key(HISTORY_ARCHIVE_KEY))?;
+    let live = storage
+        .read_format(HISTORY_KEY, HISTORY_FORMAT)
+        .map_err(with_key(HISTORY_KEY))?;
+    let mut history = History::parse(&archived.unwrap_or_default());
+    history
+        .solves
+        .append(&mut History::parse(&live.unwrap_or_default()).solves);
+    Ok(history)
+}
+
+/// Prints the `top` answers found most often and the longest daily streak from
+/// the history in the user data directory, returning the exit code: 0, or 2 if
+/// the history can't be read.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_history(top: usize) -> i32 {
+    let history = match load_history(&*crate::storage::default_storage()) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("[run_history] can't read the history: {e}");
+            return 2;
+        }
+    };
+    if history.solves.is_empty() {
+        println!("no answers found yet");
+        return 0;
+    }
+    println!("{} answers found; most often:", history.solves.len());
+    for (answer, count) in history.top_answers(top) {
+        println!("{count:>6}  {answer}");
+    }
+    if let Some((start, len)) = history.longest_streak() {
+        let end = start + len as i64 - 1;
+        let (start, end) = (format_day(start), format_day(end));
+        println!("longest streak: {len} day(s), {start} to {end}");
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    /// Answers found around the US clock changes of 2023: every day from the
+    /// 10th to the 14th of March, across the change on the 12th, then a gap,
+    /// then three days up to the change on the 5th of November.
+    const FIXTURE: &str = "\
+# jumble_helper history v1
+2023-03-10 TLAE LATE
+2023-03-11 GERTA GREAT
+2023-03-12 UKCQA QUACK
+2023-03-12 TLAE TALE
+2023-03-13 TLAE LATE
+2023-03-14 RGEAT GREAT
+2023-03-16 TLAE LATE
+2023-11-03 UKCQA QUACK
+2023-11-04 GERTA GREAT
+2023-11-05 TLAE LATE
+";
+
+    fn fixture() -> History {
+        History::parse(FIXTURE)
+    }
+
+    #[test]
+    fn parses_and_writes_back_the_same_answers() {
+        let history = fixture();
+        assert_eq!(history.solves.len(), 10);
+        assert_eq!(History::parse(&history.to_text()), history);
+        let skipped = History::parse("2023-02-30 TLAE LATE\nTLAE LATE\n2023-03-01 TLAE LATE\n");
+        assert_eq!(skipped.solves.len(), 1);
+    }
+
+    #[test]
+    fn streaks_run_across_clock_changes_and_stop_at_gaps() {
+        let (start, len) = fixture().longest_streak().unwrap();
+        assert_eq!((format_day(start).as_str(), len), ("2023-03-10", 5));
+        assert_eq!(History::default().longest_streak(), None);
+    }
+
+    #[test]
+    fn equal_streaks_keep_the_earliest() {
+        let history =
+            History::parse("2023-11-04 A B\n2023-11-05 A B\n2023-03-11 A B\n2023-03-12 A B\n");
+        let (start, len) = history.longest_streak().unwrap();
+        assert_eq!((format_day(start).as_str(), len), ("2023-03-11", 2));
+    }
+
+    #[test]
+    fn top_answers_break_ties_alphabetically() {
+        let history = fixture();
+        let top = history.top_answers(3);
+        assert_eq!(top, [("LATE", 4), ("GREAT", 3), ("QUACK", 2)]);
+    }
+
+    #[test]
+    fn last_seen_is_the_latest_earlier_day() {
+        let history = fixture();
+        let day = parse_day("2023-11-06").unwrap();
+        let seen = history.last_seen("TLAE", "LATE", day).map(format_day);
+        assert_eq!(seen.as_deref(), Some("2023-11-05"));
+        let day = parse_day("2023-03-10").unwrap();
+        assert_eq!(history.last_seen("TLAE", "LATE", day), None);
+        assert_eq!(history.last_seen("TLAE", "TEAL", day + 400), None);
+    }
+
+    #[test]
+    fn recording_skips_answers_already_found_that_day() {
+        let mut history = fixture();
+        let day = parse_day("2023-11-05").unwrap();
+        assert!(!history.record(day, "TLAE", "LATE"));
+        assert!(history.record(day, "TLAE", "TALE"));
+        assert!(history.record(day + 1, "TLAE", "LATE"));
+        assert_eq!(history.solves.len(), 12);
+        assert_eq!(history.last_seen("TLAE", "LATE", day + 1), Some(day));
+    }
+
+    #[test]
+    fn loading_reads_the_archive_then_the_live_file() {
+        let storage = MemoryStorage::default();
+        assert_eq!(load_history(&storage).unwrap(), History::default());
+        let mut live = fixture();
+        let older = live.trim(4);
+        let (older, live) = (older.to_text(), live.to_text());
+        storage.archive(HISTORY_ARCHIVE_KEY, &older).unwrap();
+        storage
+            .write_format(HISTORY_KEY, HISTORY_FORMAT, &live)
+            .unwrap();
+        assert_eq!(load_history(&storage).unwrap(), fixture());
+        // A file from a newer release is refused, naming it
+        let newer = Format {
+            version: HISTORY_FORMAT.version + 1,
+            ..HISTORY_FORMAT
+        };
+        storage.write_format(HISTORY_KEY, newer, "").unwrap();
+        let e = load_history(&storage).unwrap_err();
+        assert!(e.to_string().starts_with(HISTORY_KEY), "{e}");
+    }
+}
@@ -0,0 +1,235 @@
+//! The word inspector: everything known about one result, in one place.
+//!
+//! Ctrl+Enter on the selected result (or the answer) opens it over the screen.
+//! It shows the word's sorted letters, the other words filed under them, the
+//! word list it came from, how common the list says it is, its Scrabble points,
+//! and whether it's confirmed, hidden, or pinned. X hides the word (adding it
+//! to `MASK_KEY`), C confirms it, and B pins it, without closing the inspector;
+//! Escape or Enter closes it.
+
+use crate::modal::draw_panel;
+use crate::rank::scrabble_points;
+use crate::render::Renderer;
+use crate::strings::{Language, StringKey};
+use crate::table::{layout_table, Align};
+use crate::theme::Theme;
+use crate::wordmap::WordMap;
+use macroquad::prelude::*;
+
+/// What the word list says about a word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordInfo {
+    pub word: String,
+    /// The sorted letters the word is filed under.
+    pub key: String,
+    /// The other words filed under `key`, as listed.
+    pub anagrams: Vec<String>,
+    /// The word list's name, if its header gives one.
+    pub list_name: Option<String>,
+    /// How common the word list says the word is, if it says.
+    pub frequency: Option<u64>,
+    pub scrabble: u32,
+}
+
+impl WordInfo {
+    /// Gathers what `dict` knows about `word`.
+    pub fn gather(dict: &WordMap, word: &str) -> Self {
+        let key = dict.alphabet().key(word);
+        let anagrams = dict
+            .get(&key)
+            .unwrap_or_default()
+            .iter()
+            .filter(|other| *other != word)
+            .cloned()
+            .collect();
+        Self {
+            word: word.to_owned(),
+            key,
+            anagrams,
+            list_name: dict.header().name.clone(),
+            frequency: dict.frequency(word),
+            scrabble: scrabble_points(word),
+        }
+    }
+}
+
+/// What the app has done with a word, as opposed to what the word list says.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WordFlags {
+    pub confirmed: bool,
+    /// On the mask, whether or not masking is on.
+    pub hidden: bool,
+    pub pinned: bool,
+}
+
+/// Returns the inspector's rows, a label and a value each, for `info` and
+/// `flags`, naming the word list `source` if its header gives no name.
+pub fn inspector_rows(
+    info: &WordInfo,
+    flags: WordFlags,
+    source: &str,
+    lang: Language,
+) -> Vec<Vec<String>> {
+    let yes_no = |flag: bool| match flag {
+        true => lang.text(StringKey::InspectYes).to_owned(),
+        false => lang.text(StringKey::InspectNo).to_owned(),
+    };
+    let none = || lang.text(StringKey::InspectNone).to_owned();
+    let anagrams = match info.anagrams.is_empty() {
+        true => none(),
+        false => info.anagrams.join(", "),
+    };
+    let values = [
+        (StringKey::InspectKey, info.key.clone()),
+        (StringKey::InspectAnagrams, anagrams),
+        (
+            StringKey::InspectSource,
+            info.list_name.clone().unwrap_or_else(|| source.to_owned()),
+        ),
+        (
+            StringKey::InspectFrequency,
+            info.frequency.map_or_else(none, |n| n.to_string()),
+        ),
+        (StringKey::InspectScrabble, info.scrabble.to_string()),
+        (StringKey::InspectConfirmed, yes_no(flags.confirmed)),
+        (StringKey::InspectHidden, yes_no(flags.hidden)),
+        (StringKey::InspectPinned, yes_no(flags.pinned)),
+    ];
+    values
+        .into_iter()
+        .map(|(label, value)| vec![lang.text(label).to_owned(), value])
+        .collect()
+}
+
+/// Draws the inspector for `info` and `flags` in a dialog over a dimmed screen:
+/// the word as its title, its rows (see `inspector_rows`), and the keys.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_inspector(
+    gfx: &dyn Renderer,
+    info: &WordInfo,
+    flags: WordFlags,
+    source: &str,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+    lang: Language,
+) {
+    let measure = |text: &str| gfx.measure(text, font, font_size).width;
+    let rows = inspector_rows(info, flags, source, lang);
+    let table = layout_table(
+        &rows,
+        &[Align::Left, Align::Left],
+        font_size as f32,
+        measure,
+    );
+    let (title, hint) = (info.word.as_str(), lang.text(StringKey::InspectHint));
+
+    let line_h = font_size as f32 * 1.4;
+    let pad = font_size as f32;
+    let w = table.width.max(measure(title)).max(measure(hint)) + pad * 2.0;
+    let h = line_h * (rows.len() + 2) as f32 + pad * 2.0;
+    let panel = draw_panel(gfx, w, h, theme);
+
+    let baseline = |line: usize| panel.y + pad + line_h * (line as f32 + 0.75);
+    let centered = |text: &str| panel.center().x - measure(text) / 2.0;
+    gfx.text(
+        title,
+        vec2(centered(title), baseline(0)),
+        font,
+        font_size,
+        theme.highlight,
+    );
+    let left = panel.center().x - table.width / 2.0;
+    for (i, (row, xs)) in rows.iter().zip(&table.cells).enumerate() {
+        let colors = [theme.result, theme.entry_text];
+        for ((cell, x), color) in row.iter().zip(xs).zip(colors) {
+            gfx.text(
+                cell,
+                vec2(left + x, baseline(i + 1)),
+                font,
+                font_size,
+                color,
+            );
+        }
+    }
+    let last = rows.len() + 1;
+    gfx.text(
+        hint,
+        vec2(centered(hint), baseline(last)),
+        font,
+        font_size,
+        theme.result,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::dictionary::DictionaryHeader;
+    use crate::wordmap::make_word_map_with_progress;
+    use std::collections::HashMap;
+
+    fn word_map() -> WordMap {
+        let words = "GREAT\nGRATE\nTERGA\nUPON\n";
+        let mut map =
+            make_word_map_with_progress(words, Alphabet::English, usize::MAX, usize::MAX, |_| ());
+        map.set_header(DictionaryHeader {
+            name: Some("Test words".to_owned()),
+            ..DictionaryHeader::default()
+        });
+        map.set_frequencies(HashMap::from([("GREAT".to_owned(), 900)]));
+        map
+    }
+
+    #[test]
+    fn gathering_finds_the_key_anagrams_source_and_scores() {
+        let info = WordInfo::gather(&word_map(), "GREAT");
+        assert_eq!(
+            info,
+            WordInfo {
+                word: "GREAT".to_owned(),
+                key: "AEGRT".to_owned(),
+                anagrams: vec!["GRATE".to_owned(), "TERGA".to_owned()],
+                list_name: Some("Test words".to_owned()),
+                frequency: Some(900),
+                scrabble: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn what_the_list_doesnt_say_is_left_empty() {
+        let info = WordInfo::gather(&word_map(), "UPON");
+        assert!(info.anagrams.is_empty());
+        assert_eq!(info.frequency, None);
+
+        let map = make_word_map_with_progress(
+            "UPON\n",
+            Alphabet::English,
+            usize::MAX,
+            usize::MAX,
+            |_| (),
+        );
+        assert_eq!(WordInfo::gather(&map, "UPON").list_name, None);
+    }
+
+    #[test]
+    fn rows_fall_back_to_the_source_and_say_none() {
+        let info = WordInfo::gather(&word_map(), "UPON");
+        let info = WordInfo {
+            list_name: None,
+            ..info
+        };
+        let flags = WordFlags {
+            pinned: true,
+            ..WordFlags::default()
+        };
+        let rows = inspector_rows(&info, flags, "words.txt", Language::En);
+        let values: Vec<&str> = rows.iter().map(|row| row[1].as_str()).collect();
+        assert_eq!(
+            values,
+            ["NOPU", "none", "words.txt", "none", "6", "no", "no", "yes"]
+        );
+    }
+}
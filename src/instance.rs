@@ -0,0 +1,241 @@
+//! Keeping to one running app at a time.
+//!
+//! The first launch locks `instance.lock` in the data directory, listens on a
+//! localhost port, and writes its process id and the port into `instance.info`
+//! beside it. A later launch finds the lock held, reads the port from the info
+//! file, sends it a raise message, and exits. The info file is a separate one
+//! because on Windows a locked file can't be read by anyone else. The lock
+//! belongs to the process, so a crash releases it and the files left behind are
+//! just taken over by the next launch.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// The file the running app holds locked.
+pub const LOCK_FILE: &str = "instance.lock";
+/// The file naming the running app's process and port.
+pub const INFO_FILE: &str = "instance.info";
+/// Sent by a later launch to ask the running app to come forward.
+const RAISE: &str = "raise";
+/// The running app's answer to `RAISE`.
+const RAISED: &str = "ok";
+/// How long a later launch waits on the running app's port.
+const TIMEOUT: Duration = Duration::from_millis(500);
+/// Times a later launch looks for the running app's port before giving up; it
+/// may have the lock but not yet have written the port.
+const ATTEMPTS: u32 = 20;
+/// Time between those looks.
+const RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// What a launch found.
+pub enum Startup {
+    /// No other app was running; this one now is.
+    First(InstanceGuard),
+    /// Another app was running, and was asked to come forward.
+    AlreadyRunning,
+}
+
+/// Holds the lock while the app runs, and hears from later launches.
+pub struct InstanceGuard {
+    /// Locked until dropped.
+    _file: File,
+    raised: Receiver<()>,
+}
+
+impl InstanceGuard {
+    /// Returns whether a later launch asked to raise the app since the last call.
+    pub fn take_raised(&self) -> bool {
+        self.raised.try_iter().count() > 0
+    }
+}
+
+/// Takes the lock in `data_dir`, or asks the app holding it to come forward.
+///
+/// Errs if the lock can't be opened, or it's held but the app holding it
+/// doesn't answer.
+pub fn acquire(data_dir: &Path) -> io::Result<Startup> {
+    fs::create_dir_all(data_dir)?;
+    let path = data_dir.join(LOCK_FILE);
+    for _ in 0..ATTEMPTS {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        match file.try_lock() {
+            Ok(()) => return hold(file, data_dir).map(Startup::First),
+            Err(fs::TryLockError::WouldBlock) => (),
+            Err(fs::TryLockError::Error(e)) => return Err(e),
+        }
+        // Missing until the holder has written it, and stale if a crashed
+        // holder's was left behind
+        let holder = fs::read_to_string(data_dir.join(INFO_FILE)).unwrap_or_default();
+        if let Some((pid, port)) = parse_holder(&holder) {
+            if raise(port).is_ok() {
+                log::info!("[acquire] already running as process {pid}; asked it to come forward");
+                return Ok(Startup::AlreadyRunning);
+            }
+        }
+        std::thread::sleep(RETRY_DELAY);
+    }
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("{path:?} is locked, but the app holding it didn't answer"),
+    ))
+}
+
+/// Returns whether an app holds the lock in `data_dir`.
+pub fn is_running(data_dir: &Path) -> bool {
+    File::open(data_dir.join(LOCK_FILE))
+        .is_ok_and(|file| matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock)))
+}
+
+/// Starts listening for later launches and names this process and its port in
+/// the info file in `data_dir`, now that `file` is locked.
+///
+/// The info file is written through a temporary one and renamed into place, so
+/// a later launch never reads it half written.
+fn hold(file: File, data_dir: &Path) -> io::Result<InstanceGuard> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let temp = data_dir.join(format!("{INFO_FILE}.tmp"));
+    fs::write(&temp, format!("{} {port}\n", std::process::id()))?;
+    fs::rename(&temp, data_dir.join(INFO_FILE))?;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || listen(listener, tx));
+    Ok(InstanceGuard {
+        _file: file,
+        raised: rx,
+    })
+}
+
+/// Answers raise messages on `listener`, passing each on to `tx`, until the
+/// guard is dropped.
+fn listen(listener: TcpListener, tx: Sender<()>) {
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        let _ = stream.set_read_timeout(Some(TIMEOUT));
+        let mut line = String::new();
+        if BufReader::new(&stream).read_line(&mut line).is_err() || line.trim() != RAISE {
+            log::warn!("[listen] ignored a message that wasn't a raise: {line:?}");
+            continue;
+        }
+        if tx.send(()).is_err() {
+            return;
+        }
+        let _ = writeln!(stream, "{RAISED}");
+    }
+}
+
+/// Returns the process id and port in an info file's `text`, or `None` if it
+/// doesn't name them.
+fn parse_holder(text: &str) -> Option<(u32, u16)> {
+    let (pid, port) = text.strip_suffix('\n')?.split_once(' ')?;
+    Some((pid.parse().ok()?, port.parse().ok()?))
+}
+
+/// Asks the app listening on `port` to come forward, waiting for its answer.
+fn raise(port: u16) -> io::Result<()> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    writeln!(stream, "{RAISE}")?;
+    let mut answer = String::new();
+    BufReader::new(&stream).read_line(&mut answer)?;
+    match answer.trim() == RAISED {
+        true => Ok(()),
+        false => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected answer {answer:?}"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Barrier};
+
+    /// A data directory of its own under the system's temporary directory,
+    /// emptied.
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn a_crashed_holders_files_are_taken_over() {
+        let dir = data_dir("instance-stale");
+        fs::write(dir.join(LOCK_FILE), "").unwrap();
+        fs::write(dir.join(INFO_FILE), "99999 1\n").unwrap();
+        assert!(!is_running(&dir));
+        let Ok(Startup::First(guard)) = acquire(&dir) else {
+            panic!("the stale lock wasn't taken over");
+        };
+        assert!(is_running(&dir));
+        let info = fs::read_to_string(dir.join(INFO_FILE)).unwrap();
+        let (pid, port) = parse_holder(&info).unwrap();
+        assert_eq!(pid, std::process::id());
+        assert_ne!(port, 1);
+        drop(guard);
+        assert!(!is_running(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn of_two_launches_at_once_one_holds_and_raises_for_the_other() {
+        let dir = data_dir("instance-race");
+        let barrier = Arc::new(Barrier::new(2));
+        let launches: Vec<_> = (0..2)
+            .map(|_| {
+                let (dir, barrier) = (dir.clone(), barrier.clone());
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    acquire(&dir).unwrap()
+                })
+            })
+            .collect();
+        let mut startups: Vec<Startup> = launches.into_iter().map(|l| l.join().unwrap()).collect();
+        startups.sort_by_key(|startup| matches!(startup, Startup::AlreadyRunning));
+        let [Startup::First(guard), Startup::AlreadyRunning] = &startups[..] else {
+            panic!("expected one holder and one raise");
+        };
+        assert!(guard.take_raised());
+        assert!(!guard.take_raised());
+        drop(startups);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_held_lock_whose_holder_never_answers_is_an_error() {
+        let dir = data_dir("instance-silent");
+        let lock = File::create(dir.join(LOCK_FILE)).unwrap();
+        lock.lock().unwrap();
+        // A port nothing listens on any more
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .port();
+        fs::write(dir.join(INFO_FILE), format!("1 {port}\n")).unwrap();
+        let e = acquire(&dir).err().unwrap();
+        assert_eq!(e.kind(), io::ErrorKind::TimedOut);
+        drop(lock);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn info_needs_a_whole_line() {
+        assert_eq!(parse_holder("12 345\n"), Some((12, 345)));
+        assert_eq!(parse_holder("12 34"), None);
+        assert_eq!(parse_holder(""), None);
+        assert_eq!(parse_holder("12\n"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! On-screen letter buttons for touch screens.
+
+use crate::alphabet::Alphabet;
+use crate::letters::LetterMask;
+use crate::render::Renderer;
+use crate::strings::{Language, StringKey};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// A button on the on-screen keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyButton {
+    Letter(char),
+    DeleteBack,
+    Clear,
+}
+
+impl KeyButton {
+    fn label(self, lang: Language) -> String {
+        match self {
+            KeyButton::Letter(c) => c.to_string(),
+            KeyButton::DeleteBack => lang.text(StringKey::KeyDelete).into(),
+            KeyButton::Clear => lang.text(StringKey::KeyClear).into(),
+        }
+    }
+}
+
+/// Lays out the buttons for `alphabet`'s letters in a QWERTY grid filling `area`.
+///
+/// The bottom row gains Clear and Delete buttons on either side of the letters.
+pub fn keyboard_buttons(area: Rect, alphabet: Alphabet) -> Vec<(KeyButton, Rect)> {
+    let rows = alphabet.keyboard_rows();
+    let cols = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(1) as f32;
+    let key_w = area.w / cols;
+    let key_h = area.h / rows.len() as f32;
+    let pad = (key_w.min(key_h) * 0.06).max(1.0);
+    let mut buttons = Vec::new();
+
+    for (row_i, row) in rows.iter().enumerate() {
+        let y = area.y + row_i as f32 * key_h;
+        let row_x = area.x + (cols - row.chars().count() as f32) * key_w / 2.0;
+        for (col_i, c) in row.chars().enumerate() {
+            let x = row_x + col_i as f32 * key_w;
+            let rect = Rect::new(x + pad, y + pad, key_w - 2.0 * pad, key_h - 2.0 * pad);
+            buttons.push((KeyButton::Letter(c), rect));
+        }
+    }
+
+    let last_y = area.y + 2.0 * key_h + pad;
+    let side_w = 1.5 * key_w - 2.0 * pad;
+    buttons.push((
+        KeyButton::Clear,
+        Rect::new(area.x + pad, last_y, side_w, key_h - 2.0 * pad),
+    ));
+    buttons.push((
+        KeyButton::DeleteBack,
+        Rect::new(
+            area.right() - 1.5 * key_w + pad,
+            last_y,
+            side_w,
+            key_h - 2.0 * pad,
+        ),
+    ));
+    buttons
+}
+
+/// Returns the button under `pos`, if any.
+pub fn hit_test(buttons: &[(KeyButton, Rect)], pos: Vec2) -> Option<KeyButton> {
+    buttons
+        .iter()
+        .find(|(_, rect)| rect.contains(pos))
+        .map(|(button, _)| *button)
+}
+
+/// Draws the on-screen keyboard, greying out the letters `letters` leaves out.
+pub fn draw_keyboard(
+    gfx: &dyn Renderer,
+    buttons: &[(KeyButton, Rect)],
+    letters: &LetterMask,
+    font: Option<&Font>,
+    theme: &Theme,
+    lang: Language,
+) {
+    for (button, rect) in buttons {
+        let left_out = matches!(button, KeyButton::Letter(c) if letters.has(*c));
+        let (mut outline, mut color) = (theme.box_outline, theme.entry_text);
+        if left_out {
+            outline.a *= 0.3;
+            color.a *= 0.3;
+        }
+        gfx.rect_lines(*rect, theme.outline_thickness, outline);
+        let label = button.label(lang);
+        let size = (rect.h * 0.5) as u16;
+        let dims = gfx.measure(&label, font, size);
+        let pos = vec2(
+            rect.x + (rect.w - dims.width) / 2.0,
+            rect.y + (rect.h + dims.offset_y) / 2.0,
+        );
+        gfx.text(&label, pos, font, size, color);
+    }
+}
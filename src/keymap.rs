@@ -0,0 +1,822 @@
+//! Key bindings mapping logical actions to key chords.
+//!
+//! Bindings can be overridden in the `[keys]` table of the config file, e.g.
+//!
+//! ```toml
+//! [keys]
+//! quit = "Ctrl+Q"
+//! text_larger = "Ctrl+Equal, Ctrl+KpAdd"
+//! ```
+
+use crate::strings::{Language, StringKey};
+use macroquad::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Logical actions that can be bound to key chords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    DeleteBack,
+    ClearEntry,
+    Quit,
+    ToggleDebug,
+    CycleTheme,
+    ToggleSubAnagrams,
+    ToggleMute,
+    ToggleBigMode,
+    ToggleMini,
+    TextLarger,
+    TextSmaller,
+    TextReset,
+    OpenSettings,
+    ShowHelp,
+    CommandPalette,
+    NewTab,
+    CloseTab,
+    NextTab,
+    CycleStats,
+    TogglePin,
+    ClearPins,
+    ShowLength4,
+    ShowLength5,
+    ShowLength6,
+    ShowLength7,
+    ShowLength8,
+    ShowLength9,
+    ShowAllLengths,
+    ShowHint,
+    TogglePhonetic,
+    CopyAnswer,
+    ExplainRanking,
+    ConfirmAnswer,
+    RevealHidden,
+    SpellFinal,
+    Complete,
+    InspectWord,
+    PrintResults,
+    ToggleRevealLock,
+}
+
+impl Action {
+    pub const ALL: [Action; 39] = [
+        Action::DeleteBack,
+        Action::ClearEntry,
+        Action::Quit,
+        Action::ToggleDebug,
+        Action::CycleTheme,
+        Action::ToggleSubAnagrams,
+        Action::ToggleMute,
+        Action::ToggleBigMode,
+        Action::ToggleMini,
+        Action::TextLarger,
+        Action::TextSmaller,
+        Action::TextReset,
+        Action::OpenSettings,
+        Action::ShowHelp,
+        Action::CommandPalette,
+        Action::NewTab,
+        Action::CloseTab,
+        Action::NextTab,
+        Action::CycleStats,
+        Action::TogglePin,
+        Action::ClearPins,
+        Action::ShowLength4,
+        Action::ShowLength5,
+        Action::ShowLength6,
+        Action::ShowLength7,
+        Action::ShowLength8,
+        Action::ShowLength9,
+        Action::ShowAllLengths,
+        Action::ShowHint,
+        Action::TogglePhonetic,
+        Action::CopyAnswer,
+        Action::ExplainRanking,
+        Action::ConfirmAnswer,
+        Action::RevealHidden,
+        Action::SpellFinal,
+        Action::Complete,
+        Action::InspectWord,
+        Action::PrintResults,
+        Action::ToggleRevealLock,
+    ];
+
+    /// Returns the action's name as used in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::DeleteBack => "delete_back",
+            Action::ClearEntry => "clear_entry",
+            Action::Quit => "quit",
+            Action::ToggleDebug => "toggle_debug",
+            Action::CycleTheme => "cycle_theme",
+            Action::ToggleSubAnagrams => "toggle_sub_anagrams",
+            Action::ToggleMute => "toggle_mute",
+            Action::ToggleBigMode => "toggle_big_mode",
+            Action::ToggleMini => "toggle_mini",
+            Action::TextLarger => "text_larger",
+            Action::TextSmaller => "text_smaller",
+            Action::TextReset => "text_reset",
+            Action::OpenSettings => "open_settings",
+            Action::ShowHelp => "show_help",
+            Action::CommandPalette => "command_palette",
+            Action::NewTab => "new_tab",
+            Action::CloseTab => "close_tab",
+            Action::NextTab => "next_tab",
+            Action::CycleStats => "cycle_stats",
+            Action::TogglePin => "toggle_pin",
+            Action::ClearPins => "clear_pins",
+            Action::ShowLength4 => "show_length_4",
+            Action::ShowLength5 => "show_length_5",
+            Action::ShowLength6 => "show_length_6",
+            Action::ShowLength7 => "show_length_7",
+            Action::ShowLength8 => "show_length_8",
+            Action::ShowLength9 => "show_length_9",
+            Action::ShowAllLengths => "show_all_lengths",
+            Action::ShowHint => "show_hint",
+            Action::TogglePhonetic => "toggle_phonetic",
+            Action::CopyAnswer => "copy_answer",
+            Action::ExplainRanking => "explain_ranking",
+            Action::ConfirmAnswer => "confirm_answer",
+            Action::RevealHidden => "reveal_hidden",
+            Action::SpellFinal => "spell_final",
+            Action::Complete => "complete",
+            Action::InspectWord => "inspect_word",
+            Action::PrintResults => "print_results",
+            Action::ToggleRevealLock => "toggle_reveal_lock",
+        }
+    }
+    /// Returns the action with config name `name`, if any.
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+    /// Returns the text describing the action.
+    pub fn description(self) -> StringKey {
+        match self {
+            Action::DeleteBack => StringKey::ActionDeleteBack,
+            Action::ClearEntry => StringKey::ActionClearEntry,
+            Action::Quit => StringKey::ActionQuit,
+            Action::ToggleDebug => StringKey::ActionToggleDebug,
+            Action::CycleTheme => StringKey::ActionCycleTheme,
+            Action::ToggleSubAnagrams => StringKey::ActionToggleSubAnagrams,
+            Action::ToggleMute => StringKey::ActionToggleMute,
+            Action::ToggleBigMode => StringKey::ActionToggleBigMode,
+            Action::TextLarger => StringKey::ActionTextLarger,
+            Action::TextSmaller => StringKey::ActionTextSmaller,
+            Action::TextReset => StringKey::ActionTextReset,
+            Action::OpenSettings => StringKey::ActionOpenSettings,
+            Action::ShowHelp => StringKey::ActionShowHelp,
+            Action::CommandPalette => StringKey::ActionCommandPalette,
+            Action::NewTab => StringKey::ActionNewTab,
+            Action::CloseTab => StringKey::ActionCloseTab,
+            Action::NextTab => StringKey::ActionNextTab,
+            Action::CycleStats => StringKey::ActionCycleStats,
+            Action::TogglePin => StringKey::ActionTogglePin,
+            Action::ClearPins => StringKey::ActionClearPins,
+            Action::ShowLength4 => StringKey::ActionShowLength4,
+            Action::ShowLength5 => StringKey::ActionShowLength5,
+            Action::ShowLength6 => StringKey::ActionShowLength6,
+            Action::ShowLength7 => StringKey::ActionShowLength7,
+            Action::ShowLength8 => StringKey::ActionShowLength8,
+            Action::ShowLength9 => StringKey::ActionShowLength9,
+            Action::ShowAllLengths => StringKey::ActionShowAllLengths,
+            Action::ShowHint => StringKey::ActionShowHint,
+            Action::TogglePhonetic => StringKey::ActionTogglePhonetic,
+            Action::CopyAnswer => StringKey::ActionCopyAnswer,
+            Action::ExplainRanking => StringKey::ActionExplainRanking,
+            Action::ConfirmAnswer => StringKey::ActionConfirmAnswer,
+            Action::RevealHidden => StringKey::ActionRevealHidden,
+            Action::SpellFinal => StringKey::ActionSpellFinal,
+            Action::ToggleMini => StringKey::ActionToggleMini,
+            Action::Complete => StringKey::ActionComplete,
+            Action::InspectWord => StringKey::ActionInspectWord,
+            Action::PrintResults => StringKey::ActionPrintResults,
+            Action::ToggleRevealLock => StringKey::ActionToggleRevealLock,
+        }
+    }
+    /// Returns the group the action is listed under in the help overlay.
+    pub fn group(self) -> ActionGroup {
+        match self {
+            Action::DeleteBack
+            | Action::ClearEntry
+            | Action::CopyAnswer
+            | Action::ConfirmAnswer
+            | Action::Complete => ActionGroup::Editing,
+            Action::ToggleSubAnagrams
+            | Action::ToggleBigMode
+            | Action::ToggleMini
+            | Action::CycleTheme
+            | Action::TextLarger
+            | Action::TextSmaller
+            | Action::TextReset
+            | Action::ShowHint
+            | Action::TogglePhonetic
+            | Action::RevealHidden
+            | Action::ToggleRevealLock => ActionGroup::Modes,
+            Action::ToggleDebug
+            | Action::ShowHelp
+            | Action::CommandPalette
+            | Action::CycleStats
+            | Action::ExplainRanking
+            | Action::SpellFinal
+            | Action::InspectWord => ActionGroup::Overlays,
+            Action::NewTab | Action::CloseTab | Action::NextTab => ActionGroup::Tabs,
+            Action::TogglePin | Action::ClearPins => ActionGroup::Pins,
+            Action::ShowLength4
+            | Action::ShowLength5
+            | Action::ShowLength6
+            | Action::ShowLength7
+            | Action::ShowLength8
+            | Action::ShowLength9
+            | Action::ShowAllLengths => ActionGroup::Lengths,
+            Action::ToggleMute | Action::OpenSettings | Action::PrintResults | Action::Quit => {
+                ActionGroup::Application
+            }
+        }
+    }
+    /// Returns whether the action is also worked by holding its chord, so
+    /// letting go of it matters.
+    pub fn is_held(self) -> bool {
+        self == Action::ToggleRevealLock
+    }
+    /// Returns the word length the action shows or hides, for the `ShowLength`
+    /// actions.
+    pub fn shown_length(self) -> Option<usize> {
+        match self {
+            Action::ShowLength4 => Some(4),
+            Action::ShowLength5 => Some(5),
+            Action::ShowLength6 => Some(6),
+            Action::ShowLength7 => Some(7),
+            Action::ShowLength8 => Some(8),
+            Action::ShowLength9 => Some(9),
+            _ => None,
+        }
+    }
+}
+
+/// Groups of related actions, in the order the help overlay lists them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionGroup {
+    Editing,
+    Modes,
+    Overlays,
+    Tabs,
+    Pins,
+    Lengths,
+    Application,
+}
+
+impl ActionGroup {
+    pub const ALL: [ActionGroup; 7] = [
+        ActionGroup::Editing,
+        ActionGroup::Modes,
+        ActionGroup::Overlays,
+        ActionGroup::Tabs,
+        ActionGroup::Pins,
+        ActionGroup::Lengths,
+        ActionGroup::Application,
+    ];
+
+    /// Returns the group's heading.
+    pub fn title(self) -> StringKey {
+        match self {
+            ActionGroup::Editing => StringKey::HelpEditing,
+            ActionGroup::Modes => StringKey::HelpModes,
+            ActionGroup::Overlays => StringKey::HelpOverlays,
+            ActionGroup::Tabs => StringKey::HelpTabs,
+            ActionGroup::Pins => StringKey::HelpPins,
+            ActionGroup::Lengths => StringKey::HelpLengths,
+            ActionGroup::Application => StringKey::HelpApplication,
+        }
+    }
+}
+
+/// Modifier keys required by a chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// Super: the Windows or Command key.
+    pub logo: bool,
+}
+
+impl Modifiers {
+    /// Returns the modifier keys currently held down.
+    pub fn current() -> Self {
+        let down = |l, r| is_key_down(l) || is_key_down(r);
+        Self {
+            ctrl: down(KeyCode::LeftControl, KeyCode::RightControl),
+            shift: down(KeyCode::LeftShift, KeyCode::RightShift),
+            alt: down(KeyCode::LeftAlt, KeyCode::RightAlt),
+            logo: down(KeyCode::LeftSuper, KeyCode::RightSuper),
+        }
+    }
+    /// Returns whether these make a key work a chord rather than type: any but
+    /// Shift, which only picks the character typed.
+    pub fn is_chord(self) -> bool {
+        self.ctrl || self.alt || self.logo
+    }
+}
+
+/// A key plus the exact set of modifiers that must be held with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub key: KeyCode,
+    pub mods: Modifiers,
+}
+
+impl Chord {
+    /// Creates a chord requiring no modifiers.
+    pub const fn key(key: KeyCode) -> Self {
+        Self {
+            key,
+            mods: Modifiers {
+                ctrl: false,
+                shift: false,
+                alt: false,
+                logo: false,
+            },
+        }
+    }
+    /// Creates a chord requiring Ctrl.
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self {
+            key,
+            mods: Modifiers {
+                ctrl: true,
+                shift: false,
+                alt: false,
+                logo: false,
+            },
+        }
+    }
+    /// Parses a chord such as `"Escape"`, `"Ctrl+0"`, or `"Ctrl+Shift+E"`.
+    ///
+    /// Modifier and key names are case-insensitive.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let (key_name, mod_names) = match parts.split_last() {
+            Some((key, mods)) if !key.is_empty() => (*key, mods),
+            _ => return Err(format!("'{text}' has no key")),
+        };
+
+        let mut mods = Modifiers::default();
+        for name in mod_names {
+            let flag = match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut mods.ctrl,
+                "shift" => &mut mods.shift,
+                "alt" => &mut mods.alt,
+                "super" | "cmd" => &mut mods.logo,
+                _ => return Err(format!("'{name}' in '{text}' is not a modifier")),
+            };
+            if *flag {
+                return Err(format!("'{text}' repeats modifier '{name}'"));
+            }
+            *flag = true;
+        }
+
+        let key = parse_key_name(key_name)
+            .ok_or_else(|| format!("'{key_name}' in '{text}' is not a known key"))?;
+        Ok(Self { key, mods })
+    }
+    /// Returns whether `key` pressed with `mods` works the chord: its key, with
+    /// exactly its modifiers, so Ctrl+V is never taken for V, nor V for Ctrl+V.
+    pub fn matches(&self, key: KeyCode, mods: Modifiers) -> bool {
+        self.key == key && self.mods == mods
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.mods.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.mods.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.mods.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.mods.logo {
+            write!(f, "Super+")?;
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+/// Keys that may appear in bindings.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Space,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::Apostrophe,
+    KeyCode::LeftBracket,
+    KeyCode::RightBracket,
+    KeyCode::Backslash,
+    KeyCode::GraveAccent,
+    KeyCode::Escape,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Down,
+    KeyCode::Up,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+    KeyCode::KpAdd,
+    KeyCode::KpSubtract,
+    KeyCode::KpMultiply,
+    KeyCode::KpDivide,
+    KeyCode::KpDecimal,
+    KeyCode::KpEnter,
+];
+
+/// Returns the display name of `key`, e.g. `"0"` for `KeyCode::Key0`.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    match name.strip_prefix("Key") {
+        Some(digit) => digit.to_string(),
+        None => name,
+    }
+}
+
+/// Parses a key name (case-insensitive), accepting a few common aliases.
+pub fn parse_key_name(name: &str) -> Option<KeyCode> {
+    let alias = match name.to_ascii_lowercase().as_str() {
+        "esc" => Some(KeyCode::Escape),
+        "return" => Some(KeyCode::Enter),
+        "plus" | "=" => Some(KeyCode::Equal),
+        "-" => Some(KeyCode::Minus),
+        "del" => Some(KeyCode::Delete),
+        _ => None,
+    };
+    alias.or_else(|| {
+        BINDABLE_KEYS
+            .iter()
+            .copied()
+            .find(|&k| key_name(k).eq_ignore_ascii_case(name))
+    })
+}
+
+/// All key bindings, in the order they are checked.
+///
+/// An action may have several chords, but a chord belongs to at most one action.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Chord)>,
+}
+
+impl Keymap {
+    /// Returns the built-in bindings.
+    pub fn defaults() -> Self {
+        let shift = |key| Chord {
+            key,
+            mods: Modifiers {
+                ctrl: false,
+                shift: true,
+                alt: false,
+                logo: false,
+            },
+        };
+        let shift_ctrl = |key| Chord {
+            key,
+            mods: Modifiers {
+                ctrl: true,
+                shift: true,
+                alt: false,
+                logo: false,
+            },
+        };
+        // Plain letters and digits type, so pinning, lengths, and hints need a modifier
+        let alt = |key| Chord {
+            key,
+            mods: Modifiers {
+                ctrl: false,
+                shift: false,
+                alt: true,
+                logo: false,
+            },
+        };
+        Self {
+            bindings: vec![
+                (Action::DeleteBack, Chord::key(KeyCode::Backspace)),
+                (Action::ClearEntry, Chord::key(KeyCode::Delete)),
+                (Action::Quit, Chord::key(KeyCode::Escape)),
+                (Action::ToggleDebug, Chord::key(KeyCode::F12)),
+                (Action::CycleTheme, Chord::key(KeyCode::F3)),
+                (Action::ToggleSubAnagrams, Chord::key(KeyCode::F5)),
+                (Action::ToggleMute, Chord::ctrl(KeyCode::M)),
+                (Action::ToggleBigMode, Chord::key(KeyCode::F2)),
+                (Action::ToggleMini, Chord::key(KeyCode::F4)),
+                (Action::TextLarger, Chord::ctrl(KeyCode::Equal)),
+                (Action::TextLarger, shift_ctrl(KeyCode::Equal)),
+                (Action::TextLarger, Chord::ctrl(KeyCode::KpAdd)),
+                (Action::TextSmaller, Chord::ctrl(KeyCode::Minus)),
+                (Action::TextSmaller, Chord::ctrl(KeyCode::KpSubtract)),
+                (Action::TextReset, Chord::ctrl(KeyCode::Key0)),
+                (Action::TextReset, Chord::ctrl(KeyCode::Kp0)),
+                (Action::OpenSettings, Chord::ctrl(KeyCode::S)),
+                (Action::ShowHelp, Chord::key(KeyCode::F1)),
+                (Action::ShowHelp, shift(KeyCode::Slash)),
+                (Action::CommandPalette, Chord::ctrl(KeyCode::P)),
+                (Action::NewTab, Chord::ctrl(KeyCode::T)),
+                (Action::CloseTab, Chord::ctrl(KeyCode::W)),
+                (Action::NextTab, Chord::ctrl(KeyCode::Tab)),
+                (Action::CycleStats, Chord::key(KeyCode::F10)),
+                (Action::TogglePin, alt(KeyCode::P)),
+                (Action::ClearPins, shift_ctrl(KeyCode::P)),
+                (Action::ShowLength4, alt(KeyCode::Key4)),
+                (Action::ShowLength5, alt(KeyCode::Key5)),
+                (Action::ShowLength6, alt(KeyCode::Key6)),
+                (Action::ShowLength7, alt(KeyCode::Key7)),
+                (Action::ShowLength8, alt(KeyCode::Key8)),
+                (Action::ShowLength9, alt(KeyCode::Key9)),
+                (Action::ShowAllLengths, alt(KeyCode::Key0)),
+                (Action::ShowHint, alt(KeyCode::G)),
+                (Action::TogglePhonetic, alt(KeyCode::V)),
+                (Action::CopyAnswer, Chord::ctrl(KeyCode::C)),
+                (Action::ExplainRanking, alt(KeyCode::I)),
+                (Action::ConfirmAnswer, shift_ctrl(KeyCode::T)),
+                (Action::RevealHidden, alt(KeyCode::H)),
+                (Action::SpellFinal, alt(KeyCode::F)),
+                (Action::Complete, Chord::key(KeyCode::Tab)),
+                (Action::InspectWord, Chord::ctrl(KeyCode::Enter)),
+                (Action::PrintResults, shift_ctrl(KeyCode::E)),
+                (Action::ToggleRevealLock, Chord::ctrl(KeyCode::L)),
+            ],
+        }
+    }
+    /// Returns the defaults with the bindings of each action named in `overrides`
+    /// replaced by the given comma-separated chords.
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut keymap = Self::defaults();
+
+        for (name, chords) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| format!("'{name}' is not a bindable action"))?;
+            keymap.bindings.retain(|(a, _)| *a != action);
+            for text in chords.split(',').filter(|t| !t.trim().is_empty()) {
+                keymap.bindings.push((action, Chord::parse(text)?));
+            }
+        }
+
+        keymap.validate()?;
+        Ok(keymap)
+    }
+    /// Checks that no chord is bound to two different actions.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, (action, chord)) in self.bindings.iter().enumerate() {
+            let clash = self.bindings[i + 1..]
+                .iter()
+                .find(|(a, c)| c == chord && a != action);
+            if let Some((other, _)) = clash {
+                return Err(format!(
+                    "'{chord}' is bound to both '{}' and '{}'",
+                    action.name(),
+                    other.name()
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Returns the actions whose chords are among the keys `pressed`, each with
+    /// the modifiers held when it went down (see `ModifierTracker`).
+    pub fn pressed_actions(&self, pressed: &[(KeyCode, Modifiers)]) -> Vec<Action> {
+        let mut actions: Vec<Action> = Vec::new();
+        for (action, chord) in &self.bindings {
+            let hit = pressed.iter().any(|&(key, mods)| chord.matches(key, mods));
+            if hit && !actions.contains(action) {
+                actions.push(*action);
+            }
+        }
+        actions
+    }
+    /// Returns the actions worked by holding their chords whose keys are among
+    /// those `released`, whatever modifiers are still held, as they may come up
+    /// first.
+    pub fn released_actions(&self, released: &[(KeyCode, Modifiers)]) -> Vec<Action> {
+        let mut actions: Vec<Action> = Vec::new();
+        for (action, chord) in &self.bindings {
+            let held = action.is_held();
+            let up = released.iter().any(|&(key, _)| key == chord.key);
+            if held && up && !actions.contains(action) {
+                actions.push(*action);
+            }
+        }
+        actions
+    }
+    /// Returns the chords bound to `action`.
+    pub fn chords(&self, action: Action) -> impl Iterator<Item = &Chord> {
+        self.bindings
+            .iter()
+            .filter(move |(a, _)| *a == action)
+            .map(|(_, c)| c)
+    }
+    /// Returns the action bound to `key` without Ctrl, Alt, or Super, whose
+    /// chord also types when `key` types, if there is one.
+    pub fn typing_action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, c)| c.key == key && !c.mods.is_chord())
+            .map(|(a, _)| *a)
+    }
+    /// Returns a table of all actions and their bindings, one per line, the
+    /// columns as wide as their longest entries.
+    pub fn describe(&self) -> String {
+        let name_width = Action::ALL
+            .iter()
+            .map(|a| a.name().len())
+            .max()
+            .unwrap_or(0);
+        let description_width = Action::ALL
+            .iter()
+            .map(|a| Language::En.text(a.description()).chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut out = String::new();
+        for action in Action::ALL {
+            let chords: Vec<String> = self.chords(action).map(|c| c.to_string()).collect();
+            let chords = if chords.is_empty() {
+                "(unbound)".to_string()
+            } else {
+                chords.join(", ")
+            };
+            out.push_str(&format!(
+                "{:<name_width$} {:<description_width$} {}\n",
+                action.name(),
+                Language::En.text(action.description()),
+                chords
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_lines_up_its_columns() {
+        let table = Keymap::defaults().describe();
+        let widest = Action::ALL.iter().map(|a| a.name().len()).max().unwrap();
+        for line in table.lines() {
+            let (name, rest) = line.split_at(widest + 1);
+            assert!(!name.trim_end().contains(' '), "{line}");
+            assert!(!rest.starts_with(' '), "{line}");
+        }
+        assert_eq!(table.lines().count(), Action::ALL.len());
+    }
+
+    #[test]
+    fn chords_parse_and_print_alike() {
+        let chord = Chord::parse("ctrl + shift+e").unwrap();
+        assert_eq!(chord.key, KeyCode::E);
+        assert!(chord.mods.ctrl && chord.mods.shift && !chord.mods.alt);
+        assert_eq!(chord.to_string(), "Ctrl+Shift+E");
+        assert_eq!(Chord::parse("Ctrl+0").unwrap(), Chord::ctrl(KeyCode::Key0));
+        assert_eq!(Chord::parse("esc").unwrap(), Chord::key(KeyCode::Escape));
+    }
+
+    #[test]
+    fn bad_chords_are_refused() {
+        assert!(Chord::parse("").is_err());
+        assert!(Chord::parse("Ctrl+").is_err());
+        assert!(Chord::parse("Hyper+A").is_err());
+        assert!(Chord::parse("Ctrl+Ctrl+A").is_err());
+        assert!(Chord::parse("Ctrl+NoSuchKey").is_err());
+    }
+
+    #[test]
+    fn overrides_replace_an_actions_bindings() {
+        let overrides = BTreeMap::from([("quit".to_owned(), "Ctrl+Q, F9".to_owned())]);
+        let keymap = Keymap::with_overrides(&overrides).unwrap();
+        let chords: Vec<String> = keymap.chords(Action::Quit).map(|c| c.to_string()).collect();
+        assert_eq!(chords, ["Ctrl+Q", "F9"]);
+        let unknown = BTreeMap::from([("fly".to_owned(), "F9".to_owned())]);
+        assert!(Keymap::with_overrides(&unknown).is_err());
+    }
+
+    #[test]
+    fn a_chord_bound_twice_is_refused() {
+        let clash = BTreeMap::from([("quit".to_owned(), "F12".to_owned())]);
+        let e = Keymap::with_overrides(&clash).unwrap_err();
+        assert!(e.contains("F12"), "{e}");
+    }
+
+    #[test]
+    fn chords_need_exactly_their_modifiers() {
+        let none = Modifiers::default();
+        let ctrl = Modifiers { ctrl: true, ..none };
+        let ctrl_shift = Modifiers {
+            shift: true,
+            ..ctrl
+        };
+        let chord = Chord::parse("Ctrl+V").unwrap();
+        assert!(chord.matches(KeyCode::V, ctrl));
+        assert!(!chord.matches(KeyCode::V, none));
+        assert!(!chord.matches(KeyCode::V, ctrl_shift));
+        assert!(!chord.matches(KeyCode::C, ctrl));
+        assert!(!Chord::key(KeyCode::V).matches(KeyCode::V, ctrl));
+
+        let logo = Chord::parse("Cmd+K").unwrap();
+        assert_eq!(logo, Chord::parse("super+k").unwrap());
+        assert_eq!(logo.to_string(), "Super+K");
+        assert!(logo.matches(KeyCode::K, Modifiers { logo: true, ..none }));
+        assert!(!logo.matches(KeyCode::K, ctrl));
+    }
+
+    #[test]
+    fn actions_go_by_the_modifiers_each_key_went_down_with() {
+        let keymap = Keymap::defaults();
+        let none = Modifiers::default();
+        let ctrl = Modifiers { ctrl: true, ..none };
+        let shift = Modifiers {
+            shift: true,
+            ..none
+        };
+        let pressed = [(KeyCode::M, ctrl), (KeyCode::F2, none)];
+        assert_eq!(
+            keymap.pressed_actions(&pressed),
+            [Action::ToggleMute, Action::ToggleBigMode]
+        );
+        // A plain M types; Shift+F2 is no binding
+        assert!(keymap
+            .pressed_actions(&[(KeyCode::M, none), (KeyCode::F2, shift)])
+            .is_empty());
+        // Held actions end when their key comes up, whatever's held then
+        let held = Action::ALL.into_iter().find(|a| a.is_held()).unwrap();
+        let key = keymap.chords(held).next().unwrap().key;
+        assert_eq!(keymap.released_actions(&[(key, none)]), [held]);
+
+        assert!(!shift.is_chord() && ctrl.is_chord());
+        assert!(Modifiers { alt: true, ..none }.is_chord());
+        assert!(Modifiers { logo: true, ..none }.is_chord());
+    }
+}
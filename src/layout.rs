@@ -0,0 +1,305 @@
+//! Placement and dimensions of window components.
+
+use crate::metrics::{fit_font_size, TextMetrics};
+use macroquad::prelude::*;
+
+/// Share of the window's width a full entry may take up.
+const ENTRY_WIDTH_SHARE: f32 = 0.9;
+
+/// Regions of the window, computed from its size.
+///
+/// Portrait screens (e.g. tablets held upright) move the entry to the top and give
+/// the results and on-screen keyboard more room for fingers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenLayout {
+    pub width: f32,
+    pub height: f32,
+    pub portrait: bool,
+    /// Vertical center of the entry text box.
+    pub entry_cy: f32,
+    pub results: Rect,
+    pub keyboard: Rect,
+    /// The gear button that opens the settings screen, top right.
+    pub settings: Rect,
+    /// The strip of entry tabs, top left, shown when more than one is open.
+    pub tabs: Rect,
+}
+
+impl ScreenLayout {
+    /// Computes the layout for a `width` by `height` window, with margins scaled by
+    /// the DPI scale `dpi`.
+    pub fn new(width: f32, height: f32, dpi: f32) -> Self {
+        let portrait = height > width;
+        let at = |x: f32, y: f32, w: f32, h: f32| {
+            Rect::new(x * width, y * height, w * width, h * height)
+        };
+        let margin = 8.0 * dpi;
+        let gear = (width.min(height) * 0.08).max(32.0 * dpi);
+        let settings = Rect::new(width - gear - margin, margin, gear, gear);
+        let tabs = Rect::new(
+            margin,
+            margin,
+            (settings.x - margin * 2.0).max(0.0),
+            gear * 0.75,
+        );
+
+        if portrait {
+            Self {
+                width,
+                height,
+                portrait,
+                entry_cy: height * 0.12,
+                results: at(0.05, 0.22, 0.90, 0.36),
+                keyboard: at(0.02, 0.62, 0.96, 0.36),
+                settings,
+                tabs,
+            }
+        } else {
+            Self {
+                width,
+                height,
+                portrait,
+                entry_cy: height * 0.28,
+                results: at(0.10, 0.42, 0.80, 0.30),
+                keyboard: at(0.05, 0.74, 0.90, 0.24),
+                settings,
+                tabs,
+            }
+        }
+    }
+}
+
+/// Returns the entry's font size for an entry holding up to `capacity` letters
+/// in `layout`: `max_size`, shrunk as needed so that many of the widest letter
+/// fit across `ENTRY_WIDTH_SHARE` of the window, and so the entry box, centered
+/// on `entry_cy`, ends above the results.
+///
+/// `measure` gives the width of text at a size.
+pub fn entry_text_size(
+    capacity: usize,
+    max_size: u16,
+    layout: &ScreenLayout,
+    measure: impl Fn(&str, u16) -> f32,
+) -> u16 {
+    // Text is never taller than its font size
+    let room = 2.0 * (layout.results.y - layout.entry_cy);
+    let max_size = max_size.min(room.max(1.0) as u16);
+    let full = "W".repeat(capacity);
+    fit_font_size(&full, layout.width * ENTRY_WIDTH_SHARE, max_size, measure)
+}
+
+/// Text measuring this many pixels or less means the font wasn't ready to measure.
+pub const MIN_TEXT_DIM: f32 = 1.0;
+
+/// Stores placement and dimensions for the entry text box.
+///
+/// - `scx`: screen center x value.
+/// - `tcx`: text box center x value.
+/// - `tlx`, `tby`, `tty`: text box left x, bottom y, and top y values.
+/// - `tw`, `th`: text box width (x) and height (y) values.
+/// - `margin_y`: space between boxes and results along the y-axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowValues {
+    pub scx: f32,
+    pub tcx: f32,
+    pub tlx: f32,
+    pub tby: f32,
+    pub tty: f32,
+    pub tw: f32,
+    pub th: f32,
+    pub margin_y: f32,
+}
+
+impl WindowValues {
+    /// Sizes the text box to hold `capacity` of the font's widest letter.
+    ///
+    /// Returns `None` if the font measured degenerate; see `from_text_dims`.
+    pub fn new(metrics: &TextMetrics, layout: &ScreenLayout, capacity: usize) -> Option<Self> {
+        let tw = metrics.widest_run(capacity);
+        Self::from_text_dims(tw, metrics.height, layout)
+    }
+    /// Computes placement from the placeholder text's measured width and height.
+    ///
+    /// Some fonts measure as zero until their atlas is ready, which would give a box
+    /// with no size, so dimensions of `MIN_TEXT_DIM` or less (or NaN) give `None`.
+    pub fn from_text_dims(tw: f32, th: f32, layout: &ScreenLayout) -> Option<Self> {
+        if !(tw > MIN_TEXT_DIM && th > MIN_TEXT_DIM) {
+            return None;
+        }
+        let scx = layout.width / 2.0;
+        let tcx = scx;
+        let tlx = scx - tw / 2.0;
+        let tcy = layout.entry_cy;
+        let tby = tcy + th / 2.0;
+        let tty = tcy - th / 2.0;
+        let margin_y = th / 4.0;
+
+        Some(Self {
+            scx,
+            tcx,
+            tlx,
+            tby,
+            tty,
+            tw,
+            th,
+            margin_y,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::fixed_measure;
+    use crate::style::{AppStyle, MAX_TEXT_SCALE};
+    use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+
+    /// Window sizes from a small landscape laptop to an upright tablet.
+    const SIZES: [(f32, f32); 4] = [
+        (800.0, 600.0),
+        (1920.0, 1080.0),
+        (600.0, 900.0),
+        (1200.0, 1920.0),
+    ];
+
+    #[test]
+    fn portrait_follows_the_aspect_ratio() {
+        assert!(!ScreenLayout::new(800.0, 600.0, 1.0).portrait);
+        assert!(!ScreenLayout::new(700.0, 700.0, 1.0).portrait);
+        assert!(ScreenLayout::new(600.0, 900.0, 1.0).portrait);
+    }
+
+    #[test]
+    fn regions_stack_top_to_bottom_inside_the_window() {
+        for (width, height) in SIZES {
+            let layout = ScreenLayout::new(width, height, 1.0);
+            let window = Rect::new(0.0, 0.0, width, height);
+            for region in [layout.results, layout.keyboard] {
+                assert!(window.contains(region.point()), "{width}x{height}");
+                assert!(region.right() <= width && region.bottom() <= height);
+            }
+            assert!(layout.entry_cy < layout.results.y, "{width}x{height}");
+            assert!(
+                layout.results.bottom() < layout.keyboard.y,
+                "{width}x{height}"
+            );
+        }
+    }
+
+    #[test]
+    fn portrait_gives_fingers_more_room() {
+        let landscape = ScreenLayout::new(800.0, 600.0, 1.0);
+        let portrait = ScreenLayout::new(600.0, 800.0, 1.0);
+        let share = |r: Rect, w: f32, h: f32| r.w * r.h / (w * h);
+        assert!(share(portrait.keyboard, 600.0, 800.0) > share(landscape.keyboard, 800.0, 600.0));
+        assert!(share(portrait.results, 600.0, 800.0) > share(landscape.results, 800.0, 600.0));
+        // The entry moves up to make room
+        assert!(portrait.entry_cy / 800.0 < landscape.entry_cy / 600.0);
+    }
+
+    #[test]
+    fn the_entry_box_follows_the_window() {
+        for (width, height) in SIZES {
+            let layout = ScreenLayout::new(width, height, 1.0);
+            let wv = WindowValues::from_text_dims(400.0, 60.0, &layout).unwrap();
+            assert_eq!(wv.scx, width / 2.0);
+            assert_eq!(wv.tlx + wv.tw / 2.0, wv.scx, "{width}x{height}");
+            assert_eq!((wv.tty + wv.tby) / 2.0, layout.entry_cy);
+            assert_eq!(wv.tby - wv.tty, 60.0);
+            assert!(
+                wv.tty >= 0.0 && wv.tby <= layout.results.y,
+                "{width}x{height}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_resize_moves_the_entry_box() {
+        let small =
+            WindowValues::from_text_dims(400.0, 60.0, &ScreenLayout::new(800.0, 600.0, 1.0))
+                .unwrap();
+        let large =
+            WindowValues::from_text_dims(400.0, 60.0, &ScreenLayout::new(1920.0, 1080.0, 1.0))
+                .unwrap();
+        assert_ne!(small.tlx, large.tlx);
+        assert_ne!(small.tty, large.tty);
+        assert_eq!(small.tw, large.tw);
+    }
+
+    #[test]
+    fn degenerate_measurements_give_no_entry_box() {
+        let layout = ScreenLayout::new(800.0, 600.0, 1.0);
+        for (tw, th) in [
+            (0.0, 0.0),
+            (400.0, 0.0),
+            (0.0, 60.0),
+            (MIN_TEXT_DIM, 60.0),
+            (400.0, MIN_TEXT_DIM),
+            (f32::NAN, 60.0),
+            (400.0, f32::NAN),
+            (-400.0, 60.0),
+        ] {
+            assert!(
+                WindowValues::from_text_dims(tw, th, &layout).is_none(),
+                "{tw}x{th}"
+            );
+        }
+        assert!(WindowValues::from_text_dims(1.5, 1.5, &layout).is_some());
+    }
+
+    #[test]
+    fn margins_follow_the_dpi_scale() {
+        let plain = ScreenLayout::new(800.0, 600.0, 1.0);
+        let doubled = ScreenLayout::new(800.0, 600.0, 2.0);
+        assert_eq!((plain.tabs.x, doubled.tabs.x), (8.0, 16.0));
+        assert_eq!(doubled.settings.y, 16.0);
+        // The gear never shrinks below its scaled minimum
+        assert_eq!(plain.settings.w, 48.0);
+        assert_eq!(doubled.settings.w, 64.0);
+        assert_eq!(doubled.settings.right(), 800.0 - 16.0);
+    }
+
+    #[test]
+    fn ten_letters_fit_an_800_px_window() {
+        let style = AppStyle::default();
+        let layout = ScreenLayout::new(800.0, 600.0, style.dpi);
+        // A bold font's capitals are about as wide as the font is tall
+        let bold = |text: &str, size: u16| TextDimensions {
+            width: text.chars().count() as f32 * size as f32 * 1.1,
+            ..fixed_measure(text, None, size)
+        };
+        let size = entry_text_size(10, style.entry_size, &layout, |text, size| {
+            bold(text, size).width
+        });
+        assert!(size < style.entry_size, "{size}");
+        let metrics = TextMetrics::from_measurer(size, |text| bold(text, size));
+        let wv = WindowValues::new(&metrics, &layout, 10).unwrap();
+        assert!(wv.tlx >= 0.0 && wv.tlx + wv.tw <= 800.0, "{wv:?}");
+    }
+
+    #[test]
+    fn entry_clears_the_results_at_the_largest_scale() {
+        let style = AppStyle::new(MAX_TEXT_SCALE, 1.0);
+        let layout = ScreenLayout::new(800.0, 600.0, style.dpi);
+        let width = |text: &str, size| fixed_measure(text, None, size).width;
+        for capacity in MIN_WORD_LENGTH..=MAX_WORD_LENGTH {
+            let size = entry_text_size(capacity, style.entry_size, &layout, width);
+            let metrics = TextMetrics::from_measurer(size, |text| fixed_measure(text, None, size));
+            let wv = WindowValues::new(&metrics, &layout, capacity).unwrap();
+            assert!(wv.tby <= layout.results.y, "{capacity} letters: {wv:?}");
+            assert!(
+                wv.tlx >= 0.0 && wv.tlx + wv.tw <= layout.width,
+                "{capacity} letters: {wv:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn entry_keeps_its_size_when_it_fits() {
+        let style = AppStyle::default();
+        let layout = ScreenLayout::new(800.0, 600.0, style.dpi);
+        let width = |text: &str, size| fixed_measure(text, None, size).width;
+        let size = entry_text_size(MIN_WORD_LENGTH, style.entry_size, &layout, width);
+        assert_eq!(size, style.entry_size);
+    }
+}
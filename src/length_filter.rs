@@ -0,0 +1,203 @@
+//! Showing only the matches of chosen lengths.
+//!
+//! The Jumble mixes five- and six-letter scrambles, and sub-anagram or bank
+//! queries list words of every length. Alt+4 to Alt+9 toggle a length; with any
+//! toggled on, only words of those lengths are listed. The filter only changes
+//! which rows are shown: the matches themselves, and their indices, stay as
+//! found.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+use std::ops::RangeInclusive;
+
+/// Word lengths that can be filtered on, one per number key.
+pub const FILTER_LENGTHS: RangeInclusive<usize> = 4..=9;
+
+/// The lengths toggled on; none means every length is shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LengthFilter {
+    /// Bit `n` is set when length `n` is shown.
+    lengths: u16,
+}
+
+impl LengthFilter {
+    /// Returns whether any length is toggled on.
+    pub fn is_active(self) -> bool {
+        self.lengths != 0
+    }
+    /// Returns whether `len` is toggled on.
+    pub fn has(self, len: usize) -> bool {
+        FILTER_LENGTHS.contains(&len) && self.lengths & (1 << len) != 0
+    }
+    /// Toggles `len`, if it's one of `FILTER_LENGTHS`, returning whether the
+    /// filter changed.
+    pub fn toggle(&mut self, len: usize) -> bool {
+        if !FILTER_LENGTHS.contains(&len) {
+            return false;
+        }
+        self.lengths ^= 1 << len;
+        true
+    }
+    /// Toggles every length off, returning whether any was on.
+    pub fn clear(&mut self) -> bool {
+        std::mem::take(&mut self.lengths) != 0
+    }
+    /// Returns whether `word` is shown: always with no lengths on, or else when
+    /// its letters (not counting spaces between a phrase's words) number one of
+    /// them.
+    pub fn shows(self, word: &str) -> bool {
+        !self.is_active() || self.has(word.chars().filter(|c| !c.is_whitespace()).count())
+    }
+    /// Returns the indices of the `words` shown, in order.
+    pub fn apply(self, words: &[String]) -> impl Iterator<Item = usize> + '_ {
+        (0..words.len()).filter(move |&i| self.shows(&words[i]))
+    }
+}
+
+/// Returns the height of the row of length chips for text of `font_size`.
+pub fn chips_height(font_size: u16) -> f32 {
+    font_size as f32 * 1.6
+}
+
+/// Splits `area` into the row of length chips along its top and the rest, for
+/// the list; the row is empty when no length is toggled on.
+pub fn split_chips(area: Rect, filter: LengthFilter, font_size: u16) -> (Rect, Rect) {
+    let h = match filter.is_active() {
+        true => chips_height(font_size).min(area.h),
+        false => 0.0,
+    };
+    (
+        Rect::new(area.x, area.y, area.w, h),
+        Rect::new(area.x, area.y + h, area.w, area.h - h),
+    )
+}
+
+/// Returns the length whose chip in `row` is under `pos`, if any.
+pub fn chip_length_at(pos: Vec2, row: Rect) -> Option<usize> {
+    length_chips(row)
+        .into_iter()
+        .find(|(_, chip)| chip.contains(pos))
+        .map(|(len, _)| len)
+}
+
+/// Lays out one square chip per length in `FILTER_LENGTHS` from the left of
+/// `row`, returning each length with its chip.
+pub fn length_chips(row: Rect) -> Vec<(usize, Rect)> {
+    let size = row.h * 0.85;
+    let gap = row.h * 0.25;
+    FILTER_LENGTHS
+        .enumerate()
+        .map(|(i, len)| {
+            let x = row.x + i as f32 * (size + gap);
+            (len, Rect::new(x, row.y, size, size))
+        })
+        .filter(|(_, chip)| chip.right() <= row.right())
+        .collect()
+}
+
+/// Draws the length chips in `row`, the lengths toggled on filled in, or nothing
+/// while none are.
+pub fn draw_length_chips(
+    gfx: &dyn Renderer,
+    row: Rect,
+    filter: LengthFilter,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+) {
+    if !filter.is_active() {
+        return;
+    }
+    for (len, chip) in length_chips(row) {
+        let label = len.to_string();
+        let color = if filter.has(len) {
+            gfx.rect(chip, theme.highlight);
+            theme.background
+        } else {
+            gfx.rect_lines(chip, theme.outline_thickness, theme.box_outline);
+            theme.result
+        };
+        let dims = gfx.measure(&label, font, font_size);
+        let pos = vec2(
+            chip.center().x - dims.width / 2.0,
+            chip.center().y + dims.offset_y / 2.0,
+        );
+        gfx.text(&label, pos, font, font_size, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn no_lengths_show_everything() {
+        let filter = LengthFilter::default();
+        assert!(!filter.is_active());
+        let matches = words(&["GRATES", "GREAT", "RATE"]);
+        assert!(filter.apply(&matches).eq([0, 1, 2]));
+    }
+
+    #[test]
+    fn toggled_lengths_show_only_theirs() {
+        let mut filter = LengthFilter::default();
+        assert!(filter.toggle(4));
+        assert!(filter.toggle(6));
+        let matches = words(&["GRATES", "GREAT", "RATE", "GATE"]);
+        assert!(filter.apply(&matches).eq([0, 2, 3]));
+        // Off again
+        assert!(filter.toggle(6));
+        assert!(filter.apply(&matches).eq([2, 3]));
+        assert!(filter.has(4) && !filter.has(6));
+        assert!(filter.clear());
+        assert!(!filter.clear());
+        assert!(filter.apply(&matches).eq(0..4));
+    }
+
+    #[test]
+    fn lengths_outside_the_keys_are_ignored() {
+        let mut filter = LengthFilter::default();
+        assert!(!filter.toggle(3));
+        assert!(!filter.toggle(10));
+        assert!(!filter.toggle(0));
+        assert_eq!(filter, LengthFilter::default());
+        assert!(!filter.has(3));
+    }
+
+    #[test]
+    fn phrases_count_their_letters_only() {
+        let mut filter = LengthFilter::default();
+        filter.toggle(7);
+        assert!(filter.shows("TEN SAIL"));
+        assert!(!filter.shows("TENSAILS"));
+    }
+
+    #[test]
+    fn chips_sit_in_a_row_only_while_filtering() {
+        let area = Rect::new(0.0, 50.0, 300.0, 200.0);
+        let mut filter = LengthFilter::default();
+        let (row, list) = split_chips(area, filter, 20);
+        assert_eq!((row.h, list), (0.0, area));
+        filter.toggle(5);
+        let (row, list) = split_chips(area, filter, 20);
+        assert_eq!(
+            (row.h, list.y),
+            (chips_height(20), area.y + chips_height(20))
+        );
+
+        let chips = length_chips(row);
+        assert_eq!(chips.len(), FILTER_LENGTHS.count());
+        assert_eq!(chips[0].0, 4);
+        let five = chips[1].1.center();
+        assert_eq!(chip_length_at(five, row), Some(5));
+        assert_eq!(chip_length_at(vec2(299.0, five.y), row), None);
+        // A narrow row leaves out the chips that don't fit
+        let narrow = Rect::new(0.0, 0.0, 60.0, 32.0);
+        assert_eq!(length_chips(narrow).len(), 1);
+    }
+}
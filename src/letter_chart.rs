@@ -0,0 +1,389 @@
+//! The stats overlay: counts for the loaded dictionary, and a chart of how
+//! often each letter appears in it.
+//!
+//! F10 cycles the overlay from off to the counts, to the chart, and back. With
+//! letters entered, the chart sets the letters of the current matches beside the
+//! dictionary's, so it shows which letters set the matches apart.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use crate::wordmap::WordMapStats;
+use macroquad::prelude::*;
+
+/// Share of each letter's slot left empty between neighboring letters.
+const SLOT_GAP: f32 = 0.2;
+
+/// What the stats overlay shows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StatsView {
+    #[default]
+    Off,
+    /// The dictionary's counts, as text.
+    Summary,
+    /// The letter frequency chart.
+    Chart,
+}
+
+impl StatsView {
+    /// Returns the view after this one (F10), wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            StatsView::Off => StatsView::Summary,
+            StatsView::Summary => StatsView::Chart,
+            StatsView::Chart => StatsView::Off,
+        }
+    }
+}
+
+/// One letter's bars in the chart, and where its label goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetterBar {
+    pub letter: char,
+    /// The letter's share of the dictionary's letters.
+    pub dictionary: Rect,
+    /// The letter's share of the matches' letters, when there are matches.
+    pub matches: Option<Rect>,
+    /// Horizontal center of the letter's slot, for its label.
+    pub center_x: f32,
+}
+
+/// Lays out bars for the letter counts of the dictionary and, if given, of the
+/// matches, standing on the bottom of `area`.
+///
+/// Each series is scaled to its share of its own total so the two compare, and
+/// the tallest share of either fills the height of `area`. Returns the bars, `A`
+/// first, and that tallest share (0 if there are no letters at all).
+pub fn chart_bars(
+    area: Rect,
+    dictionary: &[usize; 26],
+    matches: Option<&[usize; 26]>,
+) -> (Vec<LetterBar>, f32) {
+    let shares = |counts: &[usize; 26]| {
+        let total = counts.iter().sum::<usize>().max(1) as f32;
+        counts.map(|n| n as f32 / total)
+    };
+    let dictionary = shares(dictionary);
+    let matches = matches.map(shares);
+    let tallest = dictionary
+        .iter()
+        .chain(matches.iter().flatten())
+        .fold(0.0, |a: f32, &b| a.max(b));
+
+    let slot_w = area.w / 26.0;
+    let gap = slot_w * SLOT_GAP;
+    let series = if matches.is_some() { 2.0 } else { 1.0 };
+    let bar_w = (slot_w - gap) / series;
+    let bar = |x: f32, share: f32| {
+        let h = if tallest > 0.0 {
+            area.h * share / tallest
+        } else {
+            0.0
+        };
+        Rect::new(x, area.bottom() - h, bar_w, h)
+    };
+    let bars = (0..26)
+        .map(|i| {
+            let x = area.x + slot_w * i as f32 + gap / 2.0;
+            LetterBar {
+                letter: (b'A' + i as u8) as char,
+                dictionary: bar(x, dictionary[i]),
+                matches: matches.map(|m| bar(x + bar_w, m[i])),
+                center_x: area.x + slot_w * (i as f32 + 0.5),
+            }
+        })
+        .collect();
+    (bars, tallest)
+}
+
+/// Returns the letters from most to least frequent in `counts`, leaving out
+/// those that never appear.
+pub fn letters_by_frequency(counts: &[usize; 26]) -> String {
+    let mut letters: Vec<usize> = (0..26).filter(|&i| counts[i] > 0).collect();
+    letters.sort_by(|&a, &b| counts[b].cmp(&counts[a]).then(a.cmp(&b)));
+    letters
+        .into_iter()
+        .map(|i| (b'A' + i as u8) as char)
+        .collect()
+}
+
+/// Returns the stats panel: the middle of the screen, with a margin.
+pub fn stats_panel(screen: Vec2) -> Rect {
+    let margin = screen.x.min(screen.y) * 0.08;
+    Rect::new(
+        margin,
+        margin,
+        (screen.x - margin * 2.0).max(0.0),
+        (screen.y - margin * 2.0).max(0.0),
+    )
+}
+
+/// Draws the stats overlay as `view` says, with the letter counts of the current
+/// matches, if any, beside the dictionary's in the chart.
+pub fn draw_stats(
+    gfx: &dyn Renderer,
+    view: StatsView,
+    stats: &WordMapStats,
+    matches: Option<&[usize; 26]>,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+) {
+    if view == StatsView::Off {
+        return;
+    }
+    let screen = gfx.screen_size();
+    let panel = stats_panel(screen);
+    let mut dim = theme.background;
+    dim.a = 0.7;
+    let mut fill = theme.background;
+    fill.a = 0.92;
+    gfx.rect(Rect::new(0.0, 0.0, screen.x, screen.y), dim);
+    gfx.rect(panel, fill);
+    gfx.rect_lines(panel, theme.outline_thickness, theme.box_outline);
+
+    let pad = font_size as f32;
+    let line_h = font_size as f32 * 1.4;
+    let small = (font_size * 3 / 4).max(1);
+    let text = |text: &str, x: f32, y: f32, size: u16, color: Color| {
+        gfx.text(text, vec2(x, y), font, size, color);
+    };
+    let title = match view {
+        StatsView::Chart => "Letter frequency",
+        _ => "Dictionary",
+    };
+    let (left, mut y) = (panel.x + pad, panel.y + pad + line_h * 0.75);
+    text(title, left, y, font_size, theme.highlight);
+    y += line_h;
+
+    if view == StatsView::Summary {
+        let title = stats.title.as_deref().unwrap_or("(unnamed)");
+        let lines = [
+            format!("Word list: {title}"),
+            format!("Words: {}", stats.words),
+            format!("Anagram groups: {}", stats.groups),
+            format!("Duplicates merged: {}", stats.merged),
+            format!("Phrases: {}", stats.phrases),
+            format!(
+                "Letters, most used first: {}",
+                letters_by_frequency(&stats.letters)
+            ),
+        ];
+        for line in &lines {
+            text(line, left, y, font_size, theme.entry_text);
+            y += line_h;
+        }
+        return;
+    }
+
+    // Legend, then the chart with room for the axis labels around it
+    let legend = |x: f32, color: Color, label: &str| {
+        let swatch = small as f32 * 0.8;
+        gfx.rect(Rect::new(x, y - swatch, swatch, swatch), color);
+        text(label, x + swatch * 1.5, y, small, theme.entry_text);
+        x + swatch * 2.5 + gfx.measure(label, font, small).width
+    };
+    let x = legend(left, theme.result, "dictionary");
+    if matches.is_some() {
+        legend(x, theme.highlight, "matches");
+    }
+    let axis_w = gfx.measure("00%", font, small).width + pad * 0.5;
+    let area = Rect::new(
+        left + axis_w,
+        y + line_h * 0.5,
+        (panel.right() - pad - left - axis_w).max(0.0),
+        (panel.bottom() - pad - line_h - y - line_h * 0.5).max(0.0),
+    );
+    let (bars, tallest) = chart_bars(area, &stats.letters, matches);
+
+    gfx.line(
+        vec2(area.x, area.bottom()),
+        vec2(area.right(), area.bottom()),
+        1.0,
+        theme.box_outline,
+    );
+    gfx.line(
+        vec2(area.x, area.y),
+        vec2(area.x, area.bottom()),
+        1.0,
+        theme.box_outline,
+    );
+    let top_label = format!("{:.0}%", tallest * 100.0);
+    text(
+        &top_label,
+        left,
+        area.y + small as f32 * 0.75,
+        small,
+        theme.entry_text,
+    );
+    text("0%", left, area.bottom(), small, theme.entry_text);
+
+    for bar in &bars {
+        gfx.rect(bar.dictionary, theme.result);
+        if let Some(rect) = bar.matches {
+            gfx.rect(rect, theme.highlight);
+        }
+        let label = bar.letter.to_string();
+        let w = gfx.measure(&label, font, small).width;
+        let y = area.bottom() + line_h * 0.8;
+        text(&label, bar.center_x - w / 2.0, y, small, theme.entry_text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{DrawCall, RecordingRenderer};
+    use crate::wordmap::count_letters;
+
+    const AREA: Rect = Rect {
+        x: 10.0,
+        y: 20.0,
+        w: 260.0,
+        h: 100.0,
+    };
+
+    fn counts(letters: &[(char, usize)]) -> [usize; 26] {
+        let mut counts = [0; 26];
+        for &(letter, n) in letters {
+            counts[(letter as u8 - b'A') as usize] = n;
+        }
+        counts
+    }
+
+    #[test]
+    fn views_cycle_back_to_off() {
+        let view = StatsView::Off.next();
+        assert_eq!(view, StatsView::Summary);
+        assert_eq!(view.next(), StatsView::Chart);
+        assert_eq!(view.next().next(), StatsView::Off);
+    }
+
+    #[test]
+    fn letters_are_ranked_by_frequency_then_alphabetically() {
+        let counts = counts(&[('E', 5), ('T', 3), ('A', 3), ('Z', 1)]);
+        assert_eq!(letters_by_frequency(&counts), "EATZ");
+        assert_eq!(letters_by_frequency(&[0; 26]), "");
+    }
+
+    #[test]
+    fn the_tallest_share_fills_the_area() {
+        // E is half the letters, A a quarter, T the rest
+        let dictionary = counts(&[('E', 2), ('A', 1), ('T', 1)]);
+        let (bars, tallest) = chart_bars(AREA, &dictionary, None);
+        assert_eq!(tallest, 0.5);
+        assert_eq!(bars.len(), 26);
+        assert_eq!(bars[0].letter, 'A');
+        assert_eq!(bars[25].letter, 'Z');
+        assert_eq!(bars[4].dictionary.h, AREA.h);
+        assert_eq!(bars[0].dictionary.h, AREA.h / 2.0);
+        assert_eq!(bars[1].dictionary.h, 0.0);
+        for bar in &bars {
+            assert_eq!(bar.dictionary.bottom(), AREA.bottom());
+            assert!(bar.matches.is_none());
+        }
+    }
+
+    #[test]
+    fn each_series_is_scaled_to_its_own_total() {
+        // Both are all E, however different their totals
+        let dictionary = counts(&[('E', 1000)]);
+        let matches = counts(&[('E', 3)]);
+        let (bars, tallest) = chart_bars(AREA, &dictionary, Some(&matches));
+        assert_eq!(tallest, 1.0);
+        let e = &bars[4];
+        assert_eq!(e.dictionary.h, AREA.h);
+        assert_eq!(e.matches.unwrap().h, AREA.h);
+        assert_eq!(e.matches.unwrap().bottom(), AREA.bottom());
+    }
+
+    #[test]
+    fn both_bars_fit_their_letters_slot() {
+        let dictionary = counts(&[('A', 1), ('Z', 1)]);
+        let (bars, _) = chart_bars(AREA, &dictionary, Some(&dictionary));
+        let slot_w = AREA.w / 26.0;
+        for (i, bar) in bars.iter().enumerate() {
+            let slot = (AREA.x + slot_w * i as f32, AREA.x + slot_w * (i + 1) as f32);
+            let matches = bar.matches.unwrap();
+            assert!(bar.dictionary.x >= slot.0);
+            assert_eq!(bar.dictionary.right(), matches.x);
+            assert!(matches.right() <= slot.1 + 1e-3);
+            assert_eq!(bar.center_x, (slot.0 + slot.1) / 2.0);
+        }
+        // Alone, the dictionary's bar takes both halves
+        let (alone, _) = chart_bars(AREA, &dictionary, None);
+        assert_eq!(alone[0].dictionary.w, bars[0].dictionary.w * 2.0);
+    }
+
+    #[test]
+    fn no_letters_give_flat_bars() {
+        let (bars, tallest) = chart_bars(AREA, &[0; 26], Some(&[0; 26]));
+        assert_eq!(tallest, 0.0);
+        for bar in &bars {
+            assert_eq!(bar.dictionary.h, 0.0);
+            assert_eq!(bar.matches.unwrap().h, 0.0);
+            assert_eq!(bar.dictionary.y, AREA.bottom());
+        }
+    }
+
+    #[test]
+    fn the_chart_draws_the_matches_beside_the_dictionary() {
+        let gfx = RecordingRenderer::new(vec2(800.0, 600.0));
+        let theme = Theme::default_theme();
+        let stats = WordMapStats {
+            title: None,
+            words: 3,
+            groups: 2,
+            letters: counts(&[('A', 4), ('E', 4), ('G', 2), ('R', 2), ('T', 4), ('L', 1)]),
+            merged: 0,
+            phrases: 0,
+        };
+        let matches = count_letters(&["GREAT".into(), "GRATE".into()]);
+
+        draw_stats(
+            &gfx,
+            StatsView::Off,
+            &stats,
+            Some(&matches),
+            None,
+            20,
+            &theme,
+        );
+        assert!(gfx.take().is_empty());
+
+        draw_stats(
+            &gfx,
+            StatsView::Chart,
+            &stats,
+            Some(&matches),
+            None,
+            20,
+            &theme,
+        );
+        let calls = gfx.take();
+        let rects = |color: Color| -> Vec<Rect> {
+            calls
+                .iter()
+                .filter_map(|call| match call {
+                    DrawCall::Rect { rect, color: c } if *c == color => Some(*rect),
+                    _ => None,
+                })
+                .collect()
+        };
+        // A legend swatch, then a bar per letter
+        let matched = rects(theme.highlight);
+        assert_eq!(matched.len(), 27);
+        let tall: String = matched[1..]
+            .iter()
+            .zip('A'..='Z')
+            .filter(|(rect, _)| rect.h > 0.0)
+            .map(|(_, letter)| letter)
+            .collect();
+        assert_eq!(tall, "AEGRT");
+        assert_eq!(rects(theme.result).len(), 27);
+        let labels = calls
+            .iter()
+            .filter(|call| matches!(call, DrawCall::Text { text, .. } if text.len() == 1))
+            .count();
+        assert_eq!(labels, 26);
+    }
+}
@@ -0,0 +1,113 @@
+//! Leaving letters out of every answer, for themed puzzle weeks.
+//!
+//! Some papers run weeks whose answers never use certain letters, such as "no
+//! Q, X, or Z". Typing `:letters -QXZ` in the entry, or choosing a set in the
+//! settings screen, leaves those letters out until the app closes: every tab
+//! drops the matches that use one, whatever the query, and so do the words
+//! offered to complete the entry and the fixes suggested for it. The on-screen
+//! keyboard greys the letters out, and the entry box says which are left out.
+//! `:letters` alone brings them all back.
+
+use crate::wordmap::fold_letter;
+use std::fmt;
+
+/// The sets of letters offered in the settings screen; the first leaves none
+/// out.
+pub const LETTER_PRESETS: [&str; 3] = ["", "QXZ", "JKQXZ"];
+
+/// The letters left out of the matches, uppercase and without accents.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LetterMask {
+    /// The letters, sorted, each once.
+    left_out: Vec<char>,
+}
+
+impl LetterMask {
+    /// Returns the mask leaving out the letters of `letters`, folded as the
+    /// entry folds them; anything else is skipped.
+    pub fn new(letters: &str) -> Self {
+        let mut left_out: Vec<char> = letters.chars().filter_map(fold_letter).collect();
+        left_out.sort_unstable();
+        left_out.dedup();
+        Self { left_out }
+    }
+    /// Parses the argument of `:letters`: a `-` followed by the letters to
+    /// leave out, as in `-QXZ`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let letters = text
+            .strip_prefix('-')
+            .ok_or_else(|| format!("'{text}' should be '-' and the letters to leave out"))?;
+        if letters.is_empty() || !letters.chars().all(|c| fold_letter(c).is_some()) {
+            return Err(format!("'{letters}' isn't all letters"));
+        }
+        Ok(Self::new(letters))
+    }
+    /// Returns whether any letter is left out.
+    pub fn is_active(&self) -> bool {
+        !self.left_out.is_empty()
+    }
+    /// Returns whether `c` is left out, whatever its case or accents.
+    pub fn has(&self, c: char) -> bool {
+        fold_letter(c).is_some_and(|c| self.left_out.binary_search(&c).is_ok())
+    }
+    /// Returns whether `word` uses none of the letters left out.
+    pub fn allows(&self, word: &str) -> bool {
+        !self.is_active() || !word.chars().any(|c| self.has(c))
+    }
+    /// Returns the letters left out, run together, as in `QXZ`.
+    pub fn letters(&self) -> String {
+        self.left_out.iter().collect()
+    }
+}
+
+impl fmt::Display for LetterMask {
+    /// Formats the mask as `:letters` takes it, as in `-QXZ`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "-{}", self.letters())
+    }
+}
+
+/// Returns the masks on offer in the settings screen: the presets, then
+/// `current` if it's none of them.
+pub fn letter_choices(current: &LetterMask) -> Vec<LetterMask> {
+    let mut choices: Vec<LetterMask> = LETTER_PRESETS.iter().map(|&p| LetterMask::new(p)).collect();
+    if !choices.contains(current) {
+        choices.push(current.clone());
+    }
+    choices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_are_folded_sorted_and_kept_once() {
+        let mask = LetterMask::new("zqxÉq!");
+        assert_eq!(mask.letters(), "EQXZ");
+        assert_eq!(mask.to_string(), "-EQXZ");
+        assert!(mask.has('é') && mask.has('q') && !mask.has('A'));
+        assert!(!LetterMask::new("").is_active());
+        assert_eq!(LetterMask::parse("-zxq"), Ok(LetterMask::new("QXZ")));
+        assert!(LetterMask::parse("QXZ").is_err());
+        assert!(LetterMask::parse("-").is_err());
+        assert!(LetterMask::parse("-Q1").is_err());
+    }
+
+    #[test]
+    fn words_using_a_letter_left_out_are_refused() {
+        let mask = LetterMask::new("QXZ");
+        assert!(mask.allows("GRATE"));
+        assert!(!mask.allows("QUEEN") && !mask.allows("boxes") && !mask.allows("HOT ZIP"));
+        assert!(LetterMask::default().allows("QUIZ"));
+    }
+
+    #[test]
+    fn a_mask_outside_the_presets_is_offered_too() {
+        let presets = letter_choices(&LetterMask::new("ZXQ"));
+        assert_eq!(presets.len(), LETTER_PRESETS.len());
+        let choices = letter_choices(&LetterMask::new("JQ"));
+        assert_eq!(choices.len(), LETTER_PRESETS.len() + 1);
+        assert_eq!(choices.last(), Some(&LetterMask::new("JQ")));
+    }
+}
@@ -0,0 +1,195 @@
+//! Jumble Helper for Mom.
+//!
+//! Key Ideas:
+//! - Jumbles generally use words of length 5.
+//! - There is a unique answer for each scrambled word in the Jumble.
+//!
+//! The app is built from this library; programs embedding the anagram solver
+//! should use `engine`, the one module whose API is kept stable. The app's own
+//! modules, and macroquad with them, are behind the default `ui` feature, so an
+//! embedder that turns default features off gets the solver alone.
+
+pub mod alloc_count;
+pub mod alphabet;
+pub mod animation;
+pub mod announce;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod assets;
+pub mod complete;
+pub mod date;
+pub mod dictionary;
+pub mod engine;
+pub mod entry;
+pub mod escalate;
+pub mod feedback;
+pub mod frequency;
+pub mod fresh;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod instance;
+pub mod letters;
+pub mod logging;
+pub mod memory;
+pub mod parse;
+pub mod query;
+pub mod rank;
+pub mod recall;
+pub mod strings;
+pub mod style;
+pub mod table;
+pub mod timing;
+pub mod wordmap;
+
+// The app itself, which needs the window
+#[cfg(feature = "ui")]
+pub mod app;
+#[cfg(feature = "ui")]
+pub mod assist;
+#[cfg(feature = "ui")]
+pub mod attract;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod backup;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod bench;
+#[cfg(feature = "ui")]
+pub mod big_mode;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod check;
+#[cfg(feature = "ui")]
+pub mod cli;
+#[cfg(feature = "ui")]
+pub mod config;
+#[cfg(feature = "ui")]
+pub mod confirmed;
+#[cfg(feature = "ui")]
+pub mod coverage;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod crash;
+#[cfg(feature = "ui")]
+pub mod debug;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod diff;
+#[cfg(feature = "ui")]
+pub mod digest;
+#[cfg(feature = "ui")]
+pub mod entry_screen;
+#[cfg(feature = "ui")]
+pub mod error_screen;
+#[cfg(feature = "ui")]
+pub mod events;
+#[cfg(feature = "ui")]
+pub mod export;
+#[cfg(feature = "ui")]
+pub mod font;
+#[cfg(feature = "ui")]
+pub mod glyphs;
+#[cfg(feature = "ui")]
+pub mod help;
+#[cfg(feature = "ui")]
+pub mod hints;
+#[cfg(feature = "ui")]
+pub mod history;
+#[cfg(feature = "ui")]
+pub mod inspect;
+#[cfg(feature = "ui")]
+pub mod keyboard;
+#[cfg(feature = "ui")]
+pub mod keymap;
+#[cfg(feature = "ui")]
+pub mod layout;
+#[cfg(feature = "ui")]
+pub mod length_filter;
+#[cfg(feature = "ui")]
+pub mod letter_chart;
+#[cfg(feature = "ui")]
+pub mod loading;
+#[cfg(feature = "ui")]
+pub mod mask;
+#[cfg(feature = "ui")]
+pub mod metrics;
+#[cfg(feature = "ui")]
+pub mod mini;
+#[cfg(feature = "ui")]
+pub mod modal;
+#[cfg(feature = "ui")]
+pub mod modifiers;
+#[cfg(feature = "ui")]
+pub mod onboarding;
+#[cfg(feature = "ui")]
+pub mod pairing;
+#[cfg(feature = "ui")]
+pub mod palette;
+#[cfg(feature = "ui")]
+pub mod phonetic;
+#[cfg(feature = "ui")]
+pub mod pins;
+#[cfg(feature = "ui")]
+pub mod platform;
+#[cfg(feature = "ui")]
+pub mod pointer;
+#[cfg(feature = "ui")]
+pub mod pool;
+#[cfg(feature = "ui")]
+pub mod post_process;
+#[cfg(feature = "ui")]
+pub mod record;
+#[cfg(feature = "ui")]
+pub mod redraw;
+#[cfg(feature = "ui")]
+pub mod remap;
+#[cfg(feature = "ui")]
+pub mod render;
+#[cfg(feature = "ui")]
+pub mod results;
+#[cfg(feature = "ui")]
+pub mod reveal;
+#[cfg(feature = "ui")]
+pub mod screen;
+#[cfg(feature = "ui")]
+pub mod session;
+#[cfg(feature = "ui")]
+pub mod settings;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod share;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod snapshot;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod soak;
+#[cfg(feature = "ui")]
+pub mod sound;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod spectate;
+#[cfg(feature = "ui")]
+pub mod storage;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod terminal;
+#[cfg(feature = "ui")]
+pub mod theme;
+#[cfg(feature = "ui")]
+pub mod tiles;
+#[cfg(feature = "ui")]
+pub mod toast;
+#[cfg(feature = "ui")]
+pub mod tutorial;
+#[cfg(feature = "ui")]
+pub mod typo;
+#[cfg(feature = "ui")]
+pub mod update;
+#[cfg(feature = "ui")]
+pub mod usage;
+#[cfg(all(feature = "ui", not(target_arch = "wasm32")))]
+pub mod verify;
+#[cfg(feature = "ui")]
+pub mod window_title;
+#[cfg(feature = "ui")]
+pub mod workspace;
+
+pub const MIN_WORD_LENGTH: usize = 4;
+pub const MAX_WORD_LENGTH: usize = 10;
+/// Most letters the entry holds when the dictionary has no words to size it by.
+pub const MAX_ENTRY_LENGTH: usize = 8;
+pub const ENTRY_TEXT_SIZE: u16 = 72;
+pub const RESULT_TEXT_SIZE: u16 = 48;
+/// How long the entry box flashes when a letter doesn't fit.
+pub const CAPACITY_FLASH_TIME: f64 = 0.25;
+/// Most entry tabs open at once.
+pub const MAX_TABS: usize = 4;
@@ -0,0 +1,305 @@
+//! The loading screen, shown while the dictionary is built in the background.
+
+use crate::app::AppContext;
+use crate::dictionary::{load_words, prepare_words, DictionaryOptions, DictionaryReport};
+use crate::entry_screen::EntryScreen;
+use crate::error_screen::ErrorScreen;
+use crate::events::AppEvent;
+use crate::frequency::load_frequencies;
+use crate::keymap::Action;
+use crate::onboarding::OnboardingScreen;
+use crate::render::Renderer;
+use crate::screen::{Screen, ScreenTrait, Transition};
+use crate::strings::{fill, Language, StringKey};
+use crate::theme::Theme;
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use macroquad::prelude::*;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// How many words the loader inserts between progress messages.
+pub const PROGRESS_INTERVAL: usize = 5000;
+
+/// Messages sent from the dictionary loader to the UI.
+pub enum LoadProgress {
+    /// Number of words loaded so far.
+    Words(usize),
+    /// The finished word map.
+    Done(Box<WordMap>),
+    /// Why the word list couldn't be read.
+    Failed(String),
+}
+
+/// State shown on the loading screen.
+pub struct LoadState {
+    pub words_loaded: usize,
+    loader: Receiver<LoadProgress>,
+}
+
+impl LoadState {
+    /// Starts loading the configured dictionary.
+    pub fn start(options: DictionaryOptions, lang: Language) -> Self {
+        Self {
+            words_loaded: 0,
+            loader: spawn_loader(options, lang),
+        }
+    }
+}
+
+impl ScreenTrait for LoadState {
+    fn handle_event(&mut self, _ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        match ev {
+            AppEvent::Action(Action::Quit) | AppEvent::CloseRequested => Some(Transition::Quit),
+            _ => None,
+        }
+    }
+    fn update(&mut self, ctx: &mut AppContext, _dt: f64) -> Option<Transition> {
+        loop {
+            match self.loader.try_recv() {
+                Ok(LoadProgress::Words(n)) => self.words_loaded = n,
+                Ok(LoadProgress::Failed(e)) => {
+                    return Some(Transition::To(Screen::Error(ErrorScreen::new(e))));
+                }
+                Ok(LoadProgress::Done(map)) if map.is_empty() => {
+                    let lang = ctx.state.config.language;
+                    let error = ErrorScreen::new(lang.text(StringKey::EmptyDictionary));
+                    return Some(Transition::To(Screen::Error(error)));
+                }
+                Ok(LoadProgress::Done(map)) => {
+                    ctx.set_dictionary(*map);
+                    if ctx.state.onboarding {
+                        let onboarding = Box::new(OnboardingScreen::new(ctx));
+                        return Some(Transition::To(Screen::Onboarding(onboarding)));
+                    }
+                    let entry = Box::new(EntryScreen::new(ctx));
+                    return Some(Transition::To(Screen::Entry(entry)));
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    log::error!("[LoadState::update] the dictionary loader stopped");
+                    let lang = ctx.state.config.language;
+                    let error = ErrorScreen::new(lang.text(StringKey::LoadFailed));
+                    return Some(Transition::To(Screen::Error(error)));
+                }
+            }
+        }
+    }
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = ctx.themes.current();
+        gfx.clear(theme.background);
+        draw_loading_screen(
+            gfx,
+            self,
+            ctx.font.as_ref(),
+            theme,
+            ctx.now,
+            ctx.state.config.language,
+        );
+    }
+    // The spinner turns every frame
+    fn is_animating(&self) -> bool {
+        true
+    }
+}
+
+/// Reads the word list and builds the word map in the background, reporting
+/// progress.
+///
+/// Web builds have no threads, so the map is built before this returns and the
+/// messages are simply waiting in the channel.
+pub fn spawn_loader(options: DictionaryOptions, lang: Language) -> Receiver<LoadProgress> {
+    let (tx, rx) = mpsc::channel();
+    let load = move || {
+        let progress_tx = tx.clone();
+        let text = load_words(&options, lang);
+        let (header, words) = match prepare_words(&text) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                log::error!("[spawn_loader] {e}");
+                let _ = tx.send(LoadProgress::Failed(e));
+                return;
+            }
+        };
+        let issues = DictionaryReport::check(&words, false).issue_count();
+        if issues > 0 {
+            log::warn!(
+                "[spawn_loader] the dictionary has {issues} issues; \
+                 run `jumble_helper check --dict <PATH>` for details"
+            );
+        }
+        let (alphabet, max_words) = (options.alphabet_for(&header), options.max_words);
+        let mut map =
+            make_word_map_with_progress(&words, alphabet, max_words, PROGRESS_INTERVAL, |n| {
+                log::debug!("[spawn_loader] {n} words loaded");
+                let _ = progress_tx.send(LoadProgress::Words(n));
+            });
+        map.set_header(header);
+        map.set_frequencies(load_frequencies(&text, &options, &map));
+        let _ = tx.send(LoadProgress::Done(Box::new(map)));
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(load);
+    #[cfg(target_arch = "wasm32")]
+    load();
+
+    rx
+}
+
+/// Draws the loading screen: app name, a spinner, and the words-loaded counter.
+pub fn draw_loading_screen(
+    gfx: &dyn Renderer,
+    state: &LoadState,
+    font: Option<&Font>,
+    theme: &Theme,
+    time: f64,
+    lang: Language,
+) {
+    let Vec2 { x: cx, y: cy } = gfx.screen_size() / 2.0;
+
+    let draw_centered = |text: &str, y: f32, size: u16, color: Color| {
+        let width = gfx.measure(text, font, size).width;
+        gfx.text(text, vec2(cx - width / 2.0, y), font, size, color);
+    };
+
+    draw_centered(
+        lang.text(StringKey::AppTitle),
+        cy - 60.0,
+        48,
+        theme.entry_text,
+    );
+
+    // Spinner: a ring of dots, brightest at the rotating head
+    const DOTS: usize = 12;
+    let head = (time * 12.0) as usize % DOTS;
+    for i in 0..DOTS {
+        let angle = i as f32 / DOTS as f32 * std::f32::consts::TAU;
+        let age = (head + DOTS - i) % DOTS;
+        let mut color = theme.highlight;
+        color.a = 1.0 - age as f32 / DOTS as f32;
+        let center = vec2(cx + 20.0 * angle.cos(), cy + 20.0 * angle.sin());
+        gfx.circle(center, 3.0, color);
+    }
+
+    let counter = fill(lang.text(StringKey::LoadedWords), state.words_loaded);
+    draw_centered(&counter, cy + 60.0, 24, theme.result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::app::{App, AppState};
+    use crate::config::Config;
+    use crate::sound::Sounds;
+    use crate::storage::FileStorage;
+    use std::sync::Arc;
+
+    #[test]
+    fn the_loader_reports_progress_then_the_map() {
+        let path =
+            std::env::temp_dir().join(format!("jumble_helper-loader-{}.txt", std::process::id()));
+        std::fs::write(&path, "pound\nupon\nsound\n").unwrap();
+        let options = DictionaryOptions {
+            path: Some(path.display().to_string()),
+            ..DictionaryOptions::default()
+        };
+        let messages: Vec<LoadProgress> = spawn_loader(options, Language::En).iter().collect();
+        std::fs::remove_file(&path).unwrap();
+        let counts: Vec<usize> = messages
+            .iter()
+            .filter_map(|m| match m {
+                LoadProgress::Words(n) => Some(*n),
+                LoadProgress::Done(_) | LoadProgress::Failed(_) => None,
+            })
+            .collect();
+        assert_eq!(counts, [3]);
+        assert!(
+            matches!(messages.last(), Some(LoadProgress::Done(map)) if map.find_match("DOSUN", 4, 8, &mut Vec::new()).is_some())
+        );
+    }
+
+    /// Measures every character as 0.6 em wide, so no window is needed.
+    fn measure(text: &str, _font: Option<&Font>, size: u16) -> TextDimensions {
+        let size = size as f32;
+        TextDimensions {
+            width: text.chars().count() as f32 * size * 0.6,
+            height: size,
+            offset_y: size * 0.8,
+        }
+    }
+
+    /// Feeds `messages` to a loading screen, returning where it leads; the loader
+    /// stops after sending them unless still `running`.
+    fn run(messages: Vec<LoadProgress>, running: bool) -> (LoadState, Option<Transition>) {
+        let dir = std::env::temp_dir().join("jumble_helper-loading-unused");
+        let state = AppState::new(Config::default(), Arc::new(FileStorage::new(dir)));
+        let sounds = Sounds::silent(0.0, true);
+        let size = vec2(800.0, 600.0);
+        let mut ctx = App::new(
+            state,
+            crate::keymap::Keymap::defaults(),
+            None,
+            sounds,
+            size,
+            || 0.0,
+            measure,
+        )
+        .ctx;
+
+        let (tx, rx) = mpsc::channel();
+        for message in messages {
+            tx.send(message).unwrap();
+        }
+        let sender = running.then_some(tx);
+        let mut load_state = LoadState {
+            words_loaded: 0,
+            loader: rx,
+        };
+        let transition = load_state.update(&mut ctx, 0.0);
+        drop(sender);
+        (load_state, transition)
+    }
+
+    #[test]
+    fn progress_updates_the_counter() {
+        let (state, transition) = run(
+            vec![LoadProgress::Words(5000), LoadProgress::Words(10000)],
+            true,
+        );
+        assert_eq!(state.words_loaded, 10000);
+        assert!(transition.is_none());
+    }
+
+    #[test]
+    fn an_empty_map_leads_to_the_error_screen() {
+        let (_, transition) = run(
+            vec![LoadProgress::Done(Box::new(WordMap::new(
+                Alphabet::English,
+            )))],
+            false,
+        );
+        let Some(Transition::To(Screen::Error(error))) = transition else {
+            panic!("no error screen");
+        };
+        assert_eq!(error.message, Language::En.text(StringKey::EmptyDictionary));
+    }
+
+    #[test]
+    fn a_loaded_map_leads_to_the_entry_screen() {
+        let map = make_word_map_with_progress("UPON\n", Alphabet::English, 10, 1, |_| ());
+        let (_, transition) = run(vec![LoadProgress::Done(Box::new(map))], false);
+        let Some(Transition::To(Screen::Entry(entry))) = transition else {
+            panic!("no entry screen");
+        };
+        assert_eq!(entry.entry(), "");
+    }
+
+    #[test]
+    fn a_loader_that_stops_leads_to_the_error_screen() {
+        let (_, transition) = run(vec![LoadProgress::Words(5000)], false);
+        let Some(Transition::To(Screen::Error(error))) = transition else {
+            panic!("no error screen");
+        };
+        assert_eq!(error.message, Language::En.text(StringKey::LoadFailed));
+    }
+}
@@ -0,0 +1,263 @@
+//! Diagnostics through the `log` facade, printed to stderr and, natively, written
+//! to a log file in the user data directory.
+//!
+//! The file is rotated when it reaches `MAX_LOG_BYTES`, keeping `LOG_GENERATIONS`
+//! older files (`jumble_helper.log.1`, `.2`). The level comes from `RUST_LOG` if
+//! set, else the config's `log_level`.
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::date::civil_from_days;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const LOG_FILE: &str = "jumble_helper.log";
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+pub const LOG_GENERATIONS: usize = 2;
+
+/// The open log file and how much has been written to it.
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+}
+
+impl LogFile {
+    /// Opens `path` for appending, rotating it first if it's already full.
+    fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        let mut log_file = Self {
+            path,
+            file,
+            written,
+        };
+        if log_file.written >= MAX_LOG_BYTES {
+            log_file.rotate()?;
+        }
+        Ok(log_file)
+    }
+    /// Appends `line`, rotating first if it wouldn't fit.
+    fn write(&mut self, line: &str) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+    /// Moves the current file to the first generation and starts a new one.
+    fn rotate(&mut self) -> io::Result<()> {
+        rotate_files(&self.path, LOG_GENERATIONS)?;
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Shifts `path` to `path.1`, `path.1` to `path.2`, and so on, keeping at most
+/// `generations` (at least one) old files.
+pub fn rotate_files(path: &Path, generations: usize) -> io::Result<()> {
+    let generation = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    };
+    let rename = |from: &Path, to: &Path| match std::fs::rename(from, to) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    };
+
+    for n in (1..generations).rev() {
+        rename(&generation(n), &generation(n + 1))?;
+    }
+    rename(path, &generation(1))
+}
+
+/// Writes records to stderr and the log file, if one could be opened.
+///
+/// File errors close the file rather than failing, so logging never panics.
+struct Logger {
+    file: Mutex<Option<LogFile>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("{} {:<5} {}\n", timestamp(), record.level(), record.args());
+        eprint!("{line}");
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Some(log_file) = file.as_mut() {
+            if let Err(e) = log_file.write(&line) {
+                eprintln!("[Logger::log] log file closed after write error: {e}");
+                *file = None;
+            }
+        }
+    }
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(log_file) = file.as_mut() {
+                let _ = log_file.file.flush();
+            }
+        }
+    }
+}
+
+/// Starts logging at `level`, to `LOG_FILE` in `dir` as well as stderr if a
+/// directory is given and writable.
+pub fn init_logging(dir: Option<&Path>, level: LevelFilter) {
+    let file = dir.and_then(|dir| match LogFile::open(dir.join(LOG_FILE)) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("[init_logging] not logging to '{}': {e}", dir.display());
+            None
+        }
+    });
+    let logger = Logger {
+        file: Mutex::new(file),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Returns the level named by `RUST_LOG` (`env`), else by the config, else `Info`.
+///
+/// Only a bare level is understood, or the last one in `target=level` form.
+pub fn select_level(env: Option<&str>, config: &str) -> LevelFilter {
+    let parse = |name: &str| name.rsplit('=').next()?.trim().parse().ok();
+    env.and_then(parse)
+        .or_else(|| parse(config))
+        .unwrap_or(LevelFilter::Info)
+}
+
+/// Returns the current UTC time as `YYYY-MM-DD HH:MM:SS`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    let (h, m, s) = (rem / 3600, rem % 3600 / 60, rem % 60);
+    format!("{year:04}-{month:02}-{day:02} {h:02}:{m:02}:{s:02}")
+}
+
+/// Web builds have no system clock, so lines go unstamped.
+#[cfg(target_arch = "wasm32")]
+pub fn timestamp() -> String {
+    String::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an empty directory of its own under the system's temporary directory.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Returns the contents of `dir/name`, or `None` if it doesn't exist.
+    fn read(dir: &Path, name: &str) -> Option<String> {
+        std::fs::read_to_string(dir.join(name)).ok()
+    }
+
+    #[test]
+    fn rotation_shifts_generations_and_drops_the_oldest() {
+        let dir = scratch_dir("rotate");
+        let path = dir.join(LOG_FILE);
+        for text in ["first", "second", "third", "fourth"] {
+            std::fs::write(&path, text).unwrap();
+            rotate_files(&path, 2).unwrap();
+        }
+        assert_eq!(read(&dir, LOG_FILE), None);
+        assert_eq!(read(&dir, "jumble_helper.log.1").as_deref(), Some("fourth"));
+        assert_eq!(read(&dir, "jumble_helper.log.2").as_deref(), Some("third"));
+        assert_eq!(read(&dir, "jumble_helper.log.3"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotation_keeps_at_least_one_generation() {
+        let dir = scratch_dir("rotate-zero");
+        let path = dir.join(LOG_FILE);
+        std::fs::write(&path, "only").unwrap();
+        rotate_files(&path, 0).unwrap();
+        assert_eq!(read(&dir, "jumble_helper.log.1").as_deref(), Some("only"));
+        // Nothing to rotate is not an error
+        rotate_files(&path, 2).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_full_log_file_rotates_before_the_line_that_overflows_it() {
+        let dir = scratch_dir("rotate-write");
+        let mut log_file = LogFile::open(dir.join(LOG_FILE)).unwrap();
+        let line = "x".repeat(MAX_LOG_BYTES as usize / 2);
+        log_file.write(&line).unwrap();
+        log_file.write(&line).unwrap();
+        assert_eq!(read(&dir, "jumble_helper.log.1"), None);
+        log_file.write("overflow\n").unwrap();
+        assert_eq!(read(&dir, LOG_FILE).as_deref(), Some("overflow\n"));
+        assert_eq!(
+            read(&dir, "jumble_helper.log.1").unwrap().len(),
+            line.len() * 2
+        );
+
+        // A full file left by the last run rotates when opened
+        std::fs::write(dir.join(LOG_FILE), "x".repeat(MAX_LOG_BYTES as usize)).unwrap();
+        let log_file = LogFile::open(dir.join(LOG_FILE)).unwrap();
+        assert_eq!(log_file.written, 0);
+        let len = |name| read(&dir, name).map(|text| text.len());
+        assert_eq!(len(LOG_FILE), Some(0));
+        assert_eq!(len("jumble_helper.log.1"), Some(MAX_LOG_BYTES as usize));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn an_unwritable_directory_is_refused() {
+        let dir = scratch_dir("unwritable");
+        // A file where the directory should be can't be created into
+        let blocker = dir.join("blocker");
+        std::fs::write(&blocker, "").unwrap();
+        assert!(LogFile::open(blocker.join(LOG_FILE)).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rust_log_overrides_the_config() {
+        assert_eq!(select_level(None, "warn"), LevelFilter::Warn);
+        assert_eq!(select_level(Some("debug"), "warn"), LevelFilter::Debug);
+        assert_eq!(select_level(Some("Trace"), "warn"), LevelFilter::Trace);
+        assert_eq!(
+            select_level(Some("jumble_helper=error"), "warn"),
+            LevelFilter::Error
+        );
+        assert_eq!(select_level(Some("off"), "warn"), LevelFilter::Off);
+    }
+
+    #[test]
+    fn unknown_levels_fall_back() {
+        assert_eq!(select_level(Some("loud"), "warn"), LevelFilter::Warn);
+        assert_eq!(select_level(Some(""), "quiet"), LevelFilter::Info);
+        assert_eq!(select_level(None, ""), LevelFilter::Info);
+    }
+}
@@ -4,268 +4,447 @@
 //! - Jumbles generally use words of length 5.
 //! - There is a unique answer for each scrambled word in the Jumble.
 
-mod wordmap;
-
+use app::*;
+use cli::*;
+use config::*;
+use events::*;
+use font::*;
+use jumble_helper::*;
+use keymap::*;
+use logging::*;
 use macroquad::prelude::*;
-use wordmap::*;
-
-pub const MIN_WORD_LENGTH: usize = 4;
-pub const MAX_WORD_LENGTH: usize = 10;
-pub const ENTRY_TEXT_SIZE: u16 = 72;
-pub const RESULT_TEXT_SIZE: u16 = 48;
-
-fn window_conf() -> Conf {
+use modifiers::*;
+use platform::*;
+use record::*;
+use redraw::*;
+use render::*;
+use screen::*;
+use sound::*;
+use std::sync::Arc;
+use storage::*;
+use strings::*;
+use window_title::*;
+
+fn window_conf(backend: miniquad::conf::LinuxBackend, lang: Language, size: Vec2) -> Conf {
     Conf {
-        window_title: "Jumble Helper".to_owned(),
-        window_width: 800,
-        window_height: 600,
+        window_title: lang.text(StringKey::AppTitle).to_owned(),
+        window_width: size.x as i32,
+        window_height: size.y as i32,
         fullscreen: false,
         platform: miniquad::conf::Platform {
-            linux_backend: miniquad::conf::LinuxBackend::WaylandOnly,
+            linux_backend: backend,
             ..Default::default()
         },
         ..Default::default()
     }
 }
 
-/// Whether the text entry data needs to be changed.
-enum EntryStatus {
-    Changed,
-    Unchanged,
-    Quit,
-}
-
-/// Stores placement and dimensions for window components.
-///
-/// - `scx`, `scy`: screen center x and y values.
-/// - `tcx`, `tcy`: text box center x and y values.
-/// - `tlx`, `tby`: text box left x, bottom y, and top y values.
-/// - `tw`, `th`: text box width (x) and height (y) values.
-/// - `tw2`: half of text box width (x).
-/// - `ew`: width of the current text entered by the user.
-/// - `margin_y`: space between boxes and results along the y-axis.
-/// - `offset_y`: amount by which text entry box is shifted upwards.
-struct WindowValues {
-    scx: f32,
-    tcx: f32,
-    tlx: f32,
-    tby: f32,
-    tty: f32,
-    tw: f32,
-    th: f32,
-    margin_y: f32,
+/// Loads key bindings from the config, falling back to the defaults if invalid.
+fn load_keymap(config: &Config) -> Keymap {
+    Keymap::with_overrides(&config.keys).unwrap_or_else(|e| {
+        log::warn!("[load_keymap] invalid key bindings, using defaults: {e}");
+        Keymap::defaults()
+    })
 }
 
-impl WindowValues {
-    fn new(text: &str, font: Option<&Font>, size: u16) -> Self {
-        let sw = screen_width();
-        let sh = screen_height();
-        let scx = sw / 2.0;
-        let scy = sh / 2.0;
-        let tdims = measure_text(text, font, size, 1.0);
-        let center = get_text_center(text, font, size, 1.0, 0.0);
-        let offset_y = sh / 20.0;
-
-        let tcx = center.x;
-        let tw = tdims.width;
-        let th = tdims.height;
-        let tlx = scx - tw / 2.0;
-        let tcy = scy - offset_y;
-        let tby = tcy + th / 2.0;
-        let tty = tcy - th / 2.0;
-        let margin_y = 0.0;
-
-        Self {
-            scx,
-            tcx,
-            tlx,
-            tby,
-            tty,
-            tw,
-            th,
-            margin_y,
+fn main() {
+    let cli = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(2);
         }
+    };
+    if cli.help {
+        println!("{USAGE}");
+        return;
     }
-}
-
-/// Draws the data entry box.
-fn draw_text_box(wv: &WindowValues) {
-    // Text box
-    draw_rectangle_lines(wv.tlx, wv.tty, wv.tw, wv.th, 1.0, BLUE);
-    // Debug circle
-    draw_circle(wv.tlx, wv.tty, 3.0, BLUE);
-}
-
-/// Draws the text entered by the user, centered in text box.
-///
-/// Entry offset shifts text to the left to keep it centered in the text box.
-///
-/// In Macroquad, text is drawn from the *bottom*, as opposed to rectangles, which are
-/// drawn from the *top* of the shape.
-fn draw_text_entry(text: &str, font: Option<&Font>, size: u16, offset_x: f32, wv: &WindowValues) {
-    let x = wv.scx - offset_x;
-    let y = wv.tby;
-
-    // Debug circle at text start
-    draw_circle(x, y, 3.0, ORANGE);
-
-    draw_text_ex(
-        text,
-        x,
-        y,
-        TextParams {
-            font_size: size,
-            font: font,
-            ..Default::default()
-        },
-    );
-}
-
-/// Draws a single text result at `pos` and centered on the x-axis.
-fn draw_answer_centered(text: &str, font: Option<&Font>, size: u16, wv: &WindowValues) {
-    let answer_dims = measure_text(text, font, size, 1.0);
-    let answer_w = answer_dims.width;
-    let answer_h = answer_dims.height;
 
-    let x = wv.tcx - answer_w / 2.0;
-    let y = wv.tby + (wv.margin_y + answer_h);
-
-    // Reference line
-    draw_line(
-        x - 2.0,
-        y - answer_h,
-        x + answer_w + 2.0,
-        y - answer_h,
-        1.0,
-        GREEN,
+    #[cfg(not(target_arch = "wasm32"))]
+    let log_dir = Some(assets::data_dir());
+    #[cfg(target_arch = "wasm32")]
+    let log_dir: Option<std::path::PathBuf> = None;
+    let rust_log = std::env::var("RUST_LOG").ok();
+    init_logging(
+        log_dir.as_deref(),
+        select_level(rust_log.as_deref(), "info"),
     );
-    // Debug circle
-    draw_circle(x - 2.0, y - answer_h, 3.0, GREEN);
-
-    draw_text_ex(
-        text,
-        x,
-        y,
-        TextParams {
-            font_size: size,
-            font: font,
-            ..Default::default()
-        },
+    log::info!(
+        "[main] jumble_helper {} on {}/{}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
     );
-}
 
-/// Handles keyboard input.
-fn handle_keyboard_input(entry: &mut String, maxlen: usize) -> EntryStatus {
-    let keys_up = get_keys_released();
-    let num_keys_up = keys_up.len();
+    let storage = default_storage();
+    let config = Config::load(storage.as_ref());
+    log::set_max_level(select_level(rust_log.as_deref(), &config.log_level));
+    let keymap = load_keymap(&config);
 
-    if num_keys_up == 0 {
-        return EntryStatus::Unchanged;
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(check) = &cli.check {
+        std::process::exit(check::run_check(check, &config.dictionary));
     }
-
-    if keys_up.contains(&KeyCode::Backspace) {
-        entry.pop();
-        return EntryStatus::Changed;
+    #[cfg(target_arch = "wasm32")]
+    if cli.check.is_some() {
+        log::warn!("[main] check is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(dir) = &cli.snapshot {
+        std::process::exit(snapshot::run_snapshots(dir));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.snapshot.is_some() {
+        log::warn!("[main] snapshot is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(top) = cli.history {
+        std::process::exit(history::run_history(top));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.history.is_some() {
+        log::warn!("[main] history is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(digest) = &cli.digest {
+        std::process::exit(digest::run_digest(digest));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.digest.is_some() {
+        log::warn!("[main] digest is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = &cli.coverage {
+        let lang = config.language;
+        std::process::exit(coverage::run_coverage(path, &config.dictionary, lang));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.coverage.is_some() {
+        log::warn!("[main] coverage is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(diff) = &cli.diff {
+        std::process::exit(diff::run_diff(diff, &config.dictionary));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.diff.is_some() {
+        log::warn!("[main] diff is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(day) = cli.restore {
+        std::process::exit(backup::run_restore(&assets::data_dir(), day));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.restore.is_some() {
+        log::warn!("[main] restore is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(merge) = &cli.merge {
+        let (key, format) = (confirmed::CONFIRMED_KEY, confirmed::CONFIRMED_FORMAT);
+        let text = match storage.read_format(key, format) {
+            Ok(text) => text.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("[main] can't read {key}: {e}");
+                std::process::exit(2);
+            }
+        };
+        let confirmed = confirmed::Confirmed::parse(&text);
+        std::process::exit(check::run_merge(merge, &confirmed, &config.dictionary));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.merge.is_some() {
+        log::warn!("[main] confirmed is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(prep) = &cli.prep {
+        std::process::exit(check::run_prep(prep, &config.dictionary));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.prep.is_some() {
+        log::warn!("[main] prep is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(verify) = &cli.verify {
+        let (options, lang) = (&config.dictionary, config.language);
+        std::process::exit(verify::run_verify(verify, options, lang, &config.ranking));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.verify.is_some() {
+        log::warn!("[main] verify is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if cli.metrics {
+        std::process::exit(usage::run_metrics());
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.metrics {
+        log::warn!("[main] metrics is not supported on the web");
     }
 
-    if keys_up.contains(&KeyCode::Delete) {
-        entry.clear();
-        return EntryStatus::Changed;
+    if cli.print_keymap {
+        print!("{}", keymap.describe());
+        return;
     }
 
-    if keys_up.contains(&KeyCode::Escape) {
-        return EntryStatus::Quit;
+    // A spectator runs none of the app, so it neither takes the lock nor backs up
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(url) = cli.spectate.clone() {
+        let backend = linux_backend(cli.backend.unwrap_or(config.backend), |var| {
+            std::env::var(var).ok()
+        });
+        let conf = window_conf(backend, config.language, mini::DEFAULT_WINDOW_SIZE);
+        macroquad::Window::from_config(conf, spectate::run_spectator(url, config, keymap));
+        return;
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.spectate.is_some() {
+        log::warn!("[main] spectate is not supported on the web");
     }
 
-    let entry_len = entry.len();
-    let entry_rem = maxlen - entry_len;
-    let keys_to_take = entry_rem.min(num_keys_up);
+    #[cfg(not(target_arch = "wasm32"))]
+    if cli.terminal {
+        let (options, lang) = (&config.dictionary, config.language);
+        std::process::exit(terminal::run_terminal(&cli, options, lang, &config.ranking));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.terminal {
+        log::warn!("[main] terminal is not supported on the web");
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if cli.bench_latency {
+        std::process::exit(bench::run_bench(cli.fail_over));
+    }
+    #[cfg(target_arch = "wasm32")]
+    if cli.bench_latency {
+        log::warn!("[main] bench-latency is not supported on the web");
+    }
 
-    for keycode in keys_up.iter().take(keys_to_take) {
-        match keycode {
-            KeyCode::A => entry.push('A'),
-            KeyCode::B => entry.push('B'),
-            KeyCode::C => entry.push('C'),
-            KeyCode::D => entry.push('D'),
-            KeyCode::E => entry.push('E'),
-            KeyCode::F => entry.push('F'),
-            KeyCode::G => entry.push('G'),
-            KeyCode::H => entry.push('H'),
-            KeyCode::I => entry.push('I'),
-            KeyCode::J => entry.push('J'),
-            KeyCode::K => entry.push('K'),
-            KeyCode::L => entry.push('L'),
-            KeyCode::M => entry.push('M'),
-            KeyCode::N => entry.push('N'),
-            KeyCode::O => entry.push('O'),
-            KeyCode::P => entry.push('P'),
-            KeyCode::Q => entry.push('Q'),
-            KeyCode::R => entry.push('R'),
-            KeyCode::S => entry.push('S'),
-            KeyCode::T => entry.push('T'),
-            KeyCode::U => entry.push('U'),
-            KeyCode::V => entry.push('V'),
-            KeyCode::W => entry.push('W'),
-            KeyCode::X => entry.push('X'),
-            KeyCode::Y => entry.push('Y'),
-            KeyCode::Z => entry.push('Z'),
-            _ => (),
+    if let Some(path) = cli.replay.as_deref().filter(|_| !cli.visual) {
+        std::process::exit(run_replay(path, config, keymap));
+    }
+    // Nothing saved yet means a first run; recordings skip onboarding so they replay alike
+    let first_run = matches!(storage.read(CONFIG_KEY), Ok(None));
+    let onboarding = cli.onboarding || (first_run && cli.record.is_none() && cli.replay.is_none());
+    let (storage, events) = match open_event_log(&cli, storage) {
+        Ok(opened) => opened,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    // Replays run on storage of their own, so they neither hold the lock nor back up
+    #[cfg(not(target_arch = "wasm32"))]
+    let instance = match cli.replay {
+        Some(_) => None,
+        None => {
+            let data_dir = assets::data_dir();
+            let guard = match instance::acquire(&data_dir) {
+                Ok(instance::Startup::First(guard)) => Some(guard),
+                Ok(instance::Startup::AlreadyRunning) => return,
+                Err(e) => {
+                    log::warn!("[main] running without the single-instance lock: {e}");
+                    None
+                }
+            };
+            // Only with the lock held, as another instance could be mid-write
+            if guard.is_some() {
+                match remove_leftover_temps(&data_dir) {
+                    Ok(0) => (),
+                    Ok(n) => log::info!("[main] removed {n} files left by cut-short writes"),
+                    Err(e) => log::warn!("[main] can't look for leftover temporary files: {e}"),
+                }
+            }
+            if let Some(today) = date::today() {
+                backup::run_daily_backup(&data_dir, today);
+            }
+            guard
+        }
+    };
+    // miniquad can't raise its window, so a later launch gets a toast instead
+    #[cfg(not(target_arch = "wasm32"))]
+    let raised = move || {
+        instance
+            .as_ref()
+            .is_some_and(instance::InstanceGuard::take_raised)
+    };
+    #[cfg(target_arch = "wasm32")]
+    let raised = || false;
+
+    let backend_choice = cli.backend.unwrap_or(config.backend);
+    let backend = linux_backend(backend_choice, |var| std::env::var(var).ok());
+    log::info!("[main] {backend_choice:?} backend choice resolved to {backend:?}");
+    let can_resize = mini::can_resize(std::env::consts::OS, backend);
+    let size = match cli.mini && can_resize {
+        true => mini::MINI_SIZE,
+        false => mini::DEFAULT_WINDOW_SIZE,
+    };
+    let launch = Launch {
+        onboarding,
+        mini: cli.mini,
+        can_resize,
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // miniquad panics when it can't open a window at all; rather than show
+        // nothing, the scrambles are looked up in the terminal instead
+        let fallback = config.clone();
+        terminal::install_startup_hook();
+        let reason = terminal::open_window(&terminal::WINDOW_STARTED, || {
+            macroquad::Window::from_config(
+                window_conf(backend, config.language, size),
+                run(config, keymap, storage, events, launch, raised),
+            );
+        });
+        if let Some(reason) = reason {
+            eprintln!("{}\n", terminal::fallback_message(&reason));
+            let (options, lang) = (&fallback.dictionary, fallback.language);
+            let weights = &fallback.ranking;
+            std::process::exit(terminal::run_terminal(&cli, options, lang, weights));
         }
     }
-
-    EntryStatus::Changed
+    #[cfg(target_arch = "wasm32")]
+    macroquad::Window::from_config(
+        window_conf(backend, config.language, size),
+        run(config, keymap, storage, events, launch, raised),
+    );
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    // Setup
-    let font_loaded = load_ttf_font("./fonts/FiraMono-Bold.ttf").await.unwrap();
-    let font = Some(&font_loaded);
-
-    let words = include_str!("../dictionary/ENGLISH_US_4_TO_8.txt");
-    let word_map = make_word_map(words);
-    let max_word = "ABCDEFGH";
+/// Sets up recording or a visual replay, as asked for on the command line.
+///
+/// Replays run on storage of their own, so they don't touch the user's files.
+fn open_event_log(
+    cli: &CliArgs,
+    storage: Arc<dyn Storage>,
+) -> Result<(Arc<dyn Storage>, EventLog), String> {
+    let mut events = EventLog {
+        recorder: None,
+        replayer: None,
+        speed: cli.speed.unwrap_or(1.0),
+    };
+    if let Some(path) = &cli.replay {
+        let recording = read_recording(path)?;
+        let storage = replay_storage(&recording);
+        events.replayer = Some(Replayer::new(recording));
+        return Ok((storage, events));
+    }
+    if let Some(path) = &cli.record {
+        let saved_entry = storage.read_format(ENTRY_KEY, ENTRY_FORMAT);
+        let saved_entry = saved_entry.ok().flatten().unwrap_or_default();
+        events.recorder = Some(Recorder::create(path, &saved_entry)?);
+        log::info!("[open_event_log] recording events to '{path}'");
+    }
+    Ok((storage, events))
+}
 
-    // Entry and Answer (Jumble will only have one answer)
-    let mut entry: String = "".into();
-    let mut answer: String = "".into();
-    let mut entry_offset = 0.0;
+/// How the app was asked to start.
+struct Launch {
+    /// Whether to walk through onboarding.
+    onboarding: bool,
+    /// Whether to start in the mini window.
+    mini: bool,
+    /// Whether the app can resize its window (see `mini::can_resize`).
+    can_resize: bool,
+}
 
-    let wv = WindowValues::new(max_word, font, ENTRY_TEXT_SIZE);
+async fn run(
+    config: Config,
+    keymap: Keymap,
+    storage: Arc<dyn Storage>,
+    mut events: EventLog,
+    launch: Launch,
+    raised: impl Fn() -> bool,
+) {
+    // Setup
+    #[cfg(not(target_arch = "wasm32"))]
+    terminal::mark_window_started();
+    #[cfg(not(target_arch = "wasm32"))]
+    let updates = update::start(&config, storage.as_ref());
+    let mut state = AppState::new(config, storage);
+    state.onboarding = launch.onboarding;
+    state.mini = launch.mini;
+    state.install_panic_hook();
+    #[cfg(not(target_arch = "wasm32"))]
+    let crash = crash::CrashReporter::install(assets::data_dir());
+    #[cfg(not(target_arch = "wasm32"))]
+    share::start(&state.config, crash.summary());
+    // Closing the window asks first, so shutdown can save before exiting
+    prevent_quit();
+    let gfx = MacroquadRenderer;
+
+    let (font, font_source) = load_font(state.config.font_path.as_deref());
+    log::info!("[run] using {font_source}");
+
+    let sounds = Sounds::load(state.config.volume, state.config.muted).await;
+
+    // Touches are handled as pointer events, so don't also report them as mouse input
+    simulate_mouse_with_touch(false);
+    let size = gfx.screen_size();
+    let mut app = App::new(
+        state,
+        keymap,
+        font,
+        sounds,
+        size,
+        get_time,
+        macroquad_measure,
+    );
+    app.ctx.can_resize = launch.can_resize;
+    let mut frame = FrameCache::new();
+    let mut titles = TitleThrottle::default();
+    let mut modifiers = ModifierTracker::new(EVENTS_RELIABLE);
 
     // -------------------- //
     //      Main Loop       //
     // -------------------- //
 
     loop {
-        // Input Handling
-        let entry_status = handle_keyboard_input(&mut entry, 8);
-
-        match entry_status {
-            EntryStatus::Unchanged => (),
-            EntryStatus::Changed => {
-                entry_offset = measure_text(&entry, font, ENTRY_TEXT_SIZE, 1.0).width / 2.0;
-                println!("Entry is now '{entry}' with offset {entry_offset}");
-                let matches = word_map.find_match(&entry, MIN_WORD_LENGTH, MAX_WORD_LENGTH);
-                println!("Matches: {matches:?}");
-                if let Some(m) = matches {
-                    if m.len() == 1 {
-                        answer = m[0].clone();
-                    }
-                } else {
-                    answer.clear();
-                }
-            }
-            EntryStatus::Quit => {
-                break;
+        let polled = poll_events(&app.ctx.keymap, app.focus(), &mut modifiers);
+        let (now, generation) = (app.ctx.now, app.ctx.dictionary_generation);
+        for event in events.frame_events(polled, now, generation) {
+            app.handle_event(event);
+        }
+        if raised() {
+            app.raised();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(manifest) = updates.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            app.update_available(manifest);
+        }
+        app.update(get_frame_time() as f64 * events.speed);
+        if let Some(text) = app.take_clipboard() {
+            miniquad::window::clipboard_set(&text);
+        }
+        if let Some(size) = app.take_window_size() {
+            request_new_screen_size(size.x, size.y);
+        }
+        if let Some(set_title) = SET_TITLE {
+            let make = || title_for(app.screen(), &app.ctx.state.config);
+            if let Some(title) = titles.offer(app.ctx.now, make) {
+                set_title(&title);
             }
         }
+        if app.is_quitting() {
+            app.shutdown();
+            break;
+        }
 
-        // Drawing
-        draw_text_box(&wv);
-        draw_text_entry(&entry, font, ENTRY_TEXT_SIZE, entry_offset, &wv);
-        draw_answer_centered(&answer, font, RESULT_TEXT_SIZE, &wv);
+        // Nothing on screen changes without a redraw
+        if frame.begin(app.take_redraw()) {
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Screen::Entry(entry) = app.screen() {
+                crash.update(entry.summary());
+            }
+            app.draw(&gfx);
+        }
+        frame.present();
+        app.draw_overlay(&gfx, get_fps(), &frame);
 
+        let frame_start = get_time();
         next_frame().await;
+        if !(app.is_busy() || frame.is_dirty()) {
+            idle_sleep(get_time() - frame_start);
+        }
     }
 }
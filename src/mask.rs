@@ -0,0 +1,90 @@
+//! Keeping vulgar words out of the results.
+//!
+//! With `mask_words` on (the default), words on the mask are left out of the
+//! result list and never taken as the answer, and a count of how many were left
+//! out is shown instead. Alt+H shows them until the entry changes. The mask only
+//! applies to what's shown, so the word map keeps every word and turning it off
+//! takes effect at once.
+//!
+//! The mask is a short list built into the app, plus the words in `MASK_KEY` in
+//! the user data directory, one per line, if there is one.
+
+use crate::memory::MemorySize;
+use crate::storage::Format;
+use std::collections::HashSet;
+
+/// Storage key of the user's own words to mask.
+pub const MASK_KEY: &str = "masked_words.txt";
+/// Format of `MASK_KEY`, which the app only reads.
+pub const MASK_FORMAT: Format = Format {
+    name: "masked-words",
+    version: 1,
+};
+
+/// Words masked whether or not there's a `MASK_KEY`.
+const EMBEDDED_MASK: &str = include_str!("../dictionary/MASKED_WORDS.txt");
+
+/// The words kept out of the results.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WordMask {
+    words: HashSet<String>,
+}
+
+impl WordMask {
+    /// Returns the built-in mask with the words of `user_text` added, skipping
+    /// blank and `#` lines.
+    pub fn new(user_text: &str) -> Self {
+        let words = EMBEDDED_MASK
+            .lines()
+            .chain(user_text.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_uppercase)
+            .collect();
+        Self { words }
+    }
+    /// Adds `word` to the mask, returning false if it already was on it.
+    pub fn insert(&mut self, word: &str) -> bool {
+        self.words.insert(word.to_uppercase())
+    }
+    /// Returns whether `word` is masked, whatever its case.
+    pub fn hides(&self, word: &str) -> bool {
+        match word.chars().any(char::is_lowercase) {
+            true => self.words.contains(&word.to_uppercase()),
+            false => self.words.contains(word),
+        }
+    }
+}
+
+impl MemorySize for WordMask {
+    fn memory_size(&self) -> usize {
+        self.words.memory_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_users_words_are_added_to_the_built_in_ones() {
+        let mask = WordMask::new("# mine\n\n  grate \nUPON\n");
+        assert!(mask.hides("GRATE"));
+        assert!(mask.hides("UPON"));
+        assert!(!mask.hides("# MINE"));
+        assert!(!mask.hides(""));
+        let built_in = EMBEDDED_MASK.lines().next().unwrap();
+        assert!(mask.hides(built_in));
+        assert!(WordMask::new("").hides(built_in));
+        assert!(!WordMask::new("").hides("GRATE"));
+    }
+
+    #[test]
+    fn words_are_masked_whatever_their_case() {
+        let mask = WordMask::new("Grate\n");
+        for word in ["GRATE", "grate", "GrAtE"] {
+            assert!(mask.hides(word), "{word}");
+        }
+        assert!(!mask.hides("GREAT"));
+    }
+}
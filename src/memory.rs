@@ -0,0 +1,131 @@
+//! Rough accounting of the memory the long-lived stores hold.
+//!
+//! Each store that grows with use (the answer history, the pins, the entries
+//! Up and Down call back, and so on) implements `MemorySize`, and the entry
+//! screen registers them with a `MemoryReport` for the debug overlay, which
+//! shows the total and the largest. The sizes count what each store holds on
+//! the heap, spare room included, but not the store itself or what the
+//! allocator keeps around each allocation, so they run a little low.
+
+use std::collections::{HashSet, VecDeque};
+use std::mem::size_of;
+
+/// Something whose memory can be estimated.
+pub trait MemorySize {
+    /// Returns about how many bytes this holds on the heap.
+    fn memory_size(&self) -> usize;
+}
+
+impl MemorySize for String {
+    fn memory_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: MemorySize> MemorySize for Vec<T> {
+    fn memory_size(&self) -> usize {
+        let slots = self.capacity() * size_of::<T>();
+        slots + self.iter().map(T::memory_size).sum::<usize>()
+    }
+}
+
+impl<T: MemorySize> MemorySize for VecDeque<T> {
+    fn memory_size(&self) -> usize {
+        let slots = self.capacity() * size_of::<T>();
+        slots + self.iter().map(T::memory_size).sum::<usize>()
+    }
+}
+
+impl<T: MemorySize> MemorySize for HashSet<T> {
+    fn memory_size(&self) -> usize {
+        // Each slot also has a control byte
+        let slots = self.capacity() * (size_of::<T>() + 1);
+        slots + self.iter().map(T::memory_size).sum::<usize>()
+    }
+}
+
+/// The sizes of the stores registered, in the order they were.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    stores: Vec<(&'static str, usize)>,
+}
+
+impl MemoryReport {
+    /// Adds `store`'s size under `name`.
+    pub fn register(&mut self, name: &'static str, store: &dyn MemorySize) {
+        self.stores.push((name, store.memory_size()));
+    }
+    /// Returns each store's name and size, in bytes.
+    pub fn stores(&self) -> &[(&'static str, usize)] {
+        &self.stores
+    }
+    /// Returns the size of all the stores together, in bytes.
+    pub fn total(&self) -> usize {
+        self.stores.iter().map(|&(_, bytes)| bytes).sum()
+    }
+    /// Returns the total and the `n` largest stores, as in
+    /// `1.2 MB (history 1.1 MB, recall 4.0 KB)`.
+    pub fn describe(&self, n: usize) -> String {
+        let mut stores = self.stores.clone();
+        stores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let largest: Vec<String> = stores
+            .iter()
+            .take(n)
+            .map(|&(name, bytes)| format!("{name} {}", format_bytes(bytes)))
+            .collect();
+        match largest.is_empty() {
+            true => format_bytes(self.total()),
+            false => format!("{} ({})", format_bytes(self.total()), largest.join(", ")),
+        }
+    }
+}
+
+/// Formats `bytes` in B, KB, or MB, as in `4.0 KB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f < KB {
+        format!("{bytes} B")
+    } else if bytes_f < KB * KB {
+        format!("{:.1} KB", bytes_f / KB)
+    } else {
+        format!("{:.1} MB", bytes_f / (KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_count_what_is_held_and_the_room_for_it() {
+        let word = String::with_capacity(16);
+        assert_eq!(word.memory_size(), 16);
+        let mut words: Vec<String> = Vec::with_capacity(4);
+        words.push("GRATE".to_owned());
+        let expected = 4 * size_of::<String>() + words[0].capacity();
+        assert_eq!(words.memory_size(), expected);
+        assert_eq!(Vec::<String>::new().memory_size(), 0);
+        let set: HashSet<String> = ["UPON".to_owned()].into();
+        assert!(set.memory_size() >= set.capacity() * size_of::<String>() + 4);
+    }
+
+    #[test]
+    fn the_report_totals_and_names_the_largest() {
+        let mut report = MemoryReport::default();
+        assert_eq!(report.describe(2), "0 B");
+        report.register("pins", &String::with_capacity(100));
+        report.register("history", &String::with_capacity(3 * 1024));
+        report.register("recall", &String::with_capacity(100));
+        assert_eq!(report.total(), 3 * 1024 + 200);
+        assert_eq!(report.stores()[0], ("pins", 100));
+        assert_eq!(report.describe(2), "3.2 KB (history 3.0 KB, pins 100 B)");
+    }
+
+    #[test]
+    fn bytes_are_shown_in_the_largest_unit_that_fits() {
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 / 2), "2.5 MB");
+    }
+}
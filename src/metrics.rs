@@ -0,0 +1,176 @@
+//! Cached text measurements.
+//!
+//! The bundled font is monospace, so after measuring one glyph the width of any
+//! string is simple arithmetic. Proportional user fonts fall back to measuring.
+
+use macroquad::prelude::*;
+
+/// Widths below this difference (in pixels) between 'i' and 'W' count as monospace.
+const MONOSPACE_TOLERANCE: f32 = 0.5;
+
+/// Measurements for one font at one size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    pub size: u16,
+    /// Advance width of every glyph, if the font is monospace.
+    pub advance: Option<f32>,
+    /// Width of the widest capital letter.
+    pub widest: f32,
+    /// Height of capital letters.
+    pub height: f32,
+    /// Distance from the top of capital letters down to the baseline.
+    pub offset_y: f32,
+}
+
+impl TextMetrics {
+    /// Builds metrics from `measure`, which returns the dimensions of a string.
+    pub fn from_measurer(size: u16, measure: impl Fn(&str) -> TextDimensions) -> Self {
+        let narrow = measure("iiiiiiiiii").width;
+        let wide = measure("WWWWWWWWWW");
+        let monospace = (narrow - wide.width).abs() <= MONOSPACE_TOLERANCE * 10.0;
+
+        let widest = ('A'..='Z')
+            .map(|c| measure(c.encode_utf8(&mut [0; 4])).width)
+            .fold(0.0, f32::max);
+
+        Self {
+            size,
+            advance: monospace.then_some(wide.width / 10.0),
+            widest,
+            height: wide.height,
+            offset_y: wide.offset_y,
+        }
+    }
+    /// Returns whether widths are computed arithmetically.
+    pub fn is_monospace(&self) -> bool {
+        self.advance.is_some()
+    }
+    /// Returns the width of `text`, calling `measure` only if not monospace.
+    pub fn width(&self, text: &str, measure: impl Fn(&str) -> TextDimensions) -> f32 {
+        match self.advance {
+            Some(advance) => advance * text.chars().count() as f32,
+            None => measure(text).width,
+        }
+    }
+    /// Returns the width of `count` copies of the widest capital letter.
+    pub fn widest_run(&self, count: usize) -> f32 {
+        match self.advance {
+            Some(advance) => advance * count as f32,
+            None => self.widest * count as f32,
+        }
+    }
+}
+
+/// Returns the largest font size up to `max_size` at which `text` is at most
+/// `target_width` wide, by binary search over `measure(text, size)`.
+///
+/// Empty text fits at any size, so it gets `max_size`. Never returns less than 1.
+pub fn fit_font_size(
+    text: &str,
+    target_width: f32,
+    max_size: u16,
+    measure: impl Fn(&str, u16) -> f32,
+) -> u16 {
+    let (mut lo, mut hi) = (1, max_size.max(1));
+    if text.is_empty() {
+        return hi;
+    }
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if measure(text, mid) <= target_width {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measures text in a fake font whose glyphs are `glyph_width(c)` wide and 30 high.
+    fn fake(glyph_width: impl Fn(char) -> f32) -> impl Fn(&str) -> TextDimensions {
+        move |text| TextDimensions {
+            width: text.chars().map(&glyph_width).sum(),
+            height: 30.0,
+            offset_y: 28.0,
+        }
+    }
+
+    #[test]
+    fn equal_advances_are_monospace() {
+        let metrics = TextMetrics::from_measurer(40, fake(|_| 24.0));
+        assert!(metrics.is_monospace());
+        assert_eq!(metrics.advance, Some(24.0));
+        assert_eq!(metrics.widest, 24.0);
+        assert_eq!((metrics.height, metrics.offset_y), (30.0, 28.0));
+    }
+
+    #[test]
+    fn small_differences_still_count_as_monospace() {
+        // Rounding in a real font leaves a fraction of a pixel between glyphs
+        let metrics = TextMetrics::from_measurer(40, fake(|c| if c == 'i' { 23.8 } else { 24.0 }));
+        assert!(metrics.is_monospace());
+    }
+
+    #[test]
+    fn narrow_i_means_proportional() {
+        let width = |c| match c {
+            'i' => 8.0,
+            'W' => 36.0,
+            'M' => 32.0,
+            _ => 20.0,
+        };
+        let metrics = TextMetrics::from_measurer(40, fake(width));
+        assert!(!metrics.is_monospace());
+        assert_eq!(metrics.widest, 36.0);
+        assert_eq!(metrics.widest_run(10), 360.0);
+    }
+
+    #[test]
+    fn monospace_widths_are_arithmetic() {
+        let metrics = TextMetrics::from_measurer(40, fake(|_| 24.0));
+        // Nothing is measured
+        let measure = |_: &str| -> TextDimensions { panic!("measured") };
+        assert_eq!(metrics.width("POUND", measure), 120.0);
+        assert_eq!(metrics.width("ÑANDU", measure), 120.0);
+        assert_eq!(metrics.width("", measure), 0.0);
+        assert_eq!(metrics.widest_run(8), 192.0);
+    }
+
+    /// Measures text as 0.6 of the font size per letter, like a typical font.
+    fn per_letter(text: &str, size: u16) -> f32 {
+        text.chars().count() as f32 * size as f32 * 0.6
+    }
+
+    #[test]
+    fn text_fits_at_the_largest_size_that_fits() {
+        // 5 letters at size 100 are 300 wide
+        assert_eq!(fit_font_size("GREAT", 300.0, 400, per_letter), 100);
+        assert_eq!(fit_font_size("GREAT", 299.0, 400, per_letter), 99);
+        // Longer text gets smaller letters
+        assert!(
+            fit_font_size("APPROACH", 300.0, 400, per_letter)
+                < fit_font_size("UPON", 300.0, 400, per_letter)
+        );
+    }
+
+    #[test]
+    fn the_size_never_exceeds_the_maximum() {
+        assert_eq!(fit_font_size("A", 10_000.0, 120, per_letter), 120);
+        assert_eq!(fit_font_size("A", 10_000.0, 0, per_letter), 1);
+    }
+
+    #[test]
+    fn text_too_wide_at_any_size_gets_the_smallest() {
+        assert_eq!(fit_font_size("APPROACH", 1.0, 400, per_letter), 1);
+    }
+
+    #[test]
+    fn empty_text_gets_the_maximum_without_measuring() {
+        let size = fit_font_size("", 0.0, 120, |_, _| panic!("measured empty text"));
+        assert_eq!(size, 120);
+    }
+}
@@ -0,0 +1,201 @@
+//! The mini window: a strip with just the entry and the best answer.
+//!
+//! F4 (or `--mini` at launch) switches to it and back. Where the window can be
+//! resized from the app (see `can_resize`), it shrinks to `MINI_SIZE` and grows
+//! back after; elsewhere the strip is drawn across the window as it is. The
+//! entry goes on the left and the top-ranked match on the right, with no list.
+//! What the full window would say under the answer is kept for a tooltip: a dot
+//! in the corner says there's something, and tapping the strip shows it for
+//! `TIP_TIME`.
+
+use crate::metrics::fit_font_size;
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::miniquad::conf::LinuxBackend;
+use macroquad::prelude::*;
+
+/// The window's size, in logical pixels, when it's mini.
+pub const MINI_SIZE: Vec2 = Vec2::new(500.0, 120.0);
+/// The window's size at launch, grown back to after a launch with `--mini`.
+pub const DEFAULT_WINDOW_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+/// Seconds the tooltip stays up after a tap.
+pub const TIP_TIME: f64 = 3.0;
+/// Fraction of the strip's width the entry gets.
+const ENTRY_SHARE: f32 = 0.45;
+/// Fraction of the strip's height each side's text may be at most.
+const TEXT_HEIGHT: f32 = 0.5;
+
+/// Returns whether the app can resize its own window on `os` (as in
+/// `std::env::consts::OS`) with `backend`. miniquad does on Windows, macOS, and
+/// X11; a Wayland compositor decides window sizes itself, and on the web and
+/// phones the window is the whole page or screen.
+pub fn can_resize(os: &str, backend: LinuxBackend) -> bool {
+    match os {
+        "windows" | "macos" => true,
+        "linux" => matches!(
+            backend,
+            LinuxBackend::X11Only | LinuxBackend::X11WithWaylandFallback
+        ),
+        _ => false,
+    }
+}
+
+/// Where the mini window's parts go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MiniLayout {
+    /// The whole strip.
+    pub strip: Rect,
+    pub entry: Rect,
+    pub answer: Rect,
+    /// Where the tooltip goes, over the bottom of the strip.
+    pub tip: Rect,
+}
+
+impl MiniLayout {
+    /// Lays the strip out in a window of `size`: across its full width, as tall
+    /// as `MINI_SIZE`'s proportions allow (or the window, if shorter), and
+    /// centered vertically.
+    pub fn new(size: Vec2) -> Self {
+        let h = size.y.min(size.x * MINI_SIZE.y / MINI_SIZE.x);
+        let strip = Rect::new(0.0, (size.y - h) / 2.0, size.x, h);
+        let pad = h * 0.1;
+        let inner = Rect::new(
+            strip.x + pad,
+            strip.y + pad,
+            strip.w - pad * 2.0,
+            h - pad * 2.0,
+        );
+        let entry_w = inner.w * ENTRY_SHARE;
+        let tip_h = h * 0.3;
+        Self {
+            strip,
+            entry: Rect::new(inner.x, inner.y, entry_w - pad / 2.0, inner.h),
+            answer: Rect::new(
+                inner.x + entry_w + pad / 2.0,
+                inner.y,
+                inner.w - entry_w - pad / 2.0,
+                inner.h,
+            ),
+            tip: Rect::new(strip.x, strip.bottom() - tip_h, strip.w, tip_h),
+        }
+    }
+    /// Returns the x of the line between the entry and the answer.
+    pub fn divider_x(&self) -> f32 {
+        (self.entry.right() + self.answer.x) / 2.0
+    }
+}
+
+/// Draws the strip: `entry` on the left, `answer` on the right in
+/// `answer_color`, each fitted to its side, and the tooltip if it's up, or a dot
+/// if there's a `status` to show in one.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_mini(
+    gfx: &dyn Renderer,
+    layout: &MiniLayout,
+    entry: &str,
+    answer: &str,
+    answer_color: Color,
+    status: &str,
+    tip_shown: bool,
+    font: Option<&Font>,
+    theme: &Theme,
+) {
+    let max_size = (layout.strip.h * TEXT_HEIGHT) as u16;
+    let fitted = |text: &str, area: Rect, color: Color| {
+        let size = fit_font_size(text, area.w, max_size, |text, size| {
+            gfx.measure(text, font, size).width
+        });
+        let width = gfx.measure(text, font, size).width;
+        let baseline = area.center().y + size as f32 * 0.35;
+        let x = area.center().x - width / 2.0;
+        gfx.text(text, vec2(x, baseline), font, size, color);
+    };
+    fitted(entry, layout.entry, theme.entry_text);
+    fitted(answer, layout.answer, answer_color);
+
+    let x = layout.divider_x();
+    let (top, bottom) = (layout.entry.y, layout.entry.bottom());
+    gfx.line(
+        vec2(x, top),
+        vec2(x, bottom),
+        theme.outline_thickness,
+        theme.box_outline,
+    );
+
+    if status.is_empty() {
+        return;
+    }
+    if !tip_shown {
+        let r = layout.strip.h * 0.04;
+        let center = vec2(layout.strip.right() - r * 2.5, layout.strip.y + r * 2.5);
+        gfx.circle(center, r, theme.result);
+        return;
+    }
+    let tip = layout.tip;
+    gfx.rect(tip, theme.background);
+    gfx.line(
+        vec2(tip.x, tip.y),
+        vec2(tip.right(), tip.y),
+        theme.outline_thickness,
+        theme.box_outline,
+    );
+    let size = fit_font_size(status, tip.w * 0.9, (tip.h * 0.6) as u16, |text, size| {
+        gfx.measure(text, font, size).width
+    });
+    let width = gfx.measure(status, font, size).width;
+    let baseline = tip.center().y + size as f32 * 0.35;
+    gfx.text(
+        status,
+        vec2(tip.center().x - width / 2.0, baseline),
+        font,
+        size,
+        theme.result,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_x11_windows_resize_on_linux() {
+        assert!(can_resize("windows", LinuxBackend::WaylandOnly));
+        assert!(can_resize("macos", LinuxBackend::WaylandOnly));
+        assert!(can_resize("linux", LinuxBackend::X11Only));
+        assert!(can_resize("linux", LinuxBackend::X11WithWaylandFallback));
+        assert!(!can_resize("linux", LinuxBackend::WaylandOnly));
+        assert!(!can_resize("linux", LinuxBackend::WaylandWithX11Fallback));
+        assert!(!can_resize("android", LinuxBackend::X11Only));
+        assert!(!can_resize("ios", LinuxBackend::X11Only));
+    }
+
+    #[test]
+    fn the_strip_fills_a_mini_window() {
+        let layout = MiniLayout::new(MINI_SIZE);
+        assert_eq!(layout.strip, Rect::new(0.0, 0.0, MINI_SIZE.x, MINI_SIZE.y));
+        assert!(layout.entry.right() < layout.divider_x());
+        assert!(layout.divider_x() < layout.answer.x);
+        assert!(
+            layout.entry.w < layout.answer.w,
+            "the answer gets the wider side"
+        );
+        assert_eq!(layout.tip.bottom(), layout.strip.bottom());
+        for part in [layout.entry, layout.answer, layout.tip] {
+            assert!(layout.strip.contains(part.point()));
+            assert!(layout.strip.contains(vec2(part.right(), part.bottom())));
+        }
+    }
+
+    #[test]
+    fn in_a_bigger_window_the_strip_keeps_its_proportions_and_centers() {
+        let layout = MiniLayout::new(DEFAULT_WINDOW_SIZE);
+        let strip = layout.strip;
+        assert_eq!(strip.w, DEFAULT_WINDOW_SIZE.x);
+        assert_eq!(strip.w / strip.h, MINI_SIZE.x / MINI_SIZE.y);
+        assert_eq!(strip.center().y, DEFAULT_WINDOW_SIZE.y / 2.0);
+
+        // A window flatter than the strip gives it its whole height
+        let layout = MiniLayout::new(vec2(800.0, 60.0));
+        assert_eq!(layout.strip, Rect::new(0.0, 0.0, 800.0, 60.0));
+    }
+}
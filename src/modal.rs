@@ -0,0 +1,190 @@
+//! Modal dialogs and routing input to them.
+//!
+//! While a dialog is open it owns the keyboard and pointer; the entry and key
+//! bindings see nothing until it closes.
+
+use crate::render::Renderer;
+use crate::strings::{Language, StringKey};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Dialogs that can capture input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modal {
+    /// Asks before quitting with work in progress.
+    ConfirmQuit,
+    /// Lists the key bindings (see `help`); any key closes it.
+    Help,
+    /// Finds and runs an action by name (see `palette`).
+    Palette,
+    /// Offers back the tabs autosaved before a crash (see `session`).
+    Restore,
+    /// Shows how the matches rank (see `rank`); any key closes it.
+    Ranking,
+    /// Spells the final answer from the entry's letters (see `pool`).
+    Pool,
+    /// Shows what's known about one result (see `inspect`).
+    Inspect,
+}
+
+impl Modal {
+    /// Returns the dialog's text in `lang`, one line per entry.
+    pub fn lines(self, lang: Language) -> Vec<&'static str> {
+        let keys: &[StringKey] = match self {
+            Modal::ConfirmQuit => &[StringKey::QuitPrompt, StringKey::QuitChoices],
+            Modal::Help => &[StringKey::HelpTitle, StringKey::HelpHint],
+            Modal::Palette | Modal::Ranking | Modal::Pool | Modal::Inspect => &[],
+            Modal::Restore => &[StringKey::RestorePrompt, StringKey::RestoreChoices],
+        };
+        keys.iter().map(|&key| lang.text(key)).collect()
+    }
+}
+
+/// Where input goes this frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    /// The entry and key bindings.
+    Entry,
+    /// The topmost open dialog.
+    Modal(Modal),
+}
+
+/// Open dialogs, newest on top; only the top one receives input.
+#[derive(Debug, Default)]
+pub struct FocusStack {
+    modals: Vec<Modal>,
+}
+
+impl FocusStack {
+    /// Opens `modal` above any already open, unless it's already on top.
+    pub fn open(&mut self, modal: Modal) {
+        if self.modals.last() != Some(&modal) {
+            self.modals.push(modal);
+        }
+    }
+    /// Closes the topmost dialog.
+    pub fn close(&mut self) {
+        self.modals.pop();
+    }
+    /// Returns who receives input.
+    pub fn focus(&self) -> Focus {
+        match self.modals.last() {
+            Some(&modal) => Focus::Modal(modal),
+            None => Focus::Entry,
+        }
+    }
+}
+
+/// Answers to the confirm-quit dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuitChoice {
+    Quit,
+    Stay,
+    SaveAndQuit,
+}
+
+impl QuitChoice {
+    /// Returns the choice made by a key pressed this frame, if any.
+    pub fn pressed() -> Option<QuitChoice> {
+        get_keys_pressed().into_iter().find_map(QuitChoice::for_key)
+    }
+    /// Returns the choice made by pressing `key`, if any.
+    pub fn for_key(key: KeyCode) -> Option<QuitChoice> {
+        match key {
+            KeyCode::Enter | KeyCode::KpEnter => Some(QuitChoice::Quit),
+            KeyCode::Escape => Some(QuitChoice::Stay),
+            KeyCode::S => Some(QuitChoice::SaveAndQuit),
+            _ => None,
+        }
+    }
+}
+
+/// Draws `modal` over a dimmed screen, centered.
+pub fn draw_modal(
+    gfx: &dyn Renderer,
+    modal: Modal,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+    lang: Language,
+) {
+    let lines = modal.lines(lang);
+    let line_h = font_size as f32 * 1.4;
+    let pad = font_size as f32;
+    let widest = lines
+        .iter()
+        .map(|line| gfx.measure(line, font, font_size).width)
+        .fold(0.0, f32::max);
+
+    let w = widest + pad * 2.0;
+    let h = line_h * lines.len() as f32 + pad * 2.0;
+    let panel = draw_panel(gfx, w, h, theme);
+
+    for (i, line) in lines.iter().enumerate() {
+        let line_w = gfx.measure(line, font, font_size).width;
+        let x = panel.center().x - line_w / 2.0;
+        let pos = vec2(x, panel.y + pad + line_h * (i as f32 + 0.75));
+        gfx.text(line, pos, font, font_size, theme.entry_text);
+    }
+}
+
+/// Dims the screen and draws an empty `w` by `h` dialog centered on it,
+/// returning where.
+pub fn draw_panel(gfx: &dyn Renderer, w: f32, h: f32, theme: &Theme) -> Rect {
+    let Vec2 { x: sw, y: sh } = gfx.screen_size();
+    let panel = Rect::new((sw - w) / 2.0, (sh - h) / 2.0, w, h);
+    let mut dim = theme.background;
+    dim.a = 0.7;
+    gfx.rect(Rect::new(0.0, 0.0, sw, sh), dim);
+    gfx.rect(panel, theme.background);
+    gfx.rect_lines(panel, theme.outline_thickness, theme.box_outline);
+    panel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_entry_has_focus_with_no_dialog_open() {
+        let mut stack = FocusStack::default();
+        assert_eq!(stack.focus(), Focus::Entry);
+        // Closing with nothing open changes nothing
+        stack.close();
+        assert_eq!(stack.focus(), Focus::Entry);
+    }
+
+    #[test]
+    fn an_open_dialog_takes_focus_until_closed() {
+        let mut stack = FocusStack::default();
+        stack.open(Modal::ConfirmQuit);
+        assert_eq!(stack.focus(), Focus::Modal(Modal::ConfirmQuit));
+        stack.close();
+        assert_eq!(stack.focus(), Focus::Entry);
+    }
+
+    #[test]
+    fn reopening_the_top_dialog_does_not_stack_it() {
+        let mut stack = FocusStack::default();
+        // Escape and a window close in the same frame both ask to quit
+        stack.open(Modal::ConfirmQuit);
+        stack.open(Modal::ConfirmQuit);
+        stack.close();
+        assert_eq!(stack.focus(), Focus::Entry);
+    }
+
+    #[test]
+    fn quit_dialog_keys() {
+        assert_eq!(QuitChoice::for_key(KeyCode::Enter), Some(QuitChoice::Quit));
+        assert_eq!(
+            QuitChoice::for_key(KeyCode::KpEnter),
+            Some(QuitChoice::Quit)
+        );
+        assert_eq!(QuitChoice::for_key(KeyCode::Escape), Some(QuitChoice::Stay));
+        assert_eq!(
+            QuitChoice::for_key(KeyCode::S),
+            Some(QuitChoice::SaveAndQuit)
+        );
+        assert_eq!(QuitChoice::for_key(KeyCode::A), None);
+    }
+}
@@ -0,0 +1,360 @@
+//! Which modifier keys are held, followed from key events.
+//!
+//! Reading the modifiers once a frame loses the order of what happened within
+//! it: Ctrl let go in the frame V went down reads as a plain V, and a modifier
+//! let go while the window was out of focus may never be reported up at all.
+//! `ModifierTracker` follows the down and up events of Ctrl, Shift, Alt, and
+//! Super instead, noting the modifiers each key went down with. It mends itself
+//! from the modifiers the platform reports with every other key, and lets them
+//! all go when the window loses the keyboard, so none is left stuck.
+//!
+//! Where modifier key events can't be trusted (see `EVENTS_RELIABLE`), the keys
+//! of each frame take the modifiers `is_key_down` reports instead.
+
+use crate::keymap::Modifiers;
+use macroquad::input::utils::{register_input_subscriber, repeat_all_miniquad_input};
+use macroquad::prelude::*;
+
+/// Whether this platform's modifier key events can be trusted. On the web the
+/// browser keeps some for its own shortcuts, so there they're polled for.
+pub const EVENTS_RELIABLE: bool = !cfg!(target_arch = "wasm32");
+
+/// Each modifier's left and right keys, and its flag in `Modifiers`.
+type ModifierKeys = ([KeyCode; 2], fn(&mut Modifiers) -> &mut bool);
+
+/// The keys of Ctrl, Shift, Alt, and Super.
+const MODIFIER_KEYS: [ModifierKeys; 4] = [
+    ([KeyCode::LeftControl, KeyCode::RightControl], |m| {
+        &mut m.ctrl
+    }),
+    ([KeyCode::LeftShift, KeyCode::RightShift], |m| &mut m.shift),
+    ([KeyCode::LeftAlt, KeyCode::RightAlt], |m| &mut m.alt),
+    ([KeyCode::LeftSuper, KeyCode::RightSuper], |m| &mut m.logo),
+];
+
+/// Returns whether `key` is a modifier, which does nothing pressed alone.
+pub fn is_modifier(key: KeyCode) -> bool {
+    MODIFIER_KEYS.iter().any(|(keys, _)| keys.contains(&key))
+}
+
+/// A key event, as the tracker takes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// A key went down, with the modifiers the platform reported held.
+    Down(KeyCode, Modifiers),
+    /// A key came up.
+    Up(KeyCode),
+    /// The window lost the keyboard, so the keys held won't be reported up.
+    FocusLost,
+}
+
+/// Follows the modifier keys held, and the keys pressed and released each frame
+/// with the modifiers they went down with.
+#[derive(Debug)]
+pub struct ModifierTracker {
+    /// Whether modifier key events are trusted, rather than polled for.
+    reliable: bool,
+    /// Modifier keys down, by their own events.
+    held: Vec<KeyCode>,
+    /// Keys down, each with the modifiers it went down with.
+    down: Vec<(KeyCode, Modifiers)>,
+    /// Keys that went down this frame.
+    pressed: Vec<(KeyCode, Modifiers)>,
+    /// Keys that came up this frame, with the modifiers they went down with.
+    released: Vec<(KeyCode, Modifiers)>,
+    /// The macroquad input subscriber the events come from, once registered.
+    subscriber: Option<usize>,
+}
+
+impl ModifierTracker {
+    /// Creates a tracker with nothing held, trusting modifier key events if
+    /// `reliable` (see `EVENTS_RELIABLE`).
+    pub fn new(reliable: bool) -> Self {
+        Self {
+            reliable,
+            held: Vec::new(),
+            down: Vec::new(),
+            pressed: Vec::new(),
+            released: Vec::new(),
+            subscriber: None,
+        }
+    }
+    /// Takes `event`.
+    pub fn feed(&mut self, event: KeyEvent) {
+        match event {
+            KeyEvent::Down(key, _) if self.down.iter().any(|&(k, _)| k == key) => (),
+            KeyEvent::Down(key, reported) => {
+                if is_modifier(key) {
+                    self.held.push(key);
+                } else {
+                    self.mend(reported);
+                }
+                let mods = self.modifiers();
+                self.down.push((key, mods));
+                self.pressed.push((key, mods));
+            }
+            KeyEvent::Up(key) => {
+                self.held.retain(|&k| k != key);
+                // A key that went down out of focus was never seen, so does nothing
+                if let Some(i) = self.down.iter().position(|&(k, _)| k == key) {
+                    self.released.push(self.down.remove(i));
+                }
+            }
+            KeyEvent::FocusLost => {
+                self.held.clear();
+                self.down.clear();
+            }
+        }
+    }
+    /// Brings the modifier keys held into line with the modifiers `reported`
+    /// with a key: one reported up is let go, as its up event was lost, and one
+    /// reported down is taken as its left key, as its down event was.
+    fn mend(&mut self, mut reported: Modifiers) {
+        for (keys, flag) in MODIFIER_KEYS {
+            let held = self.held.iter().any(|key| keys.contains(key));
+            match *flag(&mut reported) {
+                false if held => {
+                    log::debug!("[ModifierTracker::mend] letting go of stuck {keys:?}");
+                    self.held.retain(|key| !keys.contains(key));
+                }
+                true if !held => self.held.push(keys[0]),
+                _ => (),
+            }
+        }
+    }
+    /// Returns the modifiers held, by their key events.
+    pub fn modifiers(&self) -> Modifiers {
+        let mut mods = Modifiers::default();
+        for (keys, flag) in MODIFIER_KEYS {
+            *flag(&mut mods) = self.held.iter().any(|key| keys.contains(key));
+        }
+        mods
+    }
+    /// Starts a frame, forgetting the keys pressed and released in the last.
+    pub fn begin_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+    /// Ends a frame's events, returning the modifiers held. If modifier key
+    /// events aren't trusted, those are `polled`, and the frame's keys take them
+    /// too.
+    pub fn settle(&mut self, polled: Modifiers) -> Modifiers {
+        if self.reliable {
+            return self.modifiers();
+        }
+        for (_, mods) in self.pressed.iter_mut().chain(&mut self.released) {
+            *mods = polled;
+        }
+        polled
+    }
+    /// Returns the keys that went down this frame, with the modifiers held then.
+    pub fn pressed(&self) -> &[(KeyCode, Modifiers)] {
+        &self.pressed
+    }
+    /// Returns the keys that came up this frame, with the modifiers held when
+    /// they went down.
+    pub fn released(&self) -> &[(KeyCode, Modifiers)] {
+        &self.released
+    }
+    /// Takes this frame's key events from macroquad, returning the modifiers
+    /// held (see `settle`).
+    pub fn poll(&mut self) -> Modifiers {
+        self.begin_frame();
+        let subscriber = *self
+            .subscriber
+            .get_or_insert_with(register_input_subscriber);
+        repeat_all_miniquad_input(&mut Feed(self), subscriber);
+        self.settle(Modifiers::current())
+    }
+}
+
+/// Passes macroquad's key events on to a tracker.
+struct Feed<'a>(&'a mut ModifierTracker);
+
+impl miniquad::EventHandler for Feed<'_> {
+    fn update(&mut self) {}
+    fn draw(&mut self) {}
+    fn key_down_event(&mut self, key: KeyCode, mods: miniquad::KeyMods, _repeat: bool) {
+        let reported = Modifiers {
+            ctrl: mods.ctrl,
+            shift: mods.shift,
+            alt: mods.alt,
+            logo: mods.logo,
+        };
+        self.0.feed(KeyEvent::Down(key, reported));
+    }
+    fn key_up_event(&mut self, key: KeyCode, _mods: miniquad::KeyMods) {
+        self.0.feed(KeyEvent::Up(key));
+    }
+    fn window_minimized_event(&mut self) {
+        self.0.feed(KeyEvent::FocusLost);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::{Action, Keymap};
+
+    const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        shift: false,
+        alt: false,
+        logo: false,
+    };
+    const CTRL: Modifiers = Modifiers { ctrl: true, ..NONE };
+    const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..NONE
+    };
+
+    /// Feeds `events` to `tracker` as one frame, returning the modifiers held
+    /// after it.
+    fn frame(tracker: &mut ModifierTracker, events: &[KeyEvent]) -> Modifiers {
+        tracker.begin_frame();
+        for &event in events {
+            tracker.feed(event);
+        }
+        tracker.settle(NONE)
+    }
+
+    #[test]
+    fn keys_take_the_modifiers_held_when_they_went_down() {
+        let mut tracker = ModifierTracker::new(true);
+        let held = frame(
+            &mut tracker,
+            &[
+                KeyEvent::Down(KeyCode::LeftControl, NONE),
+                KeyEvent::Down(KeyCode::V, CTRL),
+                // Let go in the same frame, before V
+                KeyEvent::Up(KeyCode::LeftControl),
+                KeyEvent::Up(KeyCode::V),
+            ],
+        );
+        assert_eq!(held, NONE);
+        assert_eq!(
+            tracker.pressed(),
+            [(KeyCode::LeftControl, CTRL), (KeyCode::V, CTRL)]
+        );
+        assert_eq!(
+            tracker.released(),
+            [(KeyCode::LeftControl, CTRL), (KeyCode::V, CTRL)]
+        );
+        let keymap = Keymap::defaults();
+        assert!(keymap.pressed_actions(tracker.pressed()).is_empty());
+
+        // Ctrl+M over two frames is the chord, and a plain M after isn't
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::RightControl, NONE)]);
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::M, CTRL)]);
+        assert_eq!(
+            keymap.pressed_actions(tracker.pressed()),
+            [Action::ToggleMute]
+        );
+        frame(
+            &mut tracker,
+            &[
+                KeyEvent::Up(KeyCode::RightControl),
+                KeyEvent::Up(KeyCode::M),
+                KeyEvent::Down(KeyCode::M, NONE),
+            ],
+        );
+        assert_eq!(tracker.pressed(), [(KeyCode::M, NONE)]);
+        assert!(keymap.pressed_actions(tracker.pressed()).is_empty());
+    }
+
+    #[test]
+    fn shift_keeps_its_letters_plain() {
+        let mut tracker = ModifierTracker::new(true);
+        frame(
+            &mut tracker,
+            &[
+                KeyEvent::Down(KeyCode::LeftShift, NONE),
+                KeyEvent::Down(KeyCode::A, SHIFT),
+                KeyEvent::Up(KeyCode::A),
+            ],
+        );
+        assert_eq!(tracker.released(), [(KeyCode::A, SHIFT)]);
+        assert!(!tracker.released()[0].1.is_chord());
+        // Both Shift keys held count once, until both are up
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::RightShift, SHIFT)]);
+        assert_eq!(
+            frame(&mut tracker, &[KeyEvent::Up(KeyCode::LeftShift)]),
+            SHIFT
+        );
+        assert_eq!(
+            frame(&mut tracker, &[KeyEvent::Up(KeyCode::RightShift)]),
+            NONE
+        );
+    }
+
+    #[test]
+    fn repeats_of_a_held_key_are_not_pressed_again() {
+        let mut tracker = ModifierTracker::new(true);
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::B, NONE)]);
+        frame(
+            &mut tracker,
+            &[
+                KeyEvent::Down(KeyCode::B, NONE),
+                KeyEvent::Down(KeyCode::B, NONE),
+            ],
+        );
+        assert!(tracker.pressed().is_empty());
+        frame(&mut tracker, &[KeyEvent::Up(KeyCode::B)]);
+        assert_eq!(tracker.released(), [(KeyCode::B, NONE)]);
+    }
+
+    #[test]
+    fn losing_focus_mid_chord_lets_everything_go() {
+        let mut tracker = ModifierTracker::new(true);
+        frame(
+            &mut tracker,
+            &[
+                KeyEvent::Down(KeyCode::LeftControl, NONE),
+                KeyEvent::Down(KeyCode::LeftAlt, CTRL),
+                KeyEvent::Down(KeyCode::T, Modifiers { alt: true, ..CTRL }),
+            ],
+        );
+        assert!(tracker.modifiers().ctrl && tracker.modifiers().alt);
+        // The keys come up while another window has the keyboard
+        assert_eq!(frame(&mut tracker, &[KeyEvent::FocusLost]), NONE);
+        frame(
+            &mut tracker,
+            &[KeyEvent::Up(KeyCode::T), KeyEvent::Up(KeyCode::LeftControl)],
+        );
+        assert!(tracker.released().is_empty());
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::T, NONE)]);
+        assert_eq!(tracker.pressed(), [(KeyCode::T, NONE)]);
+    }
+
+    #[test]
+    fn modifiers_reported_with_a_key_mend_lost_events() {
+        let mut tracker = ModifierTracker::new(true);
+        // Ctrl's up event was lost; the next key says it's up
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::LeftControl, NONE)]);
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::V, NONE)]);
+        assert_eq!(tracker.pressed(), [(KeyCode::V, NONE)]);
+        assert_eq!(tracker.modifiers(), NONE);
+        // Shift's down event was lost; the next key says it's down
+        frame(&mut tracker, &[KeyEvent::Down(KeyCode::Semicolon, SHIFT)]);
+        assert_eq!(tracker.pressed(), [(KeyCode::Semicolon, SHIFT)]);
+        assert_eq!(tracker.modifiers(), SHIFT);
+    }
+
+    #[test]
+    fn unreliable_events_give_way_to_the_polled_modifiers() {
+        let mut tracker = ModifierTracker::new(false);
+        tracker.begin_frame();
+        // The browser kept Ctrl's own events
+        tracker.feed(KeyEvent::Down(KeyCode::V, NONE));
+        tracker.feed(KeyEvent::Up(KeyCode::V));
+        assert_eq!(tracker.settle(CTRL), CTRL);
+        assert_eq!(tracker.pressed(), [(KeyCode::V, CTRL)]);
+        assert_eq!(tracker.released(), [(KeyCode::V, CTRL)]);
+    }
+
+    #[test]
+    fn only_modifier_keys_are_modifiers() {
+        assert!(is_modifier(KeyCode::RightSuper) && is_modifier(KeyCode::LeftShift));
+        assert!(!is_modifier(KeyCode::V) && !is_modifier(KeyCode::CapsLock));
+    }
+}
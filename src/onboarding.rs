@@ -0,0 +1,413 @@
+//! The first-run walkthrough: a demo, the handy keys, and a quick pick of word
+//! list and text size.
+//!
+//! Shown in place of the entry screen the first time the app runs (before any
+//! config is saved) or with `--onboarding`. The demo is a real `EntryScreen` fed
+//! the bundled `demos/onboarding.txt` script through a `Replayer`, looping until
+//! the user moves on.
+
+use crate::app::AppContext;
+use crate::entry_screen::EntryScreen;
+use crate::events::{AppEvent, Nav};
+use crate::keymap::Action;
+use crate::pointer::PointerEvent;
+use crate::record::{Recording, Replayer};
+use crate::render::{wrap_words, Renderer};
+use crate::screen::{Screen, ScreenTrait, Transition};
+use crate::settings::{setting_items, SettingItem};
+use crate::strings::{fill, StringKey};
+use crate::toast::{Severity, TOAST_TIME};
+use macroquad::prelude::*;
+
+/// Events the demo replays: a scramble typed in lowercase, then cleared.
+const DEMO_SCRIPT: &str = include_str!("../demos/onboarding.txt");
+/// Seconds between the end of the demo and its next run.
+const DEMO_PAUSE: f64 = 1.0;
+/// Actions listed on the keys step, in order.
+const KEY_ACTIONS: [Action; 6] = [
+    Action::DeleteBack,
+    Action::ClearEntry,
+    Action::ToggleSubAnagrams,
+    Action::OpenSettings,
+    Action::CommandPalette,
+    Action::Quit,
+];
+/// Settings offered on the last step.
+const PICKED: [StringKey; 2] = [StringKey::SettingDictionary, StringKey::SettingTextSize];
+
+/// The steps, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingStep {
+    Demo,
+    Keys,
+    Pick,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 3] = [
+        OnboardingStep::Demo,
+        OnboardingStep::Keys,
+        OnboardingStep::Pick,
+    ];
+
+    /// Returns the step after this one, or `None` after the last.
+    pub fn next(self) -> Option<Self> {
+        match self {
+            OnboardingStep::Demo => Some(OnboardingStep::Keys),
+            OnboardingStep::Keys => Some(OnboardingStep::Pick),
+            OnboardingStep::Pick => None,
+        }
+    }
+    /// Returns the step before this one, or `None` before the first.
+    pub fn back(self) -> Option<Self> {
+        match self {
+            OnboardingStep::Demo => None,
+            OnboardingStep::Keys => Some(OnboardingStep::Demo),
+            OnboardingStep::Pick => Some(OnboardingStep::Keys),
+        }
+    }
+    fn title(self) -> StringKey {
+        match self {
+            OnboardingStep::Demo => StringKey::OnboardingDemoTitle,
+            OnboardingStep::Keys => StringKey::OnboardingKeysTitle,
+            OnboardingStep::Pick => StringKey::OnboardingPickTitle,
+        }
+    }
+}
+
+/// Returns the demo script's events.
+fn demo_replayer() -> Replayer {
+    let recording = Recording::parse(DEMO_SCRIPT).unwrap_or_else(|e| {
+        log::error!("[demo_replayer] bad demo script: {e}");
+        Recording::default()
+    });
+    Replayer::new(recording)
+}
+
+/// State of the walkthrough.
+pub struct OnboardingScreen {
+    step: OnboardingStep,
+    /// The entry screen shown once the walkthrough ends.
+    next: Option<Box<EntryScreen>>,
+    /// The entry screen the demo plays on.
+    demo: EntryScreen,
+    replayer: Replayer,
+    /// Seconds since the demo started its current run.
+    demo_time: f64,
+    items: Vec<SettingItem>,
+    selected: usize,
+    /// Why the last change was refused, shown until the next change.
+    error: Option<String>,
+}
+
+impl OnboardingScreen {
+    /// Creates the walkthrough over the loaded dictionary.
+    ///
+    /// The entry screen it ends on is made first, so it gets the entry saved
+    /// last session and the demo starts empty.
+    pub fn new(ctx: &mut AppContext) -> Self {
+        let next = Some(Box::new(EntryScreen::new(ctx)));
+        let mut demo = EntryScreen::new(ctx).into_demo();
+        demo.handle_event(ctx, AppEvent::Action(Action::ClearEntry));
+        Self {
+            step: OnboardingStep::Demo,
+            next,
+            demo,
+            replayer: demo_replayer(),
+            demo_time: 0.0,
+            items: setting_items()
+                .into_iter()
+                .filter(|item| PICKED.contains(&item.label))
+                .collect(),
+            selected: 0,
+            error: None,
+        }
+    }
+
+    /// Saves the config, so onboarding isn't shown again, and moves on to the
+    /// entry screen with whatever was picked.
+    fn finish(&mut self, ctx: &mut AppContext) -> Option<Transition> {
+        ctx.state.config_changed();
+        if !ctx.state.save() {
+            let lang = ctx.state.config.language;
+            let text = lang.text(StringKey::SettingsNotSaved);
+            ctx.toasts.push(text, Severity::Error, TOAST_TIME, ctx.now);
+        }
+        let mut next = self.next.take()?;
+        next.resume(ctx);
+        Some(Transition::To(Screen::Entry(next)))
+    }
+
+    /// Moves to the next step, or finishes after the last.
+    fn advance(&mut self, ctx: &mut AppContext) -> Option<Transition> {
+        ctx.dirty = true;
+        match self.step.next() {
+            Some(step) => {
+                self.step = step;
+                None
+            }
+            None => self.finish(ctx),
+        }
+    }
+
+    /// Returns the text size and the rectangle of pick row `i`.
+    fn row(&self, ctx: &AppContext, i: usize) -> (u16, Rect) {
+        let size = ctx.style.result_size * 2 / 3;
+        let row_h = size as f32 * 1.6;
+        let top = size as f32 * 5.0;
+        let rect = Rect::new(
+            ctx.size.x * 0.08,
+            top + i as f32 * row_h,
+            ctx.size.x * 0.84,
+            row_h,
+        );
+        (size, rect)
+    }
+
+    /// Draws the key bindings of `KEY_ACTIONS`, one per line from `top`.
+    fn draw_keys(&self, ctx: &AppContext, gfx: &dyn Renderer, top: f32, size: u16) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let line_h = size as f32 * 1.5;
+        let x = ctx.size.x * 0.08;
+        let lang = ctx.state.config.language;
+        let rows: Vec<(String, &str)> = KEY_ACTIONS
+            .into_iter()
+            .filter_map(|action| {
+                let chord = ctx.keymap.chords(action).next()?;
+                Some((chord.to_string(), lang.text(action.description())))
+            })
+            .collect();
+        let key_w = rows
+            .iter()
+            .map(|(chord, _)| gfx.measure(chord, font, size).width)
+            .fold(0.0, f32::max);
+        for (i, (chord, description)) in rows.iter().enumerate() {
+            let y = top + line_h * i as f32;
+            gfx.text(chord, vec2(x, y), font, size, theme.entry_text);
+            let desc_x = x + key_w + line_h;
+            gfx.text(description, vec2(desc_x, y), font, size, theme.result);
+        }
+
+        let help = ctx
+            .keymap
+            .chords(Action::ShowHelp)
+            .next()
+            .map_or_else(|| "?".to_owned(), |c| c.to_string());
+        let note = fill(lang.text(StringKey::OnboardingKeysText), help);
+        let y = top + line_h * (rows.len() as f32 + 0.5);
+        for (i, line) in wrap(gfx, &note, ctx.size.x * 0.84, font, size)
+            .iter()
+            .enumerate()
+        {
+            let pos = vec2(x, y + line_h * i as f32);
+            gfx.text(line, pos, font, size, theme.result);
+        }
+    }
+
+    /// Draws the picked settings, the selected one outlined, and any error.
+    fn draw_pick(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        let (size, _) = self.row(ctx, 0);
+        let baseline = |rect: Rect| rect.y + rect.h / 2.0 + size as f32 / 3.0;
+        let pad = size as f32 * 0.4;
+        for (i, item) in self.items.iter().enumerate() {
+            let (_, rect) = self.row(ctx, i);
+            if i == self.selected {
+                gfx.rect_lines(rect, theme.outline_thickness * 2.0, theme.highlight);
+            }
+            let label = lang.text(item.label);
+            let pos = vec2(rect.x + pad, baseline(rect));
+            gfx.text(label, pos, font, size, theme.result);
+            let value = format!("< {} >", item.display(ctx));
+            let width = gfx.measure(&value, font, size).width;
+            let pos = vec2(rect.right() - pad - width, baseline(rect));
+            gfx.text(&value, pos, font, size, theme.entry_text);
+        }
+        if let Some(error) = &self.error {
+            let (_, below) = self.row(ctx, self.items.len());
+            let pos = vec2(below.x + pad, baseline(below));
+            gfx.text(error, pos, font, size * 3 / 4, theme.error);
+        }
+    }
+}
+
+impl ScreenTrait for OnboardingScreen {
+    /// Enter (or a tap) moves on, Left goes back, and Escape skips to the end.
+    /// On the last step, Up and Down choose a setting and Left and Right change it.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        let picking = self.step == OnboardingStep::Pick;
+        let count = self.items.len().max(1);
+        match ev {
+            AppEvent::WindowSize(_) | AppEvent::DpiScale(_) => {
+                self.demo.handle_event(ctx, ev.clone());
+                if let Some(next) = self.next.as_mut() {
+                    next.handle_event(ctx, ev);
+                }
+            }
+            AppEvent::CloseRequested => return Some(Transition::Quit),
+            AppEvent::KeyActivity => ctx.dirty = true,
+            AppEvent::Action(Action::Quit) => return self.finish(ctx),
+            AppEvent::Nav(Nav::Select) => return self.advance(ctx),
+            AppEvent::Nav(Nav::Right) if !picking => return self.advance(ctx),
+            AppEvent::Pointer(PointerEvent::Released(_)) if !picking => return self.advance(ctx),
+            AppEvent::Nav(Nav::Left) if !picking => {
+                if let Some(step) = self.step.back() {
+                    self.step = step;
+                    ctx.dirty = true;
+                }
+            }
+            AppEvent::Nav(Nav::Up) if picking => {
+                self.selected = (self.selected + count - 1) % count;
+                ctx.dirty = true;
+            }
+            AppEvent::Nav(Nav::Down) if picking => {
+                self.selected = (self.selected + 1) % count;
+                ctx.dirty = true;
+            }
+            AppEvent::Nav(nav @ (Nav::Left | Nav::Right)) if picking => {
+                if let Some(item) = self.items.get(self.selected) {
+                    self.error = item.change(ctx, nav == Nav::Right);
+                    ctx.dirty = true;
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+    /// Plays the demo, starting it over after a pause once it ends.
+    fn update(&mut self, ctx: &mut AppContext, dt: f64) -> Option<Transition> {
+        if self.step == OnboardingStep::Demo {
+            self.demo_time += dt;
+            for ev in self.replayer.replace_input(Vec::new(), self.demo_time) {
+                self.demo.handle_event(ctx, ev);
+            }
+            if self.replayer.is_finished() && !self.demo.is_waiting() {
+                self.replayer = demo_replayer();
+                self.demo_time = -DEMO_PAUSE;
+            }
+            self.demo.update(ctx, dt);
+        }
+        None
+    }
+    /// Draws the step: the demo with a caption under it, the keys, or the pick,
+    /// with the step number and the key hint.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        let size = ctx.style.result_size * 2 / 3;
+        let (w, h) = (ctx.size.x, ctx.size.y);
+        let x = w * 0.08;
+
+        let title_y = match self.step {
+            OnboardingStep::Demo => {
+                self.demo.draw(ctx, gfx);
+                let panel = Rect::new(0.0, h * 0.62, w, h * 0.38);
+                let mut backdrop = theme.background;
+                backdrop.a = 0.92;
+                gfx.rect(panel, backdrop);
+                gfx.line(
+                    vec2(0.0, panel.y),
+                    vec2(w, panel.y),
+                    theme.outline_thickness,
+                    theme.box_outline,
+                );
+                let text = lang.text(StringKey::OnboardingDemoText);
+                let top = panel.y + size as f32 * 2.8;
+                for (i, line) in wrap(gfx, text, w * 0.84, font, size).iter().enumerate() {
+                    let pos = vec2(x, top + size as f32 * 1.3 * i as f32);
+                    gfx.text(line, pos, font, size, theme.result);
+                }
+                panel.y + size as f32 * 1.5
+            }
+            OnboardingStep::Keys => {
+                gfx.clear(theme.background);
+                self.draw_keys(ctx, gfx, size as f32 * 5.0, size);
+                size as f32 * 2.0
+            }
+            OnboardingStep::Pick => {
+                gfx.clear(theme.background);
+                self.draw_pick(ctx, gfx);
+                size as f32 * 2.0
+            }
+        };
+        let title = lang.text(self.step.title());
+        gfx.text(title, vec2(x, title_y), font, size, theme.entry_text);
+
+        let number = OnboardingStep::ALL
+            .iter()
+            .position(|&s| s == self.step)
+            .unwrap_or(0);
+        let counter = format!("{}/{}", number + 1, OnboardingStep::ALL.len());
+        let counter_w = gfx.measure(&counter, font, size).width;
+        let counter_pos = vec2(w - x - counter_w, title_y);
+        gfx.text(&counter, counter_pos, font, size, theme.box_outline);
+
+        let hint = match self.step {
+            OnboardingStep::Pick => StringKey::OnboardingPickHint,
+            _ => StringKey::OnboardingHint,
+        };
+        let hint_size = size * 3 / 4;
+        let hint_pos = vec2(x, h - hint_size as f32 * 1.5);
+        gfx.text(lang.text(hint), hint_pos, font, hint_size, theme.result);
+    }
+    fn is_animating(&self) -> bool {
+        self.step == OnboardingStep::Demo
+    }
+}
+
+/// Splits `text` at spaces into lines at most `width` wide; a word wider than
+/// that gets a line to itself.
+fn wrap(gfx: &dyn Renderer, text: &str, width: f32, font: Option<&Font>, size: u16) -> Vec<String> {
+    wrap_words(text.split_whitespace(), width, |line| {
+        gfx.measure(line, font, size).width
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::RecordingRenderer;
+
+    #[test]
+    fn steps_go_forward_and_back_in_order() {
+        let mut step = OnboardingStep::Demo;
+        let mut seen = vec![step];
+        while let Some(next) = step.next() {
+            assert_eq!(next.back(), Some(step));
+            step = next;
+            seen.push(step);
+        }
+        assert_eq!(seen, OnboardingStep::ALL);
+        assert_eq!(OnboardingStep::Demo.back(), None);
+    }
+
+    #[test]
+    fn the_demo_script_types_a_scramble_then_clears_it() {
+        let recording = Recording::parse(DEMO_SCRIPT).unwrap();
+        let events: Vec<AppEvent> = recording.events.iter().map(|r| r.event.clone()).collect();
+        let letters: String = events
+            .iter()
+            .filter_map(|ev| match ev {
+                AppEvent::Letter(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(letters, "kcauq");
+        assert_eq!(events.last(), Some(&AppEvent::Action(Action::ClearEntry)));
+    }
+
+    #[test]
+    fn text_wraps_at_spaces_to_the_width() {
+        // Six pixels a character at size 10
+        let gfx = RecordingRenderer::new(vec2(800.0, 600.0));
+        let lines = wrap(&gfx, "one two  three", 45.0, None, 10);
+        assert_eq!(lines, ["one two", "three"]);
+        let lines = wrap(&gfx, "a extraordinary b", 45.0, None, 10);
+        assert_eq!(lines, ["a", "extraordinary", "b"]);
+        assert!(wrap(&gfx, "  ", 45.0, None, 10).is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Showing which letters of the entry spell the selected match.
+//!
+//! With a match selected in the list, each of its letters is paired with a
+//! letter of the entry as the tiles show it, left to right: the first `E` of the
+//! word with the leftmost `E` among the tiles, the second with the next, and so
+//! on. The two letters of a pair are drawn in the same color, and the tile has
+//! the letter's place in the word under it, so the pairs can be told apart
+//! without the colors (see `results`). A letter the entry doesn't have, as when
+//! a looser search found the word, stays as it was.
+
+use crate::metrics::TextMetrics;
+use crate::render::Renderer;
+use crate::tiles::letter_edges;
+use macroquad::prelude::*;
+
+/// Colors the pairs take in turn (Okabe and Ito's, less the yellow, which is
+/// hard to see on white).
+pub const PAIR_COLORS: [Color; 6] = [
+    Color::new(0.902, 0.624, 0.0, 1.0),
+    Color::new(0.337, 0.706, 0.914, 1.0),
+    Color::new(0.0, 0.620, 0.451, 1.0),
+    Color::new(0.0, 0.447, 0.698, 1.0),
+    Color::new(0.835, 0.369, 0.0, 1.0),
+    Color::new(0.800, 0.475, 0.655, 1.0),
+];
+
+/// Returns, for each character of `word`, the slot of the letter among `tiles`
+/// it's paired with, if any, folding `word`'s characters with `fold` to the
+/// letters typed. Each slot is paired at most once.
+pub fn pair_letters(
+    word: &str,
+    tiles: &str,
+    fold: impl Fn(char) -> Option<char>,
+) -> Vec<Option<usize>> {
+    let mut free: Vec<Option<char>> = tiles.chars().map(Some).collect();
+    word.chars()
+        .map(|c| {
+            let letter = fold(c)?;
+            let slot = free.iter().position(|&tile| tile == Some(letter))?;
+            free[slot] = None;
+            Some(slot)
+        })
+        .collect()
+}
+
+/// Returns, for each of `count` tiles, the index of the character of the word
+/// it's paired with in `pairs` (see `pair_letters`), if any.
+pub fn tile_pairs(pairs: &[Option<usize>], count: usize) -> Vec<Option<usize>> {
+    let mut tiles = vec![None; count];
+    for (i, slot) in pairs.iter().enumerate() {
+        if let Some(tile) = slot.and_then(|slot| tiles.get_mut(slot)) {
+            *tile = Some(i);
+        }
+    }
+    tiles
+}
+
+/// Returns the color of the pair with the word's `i`th character.
+pub fn pair_color(i: usize) -> Color {
+    PAIR_COLORS[i % PAIR_COLORS.len()]
+}
+
+/// Draws `text` a character at a time with its baseline at `pos`: those paired
+/// with the word's `i`th character, by `pairs`, in that pair's color, the rest
+/// in `color`. With `numbered`, each paired character has `i + 1` in small
+/// type under it.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_paired(
+    gfx: &dyn Renderer,
+    text: &str,
+    pairs: &[Option<usize>],
+    numbered: bool,
+    pos: Vec2,
+    font: Option<&Font>,
+    metrics: &TextMetrics,
+    color: Color,
+) {
+    let size = metrics.size;
+    let measure = |text: &str| metrics.width(text, |text| gfx.measure(text, font, size));
+    let edges = letter_edges(text, pos.x, measure);
+    let small = (size / 4).max(1);
+    for (slot, c) in text.chars().enumerate() {
+        let x = edges[slot];
+        let pair = pairs.get(slot).copied().flatten();
+        let letter_color = pair.map_or(color, pair_color);
+        let letter = c.to_string();
+        gfx.text(&letter, vec2(x, pos.y), font, size, letter_color);
+        if let Some(i) = pair.filter(|_| numbered) {
+            let number = (i + 1).to_string();
+            let width = gfx.measure(&number, font, small).width;
+            let center = (x + edges[slot + 1]) / 2.0;
+            let under = vec2(center - width / 2.0, pos.y + small as f32);
+            gfx.text(&number, under, font, small, letter_color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upper(c: char) -> Option<char> {
+        Some(c.to_ascii_uppercase())
+    }
+
+    #[test]
+    fn repeated_letters_take_the_tiles_left_to_right() {
+        assert_eq!(
+            pair_letters("LETTER", "TEELRT", upper),
+            [Some(3), Some(1), Some(0), Some(5), Some(2), Some(4)]
+        );
+        assert_eq!(pair_letters("EEE", "EXE", upper), [Some(0), Some(2), None]);
+    }
+
+    #[test]
+    fn letters_the_entry_lacks_stay_unpaired() {
+        assert_eq!(
+            pair_letters("pound", "DNOU", upper),
+            [None, Some(2), Some(3), Some(1), Some(0)]
+        );
+        assert_eq!(pair_letters("abc", "ABC", |_| None), [None, None, None]);
+    }
+
+    #[test]
+    fn each_tile_points_back_at_its_letter() {
+        let pairs = pair_letters("LETTER", "TEELRTX", upper);
+        assert_eq!(
+            tile_pairs(&pairs, 7),
+            [Some(2), Some(1), Some(4), Some(0), Some(5), Some(3), None]
+        );
+        assert_eq!(tile_pairs(&[Some(9), None], 2), [None, None]);
+    }
+}
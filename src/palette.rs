@@ -0,0 +1,310 @@
+//! The command palette: find any action by typing part of its description.
+//!
+//! Ctrl+P opens it over the entry. Letters narrow the list with a fuzzy
+//! (subsequence) match, Up and Down choose, and Enter runs the action exactly as
+//! its key binding would.
+
+use crate::keymap::{Action, Keymap};
+use crate::render::Renderer;
+use crate::strings::Language;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Rows of matches shown at once.
+pub const PALETTE_ROWS: usize = 8;
+
+/// Keys the palette answers besides letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteKey {
+    Up,
+    Down,
+    /// Runs the chosen action.
+    Run,
+    /// Deletes the last letter of the query.
+    Erase,
+    Close,
+}
+
+impl PaletteKey {
+    /// Returns the palette keys pressed this frame.
+    pub fn pressed() -> Vec<PaletteKey> {
+        get_keys_pressed()
+            .into_iter()
+            .filter_map(|key| match key {
+                KeyCode::Up => Some(PaletteKey::Up),
+                KeyCode::Down => Some(PaletteKey::Down),
+                KeyCode::Enter | KeyCode::KpEnter => Some(PaletteKey::Run),
+                KeyCode::Backspace => Some(PaletteKey::Erase),
+                KeyCode::Escape => Some(PaletteKey::Close),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Scores how well `query` matches `text` as a case-insensitive subsequence,
+/// returning the score and the char indices of `text` that matched, or `None` if
+/// it doesn't match.
+///
+/// Each matched letter scores a point, with bonuses for runs of consecutive
+/// letters and for letters starting a word, and a small penalty for each letter
+/// skipped between matches.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut query = query.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut score = 0;
+    let mut matched = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in text.chars().enumerate() {
+        let Some(&q) = query.peek() else { break };
+        if c.to_lowercase().eq(q.to_lowercase()) {
+            score += 1;
+            match last_match {
+                Some(last) if last + 1 == i => score += 5,
+                Some(last) => score -= (i - last - 1).min(3) as i32,
+                None => (),
+            }
+            if prev.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            matched.push(i);
+            last_match = Some(i);
+            query.next();
+        }
+        prev = Some(c);
+    }
+    query.peek().is_none().then_some((score, matched))
+}
+
+/// An action offered by the palette, with the letters of its description that
+/// matched the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteMatch {
+    pub action: Action,
+    pub matched: Vec<usize>,
+}
+
+/// Returns the actions whose descriptions in `lang` match `query`, best first;
+/// ties keep the order of `Action::ALL`.
+pub fn filter_actions(query: &str, lang: Language) -> Vec<PaletteMatch> {
+    let mut scored: Vec<(i32, PaletteMatch)> = Action::ALL
+        .into_iter()
+        .filter(|&action| action != Action::CommandPalette)
+        .filter_map(|action| {
+            let (score, matched) = fuzzy_score(query, lang.text(action.description()))?;
+            Some((score, PaletteMatch { action, matched }))
+        })
+        .collect();
+    // Stable, so equal scores stay in declaration order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, m)| m).collect()
+}
+
+/// The query typed so far and the chosen match.
+#[derive(Debug, Default)]
+pub struct Palette {
+    pub query: String,
+    pub selected: usize,
+    /// The language descriptions are matched and shown in.
+    pub lang: Language,
+}
+
+impl Palette {
+    /// Returns an empty palette matching descriptions in `lang`.
+    pub fn new(lang: Language) -> Self {
+        Self {
+            lang,
+            ..Self::default()
+        }
+    }
+    /// Returns the actions matching the query, best first.
+    pub fn matches(&self) -> Vec<PaletteMatch> {
+        filter_actions(&self.query, self.lang)
+    }
+    /// Adds `c` to the query, choosing the best match again.
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+    /// Applies `key`, returning the action to run for Run.
+    pub fn press(&mut self, key: PaletteKey) -> Option<Action> {
+        let count = self.matches().len();
+        match key {
+            PaletteKey::Up => self.selected = self.selected.saturating_sub(1),
+            PaletteKey::Down => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            PaletteKey::Erase => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            PaletteKey::Run => return self.matches().get(self.selected).map(|m| m.action),
+            PaletteKey::Close => (),
+        }
+        None
+    }
+}
+
+/// Draws the palette over a dimmed screen: the query, then a window of matches
+/// with the chosen one outlined and each one's matched letters highlighted and
+/// underlined.
+pub fn draw_palette(
+    gfx: &dyn Renderer,
+    palette: &Palette,
+    keymap: &Keymap,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+) {
+    let screen = gfx.screen_size();
+    let line_h = font_size as f32 * 1.6;
+    let pad = font_size as f32 * 0.6;
+    let w = (screen.x * 0.8).min(font_size as f32 * 30.0);
+    let panel = Rect::new(
+        (screen.x - w) / 2.0,
+        screen.y * 0.1,
+        w,
+        line_h * (PALETTE_ROWS + 1) as f32 + pad * 2.0,
+    );
+
+    let mut dim = theme.background;
+    dim.a = 0.7;
+    gfx.rect(Rect::new(0.0, 0.0, screen.x, screen.y), dim);
+    gfx.rect(panel, theme.background);
+    gfx.rect_lines(panel, theme.outline_thickness, theme.box_outline);
+
+    let x = panel.x + pad;
+    let baseline = |row: usize| panel.y + pad + line_h * row as f32 + line_h * 0.7;
+    let query = format!("> {}_", palette.query);
+    gfx.text(
+        &query,
+        vec2(x, baseline(0)),
+        font,
+        font_size,
+        theme.entry_text,
+    );
+
+    let matches = palette.matches();
+    let first = (palette.selected + 1).saturating_sub(PALETTE_ROWS);
+    for (row, (i, m)) in matches
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(PALETTE_ROWS)
+        .enumerate()
+    {
+        let y = baseline(row + 1);
+        if i == palette.selected {
+            let outline = Rect::new(panel.x + pad / 2.0, y - line_h * 0.7, w - pad, line_h);
+            gfx.rect_lines(outline, theme.outline_thickness * 2.0, theme.highlight);
+        }
+
+        let description = palette.lang.text(m.action.description());
+        gfx.text(description, vec2(x, y), font, font_size, theme.result);
+        for &ci in &m.matched {
+            let Some((start, c)) = description.char_indices().nth(ci) else {
+                continue;
+            };
+            let cx = x + gfx.measure(&description[..start], font, font_size).width;
+            let letter = &description[start..start + c.len_utf8()];
+            let cw = gfx.measure(letter, font, font_size).width;
+            gfx.text(letter, vec2(cx, y), font, font_size, theme.highlight);
+            let under = y + font_size as f32 * 0.15;
+            gfx.line(vec2(cx, under), vec2(cx + cw, under), 2.0, theme.highlight);
+        }
+
+        if let Some(chord) = keymap.chords(m.action).next() {
+            let chord = chord.to_string();
+            let cw = gfx.measure(&chord, font, font_size).width;
+            gfx.text(
+                &chord,
+                vec2(panel.right() - pad - cw, y),
+                font,
+                font_size,
+                theme.box_outline,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(matches: &[PaletteMatch]) -> Vec<Action> {
+        matches.iter().map(|m| m.action).collect()
+    }
+
+    #[test]
+    fn runs_and_word_starts_score_higher_than_scattered_letters() {
+        // 1 + 3 for the word start, then 1 + 5 for each letter of the run
+        assert_eq!(fuzzy_score("cle", "Clear entry"), Some((16, vec![0, 1, 2])));
+        // The skipped letter costs a point
+        assert_eq!(fuzzy_score("ce", "Clear entry"), Some((4, vec![0, 2])));
+        assert_eq!(fuzzy_score("C L", "clear"), Some((10, vec![0, 1])));
+        assert_eq!(fuzzy_score("", "Quit"), Some((0, vec![])));
+    }
+
+    #[test]
+    fn letters_out_of_order_or_missing_dont_match() {
+        assert_eq!(fuzzy_score("lc", "Clear"), None);
+        assert_eq!(fuzzy_score("clears", "Clear"), None);
+        assert_eq!(fuzzy_score("x", ""), None);
+    }
+
+    #[test]
+    fn matches_come_best_first_then_in_declaration_order() {
+        let matches = filter_actions("text", Language::En);
+        // Other descriptions may match too, further down
+        assert_eq!(
+            actions(&matches)[..3],
+            [Action::TextLarger, Action::TextSmaller, Action::TextReset]
+        );
+        assert_eq!(matches[0].matched, [7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn every_action_but_the_palette_is_offered() {
+        let all = actions(&filter_actions("", Language::En));
+        assert_eq!(all.len(), Action::ALL.len() - 1);
+        assert!(!all.contains(&Action::CommandPalette));
+    }
+
+    #[test]
+    fn descriptions_are_matched_in_the_palettes_language() {
+        // "plus" is spelled out in both, ahead of scattered letters elsewhere
+        let matches = filter_actions("plus", Language::Fr);
+        assert_eq!(
+            actions(&matches)[..2],
+            [Action::TextLarger, Action::TextSmaller]
+        );
+        let english = filter_actions("plus", Language::En);
+        assert!(!actions(&english).contains(&Action::TextLarger));
+    }
+
+    #[test]
+    fn keys_choose_within_the_matches_and_run_the_choice() {
+        let mut palette = Palette::new(Language::En);
+        for c in "text".chars() {
+            palette.push(c);
+        }
+        assert_eq!(palette.press(PaletteKey::Up), None);
+        assert_eq!(palette.selected, 0);
+        let matches = palette.matches();
+        palette.press(PaletteKey::Down);
+        assert_eq!(palette.press(PaletteKey::Run), Some(matches[1].action));
+        for _ in 0..matches.len() {
+            palette.press(PaletteKey::Down);
+        }
+        assert_eq!(
+            palette.selected,
+            matches.len() - 1,
+            "stops at the last match"
+        );
+
+        palette.press(PaletteKey::Erase);
+        assert_eq!((palette.query.as_str(), palette.selected), ("tex", 0));
+        palette.query = "zzz".to_owned();
+        assert_eq!(palette.press(PaletteKey::Down), None);
+        assert_eq!(palette.press(PaletteKey::Run), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Pulling a scramble out of puzzle text copied from elsewhere.
+//!
+//! Web pages set scrambles out like `3. G A R B E E ( _ _ O _ _ _ )`: numbered,
+//! with spaces between the letters, and the answer's blanks in parentheses with
+//! any letters already known. `extract_scramble` finds the letters, and the
+//! blanks as a `Pattern` the matches must fit.
+
+use crate::wordmap::fold_letter;
+use std::fmt;
+
+/// Characters that stand for an unknown letter in a blank pattern.
+const BLANKS: [char; 3] = ['_', '?', '.'];
+
+/// The shape of an answer: its length, and any letters already known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// One per letter: the letter, uppercase, if known.
+    pub slots: Vec<Option<char>>,
+}
+
+impl Pattern {
+    /// Parses blanks and known letters, e.g. `_ _ O _ _ _` or `__O___`, ignoring
+    /// whitespace. Returns `None` for anything else, or if there are no blanks.
+    pub fn parse(text: &str) -> Option<Self> {
+        let slots = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                c if BLANKS.contains(&c) => Some(None),
+                c => fold_letter(c).map(Some),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        slots.contains(&None).then_some(Self { slots })
+    }
+    /// Returns the number of letters the pattern stands for.
+    // A pattern always has a blank, so it's never empty
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    /// Returns whether `word` has the pattern's length and known letters.
+    pub fn matches(&self, word: &str) -> bool {
+        let mut letters = word.chars().map(|c| fold_letter(c).unwrap_or(c));
+        self.slots.iter().all(|slot| {
+            letters
+                .next()
+                .is_some_and(|c| slot.is_none_or(|known| known == c))
+        }) && letters.next().is_none()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for slot in &self.slots {
+            write!(f, "{}", slot.unwrap_or('_'))?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the scramble in copied puzzle text, with the blank pattern after it if
+/// there is one.
+///
+/// Numbering and punctuation are skipped, and letters set out one by one
+/// (`G A R B E E`) are run together. Where the text holds several words, the
+/// one in capitals is taken, or the one as long as the pattern, or the last in
+/// capitals that is. Returns `None` if no word or no single best word is found.
+/// A pattern of a different length than the scramble is dropped.
+pub fn extract_scramble(text: &str) -> Option<(String, Option<Pattern>)> {
+    // Parenthesized parts are never the scramble, but one may be its pattern
+    let mut rest = String::with_capacity(text.len());
+    let mut pattern = None;
+    let mut remaining = text;
+    while let Some(open) = remaining.find(['(', '[']) {
+        rest.push_str(&remaining[..open]);
+        rest.push(' ');
+        let close = remaining[open..].find([')', ']']).map(|i| open + i);
+        let inner = &remaining[open + 1..close.unwrap_or(remaining.len())];
+        if pattern.is_none() {
+            pattern = Pattern::parse(inner);
+        }
+        remaining = close.map_or("", |close| &remaining[close + 1..]);
+    }
+    rest.push_str(remaining);
+
+    let candidates = candidate_words(&rest);
+    let pick = |filter: &dyn Fn(&&String) -> bool| {
+        let mut found = candidates.iter().filter(filter);
+        match (found.next(), found.next()) {
+            (Some(word), None) => Some(word.clone()),
+            _ => None,
+        }
+    };
+    let capitals = |word: &&String| !word.chars().any(char::is_lowercase);
+    let fits = |word: &&String| {
+        pattern
+            .as_ref()
+            .is_some_and(|p| p.len() == word.chars().count())
+    };
+    // A pattern follows its scramble, so the last word that fits it is the best guess
+    let word = pick(&|_| true)
+        .or_else(|| pick(&capitals))
+        .or_else(|| pick(&fits))
+        .or_else(|| {
+            candidates
+                .iter()
+                .rfind(|word| capitals(word) && fits(word))
+                .cloned()
+        })?;
+    let pattern = pattern.filter(|p| p.len() == word.chars().count());
+    Some((word, pattern))
+}
+
+/// Returns the words in `text`, splitting at anything that isn't a letter and
+/// running single letters set out one by one (`G A R`, `G-A-R`) into words.
+fn candidate_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut run = String::new();
+    let end_run = |run: &mut String, words: &mut Vec<String>| {
+        if run.chars().count() > 1 {
+            words.push(run.clone());
+        }
+        run.clear();
+    };
+    for chunk in text.split_whitespace() {
+        let tokens: Vec<&str> = chunk
+            .split(|c: char| fold_letter(c).is_none())
+            .filter(|token| !token.is_empty())
+            .collect();
+        let single = |token: &&str| token.chars().count() == 1;
+        match tokens[..] {
+            [letter] if single(&letter) => run.push_str(letter),
+            _ => {
+                end_run(&mut run, &mut words);
+                if tokens.len() > 1 && tokens.iter().all(single) {
+                    words.push(tokens.concat());
+                } else {
+                    words.extend(tokens.iter().filter(|t| !single(t)).map(|t| t.to_string()));
+                }
+            }
+        }
+    }
+    end_run(&mut run, &mut words);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_take_blanks_and_known_letters() {
+        let p = Pattern::parse("_ _ o _ _ _").unwrap();
+        assert_eq!(p.len(), 6);
+        assert_eq!(p.to_string(), "__O___");
+        assert_eq!(Pattern::parse("?.A"), Pattern::parse("_ _ A"));
+        // No blanks, or something that isn't a letter, isn't a pattern
+        assert_eq!(Pattern::parse("ABC"), None);
+        assert_eq!(Pattern::parse("_ 3 _"), None);
+    }
+
+    #[test]
+    fn patterns_match_length_and_known_letters() {
+        let p = Pattern::parse("__O___").unwrap();
+        assert!(p.matches("BROGUE"));
+        assert!(p.matches("brogue"));
+        assert!(!p.matches("BRAGUE"));
+        assert!(!p.matches("BROGUES"));
+        assert!(!p.matches("BROG"));
+    }
+
+    #[test]
+    fn spaced_letters_run_together_with_their_pattern() {
+        let (word, p) = extract_scramble("3. G A R B E E ( _ _ O _ _ _ )").unwrap();
+        assert_eq!(word, "GARBEE");
+        assert_eq!(p.unwrap().to_string(), "__O___");
+        let (word, p) = extract_scramble("G-A-R-B-E-E").unwrap();
+        assert_eq!((word.as_str(), p), ("GARBEE", None));
+    }
+
+    #[test]
+    fn capitals_pick_the_scramble_out_of_prose() {
+        let (word, _) = extract_scramble("Unscramble LOFWER to find it").unwrap();
+        assert_eq!(word, "LOFWER");
+        assert_eq!(extract_scramble("two lower words"), None);
+        assert_eq!(extract_scramble("1. ( _ _ _ )"), None);
+    }
+
+    #[test]
+    fn a_pattern_of_another_length_is_dropped() {
+        let (word, p) = extract_scramble("TLAE [ _ _ ]").unwrap();
+        assert_eq!((word.as_str(), p), ("TLAE", None));
+    }
+}
@@ -0,0 +1,130 @@
+//! Spelling the answer out in the NATO phonetic alphabet.
+//!
+//! Alt+V shows the unique answer spelled out under it, one code word per letter
+//! ("P - papa, O - oscar, ..."), wrapped between letters to fit the window. This
+//! is for reading the answer aloud, or writing it down, without mixing up
+//! letters that sound alike. With `copy_phonetic` on in the config, copying the
+//! answer copies it spelled out too. Letters the alphabet has no word for (an
+//! accented letter, say) are shown as they are.
+
+use crate::render::{wrap_words, Renderer};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// The code word for each letter, `A` first.
+const NATO: [&str; 26] = [
+    "alfa", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango",
+    "uniform", "victor", "whiskey", "x-ray", "yankee", "zulu",
+];
+
+/// Returns the code word for `letter`, if it's one of `A` to `Z` in either case.
+pub fn code_word(letter: char) -> Option<&'static str> {
+    let upper = letter.to_ascii_uppercase();
+    upper
+        .is_ascii_uppercase()
+        .then(|| NATO[(upper as u8 - b'A') as usize])
+}
+
+/// Returns each letter of `word` with its code word, as in "P - papa", skipping
+/// spaces between a phrase's words.
+pub fn spell_out(word: &str) -> Vec<String> {
+    word.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match code_word(c) {
+            Some(code) => format!("{} - {code}", c.to_uppercase()),
+            None => c.to_uppercase().to_string(),
+        })
+        .collect()
+}
+
+/// Returns `word` spelled out on one line, the letters separated by commas.
+pub fn phonetic(word: &str) -> String {
+    spell_out(word).join(", ")
+}
+
+/// Returns `word` spelled out as lines at most `width` wide, as measured by
+/// `measure`, breaking only between letters: each line but the last ends in a
+/// comma, and a letter too wide for `width` on its own gets a line to itself.
+pub fn phonetic_lines(word: &str, width: f32, measure: impl Fn(&str) -> f32) -> Vec<String> {
+    let letters = spell_out(word);
+    let last = letters.len().saturating_sub(1);
+    let parts: Vec<String> = letters
+        .into_iter()
+        .enumerate()
+        .map(|(i, letter)| match i < last {
+            true => format!("{letter},"),
+            false => letter,
+        })
+        .collect();
+    wrap_words(parts.iter().map(String::as_str), width, measure)
+}
+
+/// Draws `word` spelled out, one wrapped line after another down from the top of
+/// `area`, centered, leaving out lines that don't fit.
+pub fn draw_phonetic(
+    gfx: &dyn Renderer,
+    word: &str,
+    area: Rect,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let measure = |text: &str| gfx.measure(text, font, size).width;
+    let line_h = size as f32 * 1.4;
+    for (i, line) in phonetic_lines(word, area.w, measure).iter().enumerate() {
+        let baseline = area.y + size as f32 + i as f32 * line_h;
+        if baseline > area.bottom() {
+            break;
+        }
+        let pos = vec2(area.center().x - measure(line) / 2.0, baseline);
+        gfx.text(line, pos, font, size, theme.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measures text as 10 px per character.
+    fn measure(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    #[test]
+    fn spells_each_letter_skipping_spaces() {
+        assert_eq!(phonetic("up"), "U - uniform, P - papa");
+        assert_eq!(spell_out("A B"), ["A - alfa", "B - bravo"]);
+        assert_eq!(spell_out("É"), ["É"]);
+        assert_eq!(code_word('x'), Some("x-ray"));
+        assert_eq!(code_word('1'), None);
+    }
+
+    #[test]
+    fn lines_break_only_between_letters() {
+        let word = "OXYGENATE";
+        let one_line = phonetic(word);
+        for width in [40.0, 100.0, 150.0, 300.0, 1000.0] {
+            let lines = phonetic_lines(word, width, measure);
+            let last = lines.len() - 1;
+            for (i, line) in lines.iter().enumerate() {
+                assert_eq!(line.ends_with(','), i < last, "{line:?} at {width}");
+                // Each line starts with a letter and its code word
+                assert!(line.chars().nth(1) == Some(' '), "{line:?} at {width}");
+                if measure(line) > width {
+                    assert!(!line.contains(", "), "{line:?} at {width}");
+                }
+            }
+            assert_eq!(lines.join(" "), one_line);
+        }
+    }
+
+    #[test]
+    fn hyphenated_code_words_stay_whole() {
+        // "X - x-ray," is 10 characters wide
+        let lines = phonetic_lines("AXE", 100.0, measure);
+        assert_eq!(lines, ["A - alfa,", "X - x-ray,", "E - echo"]);
+        assert_eq!(phonetic_lines("X", 20.0, measure), ["X - x-ray"]);
+        assert!(phonetic_lines("", 100.0, measure).is_empty());
+    }
+}
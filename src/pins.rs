@@ -0,0 +1,281 @@
+//! Words pinned from the results, kept while the entry changes.
+//!
+//! Comparing two possible scrambles means flipping between their matches; a
+//! pinned word stays in a strip above the results, with the entry it came from,
+//! until it's unpinned, the pins are cleared, or it's recorded as an answer.
+
+use crate::memory::MemorySize;
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Most words that can be pinned at once.
+pub const PIN_CAPACITY: usize = 8;
+
+/// A pinned word and the entry (scramble or query command) it matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pin {
+    pub word: String,
+    pub query: String,
+}
+
+/// What `Pins::toggle` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinEffect {
+    Pinned,
+    Unpinned,
+    /// `PIN_CAPACITY` words are pinned already; nothing changed.
+    Full,
+}
+
+/// The pinned words, oldest first, each at most once.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Pins {
+    pins: Vec<Pin>,
+}
+
+impl Pins {
+    /// Returns the pinned words, oldest first.
+    pub fn as_slice(&self) -> &[Pin] {
+        &self.pins
+    }
+    /// Returns whether no words are pinned.
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+    /// Returns whether `word` is pinned.
+    pub fn contains(&self, word: &str) -> bool {
+        self.pins.iter().any(|pin| pin.word == word)
+    }
+    /// Unpins `word` if it's pinned, or else pins it as found for `query`.
+    pub fn toggle(&mut self, word: &str, query: &str) -> PinEffect {
+        if self.unpin(word) {
+            return PinEffect::Unpinned;
+        }
+        if self.pins.len() == PIN_CAPACITY {
+            return PinEffect::Full;
+        }
+        self.pins.push(Pin {
+            word: word.to_owned(),
+            query: query.to_owned(),
+        });
+        PinEffect::Pinned
+    }
+    /// Unpins `word`, returning whether it was pinned.
+    pub fn unpin(&mut self, word: &str) -> bool {
+        let before = self.pins.len();
+        self.pins.retain(|pin| pin.word != word);
+        self.pins.len() != before
+    }
+    /// Unpins every word, returning whether any were pinned.
+    pub fn clear(&mut self) -> bool {
+        let any = !self.pins.is_empty();
+        self.pins.clear();
+        any
+    }
+}
+
+impl MemorySize for Pin {
+    fn memory_size(&self) -> usize {
+        self.word.memory_size() + self.query.memory_size()
+    }
+}
+
+impl MemorySize for Pins {
+    fn memory_size(&self) -> usize {
+        self.pins.memory_size()
+    }
+}
+
+/// Returns the height of the pin strip for text of `font_size`.
+pub fn strip_height(font_size: u16) -> f32 {
+    font_size as f32 * 2.2
+}
+
+/// Splits the results area into the pin strip along its top and the rest, for
+/// the list; the strip is empty when nothing is pinned.
+pub fn split_results(results: Rect, pins: &Pins, font_size: u16) -> (Rect, Rect) {
+    let h = match pins.is_empty() {
+        true => 0.0,
+        false => strip_height(font_size).min(results.h),
+    };
+    (
+        Rect::new(results.x, results.y, results.w, h),
+        Rect::new(results.x, results.y + h, results.w, results.h - h),
+    )
+}
+
+/// Returns the size of the query text under each pinned word.
+fn query_size(font_size: u16) -> u16 {
+    (font_size * 2 / 3).max(1)
+}
+
+/// Lays out one chip per pin from the left of `strip`, each wide enough for its
+/// word at `font_size` and its query below, measuring text widths with
+/// `measure`. Pins that don't fit are left out.
+pub fn chip_rects(
+    strip: Rect,
+    pins: &Pins,
+    font_size: u16,
+    measure: impl Fn(&str, u16) -> f32,
+) -> Vec<Rect> {
+    let gap = strip.h * 0.2;
+    let mut x = strip.x;
+    let mut rects = Vec::with_capacity(pins.pins.len());
+    for pin in &pins.pins {
+        let text_w = measure(&pin.word, font_size).max(measure(&pin.query, query_size(font_size)));
+        let w = text_w + gap * 2.0;
+        if x + w > strip.right() {
+            break;
+        }
+        rects.push(Rect::new(x, strip.y, w, strip.h - gap));
+        x += w + gap;
+    }
+    rects
+}
+
+/// Returns the index of the pin whose chip is under `pos`, if any.
+pub fn chip_at(pos: Vec2, chips: &[Rect]) -> Option<usize> {
+    chips.iter().position(|chip| chip.contains(pos))
+}
+
+/// Returns the pin button at the right end of `row` of the list in `area`
+/// scrolled by `scroll`, or `None` if the row isn't wholly in view.
+pub fn pin_button(area: Rect, row_h: f32, scroll: f32, row: usize) -> Option<Rect> {
+    let top = area.y + row as f32 * row_h - scroll;
+    if top < area.y - 0.5 || top + row_h > area.bottom() + 0.5 {
+        return None;
+    }
+    let size = row_h * 0.6;
+    let pad = (row_h - size) / 2.0;
+    Some(Rect::new(area.right() - size - pad, top + pad, size, size))
+}
+
+/// Draws a pin in `button`: a round head on a needle, filled in when `pinned`.
+pub fn draw_pin_button(gfx: &dyn Renderer, button: Rect, pinned: bool, theme: &Theme) {
+    let head = vec2(button.center().x, button.y + button.h * 0.35);
+    let radius = button.w * 0.28;
+    let color = if pinned {
+        theme.highlight
+    } else {
+        theme.result
+    };
+    let tip = vec2(head.x, button.bottom());
+    gfx.line(head, tip, theme.outline_thickness, color);
+    gfx.circle(head, radius, color);
+    if !pinned {
+        gfx.circle(head, radius * 0.5, theme.background);
+    }
+}
+
+/// Draws the pinned words in `strip`, each with its query in smaller text below.
+pub fn draw_pins(
+    gfx: &dyn Renderer,
+    strip: Rect,
+    pins: &Pins,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+) {
+    if pins.is_empty() {
+        return;
+    }
+    let small = query_size(font_size);
+    let chips = chip_rects(strip, pins, font_size, |text, size| {
+        gfx.measure(text, font, size).width
+    });
+    for (pin, chip) in pins.pins.iter().zip(&chips) {
+        gfx.rect_lines(*chip, theme.outline_thickness, theme.box_outline);
+        let x = chip.x + strip.h * 0.2;
+        let word_y = chip.y + font_size as f32;
+        gfx.text(&pin.word, vec2(x, word_y), font, font_size, theme.highlight);
+        let query_y = word_y + small as f32 * 1.1;
+        gfx.text(&pin.query, vec2(x, query_y), font, small, theme.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(pins: &Pins) -> Vec<&str> {
+        pins.as_slice()
+            .iter()
+            .map(|pin| pin.word.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn toggling_pins_then_unpins_in_order() {
+        let mut pins = Pins::default();
+        assert_eq!(pins.toggle("GREAT", "TAGER"), PinEffect::Pinned);
+        assert_eq!(pins.toggle("UPON", "NUPO"), PinEffect::Pinned);
+        assert_eq!(words(&pins), ["GREAT", "UPON"]);
+        assert_eq!(pins.as_slice()[1].query, "NUPO");
+        assert_eq!(pins.toggle("GREAT", ":sub TAGERS"), PinEffect::Unpinned);
+        assert_eq!(words(&pins), ["UPON"]);
+        assert!(!pins.unpin("GREAT"));
+        assert!(pins.clear());
+        assert!(!pins.clear());
+        assert!(pins.is_empty());
+    }
+
+    #[test]
+    fn pins_past_capacity_are_refused() {
+        let mut pins = Pins::default();
+        for i in 0..PIN_CAPACITY {
+            assert_eq!(pins.toggle(&format!("W{i}"), "Q"), PinEffect::Pinned);
+        }
+        assert_eq!(pins.toggle("MORE", "Q"), PinEffect::Full);
+        assert!(!pins.contains("MORE"));
+        // Unpinning still works when full, and makes room
+        assert_eq!(pins.toggle("W0", "Q"), PinEffect::Unpinned);
+        assert_eq!(pins.toggle("MORE", "Q"), PinEffect::Pinned);
+        assert_eq!(pins.as_slice().len(), PIN_CAPACITY);
+    }
+
+    #[test]
+    fn the_strip_is_only_taken_with_pins() {
+        let results = Rect::new(0.0, 100.0, 400.0, 300.0);
+        let mut pins = Pins::default();
+        let (strip, list) = split_results(results, &pins, 20);
+        assert_eq!((strip.h, list), (0.0, results));
+        pins.toggle("GREAT", "TAGER");
+        let (strip, list) = split_results(results, &pins, 20);
+        assert_eq!(strip.h, strip_height(20));
+        assert_eq!((list.y, list.bottom()), (strip.bottom(), results.bottom()));
+        // Never more than the results area
+        let (strip, list) = split_results(Rect::new(0.0, 0.0, 400.0, 10.0), &pins, 20);
+        assert_eq!((strip.h, list.h), (10.0, 0.0));
+    }
+
+    #[test]
+    fn chips_fit_their_text_and_stop_at_the_edge() {
+        let mut pins = Pins::default();
+        pins.toggle("GREAT", "TAGER");
+        pins.toggle("UPON", ":sub NUPOS");
+        pins.toggle("SOUND", "DUNOS");
+        let strip = Rect::new(0.0, 0.0, 200.0, 40.0);
+        // Ten pixels a character, whatever the size
+        let chips = chip_rects(strip, &pins, 20, |text, _| text.len() as f32 * 10.0);
+        let gap = 8.0;
+        assert_eq!(chips.len(), 2, "{chips:?}");
+        assert_eq!(chips[0], Rect::new(0.0, 0.0, 50.0 + gap * 2.0, 40.0 - gap));
+        // The longer query sets the second chip's width
+        assert_eq!(chips[1].x, chips[0].right() + gap);
+        assert_eq!(chips[1].w, 100.0 + gap * 2.0);
+        assert_eq!(chip_at(vec2(chips[1].x + 1.0, 5.0), &chips), Some(1));
+        assert_eq!(chip_at(vec2(199.0, 5.0), &chips), None);
+    }
+
+    #[test]
+    fn pin_buttons_show_only_for_rows_in_view() {
+        let area = Rect::new(0.0, 100.0, 300.0, 100.0);
+        let button = pin_button(area, 20.0, 0.0, 1).unwrap();
+        assert_eq!(button, Rect::new(284.0, 124.0, 12.0, 12.0));
+        assert!(pin_button(area, 20.0, 0.0, 4).is_some());
+        assert_eq!(pin_button(area, 20.0, 0.0, 5), None);
+        assert_eq!(pin_button(area, 20.0, 10.0, 0), None);
+        assert!(pin_button(area, 20.0, 20.0, 5).is_some());
+    }
+}
@@ -0,0 +1,216 @@
+//! Linux windowing backend selection, and other hints from the desktop.
+//!
+//! `window_conf()` runs before the window exists, so detection here is plain
+//! environment inspection.
+
+use macroquad::miniquad::conf::LinuxBackend;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The windowing backend requested via config or `--backend`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendChoice {
+    /// Prefer the session's native backend, falling back to the other.
+    #[default]
+    Auto,
+    Wayland,
+    X11,
+}
+
+impl BackendChoice {
+    /// Parses `"auto"`, `"wayland"`, or `"x11"` (case-insensitive).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.to_ascii_lowercase().as_str() {
+            "auto" => Ok(BackendChoice::Auto),
+            "wayland" => Ok(BackendChoice::Wayland),
+            "x11" => Ok(BackendChoice::X11),
+            _ => Err(format!("'{text}' is not a backend (auto, wayland, x11)")),
+        }
+    }
+}
+
+/// Returns whether the environment describes a Wayland session.
+///
+/// `get_env` looks up an environment variable, so callers can fake the environment.
+pub fn is_wayland_session(get_env: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(session) = get_env("XDG_SESSION_TYPE") {
+        match session.to_ascii_lowercase().as_str() {
+            "wayland" => return true,
+            "x11" => return false,
+            _ => (),
+        }
+    }
+    get_env("WAYLAND_DISPLAY").is_some_and(|d| !d.is_empty())
+}
+
+/// Resolves `choice` to a miniquad backend.
+///
+/// Explicit choices are honored as-is; `Auto` prefers whichever backend the session
+/// uses and lets miniquad fall back to the other.
+pub fn linux_backend(
+    choice: BackendChoice,
+    get_env: impl Fn(&str) -> Option<String>,
+) -> LinuxBackend {
+    match choice {
+        BackendChoice::Wayland => LinuxBackend::WaylandOnly,
+        BackendChoice::X11 => LinuxBackend::X11Only,
+        BackendChoice::Auto if is_wayland_session(get_env) => LinuxBackend::WaylandWithX11Fallback,
+        BackendChoice::Auto => LinuxBackend::X11WithWaylandFallback,
+    }
+}
+
+/// Returns the value of the first `key=value` line of INI `text`, whatever its
+/// section.
+fn ini_value(text: &str, key: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        (name.trim() == key).then(|| value.trim().to_owned())
+    })
+}
+
+/// Returns whether the Linux desktop asks for less motion, as best it can
+/// tell: KDE with its animation speed set to instant, or elsewhere GTK's
+/// settings file with animations off. Anywhere else, or if the settings can't
+/// be read, it doesn't.
+///
+/// `get_env` looks up an environment variable, so callers can fake the environment.
+pub fn desktop_reduces_motion(get_env: impl Fn(&str) -> Option<String>) -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    let config_dir = match get_env("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => match get_env("HOME") {
+            Some(home) => PathBuf::from(home).join(".config"),
+            None => return false,
+        },
+    };
+    let read = |file: &str, key: &str| {
+        let text = std::fs::read_to_string(config_dir.join(file)).ok()?;
+        ini_value(&text, key)
+    };
+    let desktop = get_env("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    if desktop.to_ascii_uppercase().contains("KDE") {
+        let factor = read("kdeglobals", "AnimationDurationFactor");
+        return factor.and_then(|f| f.parse::<f32>().ok()) == Some(0.0);
+    }
+    let animations = read("gtk-3.0/settings.ini", "gtk-enable-animations");
+    animations.is_some_and(|on| matches!(on.as_str(), "0" | "false"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a lookup answering from `vars` only.
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn session_type_decides_when_set() {
+        assert!(is_wayland_session(env(&[("XDG_SESSION_TYPE", "wayland")])));
+        assert!(is_wayland_session(env(&[("XDG_SESSION_TYPE", "Wayland")])));
+        let x11 = [
+            ("XDG_SESSION_TYPE", "x11"),
+            ("WAYLAND_DISPLAY", "wayland-0"),
+        ];
+        assert!(!is_wayland_session(env(&x11)));
+    }
+
+    #[test]
+    fn wayland_display_decides_otherwise() {
+        assert!(is_wayland_session(env(&[("WAYLAND_DISPLAY", "wayland-0")])));
+        let tty = [
+            ("XDG_SESSION_TYPE", "tty"),
+            ("WAYLAND_DISPLAY", "wayland-1"),
+        ];
+        assert!(is_wayland_session(env(&tty)));
+        assert!(!is_wayland_session(env(&[("WAYLAND_DISPLAY", "")])));
+        assert!(!is_wayland_session(env(&[])));
+    }
+
+    #[test]
+    fn auto_prefers_the_sessions_backend() {
+        let wayland = env(&[("XDG_SESSION_TYPE", "wayland")]);
+        let backend = linux_backend(BackendChoice::Auto, wayland);
+        assert!(matches!(backend, LinuxBackend::WaylandWithX11Fallback));
+        let backend = linux_backend(BackendChoice::Auto, env(&[]));
+        assert!(matches!(backend, LinuxBackend::X11WithWaylandFallback));
+    }
+
+    #[test]
+    fn explicit_choices_ignore_the_environment() {
+        let wayland = env(&[("XDG_SESSION_TYPE", "wayland")]);
+        let backend = linux_backend(BackendChoice::X11, wayland);
+        assert!(matches!(backend, LinuxBackend::X11Only));
+        let backend = linux_backend(BackendChoice::Wayland, env(&[]));
+        assert!(matches!(backend, LinuxBackend::WaylandOnly));
+    }
+
+    #[test]
+    fn backend_names_parse_in_any_case() {
+        assert_eq!(BackendChoice::parse("X11"), Ok(BackendChoice::X11));
+        assert_eq!(BackendChoice::parse("auto"), Ok(BackendChoice::Auto));
+        assert!(BackendChoice::parse("mir").is_err());
+    }
+
+    #[test]
+    fn ini_values_are_found_in_any_section() {
+        let text = "[Settings]\ngtk-theme-name = Adwaita\n[KDE]\nAnimationDurationFactor=0\n";
+        assert_eq!(
+            ini_value(text, "gtk-theme-name").as_deref(),
+            Some("Adwaita")
+        );
+        assert_eq!(
+            ini_value(text, "AnimationDurationFactor").as_deref(),
+            Some("0")
+        );
+        assert_eq!(ini_value(text, "gtk-enable-animations"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn the_desktop_asks_for_less_motion_in_its_own_settings() {
+        let dir = std::env::temp_dir().join(format!(
+            "jumble_helper-reduced-motion-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("gtk-3.0")).unwrap();
+        let config = dir.to_str().unwrap();
+        let gtk = [
+            ("XDG_CONFIG_HOME", config),
+            ("XDG_CURRENT_DESKTOP", "GNOME"),
+        ];
+        let kde = [("XDG_CONFIG_HOME", config), ("XDG_CURRENT_DESKTOP", "KDE")];
+        assert!(!desktop_reduces_motion(env(&gtk)));
+        assert!(!desktop_reduces_motion(env(&kde)));
+
+        let settings = dir.join("gtk-3.0/settings.ini");
+        std::fs::write(&settings, "[Settings]\ngtk-enable-animations=false\n").unwrap();
+        assert!(desktop_reduces_motion(env(&gtk)));
+        std::fs::write(&settings, "[Settings]\ngtk-enable-animations=1\n").unwrap();
+        assert!(!desktop_reduces_motion(env(&gtk)));
+
+        // KDE goes by its own animation speed, not GTK's
+        std::fs::write(&settings, "[Settings]\ngtk-enable-animations=0\n").unwrap();
+        std::fs::write(
+            dir.join("kdeglobals"),
+            "[KDE]\nAnimationDurationFactor=0.5\n",
+        )
+        .unwrap();
+        assert!(!desktop_reduces_motion(env(&kde)));
+        std::fs::write(dir.join("kdeglobals"), "[KDE]\nAnimationDurationFactor=0\n").unwrap();
+        assert!(desktop_reduces_motion(env(&kde)));
+
+        // Without a config folder to look in, it can't tell
+        assert!(!desktop_reduces_motion(env(&[])));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
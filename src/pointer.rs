@@ -0,0 +1,160 @@
+//! Mouse and touch input unified as pointer events and gestures.
+
+use macroquad::prelude::*;
+
+/// Distance in pixels a pointer may travel and still count as a tap.
+pub const TAP_SLOP: f32 = 10.0;
+
+/// A single mouse or touch input, in screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEvent {
+    Pressed(Vec2),
+    Moved(Vec2),
+    Released(Vec2),
+}
+
+/// Collects this frame's pointer events from touches, or from the mouse if there
+/// are no touches.
+///
+/// Expects `simulate_mouse_with_touch(false)` so touches are not reported twice.
+pub fn poll_pointer_events() -> Vec<PointerEvent> {
+    let touches = touches();
+    if let Some(touch) = touches.first() {
+        let event = match touch.phase {
+            TouchPhase::Started => PointerEvent::Pressed(touch.position),
+            TouchPhase::Moved | TouchPhase::Stationary => PointerEvent::Moved(touch.position),
+            TouchPhase::Ended | TouchPhase::Cancelled => PointerEvent::Released(touch.position),
+        };
+        return vec![event];
+    }
+
+    let pos = Vec2::from(mouse_position());
+    let mut events = Vec::new();
+    if is_mouse_button_pressed(MouseButton::Left) {
+        events.push(PointerEvent::Pressed(pos));
+    } else if is_mouse_button_down(MouseButton::Left) {
+        events.push(PointerEvent::Moved(pos));
+    }
+    if is_mouse_button_released(MouseButton::Left) {
+        events.push(PointerEvent::Released(pos));
+    }
+    events
+}
+
+/// Higher-level interactions recognized from pointer events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// Press and release without moving beyond `TAP_SLOP`.
+    Tap(Vec2),
+    /// Movement while pressed: where the press began and the vertical change since
+    /// the previous event.
+    Drag { start: Vec2, dy: f32 },
+    /// Release after dragging, with the vertical velocity in pixels per second.
+    Fling { start: Vec2, velocity: f32 },
+}
+
+/// Turns a stream of pointer events into gestures.
+#[derive(Debug, Default)]
+pub struct GestureTracker {
+    start: Option<Vec2>,
+    last: Vec2,
+    last_time: f64,
+    dragging: bool,
+    velocity: f32,
+}
+
+impl GestureTracker {
+    /// Feeds one event observed at `time` (seconds) and returns any gesture it completes.
+    pub fn feed(&mut self, event: PointerEvent, time: f64) -> Option<Gesture> {
+        match event {
+            PointerEvent::Pressed(pos) => {
+                *self = Self {
+                    start: Some(pos),
+                    last: pos,
+                    last_time: time,
+                    ..Default::default()
+                };
+                None
+            }
+            PointerEvent::Moved(pos) => {
+                let start = self.start?;
+                if !self.dragging && start.distance(pos) <= TAP_SLOP {
+                    return None;
+                }
+                self.dragging = true;
+                let dy = pos.y - self.last.y;
+                let dt = (time - self.last_time) as f32;
+                if dt > 0.0 {
+                    // Smooth the estimate so one jittery sample doesn't dominate.
+                    self.velocity = 0.7 * (dy / dt) + 0.3 * self.velocity;
+                }
+                self.last = pos;
+                self.last_time = time;
+                Some(Gesture::Drag { start, dy })
+            }
+            PointerEvent::Released(pos) => {
+                let start = self.start.take()?;
+                if self.dragging {
+                    Some(Gesture::Fling {
+                        start,
+                        velocity: self.velocity,
+                    })
+                } else if start.distance(pos) <= TAP_SLOP {
+                    Some(Gesture::Tap(pos))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_short_press_is_a_tap() {
+        let mut tracker = GestureTracker::default();
+        assert_eq!(
+            tracker.feed(PointerEvent::Pressed(vec2(50.0, 50.0)), 0.0),
+            None
+        );
+        assert_eq!(
+            tracker.feed(PointerEvent::Moved(vec2(54.0, 52.0)), 0.05),
+            None
+        );
+        let tap = tracker.feed(PointerEvent::Released(vec2(54.0, 52.0)), 0.1);
+        assert_eq!(tap, Some(Gesture::Tap(vec2(54.0, 52.0))));
+    }
+
+    #[test]
+    fn moving_past_the_slop_drags_then_flings() {
+        let mut tracker = GestureTracker::default();
+        let start = vec2(50.0, 200.0);
+        tracker.feed(PointerEvent::Pressed(start), 0.0);
+        let drag = tracker.feed(PointerEvent::Moved(vec2(50.0, 180.0)), 0.1);
+        assert_eq!(drag, Some(Gesture::Drag { start, dy: -20.0 }));
+        tracker.feed(PointerEvent::Moved(vec2(50.0, 160.0)), 0.2);
+        match tracker.feed(PointerEvent::Released(vec2(50.0, 160.0)), 0.25) {
+            Some(Gesture::Fling { start: s, velocity }) => {
+                assert_eq!(s, start);
+                assert!(velocity < -150.0 && velocity > -250.0, "{velocity}");
+            }
+            other => panic!("expected a fling, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn events_without_a_press_are_ignored() {
+        let mut tracker = GestureTracker::default();
+        assert_eq!(
+            tracker.feed(PointerEvent::Moved(vec2(0.0, 90.0)), 0.0),
+            None
+        );
+        assert_eq!(
+            tracker.feed(PointerEvent::Released(vec2(0.0, 90.0)), 0.1),
+            None
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Spelling the final answer out of a pool of letters.
+//!
+//! The Jumble's final answer is a phrase made of the letters circled in the
+//! other answers. With those letters in the entry, Alt+F opens a panel showing
+//! them as tiles, and each letter typed for the phrase uses up one tile, as
+//! crossing letters off on paper would. A letter with no tile left is refused
+//! (and flashes), a space uses no tile, and Backspace gives the last letter back.
+
+use crate::modal::draw_panel;
+use crate::render::Renderer;
+use crate::strings::{fill, Language, StringKey};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Seconds a refused letter stays shown.
+pub const REFUSED_TIME: f64 = 0.4;
+
+/// A phrase typed from a fixed pool of letters, each used at most once.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PooledEntryBuffer {
+    /// The pool's letters, in the order shown.
+    pool: Vec<char>,
+    /// Whether each of `pool` is used up.
+    used: Vec<bool>,
+    /// The phrase typed so far, each letter with the index in `pool` it used;
+    /// spaces use none.
+    typed: Vec<(char, Option<usize>)>,
+}
+
+impl PooledEntryBuffer {
+    /// Creates an empty phrase over the letters of `pool`, uppercased; other
+    /// characters aren't part of the pool.
+    pub fn new(pool: &str) -> Self {
+        let pool: Vec<char> = pool
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_uppercase)
+            .collect();
+        Self {
+            used: vec![false; pool.len()],
+            pool,
+            typed: Vec::new(),
+        }
+    }
+    /// Adds `c` to the phrase if the pool has it, using up the first unused tile
+    /// of that letter, and returns whether it was added.
+    ///
+    /// A space is added between words without using a tile, but never first or
+    /// after another space. Anything else but a letter is refused.
+    pub fn push(&mut self, c: char) -> bool {
+        if c == ' ' {
+            let after_word = self.typed.last().is_some_and(|&(last, _)| last != ' ');
+            if after_word {
+                self.typed.push((' ', None));
+            }
+            return after_word;
+        }
+        let Some(letter) = c.to_uppercase().next().filter(|c| c.is_alphabetic()) else {
+            return false;
+        };
+        let tile = (0..self.pool.len()).find(|&i| !self.used[i] && self.pool[i] == letter);
+        if let Some(i) = tile {
+            self.used[i] = true;
+            self.typed.push((letter, Some(i)));
+        }
+        tile.is_some()
+    }
+    /// Takes the last character off the phrase, giving its tile back to the pool,
+    /// and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let (c, tile) = self.typed.pop()?;
+        if let Some(i) = tile {
+            self.used[i] = false;
+        }
+        Some(c)
+    }
+    /// Returns the phrase typed so far.
+    pub fn phrase(&self) -> String {
+        self.typed.iter().map(|&(c, _)| c).collect()
+    }
+    /// Returns the pool's letters in order.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn letters(&self) -> String {
+        self.pool.iter().collect()
+    }
+    /// Returns the pool's letters in order, each with whether it's used up.
+    pub fn tiles(&self) -> impl Iterator<Item = (char, bool)> + '_ {
+        self.pool.iter().copied().zip(self.used.iter().copied())
+    }
+    /// Returns how many of the pool's letters are left.
+    pub fn remaining(&self) -> usize {
+        self.used.iter().filter(|&&used| !used).count()
+    }
+}
+
+/// Draws the pool panel over a dimmed screen: the tiles, with those used up
+/// dimmed and struck through, then the phrase, with `refused` after it in the
+/// error color if a letter was just refused.
+pub fn draw_pool(
+    gfx: &dyn Renderer,
+    pool: &PooledEntryBuffer,
+    refused: Option<char>,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+    lang: Language,
+) {
+    let measure = |text: &str, size: u16| gfx.measure(text, font, size).width;
+    let title = lang.text(StringKey::PoolTitle);
+    let hint = fill(lang.text(StringKey::PoolHint), pool.remaining());
+    let hint = hint.as_str();
+    let tile_size = font_size * 2;
+    let tile_w = tile_size as f32 * 0.9;
+    let tiles_w = tile_w * pool.pool.len() as f32;
+    let phrase = pool.phrase();
+    let phrase_w = measure(&phrase, tile_size) + refused.map_or(0.0, |_| measure("W", tile_size));
+
+    let line_h = font_size as f32 * 1.4;
+    let pad = font_size as f32;
+    let w = tiles_w
+        .max(phrase_w)
+        .max(measure(title, font_size))
+        .max(measure(hint, font_size))
+        + pad * 2.0;
+    let h = line_h * 2.0 + tile_size as f32 * 3.0 + pad * 2.0;
+    let panel = draw_panel(gfx, w, h, theme);
+    let centered = |width: f32| panel.center().x - width / 2.0;
+
+    let title_y = panel.y + pad + line_h * 0.75;
+    gfx.text(
+        title,
+        vec2(centered(measure(title, font_size)), title_y),
+        font,
+        font_size,
+        theme.entry_text,
+    );
+
+    let mut spent = theme.result;
+    spent.a = 0.35;
+    let tiles_y = panel.y + pad + line_h + tile_size as f32 * 1.1;
+    for (i, (letter, used)) in pool.tiles().enumerate() {
+        let text = letter.to_string();
+        let x = centered(tiles_w) + tile_w * i as f32;
+        let letter_x = x + (tile_w - measure(&text, tile_size)) / 2.0;
+        let color = if used { spent } else { theme.entry_text };
+        gfx.text(&text, vec2(letter_x, tiles_y), font, tile_size, color);
+        if used {
+            let strike_y = tiles_y - tile_size as f32 * 0.35;
+            let (from, to) = (
+                vec2(x + tile_w * 0.1, strike_y),
+                vec2(x + tile_w * 0.9, strike_y),
+            );
+            gfx.line(from, to, theme.outline_thickness * 2.0, spent);
+        }
+    }
+
+    let phrase_y = tiles_y + tile_size as f32 * 1.5;
+    let phrase_x = centered(phrase_w);
+    gfx.text(
+        &phrase,
+        vec2(phrase_x, phrase_y),
+        font,
+        tile_size,
+        theme.highlight,
+    );
+    if let Some(letter) = refused {
+        let x = phrase_x + measure(&phrase, tile_size);
+        gfx.text(
+            &letter.to_string(),
+            vec2(x, phrase_y),
+            font,
+            tile_size,
+            theme.error,
+        );
+    }
+
+    let hint_y = panel.bottom() - pad - line_h * 0.25;
+    gfx.text(
+        hint,
+        vec2(centered(measure(hint, font_size)), hint_y),
+        font,
+        font_size,
+        theme.result,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn used(pool: &PooledEntryBuffer) -> Vec<bool> {
+        pool.tiles().map(|(_, used)| used).collect()
+    }
+
+    #[test]
+    fn a_doubled_letter_can_be_used_twice_and_no_more() {
+        let mut pool = PooledEntryBuffer::new("tab-a");
+        assert_eq!(pool.tiles().map(|(c, _)| c).collect::<String>(), "TABA");
+        assert!(pool.push('a'));
+        assert!(pool.push('A'));
+        assert!(!pool.push('A'));
+        assert!(!pool.push('Z'));
+        assert_eq!(pool.phrase(), "AA");
+        assert_eq!(used(&pool), [false, true, false, true]);
+        assert_eq!(pool.remaining(), 2);
+    }
+
+    #[test]
+    fn spaces_only_go_between_words() {
+        let mut pool = PooledEntryBuffer::new("ATONE");
+        assert!(!pool.push(' '));
+        assert!(pool.push('A'));
+        assert!(pool.push(' '));
+        assert!(!pool.push(' '));
+        assert!(!pool.push('-'));
+        assert!(pool.push('T'));
+        assert!(pool.push('O'));
+        assert_eq!(pool.phrase(), "A TO");
+        assert_eq!(pool.remaining(), 2);
+    }
+
+    #[test]
+    fn backspace_gives_the_tile_back() {
+        let mut pool = PooledEntryBuffer::new("NOON");
+        for c in "NO O".chars() {
+            assert!(pool.push(c), "{c}");
+        }
+        assert_eq!(pool.pop(), Some('O'));
+        assert_eq!(pool.pop(), Some(' '));
+        assert_eq!(used(&pool), [true, true, false, false]);
+        // The tile given back is the one used next
+        assert!(pool.push('O'));
+        assert_eq!(used(&pool), [true, true, true, false]);
+        while pool.pop().is_some() {}
+        assert_eq!(pool.remaining(), 4);
+        assert_eq!(pool.pop(), None);
+    }
+
+    #[test]
+    fn letters_outside_a_z_are_tiles_too() {
+        let mut pool = PooledEntryBuffer::new("AÑO");
+        assert!(pool.push('ñ'));
+        assert!(!pool.push('N'));
+        assert_eq!(pool.phrase(), "Ñ");
+    }
+}
@@ -0,0 +1,280 @@
+//! Passing the matches through a command of the user's own, which may reorder,
+//! filter, or annotate them.
+//!
+//! With `post_process_command` set, each new list of matches is written to the
+//! command's stdin as JSON:
+//!
+//! ```json
+//! {"entry":"GREAT","mode":"exact","matches":[{"word":"GRATE"},{"word":"GREAT"}]}
+//! ```
+//!
+//! where `mode` is `exact`, or the name of the query (`sub`, `pat`, `bank`, or
+//! `phrase`).
+//!
+//! and the command answers on stdout in the same form, each match optionally
+//! with a `note`. Its list replaces the one shown. The command runs in the
+//! background; if it takes longer than `POST_PROCESS_TIMEOUT`, fails, or answers
+//! with something else, the original list stays and a toast says why.
+
+// `DeJson`'s expansion for `Option` fields trips this lint
+#![allow(clippy::question_mark)]
+
+use nanoserde::{DeJson, SerJson};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Seconds the command has to answer.
+pub const POST_PROCESS_TIMEOUT: f64 = 0.2;
+
+/// One match, as passed to and from the command.
+#[derive(Debug, Clone, PartialEq, DeJson, SerJson)]
+pub struct Candidate {
+    pub word: String,
+    /// Shown beside the word.
+    #[nserde(default)]
+    pub note: Option<String>,
+}
+
+/// The matches for an entry, as passed to and from the command.
+#[derive(Debug, Clone, PartialEq, DeJson, SerJson)]
+pub struct Candidates {
+    pub entry: String,
+    /// `exact`, or the query's name.
+    pub mode: String,
+    pub matches: Vec<Candidate>,
+}
+
+impl Candidates {
+    pub fn new(entry: &str, mode: &str, words: &[String]) -> Self {
+        Self {
+            entry: entry.to_owned(),
+            mode: mode.to_owned(),
+            matches: words
+                .iter()
+                .map(|word| Candidate {
+                    word: word.clone(),
+                    note: None,
+                })
+                .collect(),
+        }
+    }
+    /// Parses the command's answer, which may only list words from `original`.
+    pub fn parse_output(text: &str, original: &[String]) -> Result<Vec<Candidate>, String> {
+        let answer = Candidates::deserialize_json(text.trim())
+            .map_err(|e| format!("malformed output: {e}"))?;
+        if let Some(stray) = answer.matches.iter().find(|c| !original.contains(&c.word)) {
+            return Err(format!("'{}' isn't one of the matches", stray.word));
+        }
+        Ok(answer.matches)
+    }
+}
+
+/// What became of a list sent to the command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostOutcome {
+    /// The list to show instead.
+    Done(Vec<Candidate>),
+    /// Why the original list stays.
+    Failed(String),
+}
+
+/// The command running on the latest list.
+struct Running {
+    generation: u64,
+    started: f64,
+    child: Child,
+    /// The list sent, to check the answer against.
+    original: Vec<String>,
+}
+
+/// Runs the post-processing command, one list at a time.
+///
+/// Like `QueryRunner`, every submission bumps the generation, so answers about
+/// lists that have since changed are dropped.
+pub struct PostProcessor {
+    /// Program and its leading arguments.
+    command: Vec<String>,
+    clock: fn() -> f64,
+    generation: u64,
+    running: Option<Running>,
+    output_tx: Sender<(u64, Result<String, String>)>,
+    output_rx: Receiver<(u64, Result<String, String>)>,
+}
+
+impl PostProcessor {
+    /// Creates a processor running `command` (e.g. `"python3 score.py"`), timing
+    /// it with `clock` (in seconds); `None` if the command is blank.
+    pub fn new(command: &str, clock: fn() -> f64) -> Option<Self> {
+        let command: Vec<String> = command.split_whitespace().map(str::to_owned).collect();
+        if command.is_empty() {
+            return None;
+        }
+        let (output_tx, output_rx) = mpsc::channel();
+        Some(Self {
+            command,
+            clock,
+            generation: 0,
+            running: None,
+            output_tx,
+            output_rx,
+        })
+    }
+    /// Starts the command on `words`, stopping any run on an older list.
+    ///
+    /// Returns why it couldn't start, if it couldn't.
+    pub fn submit(&mut self, entry: &str, mode: &str, words: &[String]) -> Result<(), String> {
+        self.cancel();
+        let (program, args) = self.command.split_first().expect("command is never empty");
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("can't run '{program}': {e}"))?;
+
+        let input = Candidates::new(entry, mode, words).serialize_json();
+        let (mut stdin, mut stdout) = (child.stdin.take(), child.stdout.take());
+        let (generation, tx) = (self.generation, self.output_tx.clone());
+        std::thread::spawn(move || {
+            // A command that exits without reading its input is its own business
+            if let Some(mut stdin) = stdin.take() {
+                let _ = stdin.write_all(input.as_bytes());
+            }
+            let mut output = String::new();
+            let result = match stdout.as_mut().map(|out| out.read_to_string(&mut output)) {
+                Some(Ok(_)) => Ok(output),
+                Some(Err(e)) => Err(format!("can't read its output: {e}")),
+                None => Err("no output".into()),
+            };
+            let _ = tx.send((generation, result));
+        });
+        self.running = Some(Running {
+            generation,
+            started: (self.clock)(),
+            child,
+            original: words.to_vec(),
+        });
+        Ok(())
+    }
+    /// Returns what became of the latest list, once the command has answered or
+    /// run out of time.
+    pub fn poll(&mut self) -> Option<PostOutcome> {
+        let running = self.running.as_mut()?;
+        let answer = self
+            .output_rx
+            .try_iter()
+            .filter(|(generation, _)| *generation == running.generation)
+            .last();
+        let outcome = match answer {
+            // Output ends when the command exits, but one that merely closed its
+            // stdout is stopped below rather than waited on
+            Some((_, Ok(text))) => match running.child.try_wait() {
+                Ok(Some(status)) if !status.success() => {
+                    let code = status.code().unwrap_or(-1);
+                    PostOutcome::Failed(format!("exited with code {code}"))
+                }
+                _ => match Candidates::parse_output(&text, &running.original) {
+                    Ok(matches) => PostOutcome::Done(matches),
+                    Err(e) => PostOutcome::Failed(e),
+                },
+            },
+            Some((_, Err(e))) => PostOutcome::Failed(e),
+            None if (self.clock)() - running.started > POST_PROCESS_TIMEOUT => PostOutcome::Failed(
+                format!("no answer within {} ms", POST_PROCESS_TIMEOUT * 1000.0),
+            ),
+            None => return None,
+        };
+        self.cancel();
+        Some(outcome)
+    }
+    /// Stops the command, if it's running, and forgets its answer.
+    pub fn cancel(&mut self) {
+        self.generation += 1;
+        if let Some(mut running) = self.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+        }
+    }
+    /// Returns whether the command is working on a list.
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+}
+
+impl Drop for PostProcessor {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::record::wall_clock;
+
+    /// Runs the helper script, answering as `mode` says (see the script), on
+    /// GERTA's matches, and waits for what becomes of them.
+    fn run_helper(mode: &str) -> PostOutcome {
+        let script = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/post_process_helper.sh");
+        let mut processor = PostProcessor::new(&format!("sh {script} {mode}"), wall_clock).unwrap();
+        let words = ["GRATE".to_owned(), "GREAT".to_owned()];
+        processor.submit("GERTA", "exact", &words).unwrap();
+        let started = wall_clock();
+        loop {
+            if let Some(outcome) = processor.poll() {
+                assert!(!processor.is_running());
+                return outcome;
+            }
+            assert!(wall_clock() - started < 5.0, "{mode}: never finished");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    fn candidate(word: &str, note: Option<&str>) -> Candidate {
+        Candidate {
+            word: word.to_owned(),
+            note: note.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn the_matches_go_out_and_come_back_as_json() {
+        let expected = vec![candidate("GRATE", None), candidate("GREAT", None)];
+        assert_eq!(run_helper("echo"), PostOutcome::Done(expected));
+    }
+
+    #[test]
+    fn the_commands_order_and_notes_replace_the_list() {
+        let expected = vec![candidate("GREAT", Some("best")), candidate("GRATE", None)];
+        assert_eq!(run_helper("reorder"), PostOutcome::Done(expected));
+    }
+
+    #[test]
+    fn a_bad_answer_keeps_the_list() {
+        for mode in ["stray", "garbage", "fail", "slow"] {
+            let outcome = run_helper(mode);
+            assert!(
+                matches!(outcome, PostOutcome::Failed(_)),
+                "{mode}: {outcome:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_missing_program_fails_to_start() {
+        let mut processor = PostProcessor::new("no-such-program-anywhere", wall_clock).unwrap();
+        assert!(processor.submit("GERTA", "exact", &[]).is_err());
+        assert!(PostProcessor::new("  ", wall_clock).is_none());
+    }
+
+    #[test]
+    fn output_is_checked_against_the_matches_sent() {
+        let original = ["GREAT".to_owned()];
+        let text = r#"{"entry":"GERTA","mode":"exact","matches":[{"word":"GREAT","note":null}]}"#;
+        let parsed = Candidates::parse_output(text, &original).unwrap();
+        assert_eq!(parsed, [candidate("GREAT", None)]);
+        assert!(Candidates::parse_output(r#"{"matches":[]}"#, &original).is_err());
+    }
+}
@@ -0,0 +1,499 @@
+//! Query modes, and moving slow ones off the main thread.
+//!
+//! Exact anagram lookups are a single hash lookup and always run in the frame that
+//! asked for them. Other modes scan the dictionary; once one of them blows the frame
+//! budget, later ones are handed to a worker thread so typing stays smooth.
+//! Query commands typed in the entry (see `lang`) always run as slow queries,
+//! and costly ones wait for Enter first (see `cost`).
+//!
+//! Each query on the worker carries a `QueryTicket` naming the entry and the
+//! dictionary it was asked about, and its answer only counts if both are still
+//! current: a slow search of a word list that has since been swapped out is
+//! asked again of the new one rather than shown.
+
+pub mod cost;
+pub mod lang;
+
+use crate::wordmap::WordMap;
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use lang::QueryKind;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+/// Longest a query may run on the main thread before slow modes are offloaded.
+pub const QUERY_BUDGET: f64 = 0.005;
+
+/// Web builds have no threads, so every query runs inline there.
+const CAN_OFFLOAD: bool = cfg!(not(target_arch = "wasm32"));
+
+/// How the entry is matched against the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryMode {
+    /// Words using exactly the entry's letters.
+    Exact,
+    /// Words using some of the entry's letters.
+    SubAnagram,
+}
+
+/// Answers queries; shared with the worker thread.
+pub trait Solver: Send + Sync + 'static {
+    /// Returns the words (or phrases) matching `query`.
+    fn solve(&self, query: &QueryKind) -> Vec<String>;
+}
+
+impl Solver for WordMap {
+    fn solve(&self, query: &QueryKind) -> Vec<String> {
+        match query {
+            QueryKind::Exact(entry) => self
+                .find_match(entry, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut Vec::new())
+                .map(<[String]>::to_vec)
+                .unwrap_or_default(),
+            QueryKind::Sub(entry) => self.sub_anagrams(entry, MIN_WORD_LENGTH),
+            QueryKind::DropOne(entry) => self.drop_one(entry),
+            QueryKind::Pattern(pattern) => self.pattern_matches(pattern),
+            QueryKind::Bank { letters, min_len } => self.letter_bank(letters, *min_len),
+            QueryKind::Phrase { letters, lengths } => self.phrases(letters, lengths),
+            QueryKind::Letters(_) => Vec::new(),
+        }
+    }
+}
+
+/// What a query was asked about: which submission, and which dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryTicket {
+    /// Counts submissions (and cancellations), so a later one supersedes it.
+    pub entry_generation: u64,
+    /// `AppContext::dictionary_generation` of the dictionary searched.
+    pub dict_generation: u32,
+}
+
+/// A query sent to the worker, with the dictionary to search.
+struct QueryRequest<S> {
+    ticket: QueryTicket,
+    query: QueryKind,
+    solver: Arc<S>,
+}
+
+/// The worker's answer to a `QueryRequest`.
+struct QueryResponse {
+    ticket: QueryTicket,
+    words: Vec<String>,
+}
+
+/// Runs queries inline until one is too slow, then on a worker thread.
+///
+/// Every submission bumps the entry generation, and every dictionary swap the
+/// dictionary generation, so results that arrive after either has changed are
+/// recognised as stale and dropped.
+pub struct QueryRunner<S: Solver> {
+    solver: Arc<S>,
+    clock: fn() -> f64,
+    ticket: QueryTicket,
+    /// The latest submission, kept to ask again of a new dictionary.
+    query: Option<QueryKind>,
+    /// The ticket of the latest request sent to the worker.
+    in_flight: Option<QueryTicket>,
+    pending: bool,
+    offload: bool,
+    requests: Option<Sender<QueryRequest<S>>>,
+    response_tx: Sender<QueryResponse>,
+    response_rx: Receiver<QueryResponse>,
+}
+
+impl<S: Solver> QueryRunner<S> {
+    /// Creates a runner for `solver`, the dictionary of `dict_generation`, timing
+    /// queries with `clock` (in seconds).
+    pub fn new(solver: Arc<S>, dict_generation: u32, clock: fn() -> f64) -> Self {
+        let (response_tx, response_rx) = mpsc::channel();
+        Self {
+            solver,
+            clock,
+            ticket: QueryTicket {
+                entry_generation: 0,
+                dict_generation,
+            },
+            query: None,
+            in_flight: None,
+            pending: false,
+            offload: false,
+            requests: None,
+            response_tx,
+            response_rx,
+        }
+    }
+    /// Searches `solver`, the dictionary of `dict_generation`, from now on. A
+    /// query still on the worker is asked again of it when its answer comes.
+    pub fn set_solver(&mut self, solver: Arc<S>, dict_generation: u32) {
+        self.solver = solver;
+        self.ticket.dict_generation = dict_generation;
+    }
+    /// Starts `query`.
+    ///
+    /// Returns the words right away if the query ran inline, or `None` if it went
+    /// to the worker; `poll` delivers those later.
+    pub fn submit(&mut self, query: QueryKind) -> Option<Vec<String>> {
+        self.ticket.entry_generation += 1;
+        self.query = Some(query.clone());
+        self.run(query)
+    }
+    /// Runs `query` under the current ticket, inline or on the worker.
+    fn run(&mut self, mut query: QueryKind) -> Option<Vec<String>> {
+        if self.offload {
+            let request = QueryRequest {
+                ticket: self.ticket,
+                query,
+                solver: Arc::clone(&self.solver),
+            };
+            let requests = self
+                .requests
+                .get_or_insert_with(|| spawn_worker(self.response_tx.clone()));
+            match requests.send(request) {
+                Ok(()) => {
+                    self.in_flight = Some(self.ticket);
+                    self.pending = true;
+                    return None;
+                }
+                Err(mpsc::SendError(request)) => query = request.query,
+            }
+            log::warn!("[QueryRunner::submit] query worker stopped; running inline");
+            self.offload = false;
+            self.requests = None;
+        }
+
+        let start = (self.clock)();
+        let words = self.solver.solve(&query);
+        let elapsed = (self.clock)() - start;
+        if elapsed > QUERY_BUDGET && CAN_OFFLOAD {
+            log::info!(
+                "[QueryRunner::submit] {} query took {:.1} ms; offloading slow queries",
+                query.name(),
+                elapsed * 1000.0
+            );
+            self.offload = true;
+        }
+        self.pending = false;
+        Some(words)
+    }
+    /// Returns the words for the latest submission once the worker has them.
+    ///
+    /// An answer from a dictionary swapped out since is dropped, and the query
+    /// asked again of the current one unless that's already under way.
+    pub fn poll(&mut self) -> Option<Vec<String>> {
+        let mut latest = None;
+        let mut outdated = false;
+        for response in self.response_rx.try_iter() {
+            if response.ticket == self.ticket {
+                latest = Some(response.words);
+            } else if response.ticket.entry_generation == self.ticket.entry_generation {
+                outdated = true;
+            }
+        }
+        if latest.is_some() {
+            self.pending = false;
+            return latest;
+        }
+        if outdated && self.pending && self.in_flight != Some(self.ticket) {
+            log::debug!("[QueryRunner::poll] dictionary changed mid-query; asking again");
+            let query = self.query.clone()?;
+            return self.run(query);
+        }
+        None
+    }
+    /// Sends queries to the worker from now on, as for one known to be slow
+    /// before it's run.
+    pub fn prefer_worker(&mut self) {
+        self.offload = CAN_OFFLOAD;
+    }
+    /// Forgets any query still in flight.
+    pub fn cancel(&mut self) {
+        self.ticket.entry_generation += 1;
+        self.query = None;
+        self.pending = false;
+    }
+    /// Returns whether a submitted query is still waiting on the worker.
+    pub fn is_searching(&self) -> bool {
+        self.pending
+    }
+}
+
+/// Holds back slow queries until the entry stops changing.
+///
+/// Typing a word quickly would otherwise search every intermediate prefix.
+#[derive(Debug, Clone, Copy)]
+pub struct Debounce {
+    delay: f64,
+    changed_at: Option<f64>,
+}
+
+impl Debounce {
+    /// Creates a debounce that waits `delay` seconds after the last change.
+    pub fn new(delay: f64) -> Self {
+        Self {
+            delay,
+            changed_at: None,
+        }
+    }
+    /// Notes that the entry changed at `now`, restarting the wait.
+    pub fn touch(&mut self, now: f64) {
+        self.changed_at = Some(now);
+    }
+    /// Drops any pending wait.
+    pub fn cancel(&mut self) {
+        self.changed_at = None;
+    }
+    /// Lets the query held back run at the next `ready`, without waiting.
+    pub fn skip(&mut self) {
+        self.changed_at = Some(f64::NEG_INFINITY);
+    }
+    /// Returns `true`, once, when the entry has been stable for the delay.
+    pub fn ready(&mut self, now: f64) -> bool {
+        match self.changed_at {
+            Some(at) if now - at >= self.delay => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Returns whether a change is still waiting out the delay.
+    pub fn is_pending(&self) -> bool {
+        self.changed_at.is_some()
+    }
+}
+
+/// Starts a thread that answers requests, skipping any superseded while it worked.
+fn spawn_worker<S: Solver>(responses: Sender<QueryResponse>) -> Sender<QueryRequest<S>> {
+    let (tx, rx) = mpsc::channel::<QueryRequest<S>>();
+    std::thread::spawn(move || {
+        while let Ok(mut request) = rx.recv() {
+            while let Ok(newer) = rx.try_recv() {
+                request = newer;
+            }
+            let words = request.solver.solve(&request.query);
+            let response = QueryResponse {
+                ticket: request.ticket,
+                words,
+            };
+            if responses.send(response).is_err() {
+                break;
+            }
+        }
+    });
+    tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    #[cfg(feature = "ui")]
+    use crate::record::wall_clock;
+    #[cfg(feature = "ui")]
+    use crate::soak::Rng;
+    #[cfg(feature = "ui")]
+    use std::time::Duration;
+
+    const DELAY: f64 = 0.15;
+
+    #[test]
+    fn a_typing_burst_waits_for_the_last_change() {
+        let mut debounce = Debounce::new(DELAY);
+        for now in [0.0, 0.05, 0.1, 0.2] {
+            debounce.touch(now);
+            assert!(!debounce.ready(now));
+        }
+        assert!(debounce.is_pending());
+        assert!(!debounce.ready(0.3));
+        assert!(debounce.ready(0.36));
+        // Once only
+        assert!(!debounce.ready(0.5));
+        assert!(!debounce.is_pending());
+    }
+
+    #[test]
+    fn nothing_is_pending_until_a_change() {
+        let mut debounce = Debounce::new(DELAY);
+        assert!(!debounce.is_pending());
+        assert!(!debounce.ready(100.0));
+    }
+
+    #[test]
+    fn cancelling_drops_the_wait() {
+        let mut debounce = Debounce::new(DELAY);
+        debounce.touch(1.0);
+        debounce.cancel();
+        assert!(!debounce.is_pending());
+        assert!(!debounce.ready(2.0));
+    }
+
+    #[test]
+    fn a_zero_delay_is_ready_at_once() {
+        let mut debounce = Debounce::new(0.0);
+        debounce.touch(3.0);
+        assert!(debounce.ready(3.0));
+    }
+
+    /// Answers with the entry itself.
+    struct Echo;
+
+    impl Solver for Echo {
+        fn solve(&self, query: &QueryKind) -> Vec<String> {
+            match query {
+                QueryKind::Exact(entry) | QueryKind::Sub(entry) => vec![entry.to_owned()],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    /// A mock clock moving on a millisecond each time it is read, so each query
+    /// takes a millisecond. Per thread, so tests can't disturb each other.
+    fn quick_clock() -> f64 {
+        thread_local! {
+            static NOW: std::cell::Cell<f64> = const { std::cell::Cell::new(0.0) };
+        }
+        NOW.with(|now| {
+            now.set(now.get() + 0.001);
+            now.get()
+        })
+    }
+
+    /// As `quick_clock`, but each query takes ten milliseconds.
+    fn slow_clock() -> f64 {
+        thread_local! {
+            static NOW: std::cell::Cell<f64> = const { std::cell::Cell::new(0.0) };
+        }
+        NOW.with(|now| {
+            now.set(now.get() + 0.01);
+            now.get()
+        })
+    }
+
+    #[test]
+    fn quick_queries_stay_inline() {
+        let mut runner = QueryRunner::new(Arc::new(Echo), 0, quick_clock);
+        for entry in ["UPON", "POUND", "SOUND"] {
+            let words = runner.submit(QueryKind::Sub(entry.into()));
+            assert_eq!(words, Some(vec![entry.to_owned()]));
+        }
+        assert!(!runner.is_searching());
+    }
+
+    #[test]
+    fn queries_over_budget_move_to_the_worker() {
+        let mut runner = QueryRunner::new(Arc::new(Echo), 0, slow_clock);
+        assert!(runner.submit(QueryKind::Sub("UPON".into())).is_some());
+        assert_eq!(runner.submit(QueryKind::Sub("POUND".into())), None);
+        assert!(runner.is_searching());
+        let words = loop {
+            if let Some(words) = runner.poll() {
+                break words;
+            }
+            std::thread::yield_now();
+        };
+        assert_eq!(words, ["POUND"]);
+        assert!(!runner.is_searching());
+    }
+
+    #[test]
+    fn the_word_map_answers_each_kind_of_query() {
+        use crate::query::lang::parse_command;
+        use crate::wordmap::make_word_map_with_progress;
+        let map = make_word_map_with_progress(
+            "GREAT\nGRATE\nRATE\nTEAR\nGATE\nEAT\n",
+            Alphabet::English,
+            usize::MAX,
+            usize::MAX,
+            |_| (),
+        );
+        let solve = |text: &str| match text.starts_with(':') {
+            true => map.solve(&parse_command(text).unwrap()),
+            false => map.solve(&QueryKind::Exact(text.into())),
+        };
+        assert_eq!(solve("TAGER"), ["GREAT", "GRATE"]);
+        assert_eq!(
+            solve(":sub TAGER"),
+            ["GRATE", "GREAT", "GATE", "RATE", "TEAR"]
+        );
+        assert_eq!(solve(":pat G___E"), ["GRATE"]);
+        assert_eq!(solve(":bank TAGE min=3"), ["GATE", "EAT"]);
+        assert_eq!(solve(":phrase AAEEGTT 4 3"), ["GATE EAT"]);
+        assert_eq!(solve(":phrase AAEEGTT 5 2"), Vec::<String>::new());
+    }
+
+    /// A dictionary that answers slowly, naming itself and the entry asked.
+    // Only the test below uses it, which takes its entries from the soak run's
+    // generator and so needs the app
+    #[cfg(feature = "ui")]
+    struct SlowDictionary {
+        generation: u32,
+    }
+
+    #[cfg(feature = "ui")]
+    impl Solver for SlowDictionary {
+        fn solve(&self, query: &QueryKind) -> Vec<String> {
+            let QueryKind::Exact(entry) = query else {
+                return Vec::new();
+            };
+            std::thread::sleep(Duration::from_micros(300 * entry.len() as u64));
+            vec![format!("{}:{entry}", self.generation)]
+        }
+    }
+
+    #[cfg(feature = "ui")]
+    fn answer(generation: u32, entry: &str) -> Vec<String> {
+        vec![format!("{generation}:{entry}")]
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn matches_follow_the_last_entry_and_dictionary() {
+        for seed in 0..4 {
+            let mut rng = Rng::new(seed);
+            let mut generation = 0;
+            let mut runner =
+                QueryRunner::new(Arc::new(SlowDictionary { generation }), 0, wall_clock);
+            let mut entry = String::from("A");
+            let mut shown = None;
+            // A long entry answers over budget, sending later queries to the worker
+            if seed % 2 == 0 {
+                entry = "A".repeat(20);
+                shown = runner.submit(QueryKind::Exact(entry.clone()));
+            }
+            for _ in 0..150 {
+                match rng.below(5) {
+                    0 | 1 => {
+                        entry = "GERTA"[..1 + rng.below(5)].to_owned();
+                        if let Some(words) = runner.submit(QueryKind::Exact(entry.clone())) {
+                            shown = Some(words);
+                        }
+                    }
+                    // A reload, looking the entry up again as the app does
+                    // unless that's left to the query in flight
+                    2 => {
+                        generation += 1;
+                        runner.set_solver(Arc::new(SlowDictionary { generation }), generation);
+                        if !runner.is_searching() || rng.below(2) == 0 {
+                            if let Some(words) = runner.submit(QueryKind::Exact(entry.clone())) {
+                                shown = Some(words);
+                            }
+                        }
+                    }
+                    _ => std::thread::sleep(Duration::from_micros(rng.below(1000) as u64)),
+                }
+                if let Some(words) = runner.poll() {
+                    assert_eq!(words, answer(generation, &entry), "seed {seed}");
+                    shown = Some(words);
+                }
+            }
+            let deadline = wall_clock() + 5.0;
+            while runner.is_searching() && wall_clock() < deadline {
+                std::thread::sleep(Duration::from_millis(1));
+                if let Some(words) = runner.poll() {
+                    shown = Some(words);
+                }
+            }
+            assert!(!runner.is_searching(), "seed {seed}: still searching");
+            assert_eq!(shown, Some(answer(generation, &entry)), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Estimating how long a query command will take, before running it.
+//!
+//! Most queries scan the word list once, which is quick however many matches
+//! there are, but a `:phrase` tries every way of sharing its letters out among
+//! its words, and with many different letters that can run for seconds even
+//! on the worker. `estimate_cost` counts the steps a query may take; above
+//! `confirm_query_cost` in the config the entry asks for Enter before running
+//! it, and above `refuse_query_cost` it says what to change instead.
+
+use super::lang::QueryKind;
+use std::collections::HashMap;
+
+/// Steps counted for one pass over the word list: about the letter groups of a
+/// large one.
+pub const SCAN_COST: u64 = 100_000;
+
+/// Estimated steps above which a query waits for Enter, unless the config says.
+pub const DEFAULT_CONFIRM_COST: u64 = 1_000_000_000;
+/// Estimated steps above which a query is refused, unless the config says.
+pub const DEFAULT_REFUSE_COST: u64 = 1_000_000_000_000;
+
+/// A rough count of the steps a query takes: letter groups looked at, and for
+/// a phrase, the ways of sharing its letters out among its words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cost(pub u64);
+
+/// What to do with a query of a given cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    /// Run it as usual.
+    Run,
+    /// Wait for Enter before running it.
+    Confirm,
+    /// Don't run it.
+    Refuse,
+}
+
+/// The costs above which queries wait for Enter, and are refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostLimits {
+    pub confirm: u64,
+    pub refuse: u64,
+}
+
+impl CostLimits {
+    /// Returns what to do with a query costing `cost`.
+    pub fn gate(self, cost: Cost) -> Gate {
+        match cost.0 {
+            n if n > self.refuse => Gate::Refuse,
+            n if n > self.confirm => Gate::Confirm,
+            _ => Gate::Run,
+        }
+    }
+}
+
+/// Returns about how many steps `query` takes.
+pub fn estimate_cost(query: &QueryKind) -> Cost {
+    let steps = match query {
+        QueryKind::Exact(_) | QueryKind::Letters(_) => 1,
+        // One lookup for each letter left out
+        QueryKind::DropOne(letters) => letters.chars().count() as u64,
+        QueryKind::Sub(_) | QueryKind::Pattern(_) | QueryKind::Bank { .. } => SCAN_COST,
+        // A scan for each word's candidates, then the search among them
+        QueryKind::Phrase { letters, lengths } => {
+            (SCAN_COST * lengths.len() as u64).saturating_add(count_splits(letters, lengths))
+        }
+    };
+    Cost(steps)
+}
+
+/// Returns what to tell the user when `query` is refused: what to change so it
+/// isn't.
+pub fn refusal(query: &QueryKind) -> String {
+    match query {
+        QueryKind::Phrase { letters, lengths } => format!(
+            "too many ways to split {} letters into {} words; try fewer letters or fewer words",
+            letters.chars().count(),
+            lengths.len()
+        ),
+        _ => format!("this {} search is too big; try fewer letters", query.name()),
+    }
+}
+
+/// Ways to share out the letters left, by how many kinds of letter are left
+/// and the room left in each word.
+type Shares = HashMap<(usize, Vec<usize>), u64>;
+
+/// Returns the ways to share out the letters of `letters` among words of
+/// `lengths`, in order, filling each: how many sets of letter groups a phrase
+/// search may try. Repeated letters are told apart only by where they go.
+pub fn count_splits(letters: &str, lengths: &[usize]) -> u64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in letters.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let mut counts: Vec<usize> = counts.into_values().collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    share(&counts, lengths.to_vec(), &mut HashMap::new())
+}
+
+/// Returns the ways to share out letters with `counts` among words with `room`
+/// letters left each, filling them all.
+fn share(counts: &[usize], mut room: Vec<usize>, seen: &mut Shares) -> u64 {
+    let Some((&count, rest)) = counts.split_first() else {
+        return u64::from(room.iter().all(|&left| left == 0));
+    };
+    let key = (counts.len(), room.clone());
+    if let Some(&ways) = seen.get(&key) {
+        return ways;
+    }
+    let ways = place(count, 0, &mut room, rest, seen);
+    seen.insert(key, ways);
+    ways
+}
+
+/// Returns the ways to put `left` copies of a letter in the words from `word`
+/// on, then share out the letters with counts `rest`.
+fn place(
+    left: usize,
+    word: usize,
+    room: &mut Vec<usize>,
+    rest: &[usize],
+    seen: &mut Shares,
+) -> u64 {
+    if word == room.len() {
+        return match left {
+            0 => share(rest, room.clone(), seen),
+            _ => 0,
+        };
+    }
+    let mut ways = 0u64;
+    for n in 0..=left.min(room[word]) {
+        room[word] -= n;
+        ways = ways.saturating_add(place(left - n, word + 1, room, rest, seen));
+        room[word] += n;
+    }
+    ways
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::lang::parse_command;
+
+    fn cost(command: &str) -> Cost {
+        estimate_cost(&parse_command(command).unwrap())
+    }
+
+    #[test]
+    fn splits_count_each_way_to_fill_the_words() {
+        assert_eq!(count_splits("AB", &[1, 1]), 2);
+        assert_eq!(count_splits("ABCD", &[2, 2]), 6);
+        // A|AB and B|AA: repeated letters aren't told apart
+        assert_eq!(count_splits("AAB", &[1, 2]), 2);
+        assert_eq!(count_splits("ABC", &[3]), 1);
+        assert_eq!(count_splits("ABC", &[2, 2]), 0);
+        // 14! / (4! 4! 3! 3!)
+        assert_eq!(count_splits("ABCDEFGHIJKLMN", &[4, 4, 3, 3]), 4_204_200);
+    }
+
+    #[test]
+    fn each_kind_of_query_is_costed() {
+        assert_eq!(estimate_cost(&QueryKind::Exact("AEGRST".into())), Cost(1));
+        assert_eq!(estimate_cost(&QueryKind::DropOne("AEGRST".into())), Cost(6));
+        assert_eq!(cost(":letters -QXZ"), Cost(1));
+        assert_eq!(cost(":sub AEGRST"), Cost(SCAN_COST));
+        assert_eq!(cost(":pat G_E__"), Cost(SCAN_COST));
+        assert_eq!(cost(":bank AEGLNPR min=4"), Cost(SCAN_COST));
+        assert_eq!(cost(":phrase ABCD 2 2"), Cost(2 * SCAN_COST + 6));
+    }
+
+    #[test]
+    fn limits_gate_only_what_is_above_them() {
+        let limits = CostLimits {
+            confirm: 10,
+            refuse: 100,
+        };
+        assert_eq!(limits.gate(Cost(10)), Gate::Run);
+        assert_eq!(limits.gate(Cost(11)), Gate::Confirm);
+        assert_eq!(limits.gate(Cost(100)), Gate::Confirm);
+        assert_eq!(limits.gate(Cost(101)), Gate::Refuse);
+    }
+
+    #[test]
+    fn only_phrases_with_many_letters_are_gated_by_default() {
+        let limits = CostLimits {
+            confirm: DEFAULT_CONFIRM_COST,
+            refuse: DEFAULT_REFUSE_COST,
+        };
+        let gate = |command: &str| limits.gate(cost(command));
+        for cheap in [
+            ":sub ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            ":pat ______________________________",
+            ":bank ABCDEFGHIJKLMNOPQRSTUVWXYZ min=2",
+            ":phrase ABCDEFGHIJKLMN 4 4 3 3",
+        ] {
+            assert_eq!(gate(cheap), Gate::Run, "{cheap}");
+        }
+        assert_eq!(gate(":phrase ABCDEFGHIJKLMNOPQRST 5 5 5 5"), Gate::Confirm);
+        assert_eq!(
+            gate(":phrase ABCDEFGHIJKLMNOPQRSTUVWX 6 6 6 6"),
+            Gate::Refuse
+        );
+    }
+
+    #[test]
+    fn refusals_say_what_to_cut() {
+        let phrase = parse_command(":phrase ABCDEFGHIJKLMNOPQRSTUVWX 6 6 6 6").unwrap();
+        assert_eq!(
+            refusal(&phrase),
+            "too many ways to split 24 letters into 4 words; try fewer letters or fewer words"
+        );
+    }
+}
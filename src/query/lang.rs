@@ -0,0 +1,304 @@
+//! The entry's power mode: an entry starting with `:` is a query command
+//! rather than letters to unscramble.
+//!
+//! ```text
+//! :sub RETAINS          words using some of the letters
+//! :pat _O__ER           words fitting the blanks
+//! :bank AEGLNPR min=4   words using only these letters, any number of times
+//! :phrase AEILNST 3 4   phrases of words of these lengths using every letter
+//! :letters -QXZ         leave these letters out of every match (see `letters`)
+//! ```
+//!
+//! Commands and options may be typed in either case. `parse_command` turns one
+//! into a `QueryKind`, or says what's wrong with it.
+
+use crate::letters::LetterMask;
+use crate::parse::Pattern;
+use crate::query::QueryMode;
+use crate::wordmap::fold_letter;
+use crate::MIN_WORD_LENGTH;
+
+/// Starts a query command in the entry.
+pub const COMMAND_PREFIX: char = ':';
+/// Most characters a query command may have, prefix included.
+pub const MAX_COMMAND_LENGTH: usize = 40;
+/// Most words a `:phrase` may ask for.
+pub const MAX_PHRASE_WORDS: usize = 4;
+/// The command names, for error messages.
+const COMMANDS: &str = "sub, pat, bank, phrase, or letters";
+
+/// What to look up in the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryKind {
+    /// Words using exactly these letters.
+    Exact(String),
+    /// Words using some of these letters.
+    Sub(String),
+    /// Words using all but one of these letters; never typed, only escalated to
+    /// (see `escalate`).
+    DropOne(String),
+    /// Words fitting a blank pattern.
+    Pattern(Pattern),
+    /// Words of at least `min_len` letters using only these letters, each as
+    /// often as needed.
+    Bank { letters: String, min_len: usize },
+    /// Phrases of words with these lengths, in order, using exactly these
+    /// letters between them.
+    Phrase {
+        letters: String,
+        lengths: Vec<usize>,
+    },
+    /// Nothing to look up: leaves these letters out of the matches from now on.
+    Letters(LetterMask),
+}
+
+impl QueryKind {
+    /// Returns the query for plain letters typed in `mode`.
+    pub fn plain(mode: QueryMode, entry: &str) -> Self {
+        match mode {
+            QueryMode::Exact => QueryKind::Exact(entry.to_owned()),
+            QueryMode::SubAnagram => QueryKind::Sub(entry.to_owned()),
+        }
+    }
+    /// Returns the query's name, as typed after the prefix (`exact` for plain
+    /// letters).
+    pub fn name(&self) -> &'static str {
+        match self {
+            QueryKind::Exact(_) => "exact",
+            QueryKind::Sub(_) => "sub",
+            QueryKind::DropOne(_) => "drop-one",
+            QueryKind::Pattern(_) => "pat",
+            QueryKind::Bank { .. } => "bank",
+            QueryKind::Phrase { .. } => "phrase",
+            QueryKind::Letters(_) => "letters",
+        }
+    }
+}
+
+/// Returns whether `entry` is a query command rather than plain letters.
+pub fn is_command(entry: &str) -> bool {
+    entry.starts_with(COMMAND_PREFIX)
+}
+
+/// Returns whether `c` may be typed in a query command: letters (folded as in
+/// plain entries), digits, and the blanks, spaces, `=`, and `-` the commands
+/// use.
+pub fn is_command_char(c: char) -> bool {
+    fold_letter(c).is_some() || c.is_ascii_digit() || matches!(c, ' ' | '_' | '?' | '.' | '=' | '-')
+}
+
+/// Parses a query command such as `:bank AEGLNPR min=4`.
+///
+/// Errors say what's wrong in a form short enough to show under the entry.
+pub fn parse_command(text: &str) -> Result<QueryKind, String> {
+    let text = text
+        .strip_prefix(COMMAND_PREFIX)
+        .ok_or_else(|| format!("a command starts with '{COMMAND_PREFIX}'"))?;
+    let mut args = text.split_whitespace();
+    let Some(name) = args.next() else {
+        return Err(format!("type a command: {COMMANDS}"));
+    };
+    let args: Vec<&str> = args.collect();
+    let name = name.to_ascii_lowercase();
+    let kind = match name.as_str() {
+        "sub" => {
+            let [letters] = args[..] else {
+                return Err(usage(&name, "RETAINS"));
+            };
+            QueryKind::Sub(parse_letters(letters)?)
+        }
+        "pat" => {
+            let [pattern] = args[..] else {
+                return Err(usage(&name, "_O__ER"));
+            };
+            let parsed = Pattern::parse(pattern)
+                .ok_or_else(|| format!("'{pattern}' isn't a pattern like _O__ER"))?;
+            QueryKind::Pattern(parsed)
+        }
+        "bank" => {
+            let Some((letters, options)) = args.split_first() else {
+                return Err(usage(&name, "AEGLNPR min=4"));
+            };
+            let mut min_len = MIN_WORD_LENGTH;
+            for option in options {
+                let value = option
+                    .split_once('=')
+                    .filter(|(key, _)| key.eq_ignore_ascii_case("min"))
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| format!("unknown option '{option}'; bank takes min=N"))?;
+                min_len = parse_number(value, "min")?;
+            }
+            let mut letters = parse_letters(letters)?.into_bytes();
+            letters.sort_unstable();
+            letters.dedup();
+            let letters = String::from_utf8(letters).expect("letters are ASCII");
+            QueryKind::Bank { letters, min_len }
+        }
+        "phrase" => {
+            let Some((letters, lengths)) = args.split_first().filter(|(_, l)| !l.is_empty()) else {
+                return Err(usage(&name, "AEILNST 3 4"));
+            };
+            let letters = parse_letters(letters)?;
+            if lengths.len() > MAX_PHRASE_WORDS {
+                return Err(format!("a phrase has at most {MAX_PHRASE_WORDS} words"));
+            }
+            let lengths = lengths
+                .iter()
+                .map(|length| match parse_number(length, "a word length")? {
+                    0 => Err("a word length can't be 0".to_owned()),
+                    n => Ok(n),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let total: usize = lengths.iter().sum();
+            if total != letters.len() {
+                let count = letters.len();
+                return Err(format!(
+                    "the lengths add up to {total}, but there are {count} letters"
+                ));
+            }
+            QueryKind::Phrase { letters, lengths }
+        }
+        "letters" => match args[..] {
+            [] => QueryKind::Letters(LetterMask::default()),
+            [letters] => QueryKind::Letters(LetterMask::parse(letters)?),
+            _ => return Err(usage(&name, "-QXZ")),
+        },
+        _ => return Err(format!("unknown command '{name}'; try {COMMANDS}")),
+    };
+    Ok(kind)
+}
+
+/// Returns the error for `name` given the wrong arguments: an example of the
+/// right ones.
+fn usage(name: &str, example: &str) -> String {
+    format!("usage: :{name} {example}")
+}
+
+/// Returns `text` as uppercase letters without accents, or an error if it has
+/// anything else.
+fn parse_letters(text: &str) -> Result<String, String> {
+    text.chars()
+        .map(fold_letter)
+        .collect::<Option<String>>()
+        .ok_or_else(|| format!("'{text}' isn't all letters"))
+}
+
+/// Returns `text` as a whole number, naming it `what` if it isn't one.
+fn parse_number(text: &str, what: &str) -> Result<usize, String> {
+    text.parse()
+        .map_err(|_| format!("{what} must be a whole number, not '{text}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters(kind: &str, text: &str) -> QueryKind {
+        match kind {
+            "sub" => QueryKind::Sub(text.into()),
+            _ => QueryKind::Exact(text.into()),
+        }
+    }
+
+    #[test]
+    fn each_command_parses_in_either_case() {
+        assert_eq!(parse_command(":sub retains"), Ok(letters("sub", "RETAINS")));
+        assert_eq!(parse_command(":SUB Élève"), Ok(letters("sub", "ELEVE")));
+        assert_eq!(
+            parse_command(":pat _o__er"),
+            Ok(QueryKind::Pattern(Pattern::parse("_O__ER").unwrap()))
+        );
+        assert_eq!(
+            parse_command(":bank RAPNGLEA MIN=4"),
+            Ok(QueryKind::Bank {
+                letters: "AEGLNPR".into(),
+                min_len: 4
+            })
+        );
+        assert_eq!(
+            parse_command(":Phrase aeilnst 3 4"),
+            Ok(QueryKind::Phrase {
+                letters: "AEILNST".into(),
+                lengths: vec![3, 4]
+            })
+        );
+    }
+
+    #[test]
+    fn a_bank_without_options_takes_the_shortest_word() {
+        assert_eq!(
+            parse_command(":bank  ab  "),
+            Ok(QueryKind::Bank {
+                letters: "AB".into(),
+                min_len: MIN_WORD_LENGTH
+            })
+        );
+    }
+
+    #[test]
+    fn errors_say_what_to_type() {
+        let error = |text: &str| parse_command(text).unwrap_err();
+        assert_eq!(error("sub X"), "a command starts with ':'");
+        assert_eq!(
+            error(":"),
+            "type a command: sub, pat, bank, phrase, or letters"
+        );
+        assert_eq!(
+            error(":solve X"),
+            "unknown command 'solve'; try sub, pat, bank, phrase, or letters"
+        );
+        assert_eq!(error(":sub"), "usage: :sub RETAINS");
+        assert_eq!(error(":sub A B"), "usage: :sub RETAINS");
+        assert_eq!(error(":sub A1"), "'A1' isn't all letters");
+        assert_eq!(error(":pat ABC"), "'ABC' isn't a pattern like _O__ER");
+        assert_eq!(error(":bank"), "usage: :bank AEGLNPR min=4");
+        assert_eq!(
+            error(":bank ABC max=4"),
+            "unknown option 'max=4'; bank takes min=N"
+        );
+        assert_eq!(
+            error(":bank ABC min=four"),
+            "min must be a whole number, not 'four'"
+        );
+        assert_eq!(error(":phrase AEILNST"), "usage: :phrase AEILNST 3 4");
+        assert_eq!(
+            error(":phrase ABCDE 1 1 1 1 1"),
+            "a phrase has at most 4 words"
+        );
+        assert_eq!(error(":phrase ABC 0 3"), "a word length can't be 0");
+        assert_eq!(
+            error(":phrase AEILNST 3 3"),
+            "the lengths add up to 6, but there are 7 letters"
+        );
+        assert_eq!(
+            error(":letters QXZ"),
+            "'QXZ' should be '-' and the letters to leave out"
+        );
+        assert_eq!(error(":letters -Q1"), "'Q1' isn't all letters");
+        assert_eq!(error(":letters -Q -X"), "usage: :letters -QXZ");
+    }
+
+    #[test]
+    fn letters_leaves_some_out_or_brings_them_back() {
+        assert_eq!(
+            parse_command(":letters -zqxq"),
+            Ok(QueryKind::Letters(LetterMask::new("QXZ")))
+        );
+        assert_eq!(
+            parse_command(":LETTERS"),
+            Ok(QueryKind::Letters(LetterMask::default()))
+        );
+    }
+
+    #[test]
+    fn plain_letters_follow_the_mode() {
+        let exact = QueryKind::plain(QueryMode::Exact, "TLAE");
+        assert_eq!((exact.name(), exact), ("exact", letters("exact", "TLAE")));
+        let sub = QueryKind::plain(QueryMode::SubAnagram, "TLAE");
+        assert_eq!((sub.name(), sub), ("sub", letters("sub", "TLAE")));
+        assert!(is_command(":sub"));
+        assert!(!is_command("SUB:"));
+        assert!("é9 _?.=-".chars().all(is_command_char));
+        assert!(!",!".chars().any(is_command_char));
+    }
+}
@@ -0,0 +1,386 @@
+//! Ranking a scramble's matches, and showing why they rank as they do.
+//!
+//! When a scramble has several matches, big mode shows the top-ranked one. Each
+//! match scores points for how common it is (from the word list's frequency
+//! column, if it has one), for being pinned, and for being confirmed as seen in
+//! print (see `confirmed`), loses some for being another
+//! word with an ending added (`PLAYED`, `TRAINS`), and can score its Scrabble
+//! points. The weights are in the `[ranking]` table of the config, and in the
+//! settings, which preview the entry's matches in ranked order as they change.
+//!
+//! Alt+I, or tapping the answer, opens a table of the matches in ranked order
+//! with each part of their scores, for checking the ranking and tuning weights.
+//! The entry screen draws it from `ranking_rows`; nothing here needs the window.
+
+use crate::strings::{Language, StringKey};
+use crate::table::Align;
+use crate::wordmap::WordMap;
+use serde::{Deserialize, Serialize};
+
+/// Most matches listed in the ranking table.
+pub const MAX_RANKED_ROWS: usize = 10;
+
+/// Endings that make one word from another, longest first so the longest
+/// that fits is tried first.
+const INFLECTIONS: [&str; 8] = ["ING", "EST", "ED", "ER", "ES", "S", "D", "R"];
+
+/// Each letter's Scrabble points, `A` first.
+const SCRABBLE_POINTS: [u8; 26] = [
+    1, 3, 3, 2, 1, 4, 2, 4, 1, 8, 5, 1, 3, 1, 1, 3, 10, 1, 1, 1, 1, 4, 4, 8, 4, 10,
+];
+
+/// How much each part of a match's score counts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankWeights {
+    /// Points per power of ten of how often the word is used.
+    pub frequency: f32,
+    /// Points for a pinned word.
+    pub pinned: f32,
+    /// Points for a word confirmed as seen in print.
+    pub confirmed: f32,
+    /// Points taken off a word that's another word with an ending added.
+    pub inflection: f32,
+    /// Points per Scrabble point; 0 leaves Scrabble points out.
+    pub scrabble: f32,
+}
+
+impl RankWeights {
+    /// The largest each weight may be; none may be less than 0.
+    pub const MAX: RankWeights = RankWeights {
+        frequency: 5.0,
+        pinned: 20.0,
+        confirmed: 20.0,
+        inflection: 10.0,
+        scrabble: 2.0,
+    };
+
+    /// Returns the weights with each clamped to `0..=MAX`, and any that isn't a
+    /// number at 0.
+    pub fn clamped(self) -> Self {
+        let clamp = |w: f32, max: f32| if w.is_nan() { 0.0 } else { w.clamp(0.0, max) };
+        let max = Self::MAX;
+        Self {
+            frequency: clamp(self.frequency, max.frequency),
+            pinned: clamp(self.pinned, max.pinned),
+            confirmed: clamp(self.confirmed, max.confirmed),
+            inflection: clamp(self.inflection, max.inflection),
+            scrabble: clamp(self.scrabble, max.scrabble),
+        }
+    }
+}
+
+impl Default for RankWeights {
+    fn default() -> Self {
+        Self {
+            frequency: 1.0,
+            pinned: 5.0,
+            confirmed: 3.0,
+            inflection: 2.0,
+            scrabble: 0.0,
+        }
+    }
+}
+
+/// The parts of a match's score, weighted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBreakdown {
+    pub frequency: f32,
+    pub pinned: f32,
+    pub confirmed: f32,
+    /// Zero or less.
+    pub inflection: f32,
+    /// `None` when Scrabble points are left out.
+    pub scrabble: Option<f32>,
+}
+
+impl ScoreBreakdown {
+    /// Returns the sum of the parts.
+    pub fn total(&self) -> f32 {
+        self.frequency
+            + self.pinned
+            + self.confirmed
+            + self.inflection
+            + self.scrabble.unwrap_or(0.0)
+    }
+}
+
+/// A match and its score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ranked {
+    /// The match's index among the matches.
+    pub index: usize,
+    pub score: ScoreBreakdown,
+}
+
+/// Returns `word`'s Scrabble points; letters outside `A` to `Z` score nothing.
+pub fn scrabble_points(word: &str) -> u32 {
+    word.chars()
+        .filter(char::is_ascii_uppercase)
+        .map(|c| SCRABBLE_POINTS[(c as u8 - b'A') as usize] as u32)
+        .sum()
+}
+
+/// Returns whether `word` is a word in `dict` with one of `INFLECTIONS` added,
+/// allowing for a dropped final `E` (`BAKING`, `BAKED`).
+pub fn is_inflected(word: &str, dict: &WordMap) -> bool {
+    INFLECTIONS.iter().any(|ending| {
+        word.strip_suffix(ending)
+            .filter(|stem| stem.len() >= 3)
+            .is_some_and(|stem| dict.contains(stem) || dict.contains(&format!("{stem}E")))
+    })
+}
+
+/// Scores `word` from `dict`, given whether it's pinned and whether it's
+/// confirmed, with `weights` clamped to their range.
+pub fn score(
+    word: &str,
+    dict: &WordMap,
+    pinned: bool,
+    confirmed: bool,
+    weights: &RankWeights,
+) -> ScoreBreakdown {
+    let weights = weights.clamped();
+    let frequency = dict.frequency(word).unwrap_or(0);
+    ScoreBreakdown {
+        frequency: weights.frequency * (frequency as f32 + 1.0).log10(),
+        pinned: if pinned { weights.pinned } else { 0.0 },
+        confirmed: if confirmed { weights.confirmed } else { 0.0 },
+        inflection: match is_inflected(word, dict) {
+            true => -weights.inflection,
+            false => 0.0,
+        },
+        scrabble: (weights.scrabble != 0.0)
+            .then(|| weights.scrabble * scrabble_points(word) as f32),
+    }
+}
+
+/// Scores each of `words` and returns them best first; ties keep their order.
+pub fn rank(
+    words: &[String],
+    dict: &WordMap,
+    is_pinned: impl Fn(&str) -> bool,
+    is_confirmed: impl Fn(&str) -> bool,
+    weights: &RankWeights,
+) -> Vec<Ranked> {
+    let mut ranked: Vec<Ranked> = words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| Ranked {
+            index,
+            score: score(word, dict, is_pinned(word), is_confirmed(word), weights),
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total().total_cmp(&a.score.total()));
+    ranked
+}
+
+/// Returns the ranking table's rows, headings first, for the first
+/// `MAX_RANKED_ROWS` of `ranked` among `words`, and how each column aligns. The
+/// Scrabble column is left out when Scrabble points are.
+pub fn ranking_rows(
+    ranked: &[Ranked],
+    words: &[String],
+    lang: Language,
+) -> (Vec<Vec<String>>, Vec<Align>) {
+    let scrabble = ranked.first().is_some_and(|r| r.score.scrabble.is_some());
+    let mut headings = vec![
+        StringKey::RankWord,
+        StringKey::RankFrequency,
+        StringKey::RankPinned,
+        StringKey::RankConfirmed,
+        StringKey::RankInflection,
+    ];
+    if scrabble {
+        headings.push(StringKey::RankScrabble);
+    }
+    headings.push(StringKey::RankTotal);
+
+    let number = |n: f32| format!("{n:.1}");
+    let mut rows = vec![headings
+        .iter()
+        .map(|&key| lang.text(key).to_owned())
+        .collect()];
+    rows.extend(ranked.iter().take(MAX_RANKED_ROWS).map(|r| {
+        let s = &r.score;
+        let mut row = vec![
+            words[r.index].clone(),
+            number(s.frequency),
+            number(s.pinned),
+            number(s.confirmed),
+            number(s.inflection),
+        ];
+        row.extend(s.scrabble.map(number));
+        row.push(number(s.total()));
+        row
+    }));
+    let mut aligns = vec![Align::Left];
+    aligns.resize(headings.len(), Align::Right);
+    (rows, aligns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::wordmap::make_word_map_with_progress;
+    use std::collections::HashMap;
+
+    fn dict(words: &str, frequencies: &[(&str, u64)]) -> WordMap {
+        let mut dict = make_word_map_with_progress(words, Alphabet::English, usize::MAX, 1, |_| ());
+        let counts = frequencies.iter().map(|&(w, n)| (w.to_owned(), n));
+        dict.set_frequencies(counts.collect::<HashMap<_, _>>());
+        dict
+    }
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&w| w.to_owned()).collect()
+    }
+
+    #[test]
+    fn endings_added_to_a_word_are_inflections() {
+        let dict = dict("PLAY\nPLAYED\nBAKE\nBAKED\nBAKING\nSEED\nREED\n", &[]);
+        assert!(is_inflected("PLAYED", &dict));
+        assert!(is_inflected("BAKING", &dict));
+        assert!(is_inflected("BAKED", &dict));
+        assert!(!is_inflected("PLAY", &dict));
+        // "RE" is too short a stem to count
+        assert!(!is_inflected("REED", &dict));
+    }
+
+    #[test]
+    fn the_breakdown_weighs_each_part() {
+        let dict = dict("PLAY\nPLAYED\n", &[("PLAY", 999)]);
+        let weights = RankWeights::default();
+        let play = score("PLAY", &dict, false, false, &weights);
+        assert_eq!(play.frequency, 3.0);
+        assert_eq!(
+            (play.pinned, play.inflection, play.scrabble),
+            (0.0, 0.0, None)
+        );
+        let played = score("PLAYED", &dict, true, false, &weights);
+        assert_eq!(played.frequency, 0.0);
+        assert_eq!(played.pinned, 5.0);
+        assert_eq!(played.inflection, -2.0);
+        assert_eq!(played.total(), 3.0);
+
+        let scrabble = RankWeights {
+            scrabble: 0.5,
+            ..weights
+        };
+        assert_eq!(scrabble_points("PLAY"), 9);
+        assert_eq!(
+            score("PLAY", &dict, false, false, &scrabble).scrabble,
+            Some(4.5)
+        );
+    }
+
+    #[test]
+    fn ranking_is_best_first_and_ties_keep_their_order() {
+        let dict = dict("TEAS\nSEAT\nEATS\nEAST\n", &[("EAST", 99), ("EATS", 9)]);
+        let list = words(&["TEAS", "SEAT", "EATS", "EAST"]);
+        let ranked = rank(
+            &list,
+            &dict,
+            |w| w == "SEAT",
+            |_| false,
+            &RankWeights::default(),
+        );
+        let order: Vec<&str> = ranked.iter().map(|r| list[r.index].as_str()).collect();
+        assert_eq!(order, ["SEAT", "EAST", "EATS", "TEAS"]);
+    }
+
+    #[test]
+    fn rows_have_a_column_per_part_shown() {
+        let dict = dict("PLAY\n", &[("PLAY", 9)]);
+        let list = words(&["PLAY"]);
+        let ranked = rank(&list, &dict, |_| false, |_| false, &RankWeights::default());
+        let (rows, aligns) = ranking_rows(&ranked, &list, Language::En);
+        assert_eq!(rows[1], ["PLAY", "1.0", "0.0", "0.0", "0.0", "1.0"]);
+        assert_eq!(rows[0].len(), 6);
+        assert_eq!(aligns[..2], [Align::Left, Align::Right]);
+
+        let weights = RankWeights {
+            scrabble: 1.0,
+            ..RankWeights::default()
+        };
+        let ranked = rank(&list, &dict, |_| false, |_| false, &weights);
+        let (rows, aligns) = ranking_rows(&ranked, &list, Language::En);
+        assert_eq!(rows[1], ["PLAY", "1.0", "0.0", "0.0", "0.0", "9.0", "10.0"]);
+        assert_eq!(aligns.len(), 7);
+    }
+
+    #[test]
+    fn changing_one_weight_reorders_the_matches() {
+        let dict = dict(
+            "PEAR\nPEARS\nSPEAR\nZEBRA\nZEBRAS\nBAKE\nBAKED\n",
+            &[("PEARS", 9), ("SPEAR", 99), ("BAKED", 999)],
+        );
+        let list = words(&["PEARS", "SPEAR", "ZEBRAS", "BAKED"]);
+        let order = |weights: RankWeights, pinned: &str| -> Vec<&str> {
+            let ranked = rank(&list, &dict, |w| w == pinned, |_| false, &weights);
+            ranked.iter().map(|r| list[r.index].as_str()).collect()
+        };
+        let weights = RankWeights::default();
+        assert_eq!(order(weights, ""), ["SPEAR", "BAKED", "PEARS", "ZEBRAS"]);
+        let no_inflection = RankWeights {
+            inflection: 0.0,
+            ..weights
+        };
+        assert_eq!(
+            order(no_inflection, ""),
+            ["BAKED", "SPEAR", "PEARS", "ZEBRAS"]
+        );
+        let scrabble = RankWeights {
+            scrabble: 1.0,
+            ..weights
+        };
+        assert_eq!(order(scrabble, ""), ["ZEBRAS", "BAKED", "SPEAR", "PEARS"]);
+        let no_frequency = RankWeights {
+            frequency: 0.0,
+            ..weights
+        };
+        assert_eq!(
+            order(no_frequency, ""),
+            ["SPEAR", "PEARS", "ZEBRAS", "BAKED"]
+        );
+        assert_eq!(
+            order(weights, "ZEBRAS"),
+            ["ZEBRAS", "SPEAR", "BAKED", "PEARS"]
+        );
+    }
+
+    #[test]
+    fn weights_are_clamped_to_their_range() {
+        let wild = RankWeights {
+            frequency: f32::NAN,
+            pinned: -3.0,
+            confirmed: 25.0,
+            inflection: 1000.0,
+            scrabble: 1.5,
+        };
+        let clamped = wild.clamped();
+        assert_eq!(clamped.frequency, 0.0);
+        assert_eq!(clamped.pinned, 0.0);
+        assert_eq!(clamped.confirmed, RankWeights::MAX.confirmed);
+        assert_eq!(clamped.inflection, RankWeights::MAX.inflection);
+        assert_eq!(clamped.scrabble, 1.5);
+        let dict = dict("PLAY\nPLAYED\n", &[]);
+        assert_eq!(
+            score("PLAYED", &dict, false, false, &wild).inflection,
+            -10.0
+        );
+    }
+
+    #[test]
+    fn confirmed_words_get_their_bonus() {
+        let dict = dict("TEAS\nSEAT\nEAST\n", &[("EAST", 99)]);
+        let weights = RankWeights::default();
+        assert_eq!(score("SEAT", &dict, false, true, &weights).confirmed, 3.0);
+        assert_eq!(score("SEAT", &dict, false, false, &weights).confirmed, 0.0);
+        let list = words(&["TEAS", "SEAT", "EAST"]);
+        let ranked = rank(&list, &dict, |_| false, |w| w == "SEAT", &weights);
+        let order: Vec<&str> = ranked.iter().map(|r| list[r.index].as_str()).collect();
+        assert_eq!(order, ["SEAT", "EAST", "TEAS"]);
+    }
+}
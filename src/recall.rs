@@ -0,0 +1,139 @@
+//! Earlier entries, called back with Up and Down as in a shell.
+//!
+//! An entry is remembered when it's cleared or turns up a unique answer. Up steps
+//! back through them, most recent first, and Down forward again, to the entry
+//! being typed before Up was first pressed. Typing anything stops stepping and
+//! leaves the called-back entry to edit. Only this session's entries are kept;
+//! the answers found are kept for good in `history`.
+
+use crate::memory::MemorySize;
+use std::collections::VecDeque;
+
+/// Most entries remembered; the oldest is forgotten to make room.
+pub const RECALL_CAPACITY: usize = 50;
+
+/// The remembered entries, and where Up and Down have got to in them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Recall {
+    /// Distinct entries, most recent first.
+    entries: VecDeque<String>,
+    /// Index into `entries` of the one called back, while stepping.
+    cursor: Option<usize>,
+    /// The entry as it was before stepping began, for Down to return to.
+    draft: String,
+}
+
+impl Recall {
+    /// Remembers `entry` as the most recent, moving it to the front if it's
+    /// already remembered. Empty entries aren't remembered, and nor is the entry
+    /// called back, so stepping carries on from it.
+    pub fn remember(&mut self, entry: &str) {
+        let called_back = self.cursor.is_some_and(|i| self.entries[i] == entry);
+        if entry.is_empty() || called_back {
+            return;
+        }
+        self.entries.retain(|e| e != entry);
+        self.entries.push_front(entry.to_owned());
+        self.entries.truncate(RECALL_CAPACITY);
+        // The indexes have moved under the cursor
+        self.cursor = None;
+    }
+    /// Steps back to the next older entry different from `current`, the entry
+    /// as it stands, returning it; or `None` if there's none older.
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let start = match self.cursor {
+            Some(i) => i + 1,
+            None => 0,
+        };
+        let found = (start..self.entries.len()).find(|&i| self.entries[i] != current)?;
+        if self.cursor.is_none() {
+            self.draft = current.to_owned();
+        }
+        self.cursor = Some(found);
+        Some(&self.entries[found])
+    }
+    /// Steps forward to the next newer entry, or past the newest back to the entry
+    /// being typed before stepping began, returning it; or `None` if not stepping.
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.cursor? {
+            0 => {
+                self.cursor = None;
+                Some(&self.draft)
+            }
+            i => {
+                self.cursor = Some(i - 1);
+                Some(&self.entries[i - 1])
+            }
+        }
+    }
+    /// Stops stepping, leaving the entry as it is.
+    pub fn stop(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+    }
+}
+
+impl MemorySize for Recall {
+    fn memory_size(&self) -> usize {
+        self.entries.memory_size() + self.draft.memory_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recall_of(entries: &[&str]) -> Recall {
+        let mut recall = Recall::default();
+        for entry in entries {
+            recall.remember(entry);
+        }
+        recall
+    }
+
+    #[test]
+    fn up_steps_back_and_down_returns_to_the_draft() {
+        let mut recall = recall_of(&["NUPO", "TAGER", "DUNOP"]);
+        assert_eq!(recall.newer(), None);
+        assert_eq!(recall.older("GR"), Some("DUNOP"));
+        assert_eq!(recall.older("DUNOP"), Some("TAGER"));
+        assert_eq!(recall.older("TAGER"), Some("NUPO"));
+        assert_eq!(recall.older("NUPO"), None);
+        assert_eq!(recall.newer(), Some("TAGER"));
+        assert_eq!(recall.newer(), Some("DUNOP"));
+        assert_eq!(recall.newer(), Some("GR"));
+        assert_eq!(recall.newer(), None);
+    }
+
+    #[test]
+    fn entries_are_kept_once_most_recent_first() {
+        let mut recall = recall_of(&["NUPO", "", "TAGER", "NUPO"]);
+        assert_eq!(recall.entries, ["NUPO", "TAGER"]);
+        // Up skips the entry already showing
+        assert_eq!(recall.older("NUPO"), Some("TAGER"));
+    }
+
+    #[test]
+    fn the_oldest_is_forgotten_past_capacity() {
+        let mut recall = Recall::default();
+        for i in 0..RECALL_CAPACITY + 5 {
+            recall.remember(&format!("E{i}"));
+        }
+        assert_eq!(recall.entries.len(), RECALL_CAPACITY);
+        assert_eq!(recall.entries.front().map(String::as_str), Some("E54"));
+        assert_eq!(recall.entries.back().map(String::as_str), Some("E5"));
+    }
+
+    #[test]
+    fn remembering_the_called_back_entry_carries_on_from_it() {
+        let mut recall = recall_of(&["NUPO", "TAGER"]);
+        assert_eq!(recall.older(""), Some("TAGER"));
+        recall.remember("TAGER");
+        assert_eq!(recall.older("TAGER"), Some("NUPO"));
+        // Anything else starts again from the newest
+        recall.remember("SOUND");
+        assert_eq!(recall.older(""), Some("SOUND"));
+        recall.stop();
+        assert_eq!(recall.newer(), None);
+    }
+}
@@ -0,0 +1,661 @@
+//! Recording a session's events to a file, and replaying them.
+//!
+//! `--record <PATH>` appends every `AppEvent` to `PATH` as it's handled, one per
+//! line, with the update time and the dictionary generation:
+//!
+//! ```text
+//! # jumble_helper events v1
+//! entry TEAC
+//! 0.000 0 size 800 600
+//! 1.250 1 letter A
+//! 1.412 1 action delete_back
+//! ```
+//!
+//! The optional `entry` line is the entry saved by the previous session, which
+//! the recorded one started with. The window size is only written when it
+//! changes.
+//!
+//! `--replay <PATH>` feeds a recording back through `App::handle_event` without a
+//! window, printing the state after each event; add `--visual` to watch it
+//! instead, optionally sped up with `--speed`.
+
+use crate::app::{App, AppState, ENTRY_FORMAT, ENTRY_KEY};
+use crate::config::Config;
+use crate::events::{AppEvent, Nav};
+use crate::keymap::{Action, Keymap};
+use crate::modal::QuitChoice;
+use crate::palette::PaletteKey;
+use crate::pointer::PointerEvent;
+use crate::render::fixed_measure;
+use crate::screen::Screen;
+use crate::sound::Sounds;
+use crate::storage::{MemoryStorage, Storage};
+use macroquad::prelude::*;
+use std::io::{LineWriter, Write};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// First line of every recording.
+const HEADER: &str = "# jumble_helper events v1";
+
+/// Longest a replay waits for the dictionary to load.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Longest step a replay advances time by, so animations see frame-sized steps.
+pub const MAX_STEP: f64 = 1.0 / 60.0;
+
+/// One recorded event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    /// `AppContext::now` when the event was handled.
+    pub time: f64,
+    /// `AppContext::dictionary_generation` when the event was handled.
+    pub generation: u32,
+    pub event: AppEvent,
+}
+
+/// A parsed recording.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// The entry the session started with, restored from the session before.
+    pub saved_entry: String,
+    pub events: Vec<Recorded>,
+}
+
+impl Recording {
+    /// Parses a recording, reporting the first bad line.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, HEADER)) => (),
+            _ => return Err(format!("not a recording: the first line isn't '{HEADER}'")),
+        }
+        let mut recording = Recording::default();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(entry) = line.strip_prefix("entry ") {
+                recording.saved_entry = entry.to_owned();
+                continue;
+            }
+            let recorded = parse_recorded(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+            recording.events.push(recorded);
+        }
+        Ok(recording)
+    }
+}
+
+/// Parses `<time> <generation> <event>`.
+fn parse_recorded(line: &str) -> Result<Recorded, String> {
+    let mut parts = line.splitn(3, ' ');
+    let (Some(time), Some(generation), Some(event)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!(
+            "expected '<time> <generation> <event>', got '{line}'"
+        ));
+    };
+    Ok(Recorded {
+        time: time.parse().map_err(|_| format!("bad time '{time}'"))?,
+        generation: generation
+            .parse()
+            .map_err(|_| format!("bad generation '{generation}'"))?,
+        event: parse_event(event)?,
+    })
+}
+
+/// Writes `event` as a line of a recording, without the time and generation.
+pub fn format_event(event: &AppEvent) -> String {
+    match *event {
+        AppEvent::Paste(ref text) => format!("paste {}", escape_text(text)),
+        AppEvent::WindowSize(size) => format!("size {} {}", size.x, size.y),
+        AppEvent::DpiScale(dpi) => format!("dpi {dpi}"),
+        AppEvent::CloseRequested => "close".into(),
+        AppEvent::KeyActivity => "keys".into(),
+        AppEvent::Action(action) => format!("action {}", action.name()),
+        AppEvent::ActionReleased(action) => format!("released {}", action.name()),
+        AppEvent::Letter(c) => format!("letter {}", escape_text(&c.to_string())),
+        AppEvent::QuitChoice(choice) => format!("quit_choice {}", quit_choice_name(choice)),
+        AppEvent::Pointer(PointerEvent::Pressed(pos)) => format!("press {} {}", pos.x, pos.y),
+        AppEvent::Pointer(PointerEvent::Moved(pos)) => format!("move {} {}", pos.x, pos.y),
+        AppEvent::Pointer(PointerEvent::Released(pos)) => format!("release {} {}", pos.x, pos.y),
+        AppEvent::Touch => "touch".into(),
+        AppEvent::Wheel(y) => format!("wheel {y}"),
+        AppEvent::Nav(nav) => format!("nav {}", nav_name(nav)),
+        AppEvent::Dismiss => "dismiss".into(),
+        AppEvent::PaletteKey(key) => format!("palette {}", palette_key_name(key)),
+    }
+}
+
+/// Returns `text` with no whitespace, so it fits on a recording's line as one
+/// word: spaces become `\s`, newlines `\n`, other whitespace `\u{...}`, and
+/// backslashes `\\`.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ' ' => escaped.push_str("\\s"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_whitespace() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverses `escape_text`.
+fn unescape_text(text: &str) -> Result<String, String> {
+    let bad = || format!("bad escape in '{text}'");
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next().ok_or_else(bad)? {
+            '\\' => unescaped.push('\\'),
+            's' => unescaped.push(' '),
+            'n' => unescaped.push('\n'),
+            'u' => {
+                let rest = chars.as_str();
+                let hex = rest.strip_prefix('{').and_then(|r| r.split_once('}'));
+                let (hex, after) = hex.ok_or_else(bad)?;
+                let c = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+                unescaped.push(c.ok_or_else(bad)?);
+                chars = after.chars();
+            }
+            _ => return Err(bad()),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Parses an event written by `format_event`.
+pub fn parse_event(text: &str) -> Result<AppEvent, String> {
+    let mut words = text.split_whitespace();
+    let kind = words.next().unwrap_or_default();
+    let mut arg = || words.next().ok_or(format!("'{kind}' is missing a value"));
+    let mut number = || -> Result<f32, String> {
+        let value = arg()?;
+        value
+            .parse()
+            .map_err(|_| format!("'{value}' is not a number"))
+    };
+    let event = match kind {
+        "size" => AppEvent::WindowSize(vec2(number()?, number()?)),
+        "dpi" => AppEvent::DpiScale(number()?),
+        "close" => AppEvent::CloseRequested,
+        "keys" => AppEvent::KeyActivity,
+        "touch" => AppEvent::Touch,
+        "dismiss" => AppEvent::Dismiss,
+        "press" => AppEvent::Pointer(PointerEvent::Pressed(vec2(number()?, number()?))),
+        "move" => AppEvent::Pointer(PointerEvent::Moved(vec2(number()?, number()?))),
+        "release" => AppEvent::Pointer(PointerEvent::Released(vec2(number()?, number()?))),
+        "wheel" => AppEvent::Wheel(number()?),
+        _ => {
+            let name = arg()?;
+            let unknown = || format!("unknown {kind} '{name}'");
+            match kind {
+                "action" => AppEvent::Action(Action::from_name(name).ok_or_else(unknown)?),
+                "released" => {
+                    AppEvent::ActionReleased(Action::from_name(name).ok_or_else(unknown)?)
+                }
+                "paste" => AppEvent::Paste(unescape_text(name)?),
+                "letter" => {
+                    let letter = unescape_text(name)?;
+                    let mut chars = letter.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => AppEvent::Letter(c),
+                        _ => return Err(unknown()),
+                    }
+                }
+                "quit_choice" => AppEvent::QuitChoice(
+                    [QuitChoice::Quit, QuitChoice::Stay, QuitChoice::SaveAndQuit]
+                        .into_iter()
+                        .find(|&c| quit_choice_name(c) == name)
+                        .ok_or_else(unknown)?,
+                ),
+                "nav" => AppEvent::Nav(
+                    [Nav::Up, Nav::Down, Nav::Left, Nav::Right, Nav::Select]
+                        .into_iter()
+                        .find(|&n| nav_name(n) == name)
+                        .ok_or_else(unknown)?,
+                ),
+                "palette" => AppEvent::PaletteKey(
+                    [
+                        PaletteKey::Up,
+                        PaletteKey::Down,
+                        PaletteKey::Run,
+                        PaletteKey::Erase,
+                        PaletteKey::Close,
+                    ]
+                    .into_iter()
+                    .find(|&k| palette_key_name(k) == name)
+                    .ok_or_else(unknown)?,
+                ),
+                _ => return Err(format!("unknown event '{kind}'")),
+            }
+        }
+    };
+    Ok(event)
+}
+
+fn quit_choice_name(choice: QuitChoice) -> &'static str {
+    match choice {
+        QuitChoice::Quit => "quit",
+        QuitChoice::Stay => "stay",
+        QuitChoice::SaveAndQuit => "save_and_quit",
+    }
+}
+
+fn nav_name(nav: Nav) -> &'static str {
+    match nav {
+        Nav::Up => "up",
+        Nav::Down => "down",
+        Nav::Left => "left",
+        Nav::Right => "right",
+        Nav::Select => "select",
+    }
+}
+
+fn palette_key_name(key: PaletteKey) -> &'static str {
+    match key {
+        PaletteKey::Up => "up",
+        PaletteKey::Down => "down",
+        PaletteKey::Run => "run",
+        PaletteKey::Erase => "erase",
+        PaletteKey::Close => "close",
+    }
+}
+
+/// Appends events to a recording file as they're handled.
+///
+/// Lines are written whole as they're recorded, so a crash loses nothing.
+pub struct Recorder {
+    out: LineWriter<std::fs::File>,
+    last_size: Option<Vec2>,
+    last_dpi: Option<f32>,
+}
+
+impl Recorder {
+    /// Starts a recording at `path` of a session starting with `saved_entry`.
+    pub fn create(path: &str, saved_entry: &str) -> Result<Self, String> {
+        let file =
+            std::fs::File::create(path).map_err(|e| format!("can't create '{path}': {e}"))?;
+        let mut out = LineWriter::new(file);
+        let mut header = format!("{HEADER}\n");
+        if !saved_entry.is_empty() {
+            header.push_str(&format!("entry {saved_entry}\n"));
+        }
+        out.write_all(header.as_bytes())
+            .map_err(|e| format!("can't write '{path}': {e}"))?;
+        Ok(Self {
+            out,
+            last_size: None,
+            last_dpi: None,
+        })
+    }
+    /// Records `event`, handled at `time` with dictionary `generation`.
+    pub fn record(&mut self, time: f64, generation: u32, event: &AppEvent) {
+        // Sizes and DPI scales come every frame; only changes are worth keeping
+        let repeated = match *event {
+            AppEvent::WindowSize(size) => self.last_size.replace(size) == Some(size),
+            AppEvent::DpiScale(dpi) => self.last_dpi.replace(dpi) == Some(dpi),
+            _ => false,
+        };
+        if repeated {
+            return;
+        }
+        let line = format!("{time:.3} {generation} {}\n", format_event(event));
+        if let Err(e) = self.out.write_all(line.as_bytes()) {
+            log::warn!("[Recorder::record] failed to write: {e}");
+        }
+    }
+}
+
+/// Feeds a recording's events in as the app's time reaches theirs, for watching a
+/// replay in the window.
+pub struct Replayer {
+    events: std::vec::IntoIter<Recorded>,
+    next: Option<Recorded>,
+}
+
+impl Replayer {
+    /// Starts replaying `recording` from its first event.
+    pub fn new(recording: Recording) -> Self {
+        let mut events = recording.events.into_iter();
+        let next = events.next();
+        Self { events, next }
+    }
+    /// Returns whether every event has been fed in.
+    pub fn is_finished(&self) -> bool {
+        self.next.is_none()
+    }
+    /// Replaces the input in `polled` with the recorded events due by `now`.
+    ///
+    /// The window keeps its own size, DPI scale, and close button, so those are
+    /// kept from `polled` and recorded sizes and scales are left out.
+    pub fn replace_input(&mut self, polled: Vec<AppEvent>, now: f64) -> Vec<AppEvent> {
+        let mut events: Vec<AppEvent> = polled
+            .into_iter()
+            .filter(|ev| {
+                matches!(
+                    ev,
+                    AppEvent::WindowSize(_) | AppEvent::DpiScale(_) | AppEvent::CloseRequested
+                )
+            })
+            .collect();
+        while let Some(recorded) = self.next.take_if(|r| r.time <= now) {
+            if !matches!(
+                recorded.event,
+                AppEvent::WindowSize(_) | AppEvent::DpiScale(_)
+            ) {
+                events.push(recorded.event);
+            }
+            self.next = self.events.next();
+            if self.next.is_none() {
+                log::info!("[Replayer::replace_input] replay finished");
+            }
+        }
+        events
+    }
+}
+
+/// Where a windowed session's input comes from, and whether it's recorded.
+pub struct EventLog {
+    pub recorder: Option<Recorder>,
+    pub replayer: Option<Replayer>,
+    /// How many times faster than recorded a replay runs.
+    pub speed: f64,
+}
+
+impl EventLog {
+    /// Returns this frame's events: `polled`, or the replay's in its place. Each
+    /// is recorded, if recording, as handled at `now` with dictionary `generation`.
+    pub fn frame_events(
+        &mut self,
+        polled: Vec<AppEvent>,
+        now: f64,
+        generation: u32,
+    ) -> Vec<AppEvent> {
+        let events = match self.replayer.as_mut() {
+            Some(replayer) => replayer.replace_input(polled, now),
+            None => polled,
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            for event in &events {
+                recorder.record(now, generation, event);
+            }
+        }
+        events
+    }
+}
+
+/// Returns storage for a replay: in memory, holding the recording's saved entry.
+pub fn replay_storage(recording: &Recording) -> Arc<dyn Storage> {
+    let storage = MemoryStorage::default();
+    if let Err(e) = storage.write_format(ENTRY_KEY, ENTRY_FORMAT, &recording.saved_entry) {
+        log::warn!("[replay_storage] {e}");
+    }
+    Arc::new(storage)
+}
+
+/// Reads and parses the recording at `path`.
+pub fn read_recording(path: &str) -> Result<Recording, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("can't read '{path}': {e}"))?;
+    Recording::parse(&text)
+}
+
+/// Seconds since the first call, for timing queries without a window.
+pub fn wall_clock() -> f64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64()
+}
+
+/// Describes what the app shows, for printing after each replayed event.
+fn describe(app: &App) -> String {
+    match app.screen() {
+        Screen::Loading(_) => "loading".into(),
+        Screen::Entry(entry) => {
+            let (tabs, active) = entry.tabs();
+            let tab = match tabs.len() {
+                1 => String::new(),
+                n => format!("tab={}/{n} ", active + 1),
+            };
+            format!(
+                "{tab}entry={:?} matches={} answer={:?}",
+                entry.entry(),
+                entry.matches().len(),
+                entry.answer()
+            )
+        }
+        Screen::Error(error) => format!("error: {}", error.message),
+        Screen::Settings(_) => "settings".into(),
+        Screen::Onboarding(_) => "onboarding".into(),
+        Screen::Attract(_) => "attract".into(),
+        Screen::Tutorial(_) => "tutorial".into(),
+    }
+}
+
+/// Updates `app` without advancing time until the dictionary has loaded,
+/// returning false if it didn't within `LOAD_TIMEOUT`.
+pub fn wait_for_load(app: &mut App) -> bool {
+    let started = Instant::now();
+    while matches!(app.screen(), Screen::Loading(_)) {
+        if started.elapsed() > LOAD_TIMEOUT || app.is_quitting() {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+        app.update(0.0);
+    }
+    true
+}
+
+/// Replays the recording at `path` without a window, printing the state after
+/// each event, and returns the exit code: 0 if it ran, 2 if it couldn't.
+pub fn run_replay(path: &str, config: Config, keymap: Keymap) -> i32 {
+    let replayed = read_recording(path)
+        .and_then(|recording| replay(recording, config, keymap, |line| println!("{line}")));
+    match replayed {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("[run_replay] {e}");
+            2
+        }
+    }
+}
+
+/// Replays `recording` without a window, passing `print` a line describing the
+/// state after each event, and returns the app as the replay left it.
+pub fn replay(
+    recording: Recording,
+    mut config: Config,
+    keymap: Keymap,
+    mut print: impl FnMut(&str),
+) -> Result<App, String> {
+    // Replays stay quiet
+    config.announce = false;
+    let sounds = Sounds::silent(config.volume, true);
+    let state = AppState::new(config, replay_storage(&recording));
+    let size = vec2(800.0, 600.0);
+    let mut app = App::new(state, keymap, None, sounds, size, wall_clock, fixed_measure);
+
+    if !wait_for_load(&mut app) {
+        return Err("the dictionary didn't load".into());
+    }
+    print(&format!("{:>9} {:<24} {}", "-", "(start)", describe(&app)));
+
+    for recorded in recording.events {
+        while app.ctx.now < recorded.time {
+            app.update((recorded.time - app.ctx.now).min(MAX_STEP));
+        }
+        // Events from before the dictionary loaded are replayed after it has
+        let generation = app.ctx.dictionary_generation;
+        if recorded.generation != 0 && recorded.generation != generation {
+            print(&format!(
+                "warning: recorded with dictionary {}, replaying with {}",
+                recorded.generation, generation
+            ));
+        }
+        let event = format_event(&recorded.event);
+        app.handle_event(recorded.event);
+        app.update(0.0);
+        print(&format!(
+            "{:>9.3} {event:<24} {}",
+            recorded.time,
+            describe(&app)
+        ));
+        if app.is_quitting() {
+            break;
+        }
+    }
+    Ok(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A file of its own under the system's temporary directory.
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn every_kind_of_event_reads_back_as_written() {
+        let events = [
+            AppEvent::WindowSize(vec2(800.0, 600.5)),
+            AppEvent::CloseRequested,
+            AppEvent::KeyActivity,
+            AppEvent::Action(Action::DeleteBack),
+            AppEvent::Letter('É'),
+            AppEvent::QuitChoice(QuitChoice::SaveAndQuit),
+            AppEvent::Pointer(PointerEvent::Pressed(vec2(1.0, 2.0))),
+            AppEvent::Pointer(PointerEvent::Moved(vec2(-3.5, 4.0))),
+            AppEvent::Pointer(PointerEvent::Released(vec2(5.0, 6.0))),
+            AppEvent::Touch,
+            AppEvent::Wheel(-1.5),
+            AppEvent::Nav(Nav::Select),
+            AppEvent::Dismiss,
+            AppEvent::PaletteKey(PaletteKey::Erase),
+            AppEvent::Paste("1. G A R\\B E\nE (_ _ O)".into()),
+        ];
+        for event in events {
+            let line = format_event(&event);
+            assert!(!line.contains('\n'), "{line}");
+            assert_eq!(parse_event(&line), Ok(event), "{line}");
+        }
+    }
+
+    #[test]
+    fn bad_lines_are_reported_by_number() {
+        assert!(Recording::parse("1.0 0 close\n").is_err(), "no header");
+        let text = format!("{HEADER}\n0.5 0 close\n\n# note\n1.0 0 letter AB\n");
+        let e = Recording::parse(&text).unwrap_err();
+        assert!(e.starts_with("line 5: unknown letter 'AB'"), "{e}");
+        assert!(parse_event("action fly").is_err());
+        assert!(parse_event("size 800").is_err());
+        assert!(parse_event("wheel up").is_err());
+        assert!(parse_event("jump").is_err());
+        assert!(parse_recorded("soon 0 close").is_err());
+    }
+
+    #[test]
+    fn the_recorder_writes_the_saved_entry_and_only_size_changes() {
+        let path = temp_path("recorder.txt");
+        let mut recorder = Recorder::create(path.to_str().unwrap(), "NUP").unwrap();
+        let size = AppEvent::WindowSize(vec2(800.0, 600.0));
+        recorder.record(0.0, 0, &size);
+        recorder.record(0.1, 0, &size);
+        recorder.record(0.25, 1, &AppEvent::Letter('O'));
+        drop(recorder);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let expected = format!("{HEADER}\nentry NUP\n0.000 0 size 800 600\n0.250 1 letter O\n");
+        assert_eq!(text, expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn the_replayer_feeds_events_as_their_time_comes() {
+        let recorded = |time, event| Recorded {
+            time,
+            generation: 1,
+            event,
+        };
+        let mut replayer = Replayer::new(Recording {
+            saved_entry: String::new(),
+            events: vec![
+                recorded(0.0, AppEvent::WindowSize(vec2(1.0, 1.0))),
+                recorded(0.5, AppEvent::Letter('A')),
+                recorded(1.0, AppEvent::Letter('B')),
+            ],
+        });
+        // Typed keys are replaced; the window's own size is kept
+        let polled = vec![AppEvent::Letter('Z'), AppEvent::WindowSize(vec2(2.0, 2.0))];
+        assert_eq!(
+            replayer.replace_input(polled, 0.2),
+            [AppEvent::WindowSize(vec2(2.0, 2.0))]
+        );
+        assert_eq!(
+            replayer.replace_input(Vec::new(), 1.0),
+            [AppEvent::Letter('A'), AppEvent::Letter('B')]
+        );
+        assert!(replayer.replace_input(Vec::new(), 9.0).is_empty());
+    }
+
+    #[test]
+    fn a_recorded_session_replays_to_the_same_state() {
+        let words = temp_path("replay-words.txt");
+        std::fs::write(&words, "UPON\nPOUND\n").unwrap();
+        let path = temp_path("replay.txt");
+
+        let mut events = EventLog {
+            recorder: Some(Recorder::create(path.to_str().unwrap(), "NUP").unwrap()),
+            replayer: None,
+            speed: 1.0,
+        };
+        let frames = [
+            (0.0, vec![AppEvent::WindowSize(vec2(800.0, 600.0))]),
+            (0.5, vec![AppEvent::Letter('X')]),
+            (0.6, vec![AppEvent::Action(Action::DeleteBack)]),
+            (1.0, vec![AppEvent::Letter('O'), AppEvent::KeyActivity]),
+        ];
+        for (now, polled) in frames.clone() {
+            assert_eq!(events.frame_events(polled.clone(), now, 1), polled);
+        }
+        drop(events);
+
+        let recording = read_recording(path.to_str().unwrap()).unwrap();
+        assert_eq!(recording.saved_entry, "NUP");
+        let handled: Vec<AppEvent> = frames.into_iter().flat_map(|(_, e)| e).collect();
+        let replayed: Vec<AppEvent> = recording.events.iter().map(|r| r.event.clone()).collect();
+        assert_eq!(replayed, handled);
+        assert_eq!(recording.events[3].time, 1.0);
+
+        let mut config = Config::default();
+        config.dictionary.path = Some(words.display().to_string());
+        let mut lines = Vec::new();
+        let app = replay(recording, config, Keymap::defaults(), |line| {
+            lines.push(line.to_owned())
+        })
+        .unwrap();
+        let Screen::Entry(entry) = app.screen() else {
+            panic!("not on the entry screen");
+        };
+        assert_eq!(entry.entry(), "NUPO");
+        assert_eq!(lines.len(), 1 + handled.len());
+        assert!(
+            lines[0].ends_with("entry=\"NUP\" matches=0 answer=None"),
+            "{lines:?}"
+        );
+        assert!(lines[2].contains("entry=\"NUPX\""), "{lines:?}");
+        assert!(lines[4].contains("answer=Some(\"UPON\")"), "{lines:?}");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&words).unwrap();
+    }
+}
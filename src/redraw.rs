@@ -0,0 +1,110 @@
+//! Redraw skipping: the scene is drawn into a cached texture only when something
+//! changed, and idle frames just present the cache.
+//!
+//! Macroquad clears the screen every frame, so skipping draw calls outright would
+//! leave a blank window; presenting one cached texture is the cheap alternative.
+
+use macroquad::prelude::*;
+
+/// Target frame time, in seconds, while nothing is changing (~30 FPS).
+pub const IDLE_FRAME_TIME: f64 = 1.0 / 30.0;
+
+/// The cached scene, whether it needs redrawing, and draw/skip counts.
+pub struct FrameCache {
+    target: RenderTarget,
+    camera: Camera2D,
+    size: (u32, u32),
+    dirty: bool,
+    pub frames_drawn: u64,
+    pub frames_skipped: u64,
+}
+
+impl FrameCache {
+    /// Creates a cache sized to the current window, initially dirty.
+    // Not `Default`: it needs the window open
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let size = screen_size_px();
+        let (target, camera) = make_target(size);
+        Self {
+            target,
+            camera,
+            size,
+            dirty: true,
+            frames_drawn: 0,
+            frames_skipped: 0,
+        }
+    }
+    /// Returns whether the next frame will be redrawn.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+    /// Starts a frame, returning `true` if the scene should be redrawn.
+    ///
+    /// The scene is redrawn on the first frame, when the window was resized, or
+    /// when `redraw` is set. When `true` is returned, drawing goes to the cache
+    /// until `present` is called.
+    pub fn begin(&mut self, redraw: bool) -> bool {
+        let size = screen_size_px();
+        if size != self.size {
+            (self.target, self.camera) = make_target(size);
+            self.size = size;
+            self.dirty = true;
+        }
+
+        if !(self.dirty || redraw) {
+            self.frames_skipped += 1;
+            return false;
+        }
+
+        self.dirty = false;
+        self.frames_drawn += 1;
+        set_camera(&self.camera);
+        true
+    }
+    /// Draws the cached scene to the screen.
+    pub fn present(&self) {
+        set_default_camera();
+        draw_texture_ex(
+            &self.target.texture,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(screen_width(), screen_height())),
+                // Render targets are stored upside down
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Sleeps out the remainder of an idle frame so idle CPU use stays low.
+///
+/// `frame_time` is how long the frame has taken so far, in seconds.
+pub fn idle_sleep(frame_time: f64) {
+    let remaining = IDLE_FRAME_TIME - frame_time;
+    #[cfg(not(target_arch = "wasm32"))]
+    if remaining > 0.0 {
+        std::thread::sleep(std::time::Duration::from_secs_f64(remaining));
+    }
+    // The browser paces frames itself; blocking would freeze the page.
+    #[cfg(target_arch = "wasm32")]
+    let _ = remaining;
+}
+
+fn screen_size_px() -> (u32, u32) {
+    (
+        screen_width().max(1.0) as u32,
+        screen_height().max(1.0) as u32,
+    )
+}
+
+fn make_target((w, h): (u32, u32)) -> (RenderTarget, Camera2D) {
+    let target = render_target(w, h);
+    target.texture.set_filter(FilterMode::Nearest);
+    let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, w as f32, h as f32));
+    camera.render_target = Some(target.clone());
+    (target, camera)
+}
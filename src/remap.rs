@@ -0,0 +1,333 @@
+//! Remapping typed characters, for typing with one hand or on an odd keyboard.
+//!
+//! The `[input]` table of the config picks a preset and a custom remap table:
+//!
+//! ```toml
+//! [input]
+//! preset = "left_hand"
+//!
+//! [input.remap]
+//! j = "O"
+//! ```
+//!
+//! Each character typed for the entry goes through the table first, as the key
+//! it was typed on, then through the preset. With `left_hand`, Space followed
+//! by a left-hand letter types the letter mirrored on the right hand (Space `F`
+//! types `J`, Space `R` types `U` on QWERTY; see `mirror`), and Space twice
+//! types a space.
+//!
+//! Letters typed with Ctrl or Alt held belong to key chords and never reach
+//! the entry, but a plain or Shift chord on a remapped key would both run its
+//! action and type, so `Remapper::new` refuses such a table (see
+//! `check_conflicts`), as the keymap refuses a chord bound twice.
+
+use crate::keymap::Keymap;
+use crate::strings::{Language, StringKey};
+use crate::typo::KeyboardLayout;
+use macroquad::prelude::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Typed before a left-hand letter to type its right-hand mirror.
+pub const PREFIX: char = ' ';
+
+/// Ways of typing letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputPreset {
+    /// Each key types its own letter.
+    #[default]
+    Standard,
+    /// Space then a left-hand letter types the right-hand letter mirroring it.
+    LeftHand,
+}
+
+/// The `[input]` table of the config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputConfig {
+    pub preset: InputPreset,
+    /// Characters typed, each to the one to type instead.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub remap: BTreeMap<String, String>,
+}
+
+/// Left-hand letters, each above the right-hand letter mirroring it on a
+/// QWERTY keyboard.
+const QWERTY_LEFT: &str = "QWERTSDFGBV";
+const QWERTY_RIGHT: &str = "POIUYLKJHNM";
+/// The same, on an AZERTY keyboard.
+const AZERTY_LEFT: &str = "AZERTQSDFGB";
+const AZERTY_RIGHT: &str = "POIUYMLKJHN";
+
+/// Returns the right-hand letter mirroring left-hand letter `c` on `layout`, if
+/// it has one.
+pub fn mirror(layout: KeyboardLayout, c: char) -> Option<char> {
+    let (left, right) = match layout {
+        KeyboardLayout::Qwerty => (QWERTY_LEFT, QWERTY_RIGHT),
+        KeyboardLayout::Azerty => (AZERTY_LEFT, AZERTY_RIGHT),
+    };
+    let i = left.find(c.to_ascii_uppercase())?;
+    right[i..].chars().next()
+}
+
+/// Returns the key typing `c` for the entry, if one does.
+pub fn key_for(c: char) -> Option<KeyCode> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+        KeyCode::H,
+        KeyCode::I,
+        KeyCode::J,
+        KeyCode::K,
+        KeyCode::L,
+        KeyCode::M,
+        KeyCode::N,
+        KeyCode::O,
+        KeyCode::P,
+        KeyCode::Q,
+        KeyCode::R,
+        KeyCode::S,
+        KeyCode::T,
+        KeyCode::U,
+        KeyCode::V,
+        KeyCode::W,
+        KeyCode::X,
+        KeyCode::Y,
+        KeyCode::Z,
+    ];
+    match c.to_ascii_uppercase() {
+        c @ 'A'..='Z' => Some(LETTERS[(c as u8 - b'A') as usize]),
+        ' ' => Some(KeyCode::Space),
+        '.' => Some(KeyCode::Period),
+        _ => None,
+    }
+}
+
+/// Parses the remap table: each key and value must be one character, the key
+/// one typed for the entry, and the value a letter or a space. Letters are
+/// taken as uppercase, as they're typed.
+pub fn parse_remap(remap: &BTreeMap<String, String>) -> Result<BTreeMap<char, char>, String> {
+    let single = |text: &str| {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c.to_uppercase().next().unwrap_or(c)),
+            _ => Err(format!("'{text}' in [input.remap] isn't one character")),
+        }
+    };
+    let mut table = BTreeMap::new();
+    for (from, to) in remap {
+        let (from, to) = (single(from)?, single(to)?);
+        if key_for(from).is_none() {
+            return Err(format!("'{from}' in [input.remap] isn't a key that types"));
+        }
+        if !to.is_alphabetic() && to != ' ' {
+            return Err(format!(
+                "[input.remap] can only type letters and spaces, not '{to}'"
+            ));
+        }
+        table.insert(from, to);
+    }
+    Ok(table)
+}
+
+/// Checks that no key the remap table or `preset` gives a meaning is also
+/// bound, without Ctrl or Alt, to an action in `keymap`.
+pub fn check_conflicts(
+    table: &BTreeMap<char, char>,
+    preset: InputPreset,
+    keymap: &Keymap,
+) -> Result<(), String> {
+    let prefix = (preset == InputPreset::LeftHand).then_some(PREFIX);
+    if let Some(prefix) = prefix.filter(|c| table.contains_key(c)) {
+        return Err(format!(
+            "'{prefix}' starts a mirrored letter in the left_hand preset, so it can't be remapped"
+        ));
+    }
+    for c in table.keys().copied().chain(prefix) {
+        let Some(key) = key_for(c) else {
+            continue;
+        };
+        if let Some(action) = keymap.typing_action(key) {
+            return Err(format!(
+                "'{c}' is given a meaning in [input], but its key is bound to '{}'",
+                action.name()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Turns characters typed into those the entry gets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Remapper {
+    preset: InputPreset,
+    layout: KeyboardLayout,
+    table: BTreeMap<char, char>,
+    /// Whether the left-hand prefix was just typed.
+    pending: bool,
+}
+
+impl Remapper {
+    /// Creates the remapper `input` asks for on `layout`, or returns why it
+    /// can't: the table doesn't parse, or conflicts with `keymap`.
+    pub fn new(
+        input: &InputConfig,
+        layout: KeyboardLayout,
+        keymap: &Keymap,
+    ) -> Result<Self, String> {
+        let table = parse_remap(&input.remap)?;
+        check_conflicts(&table, input.preset, keymap)?;
+        Ok(Self {
+            preset: input.preset,
+            layout,
+            table,
+            pending: false,
+        })
+    }
+    /// Returns what the entry gets for `c`, typed as it is; `None` if nothing,
+    /// for the left-hand prefix.
+    pub fn map(&mut self, c: char) -> Option<char> {
+        let c = self.table.get(&c).copied().unwrap_or(c);
+        if self.preset != InputPreset::LeftHand {
+            return Some(c);
+        }
+        match std::mem::take(&mut self.pending) {
+            false if c == PREFIX => {
+                self.pending = true;
+                None
+            }
+            false => Some(c),
+            true if c == PREFIX => Some(PREFIX),
+            true => Some(mirror(self.layout, c).unwrap_or(c)),
+        }
+    }
+    /// Forgets a left-hand prefix typed, as when the entry is cleared.
+    pub fn reset(&mut self) {
+        self.pending = false;
+    }
+    /// Returns what to show while typing differs from the standard, if it does.
+    pub fn indicator(&self, lang: Language) -> Option<String> {
+        let preset = match (self.preset, self.pending) {
+            (InputPreset::Standard, _) => None,
+            (InputPreset::LeftHand, false) => Some(StringKey::InputLeftHand),
+            (InputPreset::LeftHand, true) => Some(StringKey::InputMirrorNext),
+        };
+        let remapped = (!self.table.is_empty()).then_some(StringKey::InputRemapped);
+        let parts: Vec<&str> = [preset, remapped]
+            .into_iter()
+            .flatten()
+            .map(|key| lang.text(key))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remap(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(from, to)| (from.to_owned(), to.to_owned()))
+            .collect()
+    }
+
+    fn remapper(preset: InputPreset, pairs: &[(&str, &str)]) -> Result<Remapper, String> {
+        let input = InputConfig {
+            preset,
+            remap: remap(pairs),
+        };
+        Remapper::new(&input, KeyboardLayout::Qwerty, &Keymap::defaults())
+    }
+
+    fn typed(remapper: &mut Remapper, text: &str) -> String {
+        text.chars().filter_map(|c| remapper.map(c)).collect()
+    }
+
+    #[test]
+    fn left_hand_letters_mirror_on_each_layout() {
+        assert_eq!(mirror(KeyboardLayout::Qwerty, 'F'), Some('J'));
+        assert_eq!(mirror(KeyboardLayout::Qwerty, 'r'), Some('U'));
+        assert_eq!(mirror(KeyboardLayout::Azerty, 'A'), Some('P'));
+        assert_eq!(mirror(KeyboardLayout::Azerty, 'Q'), Some('M'));
+        assert_eq!(mirror(KeyboardLayout::Qwerty, 'J'), None);
+    }
+
+    #[test]
+    fn the_table_takes_single_characters_to_letters() {
+        let table = parse_remap(&remap(&[("j", "o"), (".", " ")])).unwrap();
+        assert_eq!(table, BTreeMap::from([('J', 'O'), ('.', ' ')]));
+        assert!(parse_remap(&remap(&[("jk", "O")])).is_err());
+        assert!(parse_remap(&remap(&[("J", "")])).is_err());
+        assert!(parse_remap(&remap(&[("J", "1")])).is_err());
+        assert!(parse_remap(&remap(&[("1", "J")])).is_err());
+    }
+
+    #[test]
+    fn the_table_applies_before_the_preset() {
+        let mut standard = remapper(InputPreset::Standard, &[("Q", "U")]).unwrap();
+        assert_eq!(typed(&mut standard, "Q NQ"), "U NU");
+
+        let mut left = remapper(InputPreset::LeftHand, &[("X", "F")]).unwrap();
+        // Space F and Space X (typing F) both give J; Space Space a space
+        assert_eq!(typed(&mut left, "G F X  A"), "GJJ A");
+        // A letter with no mirror types itself
+        assert_eq!(typed(&mut left, " Z"), "Z");
+    }
+
+    #[test]
+    fn clearing_forgets_a_prefix() {
+        let mut left = remapper(InputPreset::LeftHand, &[]).unwrap();
+        assert_eq!(left.map(PREFIX), None);
+        left.reset();
+        assert_eq!(left.map('F'), Some('F'));
+    }
+
+    #[test]
+    fn keys_bound_to_actions_cant_be_remapped() {
+        let keymap = Keymap::with_overrides(&remap(&[("show_hint", "J, Ctrl+K")])).unwrap();
+        let input = |preset, pairs: &[(&str, &str)]| InputConfig {
+            preset,
+            remap: remap(pairs),
+        };
+        let new = |input: &InputConfig| Remapper::new(input, KeyboardLayout::Qwerty, &keymap);
+        let e = new(&input(InputPreset::Standard, &[("J", "O")])).unwrap_err();
+        assert!(e.contains("'show_hint'"), "{e}");
+        // Held with Ctrl, K doesn't type
+        assert!(new(&input(InputPreset::Standard, &[("K", "O")])).is_ok());
+
+        // The left-hand prefix can be neither remapped nor bound
+        assert!(new(&input(InputPreset::LeftHand, &[(" ", "A")])).is_err());
+        let keymap = Keymap::with_overrides(&remap(&[("show_hint", "Space")])).unwrap();
+        let left = input(InputPreset::LeftHand, &[]);
+        assert!(Remapper::new(&left, KeyboardLayout::Qwerty, &keymap).is_err());
+        assert!(Remapper::new(&left, KeyboardLayout::Qwerty, &Keymap::defaults()).is_ok());
+    }
+
+    #[test]
+    fn the_indicator_says_how_typing_differs() {
+        assert_eq!(
+            remapper(InputPreset::Standard, &[])
+                .unwrap()
+                .indicator(Language::En),
+            None
+        );
+        let mut left = remapper(InputPreset::LeftHand, &[("Q", "U")]).unwrap();
+        assert_eq!(
+            left.indicator(Language::En).as_deref(),
+            Some("Left hand, Keys remapped")
+        );
+        left.map(PREFIX);
+        assert_eq!(
+            left.indicator(Language::En).as_deref(),
+            Some("Left hand: next letter mirrored, Keys remapped")
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! The drawing and text measuring calls the UI makes, behind a trait.
+//!
+//! Draw code takes a `&dyn Renderer` instead of calling macroquad directly, so it
+//! can be run against a renderer that records the calls instead of drawing them.
+
+use crate::glyphs::for_display;
+use macroquad::prelude::*;
+
+/// Thin layer over macroquad's shape and text drawing.
+pub trait Renderer {
+    /// Returns the window size in pixels.
+    fn screen_size(&self) -> Vec2;
+    /// Fills the whole window with `color`.
+    fn clear(&self, color: Color);
+    /// Fills `rect`.
+    fn rect(&self, rect: Rect, color: Color);
+    /// Outlines `rect`.
+    fn rect_lines(&self, rect: Rect, thickness: f32, color: Color);
+    /// Draws a line from `from` to `to`.
+    fn line(&self, from: Vec2, to: Vec2, thickness: f32, color: Color);
+    /// Fills a circle.
+    fn circle(&self, center: Vec2, radius: f32, color: Color);
+    /// Fills the triangle `a`, `b`, `c`.
+    fn triangle(&self, a: Vec2, b: Vec2, c: Vec2, color: Color);
+    /// Draws `text` with its baseline starting at `pos`.
+    fn text(&self, text: &str, pos: Vec2, font: Option<&Font>, size: u16, color: Color);
+    /// Returns the size `text` would be drawn at.
+    fn measure(&self, text: &str, font: Option<&Font>, size: u16) -> TextDimensions;
+}
+
+/// Measures text as `Renderer::measure` does, for code that runs outside drawing.
+pub type Measure = fn(&str, Option<&Font>, u16) -> TextDimensions;
+
+/// Measures with macroquad, as the text is drawn (see `glyphs`); needs the window
+/// to exist.
+pub fn macroquad_measure(text: &str, font: Option<&Font>, size: u16) -> TextDimensions {
+    measure_text(for_display(text), font, size, 1.0)
+}
+
+/// Measures every character as 0.6 em wide, for running without a window.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub fn fixed_measure(text: &str, _font: Option<&Font>, size: u16) -> TextDimensions {
+    let size = size as f32;
+    TextDimensions {
+        width: text.chars().count() as f32 * size * 0.6,
+        height: size,
+        offset_y: size * 0.8,
+    }
+}
+
+/// Joins `words` with spaces into lines at most `width` wide, as measured by
+/// `measure`; a word wider than that gets a line to itself.
+pub fn wrap_words<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+    width: f32,
+    measure: impl Fn(&str) -> f32,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if measure(&format!("{line} {word}")) <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+    lines
+}
+
+/// Draws straight to the screen (or the current render target).
+pub struct MacroquadRenderer;
+
+impl Renderer for MacroquadRenderer {
+    fn screen_size(&self) -> Vec2 {
+        vec2(screen_width(), screen_height())
+    }
+    fn clear(&self, color: Color) {
+        clear_background(color);
+    }
+    fn rect(&self, rect: Rect, color: Color) {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    }
+    fn rect_lines(&self, rect: Rect, thickness: f32, color: Color) {
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, thickness, color);
+    }
+    fn line(&self, from: Vec2, to: Vec2, thickness: f32, color: Color) {
+        draw_line(from.x, from.y, to.x, to.y, thickness, color);
+    }
+    fn circle(&self, center: Vec2, radius: f32, color: Color) {
+        draw_circle(center.x, center.y, radius, color);
+    }
+    fn triangle(&self, a: Vec2, b: Vec2, c: Vec2, color: Color) {
+        draw_triangle(a, b, c, color);
+    }
+    fn text(&self, text: &str, pos: Vec2, font: Option<&Font>, size: u16, color: Color) {
+        // Characters the font lacks are drawn as ASCII rather than left out
+        draw_text_ex(
+            for_display(text),
+            pos.x,
+            pos.y,
+            TextParams {
+                font_size: size,
+                font,
+                color,
+                ..Default::default()
+            },
+        );
+    }
+    fn measure(&self, text: &str, font: Option<&Font>, size: u16) -> TextDimensions {
+        macroquad_measure(text, font, size)
+    }
+}
+
+/// One call made to a `RecordingRenderer`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Clear {
+        color: Color,
+    },
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    RectLines {
+        rect: Rect,
+        thickness: f32,
+        color: Color,
+    },
+    Line {
+        from: Vec2,
+        to: Vec2,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Color,
+    },
+    Triangle {
+        a: Vec2,
+        b: Vec2,
+        c: Vec2,
+        color: Color,
+    },
+    Text {
+        text: String,
+        pos: Vec2,
+        size: u16,
+        color: Color,
+    },
+}
+
+/// Records draw calls instead of drawing, measuring text with `fixed_measure`,
+/// so drawing can be checked without a window (see `snapshot`).
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct RecordingRenderer {
+    pub size: Vec2,
+    pub calls: std::cell::RefCell<Vec<DrawCall>>,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl RecordingRenderer {
+    /// Creates a renderer for a `size` window, with nothing recorded.
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            calls: Default::default(),
+        }
+    }
+    /// Returns the calls recorded so far, clearing them.
+    pub fn take(&self) -> Vec<DrawCall> {
+        self.calls.take()
+    }
+    fn push(&self, call: DrawCall) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn screen_size(&self) -> Vec2 {
+        self.size
+    }
+    fn clear(&self, color: Color) {
+        self.push(DrawCall::Clear { color });
+    }
+    fn rect(&self, rect: Rect, color: Color) {
+        self.push(DrawCall::Rect { rect, color });
+    }
+    fn rect_lines(&self, rect: Rect, thickness: f32, color: Color) {
+        self.push(DrawCall::RectLines {
+            rect,
+            thickness,
+            color,
+        });
+    }
+    fn line(&self, from: Vec2, to: Vec2, thickness: f32, color: Color) {
+        self.push(DrawCall::Line {
+            from,
+            to,
+            thickness,
+            color,
+        });
+    }
+    fn circle(&self, center: Vec2, radius: f32, color: Color) {
+        self.push(DrawCall::Circle {
+            center,
+            radius,
+            color,
+        });
+    }
+    fn triangle(&self, a: Vec2, b: Vec2, c: Vec2, color: Color) {
+        self.push(DrawCall::Triangle { a, b, c, color });
+    }
+    fn text(&self, text: &str, pos: Vec2, _font: Option<&Font>, size: u16, color: Color) {
+        self.push(DrawCall::Text {
+            text: text.to_owned(),
+            pos,
+            size,
+            color,
+        });
+    }
+    fn measure(&self, text: &str, font: Option<&Font>, size: u16) -> TextDimensions {
+        fixed_measure(text, font, size)
+    }
+}
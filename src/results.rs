@@ -0,0 +1,356 @@
+//! Scrollable, selectable list of matching words, and how each is styled.
+//!
+//! Color never carries meaning alone: every `ResultStyle` with a meaningful color
+//! also has a `Mark` drawn beside the word.
+
+use crate::metrics::TextMetrics;
+use crate::pairing::draw_paired;
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// Fraction of fling velocity kept after one second of coasting.
+pub const SCROLL_FRICTION: f32 = 0.05;
+/// Velocity (pixels per second) below which coasting stops.
+pub const MIN_FLING_VELOCITY: f32 = 5.0;
+
+/// Scroll position, momentum, and selection of the results list.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResultsView {
+    /// Pixels scrolled from the top of the list.
+    pub scroll: f32,
+    /// Coasting velocity in pixels per second; positive scrolls further down the list.
+    pub velocity: f32,
+    pub selected: Option<usize>,
+}
+
+impl ResultsView {
+    /// Resets scroll and selection, e.g. after the matches change.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+    /// Scrolls by a finger movement of `dy` pixels, stopping any coasting.
+    ///
+    /// Dragging the finger up (negative `dy`) moves further down the list.
+    pub fn drag(&mut self, dy: f32, max_scroll: f32) {
+        self.velocity = 0.0;
+        self.scroll = (self.scroll - dy).clamp(0.0, max_scroll.max(0.0));
+    }
+    /// Starts coasting after a finger is lifted while moving at `finger_velocity`.
+    pub fn fling(&mut self, finger_velocity: f32) {
+        self.velocity = -finger_velocity;
+    }
+    /// Advances coasting by `dt` seconds, decaying velocity and stopping at the ends.
+    pub fn update(&mut self, dt: f32, max_scroll: f32) {
+        if self.velocity == 0.0 {
+            return;
+        }
+        let max_scroll = max_scroll.max(0.0);
+        self.scroll += self.velocity * dt;
+        self.velocity *= SCROLL_FRICTION.powf(dt);
+
+        if self.scroll <= 0.0 || self.scroll >= max_scroll {
+            self.scroll = self.scroll.clamp(0.0, max_scroll);
+            self.velocity = 0.0;
+        }
+        if self.velocity.abs() < MIN_FLING_VELOCITY {
+            self.velocity = 0.0;
+        }
+    }
+    /// Returns whether the list is still coasting.
+    pub fn is_moving(&self) -> bool {
+        self.velocity != 0.0
+    }
+    /// Returns the index of the row under `pos`, counting from the top of the
+    /// list, if any.
+    pub fn row_at(&self, pos: Vec2, area: Rect, row_h: f32, count: usize) -> Option<usize> {
+        if !area.contains(pos) || row_h <= 0.0 {
+            return None;
+        }
+        let row = ((pos.y - area.y + self.scroll) / row_h) as usize;
+        (row < count).then_some(row)
+    }
+}
+
+/// Shapes drawn beside a word to back up its color.
+///
+/// They're drawn as shapes rather than glyphs, since fonts (the embedded one
+/// included) may lack them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// ★ before the top candidate.
+    Star,
+    /// × before the no-match message.
+    Cross,
+}
+
+/// Color and mark of a result or message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultStyle {
+    pub color: Color,
+    pub mark: Option<Mark>,
+}
+
+impl ResultStyle {
+    /// Ordinary text.
+    pub fn plain(theme: &Theme) -> Self {
+        Self {
+            color: theme.result,
+            mark: None,
+        }
+    }
+    /// The top candidate: the unique answer, or the first row of the list.
+    pub fn top(theme: &Theme) -> Self {
+        Self {
+            color: theme.highlight,
+            mark: Some(Mark::Star),
+        }
+    }
+    /// The message shown when a complete entry matches nothing.
+    pub fn no_match(theme: &Theme) -> Self {
+        Self {
+            color: theme.error,
+            mark: Some(Mark::Cross),
+        }
+    }
+    /// Returns the style of row `index` of the list.
+    ///
+    /// The selected row is also outlined, so its color needs no mark.
+    pub fn for_row(index: usize, selected: bool, theme: &Theme) -> Self {
+        match (index, selected) {
+            (_, true) => Self {
+                color: theme.highlight,
+                ..Self::plain(theme)
+            },
+            (0, false) => Self::top(theme),
+            _ => Self::plain(theme),
+        }
+    }
+}
+
+/// Draws `mark` of height `size` centered on (`cx`, `cy`).
+pub fn draw_mark(gfx: &dyn Renderer, mark: Mark, cx: f32, cy: f32, size: f32, color: Color) {
+    let r = size / 2.0;
+    match mark {
+        Mark::Star => {
+            let point = |i: usize| {
+                let radius = if i.is_multiple_of(2) { r } else { r * 0.4 };
+                let angle = std::f32::consts::PI * (i as f32 / 5.0 - 0.5);
+                vec2(cx + radius * angle.cos(), cy + radius * angle.sin())
+            };
+            let center = vec2(cx, cy);
+            for i in 0..10 {
+                gfx.triangle(center, point(i), point(i + 1), color);
+            }
+        }
+        Mark::Cross => {
+            let (d, thickness) = (r * 0.7, (size / 6.0).max(1.0));
+            gfx.line(vec2(cx - d, cy - d), vec2(cx + d, cy + d), thickness, color);
+            gfx.line(vec2(cx - d, cy + d), vec2(cx + d, cy - d), thickness, color);
+        }
+    }
+}
+
+/// Draws `text` in `style` with its left edge at `x`, and its mark (if any) just
+/// to the left of that.
+pub fn draw_styled_text(
+    gfx: &dyn Renderer,
+    text: &str,
+    x: f32,
+    baseline: f32,
+    style: ResultStyle,
+    font: Option<&Font>,
+    metrics: &TextMetrics,
+) {
+    if let Some(mark) = style.mark {
+        let size = metrics.height * 0.6;
+        let cy = baseline - metrics.height / 2.0;
+        draw_mark(gfx, mark, x - size, cy, size, style.color);
+    }
+    let pos = vec2(x, baseline);
+    gfx.text(text, pos, font, metrics.size, style.color);
+}
+
+/// Returns the furthest the list can scroll.
+pub fn max_scroll(count: usize, row_h: f32, area: Rect) -> f32 {
+    (count as f32 * row_h - area.h).max(0.0)
+}
+
+/// Draws the visible rows of the `words` whose indices are in `shown`, in that
+/// order, inside `area`, highlighting the selected row, with each word's note (by
+/// index in `notes`), if any, in small text after it. The selected word's letters
+/// are colored by `pairs`, the tiles they're paired with (see `pairing`).
+#[allow(clippy::too_many_arguments)]
+pub fn draw_results(
+    gfx: &dyn Renderer,
+    words: &[String],
+    shown: impl Iterator<Item = usize>,
+    notes: &[Option<String>],
+    pairs: &[Option<usize>],
+    view: &ResultsView,
+    area: Rect,
+    row_h: f32,
+    font: Option<&Font>,
+    metrics: &TextMetrics,
+    theme: &Theme,
+) {
+    for (row, i) in shown.enumerate() {
+        let word = &words[i];
+        let top = area.y + row as f32 * row_h - view.scroll;
+        if top < area.y - 0.5 || top + row_h > area.bottom() + 0.5 {
+            continue;
+        }
+        let selected = view.selected == Some(i);
+        if selected {
+            let row = Rect::new(area.x, top, area.w, row_h);
+            gfx.rect_lines(row, theme.outline_thickness, theme.highlight);
+        }
+        let style = ResultStyle::for_row(i, selected, theme);
+        let width = metrics.width(word, |text| gfx.measure(text, font, metrics.size));
+        let x = area.x + (area.w - width) / 2.0;
+        let baseline = top + (row_h + metrics.offset_y) / 2.0;
+        if selected && pairs.iter().any(Option::is_some) {
+            let letters: Vec<Option<usize>> = pairs
+                .iter()
+                .enumerate()
+                .map(|(i, slot)| slot.map(|_| i))
+                .collect();
+            let pos = vec2(x, baseline);
+            draw_paired(gfx, word, &letters, false, pos, font, metrics, style.color);
+        } else {
+            draw_styled_text(gfx, word, x, baseline, style, font, metrics);
+        }
+        if let Some(note) = notes.get(i).and_then(Option::as_deref) {
+            let pos = vec2(x + width + metrics.size as f32 * 0.5, baseline);
+            gfx.text(note, pos, font, metrics.size / 2, theme.result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Coasts `view` in 60 FPS steps until it stops, returning the seconds taken.
+    fn coast(view: &mut ResultsView, max_scroll: f32) -> f32 {
+        let mut time = 0.0;
+        while view.velocity != 0.0 {
+            view.update(1.0 / 60.0, max_scroll);
+            time += 1.0 / 60.0;
+            assert!(time < 10.0, "still coasting: {view:?}");
+        }
+        time
+    }
+
+    #[test]
+    fn dragging_moves_the_list_against_the_finger_within_bounds() {
+        let mut view = ResultsView::default();
+        view.drag(-30.0, 100.0);
+        assert_eq!(view.scroll, 30.0);
+        view.drag(-500.0, 100.0);
+        assert_eq!(view.scroll, 100.0);
+        view.drag(500.0, 100.0);
+        assert_eq!(view.scroll, 0.0);
+        // A list shorter than its area never scrolls
+        view.drag(-30.0, -20.0);
+        assert_eq!(view.scroll, 0.0);
+    }
+
+    #[test]
+    fn a_fling_coasts_and_slows_to_a_stop() {
+        let mut view = ResultsView::default();
+        view.fling(-600.0);
+        view.update(0.1, 10_000.0);
+        let first = view.scroll;
+        assert!(first > 0.0 && view.velocity < 600.0);
+        view.update(0.1, 10_000.0);
+        assert!(view.scroll - first < first, "the second step is shorter");
+        coast(&mut view, 10_000.0);
+        // Coasting covers about v / ln(1 / friction), the continuous distance
+        let distance = 600.0 / (1.0 / SCROLL_FRICTION).ln();
+        assert!((view.scroll - distance).abs() < distance * 0.1, "{view:?}");
+    }
+
+    #[test]
+    fn coasting_stops_at_either_end() {
+        let mut view = ResultsView::default();
+        view.fling(-5000.0);
+        coast(&mut view, 50.0);
+        assert_eq!(view.scroll, 50.0);
+        view.fling(5000.0);
+        coast(&mut view, 50.0);
+        assert_eq!(view.scroll, 0.0);
+    }
+
+    #[test]
+    fn dragging_stops_coasting() {
+        let mut view = ResultsView::default();
+        view.fling(-600.0);
+        view.drag(0.0, 100.0);
+        assert_eq!(view.velocity, 0.0);
+    }
+
+    #[test]
+    fn rows_are_found_through_the_scroll() {
+        let area = Rect::new(0.0, 100.0, 200.0, 120.0);
+        let mut view = ResultsView::default();
+        assert_eq!(view.row_at(vec2(10.0, 105.0), area, 40.0, 5), Some(0));
+        assert_eq!(view.row_at(vec2(10.0, 215.0), area, 40.0, 5), Some(2));
+        view.scroll = max_scroll(5, 40.0, area);
+        assert_eq!(view.scroll, 80.0);
+        assert_eq!(view.row_at(vec2(10.0, 215.0), area, 40.0, 5), Some(4));
+        assert_eq!(view.row_at(vec2(10.0, 95.0), area, 40.0, 5), None);
+        assert_eq!(view.row_at(vec2(10.0, 105.0), area, 40.0, 1), None);
+    }
+
+    /// Every built-in theme, the colorblind one included.
+    fn themes() -> [Theme; 4] {
+        [
+            Theme::default_theme(),
+            Theme::high_contrast(),
+            Theme::dark(),
+            Theme::colorblind(),
+        ]
+    }
+
+    #[test]
+    fn meaningful_colors_come_with_a_mark() {
+        for theme in themes() {
+            let top = ResultStyle::top(&theme);
+            assert_eq!((top.color, top.mark), (theme.highlight, Some(Mark::Star)));
+            let none = ResultStyle::no_match(&theme);
+            assert_eq!((none.color, none.mark), (theme.error, Some(Mark::Cross)));
+            let plain = ResultStyle::plain(&theme);
+            assert_eq!((plain.color, plain.mark), (theme.result, None));
+        }
+    }
+
+    #[test]
+    fn the_first_row_is_starred_unless_selected() {
+        let theme = Theme::colorblind();
+        assert_eq!(
+            ResultStyle::for_row(0, false, &theme),
+            ResultStyle::top(&theme)
+        );
+        assert_eq!(
+            ResultStyle::for_row(1, false, &theme),
+            ResultStyle::plain(&theme)
+        );
+        // The selection outline backs up its color instead
+        for index in [0, 3] {
+            let selected = ResultStyle::for_row(index, true, &theme);
+            assert_eq!((selected.color, selected.mark), (theme.highlight, None));
+        }
+    }
+
+    #[test]
+    fn the_colorblind_theme_keeps_its_colors_apart() {
+        let theme = Theme::colorblind();
+        let colors = [theme.result, theme.highlight, theme.error, theme.background];
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
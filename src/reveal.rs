@@ -0,0 +1,217 @@
+//! Locking the answers away, for solving against someone.
+//!
+//! Ctrl+L locks the answers: until they're unlocked, no word the entry could
+//! be gets out. The screen shows a padlock and how many matches there are in
+//! place of the answer, the list, the hints, and the pins, and offers no words
+//! to complete the entry; the window title,
+//! the announcements, and what spectators see leave the words out; and the
+//! actions that would show, copy, or print one say the answers are locked
+//! instead. Unlocking takes holding Ctrl+L for `UNLOCK_HOLD` seconds, so a
+//! stray press doesn't give the answer away; a ring round the padlock fills
+//! meanwhile.
+//!
+//! Each of those paths asks the one `PresentationPolicy` what it may show.
+
+use crate::keymap::Action;
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+/// Seconds the lock key must be held to unlock the answers.
+pub const UNLOCK_HOLD: f64 = 1.0;
+/// Line segments drawn for the padlock's shackle and the ring round it.
+const ARC_SEGMENTS: usize = 24;
+
+/// Whether the answers are locked, and the hold unlocking them.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RevealLock {
+    locked: bool,
+    /// When the lock key went down, while it's held to unlock.
+    held_since: Option<f64>,
+}
+
+impl RevealLock {
+    /// Locks the answers if they aren't, or else starts the hold that unlocks
+    /// them at `now`. Returns whether it locked them.
+    pub fn press(&mut self, now: f64) -> bool {
+        if !self.locked {
+            self.locked = true;
+            return true;
+        }
+        // A repeating key doesn't start the hold over
+        self.held_since.get_or_insert(now);
+        false
+    }
+    /// Notes that the lock key came up, cutting the hold short.
+    pub fn release(&mut self) {
+        self.held_since = None;
+    }
+    /// Unlocks the answers once the key's been held `UNLOCK_HOLD` seconds by
+    /// `now`, returning whether it just did.
+    pub fn tick(&mut self, now: f64) -> bool {
+        match self.held_since {
+            Some(since) if now - since >= UNLOCK_HOLD => {
+                *self = Self::default();
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Returns whether the answers are locked.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+    /// Returns whether the key's being held to unlock the answers.
+    pub fn is_held(&self) -> bool {
+        self.held_since.is_some()
+    }
+    /// Returns how far through the hold `now` is, from 0.0 to 1.0, while the
+    /// key's held.
+    pub fn hold_progress(&self, now: f64) -> Option<f32> {
+        let since = self.held_since?;
+        Some(((now - since) / UNLOCK_HOLD).clamp(0.0, 1.0) as f32)
+    }
+    /// Returns what may be shown while the lock is as it is.
+    pub fn policy(&self) -> PresentationPolicy {
+        PresentationPolicy {
+            locked: self.locked,
+        }
+    }
+}
+
+/// What may be shown of the words the entry could be, asked by everything
+/// that could show one: the screen, the window title, the announcements, what
+/// spectators see, and the actions that copy, print, or open a word.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PresentationPolicy {
+    locked: bool,
+}
+
+impl PresentationPolicy {
+    /// Returns whether words may be shown at all; when they can't, only how
+    /// many matches there are is.
+    pub fn shows_words(self) -> bool {
+        !self.locked
+    }
+    /// Returns `word` if it may be shown.
+    pub fn word<T>(self, word: Option<T>) -> Option<T> {
+        word.filter(|_| self.shows_words())
+    }
+    /// Returns `words` if they may be shown, or else none.
+    pub fn words<T>(self, words: &[T]) -> &[T] {
+        match self.shows_words() {
+            true => words,
+            false => &[],
+        }
+    }
+    /// Returns whether `action` may run: those that would show, copy, print,
+    /// or type a word can't while the answers are locked.
+    pub fn allows(self, action: Action) -> bool {
+        let shows_word = matches!(
+            action,
+            Action::CopyAnswer
+                | Action::ConfirmAnswer
+                | Action::ShowHint
+                | Action::ExplainRanking
+                | Action::InspectWord
+                | Action::TogglePin
+                | Action::Complete
+                | Action::PrintResults
+        );
+        self.shows_words() || !shows_word
+    }
+}
+
+/// Draws a padlock `size` high centered on `center`, with a ring round it
+/// filled `hold` of the way, clockwise from the top, while it's being held
+/// open.
+pub fn draw_padlock(gfx: &dyn Renderer, center: Vec2, size: f32, hold: Option<f32>, theme: &Theme) {
+    let thickness = (size / 10.0).max(1.0);
+    let body = Rect::new(
+        center.x - size * 0.35,
+        center.y - size * 0.1,
+        size * 0.7,
+        size * 0.6,
+    );
+    gfx.rect(body, theme.highlight);
+    // The shackle: an arch over the body, its legs down to it
+    let radius = size * 0.22;
+    let top = vec2(center.x, body.y - size * 0.08);
+    let point = |i: usize| {
+        let angle = TAU / 2.0 * (1.0 + i as f32 / ARC_SEGMENTS as f32);
+        top + vec2(angle.cos(), angle.sin()) * radius
+    };
+    for i in 0..ARC_SEGMENTS {
+        gfx.line(point(i), point(i + 1), thickness, theme.highlight);
+    }
+    for x in [top.x - radius, top.x + radius] {
+        gfx.line(vec2(x, top.y), vec2(x, body.y), thickness, theme.highlight);
+    }
+    gfx.circle(body.center(), size * 0.07, theme.background);
+
+    let Some(hold) = hold else {
+        return;
+    };
+    let ring = size * 0.75;
+    let point = |i: usize| {
+        let angle = TAU * i as f32 / ARC_SEGMENTS as f32 - TAU / 4.0;
+        center + vec2(angle.cos(), angle.sin()) * ring
+    };
+    let filled = (hold.clamp(0.0, 1.0) * ARC_SEGMENTS as f32).ceil() as usize;
+    for i in 0..filled {
+        gfx.line(point(i), point(i + 1), thickness, theme.entry_text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_press_locks_and_only_a_full_hold_unlocks() {
+        let mut lock = RevealLock::default();
+        assert!(lock.press(0.0) && lock.is_locked());
+        // Let go too soon, and it stays locked
+        assert!(!lock.press(1.0));
+        assert_eq!(lock.hold_progress(1.5), Some(0.5));
+        lock.release();
+        assert!(!lock.tick(3.0) && lock.is_locked());
+        // A repeating key doesn't start the hold over
+        lock.press(4.0);
+        lock.press(4.5);
+        assert!(!lock.tick(4.9));
+        assert!(lock.tick(4.0 + UNLOCK_HOLD));
+        assert!(!lock.is_locked() && !lock.is_held());
+    }
+
+    #[test]
+    fn a_locked_policy_lets_no_word_out() {
+        let mut lock = RevealLock::default();
+        let open = lock.policy();
+        assert_eq!(open.word(Some("POUND")), Some("POUND"));
+        assert_eq!(open.words(&["GRATE", "GREAT"]), ["GRATE", "GREAT"]);
+        assert!(Action::ALL.into_iter().all(|action| open.allows(action)));
+
+        lock.press(0.0);
+        let locked = lock.policy();
+        assert!(!locked.shows_words());
+        assert_eq!(locked.word(Some("POUND")), None);
+        assert!(locked.words(&["GRATE", "GREAT"]).is_empty());
+        for action in [
+            Action::CopyAnswer,
+            Action::ConfirmAnswer,
+            Action::ShowHint,
+            Action::ExplainRanking,
+            Action::InspectWord,
+            Action::TogglePin,
+            Action::Complete,
+            Action::PrintResults,
+        ] {
+            assert!(!locked.allows(action), "{}", action.name());
+        }
+        // Typing, and holding the lock open, still work
+        assert!(locked.allows(Action::DeleteBack));
+        assert!(locked.allows(Action::ToggleRevealLock));
+    }
+}
@@ -0,0 +1,99 @@
+//! Top-level screens and the transitions between them.
+//!
+//! Each screen owns its own state and reports when it's done by returning a
+//! `Transition`; `App` applies them. A screen can be pushed over another (e.g.
+//! settings over the entry), which resumes where it was once the top one closes.
+//! Anything screens share lives in the `AppContext` they're handed.
+
+use crate::app::AppContext;
+use crate::attract::AttractScreen;
+use crate::entry_screen::EntryScreen;
+use crate::error_screen::ErrorScreen;
+use crate::events::AppEvent;
+use crate::loading::LoadState;
+use crate::modal::Focus;
+use crate::onboarding::OnboardingScreen;
+use crate::render::Renderer;
+use crate::settings::SettingsScreen;
+use crate::tutorial::TutorialScreen;
+
+/// What every screen does each frame.
+pub trait ScreenTrait {
+    /// Applies one event, returning a transition if it calls for one.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition>;
+    /// Advances the screen by `dt` seconds (`ctx.now` has already advanced).
+    fn update(&mut self, ctx: &mut AppContext, dt: f64) -> Option<Transition>;
+    /// Draws the screen.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer);
+    /// Returns who receives input.
+    fn focus(&self) -> Focus {
+        Focus::Entry
+    }
+    /// Returns whether the screen changes every frame and needs redrawing.
+    fn is_animating(&self) -> bool {
+        false
+    }
+    /// Returns whether the screen is waiting on a timer and needs frames soon.
+    fn is_waiting(&self) -> bool {
+        false
+    }
+    /// Called when the screen above this one closes, to pick up what it changed.
+    fn resume(&mut self, _ctx: &mut AppContext) {}
+}
+
+/// A change of screen, or leaving the app.
+pub enum Transition {
+    /// Replaces the current screen.
+    To(Screen),
+    /// Shows a screen over the current one.
+    Push(Screen),
+    /// Closes the current screen, returning to the one below.
+    Pop,
+    /// Saves and exits.
+    Quit,
+}
+
+/// The screen currently shown.
+pub enum Screen {
+    /// The dictionary is being built; only Quit is accepted.
+    Loading(LoadState),
+    /// Normal word entry.
+    Entry(Box<EntryScreen>),
+    /// Something went wrong that the app can't continue from.
+    Error(ErrorScreen),
+    /// Options, changed in place.
+    Settings(SettingsScreen),
+    /// The first-run walkthrough, ending on the entry screen.
+    Onboarding(Box<OnboardingScreen>),
+    /// A demo playing over the entry screen until there's input again.
+    Attract(Box<AttractScreen>),
+    /// The practice puzzle, over the entry screen.
+    Tutorial(Box<TutorialScreen>),
+}
+
+impl Screen {
+    /// Returns the current screen's state.
+    pub fn inner(&self) -> &dyn ScreenTrait {
+        match self {
+            Screen::Loading(screen) => screen,
+            Screen::Entry(screen) => screen.as_ref(),
+            Screen::Error(screen) => screen,
+            Screen::Settings(screen) => screen,
+            Screen::Onboarding(screen) => screen.as_ref(),
+            Screen::Attract(screen) => screen.as_ref(),
+            Screen::Tutorial(screen) => screen.as_ref(),
+        }
+    }
+    /// Returns the current screen's state, mutably.
+    pub fn inner_mut(&mut self) -> &mut dyn ScreenTrait {
+        match self {
+            Screen::Loading(screen) => screen,
+            Screen::Entry(screen) => screen.as_mut(),
+            Screen::Error(screen) => screen,
+            Screen::Settings(screen) => screen,
+            Screen::Onboarding(screen) => screen.as_mut(),
+            Screen::Attract(screen) => screen.as_mut(),
+            Screen::Tutorial(screen) => screen.as_mut(),
+        }
+    }
+}
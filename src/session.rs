@@ -0,0 +1,184 @@
+//! Autosaving the open tabs, so they can be restored after a crash.
+//!
+//! Every `AUTOSAVE_INTERVAL` seconds the entry screen writes each tab's entry
+//! and search mode under `SESSION_KEY`. A clean shutdown marks `CLEAN_KEY`; a
+//! session found at launch without that mark outlived a crash, and the app offers
+//! to restore it.
+//!
+//! The file, after its envelope line (see `storage`), looks like:
+//!
+//! ```text
+//! active 2
+//! tab exact TEACH
+//! tab sub GREAT
+//! end 2
+//! ```
+//!
+//! `end` repeats the tab count and comes last, so a file cut short is rejected
+//! rather than half restored.
+
+use crate::query::QueryMode;
+use crate::storage::Format;
+
+/// Storage key of the autosaved tabs.
+pub const SESSION_KEY: &str = "session.txt";
+/// Format of `SESSION_KEY`.
+pub const SESSION_FORMAT: Format = Format {
+    name: "session",
+    version: 1,
+};
+/// Storage key of the mark a clean shutdown leaves.
+pub const CLEAN_KEY: &str = "clean-shutdown.txt";
+/// Format of `CLEAN_KEY`.
+pub const CLEAN_FORMAT: Format = Format {
+    name: "clean-shutdown",
+    version: 1,
+};
+/// Contents of `CLEAN_KEY` after a clean shutdown; anything else means the app is
+/// running or crashed.
+pub const CLEAN: &str = "clean";
+/// Seconds between autosaves.
+pub const AUTOSAVE_INTERVAL: f64 = 30.0;
+
+/// One tab as autosaved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedTab {
+    pub entry: String,
+    pub mode: QueryMode,
+}
+
+/// Every open tab as autosaved, and which was in front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub tabs: Vec<SavedTab>,
+    pub active: usize,
+}
+
+impl Session {
+    /// Returns whether every tab is empty, leaving nothing worth restoring.
+    pub fn is_empty(&self) -> bool {
+        self.tabs.iter().all(|tab| tab.entry.is_empty())
+    }
+    /// Writes the session in the file format above.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("active {}\n", self.active + 1);
+        for tab in &self.tabs {
+            let mode = match tab.mode {
+                QueryMode::Exact => "exact",
+                QueryMode::SubAnagram => "sub",
+            };
+            let line = format!("tab {mode} {}", tab.entry);
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text.push_str(&format!("end {}\n", self.tabs.len()));
+        text
+    }
+    /// Parses a session file, out of its envelope, rejecting any that isn't
+    /// complete.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let lines = text.lines();
+        let mut tabs = Vec::new();
+        let mut active = None;
+        let mut end: Option<usize> = None;
+        for line in lines {
+            if end.is_some() {
+                return Err(format!("'{line}' after the end"));
+            }
+            let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match word {
+                "active" => {
+                    let n: usize = rest.parse().map_err(|_| format!("bad tab '{rest}'"))?;
+                    active = Some(n.saturating_sub(1));
+                }
+                "tab" => {
+                    let (mode, entry) = rest.split_once(' ').unwrap_or((rest, ""));
+                    let mode = match mode {
+                        "exact" => QueryMode::Exact,
+                        "sub" => QueryMode::SubAnagram,
+                        _ => return Err(format!("bad mode '{mode}'")),
+                    };
+                    tabs.push(SavedTab {
+                        entry: entry.to_owned(),
+                        mode,
+                    });
+                }
+                "end" => end = Some(rest.parse().map_err(|_| format!("bad count '{rest}'"))?),
+                _ => return Err(format!("unexpected line '{line}'")),
+            }
+        }
+        match end {
+            Some(count) if count == tabs.len() && count > 0 => (),
+            Some(count) => return Err(format!("expected {count} tabs, found {}", tabs.len())),
+            None => return Err("cut short: no end line".into()),
+        }
+        let active = active.ok_or("no active tab")?;
+        if active >= tabs.len() {
+            return Err(format!("active tab {} of {}", active + 1, tabs.len()));
+        }
+        Ok(Self { tabs, active })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session {
+            tabs: vec![
+                SavedTab {
+                    entry: "TEACH".to_owned(),
+                    mode: QueryMode::Exact,
+                },
+                SavedTab {
+                    entry: String::new(),
+                    mode: QueryMode::Exact,
+                },
+                SavedTab {
+                    entry: "GREAT".to_owned(),
+                    mode: QueryMode::SubAnagram,
+                },
+            ],
+            active: 2,
+        }
+    }
+
+    #[test]
+    fn sessions_read_back_as_written() {
+        let text = session().to_text();
+        assert_eq!(
+            text,
+            "active 3\ntab exact TEACH\ntab exact\ntab sub GREAT\nend 3\n"
+        );
+        assert_eq!(Session::parse(&text), Ok(session()));
+    }
+
+    #[test]
+    fn a_file_cut_short_anywhere_is_rejected() {
+        let text = session().to_text();
+        // Only the final newline can go
+        for cut in 0..text.len() - 1 {
+            assert!(Session::parse(&text[..cut]).is_err(), "{:?}", &text[..cut]);
+        }
+    }
+
+    #[test]
+    fn damaged_files_are_rejected() {
+        let parse = Session::parse;
+        assert!(parse("active 1\ntab fuzzy TEACH\nend 1\n").is_err());
+        assert!(parse("active 2\ntab exact TEACH\nend 1\n").is_err());
+        assert!(parse("tab exact TEACH\nend 1\n").is_err());
+        assert!(parse("active 1\nend 0\n").is_err());
+        assert!(parse("active 1\ntab exact TEACH\nend 1\ntab exact MORE\n").is_err());
+        assert!(parse("active 1\ntab exact TEACH\nend 1\n").is_ok());
+    }
+
+    #[test]
+    fn only_tabs_with_letters_are_worth_restoring() {
+        assert!(!session().is_empty());
+        let mut blank = session();
+        blank.tabs.iter_mut().for_each(|tab| tab.entry.clear());
+        assert!(blank.is_empty());
+    }
+}
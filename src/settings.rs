@@ -0,0 +1,769 @@
+//! The settings screen: the main options, changed in place and saved on leaving.
+//!
+//! Options are a declarative list of `SettingItem`s, each reading and writing its
+//! value through the `AppContext`, so adding one is an entry in `setting_items`.
+//! Changes apply as soon as they're made; a change that can't be applied (e.g. a
+//! word list that won't load) leaves the old value and shows why.
+
+use crate::app::AppContext;
+use crate::assist::{MAX_ASSIST_SECONDS, MIN_ASSIST_SECONDS};
+use crate::dictionary::{prepare_words, try_load_words};
+use crate::events::{AppEvent, Nav};
+use crate::frequency::load_frequencies;
+use crate::keymap::Action;
+use crate::letters::letter_choices;
+use crate::pointer::PointerEvent;
+use crate::rank::{rank, RankWeights};
+use crate::render::Renderer;
+use crate::results::{draw_mark, Mark};
+use crate::screen::{ScreenTrait, Transition};
+use crate::strings::{fill, StringKey};
+use crate::style::{AppStyle, MAX_TEXT_SCALE, MIN_TEXT_SCALE, TEXT_SCALE_STEP};
+use crate::toast::{Severity, TOAST_TIME};
+use crate::typo::KeyboardLayout;
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use macroquad::prelude::*;
+
+/// A setting's value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingValue {
+    Toggle(bool),
+    /// Index into the setting's options.
+    Choice(usize),
+    Slider(f32),
+}
+
+/// How a setting is shown and changed.
+pub enum SettingKind {
+    /// On or off.
+    Toggle,
+    /// One of a list of named options.
+    Choice(fn(&AppContext) -> Vec<String>),
+    /// A number in `min..=max`, changed by `step` and shown as a percentage.
+    Slider { min: f32, max: f32, step: f32 },
+    /// Like `Slider`, but shown as the number itself.
+    Number { min: f32, max: f32, step: f32 },
+    /// Does something when chosen; it has no value to show.
+    Button,
+}
+
+/// One line of the settings screen.
+pub struct SettingItem {
+    pub label: StringKey,
+    pub kind: SettingKind,
+    pub get: fn(&AppContext) -> SettingValue,
+    /// Applies a value, or explains why it can't be, leaving the old one.
+    pub set: fn(&mut AppContext, SettingValue) -> Result<(), String>,
+}
+
+impl SettingItem {
+    /// Returns the value one step forward (or back) from the current one.
+    fn stepped(&self, ctx: &AppContext, forward: bool) -> SettingValue {
+        match ((self.get)(ctx), &self.kind) {
+            (value, SettingKind::Button) => value,
+            (SettingValue::Toggle(on), _) => SettingValue::Toggle(!on),
+            (SettingValue::Choice(i), SettingKind::Choice(options)) => {
+                let n = options(ctx).len().max(1);
+                SettingValue::Choice(if forward {
+                    (i + 1) % n
+                } else {
+                    (i + n - 1) % n
+                })
+            }
+            (
+                SettingValue::Slider(v),
+                &(SettingKind::Slider { min, max, step } | SettingKind::Number { min, max, step }),
+            ) => {
+                let v = if forward { v + step } else { v - step };
+                // Snap to the step so repeated changes don't drift
+                SettingValue::Slider(((v / step).round() * step).clamp(min, max))
+            }
+            (value, _) => value,
+        }
+    }
+    /// Steps the setting forward or back and applies it, returning why it was
+    /// refused, if it was.
+    pub fn change(&self, ctx: &mut AppContext, forward: bool) -> Option<String> {
+        match (self.set)(ctx, self.stepped(ctx, forward)) {
+            Ok(()) => {
+                ctx.state.config_changed();
+                None
+            }
+            Err(e) => {
+                log::warn!("[SettingItem::change] {:?} refused: {e}", self.label);
+                let lang = ctx.state.config.language;
+                Some(fill(lang.text(StringKey::DictionaryRejected), e))
+            }
+        }
+    }
+    /// Returns the current value as shown to the user.
+    pub fn display(&self, ctx: &AppContext) -> String {
+        let lang = ctx.state.config.language;
+        match ((self.get)(ctx), &self.kind) {
+            (SettingValue::Toggle(true), _) => lang.text(StringKey::On).to_owned(),
+            (SettingValue::Toggle(false), _) => lang.text(StringKey::Off).to_owned(),
+            (SettingValue::Choice(i), SettingKind::Choice(options)) => {
+                options(ctx).get(i).cloned().unwrap_or_default()
+            }
+            (SettingValue::Choice(i), _) => i.to_string(),
+            (_, SettingKind::Button) => String::new(),
+            (SettingValue::Slider(v), SettingKind::Number { .. }) => format!("{v:.1}"),
+            (SettingValue::Slider(v), _) => format!("{:.0}%", v * 100.0),
+        }
+    }
+}
+
+/// Returns the settings shown, in order.
+pub fn setting_items() -> Vec<SettingItem> {
+    vec![
+        SettingItem {
+            label: StringKey::SettingDictionary,
+            kind: SettingKind::Choice(dictionary_names),
+            get: |ctx| {
+                let path = ctx.state.config.dictionary.path.as_deref();
+                let choices = dictionary_choices(path);
+                SettingValue::Choice(
+                    choices
+                        .iter()
+                        .position(|c| c.as_deref() == path)
+                        .unwrap_or(0),
+                )
+            },
+            set: set_dictionary,
+        },
+        SettingItem {
+            label: StringKey::SettingTheme,
+            kind: SettingKind::Choice(|ctx| ctx.themes.names()),
+            get: |ctx| SettingValue::Choice(ctx.themes.index()),
+            set: |ctx, value| {
+                if let SettingValue::Choice(i) = value {
+                    ctx.state.config.theme = ctx.themes.select(i).name.clone();
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingTextSize,
+            kind: SettingKind::Slider {
+                min: MIN_TEXT_SCALE,
+                max: MAX_TEXT_SCALE,
+                step: TEXT_SCALE_STEP,
+            },
+            get: |ctx| SettingValue::Slider(ctx.style.scale),
+            set: |ctx, value| {
+                if let SettingValue::Slider(scale) = value {
+                    ctx.style = AppStyle::new(scale, ctx.style.dpi);
+                    ctx.state.config.text_scale = ctx.style.scale;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingSound,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(!ctx.sounds.muted),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.sounds.muted = !on;
+                    ctx.state.config.muted = !on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingVolume,
+            kind: SettingKind::Slider {
+                min: 0.0,
+                max: 1.0,
+                step: 0.1,
+            },
+            get: |ctx| SettingValue::Slider(ctx.sounds.volume),
+            set: |ctx, value| {
+                if let SettingValue::Slider(volume) = value {
+                    ctx.sounds.volume = volume;
+                    ctx.state.config.volume = volume;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingBigMode,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.big_mode),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.big_mode = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingReducedMotion,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.reduced_motion),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.reduced_motion = Some(on);
+                    ctx.reduced_motion = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingPhonetic,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.phonetic),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.phonetic = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingCopyPhonetic,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.copy_phonetic),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.copy_phonetic = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingAutoEscalate,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.auto_escalate),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.auto_escalate = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingMaskWords,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.mask_words),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.mask_words = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingAssist,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.assist),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.assist = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingAssistSeconds,
+            kind: SettingKind::Number {
+                min: MIN_ASSIST_SECONDS,
+                max: MAX_ASSIST_SECONDS,
+                step: 5.0,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.assist_seconds),
+            set: |ctx, value| {
+                if let SettingValue::Slider(seconds) = value {
+                    ctx.state.config.assist_seconds = seconds;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingUsageMetrics,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.usage_metrics),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.usage_metrics = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingKeyboard,
+            kind: SettingKind::Toggle,
+            get: |ctx| SettingValue::Toggle(ctx.state.config.on_screen_keyboard),
+            set: |ctx, value| {
+                if let SettingValue::Toggle(on) = value {
+                    ctx.state.config.on_screen_keyboard = on;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingKeyboardLayout,
+            kind: SettingKind::Choice(|_| {
+                KeyboardLayout::ALL
+                    .iter()
+                    .map(|layout| layout.name().to_owned())
+                    .collect()
+            }),
+            get: |ctx| {
+                let layout = ctx.state.config.keyboard_layout;
+                SettingValue::Choice(
+                    KeyboardLayout::ALL
+                        .iter()
+                        .position(|&l| l == layout)
+                        .unwrap_or(0),
+                )
+            },
+            set: |ctx, value| {
+                if let SettingValue::Choice(i) = value {
+                    ctx.state.config.keyboard_layout = KeyboardLayout::ALL[i];
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingLetters,
+            kind: SettingKind::Choice(|ctx| {
+                let lang = ctx.state.config.language;
+                letter_choices(&ctx.state.letters)
+                    .iter()
+                    .map(|letters| match letters.is_active() {
+                        true => fill(lang.text(StringKey::LettersLeftOut), letters.letters()),
+                        false => lang.text(StringKey::LettersAll).to_owned(),
+                    })
+                    .collect()
+            }),
+            get: |ctx| {
+                let letters = &ctx.state.letters;
+                let choices = letter_choices(letters);
+                SettingValue::Choice(choices.iter().position(|c| c == letters).unwrap_or(0))
+            },
+            set: |ctx, value| {
+                if let SettingValue::Choice(i) = value {
+                    let choices = letter_choices(&ctx.state.letters);
+                    if let Some(letters) = choices.into_iter().nth(i) {
+                        ctx.state.letters = letters;
+                    }
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingRankFrequency,
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: RankWeights::MAX.frequency,
+                step: 0.5,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.ranking.clamped().frequency),
+            set: |ctx, value| {
+                if let SettingValue::Slider(weight) = value {
+                    ctx.state.config.ranking.frequency = weight;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingRankPinned,
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: RankWeights::MAX.pinned,
+                step: 1.0,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.ranking.clamped().pinned),
+            set: |ctx, value| {
+                if let SettingValue::Slider(weight) = value {
+                    ctx.state.config.ranking.pinned = weight;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingRankConfirmed,
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: RankWeights::MAX.confirmed,
+                step: 1.0,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.ranking.clamped().confirmed),
+            set: |ctx, value| {
+                if let SettingValue::Slider(weight) = value {
+                    ctx.state.config.ranking.confirmed = weight;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingRankInflection,
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: RankWeights::MAX.inflection,
+                step: 0.5,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.ranking.clamped().inflection),
+            set: |ctx, value| {
+                if let SettingValue::Slider(weight) = value {
+                    ctx.state.config.ranking.inflection = weight;
+                }
+                Ok(())
+            },
+        },
+        SettingItem {
+            label: StringKey::SettingRankScrabble,
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: RankWeights::MAX.scrabble,
+                step: 0.1,
+            },
+            get: |ctx| SettingValue::Slider(ctx.state.config.ranking.clamped().scrabble),
+            set: |ctx, value| {
+                if let SettingValue::Slider(weight) = value {
+                    ctx.state.config.ranking.scrabble = weight;
+                }
+                Ok(())
+            },
+        },
+        // A button's value is never shown
+        SettingItem {
+            label: StringKey::SettingRankReset,
+            kind: SettingKind::Button,
+            get: |_| SettingValue::Toggle(false),
+            set: |ctx, _| {
+                ctx.state.config.ranking = RankWeights::default();
+                Ok(())
+            },
+        },
+    ]
+}
+
+/// Returns the word lists on offer: the language default (`None`), each
+/// language's own list that's installed, and `current` if it's none of those.
+fn dictionary_choices(current: Option<&str>) -> Vec<Option<String>> {
+    let mut choices = vec![None];
+    #[cfg(not(target_arch = "wasm32"))]
+    choices.extend(
+        crate::strings::Language::ALL
+            .into_iter()
+            .filter_map(crate::strings::Language::dictionary_file)
+            .filter(|file| crate::assets::resolve_asset(file).exists())
+            .map(|file| Some(file.to_owned())),
+    );
+    if let Some(path) = current.filter(|&p| !choices.iter().any(|c| c.as_deref() == Some(p))) {
+        choices.push(Some(path.to_owned()));
+    }
+    choices
+}
+
+/// Returns the names of `dictionary_choices`: file names, or "Default", except
+/// that the list in use goes by the name its header gives, if any.
+fn dictionary_names(ctx: &AppContext) -> Vec<String> {
+    let config = &ctx.state.config;
+    let current = config.dictionary.path.as_deref();
+    let title = ctx.dictionary.as_ref().and_then(|map| map.header().title());
+    dictionary_choices(current)
+        .into_iter()
+        .map(|choice| match (choice, &title) {
+            (choice, Some(title)) if choice.as_deref() == current => title.clone(),
+            (Some(path), _) => match path.rsplit(['/', '\\']).next() {
+                Some(name) if !name.is_empty() => name.to_owned(),
+                _ => path,
+            },
+            (None, _) => config
+                .language
+                .text(StringKey::DefaultDictionary)
+                .to_owned(),
+        })
+        .collect()
+}
+
+/// Loads the chosen word list and switches to it, if it loads and isn't empty.
+fn set_dictionary(ctx: &mut AppContext, value: SettingValue) -> Result<(), String> {
+    let SettingValue::Choice(i) = value else {
+        return Ok(());
+    };
+    let config = &ctx.state.config;
+    let path = dictionary_choices(config.dictionary.path.as_deref())
+        .get(i)
+        .cloned()
+        .flatten();
+    let map = build_word_map(path.as_deref(), ctx)?;
+    log::info!("[set_dictionary] switched to {path:?}");
+    ctx.set_dictionary(map);
+    ctx.state.config.dictionary.path = path;
+    let loaded = ctx.state.config.language.text(StringKey::DictionaryLoaded);
+    ctx.toasts
+        .push(loaded, Severity::Success, TOAST_TIME, ctx.now);
+    Ok(())
+}
+
+/// Builds the word map for the list at `path`, failing if it's unreadable or empty.
+fn build_word_map(path: Option<&str>, ctx: &AppContext) -> Result<WordMap, String> {
+    let (options, lang) = (&ctx.state.config.dictionary, ctx.state.config.language);
+    let text = try_load_words(path, options, lang)?;
+    let (header, words) = prepare_words(&text)?;
+    let alphabet = options.alphabet_for(&header);
+    let mut map =
+        make_word_map_with_progress(&words, alphabet, options.max_words, usize::MAX, |_| ());
+    map.set_header(header);
+    map.set_frequencies(load_frequencies(&text, options, &map));
+    if map.is_empty() {
+        return Err(lang.text(StringKey::EmptyDictionary).to_owned());
+    }
+    Ok(map)
+}
+
+/// Returns whether the setting labeled `label` changes how matches rank.
+fn changes_ranking(label: StringKey) -> bool {
+    matches!(
+        label,
+        StringKey::SettingRankFrequency
+            | StringKey::SettingRankPinned
+            | StringKey::SettingRankConfirmed
+            | StringKey::SettingRankInflection
+            | StringKey::SettingRankScrabble
+            | StringKey::SettingRankReset
+    )
+}
+
+/// Most matches named in the ranking preview.
+const PREVIEW_WORDS: usize = 5;
+
+/// State of the settings screen.
+pub struct SettingsScreen {
+    items: Vec<SettingItem>,
+    selected: usize,
+    /// The first setting shown, when they don't all fit.
+    first: usize,
+    /// Why the last change was refused, shown until the next change.
+    error: Option<String>,
+    /// The entry's matches, shown in ranked order while a ranking weight is
+    /// selected.
+    preview: Vec<String>,
+}
+
+impl SettingsScreen {
+    /// Creates the screen with the first setting selected.
+    pub fn new() -> Self {
+        Self::with_preview(Vec::new())
+    }
+    /// Creates the screen, previewing how `matches` rank as the ranking weights
+    /// change.
+    pub fn with_preview(matches: Vec<String>) -> Self {
+        Self {
+            items: setting_items(),
+            selected: 0,
+            first: 0,
+            error: None,
+            preview: matches,
+        }
+    }
+
+    /// Steps the selected setting forward or back and applies it.
+    fn change(&mut self, ctx: &mut AppContext, forward: bool) {
+        self.error = self.items[self.selected].change(ctx, forward);
+    }
+
+    /// Returns the text size and the rectangle of row `i`.
+    fn row(&self, ctx: &AppContext, i: usize) -> (u16, Rect) {
+        let size = ctx.style.result_size * 2 / 3;
+        let row_h = size as f32 * 1.6;
+        let top = size as f32 * 3.0;
+        let rect = Rect::new(
+            ctx.size.x * 0.08,
+            top + i as f32 * row_h,
+            ctx.size.x * 0.84,
+            row_h,
+        );
+        (size, rect)
+    }
+    /// Returns how many settings fit on screen at once, leaving room under them
+    /// for an error, the key hint, and the ranking preview.
+    fn visible_rows(&self, ctx: &AppContext) -> usize {
+        let (_, first) = self.row(ctx, 0);
+        let rows = ((ctx.size.y - first.y) / first.h).max(0.0) as usize;
+        rows.saturating_sub(3).clamp(1, self.items.len())
+    }
+    /// Returns the first setting shown: the one last scrolled to, moved just
+    /// enough to show the selected one.
+    fn first_row(&self, ctx: &AppContext) -> usize {
+        let visible = self.visible_rows(ctx);
+        let lowest = (self.selected + 1).saturating_sub(visible);
+        self.first.min(self.selected).max(lowest)
+    }
+    /// Returns the preview of how the entry's matches rank, while a ranking
+    /// weight is selected and there's more than one.
+    fn ranking_preview(&self, ctx: &AppContext) -> Option<String> {
+        let dictionary = ctx.dictionary.as_ref()?;
+        if !changes_ranking(self.items[self.selected].label) || self.preview.len() < 2 {
+            return None;
+        }
+        let is_pinned = |word: &str| ctx.state.pins.contains(word);
+        let is_confirmed = |word: &str| ctx.state.confirmed.contains(word);
+        let ranked = rank(
+            &self.preview,
+            dictionary,
+            is_pinned,
+            is_confirmed,
+            &ctx.state.config.ranking,
+        );
+        let words: Vec<&str> = ranked
+            .iter()
+            .take(PREVIEW_WORDS)
+            .map(|r| self.preview[r.index].as_str())
+            .collect();
+        let lang = ctx.state.config.language;
+        Some(fill(lang.text(StringKey::RankPreview), words.join(", ")))
+    }
+}
+
+impl Default for SettingsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScreenTrait for SettingsScreen {
+    /// Escape goes back to the previous screen, saving the config on the way.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        let count = self.items.len();
+        match ev {
+            AppEvent::CloseRequested => return Some(Transition::Quit),
+            AppEvent::Action(Action::Quit | Action::OpenSettings) => {
+                let lang = ctx.state.config.language;
+                let (text, severity) = if ctx.state.save() {
+                    (lang.text(StringKey::SettingsSaved), Severity::Info)
+                } else {
+                    (lang.text(StringKey::SettingsNotSaved), Severity::Error)
+                };
+                ctx.toasts.push(text, severity, TOAST_TIME, ctx.now);
+                return Some(Transition::Pop);
+            }
+            AppEvent::KeyActivity => ctx.dirty = true,
+            AppEvent::Nav(Nav::Up) => {
+                self.selected = (self.selected + count - 1) % count;
+                self.first = self.first_row(ctx);
+            }
+            AppEvent::Nav(Nav::Down) => {
+                self.selected = (self.selected + 1) % count;
+                self.first = self.first_row(ctx);
+            }
+            AppEvent::Nav(Nav::Left) => self.change(ctx, false),
+            AppEvent::Nav(Nav::Right | Nav::Select) => self.change(ctx, true),
+            // Tapping a row selects it and steps it forward
+            AppEvent::Pointer(PointerEvent::Released(pos)) => {
+                let first = self.first_row(ctx);
+                let shown = self.visible_rows(ctx).min(count - first);
+                if let Some(slot) = (0..shown).find(|&i| self.row(ctx, i).1.contains(pos)) {
+                    self.selected = first + slot;
+                    self.first = first;
+                    self.change(ctx, true);
+                    ctx.dirty = true;
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+    fn update(&mut self, _ctx: &mut AppContext, _dt: f64) -> Option<Transition> {
+        None
+    }
+    /// Draws the title, a row for each setting that fits with the selected one
+    /// outlined, any error, the key hint, and the ranking preview.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        gfx.clear(theme.background);
+
+        let (size, first) = self.row(ctx, 0);
+        let title = lang.text(StringKey::SettingsTitle);
+        gfx.text(
+            title,
+            vec2(first.x, size as f32 * 2.0),
+            font,
+            size * 3 / 2,
+            theme.entry_text,
+        );
+
+        let baseline = |rect: Rect| rect.y + rect.h / 2.0 + size as f32 / 3.0;
+        let first = self.first_row(ctx);
+        let shown = self.visible_rows(ctx).min(self.items.len() - first);
+        for (slot, item) in self.items[first..first + shown].iter().enumerate() {
+            let (i, (_, rect)) = (first + slot, self.row(ctx, slot));
+            let pad = size as f32 * 0.4;
+            if i == self.selected {
+                gfx.rect_lines(rect, theme.outline_thickness * 2.0, theme.highlight);
+            }
+            let label = lang.text(item.label);
+            gfx.text(
+                label,
+                vec2(rect.x + pad, baseline(rect)),
+                font,
+                size,
+                theme.result,
+            );
+            if matches!(item.kind, SettingKind::Button) {
+                continue;
+            }
+            let value = format!("< {} >", item.display(ctx));
+            let width = gfx.measure(&value, font, size).width;
+            let x = rect.right() - pad - width;
+            gfx.text(
+                &value,
+                vec2(x, baseline(rect)),
+                font,
+                size,
+                theme.entry_text,
+            );
+        }
+
+        let (_, below) = self.row(ctx, shown);
+        if let Some(error) = &self.error {
+            let cy = below.y + below.h / 2.0;
+            draw_mark(
+                gfx,
+                Mark::Cross,
+                below.x + size as f32 / 2.0,
+                cy,
+                size as f32 * 0.6,
+                theme.error,
+            );
+            let pos = vec2(below.x + size as f32 * 1.2, baseline(below));
+            gfx.text(error, pos, font, size * 3 / 4, theme.error);
+        }
+        let hint = lang.text(StringKey::SettingsHint);
+        let pos = vec2(below.x, baseline(below) + below.h);
+        gfx.text(hint, pos, font, size * 3 / 4, theme.result);
+        if let Some(preview) = self.ranking_preview(ctx) {
+            let pos = vec2(below.x, baseline(below) + below.h * 2.0);
+            gfx.text(&preview, pos, font, size * 3 / 4, theme.entry_text);
+        }
+        if let Some(update) = &ctx.update {
+            let version = fill(lang.text(StringKey::UpdateAvailable), &update.version);
+            let text = match update.changelog.as_str() {
+                "" => version,
+                changelog => format!("{version}: {changelog}"),
+            };
+            let pos = vec2(below.x, baseline(below) + below.h * 3.0);
+            gfx.text(&text, pos, font, size * 3 / 4, theme.highlight);
+        }
+    }
+}
+
+/// Draws the gear button that opens the settings screen in `rect`.
+pub fn draw_gear(gfx: &dyn Renderer, rect: Rect, color: Color, background: Color) {
+    let center = rect.center();
+    let r = rect.w.min(rect.h) / 2.0;
+    const TEETH: usize = 8;
+    for i in 0..TEETH {
+        let angle = i as f32 / TEETH as f32 * std::f32::consts::TAU;
+        let dir = vec2(angle.cos(), angle.sin());
+        gfx.line(center + dir * r * 0.5, center + dir * r, r * 0.3, color);
+    }
+    gfx.circle(center, r * 0.7, color);
+    gfx.circle(center, r * 0.3, background);
+}
@@ -0,0 +1,493 @@
+//! Sharing the screen with spectators on the local network.
+//!
+//! With `share_port` and `share_token` set in the config, the app answers
+//! `GET /state` on that port with a stream of server-sent events: a `state`
+//! event carrying the `StateSummary` as JSON whenever it changes (looked at
+//! every `THROTTLE`), and a comment every `KEEPALIVE` so a spectator that's gone
+//! is noticed. A request must carry the token, as `Authorization: Bearer TOKEN`
+//! or `?token=TOKEN`. At most `MAX_SPECTATORS` watch at once, and a request
+//! with a line longer than `MAX_LINE` is refused. `jumble_helper --spectate URL` watches the stream (see
+//! `spectate`), as does anything that reads server-sent events.
+
+use crate::config::Config;
+use crate::crash::StateSummary;
+use arc_swap::ArcSwap;
+use nanoserde::{DeJson, SerJson};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Path of the stream of states.
+pub const STATE_PATH: &str = "/state";
+/// Name of the events carrying a state.
+const STATE_EVENT: &str = "state";
+/// How often each spectator's connection looks for a new state.
+const THROTTLE: Duration = Duration::from_millis(100);
+/// Longest a spectator goes without hearing anything.
+const KEEPALIVE: Duration = Duration::from_secs(15);
+/// How long a spectator has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Most header lines read from a request.
+const MAX_HEADERS: usize = 64;
+/// Longest line of a request, in bytes.
+const MAX_LINE: usize = 4096;
+/// Most spectators streamed to at once; others are turned away.
+const MAX_SPECTATORS: usize = 8;
+
+/// Starts sharing `summary` as the config asks, if it does.
+pub fn start(config: &Config, summary: Arc<ArcSwap<StateSummary>>) {
+    if config.share_port == 0 {
+        return;
+    }
+    let token = config.share_token.clone().filter(|token| !token.is_empty());
+    let Some(token) = token else {
+        log::warn!("[share] share_port is set but share_token isn't; not sharing");
+        return;
+    };
+    match serve(config.share_port, token, summary) {
+        Ok(port) => log::info!("[share] sharing on port {port}"),
+        Err(e) => log::warn!("[share] can't share on port {}: {e}", config.share_port),
+    }
+}
+
+/// Answers spectators on `port` of every interface (0 for any free port), each
+/// on a thread of its own, and returns the port.
+pub fn serve(port: u16, token: String, summary: Arc<ArcSwap<StateSummary>>) -> io::Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    let port = listener.local_addr()?.port();
+    let token = Arc::new(token);
+    let watching = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let Some(seat) = Seat::take(&watching) else {
+                log::info!("[share] turned a spectator away: {MAX_SPECTATORS} already watching");
+                let _ = refuse(stream, "503 Service Unavailable");
+                continue;
+            };
+            let (token, summary) = (Arc::clone(&token), Arc::clone(&summary));
+            std::thread::spawn(move || {
+                if let Err(e) = answer(stream, &token, &summary) {
+                    log::debug!("[share] spectator gone: {e}");
+                }
+                drop(seat);
+            });
+        }
+    });
+    Ok(port)
+}
+
+/// One of the `MAX_SPECTATORS` places, given back when dropped.
+struct Seat(Arc<AtomicUsize>);
+
+impl Seat {
+    /// Takes a place from `watching`, the count of those taken, if one is free.
+    fn take(watching: &Arc<AtomicUsize>) -> Option<Self> {
+        watching
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_SPECTATORS).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(watching)))
+    }
+}
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Answers with `status` and no body, closing the connection.
+fn refuse(mut stream: TcpStream, status: &str) -> io::Result<()> {
+    let head = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(head.as_bytes())?;
+    // Closing with the request unread would reset the connection, losing the answer
+    stream.shutdown(std::net::Shutdown::Write)?;
+    stream.set_read_timeout(Some(THROTTLE))?;
+    let _ = io::copy(
+        &mut stream.take(MAX_HEADERS as u64 * MAX_LINE as u64),
+        &mut io::sink(),
+    );
+    Ok(())
+}
+
+/// Reads a line into `line` as `BufRead::read_line` does, but fails rather than
+/// read more than `MAX_LINE` bytes of it.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let read = reader.by_ref().take(MAX_LINE as u64 + 1).read_line(line)?;
+    if read > MAX_LINE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("request line over {MAX_LINE} bytes"),
+        ));
+    }
+    Ok(read)
+}
+
+/// The parts of a request that matter here.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    /// The path, without its query.
+    path: String,
+    token: Option<String>,
+}
+
+/// Reads a request's line and headers.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let mut line = String::new();
+    read_line(reader, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method,
+        path: path.to_owned(),
+        token: query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(str::to_owned),
+    };
+    for _ in 0..MAX_HEADERS {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim().eq_ignore_ascii_case("authorization") {
+            if let Some(token) = value.trim().strip_prefix("Bearer ") {
+                request.token = Some(token.trim().to_owned());
+            }
+        }
+    }
+    Ok(request)
+}
+
+/// Answers one spectator, streaming states until it goes away.
+fn answer(stream: TcpStream, token: &str, summary: &ArcSwap<StateSummary>) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let request = match read_request(&mut BufReader::new(&stream)) {
+        Ok(request) => request,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            refuse(stream, "431 Request Header Fields Too Large")?;
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+    let mut stream = stream;
+    let refusal = if request.method != "GET" {
+        Some("405 Method Not Allowed")
+    } else if request.path != STATE_PATH {
+        Some("404 Not Found")
+    } else if request.token.as_deref() != Some(token) {
+        Some("401 Unauthorized")
+    } else {
+        None
+    };
+    if let Some(status) = refusal {
+        log::info!("[share] refused a spectator: {status}");
+        return refuse(stream, status);
+    }
+    log::info!(
+        "[share] spectator joined from {:?}",
+        stream.peer_addr().ok()
+    );
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+    )?;
+
+    // A state is stored anew only when it changes, so the same one is never sent twice
+    let mut sent: Option<Arc<StateSummary>> = None;
+    let mut last_write = Instant::now();
+    loop {
+        let current = summary.load_full();
+        if !sent
+            .as_ref()
+            .is_some_and(|sent| Arc::ptr_eq(sent, &current))
+        {
+            let frame = sse_frame(STATE_EVENT, &current.serialize_json());
+            stream.write_all(frame.as_bytes())?;
+            sent = Some(current);
+            last_write = Instant::now();
+        } else if last_write.elapsed() >= KEEPALIVE {
+            stream.write_all(b": keepalive\n\n")?;
+            last_write = Instant::now();
+        }
+        std::thread::sleep(THROTTLE);
+    }
+}
+
+/// Frames `data` as a server-sent event named `event`, with a `data:` line per
+/// line of it.
+pub fn sse_frame(event: &str, data: &str) -> String {
+    let mut frame = format!("event: {event}\n");
+    for line in data.lines() {
+        frame.push_str("data: ");
+        frame.push_str(line);
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
+/// Puts server-sent events back together from the lines of a stream.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SseReader {
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseReader {
+    /// Takes the next line, without its line break, and returns the name and
+    /// data of the event it ends, if it's the blank line ending one.
+    pub fn line(&mut self, line: &str) -> Option<(String, String)> {
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let data = std::mem::take(&mut self.data);
+            return match (event.is_empty(), data.is_empty()) {
+                (_, true) => None,
+                (true, false) => Some(("message".to_owned(), data.join("\n"))),
+                (false, false) => Some((event, data.join("\n"))),
+            };
+        }
+        // Comments, such as keepalives
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = value.to_owned(),
+            "data" => self.data.push(value.to_owned()),
+            _ => (),
+        }
+        None
+    }
+}
+
+/// Splits a spectator's URL, such as `http://192.168.1.20:7070`, into the
+/// address to connect to and the path to ask for (`STATE_PATH` if none).
+pub fn parse_url(url: &str) -> Result<(String, String), String> {
+    if url.starts_with("https://") {
+        return Err("https isn't supported; use http://".to_owned());
+    }
+    let rest = url.strip_prefix("http://").unwrap_or(url);
+    let (host, path) = match rest.find('/') {
+        Some(i) if rest.len() > i + 1 => (&rest[..i], &rest[i..]),
+        Some(i) => (&rest[..i], STATE_PATH),
+        None => (rest, STATE_PATH),
+    };
+    match host {
+        "" => Err(format!("no host in '{url}'")),
+        host if host.contains(':') => Ok((host.to_owned(), path.to_owned())),
+        host => Ok((format!("{host}:80"), path.to_owned())),
+    }
+}
+
+/// Connects to the app sharing at `url` and passes each state it sends to
+/// `on_state`, returning once the connection ends, or why it couldn't watch.
+pub fn watch(url: &str, token: &str, mut on_state: impl FnMut(StateSummary)) -> Result<(), String> {
+    let (host, path) = parse_url(url)?;
+    let io_error = |e: io::Error| e.to_string();
+    let mut stream = TcpStream::connect(&host).map_err(|e| format!("can't connect: {e}"))?;
+    let head = format!(
+        "GET {path} HTTP/1.1\r\nHost: {host}\r\nAccept: text/event-stream\r\n\
+         Authorization: Bearer {token}\r\n\r\n"
+    );
+    stream.write_all(head.as_bytes()).map_err(io_error)?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(io_error)?;
+    match line.split_whitespace().nth(1) {
+        Some("200") => (),
+        Some("401") => return Err("the token was refused".to_owned()),
+        Some(status) => return Err(format!("answered {status}")),
+        None => return Err("no answer".to_owned()),
+    }
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(io_error)? == 0 {
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut events = SseReader::default();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(io_error)? == 0 {
+            return Ok(());
+        }
+        let Some((event, data)) = events.line(line.trim_end_matches(['\r', '\n'])) else {
+            continue;
+        };
+        if event != STATE_EVENT {
+            continue;
+        }
+        match StateSummary::deserialize_json(&data) {
+            Ok(state) => on_state(state),
+            Err(e) => log::warn!("[watch] unreadable state: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "s3cret";
+
+    fn summary(entry: &str) -> StateSummary {
+        StateSummary {
+            entry: entry.to_owned(),
+            ..StateSummary::default()
+        }
+    }
+
+    /// Asks the app sharing on `port` for `target`, returning the status code
+    /// and the stream, past the response's head.
+    fn request(port: u16, target: &str) -> (String, BufReader<TcpStream>) {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        // A refusal may come before the request is sent
+        let _ = write!(stream, "GET {target} HTTP/1.1\r\nHost: test\r\n\r\n");
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let status = line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or_default()
+            .to_owned();
+        while line.trim() != "" || line.is_empty() {
+            line.clear();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+        }
+        (status, reader)
+    }
+
+    /// Reads events off `reader` until the next state.
+    fn next_state(reader: &mut BufReader<TcpStream>) -> StateSummary {
+        let mut events = SseReader::default();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            assert!(reader.read_line(&mut line).unwrap() > 0, "the stream ended");
+            if let Some((event, data)) = events.line(line.trim_end_matches(['\r', '\n'])) {
+                assert_eq!(event, STATE_EVENT);
+                return StateSummary::deserialize_json(&data).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn spectators_with_the_token_are_sent_each_new_state() {
+        let shared = Arc::new(ArcSwap::from_pointee(summary("NUPO")));
+        let port = serve(0, TOKEN.to_owned(), Arc::clone(&shared)).unwrap();
+        let (status, mut reader) = request(port, &format!("{STATE_PATH}?token={TOKEN}"));
+        assert_eq!(status, "200");
+        assert_eq!(next_state(&mut reader), summary("NUPO"));
+        shared.store(Arc::new(summary("TAGER")));
+        assert_eq!(next_state(&mut reader), summary("TAGER"));
+    }
+
+    #[test]
+    fn requests_without_the_token_or_path_are_refused() {
+        let shared = Arc::new(ArcSwap::from_pointee(summary("")));
+        let port = serve(0, TOKEN.to_owned(), shared).unwrap();
+        assert_eq!(request(port, STATE_PATH).0, "401");
+        assert_eq!(request(port, &format!("{STATE_PATH}?token=guess")).0, "401");
+        assert_eq!(request(port, &format!("/other?token={TOKEN}")).0, "404");
+        let long = format!("{STATE_PATH}?token={TOKEN}&pad={}", "x".repeat(MAX_LINE));
+        assert_eq!(request(port, &long).0, "431");
+    }
+
+    #[test]
+    fn spectators_past_the_most_are_turned_away() {
+        let shared = Arc::new(ArcSwap::from_pointee(summary("NUPO")));
+        let port = serve(0, TOKEN.to_owned(), Arc::clone(&shared)).unwrap();
+        let target = format!("{STATE_PATH}?token={TOKEN}");
+        let mut watching: Vec<_> = (0..MAX_SPECTATORS)
+            .map(|_| {
+                let (status, mut reader) = request(port, &target);
+                assert_eq!(status, "200");
+                next_state(&mut reader);
+                reader
+            })
+            .collect();
+        assert_eq!(request(port, &target).0, "503");
+        // A spectator leaving frees a place, once a write to it fails
+        drop(watching.pop());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        for n in 0.. {
+            shared.store(Arc::new(summary(&n.to_string())));
+            std::thread::sleep(THROTTLE * 2);
+            if request(port, &target).0 == "200" {
+                break;
+            }
+            assert!(Instant::now() < deadline, "no place was freed");
+        }
+    }
+
+    #[test]
+    fn the_token_is_read_from_the_query_or_the_authorization_header() {
+        let read = |text: &str| read_request(&mut text.as_bytes()).unwrap();
+        let request = read("GET /state?a=1&token=abc HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert_eq!(
+            (request.path.as_str(), request.token.as_deref()),
+            ("/state", Some("abc"))
+        );
+        let request = read("GET /state HTTP/1.1\r\nauthorization:  Bearer abc \r\n\r\n");
+        assert_eq!(request.token.as_deref(), Some("abc"));
+        assert_eq!(read("GET /state HTTP/1.1\r\n\r\n").token, None);
+        let long = format!("GET /state HTTP/1.1\r\nX: {}\r\n\r\n", "x".repeat(MAX_LINE));
+        let e = read_request(&mut long.as_bytes()).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn events_read_back_as_framed() {
+        let mut events = SseReader::default();
+        let frame = sse_frame("state", "{\"a\":1}\nsecond");
+        let mut read = frame.lines().filter_map(|line| events.line(line));
+        assert_eq!(
+            read.next(),
+            Some(("state".to_owned(), "{\"a\":1}\nsecond".to_owned()))
+        );
+        assert_eq!(events.line(": keepalive"), None);
+        assert_eq!(events.line(""), None);
+        assert_eq!(events.line("data: plain"), None);
+        assert_eq!(
+            events.line(""),
+            Some(("message".to_owned(), "plain".to_owned()))
+        );
+    }
+
+    #[test]
+    fn urls_name_a_host_and_a_path() {
+        let parsed = |url| parse_url(url).unwrap();
+        assert_eq!(
+            parsed("http://10.0.0.2:7070"),
+            ("10.0.0.2:7070".into(), STATE_PATH.into())
+        );
+        assert_eq!(
+            parsed("10.0.0.2/"),
+            ("10.0.0.2:80".into(), STATE_PATH.into())
+        );
+        assert_eq!(parsed("host:1/feed"), ("host:1".into(), "/feed".into()));
+        assert!(parse_url("https://host").is_err());
+        assert!(parse_url("http:///state").is_err());
+    }
+}
@@ -0,0 +1,431 @@
+//! Golden snapshots of what the app draws.
+//!
+//! `jumble_helper snapshot` draws a few canonical states with a
+//! `RecordingRenderer`, at two window sizes, and compares the draw calls with the
+//! YAML files committed under `snapshots/`. Coordinates may differ by
+//! `TOLERANCE` pixels, so float jitter doesn't fail the check. Set `JUMBLE_BLESS=1`
+//! to write the files instead, after a layout change that's meant to be.
+
+use crate::app::{App, AppState};
+use crate::config::Config;
+use crate::debug::DEBUG_OVERLAY_DEFAULT;
+use crate::events::AppEvent;
+use crate::keymap::{Action, Keymap};
+use crate::record::{wait_for_load, wall_clock, MAX_STEP};
+use crate::render::{fixed_measure, DrawCall, RecordingRenderer};
+use crate::sound::Sounds;
+use crate::storage::MemoryStorage;
+use macroquad::prelude::*;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Pixels a coordinate may move before a snapshot no longer matches.
+pub const TOLERANCE: f32 = 0.5;
+/// Wall-clock seconds to wait for queries and animations to finish.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Window sizes each state is drawn at: landscape and portrait.
+const SIZES: [(u32, u32); 2] = [(800, 600), (600, 900)];
+
+/// A state of the app worth keeping an eye on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotState {
+    EmptyEntry,
+    /// An entry with exactly one answer.
+    UniqueAnswer,
+    /// Sub-anagram mode with ten matches.
+    TenMatches,
+    /// The error shown for an empty dictionary.
+    ErrorScreen,
+}
+
+impl SnapshotState {
+    pub const ALL: [SnapshotState; 4] = [
+        SnapshotState::EmptyEntry,
+        SnapshotState::UniqueAnswer,
+        SnapshotState::TenMatches,
+        SnapshotState::ErrorScreen,
+    ];
+
+    /// Returns the name used in snapshot file names.
+    pub fn name(self) -> &'static str {
+        match self {
+            SnapshotState::EmptyEntry => "empty_entry",
+            SnapshotState::UniqueAnswer => "unique_answer",
+            SnapshotState::TenMatches => "ten_matches",
+            SnapshotState::ErrorScreen => "error_screen",
+        }
+    }
+    /// Returns the events that lead from a freshly loaded app to this state.
+    fn events(self) -> Vec<AppEvent> {
+        let letters = |word: &str| word.chars().map(AppEvent::Letter).collect::<Vec<_>>();
+        match self {
+            SnapshotState::EmptyEntry | SnapshotState::ErrorScreen => Vec::new(),
+            SnapshotState::UniqueAnswer => letters("QUACK"),
+            SnapshotState::TenMatches => {
+                let mut events = vec![AppEvent::Action(Action::ToggleSubAnagrams)];
+                events.extend(letters("GREAT"));
+                events
+            }
+        }
+    }
+}
+
+/// A value in a snapshot: a coordinate, size, or other number, or text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Num(f32),
+    Str(String),
+}
+
+/// One draw call as named fields, starting with `op`.
+pub type Shape = Vec<(String, Value)>;
+
+/// Returns `call` as the fields written to a snapshot.
+pub fn shape(call: &DrawCall) -> Shape {
+    let num = |name: &str, v: f32| (name.to_owned(), Value::Num(v));
+    let text = |name: &str, s: String| (name.to_owned(), Value::Str(s));
+    let op = |name: &str| text("op", name.to_owned());
+    let color = |c: &Color| text("color", color_hex(*c));
+    let rect = |r: &Rect| [num("x", r.x), num("y", r.y), num("w", r.w), num("h", r.h)];
+    let point = |p: &str, v: Vec2| [num(&format!("{p}x"), v.x), num(&format!("{p}y"), v.y)];
+
+    let mut fields = Vec::new();
+    match call {
+        DrawCall::Clear { color: c } => fields.extend([op("clear"), color(c)]),
+        DrawCall::Rect { rect: r, color: c } => {
+            fields.push(op("rect"));
+            fields.extend(rect(r));
+            fields.push(color(c));
+        }
+        DrawCall::RectLines {
+            rect: r,
+            thickness,
+            color: c,
+        } => {
+            fields.push(op("rect_lines"));
+            fields.extend(rect(r));
+            fields.extend([num("thickness", *thickness), color(c)]);
+        }
+        DrawCall::Line {
+            from,
+            to,
+            thickness,
+            color: c,
+        } => {
+            fields.push(op("line"));
+            fields.extend(point("from_", *from));
+            fields.extend(point("to_", *to));
+            fields.extend([num("thickness", *thickness), color(c)]);
+        }
+        DrawCall::Circle {
+            center,
+            radius,
+            color: c,
+        } => {
+            fields.push(op("circle"));
+            fields.extend(point("", *center));
+            fields.extend([num("radius", *radius), color(c)]);
+        }
+        DrawCall::Triangle {
+            a,
+            b,
+            c,
+            color: col,
+        } => {
+            fields.push(op("triangle"));
+            fields.extend(point("a", *a));
+            fields.extend(point("b", *b));
+            fields.extend(point("c", *c));
+            fields.push(color(col));
+        }
+        DrawCall::Text {
+            text: t,
+            pos,
+            size,
+            color: c,
+        } => {
+            fields.extend([op("text"), text("text", t.clone())]);
+            fields.extend(point("", *pos));
+            fields.extend([num("size", *size as f32), color(c)]);
+        }
+    }
+    fields
+}
+
+/// Returns `color` as `#rrggbbaa`.
+fn color_hex(color: Color) -> String {
+    let [r, g, b, a]: [u8; 4] = color.into();
+    format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+}
+
+/// Writes `shapes` as YAML: a list with one flow mapping per draw call.
+pub fn to_yaml(title: &str, shapes: &[Shape]) -> String {
+    let mut out = format!("# {title}\n");
+    for fields in shapes {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| match value {
+                // Rounded so jitter below the tolerance doesn't churn the files
+                Value::Num(v) => format!("{name}: {}", (v * 100.0).round() / 100.0 + 0.0),
+                Value::Str(s) => format!("{name}: {s:?}"),
+            })
+            .collect();
+        out.push_str(&format!("- {{{}}}\n", fields.join(", ")));
+    }
+    out
+}
+
+/// Reads snapshot YAML written by `to_yaml`.
+pub fn from_yaml(text: &str) -> Result<Vec<Shape>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| parse_shape(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+fn parse_shape(line: &str) -> Result<Shape, String> {
+    let mut rest = line
+        .trim()
+        .strip_prefix("- {")
+        .and_then(|l| l.strip_suffix('}'))
+        .ok_or("expected '- {...}'")?;
+    let mut fields = Vec::new();
+    while !rest.is_empty() {
+        let (name, after) = rest.split_once(": ").ok_or("expected 'name: value'")?;
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => parse_quoted(quoted)?,
+            None => {
+                let (value, after) = after.split_once(", ").unwrap_or((after, ""));
+                let value = value
+                    .parse()
+                    .map_err(|_| format!("'{value}' is not a number"))?;
+                (Value::Num(value), after)
+            }
+        };
+        fields.push((name.to_owned(), value));
+        rest = after.strip_prefix(", ").unwrap_or(after);
+    }
+    Ok(fields)
+}
+
+/// Parses a string after its opening quote, returning it and what follows the
+/// closing quote.
+fn parse_quoted(text: &str) -> Result<(Value, &str), String> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Value::Str(value), &text[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c @ ('"' | '\\' | '\'')) => value.push(c),
+                other => return Err(format!("unsupported escape '\\{}'", other.unwrap_or(' '))),
+            },
+            c => value.push(c),
+        }
+    }
+    Err("unterminated string".into())
+}
+
+/// Describes the first difference between `expected` and `actual`, or returns
+/// `None` if every field matches, numbers within `TOLERANCE`.
+pub fn compare(expected: &[Shape], actual: &[Shape]) -> Option<String> {
+    for (i, (want, got)) in expected.iter().zip(actual).enumerate() {
+        let same = want.len() == got.len()
+            && want.iter().zip(got).all(|((wn, wv), (gn, gv))| {
+                wn == gn
+                    && match (wv, gv) {
+                        (Value::Num(a), Value::Num(b)) => (a - b).abs() <= TOLERANCE,
+                        (a, b) => a == b,
+                    }
+            });
+        if !same {
+            return Some(format!(
+                "draw call {} differs\n  expected {}  got      {}",
+                i + 1,
+                to_yaml("", std::slice::from_ref(want)).trim_start_matches("# \n"),
+                to_yaml("", std::slice::from_ref(got)).trim_start_matches("# \n"),
+            ));
+        }
+    }
+    (expected.len() != actual.len()).then(|| {
+        format!(
+            "expected {} draw calls, got {}",
+            expected.len(),
+            actual.len()
+        )
+    })
+}
+
+/// Draws `state` headlessly in a `size` window, returning the draw calls.
+///
+/// The app runs on the default config and in-memory storage, so neither the
+/// user's settings nor their word lists change what's drawn.
+pub fn render_state(state: SnapshotState, size: Vec2) -> Result<Vec<DrawCall>, String> {
+    let mut config = Config {
+        announce: false,
+        ..Config::default()
+    };
+    if state == SnapshotState::ErrorScreen {
+        config.dictionary.max_words = 0;
+    }
+    let sounds = Sounds::silent(config.volume, true);
+    let state_ = AppState::new(config, Arc::new(MemoryStorage::default()));
+    let keymap = Keymap::defaults();
+    let mut app = App::new(
+        state_,
+        keymap,
+        None,
+        sounds,
+        size,
+        wall_clock,
+        fixed_measure,
+    );
+    if !wait_for_load(&mut app) {
+        return Err("the dictionary didn't load".into());
+    }
+
+    // Debug builds show the overlay by default; snapshots never do
+    if DEBUG_OVERLAY_DEFAULT && state != SnapshotState::ErrorScreen {
+        app.handle_event(AppEvent::Action(Action::ToggleDebug));
+    }
+    for event in state.events() {
+        app.handle_event(event);
+    }
+    settle(&mut app)?;
+
+    let gfx = RecordingRenderer::new(size);
+    app.draw(&gfx);
+    Ok(gfx.take())
+}
+
+/// Steps `app` until its queries and animations are done.
+fn settle(app: &mut App) -> Result<(), String> {
+    let started = Instant::now();
+    while app.is_busy() {
+        if started.elapsed() > SETTLE_TIMEOUT {
+            return Err("the app didn't settle".into());
+        }
+        app.take_redraw();
+        app.update(MAX_STEP);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    Ok(())
+}
+
+/// Checks every state at every size against the snapshots in `dir`, or
+/// rewrites them if `JUMBLE_BLESS` is set, and returns the exit code: 0 if all
+/// match, 1 if any differ, 2 if any are missing or couldn't be drawn.
+pub fn run_snapshots(dir: &str) -> i32 {
+    let bless = std::env::var("JUMBLE_BLESS").is_ok_and(|v| !v.is_empty() && v != "0");
+    let mut code = 0;
+    for state in SnapshotState::ALL {
+        for (w, h) in SIZES {
+            let name = format!("{}-{w}x{h}", state.name());
+            let path = Path::new(dir).join(format!("{name}.yaml"));
+            let shapes: Vec<Shape> = match render_state(state, vec2(w as f32, h as f32)) {
+                Ok(calls) => calls.iter().map(shape).collect(),
+                Err(e) => {
+                    eprintln!("{name}: {e}");
+                    code = 2;
+                    continue;
+                }
+            };
+
+            if bless {
+                let yaml = to_yaml(&format!("{} at {w}x{h}", state.name()), &shapes);
+                let written =
+                    std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, yaml));
+                match written {
+                    Ok(()) => println!("{name}: written"),
+                    Err(e) => {
+                        eprintln!("{name}: can't write '{}': {e}", path.display());
+                        code = 2;
+                    }
+                }
+                continue;
+            }
+
+            let expected = match std::fs::read_to_string(&path) {
+                Ok(text) => from_yaml(&text),
+                Err(e) => Err(format!("can't read '{}': {e}", path.display())),
+            };
+            match expected {
+                Ok(expected) => match compare(&expected, &shapes) {
+                    None => println!("{name}: ok"),
+                    Some(diff) => {
+                        println!("{name}: MISMATCH\n  {diff}");
+                        code = code.max(1);
+                    }
+                },
+                Err(e) => {
+                    eprintln!("{name}: {e} (run with JUMBLE_BLESS=1 to write it)");
+                    code = 2;
+                }
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drawing_matches_the_committed_snapshots() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+        for state in SnapshotState::ALL {
+            for (w, h) in SIZES {
+                let name = format!("{}-{w}x{h}", state.name());
+                let calls = render_state(state, vec2(w as f32, h as f32)).unwrap();
+                let shapes: Vec<Shape> = calls.iter().map(shape).collect();
+                let text = std::fs::read_to_string(dir.join(format!("{name}.yaml"))).unwrap();
+                let expected = from_yaml(&text).unwrap();
+                assert_eq!(compare(&expected, &shapes), None, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn shapes_read_back_as_written() {
+        let calls = [
+            DrawCall::Text {
+                text: "say \"hi\"\n\\".to_owned(),
+                pos: vec2(1.234, -5.0),
+                size: 24,
+                color: WHITE,
+            },
+            DrawCall::RectLines {
+                rect: Rect::new(0.0, 10.0, 20.5, 30.0),
+                thickness: 2.0,
+                color: Color::new(1.0, 0.0, 0.0, 0.5),
+            },
+        ];
+        let shapes: Vec<Shape> = calls.iter().map(shape).collect();
+        let yaml = to_yaml("two calls", &shapes);
+        assert!(yaml.starts_with("# two calls\n- {op: \"text\""), "{yaml}");
+        assert_eq!(compare(&from_yaml(&yaml).unwrap(), &shapes), None);
+        assert!(from_yaml("- {op: \"text").is_err());
+        assert!(from_yaml("- {x: wide}").is_err());
+    }
+
+    #[test]
+    fn coordinates_may_move_within_the_tolerance() {
+        let at = |x: f32| {
+            shape(&DrawCall::Circle {
+                center: vec2(x, 0.0),
+                radius: 1.0,
+                color: WHITE,
+            })
+        };
+        assert_eq!(compare(&[at(10.0)], &[at(10.0 + TOLERANCE)]), None);
+        let e = compare(&[at(10.0)], &[at(11.0)]).unwrap();
+        assert!(e.starts_with("draw call 1 differs"), "{e}");
+        let e = compare(&[at(10.0)], &[at(10.0), at(10.0)]).unwrap();
+        assert_eq!(e, "expected 1 draw calls, got 2");
+    }
+}
@@ -0,0 +1,361 @@
+//! Soak testing: random input through the app, checking it stays consistent.
+//!
+//! `run_soak`, which the `soak` binary runs, builds the app on the built-in
+//! dictionary and feeds it seeded random events, checking after every step that
+//! each tab's entry is within bounds and its answer belongs to a single match,
+//! and every so often, once the queries have caught up, that the matches agree
+//! with a plain scan of the word list. A panic or broken invariant prints the
+//! seed and the last events.
+
+use crate::app::{App, AppState};
+use crate::config::Config;
+use crate::dictionary::{load_words, DictionaryOptions};
+use crate::events::{AppEvent, Nav};
+use crate::feedback::AsTyped;
+use crate::keymap::{Action, Keymap};
+use crate::query::QueryMode;
+use crate::record::{format_event, wait_for_load, wall_clock, MAX_STEP};
+use crate::render::fixed_measure;
+use crate::screen::Screen;
+use crate::sound::Sounds;
+use crate::storage::MemoryStorage;
+use crate::strings::Language;
+use crate::wordmap::fold_accents;
+use crate::{MAX_TABS, MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Events printed when a run fails.
+const HISTORY: usize = 50;
+/// Wall-clock seconds a query may take before the run fails.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Characters typed besides letters: accented letters that fold, and ones that
+/// don't belong in the entry at all.
+const ODD_CHARS: [char; 10] = ['é', 'Ñ', 'ß', 'œ', '1', ' ', '-', '?', '\'', '中'];
+/// Actions that change modes, sizes, tabs, pins, length filters, and hints
+/// without leaving the entry screen.
+const TOGGLES: [Action; 23] = [
+    Action::ToggleSubAnagrams,
+    Action::ToggleBigMode,
+    Action::ToggleMini,
+    Action::CycleTheme,
+    Action::ToggleMute,
+    Action::ToggleDebug,
+    Action::TextLarger,
+    Action::TextSmaller,
+    Action::TextReset,
+    Action::NewTab,
+    Action::CloseTab,
+    Action::NextTab,
+    Action::CycleStats,
+    Action::TogglePin,
+    Action::ClearPins,
+    Action::ShowLength5,
+    Action::ShowLength6,
+    Action::ShowAllLengths,
+    Action::ShowHint,
+    Action::TogglePhonetic,
+    Action::CopyAnswer,
+    Action::RevealHidden,
+    Action::Complete,
+];
+
+/// A small seeded generator (SplitMix64), so a seed always gives the same run.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    /// Returns a number below `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    /// Returns a number in `0.0..1.0`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+/// Returns a random letter, in either case, or now and then an odd character.
+fn random_char(rng: &mut Rng) -> char {
+    if rng.below(10) == 0 {
+        return rng.pick(&ODD_CHARS);
+    }
+    let c = (b'A' + rng.below(26) as u8) as char;
+    if rng.below(4) == 0 {
+        c.to_ascii_lowercase()
+    } else {
+        c
+    }
+}
+
+/// Returns the next events to send: usually one, or a burst of characters
+/// standing in for a paste.
+fn random_events(rng: &mut Rng) -> Vec<AppEvent> {
+    let roll = rng.below(100);
+    match roll {
+        0..=49 => vec![AppEvent::Letter(random_char(rng))],
+        50..=64 => vec![AppEvent::Action(Action::DeleteBack)],
+        65..=67 => vec![AppEvent::Action(Action::ClearEntry)],
+        68..=72 => (0..1 + rng.below(12))
+            .map(|_| AppEvent::Letter(random_char(rng)))
+            .collect(),
+        73..=84 => vec![AppEvent::Action(rng.pick(&TOGGLES))],
+        85..=89 => {
+            // Down to nothing at all, which leaves no room to lay out
+            let size = vec2(rng.below(2000) as f32, rng.below(2000) as f32);
+            vec![AppEvent::WindowSize(size)]
+        }
+        90..=94 => vec![AppEvent::Wheel(rng.below(21) as f32 - 10.0)],
+        _ => vec![AppEvent::Nav(rng.pick(&[Nav::Up, Nav::Down, Nav::Select]))],
+    }
+}
+
+/// The word list scanned one word at a time, to check the app's lookups against.
+pub struct NaiveSolver {
+    /// Each word with its accents folded, and as listed.
+    words: Vec<(String, String)>,
+}
+
+impl NaiveSolver {
+    pub fn new(words: &str) -> Self {
+        Self {
+            words: words
+                .lines()
+                .map(|w| (fold_accents(w).into_owned(), w.to_owned()))
+                .collect(),
+        }
+    }
+    /// Returns the words matching `entry` under `mode`, sorted.
+    pub fn solve(&self, mode: QueryMode, entry: &str) -> Vec<String> {
+        let counts = |word: &str| {
+            let mut counts = [0u8; 26];
+            for b in word.bytes() {
+                if !b.is_ascii_uppercase() {
+                    return None;
+                }
+                counts[(b - b'A') as usize] += 1;
+            }
+            Some(counts)
+        };
+        let available = counts(entry);
+        let fits = |folded: &str| match mode {
+            QueryMode::Exact => {
+                (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&entry.len())
+                    && counts(folded).is_some_and(|c| Some(c) == available)
+            }
+            QueryMode::SubAnagram => {
+                folded.len() >= MIN_WORD_LENGTH
+                    && folded.len() <= entry.len()
+                    && counts(folded)
+                        .zip(available)
+                        .is_some_and(|(need, have)| need.iter().zip(&have).all(|(n, h)| n <= h))
+            }
+        };
+        let mut found: Vec<String> = self
+            .words
+            .iter()
+            .filter(|(folded, _)| fits(folded))
+            .map(|(_, word)| word.clone())
+            .collect();
+        found.sort();
+        found
+    }
+}
+
+/// Checks what holds after every step, in every tab; with `naive`, also that
+/// the matches in front are the ones a scan of the word list finds.
+fn check(app: &App, naive: Option<&NaiveSolver>) -> Result<(), String> {
+    let Screen::Entry(entry) = app.screen() else {
+        return Err("left the entry screen".into());
+    };
+    let (tabs, active) = entry.tabs();
+    if tabs.is_empty() || tabs.len() > MAX_TABS || active >= tabs.len() {
+        return Err(format!("tab {active} of {} is in front", tabs.len()));
+    }
+    for tab in tabs {
+        let text = tab.entry.as_str();
+        let max_len = tab.entry.max_len();
+        if text.len() > max_len {
+            return Err(format!("entry {text:?} is over {max_len} letters"));
+        }
+        if !text.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(format!("entry {text:?} has characters other than A-Z"));
+        }
+        let matches = tab.matches();
+        match tab.answer() {
+            Some(answer) if matches.len() != 1 => {
+                return Err(format!(
+                    "answer {answer:?} shown with {} matches for {text:?}",
+                    matches.len()
+                ))
+            }
+            // Neither a masked word nor the entry's own letters is ever the answer
+            None if matches.len() == 1
+                && tab.hidden.is_empty()
+                && tab.as_typed != AsTyped::OnlyMatch =>
+            {
+                return Err(format!("no answer for the single match of {text:?}"))
+            }
+            _ => (),
+        }
+        if let Some(i) = tab
+            .results
+            .selected
+            .filter(|&i| !tab.shown().any(|j| j == i))
+        {
+            return Err(format!(
+                "selected match {i} is hidden by the length filter or the mask"
+            ));
+        }
+    }
+    if let Some(naive) = naive {
+        let text = entry.entry();
+        let mut found = entry.matches().to_vec();
+        found.sort();
+        let expected = naive.solve(entry.mode(), text);
+        if found != expected {
+            return Err(format!(
+                "{:?} matches for {text:?} are {found:?}, but a scan finds {expected:?}",
+                entry.mode()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Steps `app` until its queries have caught up with the entry.
+fn settle(app: &mut App) -> Result<(), String> {
+    let started = Instant::now();
+    while let Screen::Entry(entry) = app.screen() {
+        if entry.is_settled() {
+            break;
+        }
+        if started.elapsed() > SETTLE_TIMEOUT {
+            return Err(format!("the query for {:?} never finished", entry.entry()));
+        }
+        app.update(MAX_STEP);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    Ok(())
+}
+
+/// Runs `iterations` random steps from `seed`, returning the exit code: 0 if
+/// every check passed, 1 if one failed or the app panicked, 2 if it couldn't
+/// start.
+pub fn run_soak(iterations: u64, seed: u64) -> i32 {
+    let options = DictionaryOptions::default();
+    let naive = NaiveSolver::new(&load_words(&options, Language::En));
+    let config = Config {
+        announce: false,
+        dictionary: options,
+        language: Language::En,
+        ..Config::default()
+    };
+    let sounds = Sounds::silent(config.volume, true);
+    let state = AppState::new(config, Arc::new(MemoryStorage::default()));
+    let size = vec2(800.0, 600.0);
+    let keymap = Keymap::defaults();
+    let mut app = App::new(state, keymap, None, sounds, size, wall_clock, fixed_measure);
+    if !wait_for_load(&mut app) {
+        eprintln!("[run_soak] the dictionary didn't load");
+        return 2;
+    }
+
+    println!("soaking with seed {seed} for {iterations} steps");
+    let mut rng = Rng::new(seed);
+    let mut history: VecDeque<(f64, AppEvent)> = VecDeque::with_capacity(HISTORY);
+    let started = Instant::now();
+    for step in 0..iterations {
+        let events = random_events(&mut rng);
+        let dt = rng.unit() * 0.05;
+        // Now and then, let the queries catch up and check the matches too
+        let full_check = rng.below(32) == 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for event in events {
+                if history.len() == HISTORY {
+                    history.pop_front();
+                }
+                history.push_back((app.ctx.now, event.clone()));
+                app.handle_event(event);
+            }
+            app.update(dt);
+            app.take_redraw();
+            if full_check {
+                settle(&mut app)?;
+            }
+            check(&app, full_check.then_some(&naive))
+        }));
+        let failure = match result {
+            Ok(Ok(())) => continue,
+            Ok(Err(e)) => e,
+            Err(panic) => match panic.downcast_ref::<&str>() {
+                Some(message) => format!("panicked: {message}"),
+                None => match panic.downcast_ref::<String>() {
+                    Some(message) => format!("panicked: {message}"),
+                    None => "panicked".into(),
+                },
+            },
+        };
+        println!("FAILED at step {step} with seed {seed}: {failure}");
+        println!("last {} events (seconds since start):", history.len());
+        for (time, event) in &history {
+            println!("{time:>9.3} {}", format_event(event));
+        }
+        println!(
+            "rerun with: cargo run --release --bin soak -- --seed {seed} --iterations {iterations}"
+        );
+        return 1;
+    }
+    println!(
+        "ok: {iterations} steps in {:.1}s",
+        started.elapsed().as_secs_f64()
+    );
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_seed_always_gives_the_same_events() {
+        let run = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..100)
+                .flat_map(|_| random_events(&mut rng))
+                .map(|event| format_event(&event))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| rng.below(3) < 3 && (0.0..1.0).contains(&rng.unit())));
+    }
+
+    #[test]
+    fn the_naive_solver_finds_exact_and_sub_anagrams() {
+        let naive = NaiveSolver::new("GREAT\nGRATE\nRATE\nTEAR\nÉTAGE\nGREATEST\n");
+        assert_eq!(naive.solve(QueryMode::Exact, "TAGER"), ["GRATE", "GREAT"]);
+        assert_eq!(naive.solve(QueryMode::Exact, "GETAE"), ["ÉTAGE"]);
+        assert_eq!(
+            naive.solve(QueryMode::SubAnagram, "TAGER"),
+            ["GRATE", "GREAT", "RATE", "TEAR"]
+        );
+        assert!(naive.solve(QueryMode::Exact, "TAG").is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Audio cues, embedded in the executable.
+//!
+//! Playback needs the `sound` feature, on by default; without it every cue is silent.
+
+use crate::feedback::MatchOutcome;
+use macroquad::audio::{load_sound_from_bytes, play_sound, PlaySoundParams, Sound};
+
+const DING: &[u8] = include_bytes!("../sounds/ding.wav");
+const THUD: &[u8] = include_bytes!("../sounds/thud.wav");
+
+/// The thud is a gentler cue than the ding.
+const THUD_VOLUME: f32 = 0.6;
+/// A refused keypress is the softest cue, so typing past the cap isn't jarring.
+const REJECT_VOLUME: f32 = 0.3;
+
+/// Sounds played when the match outcome changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// The entry has a unique answer.
+    Ding,
+    /// The entry is long enough to be a word but matches nothing.
+    Thud,
+    /// A letter was refused because the entry is full.
+    Reject,
+}
+
+impl Cue {
+    /// Returns the cue announcing a change to `outcome`, if any.
+    pub fn for_outcome(outcome: MatchOutcome) -> Option<Cue> {
+        match outcome {
+            MatchOutcome::Unique => Some(Cue::Ding),
+            MatchOutcome::NoMatch => Some(Cue::Thud),
+            MatchOutcome::Incomplete | MatchOutcome::Several | MatchOutcome::AsTyped => None,
+        }
+    }
+}
+
+/// Loaded cues plus the user's volume and mute settings.
+///
+/// Cues that fail to load stay silent, so a machine without audio still runs.
+pub struct Sounds {
+    ding: Option<Sound>,
+    thud: Option<Sound>,
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Sounds {
+    /// Loads the embedded cues played at `volume` (0.0 to 1.0).
+    pub async fn load(volume: f32, muted: bool) -> Self {
+        let (ding, thud) = if cfg!(feature = "sound") {
+            (load_cue("ding", DING).await, load_cue("thud", THUD).await)
+        } else {
+            (None, None)
+        };
+        Self {
+            ding,
+            thud,
+            volume: volume.clamp(0.0, 1.0),
+            muted,
+        }
+    }
+    /// Creates cues that never play, for running without audio (e.g. replays).
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn silent(volume: f32, muted: bool) -> Self {
+        Self {
+            ding: None,
+            thud: None,
+            volume: volume.clamp(0.0, 1.0),
+            muted,
+        }
+    }
+    /// Plays `cue`, unless muted.
+    pub fn play(&self, cue: Cue) {
+        if self.muted {
+            return;
+        }
+        let (sound, volume) = match cue {
+            Cue::Ding => (&self.ding, self.volume),
+            Cue::Thud => (&self.thud, self.volume * THUD_VOLUME),
+            Cue::Reject => (&self.thud, self.volume * REJECT_VOLUME),
+        };
+        if let Some(sound) = sound {
+            let looped = false;
+            play_sound(sound, PlaySoundParams { looped, volume });
+        }
+    }
+}
+
+/// Loads one cue, reporting (but otherwise ignoring) failures.
+async fn load_cue(name: &str, bytes: &[u8]) -> Option<Sound> {
+    match load_sound_from_bytes(bytes).await {
+        Ok(sound) => Some(sound),
+        Err(e) => {
+            log::warn!("[Sounds::load] failed to load {name} sound: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_an_answer_or_a_dead_end_has_a_cue() {
+        assert_eq!(Cue::for_outcome(MatchOutcome::Unique), Some(Cue::Ding));
+        assert_eq!(Cue::for_outcome(MatchOutcome::NoMatch), Some(Cue::Thud));
+        assert_eq!(Cue::for_outcome(MatchOutcome::Several), None);
+        assert_eq!(Cue::for_outcome(MatchOutcome::Incomplete), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! Watching another app's screen, read-only.
+//!
+//! `jumble_helper --spectate URL` opens a window showing the entry, matches,
+//! solved answers, and final answer of the app sharing at URL (see `share`), as
+//! they change, for helping someone over the phone. Nothing typed or clicked
+//! there reaches the other app: the window takes only the quit key. The token
+//! is the config's `share_token`, and a dropped connection is retried every
+//! `RETRY_DELAY`.
+
+use crate::config::Config;
+use crate::crash::StateSummary;
+use crate::font::load_font;
+use crate::keymap::{Action, Keymap};
+use crate::modifiers::{ModifierTracker, EVENTS_RELIABLE};
+use crate::render::{MacroquadRenderer, Renderer};
+use crate::share::watch;
+use crate::strings::{fill, Language, StringKey};
+use crate::theme::{Theme, ThemeSet};
+use crate::{ENTRY_TEXT_SIZE, RESULT_TEXT_SIZE};
+use macroquad::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Time between attempts to reach the sharing app.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How the connection to the sharing app stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    Connecting,
+    Watching,
+    /// Lost, or never made, for the reason given; being retried.
+    Lost(String),
+}
+
+/// What's known of the sharing app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    pub link: Link,
+    /// The last state it sent, kept while the connection is retried.
+    pub state: Option<StateSummary>,
+}
+
+/// Watches `url` on a thread of its own, reconnecting whenever the connection
+/// ends, and returns the feed it keeps up to date.
+fn start_feed(url: String, token: String) -> Arc<Mutex<Feed>> {
+    let feed = Arc::new(Mutex::new(Feed {
+        link: Link::Connecting,
+        state: None,
+    }));
+    let shared = Arc::clone(&feed);
+    std::thread::spawn(move || loop {
+        let ended = watch(&url, &token, |state| {
+            let mut feed = shared.lock().unwrap();
+            feed.link = Link::Watching;
+            feed.state = Some(state);
+        });
+        let reason = ended
+            .err()
+            .unwrap_or_else(|| "the connection closed".to_owned());
+        log::info!("[start_feed] lost '{url}': {reason}");
+        shared.lock().unwrap().link = Link::Lost(reason);
+        std::thread::sleep(RETRY_DELAY);
+    });
+    feed
+}
+
+/// Shows the app sharing at `url` until the quit key is pressed or the window
+/// is closed.
+pub async fn run_spectator(url: String, config: Config, keymap: Keymap) {
+    let token = config.share_token.clone().unwrap_or_default();
+    let feed = start_feed(url.clone(), token);
+    let (font, font_source) = load_font(config.font_path.as_deref());
+    log::info!("[run_spectator] using {font_source}");
+    let themes = ThemeSet::new(&config.themes, &config.theme);
+    let gfx = MacroquadRenderer;
+    let mut modifiers = ModifierTracker::new(EVENTS_RELIABLE);
+
+    loop {
+        // Every other key would be the sharing app's to handle, so none are taken
+        modifiers.poll();
+        if keymap
+            .pressed_actions(modifiers.pressed())
+            .contains(&Action::Quit)
+        {
+            break;
+        }
+        let feed = feed.lock().unwrap().clone();
+        let theme = themes.current();
+        draw_spectator(
+            &gfx,
+            &url,
+            &feed,
+            font.as_ref(),
+            theme,
+            config.language,
+            config.text_scale,
+        );
+        next_frame().await;
+    }
+}
+
+/// Draws the feed: a line saying how the connection stands, then the entry, the
+/// answer or matches, the solved answers, and the final answer being spelled.
+pub fn draw_spectator(
+    gfx: &dyn Renderer,
+    url: &str,
+    feed: &Feed,
+    font: Option<&Font>,
+    theme: &Theme,
+    lang: Language,
+    text_scale: f32,
+) {
+    let size = gfx.screen_size();
+    let scaled = |base: u16| (base as f32 * text_scale).round() as u16;
+    let (entry_size, result_size) = (scaled(ENTRY_TEXT_SIZE), scaled(RESULT_TEXT_SIZE));
+    let note_size = result_size * 2 / 3;
+    let pad = note_size as f32;
+    let centered = |text: &str, text_size: u16, y: f32, color: Color| {
+        let w = gfx.measure(text, font, text_size).width;
+        gfx.text(text, vec2((size.x - w) / 2.0, y), font, text_size, color);
+    };
+    gfx.clear(theme.background);
+
+    let (status, status_color) = match &feed.link {
+        Link::Connecting => (
+            fill(lang.text(StringKey::SpectateConnecting), url),
+            theme.result,
+        ),
+        Link::Watching => (
+            fill(lang.text(StringKey::SpectateWatching), url),
+            theme.result,
+        ),
+        Link::Lost(reason) => {
+            let text = fill(lang.text(StringKey::SpectateLost), url);
+            (fill(&text, reason), theme.error)
+        }
+    };
+    gfx.text(&status, vec2(pad, pad * 1.5), font, note_size, status_color);
+    let Some(state) = &feed.state else {
+        return;
+    };
+
+    let mut y = size.y * 0.3;
+    centered(&state.entry, entry_size, y, theme.entry_text);
+    y += entry_size as f32 * 0.5;
+
+    // The final answer, while it's being spelled, goes at the bottom
+    let mut bottom = size.y - pad;
+    if !state.pool.is_empty() {
+        centered(&state.phrase, result_size, bottom, theme.highlight);
+        bottom -= result_size as f32 * 1.2;
+        let pool = format!("{}: {}", lang.text(StringKey::PoolTitle), state.pool);
+        centered(&pool, note_size, bottom, theme.result);
+        bottom -= note_size as f32 * 1.6;
+    }
+    if !state.solved.is_empty() {
+        let solved = fill(
+            lang.text(StringKey::SpectateSolved),
+            state.solved.join(", "),
+        );
+        centered(&solved, note_size, bottom, theme.result);
+        bottom -= note_size as f32 * 1.6;
+    }
+
+    let row_h = result_size as f32 * 1.3;
+    match &state.answer {
+        Some(answer) => centered(answer, result_size, y + row_h, theme.highlight),
+        None => {
+            for word in &state.matches {
+                y += row_h;
+                if y > bottom {
+                    break;
+                }
+                centered(word, result_size, y, theme.result);
+            }
+        }
+    }
+}
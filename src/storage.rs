@@ -0,0 +1,478 @@
+//! Key-value persistence for config and other user data.
+//!
+//! Native builds store each key as a file in the user data directory; web builds
+//! store keys in the browser's localStorage via `quad-storage`.
+//!
+//! Each key's contents go in an envelope: a first line such as
+//! `# jumble_helper history v1` naming the file's format and its version (see
+//! `Format`), so a release reading a file written by a newer one refuses it,
+//! and won't write over it, rather than misreading it. Files without one, as
+//! older releases wrote them or as a user wrote them by hand, are read as they
+//! are.
+
+use crate::app::ENTRY_KEY;
+use crate::config::CONFIG_KEY;
+use crate::confirmed::CONFIRMED_KEY;
+use crate::coverage::COVERAGE_KEY;
+use crate::history::{HISTORY_ARCHIVE_KEY, HISTORY_KEY};
+use crate::mask::MASK_KEY;
+use crate::session::SESSION_KEY;
+use crate::usage::USAGE_KEY;
+use std::io;
+use std::sync::Arc;
+
+/// Every key the user's data is stored under with `write`; a new one goes
+/// here too, so the daily backups (see `backup`) take it.
+pub const DATA_KEYS: [&str; 8] = [
+    CONFIG_KEY,
+    ENTRY_KEY,
+    HISTORY_KEY,
+    SESSION_KEY,
+    COVERAGE_KEY,
+    CONFIRMED_KEY,
+    MASK_KEY,
+    USAGE_KEY,
+];
+/// Every key the user's data is stored under with `archive`.
+pub const ARCHIVE_KEYS: [&str; 1] = [HISTORY_ARCHIVE_KEY];
+
+/// Starts the envelope line of every stored file.
+pub const MAGIC: &str = "# jumble_helper";
+
+/// A stored file's format: its name and the newest version this release reads
+/// and writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Format {
+    pub name: &'static str,
+    pub version: u32,
+}
+
+impl Format {
+    /// Returns `contents` in this format's envelope.
+    pub fn seal(self, contents: &str) -> String {
+        format!("{MAGIC} {} v{}\n{contents}", self.name, self.version)
+    }
+    /// Returns the contents of `text`, as stored under `key`, without its
+    /// envelope, or why they can't be read: they're another format, or a newer
+    /// version of this one.
+    pub fn open<'a>(self, key: &str, text: &'a str) -> io::Result<&'a str> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        match envelope(text) {
+            None => Ok(text),
+            Some((name, Some(v), contents)) if name == self.name && v <= self.version => {
+                Ok(contents)
+            }
+            Some((name, Some(v), _)) if name == self.name => Err(invalid(format!(
+                "{key} was written by a newer Jumble Helper ({name} v{v}; this one reads \
+                 up to v{}); update the app to use it",
+                self.version
+            ))),
+            Some((name, Some(_), _)) => Err(invalid(format!(
+                "{key} holds {name} data, not {}",
+                self.name
+            ))),
+            Some(_) => Err(invalid(format!("{key} has an unreadable first line"))),
+        }
+    }
+    /// Returns whether `text` is in a newer version of this format than this
+    /// release writes.
+    pub fn is_newer(self, text: &str) -> bool {
+        envelope(text).is_some_and(|(name, version, _)| {
+            name == self.name && version.is_some_and(|v| v > self.version)
+        })
+    }
+}
+
+/// Splits `text` into the format name and version on its envelope line and the
+/// contents after it, or returns `None` if it has no envelope.
+fn envelope(text: &str) -> Option<(&str, Option<u32>, &str)> {
+    let rest = text.strip_prefix(MAGIC)?;
+    let (line, contents) = rest.split_once('\n').unwrap_or((rest, ""));
+    let mut fields = line.split_whitespace();
+    let name = fields.next().unwrap_or_default();
+    let version = fields
+        .next()
+        .and_then(|v| v.strip_prefix('v'))
+        .and_then(|v| v.parse().ok());
+    Some((name, version, contents))
+}
+
+/// Reads and writes small text blobs by key.
+///
+/// Storage is shared with the panic hook, so it must be usable from any thread.
+pub trait Storage: Send + Sync {
+    /// Returns the contents stored under `key`, or `None` if nothing is stored.
+    fn read(&self, key: &str) -> io::Result<Option<String>>;
+    /// Stores `contents` under `key`, replacing any previous contents.
+    fn write(&self, key: &str, contents: &str) -> io::Result<()>;
+
+    /// Returns the contents stored under `key` in `format`, out of their
+    /// envelope, or why they can't be read.
+    fn read_format(&self, key: &str, format: Format) -> io::Result<Option<String>> {
+        match self.read(key)? {
+            Some(text) => format
+                .open(key, &text)
+                .map(|contents| Some(contents.to_owned())),
+            None => Ok(None),
+        }
+    }
+    /// Stores `contents` under `key` in `format`'s envelope, unless what's
+    /// there is a newer version, which is left alone.
+    fn write_format(&self, key: &str, format: Format, contents: &str) -> io::Result<()> {
+        if let Ok(Some(text)) = self.read(key) {
+            if format.is_newer(&text) {
+                return format.open(key, &text).map(|_| ());
+            }
+        }
+        self.write(key, &format.seal(contents))
+    }
+    /// Adds `contents` to the end of the archive under `key`, where what's too
+    /// old to keep at hand goes. Archives are only ever added to.
+    fn archive(&self, key: &str, contents: &str) -> io::Result<()> {
+        let archived = self.read(key)?.unwrap_or_default();
+        self.write(key, &(archived + contents))
+    }
+    /// Returns everything archived under `key` with `archive`, oldest first, or
+    /// `None` if nothing is.
+    fn read_archive(&self, key: &str) -> io::Result<Option<String>> {
+        self.read(key)
+    }
+}
+
+/// Writes `bytes` to `path` through a temporary file beside it: the temporary
+/// file is synced to disk and then renamed over `path`, so a crash or a full
+/// disk mid-write leaves the old contents rather than part of the new. The
+/// temporary file is removed if anything fails.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn safe_write(path: &std::path::Path, bytes: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(TEMP_SUFFIX);
+    let temp = path.with_file_name(name);
+    let written = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written
+}
+
+/// Returns the name of the file `FileStorage` keeps the archive under `key` in.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn archive_file(key: &str) -> String {
+    format!("{key}.gz")
+}
+
+/// Ends the name of the temporary file `safe_write` writes through.
+#[cfg(not(target_arch = "wasm32"))]
+pub const TEMP_SUFFIX: &str = ".tmp";
+
+/// Removes temporary files left in `dir` by writes a crash cut short (see
+/// `safe_write`), returning how many there were. Call only while no other
+/// instance could be writing there.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove_leftover_temps(dir: &std::path::Path) -> io::Result<usize> {
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_temp = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(TEMP_SUFFIX));
+        if is_temp && path.is_file() {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Stores each key as a file named `key` inside a directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// Creates a new `FileStorage` rooted at `dir`.
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> io::Result<Option<String>> {
+        match std::fs::read_to_string(self.dir.join(key)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+    /// Writes with `safe_write`, so a crash mid-write leaves the old contents
+    /// rather than part of the new.
+    fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        safe_write(&self.dir.join(key), contents.as_bytes())
+    }
+    /// Appends to `archive_file(key)`, as a gzip member of its own, so the
+    /// whole file unpacks with any gzip tool.
+    fn archive(&self, key: &str, contents: &str) -> io::Result<()> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use io::Write;
+
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(archive_file(key));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(contents.as_bytes())?;
+        encoder.finish()?.sync_all()
+    }
+    /// Unpacks every gzip member `archive` appended to `archive_file(key)`.
+    fn read_archive(&self, key: &str) -> io::Result<Option<String>> {
+        use io::Read;
+
+        let file = match std::fs::File::open(self.dir.join(archive_file(key))) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut contents = String::new();
+        flate2::read::MultiGzDecoder::new(file).read_to_string(&mut contents)?;
+        Ok(Some(contents))
+    }
+}
+
+/// Keeps keys in memory only, for runs that mustn't touch the user's files (e.g.
+/// replays).
+#[derive(Default)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct MemoryStorage {
+    keys: std::sync::Mutex<std::collections::BTreeMap<String, String>>,
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> io::Result<Option<String>> {
+        let keys = self
+            .keys
+            .lock()
+            .map_err(|_| io::Error::other("storage lock poisoned"))?;
+        Ok(keys.get(key).cloned())
+    }
+    fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        let mut keys = self
+            .keys
+            .lock()
+            .map_err(|_| io::Error::other("storage lock poisoned"))?;
+        keys.insert(key.to_owned(), contents.to_owned());
+        Ok(())
+    }
+}
+
+/// Stores keys in the browser's localStorage.
+#[cfg(feature = "wasm")]
+pub struct WebStorage;
+
+#[cfg(feature = "wasm")]
+impl Storage for WebStorage {
+    fn read(&self, key: &str) -> io::Result<Option<String>> {
+        let storage = quad_storage::STORAGE
+            .lock()
+            .map_err(|_| io::Error::other("storage lock poisoned"))?;
+        Ok(storage.get(key))
+    }
+    fn write(&self, key: &str, contents: &str) -> io::Result<()> {
+        let mut storage = quad_storage::STORAGE
+            .lock()
+            .map_err(|_| io::Error::other("storage lock poisoned"))?;
+        storage.set(key, contents);
+        Ok(())
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("web builds require the `wasm` feature: cargo build --features wasm");
+
+/// Returns the storage appropriate for this platform.
+pub fn default_storage() -> Arc<dyn Storage> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        Arc::new(WebStorage)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Arc::new(FileStorage::new(crate::assets::data_dir()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: Format = Format {
+        name: "history",
+        version: 1,
+    };
+    const V2: Format = Format {
+        name: "history",
+        version: 2,
+    };
+
+    #[test]
+    fn envelopes_read_back_what_was_written() {
+        let storage = MemoryStorage::default();
+        storage.write_format("history.txt", V2, "GREAT\n").unwrap();
+        let text = storage.read("history.txt").unwrap().unwrap();
+        assert_eq!(text, "# jumble_helper history v2\nGREAT\n");
+        let contents = storage.read_format("history.txt", V2).unwrap();
+        assert_eq!(contents.as_deref(), Some("GREAT\n"));
+        assert_eq!(storage.read_format("other.txt", V2).unwrap(), None);
+    }
+
+    #[test]
+    fn older_versions_are_read_and_written_over_as_the_newest() {
+        let storage = MemoryStorage::default();
+        storage.write_format("history.txt", V1, "GREAT\n").unwrap();
+        let contents = storage.read_format("history.txt", V2).unwrap().unwrap();
+        assert_eq!(contents, "GREAT\n");
+        storage.write_format("history.txt", V2, &contents).unwrap();
+        let text = storage.read("history.txt").unwrap().unwrap();
+        assert!(text.starts_with("# jumble_helper history v2\n"), "{text}");
+        // As are files from before envelopes
+        storage.write("bare.txt", "GREAT\n").unwrap();
+        let contents = storage.read_format("bare.txt", V2).unwrap();
+        assert_eq!(contents.as_deref(), Some("GREAT\n"));
+    }
+
+    #[test]
+    fn newer_versions_are_refused_and_left_alone() {
+        let storage = MemoryStorage::default();
+        storage.write_format("history.txt", V2, "GREAT\n").unwrap();
+        let e = storage.read_format("history.txt", V1).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert!(storage.write_format("history.txt", V1, "QUACK\n").is_err());
+        let contents = storage.read_format("history.txt", V2).unwrap();
+        assert_eq!(contents.as_deref(), Some("GREAT\n"));
+    }
+
+    #[test]
+    fn other_formats_are_refused() {
+        let other = Format {
+            name: "config",
+            version: 1,
+        };
+        let text = other.seal("a = 1\n");
+        assert!(V1.open("history.txt", &text).is_err());
+        assert!(V1.open("history.txt", "# jumble_helper history\n").is_err());
+        assert!(!V1.is_newer(&text));
+        assert!(V1.is_newer(&V2.seal("")));
+    }
+
+    /// A directory of its own under the system's temporary directory, emptied.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("jumble_helper-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn file_storage_reads_what_was_written() {
+        let dir = scratch_dir("round-trip");
+        let storage = FileStorage::new(&dir);
+        assert_eq!(storage.read("config.toml").unwrap(), None);
+        storage.write("config.toml", "a = 1\n").unwrap();
+        storage.write("config.toml", "a = ☃\n").unwrap();
+        assert_eq!(
+            storage.read("config.toml").unwrap().as_deref(),
+            Some("a = ☃\n")
+        );
+        // Nothing is left beside it
+        let names: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["config.toml"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn memory_storage_reads_what_was_written() {
+        let storage = MemoryStorage::default();
+        assert_eq!(storage.read("config.toml").unwrap(), None);
+        storage.write("config.toml", "a = 1\n").unwrap();
+        storage.write("config.toml", "a = 2\n").unwrap();
+        assert_eq!(
+            storage.read("config.toml").unwrap().as_deref(),
+            Some("a = 2\n")
+        );
+        storage.archive("history.txt", "one\n").unwrap();
+        storage.archive("history.txt", "two\n").unwrap();
+        assert_eq!(
+            storage.read("history.txt").unwrap().as_deref(),
+            Some("one\ntwo\n")
+        );
+        let archived = storage.read_archive("history.txt").unwrap();
+        assert_eq!(archived.as_deref(), Some("one\ntwo\n"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn a_failed_write_leaves_the_old_contents_and_no_temp() {
+        let dir = scratch_dir("failed-write");
+        std::fs::create_dir_all(dir.join("config.toml")).unwrap();
+        // The rename fails, as the path is a directory
+        assert!(safe_write(&dir.join("config.toml"), b"a = 1\n").is_err());
+        assert!(dir.join("config.toml").is_dir());
+        assert!(!dir.join(format!("config.toml{TEMP_SUFFIX}")).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn file_archives_unpack_as_one() {
+        use std::io::Read;
+
+        let dir = scratch_dir("archive");
+        let storage = FileStorage::new(&dir);
+        storage.archive("history.txt", "one\n").unwrap();
+        storage.archive("history.txt", "two\n").unwrap();
+        let file = std::fs::File::open(dir.join("history.txt.gz")).unwrap();
+        let mut text = String::new();
+        flate2::read::MultiGzDecoder::new(file)
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "one\ntwo\n");
+        let archived = storage.read_archive("history.txt").unwrap();
+        assert_eq!(archived.as_deref(), Some("one\ntwo\n"));
+        assert_eq!(storage.read_archive("other.txt").unwrap(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn leftover_temps_are_removed() {
+        let dir = scratch_dir("temps");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("config.toml{TEMP_SUFFIX}")), "half").unwrap();
+        std::fs::write(dir.join("config.toml"), "whole").unwrap();
+        assert_eq!(remove_leftover_temps(&dir).unwrap(), 1);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("config.toml")).unwrap(),
+            "whole"
+        );
+        assert!(!dir.join(format!("config.toml{TEMP_SUFFIX}")).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,896 @@
+//! User-facing text in each supported language.
+//!
+//! Every `StringKey` is matched exhaustively in every language's table, so a
+//! missing translation fails the build. Text with a `{}` placeholder is completed
+//! with `fill`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Languages the interface is translated into, selected by `language` in the
+/// config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Fr,
+    Es,
+}
+
+impl Language {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub const ALL: [Language; 3] = [Language::En, Language::Fr, Language::Es];
+}
+
+/// Each piece of user-facing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKey {
+    AppTitle,
+    /// `{}` is the number of words loaded.
+    LoadedWords,
+    Searching,
+    KeyDelete,
+    KeyClear,
+    QuitPrompt,
+    QuitChoices,
+    RestorePrompt,
+    RestoreChoices,
+    EntryCleared,
+    /// `{}` is the entry, spelled out.
+    EntryIs,
+    /// `{}` is the answer.
+    AnswerIs,
+    NoMatches,
+    /// Stands in for the answer while hints are shown.
+    HintsShown,
+    HintStartsVowel,
+    HintStartsConsonant,
+    HintEndsVowel,
+    HintConsonantCluster,
+    HintDoubleLetter,
+    /// `{}` is a spelling such as `TH`.
+    HintContains,
+    /// `{}` is a beginning such as `UN`.
+    HintStartsWith,
+    /// `{}` is an ending such as `ING`.
+    HintEndsIn,
+    /// Heads the suggestions for an entry with a mistyped letter.
+    DidYouMean,
+    /// `{}` is what was copied.
+    Copied,
+    ConfirmedWord,
+    AlreadyConfirmed,
+    /// Shown when the app is launched again while it's open.
+    AlreadyOpen,
+    RankTitle,
+    RankHint,
+    RankWord,
+    RankFrequency,
+    RankPinned,
+    RankConfirmed,
+    RankInflection,
+    RankScrabble,
+    RankTotal,
+    OneMatch,
+    /// `{}` is the number of matches.
+    ManyMatches,
+    AnnounceSearching,
+    ErrorTitle,
+    LoadFailed,
+    EmptyDictionary,
+    ErrorHint,
+    SettingsTitle,
+    SettingsHint,
+    SettingDictionary,
+    SettingTheme,
+    SettingTextSize,
+    SettingSound,
+    SettingVolume,
+    SettingBigMode,
+    SettingReducedMotion,
+    SettingKeyboard,
+    SettingKeyboardLayout,
+    SettingLetters,
+    SettingPhonetic,
+    SettingCopyPhonetic,
+    SettingAutoEscalate,
+    SettingMaskWords,
+    SettingAssist,
+    SettingAssistSeconds,
+    SettingUsageMetrics,
+    SettingRankFrequency,
+    SettingRankPinned,
+    SettingRankConfirmed,
+    SettingRankInflection,
+    SettingRankScrabble,
+    SettingRankReset,
+    /// `{}` is the entry's matches, best first.
+    RankPreview,
+    On,
+    Off,
+    DefaultDictionary,
+    /// `{}` is why the word list can't be used.
+    DictionaryRejected,
+    HelpTitle,
+    HelpHint,
+    HelpEditing,
+    HelpModes,
+    HelpOverlays,
+    HelpTabs,
+    HelpPins,
+    HelpLengths,
+    HelpApplication,
+    ActionDeleteBack,
+    ActionClearEntry,
+    ActionQuit,
+    ActionToggleDebug,
+    ActionCycleTheme,
+    ActionToggleSubAnagrams,
+    ActionToggleMute,
+    ActionToggleBigMode,
+    ActionTextLarger,
+    ActionTextSmaller,
+    ActionTextReset,
+    ActionOpenSettings,
+    ActionShowHelp,
+    ActionCommandPalette,
+    ActionNewTab,
+    ActionCloseTab,
+    ActionNextTab,
+    ActionCycleStats,
+    ActionTogglePin,
+    ActionClearPins,
+    ActionShowLength4,
+    ActionShowLength5,
+    ActionShowLength6,
+    ActionShowLength7,
+    ActionShowLength8,
+    ActionShowLength9,
+    ActionShowAllLengths,
+    ActionShowHint,
+    ActionTogglePhonetic,
+    ActionCopyAnswer,
+    ActionExplainRanking,
+    ActionConfirmAnswer,
+    ActionRevealHidden,
+    ActionSpellFinal,
+    ActionToggleMini,
+    ActionComplete,
+    ActionInspectWord,
+    ActionPrintResults,
+    ActionToggleRevealLock,
+    SettingsSaved,
+    SettingsNotSaved,
+    DictionaryLoaded,
+    OnboardingDemoTitle,
+    OnboardingDemoText,
+    OnboardingKeysTitle,
+    OnboardingKeysText,
+    OnboardingPickTitle,
+    OnboardingHint,
+    OnboardingPickHint,
+    /// `{}` is the scramble to type.
+    TutorialType,
+    TutorialCommit,
+    /// `{}` is the next scramble.
+    TutorialNext,
+    /// `{}` is the keys that clear the entry.
+    TutorialCircled,
+    /// `{}` is the keys that open the panel for the final answer.
+    TutorialFinal,
+    TutorialDone,
+    TutorialHint,
+    /// `{}` is the most tabs that can be open.
+    TabLimit,
+    /// `{}` is the most words that can be pinned.
+    PinLimit,
+    /// `{}` is the date the answer was last found for the same scramble.
+    LastSeen,
+    /// `{}` is why the post-processing command's list wasn't used.
+    PostProcessFailed,
+    /// Pasted text with no scramble to be found in it.
+    NoScramble,
+    /// A pasted blank pattern now limits the matches; `{}` is the pattern.
+    PatternApplied,
+    /// `{}` is what's wrong with the query command in the entry.
+    QueryError,
+    /// A query command costly enough to wait for Enter (see `query::cost`).
+    SlowQuery,
+    /// `{}` is the word the entry's letters spell as typed, its only match.
+    AlreadySpells,
+    /// Marks the match the entry's letters spell as typed.
+    AsTyped,
+    /// Labels the entries a letter away shown when nothing matches exactly.
+    EscalatedTypo,
+    /// Labels the words a letter shorter shown when nothing matches exactly.
+    EscalatedDropOne,
+    WordsHidden,
+    PoolTitle,
+    PoolHint,
+    SpectateConnecting,
+    SpectateWatching,
+    SpectateLost,
+    SpectateSolved,
+    /// How many words could complete the entry; `{}` is the count.
+    CompletionsCount,
+    /// The words that could complete the entry; `{}` is the list.
+    CompletionsTab,
+    /// Labels of the word inspector's rows (see `inspect`).
+    InspectKey,
+    InspectAnagrams,
+    InspectSource,
+    InspectFrequency,
+    InspectScrabble,
+    InspectConfirmed,
+    InspectHidden,
+    InspectPinned,
+    /// Shown for a row of the word inspector with nothing in it.
+    InspectNone,
+    InspectYes,
+    InspectNo,
+    /// Keys of the word inspector.
+    InspectHint,
+    /// A word was added to the mask; `{}` is the word.
+    HiddenWord,
+    /// The word to mask already was; `{}` is the word.
+    AlreadyHidden,
+    /// Shown in the entry box while the left-hand preset is on.
+    InputLeftHand,
+    /// Shown once the left-hand prefix is typed, until the next letter.
+    InputMirrorNext,
+    /// Shown in the entry box while keys are remapped.
+    InputRemapped,
+    /// Shown instead of the answer while it's held back; `{}` is the seconds
+    /// left.
+    AssistCountdown,
+    /// Written faint over the attract mode's demo.
+    AttractWatermark,
+    /// Marks a match a one-letter change of the entry brought in.
+    NewMatch,
+    /// Follows the app's name in the window title with several tabs open; `{}`
+    /// is how many are solved, e.g. `2/4`.
+    TitleSolved,
+    /// Says a newer release is out; `{}` is its version.
+    UpdateAvailable,
+    /// Leaves no letter out, in the settings screen.
+    LettersAll,
+    /// Names the letters left out of the matches; `{}` is them, e.g. `QXZ`.
+    LettersLeftOut,
+    /// Heads the final answer on the printable page; `{}` is the answer.
+    PrintFinalAnswer,
+    /// The printable page was written and opened; `{}` is its path.
+    PrintOpened,
+    /// The printable page was written but nothing opened it; `{}` is its path.
+    PrintSaved,
+    /// The printable page couldn't be written; `{}` is why.
+    PrintFailed,
+    /// Locking the answers away; `{}` is the key to hold to unlock them.
+    AnswersLocked,
+    AnswersUnlocked,
+    /// Refusing an action that would show a word while the answers are locked.
+    AnswersStillLocked,
+}
+
+impl Language {
+    /// Returns this language's text for `key`.
+    pub fn text(self, key: StringKey) -> &'static str {
+        match self {
+            Language::En => english(key),
+            Language::Fr => french(key),
+            Language::Es => spanish(key),
+        }
+    }
+    /// Returns the language's code, as written in the config.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Fr => "fr",
+            Language::Es => "es",
+        }
+    }
+    /// Returns the word list used by default for this language, if it has its own.
+    ///
+    /// English uses the embedded list.
+    pub fn dictionary_file(self) -> Option<&'static str> {
+        match self {
+            Language::En => None,
+            Language::Fr => Some("dictionary/FRENCH_4_TO_8.txt"),
+            Language::Es => Some("dictionary/SPANISH_4_TO_8.txt"),
+        }
+    }
+}
+
+/// Replaces the `{}` placeholder in `text` with `value`.
+pub fn fill(text: &str, value: impl fmt::Display) -> String {
+    text.replacen("{}", &value.to_string(), 1)
+}
+
+fn english(key: StringKey) -> &'static str {
+    match key {
+        StringKey::AppTitle => "Jumble Helper",
+        StringKey::LoadedWords => "Loaded {} words",
+        StringKey::Searching => "searching...",
+        StringKey::KeyDelete => "DEL",
+        StringKey::KeyClear => "CLR",
+        StringKey::QuitPrompt => "Quit and lose this puzzle?",
+        StringKey::QuitChoices => "[Enter] quit, [Esc] stay, [S] save & quit",
+        StringKey::RestorePrompt => "Restore the tabs left open when the app last crashed?",
+        StringKey::RestoreChoices => "[Enter] restore, [Esc] start afresh",
+        StringKey::EntryCleared => "Entry cleared.",
+        StringKey::EntryIs => "Entry {}.",
+        StringKey::AnswerIs => "Answer: {}.",
+        StringKey::NoMatches => "No matches.",
+        StringKey::DidYouMean => "One letter off:",
+        StringKey::Copied => "Copied {}",
+        StringKey::ConfirmedWord => "Confirmed {}",
+        StringKey::AlreadyConfirmed => "{} was already confirmed",
+        StringKey::AlreadyOpen => "Jumble Helper is already open",
+        StringKey::RankTitle => "Why the matches rank as they do",
+        StringKey::RankHint => "Any key closes",
+        StringKey::RankWord => "Word",
+        StringKey::RankFrequency => "Frequency",
+        StringKey::RankPinned => "Pinned",
+        StringKey::RankConfirmed => "Confirmed",
+        StringKey::RankInflection => "Inflected",
+        StringKey::RankScrabble => "Scrabble",
+        StringKey::RankTotal => "Total",
+        StringKey::HintsShown => "Hints (answer hidden)",
+        StringKey::HintStartsVowel => "Starts with a vowel",
+        StringKey::HintStartsConsonant => "Starts with a consonant",
+        StringKey::HintEndsVowel => "Ends in a vowel",
+        StringKey::HintConsonantCluster => "Starts with a consonant cluster",
+        StringKey::HintDoubleLetter => "Contains a double letter",
+        StringKey::HintContains => "Contains {}",
+        StringKey::HintStartsWith => "Starts with {}-",
+        StringKey::HintEndsIn => "Ends in -{}",
+        StringKey::OneMatch => "1 match.",
+        StringKey::ManyMatches => "{} matches.",
+        StringKey::AnnounceSearching => "Searching.",
+        StringKey::ErrorTitle => "Something went wrong",
+        StringKey::LoadFailed => "The word list couldn't be loaded.",
+        StringKey::EmptyDictionary => "The word list has no words in it.",
+        StringKey::ErrorHint => "Check the [dictionary] settings, then restart.",
+        StringKey::SettingsTitle => "Settings",
+        StringKey::SettingsHint => "[Up/Down] choose, [Left/Right] change, [Esc] back",
+        StringKey::SettingDictionary => "Word list",
+        StringKey::SettingTheme => "Theme",
+        StringKey::SettingTextSize => "Text size",
+        StringKey::SettingSound => "Sound",
+        StringKey::SettingVolume => "Volume",
+        StringKey::SettingBigMode => "Large print",
+        StringKey::SettingReducedMotion => "Reduce motion",
+        StringKey::SettingKeyboard => "On-screen keyboard",
+        StringKey::SettingKeyboardLayout => "Keyboard layout",
+        StringKey::SettingLetters => "Letters left out",
+        StringKey::SettingPhonetic => "Spell out the answer",
+        StringKey::SettingCopyPhonetic => "Copy it spelled out",
+        StringKey::SettingRankFrequency => "Ranking: frequency",
+        StringKey::SettingRankPinned => "Ranking: pinned bonus",
+        StringKey::SettingRankConfirmed => "Ranking: confirmed bonus",
+        StringKey::SettingRankInflection => "Ranking: inflection penalty",
+        StringKey::SettingRankScrabble => "Ranking: Scrabble points",
+        StringKey::SettingRankReset => "Reset ranking weights",
+        StringKey::RankPreview => "Ranked: {}",
+        StringKey::On => "On",
+        StringKey::Off => "Off",
+        StringKey::DefaultDictionary => "Default",
+        StringKey::DictionaryRejected => "Can't use that word list: {}",
+        StringKey::HelpTitle => "Keys",
+        StringKey::HelpHint => "Enter: practice puzzle   Any other key: close",
+        StringKey::HelpEditing => "Editing",
+        StringKey::HelpModes => "Modes",
+        StringKey::HelpOverlays => "Overlays",
+        StringKey::HelpTabs => "Tabs",
+        StringKey::HelpPins => "Pinned words",
+        StringKey::HelpLengths => "Word lengths",
+        StringKey::HelpApplication => "Application",
+        StringKey::ActionDeleteBack => "Delete last letter",
+        StringKey::ActionClearEntry => "Clear entry",
+        StringKey::ActionQuit => "Quit",
+        StringKey::ActionToggleDebug => "Toggle debug overlay",
+        StringKey::ActionCycleTheme => "Cycle color theme",
+        StringKey::ActionToggleSubAnagrams => "Toggle sub-anagram search",
+        StringKey::ActionToggleMute => "Mute or unmute sounds",
+        StringKey::ActionToggleBigMode => "Toggle large-print mode",
+        StringKey::ActionTextLarger => "Larger text",
+        StringKey::ActionTextSmaller => "Smaller text",
+        StringKey::ActionTextReset => "Reset text size",
+        StringKey::ActionOpenSettings => "Open settings",
+        StringKey::ActionShowHelp => "Show key bindings",
+        StringKey::ActionCommandPalette => "Find an action by name",
+        StringKey::ActionNewTab => "Open a new entry tab",
+        StringKey::ActionCloseTab => "Close the entry tab",
+        StringKey::ActionNextTab => "Switch to the next entry tab",
+        StringKey::ActionCycleStats => "Show dictionary stats, then a letter chart",
+        StringKey::ActionTogglePin => "Pin or unpin the selected word",
+        StringKey::ActionClearPins => "Unpin every word",
+        StringKey::ActionShowLength4 => "Show or hide 4-letter words",
+        StringKey::ActionShowLength5 => "Show or hide 5-letter words",
+        StringKey::ActionShowLength6 => "Show or hide 6-letter words",
+        StringKey::ActionShowLength7 => "Show or hide 7-letter words",
+        StringKey::ActionShowLength8 => "Show or hide 8-letter words",
+        StringKey::ActionShowLength9 => "Show or hide 9-letter words",
+        StringKey::ActionShowAllLengths => "Show words of every length",
+        StringKey::ActionShowHint => "Hide the matches and hint at the answer",
+        StringKey::ActionTogglePhonetic => "Spell out the answer phonetically",
+        StringKey::ActionCopyAnswer => "Copy the answer",
+        StringKey::ActionExplainRanking => "Show how the matches rank",
+        StringKey::ActionConfirmAnswer => "Mark the answer as seen in print",
+        StringKey::ActionRevealHidden => "Show the vulgar words left out",
+        StringKey::ActionSpellFinal => "Spell the final answer from the entry's letters",
+        StringKey::ActionToggleMini => "Switch to the mini window and back",
+        StringKey::ActionComplete => "Type the rest of the word suggested",
+        StringKey::ActionInspectWord => "Inspect the selected word",
+        StringKey::ActionPrintResults => "Open a page of the results to print",
+        StringKey::ActionToggleRevealLock => "Lock the answers away (hold to unlock)",
+        StringKey::SettingsSaved => "Settings saved",
+        StringKey::SettingsNotSaved => "Settings couldn't be saved",
+        StringKey::DictionaryLoaded => "Word list loaded",
+        StringKey::OnboardingDemoTitle => "Type the scrambled letters",
+        StringKey::OnboardingDemoText => {
+            "Capitals or not, it doesn't matter. The answer shows once only one word fits."
+        }
+        StringKey::OnboardingKeysTitle => "Handy keys",
+        StringKey::OnboardingKeysText => "Press {} any time to see them all.",
+        StringKey::OnboardingPickTitle => "Choose a word list and text size",
+        StringKey::OnboardingHint => "Enter: next   Left: back   Esc: skip",
+        StringKey::OnboardingPickHint => "Up/Down: choose   Left/Right: change   Enter: start",
+        StringKey::TutorialType => "Type the scrambled letters {}; the keys to press are outlined",
+        StringKey::TutorialCommit => "That's the answer. Press Enter to write it in the puzzle",
+        StringKey::TutorialNext => "Now solve {} the same way, and press Enter",
+        StringKey::TutorialCircled => "Press {} to clear the entry, then type the circled letters",
+        StringKey::TutorialFinal => {
+            "Press {} and spell the answer from them: what to do with a new trick, or a new style?"
+        }
+        StringKey::TutorialDone => "Solved! Press Enter to finish",
+        StringKey::TutorialHint => "Esc: skip the tutorial",
+        StringKey::TabLimit => "At most {} tabs can be open",
+        StringKey::PinLimit => "At most {} words can be pinned",
+        StringKey::LastSeen => "last seen {}",
+        StringKey::PostProcessFailed => "Post-processor failed: {}",
+        StringKey::NoScramble => "No scramble found in the pasted text",
+        StringKey::PatternApplied => "Only answers like {}",
+        StringKey::QueryError => "Query: {}",
+        StringKey::SlowQuery => "This search may take a while; press Enter to run it",
+        StringKey::AlreadySpells => "These letters already spell {} — no other anagram found",
+        StringKey::AsTyped => "(as typed)",
+        StringKey::EscalatedTypo => "No exact match — showing close words",
+        StringKey::EscalatedDropOne => "No exact match — showing words a letter shorter",
+        StringKey::WordsHidden => "({} hidden)",
+        StringKey::PoolTitle => "Final answer",
+        StringKey::PoolHint => "{} letters left. Backspace gives one back; Esc closes.",
+        StringKey::SpectateConnecting => "Connecting to {}...",
+        StringKey::SpectateWatching => "Watching {} (read-only)",
+        StringKey::SpectateLost => "Lost {} ({}); trying again...",
+        StringKey::SpectateSolved => "Solved: {}",
+        StringKey::CompletionsCount => "{} words possible",
+        StringKey::CompletionsTab => "Tab: {}",
+        StringKey::InspectKey => "Sorted letters",
+        StringKey::InspectAnagrams => "Same letters",
+        StringKey::InspectSource => "Word list",
+        StringKey::InspectFrequency => "Frequency",
+        StringKey::InspectScrabble => "Scrabble points",
+        StringKey::InspectConfirmed => "Confirmed",
+        StringKey::InspectHidden => "Hidden",
+        StringKey::InspectPinned => "Pinned",
+        StringKey::InspectNone => "none",
+        StringKey::InspectYes => "yes",
+        StringKey::InspectNo => "no",
+        StringKey::InspectHint => "[X] hide, [C] confirm, [B] pin, [Esc] close",
+        StringKey::HiddenWord => "{} is hidden from now on",
+        StringKey::AlreadyHidden => "{} is already hidden",
+        StringKey::InputLeftHand => "Left hand",
+        StringKey::InputMirrorNext => "Left hand: next letter mirrored",
+        StringKey::InputRemapped => "Keys remapped",
+        StringKey::AssistCountdown => "Answer in {} s ([Enter] now)",
+        StringKey::AttractWatermark => "DEMO",
+        StringKey::NewMatch => "(new)",
+        StringKey::TitleSolved => "{} solved",
+        StringKey::UpdateAvailable => "Version {} is out",
+        StringKey::LettersAll => "None",
+        StringKey::LettersLeftOut => "Without {}",
+        StringKey::PrintFinalAnswer => "Final answer: {}",
+        StringKey::PrintOpened => "Opened {} to print",
+        StringKey::PrintSaved => "Saved {} to print",
+        StringKey::PrintFailed => "Can't write the page to print: {}",
+        StringKey::AnswersLocked => "Answers locked; hold {} to show them",
+        StringKey::AnswersUnlocked => "Answers unlocked",
+        StringKey::AnswersStillLocked => "The answers are locked",
+        StringKey::SettingAutoEscalate => "Show close words when nothing matches",
+        StringKey::SettingMaskWords => "Hide vulgar words",
+        StringKey::SettingAssist => "Hold back the answer",
+        StringKey::SettingAssistSeconds => "Seconds before it shows",
+        StringKey::SettingUsageMetrics => "Count features used, locally",
+    }
+}
+
+fn french(key: StringKey) -> &'static str {
+    match key {
+        StringKey::AppTitle => "Le Mélimélo",
+        StringKey::LoadedWords => "{} mots chargés",
+        StringKey::Searching => "recherche...",
+        StringKey::KeyDelete => "EFF",
+        StringKey::KeyClear => "VID",
+        StringKey::QuitPrompt => "Quitter et perdre ce puzzle ?",
+        StringKey::QuitChoices => "[Entrée] quitter, [Échap] rester, [S] sauver et quitter",
+        StringKey::RestorePrompt => "Restaurer les onglets ouverts lors du dernier plantage ?",
+        StringKey::RestoreChoices => "[Entrée] restaurer, [Échap] repartir de zéro",
+        StringKey::EntryCleared => "Saisie effacée.",
+        StringKey::EntryIs => "Saisie {}.",
+        StringKey::AnswerIs => "Réponse : {}.",
+        StringKey::NoMatches => "Aucun mot.",
+        StringKey::DidYouMean => "À une lettre près :",
+        StringKey::Copied => "{} copié",
+        StringKey::ConfirmedWord => "{} confirmé",
+        StringKey::AlreadyConfirmed => "{} était déjà confirmé",
+        StringKey::AlreadyOpen => "Jumble Helper est déjà ouvert",
+        StringKey::RankTitle => "Pourquoi ce classement",
+        StringKey::RankHint => "Une touche pour fermer",
+        StringKey::RankWord => "Mot",
+        StringKey::RankFrequency => "Fréquence",
+        StringKey::RankPinned => "Épinglé",
+        StringKey::RankConfirmed => "Confirmé",
+        StringKey::RankInflection => "Forme fléchie",
+        StringKey::RankScrabble => "Scrabble",
+        StringKey::RankTotal => "Total",
+        StringKey::HintsShown => "Indices (réponse cachée)",
+        StringKey::HintStartsVowel => "Commence par une voyelle",
+        StringKey::HintStartsConsonant => "Commence par une consonne",
+        StringKey::HintEndsVowel => "Finit par une voyelle",
+        StringKey::HintConsonantCluster => "Commence par deux consonnes",
+        StringKey::HintDoubleLetter => "Contient une lettre double",
+        StringKey::HintContains => "Contient {}",
+        StringKey::HintStartsWith => "Commence par {}-",
+        StringKey::HintEndsIn => "Finit par -{}",
+        StringKey::OneMatch => "1 mot.",
+        StringKey::ManyMatches => "{} mots.",
+        StringKey::AnnounceSearching => "Recherche.",
+        StringKey::ErrorTitle => "Un problème est survenu",
+        StringKey::LoadFailed => "Impossible de charger la liste de mots.",
+        StringKey::EmptyDictionary => "La liste de mots est vide.",
+        StringKey::ErrorHint => "Vérifiez les réglages [dictionary], puis relancez.",
+        StringKey::SettingsTitle => "Réglages",
+        StringKey::SettingsHint => "[Haut/Bas] choisir, [Gauche/Droite] modifier, [Échap] retour",
+        StringKey::SettingDictionary => "Liste de mots",
+        StringKey::SettingTheme => "Thème",
+        StringKey::SettingTextSize => "Taille du texte",
+        StringKey::SettingSound => "Son",
+        StringKey::SettingVolume => "Volume",
+        StringKey::SettingBigMode => "Gros caractères",
+        StringKey::SettingReducedMotion => "Réduire les animations",
+        StringKey::SettingKeyboard => "Clavier à l'écran",
+        StringKey::SettingKeyboardLayout => "Disposition du clavier",
+        StringKey::SettingLetters => "Lettres exclues",
+        StringKey::SettingPhonetic => "Épeler la réponse",
+        StringKey::SettingCopyPhonetic => "La copier épelée",
+        StringKey::SettingRankFrequency => "Classement : fréquence",
+        StringKey::SettingRankPinned => "Classement : bonus épinglé",
+        StringKey::SettingRankConfirmed => "Classement : bonus confirmé",
+        StringKey::SettingRankInflection => "Classement : pénalité de flexion",
+        StringKey::SettingRankScrabble => "Classement : points au Scrabble",
+        StringKey::SettingRankReset => "Rétablir les poids du classement",
+        StringKey::RankPreview => "Classés : {}",
+        StringKey::On => "Oui",
+        StringKey::Off => "Non",
+        StringKey::DefaultDictionary => "Par défaut",
+        StringKey::DictionaryRejected => "Liste de mots inutilisable : {}",
+        StringKey::HelpTitle => "Touches",
+        StringKey::HelpHint => "Entrée : puzzle d'entraînement   Autre touche : fermer",
+        StringKey::HelpEditing => "Saisie",
+        StringKey::HelpModes => "Modes",
+        StringKey::HelpOverlays => "Affichages",
+        StringKey::HelpTabs => "Onglets",
+        StringKey::HelpPins => "Mots épinglés",
+        StringKey::HelpLengths => "Longueurs des mots",
+        StringKey::HelpApplication => "Application",
+        StringKey::ActionDeleteBack => "Effacer la dernière lettre",
+        StringKey::ActionClearEntry => "Vider la saisie",
+        StringKey::ActionQuit => "Quitter",
+        StringKey::ActionToggleDebug => "Afficher le débogage",
+        StringKey::ActionCycleTheme => "Changer de thème",
+        StringKey::ActionToggleSubAnagrams => "Chercher les sous-anagrammes",
+        StringKey::ActionToggleMute => "Couper ou rétablir le son",
+        StringKey::ActionToggleBigMode => "Mode gros caractères",
+        StringKey::ActionTextLarger => "Texte plus grand",
+        StringKey::ActionTextSmaller => "Texte plus petit",
+        StringKey::ActionTextReset => "Taille de texte par défaut",
+        StringKey::ActionOpenSettings => "Ouvrir les réglages",
+        StringKey::ActionShowHelp => "Afficher les touches",
+        StringKey::ActionCommandPalette => "Trouver une action par son nom",
+        StringKey::ActionNewTab => "Ouvrir un nouvel onglet",
+        StringKey::ActionCloseTab => "Fermer l'onglet",
+        StringKey::ActionNextTab => "Passer à l'onglet suivant",
+        StringKey::ActionCycleStats => "Afficher les statistiques du dictionnaire, puis un graphique des lettres",
+        StringKey::ActionTogglePin => "Épingler ou désépingler le mot sélectionné",
+        StringKey::ActionClearPins => "Désépingler tous les mots",
+        StringKey::ActionShowLength4 => "Afficher ou masquer les mots de 4 lettres",
+        StringKey::ActionShowLength5 => "Afficher ou masquer les mots de 5 lettres",
+        StringKey::ActionShowLength6 => "Afficher ou masquer les mots de 6 lettres",
+        StringKey::ActionShowLength7 => "Afficher ou masquer les mots de 7 lettres",
+        StringKey::ActionShowLength8 => "Afficher ou masquer les mots de 8 lettres",
+        StringKey::ActionShowLength9 => "Afficher ou masquer les mots de 9 lettres",
+        StringKey::ActionShowAllLengths => "Afficher les mots de toutes les longueurs",
+        StringKey::ActionShowHint => "Masquer les résultats et donner un indice sur la réponse",
+        StringKey::ActionTogglePhonetic => "Épeler la réponse en alphabet radio",
+        StringKey::ActionCopyAnswer => "Copier la réponse",
+        StringKey::ActionExplainRanking => "Montrer le classement des résultats",
+        StringKey::ActionConfirmAnswer => "Marquer la réponse comme vue dans le journal",
+        StringKey::ActionRevealHidden => "Afficher les mots vulgaires masqués",
+        StringKey::ActionSpellFinal => "Épeler la réponse finale avec les lettres saisies",
+        StringKey::ActionToggleMini => "Passer à la mini-fenêtre et revenir",
+        StringKey::ActionComplete => "Taper la fin du mot suggéré",
+        StringKey::ActionInspectWord => "Examiner le mot sélectionné",
+        StringKey::ActionPrintResults => "Ouvrir une page des résultats à imprimer",
+        StringKey::ActionToggleRevealLock => "Mettre les réponses sous clé (maintenir pour déverrouiller)",
+        StringKey::SettingsSaved => "Réglages enregistrés",
+        StringKey::SettingsNotSaved => "Impossible d'enregistrer les réglages",
+        StringKey::DictionaryLoaded => "Liste de mots chargée",
+        StringKey::OnboardingDemoTitle => "Tapez les lettres mélangées",
+        StringKey::OnboardingDemoText => "Majuscules ou minuscules, peu importe. La réponse s'affiche dès qu'un seul mot convient.",
+        StringKey::OnboardingKeysTitle => "Touches utiles",
+        StringKey::OnboardingKeysText => "Appuyez sur {} à tout moment pour les voir toutes.",
+        StringKey::OnboardingPickTitle => "Choisissez une liste de mots et une taille de texte",
+        StringKey::OnboardingHint => "Entrée : suivant   Gauche : retour   Échap : passer",
+        StringKey::OnboardingPickHint => "Haut/Bas : choisir   Gauche/Droite : changer   Entrée : commencer",
+        StringKey::TutorialType => "Tapez les lettres mélangées {} ; les touches à presser sont encadrées",
+        StringKey::TutorialCommit => "Voilà la réponse. Entrée pour l'inscrire dans la grille",
+        StringKey::TutorialNext => "Résolvez maintenant {} de la même façon, puis Entrée",
+        StringKey::TutorialCircled => "Appuyez sur {} pour vider la saisie, puis tapez les lettres entourées",
+        StringKey::TutorialFinal => "Appuyez sur {} et formez avec elles la réponse, en anglais : que faire d'un nouveau tour, ou d'un nouveau style ?",
+        StringKey::TutorialDone => "Résolu ! Entrée pour terminer",
+        StringKey::TutorialHint => "Échap : passer le tutoriel",
+        StringKey::TabLimit => "{} onglets au plus peuvent être ouverts",
+        StringKey::PinLimit => "{} mots au plus peuvent être épinglés",
+        StringKey::LastSeen => "déjà vu le {}",
+        StringKey::PostProcessFailed => "Échec du post-traitement : {}",
+        StringKey::NoScramble => "Aucun mot mélangé dans le texte collé",
+        StringKey::PatternApplied => "Seulement les réponses en {}",
+        StringKey::QueryError => "Requête : {}",
+        StringKey::SlowQuery => "Cette recherche peut être longue ; Entrée pour la lancer",
+        StringKey::AlreadySpells => "Ces lettres forment déjà {} — aucune autre anagramme",
+        StringKey::AsTyped => "(tel quel)",
+        StringKey::EscalatedTypo => "Aucun mot exact — mots proches",
+        StringKey::EscalatedDropOne => "Aucun mot exact — mots d'une lettre de moins",
+        StringKey::WordsHidden => "({} masqués)",
+        StringKey::PoolTitle => "Réponse finale",
+        StringKey::PoolHint => "{} lettres restantes. Retour arrière en rend une ; Échap ferme.",
+        StringKey::SpectateConnecting => "Connexion à {}...",
+        StringKey::SpectateWatching => "Vous regardez {} (lecture seule)",
+        StringKey::SpectateLost => "{} perdu ({}) ; nouvel essai...",
+        StringKey::SpectateSolved => "Résolus : {}",
+        StringKey::CompletionsCount => "{} mots possibles",
+        StringKey::CompletionsTab => "Tab : {}",
+        StringKey::InspectKey => "Lettres triées",
+        StringKey::InspectAnagrams => "Mêmes lettres",
+        StringKey::InspectSource => "Liste de mots",
+        StringKey::InspectFrequency => "Fréquence",
+        StringKey::InspectScrabble => "Points au Scrabble",
+        StringKey::InspectConfirmed => "Confirmé",
+        StringKey::InspectHidden => "Masqué",
+        StringKey::InspectPinned => "Épinglé",
+        StringKey::InspectNone => "aucun",
+        StringKey::InspectYes => "oui",
+        StringKey::InspectNo => "non",
+        StringKey::InspectHint => "[X] masquer, [C] confirmer, [B] épingler, [Échap] fermer",
+        StringKey::HiddenWord => "{} est masqué désormais",
+        StringKey::AlreadyHidden => "{} est déjà masqué",
+        StringKey::InputLeftHand => "Main gauche",
+        StringKey::InputMirrorNext => "Main gauche : lettre suivante en miroir",
+        StringKey::InputRemapped => "Touches réaffectées",
+        StringKey::AssistCountdown => "Réponse dans {} s ([Entrée] tout de suite)",
+        StringKey::AttractWatermark => "DÉMO",
+        StringKey::NewMatch => "(nouveau)",
+        StringKey::TitleSolved => "{} résolus",
+        StringKey::UpdateAvailable => "La version {} est sortie",
+        StringKey::LettersAll => "Aucune",
+        StringKey::LettersLeftOut => "Sans {}",
+        StringKey::PrintFinalAnswer => "Réponse finale : {}",
+        StringKey::PrintOpened => "{} ouvert pour l'impression",
+        StringKey::PrintSaved => "{} enregistré pour l'impression",
+        StringKey::PrintFailed => "Impossible d'écrire la page à imprimer : {}",
+        StringKey::AnswersLocked => "Réponses verrouillées ; maintenez {} pour les voir",
+        StringKey::AnswersUnlocked => "Réponses déverrouillées",
+        StringKey::AnswersStillLocked => "Les réponses sont verrouillées",
+        StringKey::SettingAutoEscalate => "Mots proches si rien ne convient",
+        StringKey::SettingMaskWords => "Masquer les mots vulgaires",
+        StringKey::SettingAssist => "Retenir la réponse",
+        StringKey::SettingAssistSeconds => "Secondes avant de l'afficher",
+        StringKey::SettingUsageMetrics => "Compter les fonctions, en local",
+    }
+}
+
+fn spanish(key: StringKey) -> &'static str {
+    match key {
+        StringKey::AppTitle => "Ayudante de Jumble",
+        StringKey::LoadedWords => "{} palabras cargadas",
+        StringKey::Searching => "buscando...",
+        StringKey::KeyDelete => "BOR",
+        StringKey::KeyClear => "LIM",
+        StringKey::QuitPrompt => "¿Salir y perder este puzle?",
+        StringKey::QuitChoices => "[Intro] salir, [Esc] quedarse, [S] guardar y salir",
+        StringKey::RestorePrompt => "¿Restaurar las pestañas abiertas en el último cierre inesperado?",
+        StringKey::RestoreChoices => "[Intro] restaurar, [Esc] empezar de nuevo",
+        StringKey::EntryCleared => "Entrada borrada.",
+        StringKey::EntryIs => "Entrada {}.",
+        StringKey::AnswerIs => "Respuesta: {}.",
+        StringKey::NoMatches => "Sin resultados.",
+        StringKey::DidYouMean => "Con una letra cambiada:",
+        StringKey::Copied => "Copiado: {}",
+        StringKey::ConfirmedWord => "Confirmada: {}",
+        StringKey::AlreadyConfirmed => "{} ya estaba confirmada",
+        StringKey::AlreadyOpen => "Jumble Helper ya está abierto",
+        StringKey::RankTitle => "Por qué este orden",
+        StringKey::RankHint => "Cualquier tecla cierra",
+        StringKey::RankWord => "Palabra",
+        StringKey::RankFrequency => "Frecuencia",
+        StringKey::RankPinned => "Fijada",
+        StringKey::RankConfirmed => "Confirmada",
+        StringKey::RankInflection => "Flexionada",
+        StringKey::RankScrabble => "Scrabble",
+        StringKey::RankTotal => "Total",
+        StringKey::HintsShown => "Pistas (respuesta oculta)",
+        StringKey::HintStartsVowel => "Empieza por vocal",
+        StringKey::HintStartsConsonant => "Empieza por consonante",
+        StringKey::HintEndsVowel => "Termina en vocal",
+        StringKey::HintConsonantCluster => "Empieza por dos consonantes",
+        StringKey::HintDoubleLetter => "Tiene una letra doble",
+        StringKey::HintContains => "Contiene {}",
+        StringKey::HintStartsWith => "Empieza por {}-",
+        StringKey::HintEndsIn => "Termina en -{}",
+        StringKey::OneMatch => "1 resultado.",
+        StringKey::ManyMatches => "{} resultados.",
+        StringKey::AnnounceSearching => "Buscando.",
+        StringKey::ErrorTitle => "Algo salió mal",
+        StringKey::LoadFailed => "No se pudo cargar la lista de palabras.",
+        StringKey::EmptyDictionary => "La lista de palabras está vacía.",
+        StringKey::ErrorHint => "Revise los ajustes de [dictionary] y reinicie.",
+        StringKey::SettingsTitle => "Ajustes",
+        StringKey::SettingsHint => "[Arriba/Abajo] elegir, [Izq./Der.] cambiar, [Esc] volver",
+        StringKey::SettingDictionary => "Lista de palabras",
+        StringKey::SettingTheme => "Tema",
+        StringKey::SettingTextSize => "Tamaño del texto",
+        StringKey::SettingSound => "Sonido",
+        StringKey::SettingVolume => "Volumen",
+        StringKey::SettingBigMode => "Letra grande",
+        StringKey::SettingReducedMotion => "Reducir el movimiento",
+        StringKey::SettingKeyboard => "Teclado en pantalla",
+        StringKey::SettingKeyboardLayout => "Distribución del teclado",
+        StringKey::SettingLetters => "Letras excluidas",
+        StringKey::SettingPhonetic => "Deletrear la respuesta",
+        StringKey::SettingCopyPhonetic => "Copiarla deletreada",
+        StringKey::SettingRankFrequency => "Orden: frecuencia",
+        StringKey::SettingRankPinned => "Orden: bonus por fijar",
+        StringKey::SettingRankConfirmed => "Orden: bonus por confirmar",
+        StringKey::SettingRankInflection => "Orden: penalización por flexión",
+        StringKey::SettingRankScrabble => "Orden: puntos de Scrabble",
+        StringKey::SettingRankReset => "Restablecer los pesos del orden",
+        StringKey::RankPreview => "En orden: {}",
+        StringKey::On => "Sí",
+        StringKey::Off => "No",
+        StringKey::DefaultDictionary => "Predeterminada",
+        StringKey::DictionaryRejected => "No se puede usar esa lista: {}",
+        StringKey::HelpTitle => "Teclas",
+        StringKey::HelpHint => "Intro: pasatiempo de práctica   Otra tecla: cerrar",
+        StringKey::HelpEditing => "Edición",
+        StringKey::HelpModes => "Modos",
+        StringKey::HelpOverlays => "Superposiciones",
+        StringKey::HelpTabs => "Pestañas",
+        StringKey::HelpPins => "Palabras fijadas",
+        StringKey::HelpLengths => "Longitudes de palabra",
+        StringKey::HelpApplication => "Aplicación",
+        StringKey::ActionDeleteBack => "Borrar la última letra",
+        StringKey::ActionClearEntry => "Borrar la entrada",
+        StringKey::ActionQuit => "Salir",
+        StringKey::ActionToggleDebug => "Mostrar la depuración",
+        StringKey::ActionCycleTheme => "Cambiar de tema",
+        StringKey::ActionToggleSubAnagrams => "Buscar subanagramas",
+        StringKey::ActionToggleMute => "Silenciar o activar el sonido",
+        StringKey::ActionToggleBigMode => "Modo de letra grande",
+        StringKey::ActionTextLarger => "Texto más grande",
+        StringKey::ActionTextSmaller => "Texto más pequeño",
+        StringKey::ActionTextReset => "Tamaño de texto normal",
+        StringKey::ActionOpenSettings => "Abrir los ajustes",
+        StringKey::ActionShowHelp => "Mostrar las teclas",
+        StringKey::ActionCommandPalette => "Buscar una acción por su nombre",
+        StringKey::ActionNewTab => "Abrir una pestaña nueva",
+        StringKey::ActionCloseTab => "Cerrar la pestaña",
+        StringKey::ActionNextTab => "Ir a la pestaña siguiente",
+        StringKey::ActionCycleStats => "Mostrar estadísticas del diccionario y luego un gráfico de letras",
+        StringKey::ActionTogglePin => "Fijar o soltar la palabra seleccionada",
+        StringKey::ActionClearPins => "Soltar todas las palabras",
+        StringKey::ActionShowLength4 => "Mostrar u ocultar las palabras de 4 letras",
+        StringKey::ActionShowLength5 => "Mostrar u ocultar las palabras de 5 letras",
+        StringKey::ActionShowLength6 => "Mostrar u ocultar las palabras de 6 letras",
+        StringKey::ActionShowLength7 => "Mostrar u ocultar las palabras de 7 letras",
+        StringKey::ActionShowLength8 => "Mostrar u ocultar las palabras de 8 letras",
+        StringKey::ActionShowLength9 => "Mostrar u ocultar las palabras de 9 letras",
+        StringKey::ActionShowAllLengths => "Mostrar palabras de todas las longitudes",
+        StringKey::ActionShowHint => "Ocultar los resultados y dar una pista sobre la respuesta",
+        StringKey::ActionTogglePhonetic => "Deletrear la respuesta con el alfabeto radiofónico",
+        StringKey::ActionCopyAnswer => "Copiar la respuesta",
+        StringKey::ActionExplainRanking => "Mostrar cómo se ordenan los resultados",
+        StringKey::ActionConfirmAnswer => "Marcar la respuesta como vista en el periódico",
+        StringKey::ActionRevealHidden => "Mostrar las palabras vulgares ocultas",
+        StringKey::ActionSpellFinal => "Deletrear la respuesta final con las letras escritas",
+        StringKey::ActionToggleMini => "Cambiar a la miniventana y volver",
+        StringKey::ActionComplete => "Escribir el resto de la palabra sugerida",
+        StringKey::ActionInspectWord => "Examinar la palabra seleccionada",
+        StringKey::ActionPrintResults => "Abrir una página de resultados para imprimir",
+        StringKey::ActionToggleRevealLock => "Guardar las respuestas bajo llave (mantener para desbloquear)",
+        StringKey::SettingsSaved => "Ajustes guardados",
+        StringKey::SettingsNotSaved => "No se pudieron guardar los ajustes",
+        StringKey::DictionaryLoaded => "Lista de palabras cargada",
+        StringKey::OnboardingDemoTitle => "Escriba las letras desordenadas",
+        StringKey::OnboardingDemoText => "Mayúsculas o minúsculas, da igual. La respuesta aparece cuando solo encaja una palabra.",
+        StringKey::OnboardingKeysTitle => "Teclas útiles",
+        StringKey::OnboardingKeysText => "Pulse {} en cualquier momento para verlas todas.",
+        StringKey::OnboardingPickTitle => "Elija una lista de palabras y un tamaño de texto",
+        StringKey::OnboardingHint => "Intro: siguiente   Izquierda: atrás   Esc: omitir",
+        StringKey::OnboardingPickHint => "Arriba/Abajo: elegir   Izquierda/Derecha: cambiar   Intro: empezar",
+        StringKey::TutorialType => "Escriba las letras desordenadas {}; las teclas que pulsar están marcadas",
+        StringKey::TutorialCommit => "Esa es la respuesta. Pulse Intro para escribirla en el pasatiempo",
+        StringKey::TutorialNext => "Ahora resuelva {} del mismo modo y pulse Intro",
+        StringKey::TutorialCircled => "Pulse {} para vaciar la entrada y escriba las letras con círculo",
+        StringKey::TutorialFinal => "Pulse {} y forme con ellas la respuesta, en inglés: ¿qué hacer con un truco nuevo o un estilo nuevo?",
+        StringKey::TutorialDone => "¡Resuelto! Pulse Intro para terminar",
+        StringKey::TutorialHint => "Esc: omitir el tutorial",
+        StringKey::TabLimit => "Solo se pueden abrir {} pestañas",
+        StringKey::PinLimit => "Solo se pueden fijar {} palabras",
+        StringKey::LastSeen => "visto por última vez el {}",
+        StringKey::PostProcessFailed => "Falló el posprocesado: {}",
+        StringKey::NoScramble => "No hay ninguna palabra revuelta en el texto pegado",
+        StringKey::PatternApplied => "Solo respuestas como {}",
+        StringKey::QueryError => "Consulta: {}",
+        StringKey::SlowQuery => "Esta búsqueda puede tardar; pulsa Intro para hacerla",
+        StringKey::AlreadySpells => "Estas letras ya forman {}: no hay otro anagrama",
+        StringKey::AsTyped => "(tal cual)",
+        StringKey::EscalatedTypo => "Sin coincidencia exacta: palabras parecidas",
+        StringKey::EscalatedDropOne => "Sin coincidencia exacta: palabras con una letra menos",
+        StringKey::WordsHidden => "({} ocultas)",
+        StringKey::PoolTitle => "Respuesta final",
+        StringKey::PoolHint => "Quedan {} letras. Retroceso devuelve una; Esc cierra.",
+        StringKey::SpectateConnecting => "Conectando con {}...",
+        StringKey::SpectateWatching => "Viendo {} (solo lectura)",
+        StringKey::SpectateLost => "Se perdió {} ({}); reintentando...",
+        StringKey::SpectateSolved => "Resueltas: {}",
+        StringKey::CompletionsCount => "{} palabras posibles",
+        StringKey::CompletionsTab => "Tab: {}",
+        StringKey::InspectKey => "Letras ordenadas",
+        StringKey::InspectAnagrams => "Mismas letras",
+        StringKey::InspectSource => "Lista de palabras",
+        StringKey::InspectFrequency => "Frecuencia",
+        StringKey::InspectScrabble => "Puntos de Scrabble",
+        StringKey::InspectConfirmed => "Confirmada",
+        StringKey::InspectHidden => "Oculta",
+        StringKey::InspectPinned => "Fijada",
+        StringKey::InspectNone => "ninguna",
+        StringKey::InspectYes => "sí",
+        StringKey::InspectNo => "no",
+        StringKey::InspectHint => "[X] ocultar, [C] confirmar, [B] fijar, [Esc] cerrar",
+        StringKey::HiddenWord => "{} queda oculta desde ahora",
+        StringKey::AlreadyHidden => "{} ya está oculta",
+        StringKey::InputLeftHand => "Mano izquierda",
+        StringKey::InputMirrorNext => "Mano izquierda: siguiente letra en espejo",
+        StringKey::InputRemapped => "Teclas reasignadas",
+        StringKey::AssistCountdown => "Respuesta en {} s ([Intro] ya)",
+        StringKey::AttractWatermark => "DEMO",
+        StringKey::NewMatch => "(nuevo)",
+        StringKey::TitleSolved => "{} resueltos",
+        StringKey::UpdateAvailable => "Ya salió la versión {}",
+        StringKey::LettersAll => "Ninguna",
+        StringKey::LettersLeftOut => "Sin {}",
+        StringKey::PrintFinalAnswer => "Respuesta final: {}",
+        StringKey::PrintOpened => "{} abierto para imprimir",
+        StringKey::PrintSaved => "{} guardado para imprimir",
+        StringKey::PrintFailed => "No se puede escribir la página para imprimir: {}",
+        StringKey::AnswersLocked => "Respuestas bloqueadas; mantén {} para verlas",
+        StringKey::AnswersUnlocked => "Respuestas desbloqueadas",
+        StringKey::AnswersStillLocked => "Las respuestas están bloqueadas",
+        StringKey::SettingAutoEscalate => "Palabras parecidas si nada encaja",
+        StringKey::SettingMaskWords => "Ocultar palabras vulgares",
+        StringKey::SettingAssist => "Retener la respuesta",
+        StringKey::SettingAssistSeconds => "Segundos antes de mostrarla",
+        StringKey::SettingUsageMetrics => "Contar funciones usadas, en local",
+    }
+}
@@ -0,0 +1,232 @@
+//! Effective text sizes derived from the user's text scale factor and the
+//! display's DPI scale.
+//!
+//! The DPI scale comes from the platform each frame (see `events`), unless the
+//! config fixes it, and follows the window between monitors.
+
+use crate::{ENTRY_TEXT_SIZE, RESULT_TEXT_SIZE};
+use serde::{Deserialize, Serialize};
+
+pub const MIN_TEXT_SCALE: f32 = 0.5;
+pub const MAX_TEXT_SCALE: f32 = 3.0;
+pub const TEXT_SCALE_STEP: f32 = 0.1;
+/// Smallest and largest DPI scale used; platforms have been known to report 0.
+pub const MIN_DPI_SCALE: f32 = 0.5;
+pub const MAX_DPI_SCALE: f32 = 4.0;
+
+/// Where the DPI scale comes from: `"auto"` or a number in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "DpiSetting", into = "DpiSetting")]
+pub enum DpiScale {
+    /// Whatever the platform reports for the window's monitor.
+    #[default]
+    Auto,
+    /// This scale, whatever the monitor.
+    Fixed(f32),
+}
+
+impl DpiScale {
+    /// Returns the scale to use when the platform reports `detected`, clamped to
+    /// `MIN_DPI_SCALE..=MAX_DPI_SCALE`.
+    pub fn resolve(self, detected: f32) -> f32 {
+        match self {
+            DpiScale::Auto => clamp_dpi(detected),
+            DpiScale::Fixed(scale) => clamp_dpi(scale),
+        }
+    }
+}
+
+/// `DpiScale` as written in the config.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DpiSetting {
+    Name(String),
+    Number(f32),
+}
+
+impl TryFrom<DpiSetting> for DpiScale {
+    type Error = String;
+
+    fn try_from(setting: DpiSetting) -> Result<Self, String> {
+        match setting {
+            DpiSetting::Name(name) if name.eq_ignore_ascii_case("auto") => Ok(DpiScale::Auto),
+            DpiSetting::Name(name) => {
+                Err(format!("'{name}' is not a DPI scale (auto, or a number)"))
+            }
+            DpiSetting::Number(scale) => Ok(DpiScale::Fixed(scale)),
+        }
+    }
+}
+
+impl From<DpiScale> for DpiSetting {
+    fn from(scale: DpiScale) -> Self {
+        match scale {
+            DpiScale::Auto => DpiSetting::Name("auto".into()),
+            DpiScale::Fixed(scale) => DpiSetting::Number(scale),
+        }
+    }
+}
+
+/// Text sizes in effect after applying the user's scale factor and the DPI scale.
+///
+/// Drawing and layout code reads sizes from here rather than from the base
+/// `ENTRY_TEXT_SIZE` and `RESULT_TEXT_SIZE` constants.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AppStyle {
+    /// The user's text scale factor.
+    pub scale: f32,
+    /// The display's DPI scale, which also applies to layout margins.
+    pub dpi: f32,
+    pub entry_size: u16,
+    pub result_size: u16,
+}
+
+impl AppStyle {
+    /// Creates a new `AppStyle`, clamping `scale` and `dpi` to their allowed
+    /// ranges.
+    pub fn new(scale: f32, dpi: f32) -> Self {
+        let (scale, dpi) = (clamp_scale(scale), clamp_dpi(dpi));
+        Self {
+            scale,
+            dpi,
+            entry_size: scale_size(ENTRY_TEXT_SIZE, scale * dpi),
+            result_size: scale_size(RESULT_TEXT_SIZE, scale * dpi),
+        }
+    }
+    /// Returns the style one step larger (Ctrl+Plus).
+    pub fn scaled_up(&self) -> Self {
+        Self::new(self.scale + TEXT_SCALE_STEP, self.dpi)
+    }
+    /// Returns the style one step smaller (Ctrl+Minus).
+    pub fn scaled_down(&self) -> Self {
+        Self::new(self.scale - TEXT_SCALE_STEP, self.dpi)
+    }
+    /// Returns the style at the default scale (Ctrl+0), at the same DPI scale.
+    pub fn reset(&self) -> Self {
+        Self::new(1.0, self.dpi)
+    }
+    /// Returns the style at DPI scale `dpi`, keeping the text scale.
+    pub fn with_dpi(&self, dpi: f32) -> Self {
+        Self::new(self.scale, dpi)
+    }
+}
+
+impl Default for AppStyle {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+/// Clamps `scale` to `MIN_TEXT_SCALE..=MAX_TEXT_SCALE`, snapped to the nearest step.
+///
+/// Snapping keeps repeated steps from accumulating floating point drift. Non-finite
+/// values (e.g. from a hand-edited config) fall back to the default scale.
+pub fn clamp_scale(scale: f32) -> f32 {
+    if !scale.is_finite() {
+        return 1.0;
+    }
+    let snapped = (scale / TEXT_SCALE_STEP).round() * TEXT_SCALE_STEP;
+    snapped.clamp(MIN_TEXT_SCALE, MAX_TEXT_SCALE)
+}
+
+/// Clamps `dpi` to `MIN_DPI_SCALE..=MAX_DPI_SCALE`.
+///
+/// Zero, negative, and non-finite values (a platform that can't tell) fall back to
+/// 1.0 rather than the nearest limit.
+pub fn clamp_dpi(dpi: f32) -> f32 {
+    if !dpi.is_finite() || dpi <= 0.0 {
+        return 1.0;
+    }
+    dpi.clamp(MIN_DPI_SCALE, MAX_DPI_SCALE)
+}
+
+/// Scales a base font size, never returning less than 1.
+fn scale_size(base: u16, scale: f32) -> u16 {
+    ((base as f32 * scale).round() as u16).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_snaps_to_steps_within_its_range() {
+        assert_eq!(clamp_scale(1.04), 1.0);
+        assert_eq!(clamp_scale(0.1), MIN_TEXT_SCALE);
+        assert_eq!(clamp_scale(9.0), MAX_TEXT_SCALE);
+        assert_eq!(clamp_scale(f32::NAN), 1.0);
+        assert_eq!(clamp_scale(f32::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn steps_stop_at_the_limits_without_drift() {
+        let mut style = AppStyle::default();
+        for _ in 0..100 {
+            style = style.scaled_up();
+        }
+        assert_eq!(style.scale, MAX_TEXT_SCALE);
+        assert_eq!(
+            style.entry_size,
+            scale_size(ENTRY_TEXT_SIZE, MAX_TEXT_SCALE)
+        );
+        for _ in 0..100 {
+            style = style.scaled_down();
+        }
+        assert_eq!(style.scale, MIN_TEXT_SCALE);
+        for _ in 0..5 {
+            style = style.scaled_up();
+        }
+        assert_eq!(style.scale, clamp_scale(1.0));
+        assert_eq!(style.entry_size, ENTRY_TEXT_SIZE);
+        assert_eq!(style.scaled_up().reset(), AppStyle::default());
+    }
+
+    #[test]
+    fn dpi_falls_back_to_one_when_unknown() {
+        assert_eq!(clamp_dpi(0.0), 1.0);
+        assert_eq!(clamp_dpi(-2.0), 1.0);
+        assert_eq!(clamp_dpi(f32::NAN), 1.0);
+        assert_eq!(clamp_dpi(0.1), MIN_DPI_SCALE);
+        assert_eq!(clamp_dpi(8.0), MAX_DPI_SCALE);
+        assert_eq!(DpiScale::Fixed(2.0).resolve(1.0), 2.0);
+        assert_eq!(DpiScale::Fixed(0.0).resolve(2.0), 1.0);
+        assert_eq!(DpiScale::Auto.resolve(1.5), 1.5);
+        assert_eq!(DpiScale::Auto.resolve(100.0), MAX_DPI_SCALE);
+    }
+
+    #[test]
+    fn dpi_multiplies_the_text_scale() {
+        let style = AppStyle::new(1.5, 2.0);
+        assert_eq!(style.entry_size, scale_size(ENTRY_TEXT_SIZE, 3.0));
+        assert_eq!(style.result_size, scale_size(RESULT_TEXT_SIZE, 3.0));
+        // Stepping the text scale keeps the DPI scale, and the other way round
+        let larger = style.scaled_up();
+        assert_eq!((larger.scale, larger.dpi), (1.6, 2.0));
+        assert_eq!(style.reset().entry_size, ENTRY_TEXT_SIZE * 2);
+        let moved = style.with_dpi(1.0);
+        assert_eq!((moved.scale, moved.entry_size), (1.5, 108));
+        // Both limits apply at once
+        let huge = AppStyle::new(9.0, 9.0);
+        assert_eq!(
+            huge.entry_size,
+            scale_size(ENTRY_TEXT_SIZE, MAX_TEXT_SCALE * MAX_DPI_SCALE)
+        );
+    }
+
+    #[test]
+    fn the_config_names_auto_or_a_number() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Config {
+            dpi_scale: DpiScale,
+        }
+        let read = |text: &str| toml::from_str::<Config>(text).map(|c| c.dpi_scale);
+        assert_eq!(read("dpi_scale = \"auto\"").unwrap(), DpiScale::Auto);
+        assert_eq!(read("dpi_scale = \"AUTO\"").unwrap(), DpiScale::Auto);
+        assert_eq!(read("dpi_scale = 2.0").unwrap(), DpiScale::Fixed(2.0));
+        assert!(read("dpi_scale = \"big\"").is_err());
+        let fixed = Config {
+            dpi_scale: DpiScale::Fixed(1.5),
+        };
+        assert_eq!(toml::to_string(&fixed).unwrap().trim(), "dpi_scale = 1.5");
+    }
+}
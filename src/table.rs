@@ -0,0 +1,104 @@
+//! Laying out rows of text in columns.
+//!
+//! Each column is as wide as its widest cell. Text columns line up on the left
+//! and numeric ones on the right, so the digits of their numbers line up.
+
+/// Which side of its column a cell lines up on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    /// For numbers.
+    Right,
+}
+
+/// Where each cell of a table goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableLayout {
+    /// Each column's width.
+    pub widths: Vec<f32>,
+    /// For each row, each cell's x offset from the table's left edge.
+    pub cells: Vec<Vec<f32>>,
+    /// The whole table's width.
+    pub width: f32,
+}
+
+/// Lays out `rows` in columns aligned as `aligns` says, `gap` apart, measuring
+/// text widths with `measure`.
+///
+/// Rows may be short; their missing cells are left empty. Columns past the end
+/// of `aligns` line up on the left.
+pub fn layout_table(
+    rows: &[Vec<String>],
+    aligns: &[Align],
+    gap: f32,
+    measure: impl Fn(&str) -> f32,
+) -> TableLayout {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut widths = vec![0.0f32; columns];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = width.max(measure(cell));
+        }
+    }
+    let starts: Vec<f32> = widths
+        .iter()
+        .scan(0.0, |x, width| {
+            let start = *x;
+            *x += width + gap;
+            Some(start)
+        })
+        .collect();
+    let cells = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| match aligns.get(i) {
+                    Some(Align::Right) => starts[i] + widths[i] - measure(cell),
+                    _ => starts[i],
+                })
+                .collect()
+        })
+        .collect();
+    let width = widths.iter().sum::<f32>() + gap * columns.saturating_sub(1) as f32;
+    TableLayout {
+        widths,
+        cells,
+        width,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|&cell| cell.to_owned()).collect()
+    }
+
+    #[test]
+    fn columns_fit_their_widest_cell_and_numbers_line_up_right() {
+        let rows = [
+            row(&["WORD", "TOTAL"]),
+            row(&["AT", "1.5"]),
+            row(&["OPERA", "12.0"]),
+        ];
+        let measure = |text: &str| text.len() as f32 * 10.0;
+        let table = layout_table(&rows, &[Align::Left, Align::Right], 5.0, measure);
+        assert_eq!(table.widths, [50.0, 50.0]);
+        assert_eq!(table.width, 105.0);
+        assert_eq!(table.cells[0], [0.0, 55.0]);
+        assert_eq!(table.cells[1], [0.0, 75.0]);
+        assert_eq!(table.cells[2], [0.0, 65.0]);
+    }
+
+    #[test]
+    fn short_rows_and_unaligned_columns_are_laid_out_left() {
+        let rows = [row(&["A", "BB", "CCC"]), row(&["DDDD"])];
+        let measure = |text: &str| text.len() as f32;
+        let table = layout_table(&rows, &[Align::Right], 1.0, measure);
+        assert_eq!(table.widths, [4.0, 2.0, 3.0]);
+        assert_eq!(table.cells, [vec![3.0, 5.0, 8.0], vec![0.0]]);
+        assert_eq!(layout_table(&[], &[], 1.0, measure).width, 0.0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Looking up scrambles at a prompt in the terminal, without a window.
+//!
+//! `jumble_helper --terminal` runs it on purpose. It's also where the app goes
+//! when its window can't be opened at all: miniquad panics when it can't get a
+//! display or a GL context (an old machine's driver, a session with no
+//! display), and `main` catches that panic while the window hasn't started
+//! yet (see `fallback_reason`), explains, and carries on here instead of
+//! showing nothing. A panic once the window has started is an ordinary crash.
+
+use crate::alphabet::Alphabet;
+use crate::cli::CliArgs;
+use crate::dictionary::{load_words, prepare_words, DictionaryOptions};
+use crate::rank::{rank, RankWeights};
+use crate::strings::Language;
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use std::any::Any;
+use std::io::{self, BufRead, Write};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Most matches printed for a scramble.
+const MAX_SHOWN: usize = 10;
+
+/// Set once the window is up and the app's first frame has begun.
+pub static WINDOW_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Notes that the window is up, so a panic from now on is a crash rather than
+/// a window that couldn't be opened.
+pub fn mark_window_started() {
+    WINDOW_STARTED.store(true, Ordering::Relaxed);
+}
+
+/// Quiets panics while the window is being opened: one then only goes to the
+/// log, as `main` explains it itself. Later panics are reported as usual.
+pub fn install_startup_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match WINDOW_STARTED.load(Ordering::Relaxed) {
+            true => previous(info),
+            false => log::error!("[terminal] opening the window failed: {info}"),
+        }
+    }));
+}
+
+/// Returns the message a panic was raised with, if it was text.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => (*message).to_owned(),
+        None => payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_else(|| "unknown error".to_owned()),
+    }
+}
+
+/// Decides, from how running the window ended (`Err` holding the panic's
+/// message) and whether it had `started`, whether to carry on in the terminal,
+/// returning why if so. Only a window that failed before starting does: after
+/// that, the panic was the app's own.
+pub fn fallback_reason(ended: Result<(), String>, started: bool) -> Option<String> {
+    match ended {
+        Err(message) if !started => Some(message),
+        _ => None,
+    }
+}
+
+/// Runs `open`, which opens the window and runs the app in it, and returns why
+/// to carry on in the terminal if it panicked before `started` was set (see
+/// `fallback_reason`). A panic after that goes on unwinding.
+pub fn open_window(started: &AtomicBool, open: impl FnOnce()) -> Option<String> {
+    let ended = std::panic::catch_unwind(AssertUnwindSafe(open));
+    let outcome = ended
+        .as_ref()
+        .map(|_| ())
+        .map_err(|e| panic_message(e.as_ref()));
+    let reason = fallback_reason(outcome, started.load(Ordering::Relaxed));
+    if let (None, Err(payload)) = (&reason, ended) {
+        std::panic::resume_unwind(payload);
+    }
+    reason
+}
+
+/// Returns what to tell the user when the window couldn't be opened for
+/// `reason`.
+pub fn fallback_message(reason: &str) -> String {
+    format!(
+        "Jumble Helper couldn't open its window ({reason}).\n\
+         This computer's graphics may be too old, or there's no display to show it on.\n\
+         Carrying on in the terminal instead; the log has the details."
+    )
+}
+
+/// Returns the options given on the command line that only matter to the
+/// window, and so do nothing in the terminal.
+pub fn window_only_options(cli: &CliArgs) -> Vec<&'static str> {
+    [
+        (cli.mini, "--mini"),
+        (cli.onboarding, "--onboarding"),
+        (cli.record.is_some(), "--record"),
+        (cli.visual, "--visual"),
+    ]
+    .into_iter()
+    .filter_map(|(given, name)| given.then_some(name))
+    .collect()
+}
+
+/// Looks up each scramble typed at the prompt, with the dictionary as
+/// `options` and `lang` say and its matches ranked by `weights`, until an empty
+/// line or the end of input. Returns the exit code.
+pub fn run_terminal(
+    cli: &CliArgs,
+    options: &DictionaryOptions,
+    lang: Language,
+    weights: &RankWeights,
+) -> i32 {
+    let ignored = window_only_options(cli);
+    if !ignored.is_empty() {
+        println!(
+            "(ignoring {}, which only the window uses)",
+            ignored.join(", ")
+        );
+    }
+    let words = load_words(options, lang);
+    let (header, words) = match prepare_words(&words) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("[run_terminal] can't read the dictionary: {e}");
+            return 2;
+        }
+    };
+    let alphabet = options.alphabet_for(&header);
+    let map = make_word_map_with_progress(&words, alphabet, options.max_words, usize::MAX, |_| ());
+    println!("Type a scramble and press Enter; an empty line quits.");
+    match answer_scrambles(&map, alphabet, weights, io::stdin().lock(), io::stdout()) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[run_terminal] can't read the input: {e}");
+            2
+        }
+    }
+}
+
+/// Prints the matches in `map` for each scramble read from `input`, folded for
+/// `alphabet` and ranked by `weights`, until an empty line or the end of input.
+fn answer_scrambles(
+    map: &WordMap,
+    alphabet: Alphabet,
+    weights: &RankWeights,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut key_buf = Vec::new();
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let scramble = line.trim();
+        if scramble.is_empty() {
+            return Ok(());
+        }
+        let scramble = alphabet.fold_word(&scramble.to_uppercase()).into_owned();
+        let matches = map
+            .find_match(&scramble, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf)
+            .unwrap_or_default();
+        if matches.is_empty() {
+            writeln!(output, "no matches for {scramble}")?;
+            continue;
+        }
+        let ranked = rank(matches, map, |_| false, |_| false, weights);
+        let shown: Vec<&str> = ranked
+            .iter()
+            .take(MAX_SHOWN)
+            .map(|r| matches[r.index].as_str())
+            .collect();
+        match matches.len().saturating_sub(MAX_SHOWN) {
+            0 => writeln!(output, "{}", shown.join(", "))?,
+            more => writeln!(output, "{} (and {more} more)", shown.join(", "))?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_window_that_fails_to_open_falls_back_and_a_later_panic_doesnt() {
+        let started = AtomicBool::new(false);
+        let reason = open_window(&started, || panic!("XOpenDisplay() failed!"));
+        assert_eq!(reason.as_deref(), Some("XOpenDisplay() failed!"));
+        assert_eq!(open_window(&started, || ()), None);
+
+        let crashed = std::panic::catch_unwind(|| {
+            open_window(&started, || {
+                started.store(true, Ordering::Relaxed);
+                panic!("{} out of bounds", 7)
+            })
+        });
+        let payload = crashed.expect_err("the app's own panic carries on");
+        assert_eq!(panic_message(payload.as_ref()), "7 out of bounds");
+    }
+
+    #[test]
+    fn only_a_failure_before_starting_falls_back() {
+        let failed = || Err("no GL context".to_owned());
+        assert_eq!(
+            fallback_reason(failed(), false).as_deref(),
+            Some("no GL context")
+        );
+        assert_eq!(fallback_reason(failed(), true), None);
+        assert_eq!(fallback_reason(Ok(()), false), None);
+        assert!(fallback_message("no GL context").contains("(no GL context)"));
+    }
+
+    #[test]
+    fn options_for_the_window_are_named_as_ignored() {
+        let cli = CliArgs {
+            mini: true,
+            visual: true,
+            ..CliArgs::default()
+        };
+        assert_eq!(window_only_options(&cli), ["--mini", "--visual"]);
+        assert!(window_only_options(&CliArgs::default()).is_empty());
+    }
+
+    #[test]
+    fn scrambles_typed_are_answered_until_an_empty_line() {
+        let words = "GREAT\nGRATE\nUPON\nPOUND\n";
+        let map =
+            make_word_map_with_progress(words, Alphabet::English, usize::MAX, usize::MAX, |_| ());
+        let input = "tager\nxyzzy\n\nNUPO\n";
+        let mut output = Vec::new();
+        answer_scrambles(
+            &map,
+            Alphabet::English,
+            &RankWeights::default(),
+            input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, "> GREAT, GRATE\n> no matches for XYZZY\n> ");
+    }
+}
@@ -0,0 +1,228 @@
+//! Color themes, including user-defined themes from the config file.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Colors and outline thickness used when drawing the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub entry_text: Color,
+    pub box_outline: Color,
+    pub result: Color,
+    pub highlight: Color,
+    pub error: Color,
+    pub outline_thickness: f32,
+}
+
+impl Theme {
+    /// The original look: white text and blue outlines on black.
+    pub fn default_theme() -> Self {
+        Self {
+            name: "default".into(),
+            background: BLACK,
+            entry_text: WHITE,
+            box_outline: BLUE,
+            result: WHITE,
+            highlight: GREEN,
+            error: RED,
+            outline_thickness: 1.0,
+        }
+    }
+    /// Black on white with thick outlines, for low vision.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".into(),
+            background: WHITE,
+            entry_text: BLACK,
+            box_outline: BLACK,
+            result: BLACK,
+            highlight: Color::from_rgba(0, 0, 204, 255),
+            error: Color::from_rgba(204, 0, 0, 255),
+            outline_thickness: 4.0,
+        }
+    }
+    /// Soft light text on a dark grey background.
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".into(),
+            background: Color::from_rgba(30, 30, 36, 255),
+            entry_text: Color::from_rgba(230, 230, 230, 255),
+            box_outline: Color::from_rgba(110, 140, 200, 255),
+            result: Color::from_rgba(240, 220, 150, 255),
+            highlight: Color::from_rgba(120, 200, 140, 255),
+            error: Color::from_rgba(230, 110, 110, 255),
+            outline_thickness: 2.0,
+        }
+    }
+    /// Okabe–Ito colors, which stay distinct with any common color vision deficiency.
+    pub fn colorblind() -> Self {
+        Self {
+            name: "colorblind".into(),
+            background: BLACK,
+            entry_text: WHITE,
+            box_outline: Color::from_rgba(86, 180, 233, 255),
+            result: WHITE,
+            highlight: Color::from_rgba(230, 159, 0, 255),
+            error: Color::from_rgba(213, 94, 0, 255),
+            outline_thickness: 2.0,
+        }
+    }
+    /// Builds a custom theme from a config spec, taking unset colors from `base`.
+    pub fn from_spec(name: &str, spec: &ThemeSpec, base: &Theme) -> Result<Self, String> {
+        let pick = |value: &Option<String>, fallback: Color| match value {
+            Some(hex) => parse_hex_color(hex),
+            None => Ok(fallback),
+        };
+        let outline_thickness = spec.outline_thickness.unwrap_or(base.outline_thickness);
+        if !(outline_thickness.is_finite() && outline_thickness > 0.0) {
+            return Err(format!("invalid outline thickness {outline_thickness}"));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            background: pick(&spec.background, base.background)?,
+            entry_text: pick(&spec.entry_text, base.entry_text)?,
+            box_outline: pick(&spec.box_outline, base.box_outline)?,
+            result: pick(&spec.result, base.result)?,
+            highlight: pick(&spec.highlight, base.highlight)?,
+            error: pick(&spec.error, base.error)?,
+            outline_thickness,
+        })
+    }
+}
+
+/// A custom theme as written in the config file, with colors as hex strings.
+///
+/// ```toml
+/// [themes.sunset]
+/// background = "#2B1B17"
+/// entry_text = "#FFD8A8"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeSpec {
+    pub background: Option<String>,
+    pub entry_text: Option<String>,
+    pub box_outline: Option<String>,
+    pub result: Option<String>,
+    pub highlight: Option<String>,
+    pub error: Option<String>,
+    pub outline_thickness: Option<f32>,
+}
+
+/// Parses a `#RRGGBB` or `#RRGGBBAA` hex string (the `#` is optional).
+pub fn parse_hex_color(hex: &str) -> Result<Color, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return Err(format!("'{hex}' is not a #RRGGBB or #RRGGBBAA color"));
+    }
+    // `from_str_radix` would also take a sign, so "+F" is checked for here
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("'{hex}' contains non-hex digits"));
+    }
+
+    let channel = |i: usize| {
+        u8::from_str_radix(&digits[i..i + 2], 16)
+            .map_err(|_| format!("'{hex}' contains non-hex digits"))
+    };
+    let a = if digits.len() == 8 { channel(6)? } else { 255 };
+
+    Ok(Color::from_rgba(channel(0)?, channel(2)?, channel(4)?, a))
+}
+
+/// All available themes and the one currently in use.
+pub struct ThemeSet {
+    themes: Vec<Theme>,
+    current: usize,
+}
+
+impl ThemeSet {
+    /// Creates the built-in themes plus any valid custom themes, selecting `selected`.
+    ///
+    /// Invalid custom themes are skipped with a warning. An unknown `selected` name
+    /// falls back to the default theme.
+    pub fn new(custom: &BTreeMap<String, ThemeSpec>, selected: &str) -> Self {
+        let mut themes = vec![
+            Theme::default_theme(),
+            Theme::high_contrast(),
+            Theme::dark(),
+            Theme::colorblind(),
+        ];
+        let base = Theme::default_theme();
+
+        for (name, spec) in custom {
+            if themes.iter().any(|t| &t.name == name) {
+                log::warn!("[ThemeSet::new] custom theme '{name}' shadows a built-in; skipped");
+                continue;
+            }
+            match Theme::from_spec(name, spec, &base) {
+                Ok(theme) => themes.push(theme),
+                Err(e) => log::warn!("[ThemeSet::new] skipping theme '{name}': {e}"),
+            }
+        }
+
+        let mut set = Self { themes, current: 0 };
+        match set.find(selected) {
+            Some(i) => set.current = i,
+            None => log::warn!("[ThemeSet::new] unknown theme '{selected}'; using default"),
+        }
+        set
+    }
+    /// Returns the index of the theme called `name`, if any.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.themes.iter().position(|t| t.name == name)
+    }
+    /// Returns the theme currently in use.
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+    /// Returns the names of all themes, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.themes.iter().map(|t| t.name.clone()).collect()
+    }
+    /// Returns the index of the theme currently in use.
+    pub fn index(&self) -> usize {
+        self.current
+    }
+    /// Switches to theme `index`, if there is one, and returns the theme in use.
+    pub fn select(&mut self, index: usize) -> &Theme {
+        if index < self.themes.len() {
+            self.current = index;
+        }
+        self.current()
+    }
+    /// Switches to the next theme (F3), wrapping around, and returns it.
+    pub fn cycle(&mut self) -> &Theme {
+        self.current = (self.current + 1) % self.themes.len();
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_six_and_eight_digits() {
+        assert_eq!(
+            parse_hex_color("#FF8000"),
+            Ok(Color::from_rgba(255, 128, 0, 255))
+        );
+        assert_eq!(
+            parse_hex_color("ff800040"),
+            Ok(Color::from_rgba(255, 128, 0, 64))
+        );
+    }
+
+    #[test]
+    fn refuses_signs_and_other_characters() {
+        assert!(parse_hex_color("#+F+F+F").is_err());
+        assert!(parse_hex_color("#-1-1-1").is_err());
+        assert!(parse_hex_color("#GG0000").is_err());
+        assert!(parse_hex_color("#FFF").is_err());
+        assert!(parse_hex_color("#ÉÉÉ").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Rearranging the entry's letters by hand while thinking.
+//!
+//! A letter in the entry can be picked up with the mouse (or a finger) and
+//! dropped between two others, which shift to make room. Lookups don't care
+//! about order, so only the order the letters are shown in changes: a
+//! `TileOrder` keeps it, as indices into the entry in the order the letters
+//! were typed.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// A letter being dragged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileDrag {
+    /// The slot it was picked up from.
+    pub from: usize,
+    /// The slot it would be dropped into.
+    pub to: usize,
+    /// Where the pointer is.
+    pub x: f32,
+}
+
+/// The order the entry's letters are shown in, and any letter being dragged.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TileOrder {
+    /// For each slot, left to right, the index in the entry of its letter.
+    order: Vec<usize>,
+    /// The entry `order` was last kept in step with.
+    letters: String,
+    pub drag: Option<TileDrag>,
+    /// The centers of the slots when the dragged letter was picked up.
+    centers: Vec<f32>,
+}
+
+impl TileOrder {
+    /// Keeps the order in step with the entry, now `text`.
+    ///
+    /// Letters typed since are shown at the end, and deleting the newest letters
+    /// takes them out wherever they were moved to. Any other change (a paste, or
+    /// a query command) starts over in typing order. A change drops any drag.
+    pub fn sync(&mut self, text: &str) {
+        if text == self.letters {
+            return;
+        }
+        let (old, new) = (self.letters.chars().count(), text.chars().count());
+        if text.starts_with(&self.letters) {
+            self.order.extend(old..new);
+        } else if self.letters.starts_with(text) {
+            self.order.retain(|&i| i < new);
+        } else {
+            self.order = (0..new).collect();
+        }
+        self.letters = text.to_owned();
+        self.drag = None;
+    }
+    /// Returns the order as indices into the entry, taking a drag into account:
+    /// the dragged letter sits in the slot it would be dropped into.
+    pub fn order(&self) -> Vec<usize> {
+        let mut order = self.order.clone();
+        if let Some(drag) = self.drag {
+            let i = order.remove(drag.from);
+            order.insert(drag.to, i);
+        }
+        order
+    }
+    /// Returns `text`, the entry, in the order its letters are shown in, or as
+    /// it is if the order isn't in step with it.
+    pub fn display(&self, text: &str) -> String {
+        if text != self.letters {
+            return text.to_owned();
+        }
+        let letters: Vec<char> = text.chars().collect();
+        self.order().into_iter().map(|i| letters[i]).collect()
+    }
+    /// Picks up the letter in `slot` with the pointer at `x`, the slots being
+    /// between `edges` (see `letter_edges`), returning whether there was one.
+    pub fn pick_up(&mut self, slot: usize, x: f32, edges: &[f32]) -> bool {
+        if slot >= self.order.len() || edges.len() != self.order.len() + 1 {
+            return false;
+        }
+        self.centers = edges.windows(2).map(|e| (e[0] + e[1]) / 2.0).collect();
+        self.drag = Some(TileDrag {
+            from: slot,
+            to: slot,
+            x,
+        });
+        true
+    }
+    /// Moves the dragged letter with the pointer to `x`.
+    pub fn drag_to(&mut self, x: f32) {
+        if let Some(drag) = &mut self.drag {
+            drag.x = x;
+            drag.to = drop_slot(x, drag.from, &self.centers);
+        }
+    }
+    /// Drops the dragged letter into the slot it's over, returning whether the
+    /// order changed.
+    pub fn drop_tile(&mut self) -> bool {
+        let Some(drag) = self.drag else {
+            return false;
+        };
+        self.order = self.order();
+        self.drag = None;
+        drag.from != drag.to
+    }
+}
+
+/// Returns the slot a letter picked up from slot `from` goes into when dropped
+/// at `x`: after every other letter whose slot's center, among `centers`, is left
+/// of `x`.
+pub fn drop_slot(x: f32, from: usize, centers: &[f32]) -> usize {
+    centers
+        .iter()
+        .enumerate()
+        .filter(|&(i, &center)| i != from && center < x)
+        .count()
+}
+
+/// Returns the left edge of each letter of `text` drawn from `x`, and the right
+/// edge of the last, measuring text widths with `measure`.
+pub fn letter_edges(text: &str, x: f32, measure: impl Fn(&str) -> f32) -> Vec<f32> {
+    let mut edges = vec![x];
+    edges.extend(
+        text.char_indices()
+            .map(|(i, c)| x + measure(&text[..i + c.len_utf8()])),
+    );
+    edges
+}
+
+/// Returns the slot of the letter at `x` among letters with `edges`, if any.
+pub fn slot_at(x: f32, edges: &[f32]) -> Option<usize> {
+    edges
+        .windows(2)
+        .position(|pair| pair[0] <= x && x < pair[1])
+}
+
+/// Draws `text`, already in display order, with its baseline at `pos`, the
+/// dragged letter (in slot `drag.to`) lifted and drawn under the pointer.
+pub fn draw_tiles(
+    gfx: &dyn Renderer,
+    text: &str,
+    drag: TileDrag,
+    pos: Vec2,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let edges = letter_edges(text, pos.x, |t| gfx.measure(t, font, size).width);
+    for (slot, c) in text.chars().enumerate() {
+        let letter = c.to_string();
+        if slot == drag.to {
+            let w = edges[slot + 1] - edges[slot];
+            let lifted = vec2(drag.x - w / 2.0, pos.y - size as f32 * 0.15);
+            gfx.text(&letter, lifted, font, size, theme.highlight);
+        } else {
+            gfx.text(
+                &letter,
+                vec2(edges[slot], pos.y),
+                font,
+                size,
+                theme.entry_text,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_typed_since_go_at_the_end() {
+        let mut tiles = TileOrder::default();
+        tiles.sync("AB");
+        tiles.order = vec![1, 0];
+        tiles.sync("ABC");
+        assert_eq!(tiles.display("ABC"), "BAC");
+        tiles.sync("AB");
+        assert_eq!(tiles.display("AB"), "BA");
+    }
+
+    #[test]
+    fn counts_letters_not_bytes() {
+        let mut tiles = TileOrder::default();
+        tiles.sync("A");
+        tiles.sync("AÑ");
+        assert_eq!(tiles.display("AÑ"), "AÑ");
+        tiles.sync("AÑO");
+        assert_eq!(tiles.order(), vec![0, 1, 2]);
+        tiles.sync("AÑ");
+        assert_eq!(tiles.display("AÑ"), "AÑ");
+    }
+
+    #[test]
+    fn other_changes_start_over() {
+        let mut tiles = TileOrder::default();
+        tiles.sync("ABC");
+        tiles.order = vec![2, 1, 0];
+        tiles.sync("XYZ");
+        assert_eq!(tiles.display("XYZ"), "XYZ");
+    }
+
+    #[test]
+    fn drop_slot_counts_centers_left_of_the_pointer() {
+        let centers = [10.0, 30.0, 50.0];
+        assert_eq!(drop_slot(40.0, 0, &centers), 1);
+        assert_eq!(drop_slot(0.0, 2, &centers), 0);
+        assert_eq!(drop_slot(60.0, 0, &centers), 2);
+    }
+
+    #[test]
+    fn a_dragged_letter_moves_where_it_is_dropped() {
+        let mut tiles = TileOrder::default();
+        tiles.sync("ABC");
+        let edges = letter_edges("ABC", 0.0, |t| t.len() as f32 * 20.0);
+        assert_eq!(edges, [0.0, 20.0, 40.0, 60.0]);
+        assert_eq!(slot_at(25.0, &edges), Some(1));
+        assert_eq!(slot_at(60.0, &edges), None);
+
+        assert!(!tiles.pick_up(3, 70.0, &edges));
+        assert!(tiles.pick_up(0, 10.0, &edges));
+        tiles.drag_to(55.0);
+        assert_eq!(tiles.display("ABC"), "BCA");
+        assert!(tiles.drop_tile());
+        assert_eq!(tiles.display("ABC"), "BCA");
+        // Deleting the newest letter takes it out from where it was moved to
+        tiles.sync("AB");
+        assert_eq!(tiles.display("AB"), "BA");
+
+        // Dropped where it was picked up, nothing changes
+        assert!(tiles.pick_up(1, 30.0, &edges[..3]));
+        assert!(!tiles.drop_tile());
+        assert!(!tiles.drop_tile());
+    }
+}
@@ -0,0 +1,113 @@
+//! Optional timing of each stage of a keystroke, for `--bench-latency`.
+//!
+//! Built with `--features stage-timing`, the hooks add how long each stage took
+//! to a running total for the thread, which `take_stage_times` hands back and
+//! clears. Otherwise `time` just runs the stage and `record` does nothing, so
+//! the hooks cost nothing.
+
+/// A stage of handling a keystroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// The screen handling the event.
+    Event,
+    /// Looking the entry up, as the debug overlay's query time.
+    Query,
+    /// Ranking the matches, for big mode, the mini window, or the table.
+    Ranking,
+    /// Laying out and drawing the frame, ranking included.
+    Layout,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::Event, Stage::Query, Stage::Ranking, Stage::Layout];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Event => "event",
+            Stage::Query => "query",
+            Stage::Ranking => "ranking",
+            Stage::Layout => "layout",
+        }
+    }
+}
+
+/// Seconds spent in each stage, in the order of `Stage::ALL`, or `None` for
+/// one that didn't run.
+pub type StageTimes = [Option<f64>; Stage::ALL.len()];
+
+#[cfg(feature = "stage-timing")]
+mod totals {
+    use super::StageTimes;
+    use std::cell::Cell;
+
+    thread_local! {
+        pub static TIMES: Cell<StageTimes> = const { Cell::new([None; 4]) };
+    }
+}
+
+/// Runs `f` as `stage`, timing it if timing is built in.
+#[inline(always)]
+pub fn time<T>(stage: Stage, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "stage-timing")]
+    {
+        let start = std::time::Instant::now();
+        let result = f();
+        record(stage, start.elapsed().as_secs_f64());
+        result
+    }
+    #[cfg(not(feature = "stage-timing"))]
+    {
+        let _ = stage;
+        f()
+    }
+}
+
+/// Adds `seconds` to `stage`'s total, if timing is built in.
+#[inline(always)]
+pub fn record(stage: Stage, seconds: f64) {
+    #[cfg(feature = "stage-timing")]
+    totals::TIMES.with(|times| {
+        let mut all = times.get();
+        let i = stage as usize;
+        all[i] = Some(all[i].unwrap_or(0.0) + seconds);
+        times.set(all);
+    });
+    #[cfg(not(feature = "stage-timing"))]
+    let _ = (stage, seconds);
+}
+
+/// Returns the time spent in each stage on this thread since the last call,
+/// clearing it, or `None` if timing is not built in.
+pub fn take_stage_times() -> Option<StageTimes> {
+    #[cfg(feature = "stage-timing")]
+    {
+        Some(totals::TIMES.with(|times| times.take()))
+    }
+    #[cfg(not(feature = "stage-timing"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_are_timed_only_when_built_in() {
+        take_stage_times();
+        assert_eq!(time(Stage::Query, || 7), 7);
+        record(Stage::Layout, 0.5);
+        record(Stage::Layout, 0.25);
+        let times = take_stage_times();
+        if cfg!(feature = "stage-timing") {
+            let times = times.unwrap();
+            assert!(times[Stage::Query as usize].is_some());
+            assert_eq!(times[Stage::Layout as usize], Some(0.75));
+            assert_eq!(times[Stage::Event as usize], None);
+            assert_eq!(take_stage_times(), Some([None; Stage::ALL.len()]));
+        } else {
+            assert_eq!(times, None);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Short messages shown for a few seconds in the bottom-right corner.
+//!
+//! Anything with `AppContext` access can `push` one; `App` expires them, asks for
+//! frames while they're up, and draws them over whichever screen is shown.
+
+use crate::render::Renderer;
+use crate::results::{draw_mark, Mark};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Toasts shown at once; pushing another drops the oldest.
+pub const MAX_TOASTS: usize = 3;
+/// Seconds a toast takes to fade out at the end of its time.
+pub const FADE_TIME: f64 = 0.3;
+/// Seconds a toast stays up unless told otherwise.
+pub const TOAST_TIME: f64 = 2.5;
+
+/// How a toast is colored and marked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    /// Marked with a star.
+    Success,
+    /// Marked with a cross.
+    Error,
+}
+
+/// One message and when it goes away.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub text: String,
+    pub severity: Severity,
+    /// Time (in `AppContext::now` seconds) the toast disappears.
+    pub expires: f64,
+}
+
+impl Toast {
+    /// Returns the toast's opacity at `now`: 1.0, until it fades over the last
+    /// `FADE_TIME` seconds.
+    pub fn alpha(&self, now: f64) -> f32 {
+        ((self.expires - now) / FADE_TIME).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// The toasts currently shown, oldest first.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    shown: VecDeque<Toast>,
+    /// Whether toasts were added or removed since the last `update`.
+    changed: bool,
+}
+
+impl Toasts {
+    /// Shows `text` for `duration` seconds from `now`, dropping the oldest toast if
+    /// `MAX_TOASTS` are already up.
+    pub fn push(&mut self, text: impl Into<String>, severity: Severity, duration: f64, now: f64) {
+        if self.shown.len() == MAX_TOASTS {
+            self.shown.pop_front();
+        }
+        self.shown.push_back(Toast {
+            text: text.into(),
+            severity,
+            expires: now + duration,
+        });
+        self.changed = true;
+    }
+    /// Removes expired toasts, returning whether the shown toasts changed since
+    /// the last call.
+    pub fn update(&mut self, now: f64) -> bool {
+        let before = self.shown.len();
+        self.shown.retain(|t| t.expires > now);
+        std::mem::take(&mut self.changed) || self.shown.len() != before
+    }
+    /// Returns the toasts shown, oldest first.
+    pub fn shown(&self) -> impl Iterator<Item = &Toast> {
+        self.shown.iter()
+    }
+    /// Returns whether any toast is up, so frames are needed to expire it.
+    pub fn is_active(&self) -> bool {
+        !self.shown.is_empty()
+    }
+    /// Returns whether any toast is fading at `now`, so it changes every frame.
+    pub fn is_fading(&self, now: f64) -> bool {
+        self.shown.iter().any(|t| t.alpha(now) < 1.0)
+    }
+}
+
+/// Returns where to draw toasts of `sizes` (oldest first) on a `screen`-sized
+/// window: right-aligned `margin` from the corner, newest at the bottom, older
+/// ones stacked above.
+pub fn toast_rects(sizes: &[Vec2], screen: Vec2, margin: f32) -> Vec<Rect> {
+    let mut bottom = screen.y - margin;
+    let mut rects: Vec<Rect> = sizes
+        .iter()
+        .rev()
+        .map(|size| {
+            let rect = Rect::new(screen.x - margin - size.x, bottom - size.y, size.x, size.y);
+            bottom = rect.y - margin / 2.0;
+            rect
+        })
+        .collect();
+    rects.reverse();
+    rects
+}
+
+/// Draws the toasts shown at `now`, each in a panel sized to its text.
+pub fn draw_toasts(
+    gfx: &dyn Renderer,
+    toasts: &Toasts,
+    now: f64,
+    font: Option<&Font>,
+    font_size: u16,
+    theme: &Theme,
+) {
+    let pad = font_size as f32 * 0.6;
+    let mark_w = font_size as f32;
+    let has_mark = |t: &Toast| t.severity != Severity::Info;
+    let sizes: Vec<Vec2> = toasts
+        .shown()
+        .map(|t| {
+            let text_w = gfx.measure(&t.text, font, font_size).width;
+            let mark = if has_mark(t) { mark_w } else { 0.0 };
+            vec2(text_w + mark + pad * 2.0, font_size as f32 + pad * 2.0)
+        })
+        .collect();
+    let rects = toast_rects(&sizes, gfx.screen_size(), pad);
+
+    for (toast, rect) in toasts.shown().zip(rects) {
+        let alpha = toast.alpha(now);
+        let faded = |mut color: Color| {
+            color.a *= alpha;
+            color
+        };
+        let (color, mark) = match toast.severity {
+            Severity::Info => (theme.entry_text, None),
+            Severity::Success => (theme.highlight, Some(Mark::Star)),
+            Severity::Error => (theme.error, Some(Mark::Cross)),
+        };
+        gfx.rect(rect, faded(theme.background));
+        gfx.rect_lines(rect, theme.outline_thickness, faded(color));
+        let mut x = rect.x + pad;
+        let cy = rect.y + rect.h / 2.0;
+        if let Some(mark) = mark {
+            draw_mark(gfx, mark, x + mark_w * 0.4, cy, mark_w * 0.6, faded(color));
+            x += mark_w;
+        }
+        let baseline = cy + font_size as f32 / 3.0;
+        gfx.text(
+            &toast.text,
+            vec2(x, baseline),
+            font,
+            font_size,
+            faded(color),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(toasts: &Toasts) -> Vec<&str> {
+        toasts.shown().map(|t| t.text.as_str()).collect()
+    }
+
+    #[test]
+    fn toasts_show_oldest_first_until_they_expire() {
+        let mut toasts = Toasts::default();
+        assert!(!toasts.is_active());
+        toasts.push("first", Severity::Info, 2.0, 0.0);
+        toasts.push("second", Severity::Success, 1.0, 0.5);
+        assert!(toasts.update(0.5), "pushing is a change");
+        assert!(!toasts.update(0.6), "nothing expired");
+        assert_eq!(texts(&toasts), ["first", "second"]);
+
+        assert!(toasts.update(1.5));
+        assert_eq!(texts(&toasts), ["first"]);
+        assert!(toasts.update(2.0));
+        assert!(!toasts.is_active());
+        assert!(!toasts.update(3.0));
+    }
+
+    #[test]
+    fn a_full_queue_drops_the_oldest() {
+        let mut toasts = Toasts::default();
+        for (i, text) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            toasts.push(text, Severity::Info, TOAST_TIME, i as f64);
+        }
+        assert_eq!(toasts.shown().count(), MAX_TOASTS);
+        assert_eq!(texts(&toasts), ["b", "c", "d"]);
+    }
+
+    #[test]
+    fn toasts_fade_over_their_last_moments() {
+        let toast = Toast {
+            text: "bye".to_owned(),
+            severity: Severity::Error,
+            expires: 10.0,
+        };
+        assert_eq!(toast.alpha(0.0), 1.0);
+        assert_eq!(toast.alpha(10.0 - FADE_TIME), 1.0);
+        assert!((toast.alpha(10.0 - FADE_TIME / 2.0) - 0.5).abs() < 1e-4);
+        assert_eq!(toast.alpha(10.0), 0.0);
+
+        let mut toasts = Toasts::default();
+        toasts.push("bye", Severity::Info, 1.0, 0.0);
+        assert!(!toasts.is_fading(0.5));
+        assert!(toasts.is_fading(1.0 - FADE_TIME / 2.0));
+    }
+
+    #[test]
+    fn toasts_stack_up_from_the_corner_newest_at_the_bottom() {
+        let sizes = [vec2(100.0, 20.0), vec2(50.0, 30.0)];
+        let rects = toast_rects(&sizes, vec2(800.0, 600.0), 10.0);
+        assert_eq!(
+            rects,
+            [
+                Rect::new(690.0, 535.0, 100.0, 20.0),
+                Rect::new(740.0, 560.0, 50.0, 30.0),
+            ]
+        );
+        assert!(toast_rects(&[], vec2(800.0, 600.0), 10.0).is_empty());
+    }
+}
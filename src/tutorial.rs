@@ -0,0 +1,528 @@
+//! The tutorial: a practice puzzle solved step by step, from the help overlay.
+//!
+//! Enter in the help pushes a `TutorialScreen` over the entry screen. It plays
+//! on a demo `EntryScreen` of its own, like the attract mode, with the puzzle
+//! card and what to do next in a panel over it. `PRACTICE` is a small Jumble of
+//! two clues whose circled letters spell the final phrase. The steps of a
+//! `TutorialScript` each wait until the user has done what they ask, as checked
+//! against a `TutorialState` taken after every update: typing the first
+//! scramble, its keys outlined on the on-screen keyboard; writing its answer in
+//! with Enter; solving the second clue alone; typing the circled letters; and
+//! spelling the final phrase from them with the final answer's panel. Escape
+//! leaves it at any step.
+
+use crate::app::AppContext;
+use crate::entry_screen::EntryScreen;
+use crate::events::{AppEvent, Nav};
+use crate::keyboard::KeyButton;
+use crate::keymap::Action;
+use crate::layout::ScreenLayout;
+use crate::modal::Focus;
+use crate::recall::Recall;
+use crate::render::{wrap_words, Renderer};
+use crate::screen::{ScreenTrait, Transition};
+use crate::strings::{fill, StringKey};
+use crate::theme::Theme;
+use macroquad::prelude::*;
+
+/// A clue of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clue {
+    pub scramble: &'static str,
+    pub answer: &'static str,
+    /// Indices of the answer's letters that are circled.
+    pub circled: &'static [usize],
+}
+
+/// A Jumble: clues, and the phrase their circled letters spell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub clues: &'static [Clue],
+    pub phrase: &'static str,
+}
+
+impl Puzzle {
+    /// Returns the circled letters of every clue, in order.
+    pub fn circled(&self) -> String {
+        self.clues
+            .iter()
+            .flat_map(|clue| {
+                clue.circled
+                    .iter()
+                    .filter_map(|&i| clue.answer.chars().nth(i))
+            })
+            .collect()
+    }
+}
+
+/// The practice puzzle. Each answer is the only word of the built-in list with
+/// its letters.
+pub const PRACTICE: Puzzle = Puzzle {
+    clues: &[
+        Clue {
+            scramble: "KRICT",
+            answer: "TRICK",
+            circled: &[0, 1, 2],
+        },
+        Clue {
+            scramble: "LYTES",
+            answer: "STYLE",
+            circled: &[1, 2],
+        },
+    ],
+    phrase: "TRY IT",
+};
+
+/// What the user has done, as the steps check it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TutorialState {
+    /// The entry's letters.
+    pub entry: String,
+    /// How many clues have their answers written in, first to last.
+    pub committed: usize,
+    /// The final answer spelled so far, while its panel is open.
+    pub phrase: Option<String>,
+}
+
+/// The steps, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    /// Type the first clue's scramble.
+    Type,
+    /// Write its answer in.
+    Commit,
+    /// Solve the second clue and write its answer in.
+    Next,
+    /// Type the circled letters.
+    Circled,
+    /// Spell the final phrase from them.
+    Final,
+    /// Solved; Enter leaves.
+    Done,
+}
+
+impl TutorialStep {
+    pub const ALL: [TutorialStep; 6] = [
+        TutorialStep::Type,
+        TutorialStep::Commit,
+        TutorialStep::Next,
+        TutorialStep::Circled,
+        TutorialStep::Final,
+        TutorialStep::Done,
+    ];
+
+    /// Returns whether `state` shows the step done, for `puzzle`. `Done` never is.
+    pub fn is_done(self, puzzle: &Puzzle, state: &TutorialState) -> bool {
+        match self {
+            TutorialStep::Type => {
+                let first = puzzle.clues.first().map_or("", |clue| clue.scramble);
+                same_letters(&state.entry, first)
+            }
+            TutorialStep::Commit => state.committed >= 1,
+            TutorialStep::Next => state.committed >= puzzle.clues.len(),
+            TutorialStep::Circled => same_letters(&state.entry, &puzzle.circled()),
+            TutorialStep::Final => state.phrase.as_deref() == Some(puzzle.phrase),
+            TutorialStep::Done => false,
+        }
+    }
+}
+
+/// The steps of a puzzle and how far along them the user is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TutorialScript {
+    puzzle: Puzzle,
+    /// The index in `TutorialStep::ALL` of the step in progress.
+    current: usize,
+}
+
+impl TutorialScript {
+    /// Starts the steps of `puzzle` at the first.
+    pub fn new(puzzle: Puzzle) -> Self {
+        Self { puzzle, current: 0 }
+    }
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+    /// Returns the step in progress.
+    pub fn step(&self) -> TutorialStep {
+        TutorialStep::ALL[self.current]
+    }
+    /// Returns the step in progress's number, from 1.
+    pub fn number(&self) -> usize {
+        self.current + 1
+    }
+    /// Moves past every step `state` shows done, in order, returning whether
+    /// it moved.
+    pub fn advance(&mut self, state: &TutorialState) -> bool {
+        let start = self.current;
+        while self.step().is_done(&self.puzzle, state) {
+            self.current += 1;
+        }
+        self.current != start
+    }
+}
+
+/// Returns whether `a` and `b` have the same letters, in any order.
+fn same_letters(a: &str, b: &str) -> bool {
+    let sorted = |text: &str| {
+        let mut letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+        letters.sort_unstable();
+        letters
+    };
+    sorted(a) == sorted(b)
+}
+
+/// Returns the letters of `want` that `have` is still without, each as many
+/// times as it's missing.
+fn letters_left(want: &str, have: &str) -> Vec<char> {
+    let mut left: Vec<char> = want.chars().collect();
+    for c in have.chars() {
+        if let Some(i) = left.iter().position(|&l| l == c) {
+            left.remove(i);
+        }
+    }
+    left
+}
+
+/// The practice puzzle being played.
+pub struct TutorialScreen {
+    /// The entry screen it plays on.
+    demo: EntryScreen,
+    script: TutorialScript,
+    /// How many clues have their answers written in.
+    committed: usize,
+    /// The entries Up and Down called back before the tutorial, put back after.
+    recall: Recall,
+}
+
+impl TutorialScreen {
+    /// Creates the tutorial over the loaded dictionary, on an empty entry with
+    /// the on-screen keyboard.
+    pub fn new(ctx: &mut AppContext) -> Self {
+        let recall = ctx.state.recall.clone();
+        let mut demo = EntryScreen::new(ctx).into_demo().with_keyboard();
+        demo.handle_event(ctx, AppEvent::Action(Action::ClearEntry));
+        Self {
+            demo,
+            script: TutorialScript::new(PRACTICE),
+            committed: 0,
+            recall,
+        }
+    }
+
+    /// Returns what the steps are checked against.
+    fn state(&self) -> TutorialState {
+        TutorialState {
+            entry: self.demo.entry().to_owned(),
+            committed: self.committed,
+            phrase: self.demo.final_phrase(),
+        }
+    }
+
+    /// Puts back what the tutorial changed and returns to the entry screen.
+    fn finish(&mut self, ctx: &mut AppContext) -> Option<Transition> {
+        ctx.state.recall = std::mem::take(&mut self.recall);
+        ctx.dirty = true;
+        Some(Transition::Pop)
+    }
+
+    /// Writes the answer shown in, if it's the next clue's, and clears the
+    /// entry for what comes after; returns whether it did.
+    fn commit(&mut self, ctx: &mut AppContext) -> bool {
+        let clue = self.script.puzzle().clues.get(self.committed);
+        let Some(clue) = clue.filter(|clue| self.demo.answer() == Some(clue.answer)) else {
+            return false;
+        };
+        log::info!("[TutorialScreen::commit] {} solved", clue.scramble);
+        self.committed += 1;
+        self.demo
+            .handle_event(ctx, AppEvent::Action(Action::ClearEntry));
+        ctx.dirty = true;
+        true
+    }
+
+    /// Returns what to do for the step in progress.
+    fn instruction(&self, ctx: &AppContext) -> String {
+        let lang = ctx.state.config.language;
+        let chord = |action| {
+            ctx.keymap
+                .chords(action)
+                .next()
+                .map_or_else(|| "?".to_owned(), |c| c.to_string())
+        };
+        let scramble = |i: usize| self.script.puzzle().clues.get(i).map_or("", |c| c.scramble);
+        match self.script.step() {
+            TutorialStep::Type => fill(lang.text(StringKey::TutorialType), scramble(0)),
+            TutorialStep::Commit => lang.text(StringKey::TutorialCommit).to_owned(),
+            TutorialStep::Next => fill(lang.text(StringKey::TutorialNext), scramble(1)),
+            TutorialStep::Circled => fill(
+                lang.text(StringKey::TutorialCircled),
+                chord(Action::ClearEntry),
+            ),
+            TutorialStep::Final => fill(
+                lang.text(StringKey::TutorialFinal),
+                chord(Action::SpellFinal),
+            ),
+            TutorialStep::Done => lang.text(StringKey::TutorialDone).to_owned(),
+        }
+    }
+
+    /// Outlines the keys of the first scramble's letters not typed yet.
+    fn draw_keys(&self, gfx: &dyn Renderer, theme: &Theme) {
+        let first = self
+            .script
+            .puzzle()
+            .clues
+            .first()
+            .map_or("", |clue| clue.scramble);
+        let left = letters_left(first, self.demo.entry());
+        for (button, rect) in self.demo.key_buttons() {
+            if matches!(button, KeyButton::Letter(c) if left.contains(c)) {
+                gfx.rect_lines(*rect, theme.outline_thickness * 3.0, theme.highlight);
+            }
+        }
+    }
+
+    /// Draws the puzzle in a row of boxes `box_w` square from `top_left`: each
+    /// clue's scramble faintly until its answer is written in, the circled boxes
+    /// ringed, then the final phrase's boxes, filled in once it's solved.
+    fn draw_card(&self, ctx: &AppContext, gfx: &dyn Renderer, top_left: Vec2, box_w: f32) {
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let size = (box_w * 0.7) as u16;
+        let puzzle = self.script.puzzle();
+        let letter = |c: char, rect: Rect, color: Color| {
+            let text = c.to_string();
+            let dims = gfx.measure(&text, font, size);
+            let pos = vec2(
+                rect.x + (rect.w - dims.width) / 2.0,
+                rect.y + (rect.h + dims.offset_y) / 2.0,
+            );
+            gfx.text(&text, pos, font, size, color);
+        };
+        let mut x = top_left.x;
+        for (i, clue) in puzzle.clues.iter().enumerate() {
+            let solved = i < self.committed;
+            let shown = if solved { clue.answer } else { clue.scramble };
+            for (j, c) in shown.chars().enumerate() {
+                let rect = Rect::new(x, top_left.y, box_w, box_w);
+                if clue.circled.contains(&j) {
+                    let center = rect.center();
+                    gfx.circle(center, box_w * 0.5, theme.highlight);
+                    gfx.circle(
+                        center,
+                        box_w * 0.5 - theme.outline_thickness * 2.0,
+                        theme.background,
+                    );
+                } else {
+                    gfx.rect_lines(rect, theme.outline_thickness, theme.box_outline);
+                }
+                let mut color = theme.entry_text;
+                if !solved {
+                    color.a *= 0.35;
+                }
+                letter(c, rect, color);
+                x += box_w * 1.1;
+            }
+            x += box_w;
+        }
+        let solved = self.script.step() == TutorialStep::Done;
+        for c in puzzle.phrase.chars() {
+            if c == ' ' {
+                x += box_w * 0.5;
+                continue;
+            }
+            let rect = Rect::new(x, top_left.y, box_w, box_w);
+            gfx.rect_lines(rect, theme.outline_thickness * 2.0, theme.highlight);
+            if solved {
+                letter(c, rect, theme.entry_text);
+            }
+            x += box_w * 1.1;
+        }
+    }
+}
+
+impl ScreenTrait for TutorialScreen {
+    /// Enter writes in an answer shown for the next clue, or leaves once the
+    /// puzzle's solved, and Escape leaves any time; everything else goes to
+    /// the entry screen underneath, which can't leave it.
+    fn handle_event(&mut self, ctx: &mut AppContext, ev: AppEvent) -> Option<Transition> {
+        let entry_focused = self.demo.focus() == Focus::Entry;
+        match ev {
+            AppEvent::CloseRequested => return Some(Transition::Quit),
+            AppEvent::Action(Action::Quit) if entry_focused => return self.finish(ctx),
+            AppEvent::Nav(Nav::Select) if entry_focused => {
+                if self.script.step() == TutorialStep::Done {
+                    return self.finish(ctx);
+                }
+                if !self.commit(ctx) {
+                    self.demo.handle_event(ctx, ev);
+                }
+            }
+            _ => {
+                self.demo.handle_event(ctx, ev);
+            }
+        }
+        None
+    }
+    /// Runs the entry screen, then moves past the steps it shows done.
+    fn update(&mut self, ctx: &mut AppContext, dt: f64) -> Option<Transition> {
+        self.demo.update(ctx, dt);
+        if self.script.advance(&self.state()) {
+            log::info!("[TutorialScreen::update] on to {:?}", self.script.step());
+            ctx.dirty = true;
+        }
+        None
+    }
+    /// Draws the entry screen under a panel with the step, the puzzle, and the
+    /// key to leave: at the top, or above the keyboard on a portrait screen.
+    fn draw(&self, ctx: &AppContext, gfx: &dyn Renderer) {
+        self.demo.draw(ctx, gfx);
+        let theme = ctx.themes.current();
+        let font = ctx.font.as_ref();
+        let lang = ctx.state.config.language;
+        if self.script.step() == TutorialStep::Type && self.demo.focus() == Focus::Entry {
+            self.draw_keys(gfx, theme);
+        }
+
+        let size = ctx.style.result_size / 2;
+        let line_h = size as f32 * 1.3;
+        let (w, h) = (ctx.size.x, ctx.size.y);
+        let x = w * 0.04;
+        let counter = format!("{}/{}", self.script.number(), TutorialStep::ALL.len());
+        let counter_w = gfx.measure(&counter, font, size).width;
+        let text = self.instruction(ctx);
+        let lines = wrap_words(
+            text.split_whitespace(),
+            w * 0.92 - counter_w - line_h,
+            |line| gfx.measure(line, font, size).width,
+        );
+        let puzzle = self.script.puzzle();
+        let boxes: usize = puzzle.clues.iter().map(|c| c.answer.len()).sum::<usize>()
+            + puzzle.phrase.chars().filter(|&c| c != ' ').count();
+        let units = boxes as f32 * 1.1 + puzzle.clues.len() as f32 + 0.5;
+        let box_w = (size as f32 * 1.3).min(w * 0.92 / units);
+        let hint_size = size * 3 / 4;
+        let panel_h = line_h * (lines.len() as f32 + 0.6) + box_w + hint_size as f32 * 2.4;
+
+        let layout = ScreenLayout::new(w, h, ctx.style.dpi);
+        let top = match layout.portrait {
+            true => (layout.keyboard.y - panel_h).max(0.0),
+            false => 0.0,
+        };
+        let panel = Rect::new(0.0, top, w, panel_h);
+        let mut backdrop = theme.background;
+        backdrop.a = 0.92;
+        gfx.rect(panel, backdrop);
+        gfx.line(
+            vec2(0.0, panel.bottom()),
+            vec2(w, panel.bottom()),
+            theme.outline_thickness,
+            theme.box_outline,
+        );
+        for (i, line) in lines.iter().enumerate() {
+            let pos = vec2(x, top + line_h * (i as f32 + 1.0));
+            gfx.text(line, pos, font, size, theme.entry_text);
+        }
+        let counter_pos = vec2(w - x - counter_w, top + line_h);
+        gfx.text(&counter, counter_pos, font, size, theme.box_outline);
+        let card_y = top + line_h * (lines.len() as f32 + 0.6);
+        self.draw_card(ctx, gfx, vec2(x, card_y), box_w);
+        let hint = lang.text(StringKey::TutorialHint);
+        let hint_pos = vec2(x, panel.bottom() - hint_size as f32 * 0.6);
+        gfx.text(hint, hint_pos, font, hint_size, theme.result);
+    }
+    fn focus(&self) -> Focus {
+        self.demo.focus()
+    }
+    fn is_animating(&self) -> bool {
+        self.demo.is_animating()
+    }
+    fn is_waiting(&self) -> bool {
+        self.demo.is_waiting()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(entry: &str, committed: usize, phrase: Option<&str>) -> TutorialState {
+        TutorialState {
+            entry: entry.into(),
+            committed,
+            phrase: phrase.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn the_circled_letters_spell_the_final_phrase() {
+        assert_eq!(PRACTICE.circled(), "TRITY");
+        assert!(same_letters(&PRACTICE.circled(), PRACTICE.phrase));
+        for clue in PRACTICE.clues {
+            assert!(same_letters(clue.scramble, clue.answer));
+            assert_ne!(clue.scramble, clue.answer);
+        }
+    }
+
+    #[test]
+    fn each_step_checks_what_it_asks_for() {
+        let done = |step: TutorialStep, state: &TutorialState| step.is_done(&PRACTICE, state);
+        assert!(done(TutorialStep::Type, &state("KRICT", 0, None)));
+        assert!(done(TutorialStep::Type, &state("TRICK", 0, None)));
+        assert!(!done(TutorialStep::Type, &state("KRIC", 0, None)));
+        assert!(!done(TutorialStep::Type, &state("KRICTS", 0, None)));
+
+        assert!(!done(TutorialStep::Commit, &state("KRICT", 0, None)));
+        assert!(done(TutorialStep::Commit, &state("", 1, None)));
+        assert!(!done(TutorialStep::Next, &state("LYTES", 1, None)));
+        assert!(done(TutorialStep::Next, &state("", 2, None)));
+
+        assert!(done(TutorialStep::Circled, &state("YTTIR", 2, None)));
+        assert!(!done(TutorialStep::Circled, &state("TRIY", 2, None)));
+        assert!(!done(TutorialStep::Final, &state("TRITY", 2, Some("TRY"))));
+        assert!(!done(
+            TutorialStep::Final,
+            &state("TRITY", 2, Some("TRYIT"))
+        ));
+        assert!(done(
+            TutorialStep::Final,
+            &state("TRITY", 2, Some("TRY IT"))
+        ));
+        assert!(!done(
+            TutorialStep::Done,
+            &state("TRITY", 2, Some("TRY IT"))
+        ));
+    }
+
+    #[test]
+    fn the_script_moves_on_only_past_steps_done_in_order() {
+        let mut script = TutorialScript::new(PRACTICE);
+        assert_eq!((script.step(), script.number()), (TutorialStep::Type, 1));
+        // Later steps done don't count while an earlier one isn't
+        assert!(!script.advance(&state("", 2, Some("TRY IT"))));
+        assert_eq!(script.step(), TutorialStep::Type);
+
+        assert!(script.advance(&state("CRIKT", 0, None)));
+        assert_eq!((script.step(), script.number()), (TutorialStep::Commit, 2));
+        assert!(!script.advance(&state("CRIKT", 0, None)));
+        // Writing both answers in passes two steps at once
+        assert!(script.advance(&state("", 2, None)));
+        assert_eq!(script.step(), TutorialStep::Circled);
+        // Steps already passed stay passed
+        assert!(script.advance(&state("TRITY", 2, None)));
+        assert_eq!(script.step(), TutorialStep::Final);
+        assert!(script.advance(&state("TRITY", 2, Some("TRY IT"))));
+        assert_eq!(script.step(), TutorialStep::Done);
+        assert_eq!(script.number(), TutorialStep::ALL.len());
+        assert!(!script.advance(&state("", 0, None)));
+    }
+
+    #[test]
+    fn keys_left_count_repeated_letters() {
+        assert_eq!(letters_left("KRICT", ""), ['K', 'R', 'I', 'C', 'T']);
+        assert_eq!(letters_left("KRICT", "RCX"), ['K', 'I', 'T']);
+        assert_eq!(letters_left("TRITY", "T"), ['R', 'I', 'T', 'Y']);
+        assert!(letters_left("TRITY", "YTTRI").is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+//! Suggestions for scrambles with a mistyped letter.
+//!
+//! When the letters entered match nothing, `suggest` tries each one swapped for
+//! every other letter and keeps the swaps that do match. A slip of the finger
+//! lands on a neighboring key, so swaps between keys next to each other on the
+//! keyboard come first: typing `NUDIP` for `NUDOP` suggests the O, next to the I,
+//! ahead of letters from across the keyboard.
+
+use crate::render::Renderer;
+use crate::theme::Theme;
+use crate::wordmap::WordMap;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Most suggestions offered for one entry.
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// The letters next to each letter's key on a QWERTY keyboard, `A` first.
+const QWERTY_NEIGHBORS: [&str; 26] = [
+    "QWSZ", "GHVN", "DFXV", "ERSFXC", "WRSD", "RTDGCV", "TYFHVB", "YUGJBN", "UOJK", "UIHKNM",
+    "IOJLM", "OPK", "JKN", "HJBM", "IPKL", "OL", "WA", "ETDF", "WEADZX", "RYFG", "YIHJ", "FGCB",
+    "QEAS", "SDZC", "TUGH", "ASX",
+];
+
+/// The letters next to each letter's key on a French AZERTY keyboard, `A` first.
+const AZERTY_NEIGHBORS: [&str; 26] = [
+    "ZQ", "GHVN", "DFXV", "ERSFXC", "ZRSD", "RTDGCV", "TYFHVB", "YUGJBN", "UOJK", "UIHKN", "IOJL",
+    "OPKM", "PL", "HJB", "IPKL", "OLM", "AZSW", "ETDF", "ZEQDWX", "RYFG", "YIHJ", "FGCB", "QSX",
+    "SDWC", "TUGH", "AEQS",
+];
+
+/// Keyboard layouts, selected by `keyboard_layout` in the config, for judging
+/// which letters are typed by mistake for which.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 2] = [KeyboardLayout::Qwerty, KeyboardLayout::Azerty];
+
+    /// Returns the layout's name as shown in the settings.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyboardLayout::Qwerty => "QWERTY",
+            KeyboardLayout::Azerty => "AZERTY",
+        }
+    }
+    /// Returns whether the keys for letters `a` and `b` are next to each other.
+    pub fn adjacent(self, a: char, b: char) -> bool {
+        let table = match self {
+            KeyboardLayout::Qwerty => &QWERTY_NEIGHBORS,
+            KeyboardLayout::Azerty => &AZERTY_NEIGHBORS,
+        };
+        a.is_ascii_uppercase() && table[(a as u8 - b'A') as usize].contains(b)
+    }
+}
+
+/// An entry with one letter swapped, and the words it then unscrambles to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Index of the swapped letter in the entry.
+    pub position: usize,
+    /// The letter swapped in.
+    pub replacement: char,
+    /// Whether the letter swapped out is next to it on the keyboard.
+    pub adjacent: bool,
+    /// The entry with the letter swapped.
+    pub entry: String,
+    /// The words matching that entry.
+    pub words: Vec<String>,
+}
+
+/// Returns up to `MAX_SUGGESTIONS` entries one letter away from `q` that match
+/// words in `dict`, those swapping in a letter next to the typed one on `layout`
+/// first, then by position in `q`.
+///
+/// Swaps that give the same letters (as when `q` has a letter twice) are offered
+/// once. `q` is expected to be uppercase letters, like the entry; the swaps are
+/// looked up in place, so only those that match allocate.
+pub fn suggest(dict: &WordMap, q: &str, layout: KeyboardLayout) -> Vec<Suggestion> {
+    let alphabet = dict.alphabet();
+    let mut entry: Vec<char> = q.chars().collect();
+    let mut text = String::with_capacity(q.len() + 4);
+    let mut key_buf = Vec::with_capacity(q.len());
+    let mut found: Vec<(String, Suggestion)> = Vec::new();
+    for position in 0..entry.len() {
+        let old = entry[position];
+        for replacement in alphabet.letters().chars().filter(|&c| c != old) {
+            entry[position] = replacement;
+            text.clear();
+            text.extend(&entry);
+            let Some(words) = dict.find_match(&text, 0, usize::MAX, &mut key_buf) else {
+                continue;
+            };
+            let suggestion = Suggestion {
+                position,
+                replacement,
+                adjacent: layout.adjacent(old, replacement),
+                entry: text.clone(),
+                words: words.to_vec(),
+            };
+            let key = alphabet.key(&text);
+            match found.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, kept)) if suggestion.adjacent && !kept.adjacent => *kept = suggestion,
+                Some(_) => (),
+                None => found.push((key, suggestion)),
+            }
+        }
+        entry[position] = old;
+    }
+    // Stable, so ties stay in position order
+    found.sort_by_key(|(_, s)| !s.adjacent);
+    found
+        .into_iter()
+        .map(|(_, s)| s)
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// Draws a heading and then one suggestion per line down from the top of
+/// `area`, centered, each entry with its swapped letter underlined and followed
+/// by the words it matches. Lines that don't fit are left out.
+pub fn draw_suggestions(
+    gfx: &dyn Renderer,
+    heading: &str,
+    suggestions: &[Suggestion],
+    area: Rect,
+    font: Option<&Font>,
+    size: u16,
+    theme: &Theme,
+) {
+    let line_h = size as f32 * 1.4;
+    let measure = |text: &str| gfx.measure(text, font, size).width;
+    let mut baseline = area.y + size as f32;
+    if suggestions.is_empty() || baseline > area.bottom() {
+        return;
+    }
+    gfx.text(
+        heading,
+        vec2(area.center().x - measure(heading) / 2.0, baseline),
+        font,
+        size,
+        theme.result,
+    );
+    for suggestion in suggestions {
+        baseline += line_h;
+        if baseline > area.bottom() {
+            break;
+        }
+        let words = format!("  {}", suggestion.words.join(", "));
+        let x = area.center().x - (measure(&suggestion.entry) + measure(&words)) / 2.0;
+        gfx.text(
+            &suggestion.entry,
+            vec2(x, baseline),
+            font,
+            size,
+            theme.highlight,
+        );
+
+        let before: String = suggestion.entry.chars().take(suggestion.position).collect();
+        let letter_x = x + measure(&before);
+        let letter_w = measure(&suggestion.replacement.to_string());
+        let y = baseline + size as f32 * 0.15;
+        let thickness = (size as f32 / 12.0).max(1.0);
+        gfx.line(
+            vec2(letter_x, y),
+            vec2(letter_x + letter_w, y),
+            thickness,
+            theme.highlight,
+        );
+
+        let words_x = x + measure(&suggestion.entry);
+        gfx.text(&words, vec2(words_x, baseline), font, size, theme.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::wordmap::make_word_map_with_progress;
+
+    fn dict(words: &str) -> WordMap {
+        make_word_map_with_progress(words, Alphabet::English, usize::MAX, usize::MAX, |_| ())
+    }
+
+    fn entries(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.entry.as_str()).collect()
+    }
+
+    #[test]
+    fn neighbors_are_neighbors_both_ways() {
+        for layout in KeyboardLayout::ALL {
+            for a in 'A'..='Z' {
+                assert!(!layout.adjacent(a, a), "{layout:?} {a}");
+                for b in 'A'..='Z' {
+                    assert_eq!(
+                        layout.adjacent(a, b),
+                        layout.adjacent(b, a),
+                        "{layout:?} {a} {b}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layouts_differ_where_their_keys_do() {
+        use KeyboardLayout::*;
+        assert!(Qwerty.adjacent('A', 'W') && !Azerty.adjacent('A', 'W'));
+        assert!(Azerty.adjacent('Q', 'S') && !Qwerty.adjacent('Q', 'S'));
+        assert!(Azerty.adjacent('L', 'M') && !Qwerty.adjacent('L', 'M'));
+        assert!(Qwerty.adjacent('I', 'O') && Azerty.adjacent('I', 'O'));
+        assert!(!Qwerty.adjacent('é', 'E'));
+    }
+
+    #[test]
+    fn neighboring_keys_rank_first_then_position() {
+        // I -> O is next door; N -> L and I -> E are across the keyboard
+        let dict = dict("POUND\nUPEND\nLUPID\n");
+        let found = suggest(&dict, "NUDIP", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&found), ["NUDOP", "LUDIP", "NUDEP"]);
+        let first = &found[0];
+        assert_eq!((first.position, first.replacement), (3, 'O'));
+        assert!(first.adjacent);
+        assert_eq!(first.words, ["POUND"]);
+        assert!(!found[1].adjacent && !found[2].adjacent);
+    }
+
+    #[test]
+    fn the_layout_decides_which_swaps_are_slips() {
+        let dict = dict("MOLE\nHOLE\n");
+        let qwerty = suggest(&dict, "LOLE", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&qwerty), ["HOLE", "MOLE"]);
+        let azerty = suggest(&dict, "LOLE", KeyboardLayout::Azerty);
+        assert_eq!(entries(&azerty), ["MOLE", "HOLE"]);
+    }
+
+    #[test]
+    fn swaps_giving_the_same_letters_are_offered_once() {
+        // Either L of LOLE swapped for M spells MOLE
+        let found = suggest(&dict("MOLE\n"), "LOLE", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&found), ["MOLE"]);
+        assert_eq!(found[0].position, 0);
+    }
+
+    #[test]
+    fn suggestions_stop_at_the_most_offered() {
+        let dict = dict("CATE\nDATE\nFATE\nGATE\nHATE\nKATE\nLATE\nMATE\nRATE\n");
+        let found = suggest(&dict, "ZATE", KeyboardLayout::Qwerty);
+        assert_eq!(found.len(), MAX_SUGGESTIONS);
+        assert_eq!(entries(&found), ["CATE", "DATE", "FATE", "GATE", "HATE"]);
+        assert!(suggest(&dict, "QQQQ", KeyboardLayout::Qwerty).is_empty());
+    }
+
+    #[test]
+    fn letters_are_swapped_from_the_word_lists_alphabet() {
+        let words = "NIÑO\nNIDO\n";
+        let spanish = make_word_map_with_progress(words, Alphabet::Spanish, 10, 10, |_| ());
+        let found = suggest(&spanish, "NIXO", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&found), ["NIDO", "NIÑO"]);
+        // Ñ is a letter of its own, so it can be swapped out too
+        let found = suggest(&spanish, "NIÑA", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&found), ["NIÑO"]);
+        // In English Ñ folds into N, which is all there is to swap in
+        let english = dict(words);
+        let found = suggest(&english, "NIXO", KeyboardLayout::Qwerty);
+        assert_eq!(entries(&found), ["NIDO", "NINO"]);
+    }
+}
@@ -0,0 +1,364 @@
+//! Saying when a newer release is out.
+//!
+//! With `update_check` on and `update_url` set in the config, the app fetches
+//! a small manifest from that URL at start, at most once a day:
+//!
+//! ```text
+//! {"version": "0.4.0", "changelog": "Phrases in word lists"}
+//! ```
+//!
+//! If its version is newer than this build's, a toast says so and the settings
+//! screen shows the changelog line. Nothing is downloaded. The fetch runs on its
+//! own thread with a short timeout, and a failure is only logged, at debug.
+//! Like `share`, it speaks plain HTTP, asking for HTTP/1.0 so the answer comes
+//! whole rather than in chunks. There's no TLS client among the app's
+//! dependencies, so hosts that only serve https, which is most of them
+//! (GitHub's release pages and raw files included), can't be reached: an https
+//! URL is refused with a warning in the log, and the manifest has to be served
+//! over plain http, from a machine on the home network for instance.
+
+// `DeJson`'s expansion for `Option` fields trips this lint
+#![allow(clippy::question_mark)]
+
+use crate::storage::Format;
+use nanoserde::DeJson;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Key under which the day of the last check is kept.
+pub const UPDATE_CHECK_KEY: &str = "update_check.txt";
+/// Format of `UPDATE_CHECK_KEY`: a `YYYY-MM-DD` date.
+pub const UPDATE_CHECK_FORMAT: Format = Format {
+    name: "update check",
+    version: 1,
+};
+/// Longest the fetch waits to connect, and then for each read.
+#[cfg(not(target_arch = "wasm32"))]
+const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+/// Most bytes of the manifest read.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_MANIFEST: u64 = 16 * 1024;
+
+/// A semantic version, such as `1.2.3` or `1.3.0-beta.2`; build metadata after
+/// a `+` is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers, such as `["beta", "2"]`; empty for a release.
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    /// Parses `text`, allowing a leading `v`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix('v').unwrap_or(text);
+        let text = text.split_once('+').map_or(text, |(version, _)| version);
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (text, None),
+        };
+        let number = |part: &str| {
+            let leading_zero = part.len() > 1 && part.starts_with('0');
+            let digits = !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            (digits && !leading_zero)
+                .then(|| part.parse().ok())
+                .flatten()
+        };
+        let mut parts = core.split('.');
+        let major = number(parts.next()?)?;
+        let minor = number(parts.next()?)?;
+        let patch = number(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        let pre: Vec<String> = match pre {
+            Some(pre) => pre.split('.').map(str::to_owned).collect(),
+            None => Vec::new(),
+        };
+        let valid = |id: &String| {
+            !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        };
+        if !pre.iter().all(valid) {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+    /// Returns this build's version.
+    pub fn current() -> Self {
+        Self::parse(env!("CARGO_PKG_VERSION")).expect("the package version is semver")
+    }
+}
+
+/// Compares pre-release identifiers: numeric ones by value and below
+/// alphanumeric ones, the rest as text.
+fn compare_identifiers(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let core = (self.major, self.minor, self.patch);
+        core.cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // A pre-release comes before its release
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    let pairs = self.pre.iter().zip(&other.pre);
+                    pairs
+                        .map(|(a, b)| compare_identifiers(a, b))
+                        .find(|order| order.is_ne())
+                        .unwrap_or_else(|| self.pre.len().cmp(&other.pre.len()))
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// What the manifest says of the latest release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub version: Version,
+    /// A line on what's new in it.
+    pub changelog: String,
+}
+
+/// The manifest as sent.
+#[derive(DeJson)]
+struct RawManifest {
+    version: String,
+    changelog: Option<String>,
+}
+
+/// Parses manifest JSON `text`, or says why it can't.
+pub fn parse_manifest(text: &str) -> Result<Manifest, String> {
+    let raw = RawManifest::deserialize_json(text).map_err(|e| format!("unreadable: {e}"))?;
+    let version =
+        Version::parse(&raw.version).ok_or_else(|| format!("'{}' isn't a version", raw.version))?;
+    let changelog = raw.changelog.unwrap_or_default();
+    let changelog = changelog
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_owned();
+    Ok(Manifest { version, changelog })
+}
+
+/// Returns whether a check is due `today`, given the day of the last one.
+pub fn is_due(last: Option<i64>, today: i64) -> bool {
+    last.is_none_or(|last| today != last)
+}
+
+/// Returns whether `url` asks for https, which `http_get` can't speak.
+pub fn is_https(url: &str) -> bool {
+    url.get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+}
+
+/// Fetches `url` over plain HTTP/1.0, returning the body of a 200 answer.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn http_get(url: &str) -> Result<String, String> {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    if is_https(url) {
+        return Err(format!(
+            "'{url}' is https, which isn't supported; use an http:// URL"
+        ));
+    }
+    let rest = url.strip_prefix("http://").unwrap_or(url);
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = match host {
+        "" => return Err(format!("no host in '{url}'")),
+        host if host.contains(':') => host.to_owned(),
+        host => format!("{host}:80"),
+    };
+    let io_error = |e: std::io::Error| e.to_string();
+    let socket = address.to_socket_addrs().map_err(io_error)?.next();
+    let socket = socket.ok_or_else(|| format!("can't resolve '{host}'"))?;
+    let mut stream = TcpStream::connect_timeout(&socket, FETCH_TIMEOUT)
+        .map_err(|e| format!("can't connect: {e}"))?;
+    stream
+        .set_read_timeout(Some(FETCH_TIMEOUT))
+        .map_err(io_error)?;
+    let head = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\nAccept: application/json\r\n\r\n");
+    stream.write_all(head.as_bytes()).map_err(io_error)?;
+
+    let mut reader = BufReader::new(stream.take(MAX_MANIFEST));
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(io_error)?;
+    match line.split_whitespace().nth(1) {
+        Some("200") => (),
+        Some(status) => return Err(format!("answered {status}")),
+        None => return Err("no answer".to_owned()),
+    }
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(io_error)? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+    let mut body = String::new();
+    reader.read_to_string(&mut body).map_err(io_error)?;
+    Ok(body)
+}
+
+/// Starts checking for a newer release as the config asks, if it does and one
+/// is due, noting the day in `storage`. The receiver gets the manifest only if
+/// its version is newer than this build's.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn start(
+    config: &crate::config::Config,
+    storage: &dyn crate::storage::Storage,
+) -> Option<std::sync::mpsc::Receiver<Manifest>> {
+    use crate::date::{format_day, parse_day, today};
+
+    if !config.update_check {
+        return None;
+    }
+    let url = config.update_url.clone().filter(|url| !url.is_empty())?;
+    if is_https(&url) {
+        log::warn!(
+            "[update] update_url '{url}' is https, which isn't supported; use an http:// URL"
+        );
+        return None;
+    }
+    let today = today()?;
+    let last = storage.read_format(UPDATE_CHECK_KEY, UPDATE_CHECK_FORMAT);
+    let last = last.ok().flatten().and_then(|text| parse_day(text.trim()));
+    if !is_due(last, today) {
+        return None;
+    }
+    if let Err(e) = storage.write_format(UPDATE_CHECK_KEY, UPDATE_CHECK_FORMAT, &format_day(today))
+    {
+        log::warn!("[update] failed to save {UPDATE_CHECK_KEY}: {e}");
+    }
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let manifest = http_get(&url).and_then(|body| parse_manifest(&body));
+        match manifest {
+            Ok(manifest) if manifest.version > Version::current() => {
+                log::info!("[update] version {} is out", manifest.version);
+                let _ = tx.send(manifest);
+            }
+            Ok(_) => log::debug!("[update] up to date"),
+            Err(e) => log::debug!("[update] couldn't check '{url}': {e}"),
+        }
+    });
+    Some(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn versions_order_as_semver_says() {
+        let ordered = [
+            "0.9.9",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(version(pair[0]) < version(pair[1]), "{pair:?}");
+        }
+        assert_eq!(version("v1.2.3+build.5"), version("1.2.3"));
+        for bad in ["1.2", "1.2.3.4", "01.2.3", "1.2.x", "1.2.3-", "1.2.3-be$ta"] {
+            assert_eq!(Version::parse(bad), None, "{bad}");
+        }
+        assert_eq!(version("1.3.0-beta.2").to_string(), "1.3.0-beta.2");
+    }
+
+    #[test]
+    fn checks_are_due_once_a_day() {
+        assert!(is_due(None, 100));
+        assert!(!is_due(Some(100), 100));
+        assert!(is_due(Some(99), 100));
+        // A clock set back still checks
+        assert!(is_due(Some(101), 100));
+    }
+
+    #[test]
+    fn manifests_parse_or_say_why_not() {
+        let text = r#"{"version": "0.4.0", "changelog": "Phrases in word lists\nand more"}"#;
+        let manifest = parse_manifest(text).unwrap();
+        assert_eq!(manifest.version, version("0.4.0"));
+        assert_eq!(manifest.changelog, "Phrases in word lists");
+        let bare = parse_manifest(r#"{"version": "0.4.0"}"#).unwrap();
+        assert_eq!(bare.changelog, "");
+        assert!(parse_manifest(r#"{"version": "soon"}"#).is_err());
+        assert!(parse_manifest(r#"{"changelog": "no version"}"#).is_err());
+        assert!(parse_manifest("<html>not found</html>").is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn fetches_ask_for_http_1_0_and_refuse_https() {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            BufReader::new(&stream).read_line(&mut request).unwrap();
+            let body = r#"{"version": "9.0.0"}"#;
+            let answer = format!(
+                "HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            (&stream).write_all(answer.as_bytes()).unwrap();
+            request
+        });
+        let body = http_get(&format!("http://127.0.0.1:{port}/latest.json")).unwrap();
+        assert_eq!(body, r#"{"version": "9.0.0"}"#);
+        assert_eq!(server.join().unwrap(), "GET /latest.json HTTP/1.0\r\n");
+
+        let e = http_get("HTTPS://example.com/latest.json").unwrap_err();
+        assert!(e.contains("https"), "{e}");
+    }
+}
@@ -0,0 +1,430 @@
+//! Opt-in counts of the features used, kept on this machine.
+//!
+//! With `usage_metrics` on in the config (it's off unless turned on in the
+//! settings), each use of a feature in `Feature` adds one to that day's count.
+//! Nothing else is kept: a `Feature` carries no data, so no entry, word, or
+//! pasted text can reach the file, and the file is never sent anywhere. The
+//! counts made since the last save are added to those in `USAGE_KEY` when the
+//! state is saved, so sessions, restarts, and other windows add up rather than
+//! write over each other. `jumble_helper metrics --summary` prints them.
+//!
+//! `USAGE_KEY` is JSON, for reading as it is, so its version is a field rather
+//! than an envelope line (see `storage`): each day's counts by feature name,
+//! `{"version":1,"days":[{"day":"2024-03-01","counts":{"big_mode":2}}]}`.
+
+use crate::date::{format_day, parse_day};
+use crate::keymap::Action;
+use crate::storage::Storage;
+use nanoserde::DeJson;
+use std::collections::{BTreeMap, HashMap};
+
+/// Storage key of the usage counts.
+pub const USAGE_KEY: &str = "usage.json";
+/// Newest version of `USAGE_KEY` this release reads and writes.
+pub const USAGE_VERSION: u32 = 1;
+
+/// A feature whose uses are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    BigMode,
+    MiniWindow,
+    SubAnagrams,
+    Phonetic,
+    RevealLock,
+    Hint,
+    Complete,
+    Paste,
+    KeyboardTap,
+    Palette,
+    Help,
+    Tutorial,
+    Settings,
+    Stats,
+    Ranking,
+    Inspect,
+    SpellFinal,
+    Pin,
+    NewTab,
+    CopyAnswer,
+    ConfirmAnswer,
+    PrintResults,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 22] = [
+        Feature::BigMode,
+        Feature::MiniWindow,
+        Feature::SubAnagrams,
+        Feature::Phonetic,
+        Feature::RevealLock,
+        Feature::Hint,
+        Feature::Complete,
+        Feature::Paste,
+        Feature::KeyboardTap,
+        Feature::Palette,
+        Feature::Help,
+        Feature::Tutorial,
+        Feature::Settings,
+        Feature::Stats,
+        Feature::Ranking,
+        Feature::Inspect,
+        Feature::SpellFinal,
+        Feature::Pin,
+        Feature::NewTab,
+        Feature::CopyAnswer,
+        Feature::ConfirmAnswer,
+        Feature::PrintResults,
+    ];
+
+    /// Returns the feature's name in `USAGE_KEY` and the summary.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::BigMode => "big_mode",
+            Feature::MiniWindow => "mini_window",
+            Feature::SubAnagrams => "sub_anagrams",
+            Feature::Phonetic => "phonetic",
+            Feature::RevealLock => "reveal_lock",
+            Feature::Hint => "hint",
+            Feature::Complete => "complete",
+            Feature::Paste => "paste",
+            Feature::KeyboardTap => "keyboard_tap",
+            Feature::Palette => "palette",
+            Feature::Help => "help",
+            Feature::Tutorial => "tutorial",
+            Feature::Settings => "settings",
+            Feature::Stats => "stats",
+            Feature::Ranking => "ranking",
+            Feature::Inspect => "inspect",
+            Feature::SpellFinal => "spell_final",
+            Feature::Pin => "pin",
+            Feature::NewTab => "new_tab",
+            Feature::CopyAnswer => "copy_answer",
+            Feature::ConfirmAnswer => "confirm_answer",
+            Feature::PrintResults => "print_results",
+        }
+    }
+    /// Returns the feature with name `name`, if any.
+    pub fn from_name(name: &str) -> Option<Feature> {
+        Feature::ALL.into_iter().find(|f| f.name() == name)
+    }
+    /// Returns the feature `action` uses, if it's one counted; the action's
+    /// keys, the palette, and the gestures standing in for them all count.
+    pub fn for_action(action: Action) -> Option<Feature> {
+        match action {
+            Action::ToggleBigMode => Some(Feature::BigMode),
+            Action::ToggleMini => Some(Feature::MiniWindow),
+            Action::ToggleSubAnagrams => Some(Feature::SubAnagrams),
+            Action::TogglePhonetic => Some(Feature::Phonetic),
+            Action::ToggleRevealLock => Some(Feature::RevealLock),
+            Action::ShowHint => Some(Feature::Hint),
+            Action::Complete => Some(Feature::Complete),
+            Action::CommandPalette => Some(Feature::Palette),
+            Action::ShowHelp => Some(Feature::Help),
+            Action::OpenSettings => Some(Feature::Settings),
+            Action::CycleStats => Some(Feature::Stats),
+            Action::ExplainRanking => Some(Feature::Ranking),
+            Action::InspectWord => Some(Feature::Inspect),
+            Action::SpellFinal => Some(Feature::SpellFinal),
+            Action::TogglePin => Some(Feature::Pin),
+            Action::NewTab => Some(Feature::NewTab),
+            Action::CopyAnswer => Some(Feature::CopyAnswer),
+            Action::ConfirmAnswer => Some(Feature::ConfirmAnswer),
+            Action::PrintResults => Some(Feature::PrintResults),
+            _ => None,
+        }
+    }
+}
+
+/// Each feature's count, in the order of `Feature::ALL`.
+type Counts = [u64; Feature::ALL.len()];
+
+/// How many times each feature was used, by day.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UsageLog {
+    /// Days (as `date` counts them) with any uses, and their counts.
+    days: BTreeMap<i64, Counts>,
+}
+
+/// `USAGE_KEY` as read.
+#[derive(DeJson)]
+struct UsageFile {
+    version: u32,
+    days: Vec<UsageDay>,
+}
+
+/// One day of `USAGE_KEY`.
+#[derive(DeJson)]
+struct UsageDay {
+    day: String,
+    counts: HashMap<String, u64>,
+}
+
+impl UsageLog {
+    /// Parses the contents of `USAGE_KEY`; empty text has no counts. Features
+    /// this release doesn't know are left out.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let file = UsageFile::deserialize_json(text).map_err(|e| e.to_string())?;
+        if file.version > USAGE_VERSION {
+            return Err(format!(
+                "written by a newer Jumble Helper (v{}; this one reads up to v{USAGE_VERSION}); \
+                 update the app to use it",
+                file.version
+            ));
+        }
+        let mut usage = Self::default();
+        for UsageDay { day, counts } in file.days {
+            let day = parse_day(&day).ok_or_else(|| format!("'{day}' isn't a date"))?;
+            for (name, count) in counts {
+                match Feature::from_name(&name) {
+                    Some(feature) => usage.add(day, feature, count),
+                    None => log::debug!("[UsageLog::parse] leaving out unknown feature {name}"),
+                }
+            }
+        }
+        Ok(usage)
+    }
+    /// Returns the counts as the contents of `USAGE_KEY`, days in order and
+    /// features in the order of `Feature::ALL`, leaving out those never used.
+    pub fn to_json(&self) -> String {
+        let days: Vec<String> = self
+            .days
+            .iter()
+            .map(|(&day, counts)| {
+                let counts: Vec<String> = Feature::ALL
+                    .into_iter()
+                    .zip(counts)
+                    .filter(|&(_, &count)| count > 0)
+                    .map(|(feature, count)| format!("\"{}\":{count}", feature.name()))
+                    .collect();
+                format!(
+                    "{{\"day\":\"{}\",\"counts\":{{{}}}}}",
+                    format_day(day),
+                    counts.join(",")
+                )
+            })
+            .collect();
+        format!(
+            "{{\"version\":{USAGE_VERSION},\"days\":[\n{}\n]}}\n",
+            days.join(",\n")
+        )
+    }
+    /// Counts a use of `feature` on `day`.
+    pub fn count(&mut self, day: i64, feature: Feature) {
+        self.add(day, feature, 1);
+    }
+    fn add(&mut self, day: i64, feature: Feature, count: u64) {
+        let counts = self.days.entry(day).or_insert([0; Feature::ALL.len()]);
+        let i = feature as usize;
+        counts[i] = counts[i].saturating_add(count);
+    }
+    /// Adds the counts of `other` to these.
+    pub fn merge(&mut self, other: &UsageLog) {
+        for (&day, counts) in &other.days {
+            for (feature, &count) in Feature::ALL.into_iter().zip(counts) {
+                self.add(day, feature, count);
+            }
+        }
+    }
+    /// Returns whether nothing's been counted.
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+    /// Returns how many times `feature` was used on `day`.
+    pub fn get(&self, day: i64, feature: Feature) -> u64 {
+        self.days
+            .get(&day)
+            .map_or(0, |counts| counts[feature as usize])
+    }
+    /// Returns a table of each feature's uses, the days it was used on, and the
+    /// last of them, most used first, with the never used at the end.
+    pub fn summary(&self) -> String {
+        let (Some(&first), Some(&last)) = (self.days.keys().next(), self.days.keys().next_back())
+        else {
+            return "no uses counted yet\n".into();
+        };
+        let mut rows: Vec<(Feature, u64, usize, Option<i64>)> = Feature::ALL
+            .into_iter()
+            .map(|feature| {
+                let used = || self.days.iter().filter(|(_, c)| c[feature as usize] > 0);
+                let total = used().map(|(_, c)| c[feature as usize]).sum();
+                (
+                    feature,
+                    total,
+                    used().count(),
+                    used().next_back().map(|(&day, _)| day),
+                )
+            })
+            .collect();
+        rows.sort_by_key(|row| std::cmp::Reverse(row.1));
+        let mut text = format!(
+            "uses counted on {} day(s), {} to {}\n",
+            self.days.len(),
+            format_day(first),
+            format_day(last)
+        );
+        text += &format!(
+            "{:<16} {:>7} {:>5}  {}\n",
+            "feature", "uses", "days", "last used"
+        );
+        for (feature, total, days, last) in rows {
+            let last = last.map_or_else(|| "-".to_owned(), format_day);
+            text += &format!("{:<16} {total:>7} {days:>5}  {last}\n", feature.name());
+        }
+        text
+    }
+}
+
+/// Adds `usage` to the counts in `USAGE_KEY`, or returns why it couldn't; what
+/// it holds is left alone if it can't be read.
+pub fn save_usage(storage: &dyn Storage, usage: &UsageLog) -> Result<(), String> {
+    let text = storage.read(USAGE_KEY).map_err(|e| e.to_string())?;
+    let mut stored = UsageLog::parse(&text.unwrap_or_default())?;
+    stored.merge(usage);
+    storage
+        .write(USAGE_KEY, &stored.to_json())
+        .map_err(|e| e.to_string())
+}
+
+/// Prints the summary of the counts in `USAGE_KEY`, returning the exit code: 0,
+/// or 2 if they can't be read.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_metrics() -> i32 {
+    let storage = crate::storage::default_storage();
+    let text = match storage.read(USAGE_KEY) {
+        Ok(text) => text.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("[run_metrics] can't read {USAGE_KEY}: {e}");
+            return 2;
+        }
+    };
+    match UsageLog::parse(&text) {
+        Ok(log) => {
+            print!("{}", log.summary());
+            0
+        }
+        Err(e) => {
+            eprintln!("[run_metrics] can't read {USAGE_KEY}: {e}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn day(text: &str) -> i64 {
+        parse_day(text).unwrap()
+    }
+
+    #[test]
+    fn uses_are_counted_by_day() {
+        let mut usage = UsageLog::default();
+        assert!(usage.is_empty());
+        usage.count(day("2024-03-01"), Feature::BigMode);
+        usage.count(day("2024-03-01"), Feature::BigMode);
+        usage.count(day("2024-03-01"), Feature::Paste);
+        usage.count(day("2024-03-02"), Feature::BigMode);
+        assert_eq!(usage.get(day("2024-03-01"), Feature::BigMode), 2);
+        assert_eq!(usage.get(day("2024-03-02"), Feature::BigMode), 1);
+        assert_eq!(usage.get(day("2024-03-02"), Feature::Paste), 0);
+        assert_eq!(usage.get(day("2024-03-03"), Feature::BigMode), 0);
+        assert_eq!(
+            usage.to_json(),
+            "{\"version\":1,\"days\":[\n\
+             {\"day\":\"2024-03-01\",\"counts\":{\"big_mode\":2,\"paste\":1}},\n\
+             {\"day\":\"2024-03-02\",\"counts\":{\"big_mode\":1}}\n]}\n"
+        );
+    }
+
+    #[test]
+    fn the_file_reads_back_as_written() {
+        let mut usage = UsageLog::default();
+        for feature in Feature::ALL {
+            usage.count(day("2024-02-29"), feature);
+        }
+        usage.count(day("2024-12-31"), Feature::Tutorial);
+        assert_eq!(UsageLog::parse(&usage.to_json()), Ok(usage));
+        assert_eq!(UsageLog::parse(""), Ok(UsageLog::default()));
+    }
+
+    #[test]
+    fn unknown_features_are_left_out_and_newer_files_refused() {
+        let text =
+            r#"{"version":1,"days":[{"day":"2024-03-01","counts":{"hint":3,"teleport":9}}]}"#;
+        let usage = UsageLog::parse(text).unwrap();
+        assert_eq!(usage.get(day("2024-03-01"), Feature::Hint), 3);
+        let newer = r#"{"version":2,"days":[]}"#;
+        assert!(UsageLog::parse(newer).unwrap_err().contains("newer"));
+        let bad_day = r#"{"version":1,"days":[{"day":"March 1","counts":{}}]}"#;
+        assert_eq!(
+            UsageLog::parse(bad_day),
+            Err("'March 1' isn't a date".into())
+        );
+        assert!(UsageLog::parse("{").is_err());
+    }
+
+    #[test]
+    fn saves_add_to_the_stored_counts() {
+        let storage = MemoryStorage::default();
+        // One session, then another after a restart, on the same day and the next
+        let mut first = UsageLog::default();
+        first.count(day("2024-03-01"), Feature::Hint);
+        first.count(day("2024-03-01"), Feature::Pin);
+        save_usage(&storage, &first).unwrap();
+        let mut second = UsageLog::default();
+        second.count(day("2024-03-01"), Feature::Hint);
+        second.count(day("2024-03-02"), Feature::Help);
+        save_usage(&storage, &second).unwrap();
+
+        let text = storage.read(USAGE_KEY).unwrap().unwrap();
+        let stored = UsageLog::parse(&text).unwrap();
+        assert_eq!(stored.get(day("2024-03-01"), Feature::Hint), 2);
+        assert_eq!(stored.get(day("2024-03-01"), Feature::Pin), 1);
+        assert_eq!(stored.get(day("2024-03-02"), Feature::Help), 1);
+    }
+
+    #[test]
+    fn an_unreadable_file_is_left_alone() {
+        let storage = MemoryStorage::default();
+        storage.write(USAGE_KEY, "not json").unwrap();
+        let mut usage = UsageLog::default();
+        usage.count(day("2024-03-01"), Feature::Hint);
+        assert!(save_usage(&storage, &usage).is_err());
+        assert_eq!(storage.read(USAGE_KEY).unwrap().unwrap(), "not json");
+    }
+
+    #[test]
+    fn the_summary_puts_the_most_used_first() {
+        assert_eq!(UsageLog::default().summary(), "no uses counted yet\n");
+        let mut usage = UsageLog::default();
+        usage.count(day("2024-03-01"), Feature::Paste);
+        usage.count(day("2024-03-03"), Feature::Hint);
+        usage.count(day("2024-03-03"), Feature::Hint);
+        usage.count(day("2024-03-01"), Feature::Hint);
+        let summary = usage.summary();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(
+            lines[0],
+            "uses counted on 2 day(s), 2024-03-01 to 2024-03-03"
+        );
+        assert_eq!(lines[2], "hint                   3     2  2024-03-03");
+        assert_eq!(lines[3], "paste                  1     1  2024-03-01");
+        assert_eq!(lines[4], "big_mode               0     0  -");
+        assert_eq!(lines.len(), 2 + Feature::ALL.len());
+    }
+
+    #[test]
+    fn feature_names_are_unique_and_read_back() {
+        for (i, feature) in Feature::ALL.into_iter().enumerate() {
+            assert_eq!(feature as usize, i);
+            assert_eq!(Feature::from_name(feature.name()), Some(feature));
+        }
+        assert_eq!(Feature::for_action(Action::ShowHint), Some(Feature::Hint));
+        assert_eq!(Feature::for_action(Action::DeleteBack), None);
+    }
+}
@@ -0,0 +1,341 @@
+//! The `verify` command: checks the solver against an archive of published
+//! answers.
+//!
+//! Each row of the archive is `DATE,SCRAMBLE,ANSWER`, with the date as
+//! `YYYY-MM-DD`; a first row whose date doesn't parse is taken as a header. Each
+//! scramble is looked up as the app would, and the report says whether the
+//! official answer was among its matches, whether it was the only one, and
+//! whether it ranked first (see `rank`, with nothing pinned or confirmed). A
+//! table of those shares by year follows, so an archive doubles as a check on
+//! word list and ranking changes: `--fail-under` makes the command fail when
+//! the share ranked first drops below it.
+
+use crate::cli::VerifyArgs;
+use crate::date::{civil_from_days, format_day, parse_day};
+use crate::dictionary::{load_words, prepare_words, DictionaryOptions};
+use crate::rank::{rank, RankWeights};
+use crate::strings::Language;
+use crate::wordmap::{make_word_map_with_progress, WordMap};
+use crate::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use std::collections::BTreeMap;
+
+/// A published puzzle and its official answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    /// Days since 1970-01-01.
+    pub day: i64,
+    pub scramble: String,
+    pub answer: String,
+}
+
+/// The puzzles of an archive, in its order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Archive {
+    pub puzzles: Vec<Puzzle>,
+    /// The 1-based lines that weren't puzzles, header aside.
+    pub skipped: Vec<usize>,
+}
+
+impl Archive {
+    /// Parses an archive, skipping blank lines and noting rows it can't read.
+    pub fn parse(text: &str) -> Self {
+        let mut archive = Self::default();
+        let rows = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        for (n, (i, line)) in rows.enumerate() {
+            let puzzle = match fields(line)[..] {
+                [day, scramble, answer] => parse_day(day)
+                    .filter(|_| !scramble.is_empty() && !answer.is_empty())
+                    .map(|day| Puzzle {
+                        day,
+                        scramble: scramble.to_uppercase(),
+                        answer: answer.to_uppercase(),
+                    }),
+                _ => None,
+            };
+            match puzzle {
+                Some(puzzle) => archive.puzzles.push(puzzle),
+                // Column names, most likely
+                None if n == 0 => (),
+                None => archive.skipped.push(i + 1),
+            }
+        }
+        archive
+    }
+}
+
+/// Returns the comma-separated fields of `line`, trimmed and unquoted.
+fn fields(line: &str) -> Vec<&str> {
+    line.split(',')
+        .map(|field| field.trim().trim_matches('"').trim())
+        .collect()
+}
+
+/// How the solver did on a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Verdict {
+    /// How many words matched the scramble.
+    pub matches: usize,
+    /// Where the official answer ranked among them, from 1, if it was one.
+    pub rank: Option<usize>,
+}
+
+impl Verdict {
+    /// Looks up `puzzle` in `map`, ranking its matches with `weights`.
+    pub fn of(puzzle: &Puzzle, map: &WordMap, weights: &RankWeights) -> Self {
+        let alphabet = map.alphabet();
+        let scramble = alphabet.fold_word(&puzzle.scramble);
+        let answer = alphabet.fold_word(&puzzle.answer);
+        let mut key_buf = Vec::new();
+        let matches = map
+            .find_match(&scramble, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf)
+            .unwrap_or_default();
+        let ranked = rank(matches, map, |_| false, |_| false, weights);
+        Self {
+            matches: matches.len(),
+            rank: ranked
+                .iter()
+                .position(|r| matches[r.index] == answer)
+                .map(|place| place + 1),
+        }
+    }
+    /// Returns the verdict as a line such as `found, 2 matches, ranked 1`.
+    pub fn describe(&self) -> String {
+        match self.rank {
+            None => format!("missing ({} matches)", self.matches),
+            Some(_) if self.matches == 1 => "found, unique".to_owned(),
+            Some(place) => format!("found, {} matches, ranked {place}", self.matches),
+        }
+    }
+}
+
+/// Counts of puzzles by how the solver did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub puzzles: usize,
+    /// Puzzles whose answer was among the matches.
+    pub found: usize,
+    /// Puzzles whose answer was the only match.
+    pub unique: usize,
+    /// Puzzles whose answer ranked first.
+    pub first: usize,
+}
+
+impl Tally {
+    /// Counts `verdict`.
+    pub fn record(&mut self, verdict: Verdict) {
+        self.puzzles += 1;
+        if verdict.rank.is_some() {
+            self.found += 1;
+        }
+        if verdict.rank.is_some() && verdict.matches == 1 {
+            self.unique += 1;
+        }
+        if verdict.rank == Some(1) {
+            self.first += 1;
+        }
+    }
+    /// Returns `count` as a share of the puzzles, from 0 to 1, or `None` if
+    /// there were none.
+    pub fn share(&self, count: usize) -> Option<f64> {
+        (self.puzzles > 0).then(|| count as f64 / self.puzzles as f64)
+    }
+}
+
+/// Returns the tallies for each year, in order, and for every puzzle.
+pub fn tally_by_year(verdicts: &[(i64, Verdict)]) -> (BTreeMap<i64, Tally>, Tally) {
+    let mut years: BTreeMap<i64, Tally> = BTreeMap::new();
+    let mut all = Tally::default();
+    for &(day, verdict) in verdicts {
+        let (year, _, _) = civil_from_days(day);
+        years.entry(year).or_default().record(verdict);
+        all.record(verdict);
+    }
+    (years, all)
+}
+
+/// Returns the table of shares by year, with a last row for every puzzle.
+fn describe_tallies(years: &BTreeMap<i64, Tally>, all: &Tally) -> String {
+    let percent = |tally: &Tally, count: usize| match tally.share(count) {
+        Some(share) => format!("{:.1}%", share * 100.0),
+        None => "-".to_owned(),
+    };
+    let row = |label: &str, tally: &Tally| {
+        format!(
+            "{label:<6}{:>8}{:>8}{:>8}{:>8}\n",
+            tally.puzzles,
+            percent(tally, tally.found),
+            percent(tally, tally.unique),
+            percent(tally, tally.first)
+        )
+    };
+    let headings = ["puzzles", "found", "unique", "first"];
+    let mut text = format!("{:<6}", "year");
+    text.extend(headings.map(|heading| format!("{heading:>8}")));
+    text.push('\n');
+    for (year, tally) in years {
+        text.push_str(&row(&year.to_string(), tally));
+    }
+    text.push_str(&row("all", all));
+    text
+}
+
+/// Verifies the configured word list against the archive named in `args`,
+/// printing a line per puzzle and the table of shares, and returns the exit
+/// code: 0 if done (and ranked first at least as often as `--fail-under` asks),
+/// 1 if not ranked first often enough, 2 if it couldn't run.
+pub fn run_verify(
+    args: &VerifyArgs,
+    options: &DictionaryOptions,
+    lang: Language,
+    weights: &RankWeights,
+) -> i32 {
+    let archive = match std::fs::read_to_string(&args.archive) {
+        Ok(text) => Archive::parse(&text),
+        Err(e) => {
+            eprintln!("[run_verify] can't read '{}': {e}", args.archive);
+            return 2;
+        }
+    };
+    let words = load_words(options, lang);
+    let (header, words) = match prepare_words(&words) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("[run_verify] can't read the dictionary: {e}");
+            return 2;
+        }
+    };
+    let alphabet = options.alphabet_for(&header);
+    let map = make_word_map_with_progress(&words, alphabet, options.max_words, usize::MAX, |_| ());
+
+    let mut verdicts = Vec::with_capacity(archive.puzzles.len());
+    for puzzle in &archive.puzzles {
+        let verdict = Verdict::of(puzzle, &map, weights);
+        println!(
+            "{}  {:<10} {:<10} {}",
+            format_day(puzzle.day),
+            puzzle.scramble,
+            puzzle.answer,
+            verdict.describe()
+        );
+        verdicts.push((puzzle.day, verdict));
+    }
+    if !archive.skipped.is_empty() {
+        let lines = archive
+            .skipped
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>();
+        println!("skipped unreadable lines: {}", lines.join(", "));
+    }
+    let (years, all) = tally_by_year(&verdicts);
+    print!("\n{}", describe_tallies(&years, &all));
+
+    let first = all.share(all.first).unwrap_or(0.0);
+    match args.fail_under {
+        Some(threshold) if first < threshold => {
+            println!(
+                "ranked first {:.1}% of the time, under {:.1}%",
+                first * 100.0,
+                threshold * 100.0
+            );
+            1
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+
+    fn verdict(matches: usize, rank: Option<usize>) -> Verdict {
+        Verdict { matches, rank }
+    }
+
+    #[test]
+    fn a_first_row_that_isnt_a_puzzle_is_a_header() {
+        let archive = Archive::parse("date,scramble,answer\n2024-03-01,nupo,upon\n");
+        assert_eq!(archive.skipped, Vec::<usize>::new());
+        assert_eq!(
+            archive.puzzles,
+            [Puzzle {
+                day: parse_day("2024-03-01").unwrap(),
+                scramble: "NUPO".into(),
+                answer: "UPON".into(),
+            }]
+        );
+        // Without a header the first row is read like the rest
+        let archive = Archive::parse("2024-03-01, \"TAGER\" ,GREAT\n");
+        assert_eq!(archive.puzzles[0].scramble, "TAGER");
+    }
+
+    #[test]
+    fn unreadable_rows_after_the_first_are_noted() {
+        let text = "2024-03-01,NUPO,UPON\n\nMarch 2,TAGER,GREAT\n2024-03-03,DNUOP\n\
+                    2024-03-04,,POUND\n2024-03-05,DNUOP,POUND\n";
+        let archive = Archive::parse(text);
+        assert_eq!(archive.puzzles.len(), 2);
+        assert_eq!(archive.skipped, [3, 4, 5]);
+    }
+
+    #[test]
+    fn verdicts_say_where_the_answer_ranked() {
+        let map = make_word_map_with_progress(
+            "GREAT\nGRATE\nUPON\n",
+            Alphabet::English,
+            usize::MAX,
+            usize::MAX,
+            |_| (),
+        );
+        let weights = RankWeights::default();
+        let of = |scramble: &str, answer: &str| {
+            let puzzle = Puzzle {
+                day: 0,
+                scramble: scramble.into(),
+                answer: answer.into(),
+            };
+            Verdict::of(&puzzle, &map, &weights)
+        };
+        assert_eq!(of("NUPO", "UPON"), verdict(1, Some(1)));
+        assert_eq!(of("TAGER", "GRATE"), verdict(2, Some(2)));
+        assert_eq!(of("TAGER", "TERGA"), verdict(2, None));
+        assert_eq!(of("ZZZZ", "ZZZZ"), verdict(0, None));
+        assert_eq!(verdict(1, Some(1)).describe(), "found, unique");
+        assert_eq!(verdict(2, Some(2)).describe(), "found, 2 matches, ranked 2");
+        assert_eq!(verdict(2, None).describe(), "missing (2 matches)");
+    }
+
+    #[test]
+    fn puzzles_are_tallied_by_year_and_overall() {
+        let day = |text| parse_day(text).unwrap();
+        let verdicts = [
+            (day("2023-12-31"), verdict(1, Some(1))),
+            (day("2024-01-01"), verdict(3, Some(1))),
+            (day("2024-06-30"), verdict(3, Some(2))),
+            (day("2024-12-31"), verdict(0, None)),
+        ];
+        let (years, all) = tally_by_year(&verdicts);
+        let tally = |puzzles, found, unique, first| Tally {
+            puzzles,
+            found,
+            unique,
+            first,
+        };
+        assert_eq!(years.keys().copied().collect::<Vec<_>>(), [2023, 2024]);
+        assert_eq!(years[&2023], tally(1, 1, 1, 1));
+        assert_eq!(years[&2024], tally(3, 2, 0, 1));
+        assert_eq!(all, tally(4, 3, 1, 2));
+        assert_eq!(
+            describe_tallies(&years, &all),
+            "year   puzzles   found  unique   first\n\
+             2023         1  100.0%  100.0%  100.0%\n\
+             2024         3   66.7%    0.0%   33.3%\n\
+             all          4   75.0%   25.0%   50.0%\n"
+        );
+        assert_eq!(Tally::default().share(0), None);
+    }
+}
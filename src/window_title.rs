@@ -0,0 +1,104 @@
+//! Keeping the window title up with the entry, as in `Jumble Helper — NUDOP →
+//! POUND`, or `Jumble Helper — 2/4 solved` with several tabs open.
+//!
+//! `title_for` works the title out from the screen shown, and `TitleThrottle`
+//! passes it on only when it's changed, and at most once a second, since some
+//! window managers redraw the whole frame for a new title. A held back answer
+//! (see `assist`) stays out of the title too, as do locked ones (see `reveal`).
+//!
+//! Changing the title once the window is open is up to the platform layer:
+//! `SET_TITLE` is the call that does it, or `None` where there's none. miniquad
+//! 0.4 has no such call on any platform (it sets the title only as the window
+//! opens), so `SET_TITLE` is `None` and the title stays the app's name; the
+//! main loop skips the work entirely until a miniquad with a setter is in use.
+
+use crate::config::Config;
+use crate::screen::Screen;
+use crate::strings::{fill, StringKey};
+
+/// Seconds between changes of the title, at the least.
+pub const TITLE_INTERVAL: f64 = 1.0;
+/// Changes the open window's title, where the platform can.
+pub const SET_TITLE: Option<fn(&str)> = None;
+
+/// Returns the title for the window showing `screen`: the app's name, then
+/// the entry and its answer, or how many tabs are solved when there are
+/// several.
+pub fn title_for(screen: &Screen, config: &Config) -> String {
+    let lang = config.language;
+    let name = lang.text(StringKey::AppTitle);
+    let Screen::Entry(entry) = screen else {
+        return name.to_owned();
+    };
+    let (tabs, active) = entry.tabs();
+    let answer = |i: usize| {
+        let tab = &tabs[i];
+        let held_back = config.assist && !tab.assist.is_revealed();
+        tab.answer().filter(|_| !held_back)
+    };
+    if tabs.len() > 1 {
+        let solved = (0..tabs.len()).filter(|&i| answer(i).is_some()).count();
+        let count = format!("{solved}/{}", tabs.len());
+        return format!(
+            "{name} — {}",
+            fill(lang.text(StringKey::TitleSolved), count)
+        );
+    }
+    match (
+        tabs[active].entry.as_str(),
+        entry.policy().word(answer(active)),
+    ) {
+        ("", _) => name.to_owned(),
+        (letters, Some(answer)) => format!("{name} — {letters} → {answer}"),
+        (letters, None) => format!("{name} — {letters}"),
+    }
+}
+
+/// Passes a new title on when it's changed, at most once per `TITLE_INTERVAL`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TitleThrottle {
+    /// The title last passed on.
+    last: String,
+    /// When the next may be.
+    next_at: f64,
+}
+
+impl TitleThrottle {
+    /// Returns the title `make` gives, if it's time at `now` for a new one and
+    /// it's changed; `make` is only called when it's time.
+    pub fn offer(&mut self, now: f64, make: impl FnOnce() -> String) -> Option<String> {
+        if now < self.next_at {
+            return None;
+        }
+        let title = make();
+        if title == self.last {
+            return None;
+        }
+        self.last.clone_from(&title);
+        self.next_at = now + TITLE_INTERVAL;
+        Some(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_title_is_passed_on_only_when_changed_and_not_too_often() {
+        let mut throttle = TitleThrottle::default();
+        assert_eq!(throttle.offer(0.0, || "A".into()), Some("A".into()));
+        // Too soon: not even worked out
+        assert_eq!(throttle.offer(0.5, || panic!("worked out too soon")), None);
+        assert_eq!(throttle.offer(TITLE_INTERVAL - 0.01, || "B".into()), None);
+        assert_eq!(
+            throttle.offer(TITLE_INTERVAL, || "B".into()),
+            Some("B".into())
+        );
+        // Unchanged, so nothing, and the next change needn't wait
+        assert_eq!(throttle.offer(5.0, || "B".into()), None);
+        assert_eq!(throttle.offer(5.1, || "C".into()), Some("C".into()));
+        assert_eq!(throttle.offer(5.2, || "A".into()), None);
+        assert_eq!(throttle.offer(6.1, || "A".into()), Some("A".into()));
+    }
+}
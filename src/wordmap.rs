@@ -1,100 +1,453 @@
 //! Jumble Helper for Mom (FEB 2024)
 
+use crate::alphabet::{Alphabet, MAX_LETTERS};
+use crate::complete::{Completions, KeyTrie, TrieCursor};
+use crate::dictionary::DictionaryHeader;
+use crate::parse::Pattern;
 use itertools::Itertools;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io;
-use std::io::prelude::*;
 
-/// Stores an entire dictionary in `WordMap`s sorted by word length.
-struct WordMaps {
-    maps: Vec<WordMap>,
-}
-
-impl WordMaps {
-    /// Creates a new `WordMaps` instance.
-    fn new(max_word_len: usize) -> Self {
-        let mut maps = Vec::new();
-        for _ in 0..(max_word_len + 1) {
-            let wm = WordMap::new();
-            maps.push(wm);
-        }
-        Self { maps }
-    }
-}
+/// Most phrases `WordMap::phrases` lists.
+pub const MAX_PHRASES: usize = 1000;
+/// Characters joining the words of a phrase listed on one line, as in `HOT DOG`
+/// or `IN-LAW`.
+pub const PHRASE_JOINERS: [char; 2] = [' ', '-'];
 
 /// Stores all words in {ordered_word, [actual_words]} format.
 ///
 /// Each ordered word represents the corresponding actual word(s) whose characters
-/// have been sorted in alphabetical order.
+/// have been sorted in the order of the map's alphabet.
 pub struct WordMap {
     inner: HashMap<String, Vec<String>>,
+    /// The keys of `inner`, for finding those that use some letters.
+    trie: KeyTrie,
+    /// The letters the words are spelled with, which the keys are sorted by.
+    alphabet: Alphabet,
+    /// What the word list said about itself.
+    header: DictionaryHeader,
+    words: usize,
+    /// How often each letter `A`-`Z` appears across the keys.
+    letters: [usize; 26],
+    /// How common each word is, from the word list's frequency column.
+    frequencies: HashMap<String, u64>,
+    /// Letters in the shortest and longest words, once there are any.
+    lengths: Option<(usize, usize)>,
+    /// Words skipped as already filed under their key.
+    merged: usize,
+    /// Phrases listed in the word list, by the sorted key of their letters;
+    /// only `phrases` looks at them.
+    listed_phrases: HashMap<String, Vec<String>>,
+}
+
+/// Counts describing a `WordMap`, for the stats overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMapStats {
+    /// The word list's name and language, if its header gives a name.
+    pub title: Option<String>,
+    pub words: usize,
+    /// Distinct sorted keys, i.e. groups of anagrams.
+    pub groups: usize,
+    /// How often each letter `A`-`Z` appears across all the words.
+    pub letters: [usize; 26],
+    /// Words listed more than once, counted once each extra time.
+    pub merged: usize,
+    /// Phrases listed, which aren't counted among the words.
+    pub phrases: usize,
 }
 
 impl WordMap {
-    /// Creates a new `WordMap` instance.
-    pub fn new() -> Self {
+    /// Creates a new `WordMap` instance for words spelled in `alphabet`.
+    pub fn new(alphabet: Alphabet) -> Self {
         Self {
             inner: Default::default(),
+            trie: KeyTrie::new(alphabet),
+            alphabet,
+            header: DictionaryHeader::default(),
+            words: 0,
+            letters: [0; 26],
+            frequencies: HashMap::new(),
+            lengths: None,
+            merged: 0,
+            listed_phrases: HashMap::new(),
         }
     }
     /// Adds a sorted key and its unsorted (actual) value to the word map.
     ///
     /// If the word, when sorted, is *not* in the map, a new entry is created. If
-    /// it *is* in the map, the unsorted (actual) word is added to the existing entry.
-    pub fn insert(&mut self, sorted: String, unsorted: String) {
-        // println!("[Wordmap.insert] inserting {}, {}", unsorted, sorted);
+    /// it *is* in the map, the unsorted (actual) word is added to the existing entry,
+    /// unless it's already there: then it's counted as merged and `false` returned.
+    pub fn insert(&mut self, sorted: String, unsorted: String) -> bool {
+        if self
+            .get(&sorted)
+            .is_some_and(|words| words.contains(&unsorted))
+        {
+            self.merged += 1;
+            return false;
+        }
+        add_letters(&mut self.letters, &sorted);
+        let len = sorted.chars().count();
+        self.lengths = Some(match self.lengths {
+            Some((shortest, longest)) => (shortest.min(len), longest.max(len)),
+            None => (len, len),
+        });
+        self.words += 1;
+        if !self.inner.contains_key(&sorted) {
+            self.trie.insert(&sorted);
+        }
         self.inner.entry(sorted).or_default().push(unsorted);
+        true
+    }
+    /// Adds a phrase listed in the word list under the sorted key of its
+    /// letters, returning `false` if it's already there. Phrases aren't words:
+    /// lookups of the entry never find them, only `phrases` does.
+    pub fn insert_phrase(&mut self, sorted: String, phrase: String) -> bool {
+        let phrases = self.listed_phrases.entry(sorted).or_default();
+        if phrases.contains(&phrase) {
+            self.merged += 1;
+            return false;
+        }
+        phrases.push(phrase);
+        true
+    }
+    /// Returns the word, group, and letter counts, kept up as words are inserted.
+    pub fn stats(&self) -> WordMapStats {
+        WordMapStats {
+            title: self.header.title(),
+            words: self.words,
+            groups: self.inner.len(),
+            letters: self.letters,
+            merged: self.merged,
+            phrases: self.listed_phrases.values().map(Vec::len).sum(),
+        }
+    }
+    /// Returns the letters the words are spelled with.
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+    /// Returns the letters in the shortest and longest words, or `None` if there
+    /// are no words.
+    pub fn word_length_bounds(&self) -> Option<(usize, usize)> {
+        self.lengths
+    }
+    /// Returns what the word list said about itself.
+    pub fn header(&self) -> &DictionaryHeader {
+        &self.header
+    }
+    /// Records what the word list said about itself.
+    pub fn set_header(&mut self, header: DictionaryHeader) {
+        self.header = header;
+    }
+    /// Records how common each word is.
+    pub fn set_frequencies(&mut self, frequencies: HashMap<String, u64>) {
+        self.frequencies = frequencies;
+    }
+    /// Returns how common `word` is, if the word list said.
+    pub fn frequency(&self, word: &str) -> Option<u64> {
+        self.frequencies.get(word).copied()
+    }
+    /// Returns whether `word` is in the map, as listed.
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(&self.alphabet.key(word))
+            .is_some_and(|words| words.iter().any(|w| w == word))
+    }
+    /// Returns whether the map holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+    /// Returns the words, if any, filed under the sorted `key`.
+    pub fn get(&self, key: &str) -> Option<&[String]> {
+        self.inner.get(key).map(Vec::as_slice)
+    }
+    /// Returns every sorted key with its words, in no particular order.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn groups(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.inner
+            .iter()
+            .map(|(key, words)| (key.as_str(), words.as_slice()))
     }
     /// Returns the words, if any, that match the given unsorted query.
-    pub fn find_match(&self, q: &str, minlen: usize, maxlen: usize) -> Option<&Vec<String>> {
-        if q.len() < minlen || q.len() > maxlen {
+    ///
+    /// `key_buf` holds the sorted query; reusing it across calls means ASCII queries
+    /// allocate nothing once the buffer has grown to the longest query. (Every
+    /// alphabet keeps `A`-`Z` in order, so sorting their bytes gives the key.)
+    pub fn find_match(
+        &self,
+        q: &str,
+        minlen: usize,
+        maxlen: usize,
+        key_buf: &mut Vec<u8>,
+    ) -> Option<&[String]> {
+        let len = q.chars().count();
+        if len < minlen || len > maxlen {
             return None;
         }
-        let sorted_q = q.chars().sorted().collect::<String>();
-        self.inner.get(&sorted_q)
+        if !q.is_ascii() {
+            return self.inner.get(&self.alphabet.key(q)).map(Vec::as_slice);
+        }
+        key_buf.clear();
+        key_buf.extend_from_slice(q.as_bytes());
+        key_buf.sort_unstable();
+        let key = std::str::from_utf8(key_buf).ok()?;
+        self.inner.get(key).map(Vec::as_slice)
     }
-    /// Iterates over inner HashMap.
-    pub fn iter(&self) -> std::collections::hash_map::Iter<String, Vec<String>> {
-        self.inner.iter()
+    /// Returns every word of at least `minlen` letters spelled from some of the
+    /// letters in `q`, longest first.
+    ///
+    /// This scans the whole map, so it's far slower than `find_match`.
+    pub fn sub_anagrams(&self, q: &str, minlen: usize) -> Vec<String> {
+        let Some(available) = self.letter_counts(q) else {
+            return Vec::new();
+        };
+        let fits = |key: &str| {
+            self.letter_counts(key)
+                .is_some_and(|counts| fits_in(&counts, &available))
+        };
+        let q_len = q.chars().count();
+
+        let mut found: Vec<String> = self
+            .inner
+            .iter()
+            .filter(|(key, _)| (minlen..=q_len).contains(&key.chars().count()) && fits(key))
+            .flat_map(|(_, words)| words.iter().cloned())
+            .collect();
+        found.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        found
     }
-    /// Returns the number of words in the map.
-    pub fn len(&self) -> usize {
-        self.inner.len()
+    /// Returns a cursor on the keys using every letter in `typed` (see
+    /// `complete`), moved on from `cursor` if `typed` only adds letters to those
+    /// it followed, or started afresh if not.
+    pub fn follow(&self, cursor: Option<TrieCursor>, typed: &str) -> TrieCursor {
+        let key = self.alphabet.key(typed);
+        let mut letters: Vec<char> = key.chars().collect();
+        let mut take = |c| match letters.iter().position(|&l| l == c) {
+            Some(i) => {
+                letters.remove(i);
+                true
+            }
+            None => false,
+        };
+        match cursor.filter(|cursor| cursor.typed().chars().all(&mut take)) {
+            Some(mut cursor) => {
+                for letter in letters {
+                    self.trie.advance(&mut cursor, letter);
+                }
+                cursor
+            }
+            None => self.trie.cursor(&key),
+        }
+    }
+    /// Returns the words longer than the letters `cursor` followed, of at most
+    /// `maxlen` letters, that use every one of them, keeping only those `shows`
+    /// (see `complete`).
+    pub fn suggestions_at_cursor(
+        &self,
+        cursor: &TrieCursor,
+        maxlen: usize,
+        shows: impl Fn(&str) -> bool,
+    ) -> Completions {
+        let mut completions = Completions::default();
+        self.trie.keys_below(cursor, maxlen, |key| {
+            for word in self.get(key).unwrap_or_default() {
+                if shows(word) {
+                    completions.add(word);
+                }
+            }
+        });
+        completions.finish();
+        completions
+    }
+    /// Returns every word spelled from all but one of the letters in `q`,
+    /// alphabetically and each once.
+    pub fn drop_one(&self, q: &str) -> Vec<String> {
+        let letters: Vec<char> = q.chars().collect();
+        let mut found: Vec<String> = (0..letters.len())
+            .map(|skip| {
+                let rest = letters.iter().enumerate().filter(|&(i, _)| i != skip);
+                self.alphabet
+                    .key(&rest.map(|(_, &c)| c).collect::<String>())
+            })
+            .unique()
+            .filter_map(|key| self.get(&key))
+            .flatten()
+            .cloned()
+            .collect();
+        found.sort();
+        found
+    }
+    /// Returns every word fitting `pattern`, alphabetically.
+    pub fn pattern_matches(&self, pattern: &Pattern) -> Vec<String> {
+        let mut found: Vec<String> = self
+            .inner
+            .iter()
+            .filter(|(key, _)| key.chars().count() == pattern.len())
+            .flat_map(|(_, words)| words)
+            .filter(|word| pattern.matches(&fold_accents(word)))
+            .cloned()
+            .collect();
+        found.sort();
+        found
+    }
+    /// Returns every word of at least `minlen` letters spelled only from the
+    /// letters in `bank`, each used as often as needed, longest first.
+    pub fn letter_bank(&self, bank: &str, minlen: usize) -> Vec<String> {
+        let mut found: Vec<String> = self
+            .inner
+            .iter()
+            .filter(|(key, _)| {
+                key.chars().count() >= minlen && key.chars().all(|c| bank.contains(c))
+            })
+            .flat_map(|(_, words)| words.iter().cloned())
+            .collect();
+        found.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        found
+    }
+    /// Returns the phrases of words with `lengths`, in that order, that use
+    /// exactly the letters in `q` between them; at most `MAX_PHRASES`. Phrases
+    /// listed in the word list come first, then those put together from its
+    /// words, each alphabetically.
+    ///
+    /// Neighboring words of the same length are only taken in alphabetical
+    /// order, so a phrase isn't listed again with them swapped.
+    pub fn phrases(&self, q: &str, lengths: &[usize]) -> Vec<String> {
+        let Some(available) = self.letter_counts(q) else {
+            return Vec::new();
+        };
+        if lengths.iter().sum::<usize>() != q.chars().count() {
+            return Vec::new();
+        }
+        let mut listed: Vec<String> = self
+            .listed_phrases
+            .get(&self.alphabet.key(q))
+            .into_iter()
+            .flatten()
+            .filter(|phrase| phrase_lengths(phrase) == lengths)
+            .cloned()
+            .collect();
+        listed.sort();
+        // Groups that could fill each word, before the others take their letters
+        let slots: Vec<Vec<(LetterCounts, &[String])>> = lengths
+            .iter()
+            .map(|&len| {
+                self.inner
+                    .iter()
+                    .filter(|(key, _)| key.chars().count() == len)
+                    .filter_map(|(key, words)| Some((self.letter_counts(key)?, words.as_slice())))
+                    .filter(|(counts, _)| fits_in(counts, &available))
+                    .collect()
+            })
+            .collect();
+        let mut found = Vec::new();
+        extend_phrases(&slots, lengths, available, &mut Vec::new(), &mut found);
+        found.sort();
+        // A listed phrase isn't put together again from its words
+        found.retain(|phrase| !listed.iter().any(|l| l.replace('-', " ") == *phrase));
+        listed.extend(found);
+        listed.truncate(MAX_PHRASES);
+        listed
+    }
+    /// Counts each of the alphabet's letters in `word`, or `None` if it has any
+    /// other character.
+    fn letter_counts(&self, word: &str) -> Option<LetterCounts> {
+        let mut counts = [0; MAX_LETTERS];
+        for c in word.chars() {
+            counts[self.alphabet.index(c)?] += 1;
+        }
+        Some(counts)
     }
 }
 
-/// Loads words from dictionary text file.
-pub fn load_words_to_string(filename: &str) -> io::Result<String> {
-    let mut f = File::open(filename)?;
-    let mut buffer = String::new();
-    f.read_to_string(&mut buffer)?;
+/// How often each letter of an alphabet appears, by its place in the alphabet.
+type LetterCounts = [u8; MAX_LETTERS];
 
-    println!("[load_words_to_string] First 10 words:");
-    for word in buffer.lines().take(10) {
-        println!("{}", word);
+/// Adds to `found` the phrases that start with `phrase` and fill the rest of
+/// `slots` from the letters left in `available`.
+fn extend_phrases<'a>(
+    slots: &[Vec<(LetterCounts, &'a [String])>],
+    lengths: &[usize],
+    available: LetterCounts,
+    phrase: &mut Vec<&'a str>,
+    found: &mut Vec<String>,
+) {
+    let i = phrase.len();
+    if i == slots.len() {
+        found.push(phrase.join(" "));
+        return;
+    }
+    let after = phrase
+        .last()
+        .copied()
+        .filter(|_| lengths[i] == lengths[i - 1]);
+    for (counts, words) in &slots[i] {
+        if !fits_in(counts, &available) {
+            continue;
+        }
+        let mut left = available;
+        left.iter_mut().zip(counts).for_each(|(a, n)| *a -= n);
+        for word in words
+            .iter()
+            .filter(|w| after.is_none_or(|a| w.as_str() >= a))
+        {
+            if found.len() == MAX_PHRASES {
+                return;
+            }
+            phrase.push(word);
+            extend_phrases(slots, lengths, left, phrase, found);
+            phrase.pop();
+        }
     }
+}
 
-    Ok(buffer)
+/// Returns whether the letters counted in `need` are all in `have`.
+fn fits_in(need: &LetterCounts, have: &LetterCounts) -> bool {
+    need.iter().zip(have).all(|(n, h)| n <= h)
 }
 
-/// Takes word list as a `String` and converts them into a list of words whose
-/// letters are arranged in alphabetical order.
-fn sorted_words(words: String) -> Vec<String> {
-    println!("[sorted_words] First 10 words:");
-    for word in words.lines().take(10) {
-        println!("{}", word);
+/// Adds how often each letter `A`-`Z` (in either case) appears in `key` to
+/// `counts`, ignoring any other characters.
+fn add_letters(counts: &mut [usize; 26], key: &str) {
+    for b in key.bytes().map(|b| b.to_ascii_uppercase()) {
+        if !b.is_ascii_uppercase() {
+            continue;
+        }
+        counts[(b - b'A') as usize] += 1;
     }
-    // let ordered_words = words.lines().map(|&word| word.sort() ).collect::<Vec>();
+}
 
-    let mut ordered_words = Vec::with_capacity(100000);
+/// Returns how often each letter `A`-`Z` appears across `words`, with accents
+/// folded as for the keys.
+pub fn count_letters(words: &[String]) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for word in words {
+        add_letters(&mut counts, &fold_accents(word));
+    }
+    counts
+}
 
-    for word in words.lines() {
-        let sorted_word = word.chars().sorted().collect::<String>();
-        ordered_words.push(sorted_word);
+/// Returns `c` as an uppercase letter `A`-`Z`, with any accent dropped, or `None`
+/// if it isn't a letter that folds to one (e.g. `é` gives `E`).
+pub fn fold_letter(c: char) -> Option<char> {
+    if c.is_ascii_alphabetic() {
+        return Some(c.to_ascii_uppercase());
     }
+    let folded = match c.to_uppercase().next()? {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'Ç' => 'C',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'Ñ' => 'N',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'O',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'Ý' | 'Ÿ' => 'Y',
+        _ => return None,
+    };
+    Some(folded)
+}
 
-    ordered_words
+/// Returns `word` uppercased with accents dropped and ligatures spelled out, so it
+/// matches what can be typed (`Œuvre` gives `OEUVRE`), as in English.
+pub fn fold_accents(word: &str) -> Cow<'_, str> {
+    Alphabet::English.fold_word(word)
 }
 
 /// Converts word list to a map of {ordered_word, [actual word, ...]} pairs.
@@ -112,44 +465,251 @@ fn sorted_words(words: String) -> Vec<String> {
 ///    "rsttu": ["trust"],
 /// }
 /// ```
-pub fn make_word_map(words: &str) -> WordMap {
-    println!("[words_to_word_map]");
+///
+/// Keys are built from the words with accents folded as `alphabet` says, so
+/// `ÉLÈVE` is found by typing `ELEVE`. At most `max_words` words are loaded.
+/// `progress` is called with the number of words loaded so far after every
+/// `every` words, and once more at the end.
+pub fn make_word_map_with_progress(
+    words: &str,
+    alphabet: Alphabet,
+    max_words: usize,
+    every: usize,
+    mut progress: impl FnMut(usize),
+) -> WordMap {
+    log::info!("[words_to_word_map] {} alphabet", alphabet.code());
 
-    let mut word_map = WordMap::new();
+    let mut word_map = WordMap::new(alphabet);
+    let mut count = 0;
 
     for word in words.lines() {
-        let sorted_word = word.chars().sorted().collect::<String>();
-        word_map.insert(sorted_word, word.to_string());
+        if count == max_words {
+            log::warn!("[words_to_word_map] stopped at {max_words} words");
+            break;
+        }
+        let word = canonical_word(word);
+        if is_phrase(&word) {
+            word_map.insert_phrase(alphabet.key(&phrase_letters(&word)), word.into_owned());
+        } else if !word.is_empty() {
+            word_map.insert(alphabet.key(&word), word.into_owned());
+        }
+        count += 1;
+        if count % every == 0 {
+            progress(count);
+        }
+    }
+    progress(count);
+    if word_map.merged > 0 {
+        log::info!(
+            "[words_to_word_map] merged {} duplicate words",
+            word_map.merged
+        );
     }
 
     word_map
 }
 
-fn make_word_map_string(words: String) -> WordMap {
-    println!("[words_to_word_map]");
+/// Returns whether `word`, trimmed, is a phrase: words joined by spaces or
+/// hyphens.
+pub fn is_phrase(word: &str) -> bool {
+    word.trim().contains(PHRASE_JOINERS)
+}
 
-    let mut word_map = WordMap::new();
+/// Returns the letters of `phrase`, without what joins its words.
+pub fn phrase_letters(phrase: &str) -> String {
+    phrase
+        .chars()
+        .filter(|c| !PHRASE_JOINERS.contains(c))
+        .collect()
+}
 
-    for word in words.lines() {
-        let sorted_word = word.chars().sorted().collect::<String>();
-        word_map.insert(sorted_word, word.to_string());
-    }
+/// Returns how many letters each word of `phrase` has.
+pub fn phrase_lengths(phrase: &str) -> Vec<usize> {
+    phrase
+        .split(PHRASE_JOINERS)
+        .filter(|word| !word.is_empty())
+        .map(|word| word.chars().count())
+        .collect()
+}
 
-    word_map
+/// Returns `word` as a `WordMap` keeps it: trimmed and uppercased, so the same
+/// word listed again in another case or with stray spaces is merged with it.
+pub fn canonical_word(word: &str) -> Cow<'_, str> {
+    let word = word.trim();
+    match word.chars().any(char::is_lowercase) {
+        true => Cow::Owned(word.to_uppercase()),
+        false => Cow::Borrowed(word),
+    }
 }
 
-fn main() {
-    println!("=== Jumble Helper ===");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+
+    #[test]
+    fn accented_letters_fold_to_their_base_letter() {
+        assert_eq!(fold_letter('a'), Some('A'));
+        assert_eq!(fold_letter('é'), Some('E'));
+        assert_eq!(fold_letter('Ñ'), Some('N'));
+        assert_eq!(fold_letter('ÿ'), Some('Y'));
+        assert_eq!(fold_letter('Ω'), None);
+        assert_eq!(fold_letter('1'), None);
+    }
+
+    #[test]
+    fn words_fold_with_ligatures_spelled_out() {
+        assert_eq!(fold_accents("ÉLÈVE"), "ELEVE");
+        assert_eq!(fold_accents("Œuvre"), "OEUVRE");
+        assert_eq!(fold_accents("straße"), "STRASSE");
+        assert!(matches!(fold_accents("POUND"), Cow::Borrowed("POUND")));
+    }
+
+    #[test]
+    fn accented_words_are_found_by_their_plain_letters() {
+        let map =
+            make_word_map_with_progress("ÉLÈVE\nNIÑO\n", Alphabet::English, usize::MAX, 1, |_| ());
+        let mut key_buf = Vec::new();
+        assert_eq!(
+            map.find_match("VEELE", 4, 8, &mut key_buf),
+            Some(&["ÉLÈVE".to_string()][..])
+        );
+        assert_eq!(
+            map.find_match("ONIN", 4, 8, &mut key_buf),
+            Some(&["NIÑO".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn stats_count_words_groups_and_letters() {
+        let map = make_word_map_with_progress(
+            "TALE
+LATE
+ÉTÉ
+",
+            Alphabet::English,
+            usize::MAX,
+            1,
+            |_| (),
+        );
+        let stats = map.stats();
+        assert_eq!((stats.words, stats.groups), (3, 2));
+        let letter = |c: u8| stats.letters[(c - b'A') as usize];
+        assert_eq!((letter(b'A'), letter(b'E'), letter(b'T')), (2, 4, 3));
+        assert_eq!(count_letters(&["Été".into()]), {
+            let mut counts = [0; 26];
+            counts[4] = 2;
+            counts[19] = 1;
+            counts
+        });
+    }
 
-    let words = load_words_to_string("dictionary/ENGLISH_US.txt").expect("Valid text file");
-    // let ordered_words = sorted_words(words);
+    fn map_of(words: &str) -> WordMap {
+        make_word_map_with_progress(words, Alphabet::English, usize::MAX, usize::MAX, |_| ())
+    }
+
+    #[test]
+    fn patterns_find_words_of_their_length_alphabetically() {
+        let map = map_of("POWDER\nBOILER\nBANTER\nBOXER\nHOLDER\n");
+        let pattern = Pattern::parse("_O__ER").unwrap();
+        assert_eq!(
+            map.pattern_matches(&pattern),
+            ["BOILER", "HOLDER", "POWDER"]
+        );
+        let pattern = Pattern::parse("_Z__").unwrap();
+        assert!(map.pattern_matches(&pattern).is_empty());
+    }
 
-    // println!("Second word: {}", ordered_words[1]);
+    #[test]
+    fn banks_reuse_letters_and_skip_short_words() {
+        let map = map_of("APPLE\nPANEL\nPLANE\nGRAPE\nPEARL\nAPE\nPLANET\nLEAP\n");
+        assert_eq!(
+            map.letter_bank("AEGLNPR", 4),
+            ["APPLE", "GRAPE", "PANEL", "PEARL", "PLANE", "LEAP"]
+        );
+        assert_eq!(map.letter_bank("AEGLNPR", 6), Vec::<String>::new());
+        assert_eq!(map.letter_bank("AELP", 3), ["APPLE", "LEAP", "APE"]);
+    }
+
+    #[test]
+    fn phrases_use_every_letter_in_the_lengths_given() {
+        let map = map_of("TEN\nSAIL\nANT\nLIES\nNIT\nSALE\nTIN\nTAN\nTENS\nALI\n");
+        assert_eq!(
+            map.phrases("AEILNST", &[3, 4]),
+            ["ALI TENS", "ANT LIES", "NIT SALE", "TAN LIES", "TEN SAIL", "TIN SALE"]
+        );
+        // The order of the lengths is the order of the words
+        assert!(map
+            .phrases("AEILNST", &[4, 3])
+            .contains(&"TENS ALI".to_owned()));
+        assert!(map.phrases("AEILNST", &[3, 3]).is_empty());
+        assert!(map.phrases("AEILNS7", &[3, 4]).is_empty());
+    }
+
+    #[test]
+    fn equal_lengths_are_not_listed_swapped() {
+        let map = map_of("TOP\nPOT\nOPT\n");
+        assert_eq!(
+            map.phrases("OOPPTT", &[3, 3]),
+            ["OPT OPT", "OPT POT", "OPT TOP", "POT POT", "POT TOP", "TOP TOP"]
+        );
+    }
 
-    // let mut word_map = make_word_map(words);
-    // for pair in word_map.iter().take(25) {
-    //     println!("{pair:?}");
-    // }
-    // let num_words = word_map.inner.len();
-    // println!("number of words: {num_words}");
+    #[test]
+    fn phrases_stop_at_the_cap() {
+        // Seven spellings of A make 1716 phrases of seven of them
+        let map = map_of("A\nÀ\nÁ\nÂ\nÃ\nÄ\nÅ\n");
+        assert_eq!(map.phrases("AAAAAAA", &[1; 7]).len(), MAX_PHRASES);
+        assert_eq!(map.phrases("AAA", &[1, 1, 1]).len(), 84);
+    }
+
+    #[test]
+    fn words_differing_in_case_or_spaces_are_merged() {
+        assert_eq!(canonical_word(" pound\t"), "POUND");
+        assert!(matches!(canonical_word("POUND"), Cow::Borrowed("POUND")));
+        assert!(matches!(canonical_word("Été"), Cow::Owned(word) if word == "ÉTÉ"));
+
+        let map = map_of("POUND\npound\n POUND \nUPON\n\n   \nÉté\nÉTÉ\n");
+        assert_eq!(map.get("DNOPU"), Some(&["POUND".to_owned()][..]));
+        assert_eq!(map.get("EET"), Some(&["ÉTÉ".to_owned()][..]));
+        let stats = map.stats();
+        assert_eq!((stats.words, stats.merged), (3, 3));
+    }
+
+    #[test]
+    fn phrases_are_words_joined_by_spaces_or_hyphens() {
+        assert!(is_phrase("HOT DOG") && is_phrase("IN-LAW"));
+        assert!(!is_phrase("HOTDOG") && !is_phrase(" POUND "));
+        assert_eq!(phrase_letters("JACK-IN-THE BOX"), "JACKINTHEBOX");
+        assert_eq!(phrase_lengths("JACK-IN-THE BOX"), [4, 2, 3, 3]);
+        assert_eq!(phrase_lengths("HOT  DOG"), [3, 3]);
+    }
+
+    #[test]
+    fn listed_phrases_come_first_and_never_match_the_entry() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/phrase_words.txt");
+        let text = std::fs::read_to_string(path).unwrap();
+        let (header, words) = crate::dictionary::prepare_words(&text).unwrap();
+        let mut map = map_of(&words);
+        map.set_header(header);
+        let stats = map.stats();
+        // HOT DOG is listed twice, once in lowercase
+        assert_eq!((stats.words, stats.phrases, stats.merged), (7, 2, 1));
+
+        let mut key_buf = Vec::new();
+        assert_eq!(map.find_match("DGHOOT", 1, 10, &mut key_buf), None);
+        assert_eq!(map.find_match("AILNW", 1, 10, &mut key_buf), None);
+        assert!(map.sub_anagrams("DGHOOT", 1).iter().all(|w| !is_phrase(w)));
+
+        // Before the phrases put together, though they sort first
+        assert_eq!(
+            map.phrases("DGHOOT", &[3, 3]),
+            ["HOT DOG", "DOG HOT", "HOG TOD"]
+        );
+        // Not listed again as put together from its words
+        assert_eq!(map.phrases("AILNW", &[2, 3]), ["IN-LAW"]);
+        // Only in the lengths of its words
+        assert_eq!(map.phrases("AILNW", &[3, 2]), ["LAW IN"]);
+        assert!(map.phrases("AILNW", &[5]).is_empty());
+    }
 }
@@ -0,0 +1,711 @@
+//! One entry tab: its letters, matches, and the queries that find them.
+//!
+//! Tabs share the loaded dictionary but nothing else, so a slow query in one
+//! carries on while another is in front. With a post-processing command
+//! configured, each new list of matches also goes through it (see
+//! `post_process`). An entry starting with `:` is a query command (see
+//! `query::lang`) rather than letters. With `auto_escalate` on, an entry with no
+//! exact match falls back to looser searches (see `escalate`). Matches on the
+//! word mask are left out of view, but not out of the matches (see `mask`).
+//! Matches a one-letter change of the entry brought in are marked new for a
+//! few seconds (see `fresh`). Matches using a letter left out for the session
+//! are dropped, whatever the query (see `letters`).
+
+use crate::alloc_count::allocation_count;
+use crate::app::AppContext;
+use crate::assist::AssistTimer;
+use crate::complete::{Completions, TrieCursor, MIN_TYPED};
+use crate::debug::DebugInfo;
+use crate::entry::{EditSummary, EntryBuffer};
+use crate::escalate::Strategy;
+use crate::feedback::{AsTyped, MatchOutcome, OutcomeTracker};
+use crate::fresh::FreshMatches;
+use crate::length_filter::LengthFilter;
+use crate::letters::LetterMask;
+use crate::mask::WordMask;
+use crate::parse::Pattern;
+use crate::post_process::{PostOutcome, PostProcessor};
+use crate::query::cost::{estimate_cost, refusal, Gate};
+use crate::query::lang::{parse_command, QueryKind};
+use crate::query::{Debounce, QueryMode, QueryRunner};
+use crate::results::ResultsView;
+use crate::strings::{fill, StringKey};
+use crate::tiles::TileOrder;
+use crate::timing::{record, Stage};
+use crate::toast::{Severity, TOAST_TIME};
+use crate::typo::{suggest, Suggestion};
+use crate::wordmap::WordMap;
+use crate::{MAX_ENTRY_LENGTH, MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Whether the text entry data needs to be changed.
+pub enum EntryStatus {
+    Changed,
+    Unchanged,
+}
+
+/// Where the current matches are kept.
+///
+/// Exact matches are looked up in the dictionary when needed rather than copied
+/// out of it.
+pub enum Matches {
+    /// Exact matches, filed under the sorted key in `key_buf`.
+    Exact,
+    /// Nothing matches, or the query hasn't run yet.
+    Empty,
+    /// Words found by a slow query.
+    Found(Vec<String>),
+}
+
+/// The state of one entry tab.
+pub struct Workspace {
+    pub word_map: Arc<WordMap>,
+    // Entry and Answer (Jumble will only have one answer)
+    pub entry: EntryBuffer,
+    pub entry_status: EntryStatus,
+    /// Edits made by this frame's events, applied in `update`.
+    pub edits: EditSummary,
+    pub answer: Option<usize>,
+    /// Whether the entry's letters, as typed, already spell one of the matches.
+    pub as_typed: AsTyped,
+    pub mode: QueryMode,
+    pub queries: QueryRunner<WordMap>,
+    pub debounce: Debounce,
+    pub matches: Matches,
+    pub outcomes: OutcomeTracker,
+    pub key_buf: Vec<u8>,
+    pub results: ResultsView,
+    /// The user's command for reordering and annotating the matches, if set.
+    pub post: Option<PostProcessor>,
+    /// Notes the command gave the matches, by index; empty when it gave none.
+    pub notes: Vec<Option<String>>,
+    /// A blank pattern pasted with the scramble, which the matches must fit
+    /// while the entry is still that scramble (kept alongside).
+    pub pattern: Option<(String, Pattern)>,
+    /// What's wrong with the query command in the entry, if it is one that
+    /// doesn't parse.
+    pub command_error: Option<String>,
+    /// Whether the query command in the entry is costly enough to wait for
+    /// Enter before it runs (see `query::cost`).
+    pub held: bool,
+    /// The word lengths the list is limited to, kept as the matches change.
+    pub lengths: LengthFilter,
+    /// Entries one mistyped letter away that match, when the letters entered
+    /// match nothing.
+    pub suggestions: Vec<Suggestion>,
+    /// How many hints are shown, once asked for since the entry was last edited;
+    /// one more than there are shows the matches again (see `hints`).
+    pub hints: Option<usize>,
+    /// The order the entry's letters are shown in.
+    pub tiles: TileOrder,
+    /// The search that found what's shown, looser than the entry asked for when
+    /// nothing matched it exactly.
+    pub escalation: Strategy,
+    /// Indices of the matches on the word mask.
+    pub hidden: Vec<usize>,
+    /// Whether the masked matches are shown anyway, until the entry changes.
+    pub revealed: bool,
+    /// Indices of the matches listed already at a lower index, left out of the
+    /// list.
+    repeats: Vec<usize>,
+    /// Words that could complete an entry that matches nothing yet.
+    pub completions: Completions,
+    /// Where the letters typed lead among the dictionary's keys, since
+    /// completions were last looked for.
+    cursor: Option<TrieCursor>,
+    /// The countdown before the answer shows, when it's held back (see
+    /// `assist`).
+    pub assist: AssistTimer,
+    /// The matches a one-letter change brought in (see `fresh`).
+    pub fresh: FreshMatches,
+    /// The letters the matches may not use, as the session last had them.
+    pub letters: LetterMask,
+}
+
+impl Workspace {
+    /// Creates an empty tab looking words up in `word_map`, set up as `ctx`'s
+    /// config says.
+    pub fn new(word_map: Arc<WordMap>, ctx: &AppContext) -> Self {
+        let config = &ctx.state.config;
+        let capacity = entry_capacity(&word_map);
+        let mut entry = EntryBuffer::new(capacity);
+        entry.set_alphabet(word_map.alphabet());
+        Self {
+            queries: QueryRunner::new(Arc::clone(&word_map), ctx.dictionary_generation, ctx.clock),
+            word_map,
+            entry,
+            entry_status: EntryStatus::Changed,
+            edits: EditSummary::default(),
+            answer: None,
+            as_typed: AsTyped::NotAMatch,
+            mode: QueryMode::Exact,
+            debounce: Debounce::new(config.debounce_ms as f64 / 1000.0),
+            matches: Matches::Empty,
+            outcomes: OutcomeTracker::default(),
+            key_buf: Vec::with_capacity(MAX_WORD_LENGTH),
+            results: ResultsView::default(),
+            post: config
+                .post_process_command
+                .as_deref()
+                .and_then(|command| PostProcessor::new(command, ctx.clock)),
+            notes: Vec::new(),
+            pattern: None,
+            command_error: None,
+            held: false,
+            lengths: LengthFilter::default(),
+            suggestions: Vec::new(),
+            hints: None,
+            tiles: TileOrder::default(),
+            escalation: Strategy::Exact,
+            hidden: Vec::new(),
+            revealed: false,
+            repeats: Vec::new(),
+            completions: Completions::default(),
+            cursor: None,
+            assist: AssistTimer::default(),
+            fresh: FreshMatches::default(),
+            letters: ctx.state.letters.clone(),
+        }
+    }
+    /// Returns the words matching the entry.
+    pub fn matches(&self) -> &[String] {
+        match &self.matches {
+            // The entry only holds ASCII letters, which `find_match` sorts into `key_buf`
+            Matches::Exact => std::str::from_utf8(&self.key_buf)
+                .ok()
+                .and_then(|key| self.word_map.get(key))
+                .unwrap_or_default(),
+            Matches::Empty => &[],
+            Matches::Found(words) => words,
+        }
+    }
+    /// Returns the indices of the matches the list shows, as limited by
+    /// `lengths` and the word mask and each word once, with the entry's own
+    /// letters as typed (if a match) last.
+    pub fn shown(&self) -> impl Iterator<Item = usize> + '_ {
+        let typed = match self.as_typed {
+            AsTyped::AmongOthers(i) => Some(i),
+            _ => None,
+        };
+        let listed = move || {
+            self.lengths
+                .apply(self.matches())
+                .filter(move |&i| !self.is_hidden(i) && !self.repeats.contains(&i))
+        };
+        let last = typed.filter(|&i| listed().any(|j| j == i));
+        listed().filter(move |&j| Some(j) != typed).chain(last)
+    }
+    /// Returns whether the match at `index` is masked and not revealed.
+    pub fn is_hidden(&self, index: usize) -> bool {
+        !self.revealed && self.hidden.contains(&index)
+    }
+    /// Returns how many matches are masked and not revealed.
+    pub fn hidden_count(&self) -> usize {
+        match self.revealed {
+            true => 0,
+            false => self.hidden.len(),
+        }
+    }
+    /// Returns the unique (or chosen) answer, if any.
+    pub fn answer(&self) -> Option<&str> {
+        self.answer.map(|i| self.matches()[i].as_str())
+    }
+    /// Returns whether a slow query is held back or running.
+    pub fn is_searching(&self) -> bool {
+        self.debounce.is_pending() || self.queries.is_searching()
+    }
+    /// Runs the query command held back for its cost, if there is one,
+    /// returning whether there was.
+    pub fn run_held(&mut self) -> bool {
+        if !std::mem::take(&mut self.held) {
+            return false;
+        }
+        self.queries.prefer_worker();
+        self.debounce.skip();
+        true
+    }
+    /// Returns whether the post-processing command is working on the matches.
+    pub fn is_post_processing(&self) -> bool {
+        self.post.as_ref().is_some_and(PostProcessor::is_running)
+    }
+    /// Returns whether the matches are up to date with the entry, with no query
+    /// changed, held back, or running.
+    pub fn is_settled(&self) -> bool {
+        matches!(self.entry_status, EntryStatus::Unchanged)
+            && !self.edits.changed
+            && !self.is_searching()
+            && !self.is_post_processing()
+    }
+    /// Switches to `dictionary`, of `generation`, if it isn't the one in use, and
+    /// looks the entry up again either way.
+    pub fn set_dictionary(&mut self, dictionary: &Arc<WordMap>, generation: u32) {
+        if !Arc::ptr_eq(dictionary, &self.word_map) {
+            self.word_map = Arc::clone(dictionary);
+            self.queries.set_solver(Arc::clone(dictionary), generation);
+            self.cursor = None;
+            self.entry.set_max_len(entry_capacity(dictionary));
+            self.entry.set_alphabet(dictionary.alphabet());
+        }
+        self.entry_status = EntryStatus::Changed;
+    }
+    /// Returns the query the entry calls for: its query command, or its letters
+    /// in the tab's mode.
+    pub fn query(&self) -> Result<QueryKind, String> {
+        match self.entry.is_command() {
+            true => parse_command(self.entry.as_str()),
+            false => Ok(QueryKind::plain(self.mode, self.entry.as_str())),
+        }
+    }
+
+    /// Replaces the matches with `words` from a slow query, masking those on
+    /// `mask`, and returns the outcome.
+    fn set_matches(&mut self, words: Vec<String>, mask: Option<&WordMask>) -> MatchOutcome {
+        self.matches = Matches::Found(words);
+        self.apply_limits();
+        if self.escalation == Strategy::DropOne && self.matches().is_empty() {
+            self.escalation = Strategy::Exact;
+        }
+        self.results.reset();
+        self.settle_answer(mask);
+        self.outcome()
+    }
+    /// Returns the outcome of the matches; words found by a looser search than
+    /// the entry asked for are still no match for it.
+    fn outcome(&self) -> MatchOutcome {
+        match self.escalation {
+            Strategy::Exact => {
+                let outcome = MatchOutcome::of(self.entry.len(), self.matches().len());
+                self.as_typed.adjust(outcome)
+            }
+            Strategy::Typo | Strategy::DropOne => MatchOutcome::NoMatch,
+        }
+    }
+    /// Notes which matches are on `mask` or listed twice, and takes a lone match
+    /// as the answer, unless it's masked, the entry's letters as typed already
+    /// spell it, or it was found by a looser search.
+    fn settle_answer(&mut self, mask: Option<&WordMask>) {
+        self.hidden = match mask {
+            Some(mask) => self
+                .matches()
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| mask.hides(word))
+                .map(|(i, _)| i)
+                .collect(),
+            None => Vec::new(),
+        };
+        // The word map keeps each word once, but a query may still find one twice
+        self.repeats = match &self.matches {
+            Matches::Found(words) => {
+                let mut seen = HashSet::new();
+                (0..words.len())
+                    .filter(|&i| !seen.insert(words[i].as_str()))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        self.as_typed = match self.entry.is_command() || self.escalation != Strategy::Exact {
+            true => AsTyped::NotAMatch,
+            false => AsTyped::of(self.entry.as_str(), self.matches(), |word| {
+                self.word_map.contains(word)
+            }),
+        };
+        self.answer = match self.as_typed {
+            AsTyped::OnlyMatch => None,
+            _ => single_answer(self.matches(), &self.repeats).filter(|i| !self.hidden.contains(i)),
+        };
+    }
+    /// Drops the matches that don't fit the pasted pattern, if there is one, or
+    /// that use a letter left out.
+    fn apply_limits(&mut self) {
+        let pattern = self.pattern.as_ref().map(|(_, pattern)| pattern);
+        if pattern.is_none() && !self.letters.is_active() {
+            return;
+        }
+        let words: Vec<String> = self
+            .matches()
+            .iter()
+            .filter(|word| pattern.is_none_or(|pattern| pattern.matches(word)))
+            .filter(|word| self.letters.allows(word))
+            .cloned()
+            .collect();
+        self.matches = match words.is_empty() {
+            true => Matches::Empty,
+            false => Matches::Found(words),
+        };
+    }
+    /// Looks for entries a mistyped letter away from an exact entry that matches
+    /// nothing, keeping the words that fit the pasted pattern, if there is one,
+    /// and use no letter left out.
+    fn suggest_fixes(&mut self, ctx: &AppContext) {
+        let entry = self.entry.as_str();
+        if entry.len() < MIN_WORD_LENGTH || !self.matches().is_empty() {
+            return;
+        }
+        let layout = ctx.state.config.keyboard_layout;
+        self.suggestions = suggest(&self.word_map, entry, layout);
+        let pattern = self.pattern.as_ref().map(|(_, pattern)| pattern);
+        for suggestion in &mut self.suggestions {
+            suggestion.words.retain(|word| {
+                pattern.is_none_or(|pattern| pattern.matches(word)) && self.letters.allows(word)
+            });
+        }
+        self.suggestions.retain(|s| !s.words.is_empty());
+    }
+    /// Returns the words that could complete the entry as it stands: plain
+    /// letters, in exact mode, that match nothing yet (see `complete`). Words
+    /// the list wouldn't show, for their length, the mask, or a letter left
+    /// out, aren't counted.
+    pub fn find_completions(&mut self, mask: Option<&WordMask>) -> Completions {
+        let entry = self.entry.as_str();
+        let (len, capacity) = (self.entry.len(), self.entry.max_len());
+        let plain = self.mode == QueryMode::Exact && !self.entry.is_command();
+        if !plain || len < MIN_TYPED || len >= capacity {
+            return Completions::default();
+        }
+        let mut key_buf = Vec::new();
+        let found = self
+            .word_map
+            .find_match(entry, MIN_WORD_LENGTH, MAX_WORD_LENGTH, &mut key_buf);
+        if found.is_some() {
+            return Completions::default();
+        }
+        let cursor = self.word_map.follow(self.cursor.take(), entry);
+        let cursor = self.cursor.insert(cursor);
+        let shows = |word: &str| {
+            self.lengths.shows(word)
+                && !mask.is_some_and(|mask| mask.hides(word))
+                && self.letters.allows(word)
+        };
+        self.word_map.suggestions_at_cursor(cursor, capacity, shows)
+    }
+    /// Falls back to looser searches while the exact entry matches nothing, if
+    /// the config says to, until one finds something (see `escalate`). Returns
+    /// whether the last is still running on the query worker.
+    fn escalate(&mut self, ctx: &AppContext) -> bool {
+        if !ctx.state.config.auto_escalate {
+            return false;
+        }
+        let len = self.entry.len();
+        let mut strategy = Strategy::Exact;
+        let mut found = self.matches().len();
+        while let Some(next) = strategy.next(len, found) {
+            strategy = next;
+            found = match next {
+                Strategy::Exact => self.matches().len(),
+                Strategy::Typo => self.suggestions.iter().map(|s| s.words.len()).sum(),
+                Strategy::DropOne => {
+                    let query = QueryKind::DropOne(self.entry.as_str().to_owned());
+                    let Some(words) = self.queries.submit(query) else {
+                        // `set_matches` takes the words when the worker is done
+                        self.escalation = next;
+                        return true;
+                    };
+                    self.matches = Matches::Found(words);
+                    self.apply_limits();
+                    self.matches().len()
+                }
+            };
+        }
+        self.escalation = match found {
+            0 => Strategy::Exact,
+            _ => strategy,
+        };
+        false
+    }
+    /// Sends the matches to the post-processing command, if there is one and
+    /// there are matches to send. Words found by a looser search aren't sent.
+    fn post_process(&mut self, ctx: &mut AppContext) {
+        let mode = self.query().map_or("exact", |query| query.name());
+        let Some(post) = self
+            .post
+            .as_mut()
+            .filter(|_| self.escalation == Strategy::Exact)
+        else {
+            return;
+        };
+        let words = match &self.matches {
+            Matches::Found(words) => words.as_slice(),
+            Matches::Exact => std::str::from_utf8(&self.key_buf)
+                .ok()
+                .and_then(|key| self.word_map.get(key))
+                .unwrap_or_default(),
+            Matches::Empty => &[],
+        };
+        if words.is_empty() {
+            return;
+        }
+        if let Err(e) = post.submit(self.entry.as_str(), mode, words) {
+            post_process_failed(ctx, &e);
+        }
+    }
+
+    /// Runs whatever queries the entry calls for, returning the outcome if the
+    /// matches changed. Query timings go to `debug`, if given.
+    pub fn update(
+        &mut self,
+        ctx: &mut AppContext,
+        debug: Option<&mut DebugInfo>,
+    ) -> Option<MatchOutcome> {
+        let now = ctx.now;
+        let mut outcome = None;
+        // The letters left out are the session's, so another tab may have changed them
+        if self.letters != ctx.state.letters {
+            self.letters = ctx.state.letters.clone();
+            self.entry_status = EntryStatus::Changed;
+        }
+        match std::mem::replace(&mut self.entry_status, EntryStatus::Unchanged) {
+            EntryStatus::Unchanged => (),
+            EntryStatus::Changed => {
+                self.notes.clear();
+                self.held = false;
+                self.suggestions.clear();
+                self.completions = Completions::default();
+                self.escalation = Strategy::Exact;
+                self.revealed = false;
+                // The pattern belongs to the pasted scramble, not to edits of it
+                if self
+                    .pattern
+                    .as_ref()
+                    .is_some_and(|(scramble, _)| scramble != self.entry.as_str())
+                {
+                    self.pattern = None;
+                }
+                if let Some(post) = self.post.as_mut() {
+                    post.cancel();
+                }
+                let allocs_before = allocation_count();
+                let query_start = (ctx.clock)();
+                let query = self.query();
+                self.command_error = query.as_ref().err().cloned();
+                let sets_letters = matches!(query, Ok(QueryKind::Letters(_)));
+                // Exact lookups are cheap enough to always answer this frame, and
+                // a command that doesn't parse has nothing to look up
+                let settled = match query {
+                    Err(_) => {
+                        self.queries.cancel();
+                        self.debounce.cancel();
+                        self.matches = Matches::Empty;
+                        true
+                    }
+                    Ok(QueryKind::Letters(letters)) => {
+                        self.queries.cancel();
+                        self.debounce.cancel();
+                        self.matches = Matches::Empty;
+                        self.letters = letters.clone();
+                        ctx.state.letters = letters;
+                        true
+                    }
+                    Ok(QueryKind::Exact(_)) => {
+                        self.queries.cancel();
+                        self.debounce.cancel();
+                        let found = self.word_map.find_match(
+                            self.entry.as_str(),
+                            MIN_WORD_LENGTH,
+                            MAX_WORD_LENGTH,
+                            &mut self.key_buf,
+                        );
+                        self.matches = match found {
+                            Some(_) => Matches::Exact,
+                            None => Matches::Empty,
+                        };
+                        self.apply_limits();
+                        self.suggest_fixes(ctx);
+                        self.completions = self.find_completions(ctx.state.active_mask());
+                        !self.escalate(ctx)
+                    }
+                    Ok(query) => {
+                        self.queries.cancel();
+                        self.matches = Matches::Empty;
+                        match ctx.state.config.query_limits().gate(estimate_cost(&query)) {
+                            Gate::Run => {
+                                self.debounce.touch(now);
+                                false
+                            }
+                            Gate::Confirm => {
+                                self.debounce.cancel();
+                                self.held = true;
+                                true
+                            }
+                            Gate::Refuse => {
+                                self.debounce.cancel();
+                                self.command_error = Some(refusal(&query));
+                                true
+                            }
+                        }
+                    }
+                };
+                let query_us = ((ctx.clock)() - query_start) * 1_000_000.0;
+                record(Stage::Query, query_us / 1_000_000.0);
+                self.results.reset();
+                self.settle_answer(ctx.state.active_mask());
+                let match_count = self.matches().len();
+                // Leaving letters out finds nothing, so isn't a miss
+                let counted =
+                    settled && self.command_error.is_none() && !sets_letters && !self.held;
+                outcome = Some(if counted {
+                    self.outcome()
+                } else {
+                    MatchOutcome::Incomplete
+                });
+                if let Some(debug) = debug {
+                    debug.last_query_us = Some(query_us);
+                    debug.allocs_per_keystroke = allocation_count()
+                        .zip(allocs_before)
+                        .map(|(after, before)| after - before);
+                }
+                log::debug!(
+                    "[Workspace::update] entry '{}': {match_count} matches in {:?} mode",
+                    self.entry.as_str(),
+                    self.mode
+                );
+                if let Some(e) = &self.command_error {
+                    log::debug!("[Workspace::update] query command: {e}");
+                }
+                if settled {
+                    self.post_process(ctx);
+                }
+            }
+        }
+
+        // Slow modes run once typing pauses, inline or on the query worker
+        if self.debounce.ready(now) {
+            let words = self
+                .query()
+                .ok()
+                .and_then(|query| self.queries.submit(query));
+            if let Some(words) = words {
+                outcome = Some(self.set_matches(words, ctx.state.active_mask()));
+                self.post_process(ctx);
+            }
+            ctx.dirty = true;
+        }
+        if let Some(words) = self.queries.poll() {
+            outcome = Some(self.set_matches(words, ctx.state.active_mask()));
+            self.post_process(ctx);
+            ctx.dirty = true;
+        }
+        match self.post.as_mut().and_then(PostProcessor::poll) {
+            Some(PostOutcome::Done(candidates)) => {
+                let (words, notes) = candidates.into_iter().map(|c| (c.word, c.note)).unzip();
+                outcome = Some(self.set_matches(words, ctx.state.active_mask()));
+                self.notes = notes;
+                ctx.dirty = true;
+            }
+            Some(PostOutcome::Failed(e)) => post_process_failed(ctx, &e),
+            None => (),
+        }
+
+        if outcome.is_some() {
+            let mut fresh = std::mem::take(&mut self.fresh);
+            fresh.observe(self.entry.as_str(), self.matches(), now);
+            self.fresh = fresh;
+        }
+        if self.fresh.expire(now) {
+            ctx.dirty = true;
+        }
+        outcome
+    }
+}
+
+/// Returns the most letters an entry looked up in `word_map` may hold: as many as
+/// its longest word, or `MAX_ENTRY_LENGTH` if it has no words.
+pub fn entry_capacity(word_map: &WordMap) -> usize {
+    word_map
+        .word_length_bounds()
+        .map_or(MAX_ENTRY_LENGTH, |(_, longest)| {
+            longest.min(MAX_WORD_LENGTH)
+        })
+}
+
+/// Returns the index of the answer when `matches` holds exactly one word, once
+/// the `repeats` of words listed already are left out.
+fn single_answer(matches: &[String], repeats: &[usize]) -> Option<usize> {
+    (matches.len() == repeats.len() + 1).then_some(0)
+}
+
+/// Reports that the post-processing command failed; the matches stay as found.
+fn post_process_failed(ctx: &mut AppContext, reason: &str) {
+    log::warn!("[post_process_failed] {reason}");
+    let text = fill(
+        ctx.state.config.language.text(StringKey::PostProcessFailed),
+        reason,
+    );
+    ctx.toasts.push(text, Severity::Error, TOAST_TIME, ctx.now);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::wordmap::make_word_map_with_progress;
+
+    #[test]
+    fn the_entry_holds_as_many_letters_as_the_longest_word() {
+        let map = |words: &str| {
+            make_word_map_with_progress(words, Alphabet::English, usize::MAX, 1, |_| ())
+        };
+        assert_eq!(entry_capacity(&map("UPON\nGRATES\n")), 6);
+        assert_eq!(entry_capacity(&map("")), MAX_ENTRY_LENGTH);
+        let long = "A".repeat(MAX_WORD_LENGTH + 2);
+        assert_eq!(entry_capacity(&map(&long)), MAX_WORD_LENGTH);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn a_word_listed_three_ways_is_stored_and_shown_once() {
+        use crate::app::{App, AppState};
+        use crate::config::Config;
+        use crate::dictionary::DictionaryOptions;
+        use crate::events::AppEvent;
+        use crate::keymap::Keymap;
+        use crate::record::{wait_for_load, wall_clock, MAX_STEP};
+        use crate::render::fixed_measure;
+        use crate::screen::Screen;
+        use crate::sound::Sounds;
+        use crate::storage::MemoryStorage;
+        use macroquad::prelude::vec2;
+
+        // POUND, pound, and POUND with a trailing space
+        let list = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/duplicate_words.txt");
+        let config = Config {
+            announce: false,
+            dictionary: DictionaryOptions {
+                path: Some(list.to_owned()),
+                ..DictionaryOptions::default()
+            },
+            ..Config::default()
+        };
+        let sounds = Sounds::silent(config.volume, true);
+        let state = AppState::new(config, Arc::new(MemoryStorage::default()));
+        let size = vec2(800.0, 600.0);
+        let keymap = Keymap::defaults();
+        let mut app = App::new(state, keymap, None, sounds, size, wall_clock, fixed_measure);
+        assert!(wait_for_load(&mut app));
+        let map = Arc::clone(app.ctx.dictionary.as_ref().unwrap());
+        assert_eq!(map.get("DNOPU"), Some(&["POUND".to_owned()][..]));
+        assert_eq!(map.stats().merged, 2);
+
+        for c in "DNUOP".chars() {
+            app.handle_event(AppEvent::Letter(c));
+        }
+        app.update(MAX_STEP);
+        let Screen::Entry(entry) = app.screen() else {
+            panic!("not on the entry screen");
+        };
+        let (tabs, active) = entry.tabs();
+        let tab = &tabs[active];
+        assert_eq!(tab.shown().collect::<Vec<_>>(), [0]);
+        assert_eq!(tab.answer(), Some("POUND"));
+
+        // A word a query finds twice is still shown once, and is the answer
+        let mut tab = Workspace::new(map, &app.ctx);
+        let words = vec!["POUND".to_owned(), "UPON".to_owned(), "POUND".to_owned()];
+        tab.set_matches(words, None);
+        assert_eq!(tab.shown().collect::<Vec<_>>(), [0, 1]);
+        tab.set_matches(vec!["POUND".to_owned(), "POUND".to_owned()], None);
+        assert_eq!(tab.shown().collect::<Vec<_>>(), [0]);
+        assert_eq!(tab.answer(), Some("POUND"));
+    }
+}
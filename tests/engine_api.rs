@@ -0,0 +1,141 @@
+//! The `engine` module's promises, checked from outside the crate as an
+//! embedder sees them: the signatures, that everything crosses threads, and
+//! that an engine works from word lists held in memory.
+
+use jumble_helper::engine::{DictionarySpec, Engine, Match, Query, Solution};
+use std::sync::Arc;
+use std::thread;
+
+const WORDS: &str = "GREAT\nGRATE\nUPON\nPOUND\nGRATES\nRATE\n";
+
+fn engine(words: &str) -> Engine {
+    Engine::new(DictionarySpec::new(words)).unwrap()
+}
+
+fn words(solution: &Solution) -> Vec<&str> {
+    solution.matches.iter().map(|m| m.word.as_str()).collect()
+}
+
+// Stops compiling if a change breaks a stable signature, or the promise that
+// every type can be sent and shared between threads
+#[test]
+fn the_signatures_are_pinned() {
+    let _: fn(DictionarySpec) -> Result<Engine, String> = Engine::new;
+    let _: fn(&Engine, Query) -> Solution = Engine::solve;
+    let _: fn(&Engine) -> usize = Engine::word_count;
+    let _: fn(&Solution) -> Option<&str> = Solution::answer;
+    let _: fn(String) -> DictionarySpec = DictionarySpec::new;
+    let _: fn(&'static str) -> Query = Query::new;
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<DictionarySpec>();
+    send_sync::<Query>();
+    send_sync::<Match>();
+    send_sync::<Solution>();
+    send_sync::<Engine>();
+
+    let DictionarySpec {
+        words: _,
+        alphabet: _,
+        max_words: _,
+    } = DictionarySpec::default();
+    let Query {
+        letters: _,
+        min_len: _,
+        max_len: _,
+        sub_anagrams: _,
+    } = Query::new("");
+    let Match {
+        word: _,
+        score: _,
+        frequency: _,
+        scrabble: _,
+    } = Match {
+        word: String::new(),
+        score: 0.0,
+        frequency: None,
+        scrabble: 0,
+    };
+    let Solution {
+        letters: _,
+        matches: _,
+    } = Solution::default();
+}
+
+#[test]
+fn scrambles_solve_from_a_list_in_memory() {
+    let engine = engine(WORDS);
+    assert_eq!(engine.word_count(), 6);
+
+    let solution = engine.solve(Query::new("nudop"));
+    assert_eq!(solution.letters, "NUDOP");
+    assert_eq!(solution.answer(), Some("POUND"));
+
+    let solution = engine.solve(Query::new("TAGER"));
+    let mut found = words(&solution);
+    found.sort();
+    assert_eq!(found, ["GRATE", "GREAT"]);
+    assert_eq!(solution.answer(), None);
+
+    let solution = engine.solve(Query::new("XYZZY"));
+    assert!(solution.matches.is_empty());
+    assert_eq!(solution.answer(), None);
+}
+
+#[test]
+fn matches_carry_their_frequency_and_points_best_first() {
+    let engine = engine("#! frequency_column=true\nGRATE 5\nGREAT 900\n");
+    let solution = engine.solve(Query::new("TAGER"));
+    assert_eq!(words(&solution), ["GREAT", "GRATE"]);
+    let best = &solution.matches[0];
+    assert_eq!((best.frequency, best.scrabble), (Some(900), 6));
+    assert!(best.score > solution.matches[1].score);
+}
+
+#[test]
+fn sub_anagrams_stay_within_the_lengths_asked() {
+    let engine = engine(WORDS);
+    let mut query = Query::new("SETAGR");
+    query.sub_anagrams = true;
+    assert_eq!(
+        words(&engine.solve(query.clone())),
+        ["GRATE", "GREAT", "RATE", "GRATES"]
+    );
+    query.max_len = 5;
+    assert_eq!(
+        words(&engine.solve(query.clone())),
+        ["GRATE", "GREAT", "RATE"]
+    );
+    query.min_len = 5;
+    assert_eq!(words(&engine.solve(query)), ["GRATE", "GREAT"]);
+}
+
+#[test]
+fn a_bad_spec_is_refused_with_the_reason() {
+    let spec = DictionarySpec {
+        alphabet: Some("xx".into()),
+        ..DictionarySpec::new(WORDS)
+    };
+    let error = Engine::new(spec).err();
+    assert!(error.is_some_and(|e| e.contains("xx")));
+    let error = Engine::new(DictionarySpec::new("#! min_len=five\nGREAT\n")).err();
+    assert!(error.is_some_and(|e| e.contains("min_len")));
+}
+
+#[test]
+fn one_engine_solves_from_several_threads() {
+    let engine = Arc::new(engine(WORDS));
+    let answers: Vec<_> = ["NUDOP", "NOUP"]
+        .map(|letters| {
+            let engine = Arc::clone(&engine);
+            thread::spawn(move || {
+                engine
+                    .solve(Query::new(letters))
+                    .answer()
+                    .map(str::to_owned)
+            })
+        })
+        .into_iter()
+        .map(|solver| solver.join().unwrap())
+        .collect();
+    assert_eq!(answers, [Some("POUND".into()), Some("UPON".into())]);
+}
@@ -0,0 +1,19 @@
+#!/bin/sh
+# Stands in for a post_process_command in the tests of src/post_process.rs.
+# Reads the matches on stdin, then answers as the first argument says:
+#   echo     the matches as they came
+#   reorder  GREAT noted "best", then GRATE
+#   stray    a word that wasn't among the matches
+#   garbage  something that isn't JSON
+#   fail     nothing, exiting with 3
+#   slow     nothing, for longer than the timeout
+input=$(cat)
+case "$1" in
+echo) printf '%s\n' "$input" ;;
+reorder) printf '%s\n' '{"entry":"GERTA","mode":"exact","matches":[{"word":"GREAT","note":"best"},{"word":"GRATE"}]}' ;;
+stray) printf '%s\n' '{"entry":"GERTA","mode":"exact","matches":[{"word":"TREAG"}]}' ;;
+garbage) printf '%s\n' 'GREAT GRATE' ;;
+fail) exit 3 ;;
+slow) sleep 2 ;;
+*) echo "unknown mode '$1'" >&2; exit 2 ;;
+esac